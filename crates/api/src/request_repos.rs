@@ -3,24 +3,31 @@ use std::sync::Arc;
 use gotong_domain::ports::adaptive_path::AdaptivePathRepository;
 use gotong_domain::ports::chat::ChatRepository;
 use gotong_domain::ports::contributions::ContributionRepository;
-use gotong_domain::ports::discovery::{FeedRepository, NotificationRepository};
+use gotong_domain::ports::digest::DigestSubscriptionRepository;
+use gotong_domain::ports::discovery::{
+    FeedPreferenceRepository, FeedRepository, NotificationRepository,
+};
 use gotong_domain::ports::evidence::EvidenceRepository;
 use gotong_domain::ports::moderation::ModerationRepository;
+use gotong_domain::ports::notification_preferences::NotificationPreferenceRepository;
 use gotong_domain::ports::ontology::OntologyRepository;
+use gotong_domain::ports::push::PushSubscriptionRepository;
 use gotong_domain::ports::siaga::SiagaRepository;
 use gotong_domain::ports::vault::VaultRepository;
 use gotong_domain::ports::vouches::VouchRepository;
 use gotong_domain::ports::webhook::WebhookOutboxRepository;
 use gotong_infra::repositories::{
     SurrealAdaptivePathRepository, SurrealChatRepository, SurrealContributionRepository,
-    SurrealDiscoveryFeedRepository, SurrealDiscoveryFeedRepositoryOptions,
-    SurrealDiscoveryNotificationRepository, SurrealEvidenceRepository, SurrealModerationRepository,
-    SurrealOntologyRepository, SurrealSiagaRepository, SurrealVaultRepository,
+    SurrealDigestSubscriptionRepository, SurrealDiscoveryFeedRepository,
+    SurrealDiscoveryFeedRepositoryOptions, SurrealDiscoveryNotificationRepository,
+    SurrealEvidenceRepository, SurrealFeedPreferenceRepository, SurrealModerationRepository,
+    SurrealNotificationPreferenceRepository, SurrealOntologyRepository,
+    SurrealPushSubscriptionRepository, SurrealSiagaRepository, SurrealVaultRepository,
     SurrealVouchRepository, SurrealWebhookOutboxRepository,
 };
 
 use crate::middleware::AuthContext;
-use crate::state::AppState;
+use crate::state::{AppState, indexed_feed_repo, indexed_ontology_repo};
 
 pub fn adaptive_path_repo(state: &AppState, auth: &AuthContext) -> Arc<dyn AdaptivePathRepository> {
     match &auth.surreal_db_session {
@@ -73,7 +80,10 @@ pub fn moderation_repo(state: &AppState, auth: &AuthContext) -> Arc<dyn Moderati
 
 pub fn ontology_repo(state: &AppState, auth: &AuthContext) -> Arc<dyn OntologyRepository> {
     match &auth.surreal_db_session {
-        Some(session) => Arc::new(SurrealOntologyRepository::with_client(session.client())),
+        Some(session) => indexed_ontology_repo(
+            Arc::new(SurrealOntologyRepository::with_client(session.client())),
+            &state.concept_label_index,
+        ),
         None => state.ontology_repo.clone(),
     }
 }
@@ -88,15 +98,66 @@ pub fn siaga_repo(state: &AppState, auth: &AuthContext) -> Arc<dyn SiagaReposito
 
 pub fn feed_repo(state: &AppState, auth: &AuthContext) -> Arc<dyn FeedRepository> {
     match &auth.surreal_db_session {
-        Some(session) => Arc::new(SurrealDiscoveryFeedRepository::with_client_and_options(
+        Some(session) => indexed_feed_repo(
+            Arc::new(SurrealDiscoveryFeedRepository::with_client_and_options(
+                session.client(),
+                SurrealDiscoveryFeedRepositoryOptions {
+                    involvement_fallback_enabled: state
+                        .config
+                        .discovery_feed_involvement_fallback_enabled,
+                },
+            )),
+            state.feed_search_index_queue.as_ref(),
+        ),
+        None => state.feed_repo.clone(),
+    }
+}
+
+pub fn feed_preference_repo(
+    state: &AppState,
+    auth: &AuthContext,
+) -> Arc<dyn FeedPreferenceRepository> {
+    match &auth.surreal_db_session {
+        Some(session) => Arc::new(SurrealFeedPreferenceRepository::with_client(
             session.client(),
-            SurrealDiscoveryFeedRepositoryOptions {
-                involvement_fallback_enabled: state
-                    .config
-                    .discovery_feed_involvement_fallback_enabled,
-            },
         )),
-        None => state.feed_repo.clone(),
+        None => state.feed_preference_repo.clone(),
+    }
+}
+
+pub fn digest_subscription_repo(
+    state: &AppState,
+    auth: &AuthContext,
+) -> Arc<dyn DigestSubscriptionRepository> {
+    match &auth.surreal_db_session {
+        Some(session) => Arc::new(SurrealDigestSubscriptionRepository::with_client(
+            session.client(),
+        )),
+        None => state.digest_subscription_repo.clone(),
+    }
+}
+
+pub fn notification_preference_repo(
+    state: &AppState,
+    auth: &AuthContext,
+) -> Arc<dyn NotificationPreferenceRepository> {
+    match &auth.surreal_db_session {
+        Some(session) => Arc::new(SurrealNotificationPreferenceRepository::with_client(
+            session.client(),
+        )),
+        None => state.notification_preference_repo.clone(),
+    }
+}
+
+pub fn push_subscription_repo(
+    state: &AppState,
+    auth: &AuthContext,
+) -> Arc<dyn PushSubscriptionRepository> {
+    match &auth.surreal_db_session {
+        Some(session) => Arc::new(SurrealPushSubscriptionRepository::with_client(
+            session.client(),
+        )),
+        None => state.push_subscription_repo.clone(),
     }
 }
 
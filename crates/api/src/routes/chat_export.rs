@@ -0,0 +1,364 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use axum::extract::{Extension, Path, Query, State};
+use axum::http::HeaderValue;
+use axum::http::header::{CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::response::{IntoResponse, Response};
+use gotong_domain::chat::{ChatService, ChatTranscript, ChatTranscriptRange};
+use gotong_domain::util::format_ms_rfc3339;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ApiError;
+use crate::middleware::AuthContext;
+use crate::request_repos;
+use crate::state::AppState;
+
+use super::{
+    actor_identity, chat_attachment_response, hydrate_chat_message_views, map_domain_error,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ChatTranscriptFormat {
+    Markdown,
+    Json,
+    Text,
+}
+
+impl ChatTranscriptFormat {
+    fn parse(value: Option<&str>) -> Result<Self, ApiError> {
+        match value
+            .map(|value| value.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            None | Some("") | Some("markdown") | Some("md") => Ok(Self::Markdown),
+            Some("json") => Ok(Self::Json),
+            Some("text") | Some("txt") => Ok(Self::Text),
+            Some(_) => Err(ApiError::Validation(
+                "format must be one of: markdown, json, text".into(),
+            )),
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Markdown => "text/markdown; charset=utf-8",
+            Self::Json => "application/json",
+            Self::Text => "text/plain; charset=utf-8",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Json => "json",
+            Self::Text => "txt",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct ChatTranscriptQuery {
+    format: Option<String>,
+    from_ms: Option<i64>,
+    to_ms: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatTranscriptExport {
+    thread_id: String,
+    scope_id: String,
+    generated_at_ms: i64,
+    generated_by: String,
+    generated_by_name: String,
+    current_member_count: usize,
+    from_ms: Option<i64>,
+    to_ms: Option<i64>,
+    truncated: bool,
+    message_count: usize,
+    /// Signed attachment links stop working after this instant.
+    attachment_links_expire_at_ms: Option<i64>,
+    messages: Vec<ChatTranscriptEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatTranscriptEntry {
+    message_id: String,
+    author_id: String,
+    author_name: String,
+    body: String,
+    created_at_ms: i64,
+    edited_at_ms: Option<i64>,
+    deleted_at_ms: Option<i64>,
+    attachments: Vec<ChatTranscriptAttachment>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatTranscriptAttachment {
+    attachment_id: String,
+    file_name: String,
+    /// `None` when the attachment record is gone or not visible to the exporter.
+    url: Option<String>,
+}
+
+/// Exports a thread (or a time range of it) for meeting minutes. Only current
+/// members may export; the domain service enforces that.
+pub(super) async fn export_chat_thread(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(thread_id): Path<String>,
+    Query(query): Query<ChatTranscriptQuery>,
+) -> Result<Response, ApiError> {
+    let format = ChatTranscriptFormat::parse(query.format.as_deref())?;
+    let actor = actor_identity(&auth)?;
+    let service = ChatService::new(request_repos::chat_repo(&state, &auth));
+    let transcript = service
+        .export_transcript(
+            &actor,
+            &thread_id,
+            ChatTranscriptRange {
+                from_ms: query.from_ms,
+                to_ms: query.to_ms,
+            },
+        )
+        .await
+        .map_err(map_domain_error)?;
+
+    let ChatTranscript {
+        thread,
+        members,
+        messages,
+        range,
+        truncated,
+        generated_by,
+        generated_at_ms,
+    } = transcript;
+    let views = hydrate_chat_message_views(&auth, &actor, messages).await;
+    let generated_by_name = views
+        .iter()
+        .filter_map(|view| view.author.as_ref())
+        .find(|author| author.user_id == generated_by)
+        .map(|author| author.name.clone())
+        .unwrap_or_else(|| actor.username.clone());
+
+    // Attachment id -> (file name, signed url); `None` when it cannot be resolved.
+    let mut links = HashMap::<String, Option<(String, String)>>::new();
+    let mut attachment_links_expire_at_ms = None;
+    let mut entries = Vec::with_capacity(views.len());
+    for view in views {
+        let message = view.message;
+        let mut attachments = Vec::new();
+        if message.deleted_at_ms.is_none() {
+            for value in &message.attachments {
+                let Some(attachment_id) = value
+                    .get("attachment_id")
+                    .and_then(|value| value.as_str())
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+                else {
+                    continue;
+                };
+                if !links.contains_key(attachment_id) {
+                    let link = match service.get_attachment(&actor, attachment_id).await {
+                        Ok(attachment) => {
+                            let response = chat_attachment_response(&state, attachment);
+                            attachment_links_expire_at_ms = Some(response.expires_at_ms);
+                            Some((response.file_name, response.url))
+                        }
+                        Err(_) => None,
+                    };
+                    links.insert(attachment_id.to_string(), link);
+                }
+                let (file_name, url) = match links.get(attachment_id).cloned().flatten() {
+                    Some((file_name, url)) => (file_name, Some(url)),
+                    None => (
+                        value
+                            .get("file_name")
+                            .and_then(|value| value.as_str())
+                            .unwrap_or(attachment_id)
+                            .to_string(),
+                        None,
+                    ),
+                };
+                attachments.push(ChatTranscriptAttachment {
+                    attachment_id: attachment_id.to_string(),
+                    file_name,
+                    url,
+                });
+            }
+        }
+        entries.push(ChatTranscriptEntry {
+            author_name: view
+                .author
+                .map(|author| author.name)
+                .unwrap_or_else(|| message.author_id.clone()),
+            message_id: message.message_id,
+            author_id: message.author_id,
+            body: message.body,
+            created_at_ms: message.created_at_ms,
+            edited_at_ms: message.edited_at_ms,
+            deleted_at_ms: message.deleted_at_ms,
+            attachments,
+        });
+    }
+
+    let export = ChatTranscriptExport {
+        thread_id: thread.thread_id,
+        scope_id: thread.scope_id,
+        generated_at_ms,
+        generated_by,
+        generated_by_name,
+        current_member_count: members.len(),
+        from_ms: range.from_ms,
+        to_ms: range.to_ms,
+        truncated,
+        message_count: entries.len(),
+        attachment_links_expire_at_ms,
+        messages: entries,
+    };
+    let body = match format {
+        ChatTranscriptFormat::Markdown => render_markdown(&export),
+        ChatTranscriptFormat::Text => render_text(&export),
+        ChatTranscriptFormat::Json => {
+            serde_json::to_string_pretty(&export).map_err(|_| ApiError::Internal)?
+        }
+    };
+
+    let mut response = body.into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static(format.content_type()),
+    );
+    headers.insert(CACHE_CONTROL, HeaderValue::from_static("private, no-store"));
+    let disposition = format!(
+        "attachment; filename=\"chat-{}-{}.{}\"",
+        export.thread_id,
+        export.generated_at_ms,
+        format.extension()
+    );
+    headers.insert(
+        CONTENT_DISPOSITION,
+        HeaderValue::from_str(&disposition).map_err(|_| ApiError::Internal)?,
+    );
+    Ok(response)
+}
+
+fn range_label(export: &ChatTranscriptExport) -> String {
+    let from = export
+        .from_ms
+        .map(format_ms_rfc3339)
+        .unwrap_or_else(|| "beginning of thread".to_string());
+    let to = format_ms_rfc3339(export.to_ms.unwrap_or(export.generated_at_ms));
+    format!("{from} to {to}")
+}
+
+fn header_lines(export: &ChatTranscriptExport) -> Vec<String> {
+    let mut lines = vec![
+        format!("Scope: {}", export.scope_id),
+        format!(
+            "Generated at: {} by {}",
+            format_ms_rfc3339(export.generated_at_ms),
+            export.generated_by_name
+        ),
+        format!("Range: {}", range_label(export)),
+        format!("Current members: {}", export.current_member_count),
+        format!("Messages: {}", export.message_count),
+    ];
+    if export.truncated {
+        lines.push(
+            "Truncated: the range holds more messages than one export; narrow it with from_ms/to_ms"
+                .to_string(),
+        );
+    }
+    if let Some(expires_at_ms) = export.attachment_links_expire_at_ms {
+        lines.push(format!(
+            "Attachment links expire at: {}",
+            format_ms_rfc3339(expires_at_ms)
+        ));
+    }
+    lines
+}
+
+fn render_markdown(export: &ChatTranscriptExport) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Chat transcript: thread {}\n", export.thread_id);
+    for line in header_lines(export) {
+        let _ = writeln!(out, "- {line}");
+    }
+    for entry in &export.messages {
+        let _ = write!(
+            out,
+            "\n**{}** · {}",
+            entry.author_name,
+            format_ms_rfc3339(entry.created_at_ms)
+        );
+        if let Some(deleted_at_ms) = entry.deleted_at_ms {
+            let _ = writeln!(
+                out,
+                " _(message deleted {})_",
+                format_ms_rfc3339(deleted_at_ms)
+            );
+            continue;
+        }
+        if let Some(edited_at_ms) = entry.edited_at_ms {
+            let _ = write!(out, " _(edited {})_", format_ms_rfc3339(edited_at_ms));
+        }
+        out.push('\n');
+        // Quote the body so user-authored Markdown cannot break the transcript layout.
+        for line in entry.body.lines() {
+            let _ = writeln!(out, "> {line}");
+        }
+        for attachment in &entry.attachments {
+            match attachment.url.as_deref() {
+                Some(url) => {
+                    let _ = writeln!(out, "- Attachment: [{}]({url})", attachment.file_name);
+                }
+                None => {
+                    let _ = writeln!(out, "- Attachment: {} (unavailable)", attachment.file_name);
+                }
+            }
+        }
+    }
+    out
+}
+
+fn render_text(export: &ChatTranscriptExport) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Chat transcript: thread {}", export.thread_id);
+    for line in header_lines(export) {
+        let _ = writeln!(out, "{line}");
+    }
+    out.push('\n');
+    for entry in &export.messages {
+        let timestamp = format_ms_rfc3339(entry.created_at_ms);
+        if let Some(deleted_at_ms) = entry.deleted_at_ms {
+            let _ = writeln!(
+                out,
+                "[{timestamp}] {}: [message deleted {}]",
+                entry.author_name,
+                format_ms_rfc3339(deleted_at_ms)
+            );
+            continue;
+        }
+        let edited = entry
+            .edited_at_ms
+            .map(|edited_at_ms| format!(" (edited {})", format_ms_rfc3339(edited_at_ms)))
+            .unwrap_or_default();
+        let mut lines = entry.body.lines();
+        let _ = writeln!(
+            out,
+            "[{timestamp}] {}{edited}: {}",
+            entry.author_name,
+            lines.next().unwrap_or_default()
+        );
+        for line in lines {
+            let _ = writeln!(out, "    {line}");
+        }
+        for attachment in &entry.attachments {
+            let url = attachment.url.as_deref().unwrap_or("unavailable");
+            let _ = writeln!(out, "    attachment: {} ({url})", attachment.file_name);
+        }
+    }
+    out
+}
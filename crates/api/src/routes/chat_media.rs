@@ -0,0 +1,464 @@
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::{ImageFormat, ImageReader, Limits};
+
+const CHAT_THUMBNAIL_MAX_EDGE: u32 = 320;
+const CHAT_THUMBNAIL_JPEG_QUALITY: u8 = 80;
+const CHAT_IMAGE_MAX_EDGE: u32 = 16_384;
+const CHAT_IMAGE_MAX_ALLOC_BYTES: u64 = 256 * 1024 * 1024;
+
+const EXIF_TAG_GPS_IFD: u16 = 0x8825;
+
+/// Content type derived from the leading bytes of an upload. Only formats on this
+/// allowlist are accepted, whatever the client declared in the multipart header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct SniffedMedia {
+    pub mime_type: &'static str,
+    pub media_type: &'static str,
+}
+
+impl SniffedMedia {
+    const fn image(mime_type: &'static str) -> Self {
+        Self {
+            mime_type,
+            media_type: "image",
+        }
+    }
+
+    const fn video(mime_type: &'static str) -> Self {
+        Self {
+            mime_type,
+            media_type: "video",
+        }
+    }
+
+    const fn audio(mime_type: &'static str) -> Self {
+        Self {
+            mime_type,
+            media_type: "audio",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct ImagePreview {
+    pub width: u32,
+    pub height: u32,
+    pub thumbnail: Vec<u8>,
+}
+
+pub(super) fn sniff_chat_media(bytes: &[u8]) -> Option<SniffedMedia> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some(SniffedMedia::image("image/jpeg"));
+    }
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some(SniffedMedia::image("image/png"));
+    }
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        return Some(SniffedMedia::image("image/gif"));
+    }
+    if bytes.len() >= 12 && bytes.starts_with(b"RIFF") {
+        return match &bytes[8..12] {
+            b"WEBP" => Some(SniffedMedia::image("image/webp")),
+            b"WAVE" => Some(SniffedMedia::audio("audio/wav")),
+            _ => None,
+        };
+    }
+    if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" {
+        return match &bytes[8..12] {
+            b"qt  " => Some(SniffedMedia::video("video/quicktime")),
+            b"M4A " | b"M4B " => Some(SniffedMedia::audio("audio/mp4")),
+            b"isom" | b"iso2" | b"iso4" | b"iso5" | b"iso6" | b"mp41" | b"mp42" | b"avc1"
+            | b"dash" | b"M4V " | b"MSNV" => Some(SniffedMedia::video("video/mp4")),
+            _ => None,
+        };
+    }
+    if bytes.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        let header = &bytes[..bytes.len().min(64)];
+        return header
+            .windows(4)
+            .any(|window| window == b"webm")
+            .then_some(SniffedMedia::video("video/webm"));
+    }
+    if bytes.starts_with(b"OggS") {
+        return Some(SniffedMedia::audio("audio/ogg"));
+    }
+    if bytes.starts_with(b"ID3") {
+        return Some(SniffedMedia::audio("audio/mpeg"));
+    }
+    if bytes.len() >= 2 && bytes[0] == 0xFF {
+        // ADTS (AAC) uses layer bits 00; MPEG audio frames use a non-zero layer.
+        if bytes[1] & 0xF6 == 0xF0 {
+            return Some(SniffedMedia::audio("audio/aac"));
+        }
+        if bytes[1] & 0xE0 == 0xE0 && bytes[1] & 0x06 != 0 {
+            return Some(SniffedMedia::audio("audio/mpeg"));
+        }
+    }
+    None
+}
+
+/// Removes GPS location tags from embedded EXIF blocks in place. The byte length
+/// of the file never changes; the GPS directory is emptied and its values zeroed.
+pub(super) fn strip_exif_gps(mime_type: &str, bytes: &mut [u8]) {
+    match mime_type {
+        "image/jpeg" => strip_jpeg_exif_gps(bytes),
+        "image/png" => strip_png_exif_gps(bytes),
+        "image/webp" => strip_webp_exif_gps(bytes),
+        _ => {}
+    }
+}
+
+/// Decodes an allowlisted image to read its dimensions and render a JPEG thumbnail
+/// no larger than [`CHAT_THUMBNAIL_MAX_EDGE`] on either side.
+pub(super) fn build_image_preview(mime_type: &str, bytes: &[u8]) -> Option<ImagePreview> {
+    let format = ImageFormat::from_mime_type(mime_type)?;
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), format);
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(CHAT_IMAGE_MAX_EDGE);
+    limits.max_image_height = Some(CHAT_IMAGE_MAX_EDGE);
+    limits.max_alloc = Some(CHAT_IMAGE_MAX_ALLOC_BYTES);
+    reader.limits(limits);
+    let decoded = reader.decode().ok()?;
+
+    let thumbnail = decoded
+        .thumbnail(CHAT_THUMBNAIL_MAX_EDGE, CHAT_THUMBNAIL_MAX_EDGE)
+        .to_rgb8();
+    let mut encoded = Vec::new();
+    JpegEncoder::new_with_quality(&mut encoded, CHAT_THUMBNAIL_JPEG_QUALITY)
+        .encode_image(&thumbnail)
+        .ok()?;
+    Some(ImagePreview {
+        width: decoded.width(),
+        height: decoded.height(),
+        thumbnail: encoded,
+    })
+}
+
+fn strip_jpeg_exif_gps(bytes: &mut [u8]) {
+    let mut offset = 2;
+    while offset + 4 <= bytes.len() {
+        if bytes[offset] != 0xFF {
+            return;
+        }
+        let marker = bytes[offset + 1];
+        // Standalone markers carry no length field.
+        if marker == 0x01 || (0xD0..=0xD7).contains(&marker) || marker == 0xFF {
+            offset += if marker == 0xFF { 1 } else { 2 };
+            continue;
+        }
+        // Start of scan: entropy-coded data follows and no more metadata segments.
+        if marker == 0xDA || marker == 0xD9 {
+            return;
+        }
+        let length = usize::from(u16::from_be_bytes([bytes[offset + 2], bytes[offset + 3]]));
+        let segment_end = offset + 2 + length;
+        if length < 2 || segment_end > bytes.len() {
+            return;
+        }
+        let payload = &mut bytes[offset + 4..segment_end];
+        if marker == 0xE1 && payload.starts_with(b"Exif\0\0") {
+            strip_tiff_gps(&mut payload[6..]);
+        }
+        offset = segment_end;
+    }
+}
+
+fn strip_png_exif_gps(bytes: &mut [u8]) {
+    let mut offset = 8;
+    while offset + 12 <= bytes.len() {
+        let length = u32::from_be_bytes([
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ]) as usize;
+        let data_start = offset + 8;
+        let Some(crc_start) = data_start.checked_add(length) else {
+            return;
+        };
+        if crc_start + 4 > bytes.len() {
+            return;
+        }
+        if &bytes[offset + 4..offset + 8] == b"eXIf" {
+            strip_tiff_gps(&mut bytes[data_start..crc_start]);
+            let crc = crc32(&bytes[offset + 4..crc_start]);
+            bytes[crc_start..crc_start + 4].copy_from_slice(&crc.to_be_bytes());
+        }
+        if &bytes[offset + 4..offset + 8] == b"IEND" {
+            return;
+        }
+        offset = crc_start + 4;
+    }
+}
+
+fn strip_webp_exif_gps(bytes: &mut [u8]) {
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let length = u32::from_le_bytes([
+            bytes[offset + 4],
+            bytes[offset + 5],
+            bytes[offset + 6],
+            bytes[offset + 7],
+        ]) as usize;
+        let data_start = offset + 8;
+        let Some(data_end) = data_start.checked_add(length) else {
+            return;
+        };
+        if data_end > bytes.len() {
+            return;
+        }
+        if &bytes[offset..offset + 4] == b"EXIF" {
+            let payload = &mut bytes[data_start..data_end];
+            // Some encoders keep the JPEG-style "Exif\0\0" prefix inside the chunk.
+            if payload.starts_with(b"Exif\0\0") {
+                strip_tiff_gps(&mut payload[6..]);
+            } else {
+                strip_tiff_gps(payload);
+            }
+        }
+        offset = data_end + (length & 1);
+    }
+}
+
+/// Empties the GPS IFD referenced from IFD0 of a TIFF-structured EXIF block.
+fn strip_tiff_gps(tiff: &mut [u8]) {
+    let little_endian = match tiff.get(..4) {
+        Some([b'I', b'I', 0x2A, 0x00]) => true,
+        Some([b'M', b'M', 0x00, 0x2A]) => false,
+        _ => return,
+    };
+    let read_u16 = |data: &[u8], at: usize| -> Option<u16> {
+        let raw = [*data.get(at)?, *data.get(at + 1)?];
+        Some(if little_endian {
+            u16::from_le_bytes(raw)
+        } else {
+            u16::from_be_bytes(raw)
+        })
+    };
+    let read_u32 = |data: &[u8], at: usize| -> Option<u32> {
+        let raw = [
+            *data.get(at)?,
+            *data.get(at + 1)?,
+            *data.get(at + 2)?,
+            *data.get(at + 3)?,
+        ];
+        Some(if little_endian {
+            u32::from_le_bytes(raw)
+        } else {
+            u32::from_be_bytes(raw)
+        })
+    };
+
+    let Some(ifd0) = read_u32(tiff, 4).map(|value| value as usize) else {
+        return;
+    };
+    let Some(entry_count) = read_u16(tiff, ifd0) else {
+        return;
+    };
+    let gps_ifd = (0..usize::from(entry_count))
+        .map(|index| ifd0 + 2 + index * 12)
+        .find(|entry| read_u16(tiff, *entry) == Some(EXIF_TAG_GPS_IFD))
+        .and_then(|entry| read_u32(tiff, entry + 8))
+        .map(|value| value as usize);
+    let Some(gps_ifd) = gps_ifd else {
+        return;
+    };
+    let Some(gps_count) = read_u16(tiff, gps_ifd) else {
+        return;
+    };
+    let entries_end = gps_ifd + 2 + usize::from(gps_count) * 12;
+    if entries_end > tiff.len() {
+        return;
+    }
+
+    for index in 0..usize::from(gps_count) {
+        let entry = gps_ifd + 2 + index * 12;
+        let (Some(field_type), Some(count)) =
+            (read_u16(tiff, entry + 2), read_u32(tiff, entry + 4))
+        else {
+            continue;
+        };
+        let unit = match field_type {
+            1 | 2 | 6 | 7 => 1,
+            3 | 8 => 2,
+            4 | 9 | 11 => 4,
+            5 | 10 | 12 => 8,
+            _ => 0,
+        };
+        let value_len = unit * count as usize;
+        // Values of four bytes or fewer are stored inline in the entry itself.
+        let value_range = (value_len > 4)
+            .then(|| read_u32(tiff, entry + 8))
+            .flatten()
+            .map(|value_offset| value_offset as usize..value_offset as usize + value_len);
+        if let Some(value) = value_range.and_then(|range| tiff.get_mut(range)) {
+            value.fill(0);
+        }
+    }
+    tiff[gps_ifd..entries_end].fill(0);
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in bytes {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Little-endian TIFF with IFD0 pointing at a GPS IFD holding a latitude rational.
+    fn exif_tiff_with_gps() -> Vec<u8> {
+        let mut tiff = Vec::new();
+        tiff.extend_from_slice(b"II\x2A\x00");
+        tiff.extend_from_slice(&8u32.to_le_bytes());
+        // IFD0: one entry (GPS pointer) at offset 8.
+        tiff.extend_from_slice(&1u16.to_le_bytes());
+        tiff.extend_from_slice(&EXIF_TAG_GPS_IFD.to_le_bytes());
+        tiff.extend_from_slice(&4u16.to_le_bytes());
+        tiff.extend_from_slice(&1u32.to_le_bytes());
+        tiff.extend_from_slice(&26u32.to_le_bytes());
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+        // GPS IFD at offset 26: GPSLatitude, 3 rationals stored at offset 44.
+        tiff.extend_from_slice(&1u16.to_le_bytes());
+        tiff.extend_from_slice(&2u16.to_le_bytes());
+        tiff.extend_from_slice(&5u16.to_le_bytes());
+        tiff.extend_from_slice(&3u32.to_le_bytes());
+        tiff.extend_from_slice(&44u32.to_le_bytes());
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+        for value in [6u32, 1, 12, 1, 30, 1] {
+            tiff.extend_from_slice(&value.to_le_bytes());
+        }
+        tiff
+    }
+
+    fn assert_gps_removed(tiff: &[u8]) {
+        assert_eq!(&tiff[26..28], &[0, 0], "gps entry count must be zeroed");
+        assert!(tiff[44..68].iter().all(|byte| *byte == 0));
+        assert_eq!(&tiff[..8], b"II\x2A\x00\x08\x00\x00\x00");
+    }
+
+    #[test]
+    fn sniffs_allowlisted_signatures() {
+        let cases: [(&[u8], Option<&str>); 10] = [
+            (b"\xFF\xD8\xFF\xE0rest", Some("image/jpeg")),
+            (b"\x89PNG\r\n\x1a\nrest", Some("image/png")),
+            (b"GIF89a....", Some("image/gif")),
+            (b"RIFF\0\0\0\0WEBPVP8 ", Some("image/webp")),
+            (b"RIFF\0\0\0\0WAVEfmt ", Some("audio/wav")),
+            (b"\0\0\0\x18ftypmp42\0\0\0\0", Some("video/mp4")),
+            (b"\0\0\0\x18ftypM4A \0\0\0\0", Some("audio/mp4")),
+            (b"ID3\x04\0\0\0\0\0\0", Some("audio/mpeg")),
+            (b"%PDF-1.7", None),
+            (b"PNGDATA", None),
+        ];
+        for (bytes, expected) in cases {
+            assert_eq!(
+                sniff_chat_media(bytes).map(|media| media.mime_type),
+                expected,
+                "{bytes:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn sniff_rejects_unlisted_containers() {
+        assert_eq!(sniff_chat_media(b"\0\0\0\x18ftypheic\0\0\0\0"), None);
+        assert_eq!(
+            sniff_chat_media(b"\x1A\x45\xDF\xA3\x9F\x42\x82\x88matroska"),
+            None
+        );
+        assert_eq!(
+            sniff_chat_media(b"\x1A\x45\xDF\xA3\x9F\x42\x82\x84webm"),
+            Some(SniffedMedia::video("video/webm"))
+        );
+    }
+
+    #[test]
+    fn strips_gps_from_jpeg_app1() {
+        let tiff = exif_tiff_with_gps();
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+        jpeg.extend_from_slice(b"Exif\0\0");
+        jpeg.extend_from_slice(&tiff);
+        jpeg.extend_from_slice(&[0xFF, 0xD9]);
+        let original_len = jpeg.len();
+
+        strip_exif_gps("image/jpeg", &mut jpeg);
+
+        assert_eq!(jpeg.len(), original_len);
+        assert_gps_removed(&jpeg[12..12 + tiff.len()]);
+    }
+
+    #[test]
+    fn strips_gps_from_png_exif_chunk_and_fixes_crc() {
+        let tiff = exif_tiff_with_gps();
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png.extend_from_slice(&(tiff.len() as u32).to_be_bytes());
+        png.extend_from_slice(b"eXIf");
+        png.extend_from_slice(&tiff);
+        let mut crc_input = b"eXIf".to_vec();
+        crc_input.extend_from_slice(&tiff);
+        png.extend_from_slice(&crc32(&crc_input).to_be_bytes());
+
+        strip_exif_gps("image/png", &mut png);
+
+        let data = &png[16..16 + tiff.len()];
+        assert_gps_removed(data);
+        let mut expected_input = b"eXIf".to_vec();
+        expected_input.extend_from_slice(data);
+        let crc_at = 16 + tiff.len();
+        assert_eq!(
+            &png[crc_at..crc_at + 4],
+            &crc32(&expected_input).to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn strips_gps_from_webp_exif_chunk() {
+        let tiff = exif_tiff_with_gps();
+        let mut webp = b"RIFF\0\0\0\0WEBP".to_vec();
+        webp.extend_from_slice(b"EXIF");
+        webp.extend_from_slice(&(tiff.len() as u32).to_le_bytes());
+        webp.extend_from_slice(&tiff);
+
+        strip_exif_gps("image/webp", &mut webp);
+
+        assert_gps_removed(&webp[20..20 + tiff.len()]);
+    }
+
+    #[test]
+    fn crc32_matches_reference_vector() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn image_preview_reports_dimensions_and_bounds_thumbnail() {
+        let source = image::RgbImage::from_pixel(640, 480, image::Rgb([200, 40, 40]));
+        let mut png = Vec::new();
+        source
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .expect("encode png");
+
+        let preview = build_image_preview("image/png", &png).expect("preview");
+
+        assert_eq!((preview.width, preview.height), (640, 480));
+        let thumbnail = image::load_from_memory_with_format(&preview.thumbnail, ImageFormat::Jpeg)
+            .expect("decode thumbnail");
+        assert_eq!((thumbnail.width(), thumbnail.height()), (320, 240));
+    }
+
+    #[test]
+    fn image_preview_rejects_undecodable_bytes() {
+        assert!(build_image_preview("image/png", b"\x89PNG\r\n\x1a\ngarbage").is_none());
+    }
+}
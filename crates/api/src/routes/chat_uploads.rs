@@ -0,0 +1,957 @@
+use std::collections::HashSet;
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::Bytes;
+use axum::extract::{Extension, Path, State};
+use axum::http::HeaderMap;
+use axum::http::header::{CACHE_CONTROL, CONTENT_LENGTH, CONTENT_TYPE, HeaderValue, LOCATION};
+use axum::http::{HeaderName, StatusCode};
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use base64::Engine as _;
+use rusty_s3::actions::CreateMultipartUpload;
+use rusty_s3::{Bucket as S3Bucket, Credentials as S3Credentials, S3Action};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use validator::Validate;
+
+use crate::error::ApiError;
+use crate::middleware::AuthContext;
+use crate::request_repos;
+use crate::state::{AppState, ChatAttachmentStorage};
+use crate::validation;
+use gotong_domain::chat::ChatService;
+use gotong_domain::identity::ActorIdentity;
+
+use super::{
+    CHAT_ATTACHMENT_MAX_BYTES, ChatAttachmentOriginal, ChatAttachmentResponse,
+    PendingChatAttachment, actor_identity, chat_attachment_file_path, chat_attachment_quota,
+    chat_attachment_response, chat_media, is_valid_chat_attachment_id, map_domain_error,
+    persist_chat_attachment, prepare_chat_image, put_chat_attachment_s3_object,
+};
+
+/// Upper bound for direct and resumable uploads. Images are still capped at
+/// `CHAT_ATTACHMENT_MAX_BYTES` because they are decoded for thumbnails.
+pub(super) const CHAT_ATTACHMENT_RESUMABLE_MAX_BYTES: u64 = 512 * 1024 * 1024;
+/// S3 multipart part size; also the largest tus PATCH body accepted in one request.
+pub(super) const CHAT_ATTACHMENT_PART_BYTES: u64 = 8 * 1024 * 1024;
+const CHAT_ATTACHMENT_UPLOAD_TTL_MS: i64 = 12 * 60 * 60 * 1000;
+const CHAT_ATTACHMENT_SNIFF_BYTES: u64 = 64;
+
+const TUS_VERSION: &str = "1.0.0";
+const TUS_EXTENSIONS: &str = "creation,termination";
+const TUS_CONTENT_TYPE: &str = "application/offset+octet-stream";
+const TUS_RESUMABLE: HeaderName = HeaderName::from_static("tus-resumable");
+const TUS_VERSION_HEADER: HeaderName = HeaderName::from_static("tus-version");
+const TUS_EXTENSION_HEADER: HeaderName = HeaderName::from_static("tus-extension");
+const TUS_MAX_SIZE_HEADER: HeaderName = HeaderName::from_static("tus-max-size");
+const UPLOAD_OFFSET: HeaderName = HeaderName::from_static("upload-offset");
+const UPLOAD_LENGTH: HeaderName = HeaderName::from_static("upload-length");
+const UPLOAD_METADATA: HeaderName = HeaderName::from_static("upload-metadata");
+
+/// Server-side state of an upload that has been started but not yet finalized. It
+/// lives next to the attachment in the storage backend so any API replica, including
+/// one started after a restart, can resume it.
+#[derive(Debug, Deserialize, Serialize)]
+struct ChatAttachmentUploadSession {
+    attachment_id: String,
+    uploaded_by: String,
+    thread_id: Option<String>,
+    file_name: String,
+    size_bytes: u64,
+    #[serde(default)]
+    multipart_upload_id: Option<String>,
+    #[serde(default)]
+    part_count: Option<u16>,
+    created_at_ms: i64,
+    expires_at_ms: i64,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub(super) struct CreateChatAttachmentDirectUploadRequest {
+    #[validate(length(min = 1, max = 255))]
+    file_name: String,
+    #[validate(range(min = 1))]
+    size_bytes: u64,
+    #[validate(length(min = 1, max = 128))]
+    thread_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatAttachmentUploadPart {
+    part_number: u16,
+    url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct ChatAttachmentDirectUploadResponse {
+    attachment_id: String,
+    mode: &'static str,
+    put_url: Option<String>,
+    part_size_bytes: Option<u64>,
+    parts: Vec<ChatAttachmentUploadPart>,
+    finalize_url: String,
+    expires_at_ms: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct ChatAttachmentCompletedPart {
+    part_number: u16,
+    etag: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct FinalizeChatAttachmentDirectUploadRequest {
+    #[serde(default)]
+    parts: Vec<ChatAttachmentCompletedPart>,
+}
+
+fn chat_attachment_upload_session_path(root: &FsPath, attachment_id: &str) -> PathBuf {
+    root.join(format!("{attachment_id}.upload.json"))
+}
+
+fn chat_attachment_upload_part_path(root: &FsPath, attachment_id: &str) -> PathBuf {
+    root.join(format!("{attachment_id}.part"))
+}
+
+fn chat_attachment_s3_upload_session_key(key_prefix: &str, attachment_id: &str) -> String {
+    format!("{key_prefix}/{attachment_id}.upload.json")
+}
+
+/// Where presigned uploads land. The client's URLs stay valid for the whole upload
+/// TTL, so they must never point at the finalized attachment.
+fn chat_attachment_s3_upload_staging_key(key_prefix: &str, attachment_id: &str) -> String {
+    format!("{key_prefix}/{attachment_id}.upload.bin")
+}
+
+fn validate_resumable_upload_size(size_bytes: u64) -> Result<(), ApiError> {
+    if size_bytes == 0 || size_bytes > CHAT_ATTACHMENT_RESUMABLE_MAX_BYTES {
+        return Err(ApiError::Validation(format!(
+            "attachment size must be between 1 and {CHAT_ATTACHMENT_RESUMABLE_MAX_BYTES} bytes"
+        )));
+    }
+    Ok(())
+}
+
+fn new_upload_session(
+    actor: &ActorIdentity,
+    thread_id: Option<String>,
+    file_name: String,
+    size_bytes: u64,
+) -> ChatAttachmentUploadSession {
+    let created_at_ms = gotong_domain::jobs::now_ms();
+    ChatAttachmentUploadSession {
+        attachment_id: gotong_domain::util::uuid_v7_without_dashes(),
+        uploaded_by: actor.user_id.clone(),
+        thread_id,
+        file_name,
+        size_bytes,
+        multipart_upload_id: None,
+        part_count: None,
+        created_at_ms,
+        expires_at_ms: created_at_ms + CHAT_ATTACHMENT_UPLOAD_TTL_MS,
+    }
+}
+
+/// Rejects the upload early when its declared size would already exceed a quota. The
+/// quota is charged again with the real stored size when the upload is finalized.
+async fn precheck_upload_quota(
+    state: &AppState,
+    auth: &AuthContext,
+    actor: &ActorIdentity,
+    thread_id: Option<&str>,
+    size_bytes: u64,
+) -> Result<(), ApiError> {
+    ChatService::new(request_repos::chat_repo(state, auth))
+        .check_attachment_quota(actor, thread_id, size_bytes, &chat_attachment_quota(state))
+        .await
+        .map_err(map_domain_error)
+}
+
+/// Only the uploader may touch an upload session; anyone else sees it as missing.
+fn ensure_upload_session_owner(
+    session: &ChatAttachmentUploadSession,
+    actor: &ActorIdentity,
+) -> Result<(), ApiError> {
+    if session.uploaded_by != actor.user_id || session.expires_at_ms < gotong_domain::jobs::now_ms()
+    {
+        return Err(ApiError::NotFound);
+    }
+    Ok(())
+}
+
+fn pending_from_session(
+    session: &ChatAttachmentUploadSession,
+    sniffed: chat_media::SniffedMedia,
+    size_bytes: u64,
+) -> PendingChatAttachment {
+    PendingChatAttachment {
+        attachment_id: session.attachment_id.clone(),
+        thread_id: session.thread_id.clone(),
+        raw_file_name: session.file_name.clone(),
+        sniffed,
+        size_bytes: size_bytes as usize,
+    }
+}
+
+fn sniff_or_reject(prefix: &[u8]) -> Result<chat_media::SniffedMedia, ApiError> {
+    chat_media::sniff_chat_media(prefix).ok_or_else(|| {
+        ApiError::Validation(
+            "unsupported attachment content; only image/video/audio files are allowed".into(),
+        )
+    })
+}
+
+fn ensure_image_within_decode_limit(
+    sniffed: chat_media::SniffedMedia,
+    size_bytes: u64,
+) -> Result<(), ApiError> {
+    if sniffed.media_type == "image" && size_bytes > CHAT_ATTACHMENT_MAX_BYTES as u64 {
+        return Err(ApiError::Validation(format!(
+            "image attachments are limited to {CHAT_ATTACHMENT_MAX_BYTES} bytes"
+        )));
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Direct-to-S3 uploads
+// ---------------------------------------------------------------------------
+
+struct S3Target<'a> {
+    client: &'a reqwest::Client,
+    bucket: &'a S3Bucket,
+    credentials: &'a S3Credentials,
+    key_prefix: &'a str,
+}
+
+impl<'a> S3Target<'a> {
+    fn from_state(state: &'a AppState) -> Result<Self, ApiError> {
+        match &state.chat_attachment_storage {
+            ChatAttachmentStorage::S3 {
+                client,
+                bucket,
+                credentials,
+                key_prefix,
+            } => Ok(Self {
+                client,
+                bucket,
+                credentials,
+                key_prefix,
+            }),
+            ChatAttachmentStorage::Local { .. } => Err(ApiError::Validation(
+                "direct uploads require the s3 attachment backend; use the resumable upload endpoint"
+                    .into(),
+            )),
+        }
+    }
+
+    async fn get(&self, key: &str, range: Option<u64>) -> Result<Option<Bytes>, ApiError> {
+        let url = self
+            .bucket
+            .get_object(Some(self.credentials), key)
+            .sign(Duration::from_secs(60));
+        let mut request = self.client.get(url);
+        if let Some(range) = range {
+            request = request.header("range", format!("bytes=0-{}", range.saturating_sub(1)));
+        }
+        let response = request.send().await.map_err(|_| ApiError::Internal)?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(ApiError::Internal);
+        }
+        response
+            .bytes()
+            .await
+            .map(Some)
+            .map_err(|_| ApiError::Internal)
+    }
+
+    async fn content_length(&self, key: &str) -> Result<Option<u64>, ApiError> {
+        let url = self
+            .bucket
+            .head_object(Some(self.credentials), key)
+            .sign(Duration::from_secs(60));
+        let response = self
+            .client
+            .head(url)
+            .send()
+            .await
+            .map_err(|_| ApiError::Internal)?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(ApiError::Internal);
+        }
+        Ok(response
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok()))
+    }
+
+    /// Best-effort removal used to clean up rejected or abandoned uploads.
+    async fn delete(&self, key: &str) {
+        let url = self
+            .bucket
+            .delete_object(Some(self.credentials), key)
+            .sign(Duration::from_secs(60));
+        if let Err(err) = self.client.delete(url).send().await {
+            tracing::warn!(error = %err, key, "failed to delete chat attachment object");
+        }
+    }
+
+    async fn load_session(
+        &self,
+        attachment_id: &str,
+    ) -> Result<Option<ChatAttachmentUploadSession>, ApiError> {
+        let key = chat_attachment_s3_upload_session_key(self.key_prefix, attachment_id);
+        let Some(bytes) = self.get(&key, None).await? else {
+            return Ok(None);
+        };
+        serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|_| ApiError::Internal)
+    }
+
+    async fn save_session(&self, session: &ChatAttachmentUploadSession) -> Result<(), ApiError> {
+        let key = chat_attachment_s3_upload_session_key(self.key_prefix, &session.attachment_id);
+        let bytes = serde_json::to_vec(session).map_err(|_| ApiError::Internal)?;
+        put_chat_attachment_s3_object(self.client, self.bucket, self.credentials, &key, bytes).await
+    }
+
+    async fn create_multipart_upload(&self, key: &str) -> Result<String, ApiError> {
+        let url = self
+            .bucket
+            .create_multipart_upload(Some(self.credentials), key)
+            .sign(Duration::from_secs(60));
+        let response = self
+            .client
+            .post(url)
+            .send()
+            .await
+            .map_err(|_| ApiError::Internal)?;
+        if !response.status().is_success() {
+            return Err(ApiError::Internal);
+        }
+        let body = response.text().await.map_err(|_| ApiError::Internal)?;
+        let multipart =
+            CreateMultipartUpload::parse_response(&body).map_err(|_| ApiError::Internal)?;
+        Ok(multipart.upload_id().to_string())
+    }
+
+    async fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        etags: &[&str],
+    ) -> Result<(), ApiError> {
+        let action = self.bucket.complete_multipart_upload(
+            Some(self.credentials),
+            key,
+            upload_id,
+            etags.iter().copied(),
+        );
+        let url = action.sign(Duration::from_secs(60));
+        let body = action.body();
+        let response = self
+            .client
+            .post(url)
+            .body(body)
+            .send()
+            .await
+            .map_err(|_| ApiError::Internal)?;
+        if response.status().is_client_error() {
+            return Err(ApiError::Validation(
+                "multipart upload could not be completed; check part etags".into(),
+            ));
+        }
+        if !response.status().is_success() {
+            return Err(ApiError::Internal);
+        }
+        Ok(())
+    }
+}
+
+/// Issues presigned URLs so the client can send the bytes straight to S3: one PUT for
+/// files up to a single part, otherwise one URL per multipart part.
+pub(super) async fn create_chat_attachment_direct_upload(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Json(payload): Json<CreateChatAttachmentDirectUploadRequest>,
+) -> Result<Json<ChatAttachmentDirectUploadResponse>, ApiError> {
+    validation::validate(&payload)?;
+    let actor = actor_identity(&auth)?;
+    let s3 = S3Target::from_state(&state)?;
+    validate_resumable_upload_size(payload.size_bytes)?;
+    precheck_upload_quota(
+        &state,
+        &auth,
+        &actor,
+        payload.thread_id.as_deref(),
+        payload.size_bytes,
+    )
+    .await?;
+
+    let mut session = new_upload_session(
+        &actor,
+        payload.thread_id,
+        payload.file_name,
+        payload.size_bytes,
+    );
+    let attachment_id = session.attachment_id.clone();
+    let staging_key = chat_attachment_s3_upload_staging_key(s3.key_prefix, &attachment_id);
+    let url_ttl = Duration::from_millis(CHAT_ATTACHMENT_UPLOAD_TTL_MS as u64);
+
+    let mut put_url = None;
+    let mut parts = Vec::new();
+    if session.size_bytes <= CHAT_ATTACHMENT_PART_BYTES {
+        put_url = Some(
+            s3.bucket
+                .put_object(Some(s3.credentials), &staging_key)
+                .sign(url_ttl)
+                .to_string(),
+        );
+    } else {
+        let upload_id = s3.create_multipart_upload(&staging_key).await?;
+        let part_count = session.size_bytes.div_ceil(CHAT_ATTACHMENT_PART_BYTES) as u16;
+        parts = (1..=part_count)
+            .map(|part_number| ChatAttachmentUploadPart {
+                part_number,
+                url: s3
+                    .bucket
+                    .upload_part(Some(s3.credentials), &staging_key, part_number, &upload_id)
+                    .sign(url_ttl)
+                    .to_string(),
+            })
+            .collect();
+        session.multipart_upload_id = Some(upload_id);
+        session.part_count = Some(part_count);
+    }
+    s3.save_session(&session).await?;
+
+    Ok(Json(ChatAttachmentDirectUploadResponse {
+        finalize_url: format!("/v1/chat/attachments/direct-uploads/{attachment_id}/finalize"),
+        attachment_id,
+        mode: if put_url.is_some() {
+            "single"
+        } else {
+            "multipart"
+        },
+        part_size_bytes: session
+            .multipart_upload_id
+            .as_ref()
+            .map(|_| CHAT_ATTACHMENT_PART_BYTES),
+        put_url,
+        parts,
+        expires_at_ms: session.expires_at_ms,
+    }))
+}
+
+/// Called by the client once every byte is in S3. Completes the multipart upload,
+/// validates the staged object like an API upload, and records the attachment under
+/// its final key. Repeating the call after success returns the recorded attachment.
+/// Content the server rejects is deleted; any other failure, such as a full quota,
+/// keeps the upload so the client can finalize again.
+pub(super) async fn finalize_chat_attachment_direct_upload(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(attachment_id): Path<String>,
+    payload: Option<Json<FinalizeChatAttachmentDirectUploadRequest>>,
+) -> Result<Json<ChatAttachmentResponse>, ApiError> {
+    if !is_valid_chat_attachment_id(&attachment_id) {
+        return Err(ApiError::Validation("invalid attachment_id".into()));
+    }
+    let actor = actor_identity(&auth)?;
+    let s3 = S3Target::from_state(&state)?;
+    let service = ChatService::new(request_repos::chat_repo(&state, &auth));
+    let Some(mut session) = s3.load_session(&attachment_id).await? else {
+        let attachment = service
+            .get_attachment(&actor, &attachment_id)
+            .await
+            .map_err(map_domain_error)?;
+        return Ok(Json(chat_attachment_response(&state, attachment)));
+    };
+    ensure_upload_session_owner(&session, &actor)?;
+    let payload = payload.map(|Json(payload)| payload).unwrap_or_default();
+
+    let staging_key = chat_attachment_s3_upload_staging_key(s3.key_prefix, &attachment_id);
+    if let (Some(upload_id), Some(part_count)) =
+        (session.multipart_upload_id.as_deref(), session.part_count)
+    {
+        let mut completed = payload.parts;
+        completed.sort_by_key(|part| part.part_number);
+        let numbered_in_order = completed
+            .iter()
+            .enumerate()
+            .all(|(index, part)| usize::from(part.part_number) == index + 1);
+        if completed.len() != usize::from(part_count) || !numbered_in_order {
+            return Err(ApiError::Validation(format!(
+                "expected etags for parts 1..={part_count}"
+            )));
+        }
+        let etags = completed
+            .iter()
+            .map(|part| part.etag.as_str())
+            .collect::<Vec<_>>();
+        s3.complete_multipart_upload(&staging_key, upload_id, &etags)
+            .await?;
+        // A retried finalize must not complete the upload a second time.
+        session.multipart_upload_id = None;
+        session.part_count = None;
+        s3.save_session(&session).await?;
+    }
+
+    // Not uploaded yet: the session stays so the client can upload and retry.
+    let Some(size_bytes) = s3.content_length(&staging_key).await? else {
+        return Err(ApiError::Validation(
+            "uploaded object not found; upload the file before finalizing".into(),
+        ));
+    };
+    let staged = match prepare_staged_s3_object(&s3, &session, &staging_key, size_bytes).await {
+        Ok(staged) => staged,
+        Err(err) => {
+            if matches!(err, ApiError::Validation(_)) {
+                discard_direct_upload(&s3, &attachment_id, &staging_key).await;
+            }
+            return Err(err);
+        }
+    };
+    let (pending, original, preview) = staged;
+    let attachment =
+        persist_chat_attachment(&state, &service, &actor, pending, original, preview).await?;
+    discard_direct_upload(&s3, &attachment_id, &staging_key).await;
+    Ok(Json(chat_attachment_response(&state, attachment)))
+}
+
+async fn discard_direct_upload(s3: &S3Target<'_>, attachment_id: &str, staging_key: &str) {
+    s3.delete(staging_key).await;
+    s3.delete(&chat_attachment_s3_upload_session_key(
+        s3.key_prefix,
+        attachment_id,
+    ))
+    .await;
+}
+
+type StagedChatAttachment = (
+    PendingChatAttachment,
+    ChatAttachmentOriginal,
+    Option<chat_media::ImagePreview>,
+);
+
+/// Sniffs and size-checks the staged object. `Validation` errors mean the content
+/// itself is unacceptable and retrying cannot help.
+async fn prepare_staged_s3_object(
+    s3: &S3Target<'_>,
+    session: &ChatAttachmentUploadSession,
+    staging_key: &str,
+    size_bytes: u64,
+) -> Result<StagedChatAttachment, ApiError> {
+    validate_resumable_upload_size(size_bytes)?;
+    let prefix = s3
+        .get(staging_key, Some(CHAT_ATTACHMENT_SNIFF_BYTES))
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    let sniffed = sniff_or_reject(&prefix)?;
+    ensure_image_within_decode_limit(sniffed, size_bytes)?;
+
+    let pending = pending_from_session(session, sniffed, size_bytes);
+    if sniffed.media_type != "image" {
+        return Ok((
+            pending,
+            ChatAttachmentOriginal::S3Staged(staging_key.to_string()),
+            None,
+        ));
+    }
+    let file_bytes = s3.get(staging_key, None).await?.ok_or(ApiError::NotFound)?;
+    let (file_bytes, preview) = prepare_chat_image(sniffed, file_bytes.to_vec()).await?;
+    let pending = PendingChatAttachment {
+        size_bytes: file_bytes.len(),
+        ..pending
+    };
+    Ok((
+        pending,
+        ChatAttachmentOriginal::Bytes(file_bytes),
+        Some(preview),
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// tus 1.0 resumable uploads (local backend)
+// ---------------------------------------------------------------------------
+
+fn local_upload_root(state: &AppState) -> Result<&FsPath, ApiError> {
+    match &state.chat_attachment_storage {
+        ChatAttachmentStorage::Local { root } => Ok(root.as_path()),
+        ChatAttachmentStorage::S3 { .. } => Err(ApiError::Validation(
+            "resumable uploads require the local attachment backend; use direct uploads".into(),
+        )),
+    }
+}
+
+fn tus_response(status: StatusCode) -> Response {
+    let mut response = status.into_response();
+    response
+        .headers_mut()
+        .insert(TUS_RESUMABLE, HeaderValue::from_static(TUS_VERSION));
+    response
+}
+
+/// tus requires every request except OPTIONS to name the protocol version it speaks;
+/// returns the 412 response for requests that do not.
+fn tus_version_rejection(headers: &HeaderMap) -> Option<Response> {
+    let version = headers
+        .get(TUS_RESUMABLE)
+        .and_then(|value| value.to_str().ok());
+    if version == Some(TUS_VERSION) {
+        return None;
+    }
+    let mut response = tus_response(StatusCode::PRECONDITION_FAILED);
+    response
+        .headers_mut()
+        .insert(TUS_VERSION_HEADER, HeaderValue::from_static(TUS_VERSION));
+    Some(response)
+}
+
+fn header_u64(headers: &HeaderMap, name: &HeaderName) -> Option<u64> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+}
+
+/// Parses `Upload-Metadata`: comma-separated `key base64(value)` pairs.
+fn parse_tus_metadata(headers: &HeaderMap) -> Vec<(String, String)> {
+    let Some(raw) = headers
+        .get(UPLOAD_METADATA)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return Vec::new();
+    };
+    raw.split(',')
+        .filter_map(|pair| {
+            let mut parts = pair.trim().splitn(2, ' ');
+            let key = parts.next()?.trim();
+            if key.is_empty() {
+                return None;
+            }
+            let value = parts
+                .next()
+                .and_then(|encoded| {
+                    base64::engine::general_purpose::STANDARD
+                        .decode(encoded.trim())
+                        .ok()
+                })
+                .and_then(|decoded| String::from_utf8(decoded).ok())
+                .unwrap_or_default();
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+/// Exclusive hold on one tus upload for the length of a PATCH or DELETE, so the
+/// `Upload-Offset` check and the append cannot interleave with another request's.
+struct TusUploadClaim {
+    in_flight: Arc<std::sync::Mutex<HashSet<String>>>,
+    attachment_id: String,
+}
+
+impl TusUploadClaim {
+    /// `None` when another request already holds the upload; the caller answers 409
+    /// and the client re-syncs its offset with HEAD.
+    fn acquire(state: &AppState, attachment_id: &str) -> Option<Self> {
+        let claimed = state
+            .tus_uploads_in_flight
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(attachment_id.to_string());
+        claimed.then(|| Self {
+            in_flight: state.tus_uploads_in_flight.clone(),
+            attachment_id: attachment_id.to_string(),
+        })
+    }
+}
+
+impl Drop for TusUploadClaim {
+    fn drop(&mut self) {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&self.attachment_id);
+    }
+}
+
+async fn load_local_session(
+    root: &FsPath,
+    attachment_id: &str,
+) -> Result<ChatAttachmentUploadSession, ApiError> {
+    let bytes = tokio::fs::read(chat_attachment_upload_session_path(root, attachment_id))
+        .await
+        .map_err(|_| ApiError::NotFound)?;
+    serde_json::from_slice(&bytes).map_err(|_| ApiError::Internal)
+}
+
+async fn local_upload_offset(root: &FsPath, attachment_id: &str) -> Result<u64, ApiError> {
+    tokio::fs::metadata(chat_attachment_upload_part_path(root, attachment_id))
+        .await
+        .map(|metadata| metadata.len())
+        .map_err(|_| ApiError::NotFound)
+}
+
+async fn remove_local_upload(root: &FsPath, attachment_id: &str) {
+    let _ = tokio::fs::remove_file(chat_attachment_upload_part_path(root, attachment_id)).await;
+    let _ = tokio::fs::remove_file(chat_attachment_upload_session_path(root, attachment_id)).await;
+}
+
+pub(super) async fn tus_options() -> Response {
+    let mut response = tus_response(StatusCode::NO_CONTENT);
+    let headers = response.headers_mut();
+    headers.insert(TUS_VERSION_HEADER, HeaderValue::from_static(TUS_VERSION));
+    headers.insert(
+        TUS_EXTENSION_HEADER,
+        HeaderValue::from_static(TUS_EXTENSIONS),
+    );
+    headers.insert(
+        TUS_MAX_SIZE_HEADER,
+        HeaderValue::from(CHAT_ATTACHMENT_RESUMABLE_MAX_BYTES),
+    );
+    response
+}
+
+/// tus creation: reserves an upload of `Upload-Length` bytes. `Upload-Metadata` may
+/// carry `filename` and `thread_id`.
+pub(super) async fn tus_create_upload(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    if let Some(response) = tus_version_rejection(&headers) {
+        return Ok(response);
+    }
+    let actor = actor_identity(&auth)?;
+    let root = local_upload_root(&state)?;
+    let Some(size_bytes) = header_u64(&headers, &UPLOAD_LENGTH) else {
+        return Err(ApiError::Validation(
+            "Upload-Length header is required".into(),
+        ));
+    };
+    if size_bytes > CHAT_ATTACHMENT_RESUMABLE_MAX_BYTES {
+        return Ok(tus_response(StatusCode::PAYLOAD_TOO_LARGE));
+    }
+    validate_resumable_upload_size(size_bytes)?;
+
+    let mut file_name = String::new();
+    let mut thread_id = None;
+    for (key, value) in parse_tus_metadata(&headers) {
+        match key.as_str() {
+            "filename" | "name" => file_name = value,
+            "thread_id" if !value.trim().is_empty() => thread_id = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+    precheck_upload_quota(&state, &auth, &actor, thread_id.as_deref(), size_bytes).await?;
+
+    let session = new_upload_session(&actor, thread_id, file_name, size_bytes);
+    tokio::fs::create_dir_all(root)
+        .await
+        .map_err(|_| ApiError::Internal)?;
+    tokio::fs::write(
+        chat_attachment_upload_part_path(root, &session.attachment_id),
+        b"",
+    )
+    .await
+    .map_err(|_| ApiError::Internal)?;
+    let session_bytes = serde_json::to_vec(&session).map_err(|_| ApiError::Internal)?;
+    tokio::fs::write(
+        chat_attachment_upload_session_path(root, &session.attachment_id),
+        session_bytes,
+    )
+    .await
+    .map_err(|_| ApiError::Internal)?;
+
+    let mut response = tus_response(StatusCode::CREATED);
+    let location = format!("/v1/chat/attachments/tus/{}", session.attachment_id);
+    response.headers_mut().insert(
+        LOCATION,
+        HeaderValue::from_str(&location).map_err(|_| ApiError::Internal)?,
+    );
+    response
+        .headers_mut()
+        .insert(UPLOAD_OFFSET, HeaderValue::from(0u64));
+    Ok(response)
+}
+
+/// tus offset discovery: how many bytes the server already holds.
+pub(super) async fn tus_upload_status(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(attachment_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    if let Some(response) = tus_version_rejection(&headers) {
+        return Ok(response);
+    }
+    if !is_valid_chat_attachment_id(&attachment_id) {
+        return Err(ApiError::NotFound);
+    }
+    let actor = actor_identity(&auth)?;
+    let root = local_upload_root(&state)?;
+    let session = load_local_session(root, &attachment_id).await?;
+    ensure_upload_session_owner(&session, &actor)?;
+    let offset = local_upload_offset(root, &attachment_id).await?;
+
+    let mut response = tus_response(StatusCode::OK);
+    let headers = response.headers_mut();
+    headers.insert(UPLOAD_OFFSET, HeaderValue::from(offset));
+    headers.insert(UPLOAD_LENGTH, HeaderValue::from(session.size_bytes));
+    headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    Ok(response)
+}
+
+/// tus core PATCH: appends a chunk at `Upload-Offset`. The request that supplies the
+/// final byte also validates and records the attachment, whose id is the upload id.
+pub(super) async fn tus_append_upload(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(attachment_id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, ApiError> {
+    if let Some(response) = tus_version_rejection(&headers) {
+        return Ok(response);
+    }
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok());
+    if content_type != Some(TUS_CONTENT_TYPE) {
+        return Ok(tus_response(StatusCode::UNSUPPORTED_MEDIA_TYPE));
+    }
+    if !is_valid_chat_attachment_id(&attachment_id) {
+        return Err(ApiError::NotFound);
+    }
+    let actor = actor_identity(&auth)?;
+    let root = local_upload_root(&state)?;
+    let session = load_local_session(root, &attachment_id).await?;
+    ensure_upload_session_owner(&session, &actor)?;
+
+    let Some(_claim) = TusUploadClaim::acquire(&state, &attachment_id) else {
+        return Err(ApiError::Conflict);
+    };
+    let offset = local_upload_offset(root, &attachment_id).await?;
+    if header_u64(&headers, &UPLOAD_OFFSET) != Some(offset) {
+        return Err(ApiError::Conflict);
+    }
+    let new_offset = offset + body.len() as u64;
+    if new_offset > session.size_bytes {
+        return Err(ApiError::Validation(
+            "chunk extends past the declared Upload-Length".into(),
+        ));
+    }
+    let part_path = chat_attachment_upload_part_path(root, &attachment_id);
+    let mut part_file = tokio::fs::OpenOptions::new()
+        .append(true)
+        .open(&part_path)
+        .await
+        .map_err(|_| ApiError::NotFound)?;
+    part_file
+        .write_all(&body)
+        .await
+        .map_err(|_| ApiError::Internal)?;
+    part_file.flush().await.map_err(|_| ApiError::Internal)?;
+
+    if new_offset == session.size_bytes {
+        let service = ChatService::new(request_repos::chat_repo(&state, &auth));
+        let result =
+            finalize_local_upload(&state, &service, &actor, &session, root, &part_path).await;
+        remove_local_upload(root, &attachment_id).await;
+        result?;
+    }
+
+    let mut response = tus_response(StatusCode::NO_CONTENT);
+    response
+        .headers_mut()
+        .insert(UPLOAD_OFFSET, HeaderValue::from(new_offset));
+    Ok(response)
+}
+
+async fn finalize_local_upload(
+    state: &AppState,
+    service: &ChatService,
+    actor: &ActorIdentity,
+    session: &ChatAttachmentUploadSession,
+    root: &FsPath,
+    part_path: &FsPath,
+) -> Result<(), ApiError> {
+    let mut prefix = Vec::new();
+    tokio::fs::File::open(part_path)
+        .await
+        .map_err(|_| ApiError::NotFound)?
+        .take(CHAT_ATTACHMENT_SNIFF_BYTES)
+        .read_to_end(&mut prefix)
+        .await
+        .map_err(|_| ApiError::Internal)?;
+    let sniffed = sniff_or_reject(&prefix)?;
+    ensure_image_within_decode_limit(sniffed, session.size_bytes)?;
+
+    let pending = pending_from_session(session, sniffed, session.size_bytes);
+    let (original, preview, pending) = if sniffed.media_type == "image" {
+        let file_bytes = tokio::fs::read(part_path)
+            .await
+            .map_err(|_| ApiError::Internal)?;
+        let (file_bytes, preview) = prepare_chat_image(sniffed, file_bytes).await?;
+        let pending = PendingChatAttachment {
+            size_bytes: file_bytes.len(),
+            ..pending
+        };
+        (
+            ChatAttachmentOriginal::Bytes(file_bytes),
+            Some(preview),
+            pending,
+        )
+    } else {
+        (
+            ChatAttachmentOriginal::LocalFile(part_path.to_path_buf()),
+            None,
+            pending,
+        )
+    };
+    let attachment_id = pending.attachment_id.clone();
+    if let Err(err) =
+        persist_chat_attachment(state, service, actor, pending, original, preview).await
+    {
+        let _ = tokio::fs::remove_file(chat_attachment_file_path(root, &attachment_id)).await;
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// tus termination: discards an unfinished upload.
+pub(super) async fn tus_delete_upload(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(attachment_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    if let Some(response) = tus_version_rejection(&headers) {
+        return Ok(response);
+    }
+    if !is_valid_chat_attachment_id(&attachment_id) {
+        return Err(ApiError::NotFound);
+    }
+    let actor = actor_identity(&auth)?;
+    let root = local_upload_root(&state)?;
+    let session = load_local_session(root, &attachment_id).await?;
+    ensure_upload_session_owner(&session, &actor)?;
+    let Some(_claim) = TusUploadClaim::acquire(&state, &attachment_id) else {
+        return Err(ApiError::Conflict);
+    };
+    remove_local_upload(root, &attachment_id).await;
+    Ok(tus_response(StatusCode::NO_CONTENT))
+}
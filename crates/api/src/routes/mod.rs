@@ -5,40 +5,51 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use axum::extract::ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade};
-use axum::extract::{Extension, Multipart, Path, Query, State};
+use axum::extract::{DefaultBodyLimit, Extension, Multipart, Path, Query, State};
 use axum::{
     Json, Router,
     extract::ws::close_code,
     http::{
         HeaderMap, StatusCode,
-        header::{CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_TYPE, HeaderValue, LOCATION},
+        header::{
+            ACCEPT, CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_TYPE, HeaderName, HeaderValue,
+            LOCATION,
+        },
     },
     middleware,
     response::sse::{Event, KeepAlive, Sse},
-    response::{IntoResponse, Response},
-    routing::{delete, get, post},
+    response::{Html, IntoResponse, Response},
+    routing::{delete, get, head, patch, post, put},
 };
 use futures_util::{SinkExt, StreamExt};
 use gotong_domain::{
     adaptive_path::{
-        AdaptivePathBranchDraftInput, AdaptivePathCheckpointDraftInput, AdaptivePathEditorRole,
-        AdaptivePathEvent, AdaptivePathPhaseDraftInput, AdaptivePathPlan,
-        AdaptivePathPlanPayloadDraft, AdaptivePathService, AdaptivePathSuggestion,
-        CreateAdaptivePathInput, SuggestAdaptivePathInput, SuggestionReviewInput,
-        UpdateAdaptivePathInput,
+        AdaptivePathBranchDraftInput, AdaptivePathCheckpointDraftInput, AdaptivePathEvent,
+        AdaptivePathPhaseDraftInput, AdaptivePathPlan, AdaptivePathPlanDiff,
+        AdaptivePathPlanPayloadDraft, AdaptivePathPlanVersionSummary, AdaptivePathService,
+        AdaptivePathSuggestion, CreateAdaptivePathInput, RollbackAdaptivePathInput,
+        SuggestAdaptivePathInput, SuggestionReviewInput, UpdateAdaptivePathInput,
     },
+    adaptive_path_roles::AdaptivePathRoleResolver,
     chat::{
-        ChatMember, ChatMessage, ChatReadCursor, ChatService, ChatThread, ChatThreadCreate,
-        MessageCatchup, SendMessageInput, build_message_catchup,
+        ChatAttachment, ChatAttachmentQuota, ChatMember, ChatMessage, ChatReadCursor,
+        ChatReadReceipt, ChatRetentionPolicy, ChatService, ChatThread, ChatThreadCreate,
+        ChatUnreadSummary, MessageCatchup, SendMessageInput, build_message_catchup,
     },
     contributions::{Contribution, ContributionCreate, ContributionService, ContributionType},
+    digest::{
+        DigestFrequency, DigestLocale, DigestService, DigestSettingsUpdate, DigestSubscription,
+    },
     discovery::{
-        DiscoveryService, FEED_SOURCE_CONTRIBUTION, FEED_SOURCE_ONTOLOGY_NOTE, FEED_SOURCE_VOUCH,
-        FeedIngestInput, FeedListQuery, FeedSuggestion, FeedSuggestionsQuery, InAppNotification,
-        NotificationListQuery, PagedNotifications, SearchListQuery, SearchPage, WeeklyDigest,
+        DiscoveryEvent, DiscoveryService, FEED_SOURCE_CONTRIBUTION, FEED_SOURCE_ONTOLOGY_NOTE,
+        FEED_SOURCE_VOUCH, FeedFollowPreference, FeedIngestInput, FeedListQuery, FeedMode,
+        FeedMonitorPreference, FeedRankingDebug, FeedRankingWeights, FeedSuggestion,
+        FeedSuggestionsQuery, InAppNotification, NotificationListQuery, PagedNotifications,
+        SearchListQuery, SearchPage, WeeklyDigest,
     },
     error::DomainError,
     evidence::{Evidence, EvidenceCreate, EvidenceService, EvidenceType},
+    geo::{GeoPoint, GeoRadius},
     idempotency::BeginOutcome,
     identity::ActorIdentity,
     jobs::{
@@ -49,14 +60,33 @@ use gotong_domain::{
     moderation::{
         ContentModeration, ModerationApplyCommand, ModerationDecision, ModerationService,
     },
+    notification_preferences::{
+        NotificationPreferenceService, NotificationPreferences, NotificationPreferencesUpdate,
+    },
     ontology::{
-        ActionType, NoteFeedbackCounts, OntologyConcept, OntologyEdgeKind, OntologyNoteCreate,
-        OntologyTripleCreate,
+        ActionType, CONCEPT_MERGE_REASON_ADMIN, NoteFeedbackCounts, OntologyConcept,
+        OntologyConceptLabel, OntologyConceptLabels, OntologyConceptMergeResult, OntologyEdgeKind,
+        OntologyNote, OntologyNoteCreate, OntologyTripleCreate,
+    },
+    ontology_autocomplete::{
+        ConceptAutocompleteQuery, ConceptSuggestion, autocomplete_concepts,
+        normalize_concept_labels,
     },
+    ontology_export::{
+        DEFAULT_EXPORT_PAGE_SIZE, OntologyExportFilter, OntologyExportFormat, load_ontology_export,
+        render_ontology_export,
+    },
+    ontology_gazetteer::{ReverseGeocodeResult, located_at_triple, reverse_geocode},
+    ontology_merge::{ConceptMergeCommand, merge_ontology_concepts},
+    ontology_query::{OntologyGraphPage, OntologyGraphQuery, query_ontology_graph},
+    ontology_ranking::{NoteRanking, rank_ontology_note},
+    ontology_revision::{self, NoteEditCommand, NoteHistory},
     ports::group::{GroupJoinRequestRecord, GroupMemberRecord, GroupRecord},
     ports::idempotency::{IdempotencyKey, IdempotencyResponse},
     ports::jobs::JobType,
+    push::{PushSubscription, PushSubscriptionInput, PushSubscriptionService},
     ranking::wilson_score,
+    trending::{TrendingTopic, TrendingTopicKind, normalize_trending_limit},
     vault::{
         AddTrustee, CreateVaultDraft, ExpireVault, PublishVault, RemoveTrustee, RevokeVault,
         SealVault, UpdateVaultDraft, VaultEntry, VaultService, VaultTimelineEvent,
@@ -67,6 +97,7 @@ use gotong_domain::{
     },
 };
 use gotong_infra::auth::{SigninParams, SignupParams};
+use gotong_infra::config::AppConfig;
 use gotong_infra::markov_client::{
     CacheMetadata, CachedJson, MarkovClientError, MarkovProfileSnapshot,
 };
@@ -80,6 +111,9 @@ use tokio::time::interval;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use validator::Validate;
 
+mod chat_export;
+mod chat_media;
+mod chat_uploads;
 mod edgepod;
 
 use crate::middleware::AuthContext;
@@ -88,9 +122,9 @@ use crate::{
     error::ApiError,
     middleware as app_middleware, observability,
     state::{
-        AppState, ChatAttachmentStorage, TriageSessionMessageState, TriageSessionState,
-        WitnessImpactVerificationState, WitnessSignalEntry, WitnessSignalState,
-        WitnessStempelObjection, WitnessStempelState,
+        AppState, ChatAttachmentStorage, ChatRealtimeEvent, DiscoveryRealtimeSubscription,
+        TriageSessionMessageState, TriageSessionState, WitnessImpactVerificationState,
+        WitnessSignalEntry, WitnessSignalState, WitnessStempelObjection, WitnessStempelState,
     },
     validation,
 };
@@ -123,6 +157,22 @@ pub fn router(state: AppState) -> Router {
             "/v1/adaptive-path/plans/:plan_id/update",
             post(update_adaptive_path_plan),
         )
+        .route(
+            "/v1/adaptive-path/plans/:plan_id/versions",
+            get(list_adaptive_path_plan_versions),
+        )
+        .route(
+            "/v1/adaptive-path/plans/:plan_id/versions/:version",
+            get(get_adaptive_path_plan_version),
+        )
+        .route(
+            "/v1/adaptive-path/plans/:plan_id/diff",
+            get(diff_adaptive_path_plan_versions),
+        )
+        .route(
+            "/v1/adaptive-path/plans/:plan_id/rollback",
+            post(rollback_adaptive_path_plan),
+        )
         .route(
             "/v1/adaptive-path/plans/:plan_id/events",
             get(list_adaptive_path_events),
@@ -180,10 +230,15 @@ pub fn router(state: AppState) -> Router {
         )
         .route("/v1/moderations/:content_id", get(get_moderation_view))
         .route("/v1/feed/suggestions", get(list_discovery_feed_suggestions))
+        .route("/v1/feed/trending", get(list_discovery_feed_trending))
         .route(
             "/v1/feed/preferences/monitor/:witness_id",
             post(set_feed_monitor_preference),
         )
+        .route(
+            "/v1/feed/preferences/follow",
+            get(list_feed_follow_preferences),
+        )
         .route(
             "/v1/feed/preferences/follow/:entity_id",
             post(set_feed_follow_preference),
@@ -195,6 +250,18 @@ pub fn router(state: AppState) -> Router {
             "/v1/ontology/concepts/:qid",
             get(get_ontology_concept_by_qid),
         )
+        .route(
+            "/v1/ontology/concepts/:qid/merge",
+            post(merge_ontology_concept),
+        )
+        .route(
+            "/v1/ontology/concepts/:qid/labels",
+            put(replace_ontology_concept_labels),
+        )
+        .route(
+            "/v1/ontology/autocomplete",
+            get(autocomplete_ontology_concepts),
+        )
         .route(
             "/v1/ontology/concepts/:concept_id/broader/:broader_id",
             post(add_ontology_broader_edge),
@@ -203,7 +270,22 @@ pub fn router(state: AppState) -> Router {
             "/v1/ontology/concepts/:concept_id/hierarchy",
             get(list_ontology_hierarchy),
         )
+        .route("/v1/ontology/export", get(export_ontology))
         .route("/v1/ontology/feed", post(create_ontology_feed))
+        .route("/v1/ontology/graph/notes", get(query_ontology_graph_notes))
+        .route(
+            "/v1/ontology/places/reverse",
+            get(reverse_geocode_ontology_place),
+        )
+        .route("/v1/ontology/notes/:note_id", patch(update_ontology_note))
+        .route(
+            "/v1/ontology/notes/:note_id/retract",
+            post(retract_ontology_note),
+        )
+        .route(
+            "/v1/ontology/notes/:note_id/versions",
+            get(list_ontology_note_versions),
+        )
         .route(
             "/v1/ontology/notes/:note_id/vouches",
             post(vouch_ontology_note),
@@ -224,6 +306,26 @@ pub fn router(state: AppState) -> Router {
             "/v1/notifications/weekly-digest",
             get(discovery_weekly_digest),
         )
+        .route(
+            "/v1/notifications/digest-settings",
+            get(get_digest_settings).put(update_digest_settings),
+        )
+        .route(
+            "/v1/notifications/preferences",
+            get(get_notification_preferences).put(update_notification_preferences),
+        )
+        .route(
+            "/v1/notifications/push/public-key",
+            get(get_web_push_public_key),
+        )
+        .route(
+            "/v1/notifications/push/subscriptions",
+            get(list_push_subscriptions).post(create_push_subscription),
+        )
+        .route(
+            "/v1/notifications/push/subscriptions/:subscription_id",
+            delete(delete_push_subscription),
+        )
         .route(
             "/v1/notifications/unread-count",
             get(discovery_unread_count),
@@ -233,6 +335,8 @@ pub fn router(state: AppState) -> Router {
             post(mark_notification_read),
         )
         .route("/v1/notifications", get(list_discovery_notifications))
+        .route("/v1/realtime/stream", get(stream_discovery_events_sse))
+        .route("/v1/realtime/ws", get(stream_discovery_events_ws))
         .route("/v1/triage/operator", post(triage_operator_stub))
         .route("/v1/triage/sessions", post(start_triage_session))
         .route(
@@ -385,10 +489,19 @@ pub fn router(state: AppState) -> Router {
             "/v1/chat/threads",
             post(create_chat_thread).get(list_chat_threads),
         )
+        .route("/v1/chat/unread-count", get(get_chat_unread_summary))
         .route(
             "/v1/chat/threads/:thread_id/members",
             get(list_chat_members),
         )
+        .route(
+            "/v1/chat/threads/:thread_id/retention",
+            post(set_chat_thread_retention),
+        )
+        .route(
+            "/v1/chat/threads/:thread_id/export",
+            get(chat_export::export_chat_thread),
+        )
         .route("/v1/chat/threads/:thread_id/join", post(join_chat_thread))
         .route("/v1/chat/threads/:thread_id/leave", post(leave_chat_thread))
         .route(
@@ -399,7 +512,36 @@ pub fn router(state: AppState) -> Router {
             "/v1/chat/threads/:thread_id/messages/send",
             post(send_chat_message),
         )
+        .route(
+            "/v1/chat/threads/:thread_id/messages/:message_id/seen-by",
+            get(list_chat_message_read_receipts),
+        )
         .route("/v1/chat/attachments/upload", post(upload_chat_attachment))
+        .route(
+            "/v1/chat/attachments/direct-uploads",
+            post(chat_uploads::create_chat_attachment_direct_upload),
+        )
+        .route(
+            "/v1/chat/attachments/direct-uploads/:attachment_id/finalize",
+            post(chat_uploads::finalize_chat_attachment_direct_upload),
+        )
+        .route(
+            "/v1/chat/attachments/tus",
+            post(chat_uploads::tus_create_upload).options(chat_uploads::tus_options),
+        )
+        .route(
+            "/v1/chat/attachments/tus/:upload_id",
+            head(chat_uploads::tus_upload_status)
+                .patch(chat_uploads::tus_append_upload)
+                .layer(DefaultBodyLimit::max(
+                    chat_uploads::CHAT_ATTACHMENT_PART_BYTES as usize,
+                ))
+                .delete(chat_uploads::tus_delete_upload),
+        )
+        .route(
+            "/v1/chat/attachments/:attachment_id",
+            get(get_chat_attachment),
+        )
         .route(
             "/v1/chat/threads/:thread_id/messages/poll",
             get(poll_chat_messages),
@@ -469,11 +611,19 @@ pub fn router(state: AppState) -> Router {
             "/v1/chat/attachments/:attachment_id/download",
             get(download_chat_attachment),
         )
+        .route(
+            "/v1/chat/attachments/:attachment_id/thumbnail",
+            get(download_chat_attachment_thumbnail),
+        )
         .route("/v1/auth/signup", post(auth_signup))
         .route("/v1/auth/signin", post(auth_signin))
         .route("/v1/auth/refresh", post(auth_refresh))
         .route("/v1/auth/logout", post(auth_logout))
         .route("/v1/auth/me", get(auth_me))
+        .route(
+            "/v1/digest/unsubscribe",
+            get(digest_unsubscribe_confirmation).post(digest_unsubscribe),
+        )
         .merge(protected)
         .merge(api_edgepod_routes)
         .layer(middleware::from_fn(app_middleware::metrics_layer))
@@ -5161,6 +5311,27 @@ struct UpsertOntologyConceptRequest {
     pub verified: Option<bool>,
 }
 
+#[derive(Debug, Deserialize, Validate)]
+struct MergeOntologyConceptRequest {
+    #[validate(length(min = 1, max = 128))]
+    pub into_qid: String,
+    #[validate(length(min = 1, max = 64))]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReplaceOntologyConceptLabelsRequest {
+    pub labels: Vec<OntologyConceptLabel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OntologyAutocompleteQueryParams {
+    pub q: String,
+    pub community_id: Option<String>,
+    pub lang: Option<String>,
+    pub limit: Option<usize>,
+}
+
 #[derive(Debug, Deserialize, Validate)]
 struct CreateOntologyFeedRequest {
     pub note_id: Option<String>,
@@ -5175,6 +5346,9 @@ struct CreateOntologyFeedRequest {
     pub rahasia_level: Option<i64>,
     pub confidence: Option<f64>,
     pub triples: Option<Vec<CreateOntologyFeedTripleRequest>>,
+    /// `{lat, lng}`; tags the note `LOCATED_AT` the smallest gazetteer area
+    /// holding it unless a `LOCATED_AT` triple is given.
+    pub location: Option<GeoPoint>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -5187,6 +5361,34 @@ struct CreateOntologyFeedTripleRequest {
     pub metadata: Option<Value>,
 }
 
+#[derive(Debug, Deserialize)]
+struct OntologyExportQueryParams {
+    pub format: Option<String>,
+    pub community_id: Option<String>,
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// List facets are comma separated: `about=Q8068,Q3839081`.
+#[derive(Debug, Deserialize)]
+struct OntologyGraphQueryParams {
+    pub about: Option<String>,
+    pub narrower_depth: Option<usize>,
+    pub located_at: Option<String>,
+    pub has_action: Option<String>,
+    pub community_id: Option<String>,
+    pub from_ms: Option<i64>,
+    pub to_ms: Option<i64>,
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReverseGeocodeParams {
+    pub lat: f64,
+    pub lng: f64,
+}
+
 #[derive(Debug, Deserialize)]
 struct OntologyFeedbackRequest {
     pub metadata: Option<Value>,
@@ -5197,16 +5399,39 @@ struct OntologyFeedbackResponse {
     note_id: String,
     vouch_count: usize,
     challenge_count: usize,
+    earlier_version_vouch_count: usize,
+    earlier_version_challenge_count: usize,
 }
 
-#[derive(Debug, Serialize)]
-struct OntologyRankingResponse {
-    note_id: String,
-    vouch_count: usize,
-    challenge_count: usize,
-    score: f64,
+impl OntologyFeedbackResponse {
+    fn new(note_id: String, counts: &NoteFeedbackCounts) -> Self {
+        Self {
+            note_id,
+            vouch_count: counts.vouch_count,
+            challenge_count: counts.challenge_count,
+            earlier_version_vouch_count: counts.earlier_version_vouch_count,
+            earlier_version_challenge_count: counts.earlier_version_challenge_count,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct UpdateOntologyNoteRequest {
+    /// The version the author edited; `409` when the note has moved on.
+    pub expected_version: Option<i64>,
+    #[validate(length(min = 1, max = 2_000))]
+    pub content: Option<String>,
+    pub confidence: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct RetractOntologyNoteRequest {
+    #[validate(length(max = 280))]
+    pub reason: Option<String>,
 }
 
+const ONTOLOGY_RETRACTED_HIDDEN_REASON: &str = "ontology_note_retracted";
+
 fn normalize_ontology_temporal_class(value: &str) -> Result<String, ApiError> {
     let normalized = value.trim().to_ascii_lowercase();
     if matches!(normalized.as_str(), "ephemeral" | "periodic" | "persistent") {
@@ -5372,6 +5597,8 @@ fn build_feedback_patch(feedback: &NoteFeedbackCounts) -> Value {
             "feedback": {
                 "vouch_count": feedback.vouch_count,
                 "challenge_count": feedback.challenge_count,
+                "earlier_version_vouch_count": feedback.earlier_version_vouch_count,
+                "earlier_version_challenge_count": feedback.earlier_version_challenge_count,
                 "score": score,
             }
         }
@@ -5433,6 +5660,9 @@ fn validate_ontology_feed(payload: &CreateOntologyFeedRequest) -> Result<String,
             validate_ontology_action_predicate(&triple.edge, triple.predicate.as_deref())?;
         }
     }
+    if let Some(location) = payload.location.as_ref() {
+        location.validate().map_err(map_domain_error)?;
+    }
     Ok(temporal_class)
 }
 
@@ -5469,6 +5699,83 @@ async fn get_ontology_concept_by_qid(
     Ok(Json(concept))
 }
 
+/// Admin-only: folds `qid` into `into_qid` and leaves a redirect behind, so
+/// lookups of the old QID keep answering with the survivor.
+async fn merge_ontology_concept(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(qid): Path<String>,
+    Json(payload): Json<MergeOntologyConceptRequest>,
+) -> Result<Json<OntologyConceptMergeResult>, ApiError> {
+    require_admin_role(&auth.role)?;
+    validation::validate(&payload)?;
+    let command = ConceptMergeCommand {
+        from_qid: qid,
+        into_qid: payload.into_qid,
+        reason: payload
+            .reason
+            .unwrap_or_else(|| CONCEPT_MERGE_REASON_ADMIN.to_string()),
+    };
+    let repo = request_repos::ontology_repo(&state, &auth);
+    let result = merge_ontology_concepts(repo.as_ref(), &command, gotong_domain::jobs::now_ms())
+        .await
+        .map_err(map_domain_error)?;
+    Ok(Json(result))
+}
+
+/// Admin-only: replaces the aliases and extra-language labels of `qid`, such
+/// as Javanese (`jv`) and Sundanese (`su`) names, and refreshes autocomplete.
+async fn replace_ontology_concept_labels(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(qid): Path<String>,
+    Json(payload): Json<ReplaceOntologyConceptLabelsRequest>,
+) -> Result<Json<OntologyConceptLabels>, ApiError> {
+    require_admin_role(&auth.role)?;
+    let labels = normalize_concept_labels(&payload.labels).map_err(map_domain_error)?;
+    let repo = request_repos::ontology_repo(&state, &auth);
+    // Labels follow the survivor of a merged QID.
+    let concept = repo
+        .get_concept_by_qid(&qid)
+        .await
+        .map_err(map_domain_error)?
+        .ok_or(ApiError::NotFound)?;
+    repo.replace_concept_labels(&concept.qid, &labels)
+        .await
+        .map_err(map_domain_error)?;
+    let stored = repo
+        .get_concept_labels(std::slice::from_ref(&concept.qid))
+        .await
+        .map_err(map_domain_error)?
+        .into_iter()
+        .next()
+        .ok_or(ApiError::NotFound)?;
+    Ok(Json(stored))
+}
+
+async fn autocomplete_ontology_concepts(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Query(query): Query<OntologyAutocompleteQueryParams>,
+) -> Result<Json<Vec<ConceptSuggestion>>, ApiError> {
+    actor_identity(&auth)?;
+    let autocomplete_query = ConceptAutocompleteQuery {
+        q: query.q,
+        community_id: query.community_id,
+        lang: query.lang,
+        limit: query.limit,
+    };
+    let repo = request_repos::ontology_repo(&state, &auth);
+    let suggestions = autocomplete_concepts(
+        state.concept_label_index.as_ref(),
+        repo.as_ref(),
+        &autocomplete_query,
+    )
+    .await
+    .map_err(map_domain_error)?;
+    Ok(Json(suggestions))
+}
+
 async fn add_ontology_broader_edge(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
@@ -5493,6 +5800,134 @@ async fn list_ontology_hierarchy(
     Ok(Json(concepts))
 }
 
+/// Partner export: always the public filter, so only `rahasia_level` 0,
+/// `ai_readable` notes leave. `format` wins over the `Accept` header. Notes are
+/// paged by `note_id`; `x-next-cursor` carries the cursor for the next page.
+async fn export_ontology(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(auth): Extension<AuthContext>,
+    Query(query): Query<OntologyExportQueryParams>,
+) -> Result<Response, ApiError> {
+    actor_identity(&auth)?;
+    let format = match query.format.as_deref() {
+        Some(format) => OntologyExportFormat::parse(format).map_err(map_domain_error)?,
+        None => {
+            let accepts_turtle = headers
+                .get(ACCEPT)
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|value| value.contains("text/turtle"));
+            if accepts_turtle {
+                OntologyExportFormat::Turtle
+            } else {
+                OntologyExportFormat::JsonLd
+            }
+        }
+    };
+    let filter = OntologyExportFilter {
+        community_id: query
+            .community_id
+            .as_deref()
+            .map(str::trim)
+            .filter(|community_id| !community_id.is_empty())
+            .map(str::to_string),
+        after_note_id: query
+            .cursor
+            .as_deref()
+            .map(str::trim)
+            .filter(|cursor| !cursor.is_empty())
+            .map(str::to_string),
+        limit: Some(query.limit.unwrap_or(DEFAULT_EXPORT_PAGE_SIZE)),
+        ..OntologyExportFilter::default()
+    };
+    let repo = request_repos::ontology_repo(&state, &auth);
+    let graph = load_ontology_export(repo.as_ref(), &filter, gotong_domain::jobs::now_ms())
+        .await
+        .map_err(map_domain_error)?;
+    let body = render_ontology_export(&graph, format, &state.config.ontology_export_base_iri);
+
+    let mut response = (StatusCode::OK, body).into_response();
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static(format.content_type()),
+    );
+    if let Ok(content_disposition) = HeaderValue::from_str(&format!(
+        "inline; filename=\"gotong-ontology.{}\"",
+        format.file_extension()
+    )) {
+        response
+            .headers_mut()
+            .insert(CONTENT_DISPOSITION, content_disposition);
+    }
+    if let Some(next_cursor) = graph
+        .next_cursor
+        .as_deref()
+        .and_then(|cursor| HeaderValue::from_str(cursor).ok())
+    {
+        response
+            .headers_mut()
+            .insert(HeaderName::from_static("x-next-cursor"), next_cursor);
+    }
+    Ok(response)
+}
+
+fn comma_separated(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Faceted exploration: notes `ABOUT` the given concepts or anything up to
+/// `narrower_depth` levels below them, `LOCATED_AT` and `HAS_ACTION` the given
+/// targets. Restricted notes are only returned to their author.
+async fn query_ontology_graph_notes(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Query(query): Query<OntologyGraphQueryParams>,
+) -> Result<Json<OntologyGraphPage>, ApiError> {
+    let actor = actor_identity(&auth)?;
+    let graph_query = OntologyGraphQuery {
+        about: comma_separated(query.about.as_deref()),
+        narrower_depth: query.narrower_depth,
+        located_at: comma_separated(query.located_at.as_deref()),
+        has_action: comma_separated(query.has_action.as_deref()),
+        community_id: query.community_id,
+        from_ms: query.from_ms,
+        to_ms: query.to_ms,
+        cursor: query.cursor,
+        limit: query.limit,
+    };
+    let repo = request_repos::ontology_repo(&state, &auth);
+    let page = query_ontology_graph(
+        repo.as_ref(),
+        &actor.user_id,
+        &graph_query,
+        gotong_domain::jobs::now_ms(),
+    )
+    .await
+    .map_err(map_domain_error)?;
+    Ok(Json(page))
+}
+
+/// Smallest gazetteer area holding the point, provinsi first in `hierarchy`.
+async fn reverse_geocode_ontology_place(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Query(query): Query<ReverseGeocodeParams>,
+) -> Result<Json<ReverseGeocodeResult>, ApiError> {
+    let point = GeoPoint::new(query.lat, query.lng).map_err(map_domain_error)?;
+    let repo = request_repos::ontology_repo(&state, &auth);
+    reverse_geocode(repo.as_ref(), &point)
+        .await
+        .map_err(map_domain_error)?
+        .map(Json)
+        .ok_or(ApiError::NotFound)
+}
+
 async fn create_ontology_feed(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -5504,6 +5939,7 @@ async fn create_ontology_feed(
     let request_id = request_id_from_headers(&headers)?;
     let correlation_id = correlation_id_from_headers(&headers)?;
     let temporal_class = validate_ontology_feed(&payload)?;
+    let location = payload.location;
 
     let key = IdempotencyKey::new(
         "ontology_note_create",
@@ -5534,7 +5970,7 @@ async fn create_ontology_feed(
                 .await
                 .map_err(map_domain_error)?;
 
-            let triples = payload
+            let mut triples = payload
                 .triples
                 .unwrap_or_default()
                 .into_iter()
@@ -5565,6 +6001,28 @@ async fn create_ontology_feed(
                 })
                 .collect::<Result<Vec<_>, ApiError>>()?;
 
+            let note_record = format!("note:{}", created_note.note_id);
+            let mut located_at = None;
+            if let Some(point) = location.as_ref()
+                && !triples.iter().any(|triple| {
+                    triple.edge == OntologyEdgeKind::LocatedAt && triple.from_id == note_record
+                })
+            {
+                // A gazetteer miss or failure leaves the note untagged.
+                match reverse_geocode(request_repos::ontology_repo(&state, &auth).as_ref(), point)
+                    .await
+                {
+                    Ok(Some(result)) => {
+                        triples.push(located_at_triple(&note_record, &result));
+                        located_at = Some(result.area);
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        tracing::warn!(error = %err, note_id = %created_note.note_id, "failed to reverse geocode ontology note");
+                    }
+                }
+            }
+
             if !triples.is_empty() {
                 request_repos::ontology_repo(&state, &auth)
                     .write_triples(&triples)
@@ -5582,6 +6040,9 @@ async fn create_ontology_feed(
                 "triple_count": triples.len(),
                 "feedback": feedback,
             });
+            if let Some(area) = located_at {
+                response_body["located_at"] = json!(area);
+            }
 
             if response_body
                 .get("note")
@@ -5627,7 +6088,8 @@ async fn create_ontology_feed(
                 };
 
                 let service =
-                    DiscoveryService::new(state.feed_repo.clone(), state.notification_repo.clone());
+                    DiscoveryService::new(state.feed_repo.clone(), state.notification_repo.clone())
+                        .with_event_publisher(Arc::new(state.discovery_realtime.clone()));
                 let input = FeedIngestInput {
                     source_type: FEED_SOURCE_ONTOLOGY_NOTE.to_string(),
                     source_id: note_id.clone(),
@@ -5645,6 +6107,7 @@ async fn create_ontology_feed(
                         "note": response_body.get("note").cloned().unwrap_or(Value::Null),
                         "enrichment": enrichment,
                     })),
+                    location,
                 };
 
                 match service.ingest_feed(input).await {
@@ -5696,62 +6159,233 @@ async fn create_ontology_feed(
     }
 }
 
-async fn vouch_ontology_note(
+async fn update_ontology_note(
     State(state): State<AppState>,
     Path(note_id): Path<String>,
+    headers: HeaderMap,
     Extension(auth): Extension<AuthContext>,
-    Json(payload): Json<OntologyFeedbackRequest>,
-) -> Result<(StatusCode, Json<OntologyFeedbackResponse>), ApiError> {
+    Json(payload): Json<UpdateOntologyNoteRequest>,
+) -> Result<Json<Value>, ApiError> {
+    validation::validate(&payload)?;
     let actor = actor_identity(&auth)?;
-    let write_result = request_repos::ontology_repo(&state, &auth)
-        .write_triples(&[OntologyTripleCreate {
-            edge: OntologyEdgeKind::Vouches,
-            from_id: format!("warga:{}", actor.user_id),
-            to_id: format!("note:{note_id}"),
-            predicate: None,
-            metadata: payload.metadata,
-        }])
-        .await;
-    match write_result {
-        Ok(()) => {}
-        Err(DomainError::Conflict) => {}
-        Err(err) => return Err(map_domain_error(err)),
-    }
-
-    let counts = request_repos::ontology_repo(&state, &auth)
-        .note_feedback_counts(&note_id)
+    let request_id = request_id_from_headers(&headers)?;
+    let correlation_id = correlation_id_from_headers(&headers)?;
+    let repo = request_repos::ontology_repo(&state, &auth);
+    let note = ontology_revision::edit_ontology_note(
+        repo.as_ref(),
+        &actor.user_id,
+        &NoteEditCommand {
+            note_id,
+            expected_version: payload.expected_version,
+            content: payload.content,
+            confidence: payload.confidence,
+        },
+        gotong_domain::jobs::now_ms(),
+    )
+    .await
+    .map_err(map_domain_error)?;
+    let feedback = repo
+        .note_feedback_counts(&note.note_id)
         .await
         .map_err(map_domain_error)?;
-    patch_ontology_note_feedback_in_feed(&state, &note_id, &counts, "vouch").await;
-    Ok((
-        StatusCode::CREATED,
-        Json(OntologyFeedbackResponse {
-            note_id,
-            vouch_count: counts.vouch_count,
-            challenge_count: counts.challenge_count,
-        }),
-    ))
+    if note.rahasia_level == 0 {
+        refresh_edited_ontology_note_in_feed(&state, &note, &feedback, request_id, correlation_id)
+            .await;
+    }
+    Ok(Json(json!({
+        "note": note,
+        "feedback": feedback,
+    })))
 }
 
-async fn challenge_ontology_note(
-    State(state): State<AppState>,
-    Path(note_id): Path<String>,
-    Extension(auth): Extension<AuthContext>,
-    Json(payload): Json<OntologyFeedbackRequest>,
-) -> Result<(StatusCode, Json<OntologyFeedbackResponse>), ApiError> {
-    let actor = actor_identity(&auth)?;
-    let write_result = request_repos::ontology_repo(&state, &auth)
-        .write_triples(&[OntologyTripleCreate {
-            edge: OntologyEdgeKind::Challenges,
-            from_id: format!("warga:{}", actor.user_id),
-            to_id: format!("note:{note_id}"),
-            predicate: None,
-            metadata: payload.metadata,
-        }])
-        .await;
-    match write_result {
-        Ok(()) => {}
-        Err(DomainError::Conflict) => {}
+/// Rewrites the feed card of an edited note and queues `OntologyNoteEnrich`
+/// for it again. Like feedback patches, failures only log.
+async fn refresh_edited_ontology_note_in_feed(
+    state: &AppState,
+    note: &OntologyNote,
+    feedback: &NoteFeedbackCounts,
+    request_id: String,
+    correlation_id: String,
+) {
+    let item = match state
+        .feed_repo
+        .get_latest_by_source(FEED_SOURCE_ONTOLOGY_NOTE, &note.note_id)
+        .await
+    {
+        Ok(Some(item)) => item,
+        Ok(None) => return,
+        Err(err) => {
+            tracing::warn!(error = %err, note_id = %note.note_id, "failed to fetch latest ontology feed item for note edit");
+            return;
+        }
+    };
+    if let Err(err) = state
+        .feed_repo
+        .update_title(&item.feed_id, &ontology_note_title(&note.content))
+        .await
+    {
+        tracing::warn!(error = %err, feed_id = %item.feed_id, note_id = %note.note_id, "failed to update discovery feed title after ontology note edit");
+    }
+
+    let mut payload_patch = build_feedback_patch(feedback);
+    payload_patch["note"] = json!(note);
+    let job = match state.job_queue.as_ref() {
+        Some(queue) => {
+            payload_patch["enrichment"]["status"] = json!("pending");
+            let payload = serde_json::to_value(OntologyNoteEnrichPayload {
+                note_id: note.note_id.clone(),
+                feed_id: Some(item.feed_id.clone()),
+                requested_ms: gotong_domain::jobs::now_ms(),
+            })
+            .unwrap_or(Value::Null);
+            let job = new_job(
+                format!(
+                    "ontology_note_enrich:{}:{}:v{}",
+                    note.note_id, item.feed_id, note.version
+                ),
+                JobType::OntologyNoteEnrich,
+                payload,
+                request_id,
+                correlation_id,
+                JobDefaults { max_attempts: 3 },
+            );
+            Some((queue, job))
+        }
+        None => None,
+    };
+    if let Err(err) = state
+        .feed_repo
+        .merge_payload(&item.feed_id, payload_patch)
+        .await
+    {
+        tracing::warn!(error = %err, feed_id = %item.feed_id, note_id = %note.note_id, "failed to patch discovery feed payload after ontology note edit");
+    }
+    if let Some((queue, job)) = job
+        && let Err(err) = queue.enqueue(&job).await
+    {
+        tracing::warn!(error = %err, feed_id = %item.feed_id, note_id = %note.note_id, "failed to enqueue ontology note enrich job after edit");
+    }
+}
+
+async fn retract_ontology_note(
+    State(state): State<AppState>,
+    Path(note_id): Path<String>,
+    Extension(auth): Extension<AuthContext>,
+    Json(payload): Json<RetractOntologyNoteRequest>,
+) -> Result<Json<OntologyNote>, ApiError> {
+    validation::validate(&payload)?;
+    let actor = actor_identity(&auth)?;
+    let now_ms = gotong_domain::jobs::now_ms();
+    let note = ontology_revision::retract_ontology_note(
+        request_repos::ontology_repo(&state, &auth).as_ref(),
+        &actor.user_id,
+        &note_id,
+        payload.reason.as_deref(),
+        now_ms,
+    )
+    .await
+    .map_err(map_domain_error)?;
+
+    // The card leaves the feed; its payload keeps the note with the marker.
+    match state
+        .feed_repo
+        .get_latest_by_source(FEED_SOURCE_ONTOLOGY_NOTE, &note.note_id)
+        .await
+    {
+        Ok(Some(item)) => {
+            let payload_patch = json!({
+                "note": note,
+                "lifecycle": {
+                    "hidden": true,
+                    "hidden_reason": ONTOLOGY_RETRACTED_HIDDEN_REASON,
+                    "hidden_at_ms": note.retracted_at_ms.unwrap_or(now_ms),
+                }
+            });
+            if let Err(err) = state
+                .feed_repo
+                .merge_payload(&item.feed_id, payload_patch)
+                .await
+            {
+                tracing::warn!(error = %err, feed_id = %item.feed_id, note_id = %note.note_id, "failed to hide retracted ontology note feed item");
+            }
+        }
+        Ok(None) => {}
+        Err(err) => {
+            tracing::warn!(error = %err, note_id = %note.note_id, "failed to fetch latest ontology feed item for retraction");
+        }
+    }
+    Ok(Json(note))
+}
+
+async fn list_ontology_note_versions(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(note_id): Path<String>,
+) -> Result<Json<NoteHistory>, ApiError> {
+    let actor = actor_identity(&auth)?;
+    let history = ontology_revision::ontology_note_history(
+        request_repos::ontology_repo(&state, &auth).as_ref(),
+        &actor.user_id,
+        &note_id,
+        gotong_domain::jobs::now_ms(),
+    )
+    .await
+    .map_err(map_domain_error)?;
+    Ok(Json(history))
+}
+
+async fn vouch_ontology_note(
+    State(state): State<AppState>,
+    Path(note_id): Path<String>,
+    Extension(auth): Extension<AuthContext>,
+    Json(payload): Json<OntologyFeedbackRequest>,
+) -> Result<(StatusCode, Json<OntologyFeedbackResponse>), ApiError> {
+    let actor = actor_identity(&auth)?;
+    let write_result = request_repos::ontology_repo(&state, &auth)
+        .write_triples(&[OntologyTripleCreate {
+            edge: OntologyEdgeKind::Vouches,
+            from_id: format!("warga:{}", actor.user_id),
+            to_id: format!("note:{note_id}"),
+            predicate: None,
+            metadata: payload.metadata,
+        }])
+        .await;
+    match write_result {
+        Ok(()) => {}
+        Err(DomainError::Conflict) => {}
+        Err(err) => return Err(map_domain_error(err)),
+    }
+
+    let counts = request_repos::ontology_repo(&state, &auth)
+        .note_feedback_counts(&note_id)
+        .await
+        .map_err(map_domain_error)?;
+    patch_ontology_note_feedback_in_feed(&state, &note_id, &counts, "vouch").await;
+    Ok((
+        StatusCode::CREATED,
+        Json(OntologyFeedbackResponse::new(note_id, &counts)),
+    ))
+}
+
+async fn challenge_ontology_note(
+    State(state): State<AppState>,
+    Path(note_id): Path<String>,
+    Extension(auth): Extension<AuthContext>,
+    Json(payload): Json<OntologyFeedbackRequest>,
+) -> Result<(StatusCode, Json<OntologyFeedbackResponse>), ApiError> {
+    let actor = actor_identity(&auth)?;
+    let write_result = request_repos::ontology_repo(&state, &auth)
+        .write_triples(&[OntologyTripleCreate {
+            edge: OntologyEdgeKind::Challenges,
+            from_id: format!("warga:{}", actor.user_id),
+            to_id: format!("note:{note_id}"),
+            predicate: None,
+            metadata: payload.metadata,
+        }])
+        .await;
+    match write_result {
+        Ok(()) => {}
+        Err(DomainError::Conflict) => {}
         Err(err) => return Err(map_domain_error(err)),
     }
 
@@ -5762,11 +6396,7 @@ async fn challenge_ontology_note(
     patch_ontology_note_feedback_in_feed(&state, &note_id, &counts, "challenge").await;
     Ok((
         StatusCode::CREATED,
-        Json(OntologyFeedbackResponse {
-            note_id,
-            vouch_count: counts.vouch_count,
-            challenge_count: counts.challenge_count,
-        }),
+        Json(OntologyFeedbackResponse::new(note_id, &counts)),
     ))
 }
 
@@ -5779,30 +6409,24 @@ async fn get_ontology_note_feedback(
         .note_feedback_counts(&note_id)
         .await
         .map_err(map_domain_error)?;
-    Ok(Json(OntologyFeedbackResponse {
-        note_id,
-        vouch_count: counts.vouch_count,
-        challenge_count: counts.challenge_count,
-    }))
+    Ok(Json(OntologyFeedbackResponse::new(note_id, &counts)))
 }
 
 async fn get_ontology_note_ranking(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
     Path(note_id): Path<String>,
-) -> Result<Json<OntologyRankingResponse>, ApiError> {
-    let counts = request_repos::ontology_repo(&state, &auth)
-        .note_feedback_counts(&note_id)
-        .await
-        .map_err(map_domain_error)?;
-    let total_feedback = counts.vouch_count + counts.challenge_count;
-    let score = wilson_score(counts.vouch_count as u64, total_feedback as u64);
-    Ok(Json(OntologyRankingResponse {
-        note_id,
-        vouch_count: counts.vouch_count,
-        challenge_count: counts.challenge_count,
-        score,
-    }))
+) -> Result<Json<NoteRanking>, ApiError> {
+    let repo = request_repos::ontology_repo(&state, &auth);
+    let ranking = rank_ontology_note(
+        repo.as_ref(),
+        Some(state.markov_client.as_ref()),
+        &note_id,
+        gotong_domain::jobs::now_ms(),
+    )
+    .await
+    .map_err(map_domain_error)?;
+    Ok(Json(ranking))
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -6369,6 +6993,11 @@ struct FeedListQueryParams {
     pub from_ms: Option<i64>,
     pub to_ms: Option<i64>,
     pub involvement_only: Option<bool>,
+    pub mode: Option<String>,
+    pub debug: Option<bool>,
+    pub near_lat: Option<f64>,
+    pub near_lng: Option<f64>,
+    pub radius_m: Option<f64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -6380,6 +7009,22 @@ struct FeedSuggestionsQueryParams {
     pub to_ms: Option<i64>,
 }
 
+#[derive(Debug, Deserialize)]
+struct FeedTrendingQueryParams {
+    pub scope_id: Option<String>,
+    pub kind: Option<TrendingTopicKind>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct FeedTrendingResponse {
+    scope_id: Option<String>,
+    /// `None` until the worker has computed a snapshot for the scope.
+    computed_at_ms: Option<i64>,
+    window_ms: Option<i64>,
+    topics: Vec<TrendingTopic>,
+}
+
 #[derive(Debug, Deserialize)]
 struct FeedMonitorPreferenceRequest {
     monitored: bool,
@@ -6402,12 +7047,25 @@ struct FeedFollowPreferenceResponse {
     followed: bool,
 }
 
+#[derive(Debug, Serialize)]
+struct FeedFollowedEntityDto {
+    entity_id: String,
+    followed_at_ms: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct FeedFollowListResponse {
+    items: Vec<FeedFollowedEntityDto>,
+}
+
 #[derive(Debug, Serialize)]
 struct FeedStreamResponse {
     items: Vec<gotong_domain::discovery::FeedItem>,
     stream: Vec<FeedStreamItemDto>,
     next_cursor: Option<String>,
     has_more: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ranking: Option<FeedRankingDebug>,
 }
 
 #[derive(Debug, Serialize)]
@@ -6477,6 +7135,9 @@ struct SearchListQueryParams {
     pub to_ms: Option<i64>,
     pub involvement_only: Option<bool>,
     pub exclude_vault: Option<bool>,
+    pub near_lat: Option<f64>,
+    pub near_lng: Option<f64>,
+    pub radius_m: Option<f64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -6484,6 +7145,7 @@ struct NotificationsListQueryParams {
     pub cursor: Option<String>,
     pub limit: Option<usize>,
     pub include_read: Option<bool>,
+    pub grouped: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -6492,17 +7154,78 @@ struct WeeklyDigestQuery {
     pub window_end_ms: Option<i64>,
 }
 
+#[derive(Debug, Serialize)]
+struct DigestSettingsResponse {
+    configured: bool,
+    email: Option<String>,
+    locale: DigestLocale,
+    timezone: String,
+    frequency: DigestFrequency,
+    send_hour_local: u8,
+    send_weekday: u8,
+    last_sent_at_ms: Option<i64>,
+}
+
+impl From<Option<DigestSubscription>> for DigestSettingsResponse {
+    fn from(subscription: Option<DigestSubscription>) -> Self {
+        match subscription {
+            Some(subscription) => Self {
+                configured: true,
+                email: Some(subscription.email).filter(|email| !email.is_empty()),
+                locale: subscription.locale,
+                timezone: subscription.timezone,
+                frequency: subscription.frequency,
+                send_hour_local: subscription.send_hour_local,
+                send_weekday: subscription.send_weekday,
+                last_sent_at_ms: subscription.last_sent_at_ms,
+            },
+            // Nothing is sent until the user saves settings with an email address.
+            None => Self {
+                configured: false,
+                email: None,
+                locale: DigestLocale::default(),
+                timezone: "Asia/Jakarta".to_string(),
+                frequency: DigestFrequency::Off,
+                send_hour_local: 7,
+                send_weekday: 1,
+                last_sent_at_ms: None,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DigestUnsubscribeQuery {
+    pub token: String,
+}
+
 #[derive(Serialize)]
 struct DiscoveryUnreadCountResponse {
     unread_count: usize,
 }
 
-const FEED_MONITOR_PREFERENCE_TABLE: &str = "feed_monitor_preference";
-const FEED_FOLLOW_PREFERENCE_TABLE: &str = "feed_follow_preference";
 const FEED_DB_SEED_SOURCE_PREFIX: &str = "seed-";
-
-fn feed_preference_key(actor_id: &str, target_id: &str) -> String {
-    format!("{actor_id}:{target_id}")
+const FEED_NEAR_DEFAULT_RADIUS_M: f64 = 2_000.0;
+const FEED_NEAR_MAX_RADIUS_M: f64 = 50_000.0;
+
+fn feed_near_filter(
+    near_lat: Option<f64>,
+    near_lng: Option<f64>,
+    radius_m: Option<f64>,
+) -> Result<Option<GeoRadius>, ApiError> {
+    match (near_lat, near_lng) {
+        (Some(lat), Some(lng)) => GeoRadius::new(
+            GeoPoint { lat, lng },
+            radius_m.unwrap_or(FEED_NEAR_DEFAULT_RADIUS_M),
+            FEED_NEAR_MAX_RADIUS_M,
+        )
+        .map(Some)
+        .map_err(map_domain_error),
+        (None, None) if radius_m.is_none() => Ok(None),
+        _ => Err(ApiError::Validation(
+            "near_lat and near_lng are required together, and radius_m needs both".into(),
+        )),
+    }
 }
 
 fn extract_witness_id(item: &gotong_domain::discovery::FeedItem) -> String {
@@ -6741,222 +7464,16 @@ fn build_feed_stream(
     stream
 }
 
-async fn persist_feed_monitor_preference(
-    state: &AppState,
-    auth: &AuthContext,
-    actor_id: &str,
-    witness_id: &str,
-    monitored: bool,
-) -> Result<(), ApiError> {
-    if let Some(session) = auth.surreal_db_session.as_ref() {
-        let record_id = feed_preference_key(actor_id, witness_id);
-        if monitored {
-            session
-                .client()
-                .query(format!(
-                    "UPSERT type::thing('{FEED_MONITOR_PREFERENCE_TABLE}', $record_id) CONTENT {{ user_id: $user_id, witness_id: $witness_id, monitored: $monitored, updated_at_ms: $updated_at_ms }};"
-                ))
-                .bind(("record_id", record_id))
-                .bind(("user_id", actor_id.to_string()))
-                .bind(("witness_id", witness_id.to_string()))
-                .bind(("monitored", monitored))
-                .bind(("updated_at_ms", gotong_domain::jobs::now_ms()))
-                .await
-                .map_err(|err| {
-                    tracing::error!(error = %err, actor_id = %actor_id, witness_id = %witness_id, "failed to persist monitor preference");
-                    ApiError::Internal
-                })?;
-        } else {
-            session
-                .client()
-                .query(format!(
-                    "DELETE type::thing('{FEED_MONITOR_PREFERENCE_TABLE}', $record_id);"
-                ))
-                .bind(("record_id", record_id))
-                .await
-                .map_err(|err| {
-                    tracing::error!(error = %err, actor_id = %actor_id, witness_id = %witness_id, "failed to delete monitor preference");
-                    ApiError::Internal
-                })?;
-        }
-        return Ok(());
-    }
-
-    let key = feed_preference_key(actor_id, witness_id);
-    let mut prefs = state.feed_monitor_preferences.write().await;
-    if monitored {
-        prefs.insert(key, true);
-    } else {
-        prefs.remove(&key);
-    }
-    Ok(())
-}
-
-async fn persist_feed_follow_preference(
-    state: &AppState,
-    auth: &AuthContext,
-    actor_id: &str,
-    entity_id: &str,
-    followed: bool,
-) -> Result<(), ApiError> {
-    if let Some(session) = auth.surreal_db_session.as_ref() {
-        let record_id = feed_preference_key(actor_id, entity_id);
-        if followed {
-            session
-                .client()
-                .query(format!(
-                    "UPSERT type::thing('{FEED_FOLLOW_PREFERENCE_TABLE}', $record_id) CONTENT {{ user_id: $user_id, entity_id: $entity_id, followed: $followed, updated_at_ms: $updated_at_ms }};"
-                ))
-                .bind(("record_id", record_id))
-                .bind(("user_id", actor_id.to_string()))
-                .bind(("entity_id", entity_id.to_string()))
-                .bind(("followed", followed))
-                .bind(("updated_at_ms", gotong_domain::jobs::now_ms()))
-                .await
-                .map_err(|err| {
-                    tracing::error!(error = %err, actor_id = %actor_id, entity_id = %entity_id, "failed to persist follow preference");
-                    ApiError::Internal
-                })?;
-        } else {
-            session
-                .client()
-                .query(format!(
-                    "DELETE type::thing('{FEED_FOLLOW_PREFERENCE_TABLE}', $record_id);"
-                ))
-                .bind(("record_id", record_id))
-                .await
-                .map_err(|err| {
-                    tracing::error!(error = %err, actor_id = %actor_id, entity_id = %entity_id, "failed to delete follow preference");
-                    ApiError::Internal
-                })?;
-        }
-        return Ok(());
-    }
-
-    let key = feed_preference_key(actor_id, entity_id);
-    let mut prefs = state.feed_follow_preferences.write().await;
-    if followed {
-        prefs.insert(key, true);
-    } else {
-        prefs.remove(&key);
-    }
-    Ok(())
-}
-
-async fn load_monitor_preferences(
-    state: &AppState,
-    auth: &AuthContext,
-    actor_id: &str,
-    witness_ids: &[String],
-) -> Result<HashMap<String, bool>, ApiError> {
-    if witness_ids.is_empty() {
-        return Ok(HashMap::new());
-    }
-
-    if let Some(session) = auth.surreal_db_session.as_ref() {
-        let mut response = session
-            .client()
-            .query(format!(
-                "SELECT witness_id, monitored FROM {FEED_MONITOR_PREFERENCE_TABLE} WHERE user_id = $user_id AND witness_id IN $witness_ids;"
-            ))
-            .bind(("user_id", actor_id.to_string()))
-            .bind(("witness_ids", witness_ids.to_vec()))
-            .await
-            .map_err(|err| {
-                tracing::error!(error = %err, actor_id = %actor_id, "failed to load monitor preferences");
-                ApiError::Internal
-            })?;
-        let rows: Vec<Value> = response.take(0).map_err(|err| {
-            tracing::error!(error = %err, actor_id = %actor_id, "failed to decode monitor preferences");
-            ApiError::Internal
-        })?;
-        let mapped = rows
-            .into_iter()
-            .filter_map(|row| {
-                let obj = row.as_object()?;
-                let witness_id = obj
-                    .get("witness_id")
-                    .and_then(Value::as_str)
-                    .map(str::trim)
-                    .filter(|value| !value.is_empty())?
-                    .to_string();
-                let monitored = obj
-                    .get("monitored")
-                    .and_then(Value::as_bool)
-                    .unwrap_or(false);
-                Some((witness_id, monitored))
-            })
-            .collect();
-        return Ok(mapped);
-    }
-
-    let prefs = state.feed_monitor_preferences.read().await;
-    let result = witness_ids
-        .iter()
-        .filter_map(|witness_id| {
-            let key = feed_preference_key(actor_id, witness_id);
-            prefs.get(&key).map(|value| (witness_id.clone(), *value))
-        })
-        .collect();
-    Ok(result)
-}
-
-async fn load_follow_preferences(
-    state: &AppState,
-    auth: &AuthContext,
-    actor_id: &str,
-    entity_ids: &[String],
-) -> Result<HashMap<String, bool>, ApiError> {
-    if entity_ids.is_empty() {
-        return Ok(HashMap::new());
-    }
-
-    if let Some(session) = auth.surreal_db_session.as_ref() {
-        let mut response = session
-            .client()
-            .query(format!(
-                "SELECT entity_id, followed FROM {FEED_FOLLOW_PREFERENCE_TABLE} WHERE user_id = $user_id AND entity_id IN $entity_ids;"
-            ))
-            .bind(("user_id", actor_id.to_string()))
-            .bind(("entity_ids", entity_ids.to_vec()))
-            .await
-            .map_err(|err| {
-                tracing::error!(error = %err, actor_id = %actor_id, "failed to load follow preferences");
-                ApiError::Internal
-            })?;
-        let rows: Vec<Value> = response.take(0).map_err(|err| {
-            tracing::error!(error = %err, actor_id = %actor_id, "failed to decode follow preferences");
-            ApiError::Internal
-        })?;
-        let mapped = rows
-            .into_iter()
-            .filter_map(|row| {
-                let obj = row.as_object()?;
-                let entity_id = obj
-                    .get("entity_id")
-                    .and_then(Value::as_str)
-                    .map(str::trim)
-                    .filter(|value| !value.is_empty())?
-                    .to_string();
-                let followed = obj
-                    .get("followed")
-                    .and_then(Value::as_bool)
-                    .unwrap_or(false);
-                Some((entity_id, followed))
-            })
-            .collect();
-        return Ok(mapped);
+fn feed_ranking_weights(config: &AppConfig) -> FeedRankingWeights {
+    FeedRankingWeights {
+        recency: config.discovery_feed_rank_recency_weight,
+        involvement: config.discovery_feed_rank_involvement_weight,
+        followed: config.discovery_feed_rank_followed_weight,
+        feedback: config.discovery_feed_rank_feedback_weight,
+        severity: config.discovery_feed_rank_severity_weight,
+        recency_half_life_ms: i64::try_from(config.discovery_feed_rank_recency_half_life_ms)
+            .unwrap_or(i64::MAX),
     }
-
-    let prefs = state.feed_follow_preferences.read().await;
-    let result = entity_ids
-        .iter()
-        .filter_map(|entity_id| {
-            let key = feed_preference_key(actor_id, entity_id);
-            prefs.get(&key).map(|value| (entity_id.clone(), *value))
-        })
-        .collect();
-    Ok(result)
 }
 
 async fn set_feed_monitor_preference(
@@ -6970,14 +7487,15 @@ async fn set_feed_monitor_preference(
     if witness_id.is_empty() {
         return Err(ApiError::Validation("witness_id is required".into()));
     }
-    persist_feed_monitor_preference(
-        &state,
-        &auth,
-        &actor.user_id,
-        &witness_id,
-        payload.monitored,
-    )
-    .await?;
+    request_repos::feed_preference_repo(&state, &auth)
+        .set_monitor_preference(&FeedMonitorPreference {
+            user_id: actor.user_id,
+            witness_id: witness_id.clone(),
+            monitored: payload.monitored,
+            updated_at_ms: gotong_domain::jobs::now_ms(),
+        })
+        .await
+        .map_err(map_domain_error)?;
     Ok(Json(FeedMonitorPreferenceResponse {
         witness_id,
         monitored: payload.monitored,
@@ -6995,32 +7513,62 @@ async fn set_feed_follow_preference(
     if entity_id.is_empty() {
         return Err(ApiError::Validation("entity_id is required".into()));
     }
-    persist_feed_follow_preference(&state, &auth, &actor.user_id, &entity_id, payload.followed)
-        .await?;
+    request_repos::feed_preference_repo(&state, &auth)
+        .set_follow_preference(&FeedFollowPreference {
+            user_id: actor.user_id,
+            entity_id: entity_id.clone(),
+            followed: payload.followed,
+            updated_at_ms: gotong_domain::jobs::now_ms(),
+        })
+        .await
+        .map_err(map_domain_error)?;
     Ok(Json(FeedFollowPreferenceResponse {
         entity_id,
         followed: payload.followed,
     }))
 }
 
-async fn list_discovery_feed(
+async fn list_feed_follow_preferences(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
-    Query(query): Query<FeedListQueryParams>,
-) -> Result<Json<FeedStreamResponse>, ApiError> {
+) -> Result<Json<FeedFollowListResponse>, ApiError> {
     let actor = actor_identity(&auth)?;
-    let actor_id = actor.user_id.clone();
-    let feed_scope_id = query.scope_id.clone();
-    let feed_privacy_level = query.privacy_level.clone();
-    let feed_from_ms = query.from_ms;
+    let items = request_repos::feed_preference_repo(&state, &auth)
+        .list_follows(&actor.user_id)
+        .await
+        .map_err(map_domain_error)?
+        .into_iter()
+        .map(|preference| FeedFollowedEntityDto {
+            entity_id: preference.entity_id,
+            followed_at_ms: preference.updated_at_ms,
+        })
+        .collect();
+    Ok(Json(FeedFollowListResponse { items }))
+}
+
+async fn list_discovery_feed(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Query(query): Query<FeedListQueryParams>,
+) -> Result<Json<FeedStreamResponse>, ApiError> {
+    let actor = actor_identity(&auth)?;
+    let actor_id = actor.user_id.clone();
+    let feed_scope_id = query.scope_id.clone();
+    let feed_privacy_level = query.privacy_level.clone();
+    let feed_from_ms = query.from_ms;
     let feed_to_ms = query.to_ms;
     let feed_limit = query.limit;
     let feed_cursor = query.cursor.clone();
     let feed_involvement_only = query.involvement_only.unwrap_or(false);
+    let feed_mode = FeedMode::parse(query.mode.as_deref()).map_err(map_domain_error)?;
+    let feed_near = feed_near_filter(query.near_lat, query.near_lng, query.radius_m)?;
+    let feed_preference_repo = request_repos::feed_preference_repo(&state, &auth);
     let service = DiscoveryService::new(
         request_repos::feed_repo(&state, &auth),
         request_repos::notification_repo(&state, &auth),
-    );
+    )
+    .with_ranking_weights(feed_ranking_weights(&state.config))
+    .with_feed_preferences(feed_preference_repo.clone());
     let request = FeedListQuery {
         actor_id: actor_id.clone(),
         cursor: feed_cursor,
@@ -7030,6 +7578,9 @@ async fn list_discovery_feed(
         from_ms: feed_from_ms,
         to_ms: feed_to_ms,
         involvement_only: feed_involvement_only,
+        mode: feed_mode,
+        include_ranking_debug: query.debug.unwrap_or(false),
+        near: feed_near,
     };
     let mut response = service.list_feed(request).await.map_err(map_domain_error)?;
 
@@ -7041,8 +7592,14 @@ async fn list_discovery_feed(
         .collect::<HashSet<_>>()
         .into_iter()
         .collect();
-    let monitor_map = load_monitor_preferences(&state, &auth, &actor_id, &witness_ids).await?;
-    let follow_map = load_follow_preferences(&state, &auth, &actor_id, &entity_ids).await?;
+    let monitor_map = feed_preference_repo
+        .monitor_states(&actor_id, &witness_ids)
+        .await
+        .map_err(map_domain_error)?;
+    let follow_map = feed_preference_repo
+        .follow_states(&actor_id, &entity_ids)
+        .await
+        .map_err(map_domain_error)?;
 
     for item in &mut response.items {
         let witness_id = extract_witness_id(item);
@@ -7055,7 +7612,7 @@ async fn list_discovery_feed(
         item.payload = Some(Value::Object(payload));
     }
 
-    let mut suggestions = service
+    let suggestions = service
         .list_feed_suggestions(FeedSuggestionsQuery {
             actor_id: actor_id.clone(),
             limit: Some(6),
@@ -7066,13 +7623,9 @@ async fn list_discovery_feed(
         })
         .await
         .map_err(map_domain_error)?;
-    for suggestion in &mut suggestions {
-        if let Some(followed) = follow_map.get(&suggestion.entity_id) {
-            suggestion.followed = *followed;
-        }
-    }
 
     let next_cursor = response.next_cursor;
+    let ranking = response.ranking;
     let witness_items = response.items;
     let system_cards = build_feed_system_cards(&suggestions, witness_items.len());
     let stream_items = build_feed_stream(witness_items.clone(), system_cards);
@@ -7083,6 +7636,7 @@ async fn list_discovery_feed(
         stream: stream_items,
         next_cursor,
         has_more,
+        ranking,
     }))
 }
 
@@ -7092,33 +7646,71 @@ async fn list_discovery_feed_suggestions(
     Query(query): Query<FeedSuggestionsQueryParams>,
 ) -> Result<Json<Vec<FeedSuggestion>>, ApiError> {
     let actor = actor_identity(&auth)?;
-    let actor_id = actor.user_id.clone();
     let service = DiscoveryService::new(
         request_repos::feed_repo(&state, &auth),
         request_repos::notification_repo(&state, &auth),
-    );
+    )
+    .with_feed_preferences(request_repos::feed_preference_repo(&state, &auth));
     let request = FeedSuggestionsQuery {
-        actor_id: actor_id.clone(),
+        actor_id: actor.user_id,
         limit: query.limit,
         scope_id: query.scope_id,
         privacy_level: query.privacy_level,
         from_ms: query.from_ms,
         to_ms: query.to_ms,
     };
-    let mut response = service
+    let response = service
         .list_feed_suggestions(request)
         .await
         .map_err(map_domain_error)?;
-    let entity_ids: Vec<String> = response.iter().map(|item| item.entity_id.clone()).collect();
-    let follow_map = load_follow_preferences(&state, &auth, &actor_id, &entity_ids).await?;
-    for suggestion in &mut response {
-        if let Some(followed) = follow_map.get(&suggestion.entity_id) {
-            suggestion.followed = *followed;
-        }
-    }
     Ok(Json(response))
 }
 
+/// Serves the worker's cached snapshot; nothing is counted on the request path.
+async fn list_discovery_feed_trending(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Query(query): Query<FeedTrendingQueryParams>,
+) -> Result<Json<FeedTrendingResponse>, ApiError> {
+    actor_identity(&auth)?;
+    let limit = normalize_trending_limit(query.limit).map_err(map_domain_error)?;
+    let scope_id = query
+        .scope_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|scope_id| !scope_id.is_empty())
+        .map(str::to_string);
+    // A cache outage degrades to "nothing trending" rather than failing the page.
+    let snapshot = state
+        .trending_store
+        .get_snapshot(scope_id.as_deref())
+        .await
+        .unwrap_or_else(|err| {
+            tracing::warn!(error = %err, "trending snapshot read failed");
+            None
+        });
+    let Some(snapshot) = snapshot else {
+        return Ok(Json(FeedTrendingResponse {
+            scope_id,
+            computed_at_ms: None,
+            window_ms: None,
+            topics: Vec::new(),
+        }));
+    };
+    let topics = snapshot
+        .topics
+        .into_iter()
+        .filter(|topic| query.kind.is_none_or(|kind| topic.kind == kind))
+        .take(limit)
+        .collect();
+    Ok(Json(FeedTrendingResponse {
+        scope_id,
+        computed_at_ms: Some(snapshot.computed_at_ms),
+        window_ms: Some(snapshot.window_ms),
+        topics,
+    }))
+}
+
 async fn list_discovery_search(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
@@ -7132,10 +7724,14 @@ async fn list_discovery_search(
         .filter(|query_text| !query_text.is_empty())
         .map(str::to_string)
         .ok_or_else(|| ApiError::Validation("query_text is required".into()))?;
-    let service = DiscoveryService::new(
+    let near = feed_near_filter(query.near_lat, query.near_lng, query.radius_m)?;
+    let mut service = DiscoveryService::new(
         request_repos::feed_repo(&state, &auth),
         request_repos::notification_repo(&state, &auth),
     );
+    if let Some(index) = state.feed_search_index.clone() {
+        service = service.with_search_index(index);
+    }
     let request = SearchListQuery {
         actor_id: actor.user_id,
         query_text,
@@ -7147,6 +7743,7 @@ async fn list_discovery_search(
         to_ms: query.to_ms,
         involvement_only: query.involvement_only.unwrap_or(false),
         exclude_vault: query.exclude_vault.unwrap_or(false),
+        near,
     };
     let response = service.search(request).await.map_err(map_domain_error)?;
     Ok(Json(response))
@@ -7167,6 +7764,7 @@ async fn list_discovery_notifications(
         cursor: query.cursor,
         limit: query.limit,
         include_read: query.include_read,
+        grouped: query.grouped,
     };
     let response = service
         .list_notifications(request)
@@ -7175,6 +7773,144 @@ async fn list_discovery_notifications(
     Ok(Json(response))
 }
 
+/// Opens the actor's realtime stream, seeded with their current unread count.
+async fn open_discovery_stream(
+    state: &AppState,
+    auth: &AuthContext,
+) -> Result<(DiscoveryRealtimeSubscription, DiscoveryEvent), ApiError> {
+    let actor = actor_identity(auth)?;
+    let subscription = state.discovery_realtime.subscribe(&actor.user_id).await;
+    let service = DiscoveryService::new(
+        request_repos::feed_repo(state, auth),
+        request_repos::notification_repo(state, auth),
+    );
+    let unread_count = service
+        .unread_notification_count(&actor.user_id)
+        .await
+        .map_err(map_domain_error)?;
+    Ok((
+        subscription,
+        DiscoveryEvent::UnreadCount {
+            user_id: actor.user_id,
+            unread_count,
+        },
+    ))
+}
+
+fn discovery_stream_event(event: &DiscoveryEvent) -> Event {
+    let name = match event {
+        DiscoveryEvent::Notification { .. } => "notification",
+        DiscoveryEvent::UnreadCount { .. } => "unread_count",
+        DiscoveryEvent::FeedItem { .. } => "feed_item",
+    };
+    Event::default()
+        .event(name)
+        .json_data(event)
+        .unwrap_or_else(|_| {
+            Event::default()
+                .event("error")
+                .data("failed-to-serialize-event")
+        })
+}
+
+async fn stream_discovery_events_sse(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Response, ApiError> {
+    let (mut subscription, unread) = open_discovery_stream(&state, &auth).await?;
+    let (tx, rx) = mpsc::unbounded_channel::<Result<Event, Infallible>>();
+    let _ = tx.send(Ok(discovery_stream_event(&unread)));
+
+    tokio::spawn(async move {
+        let mut heartbeat = interval(Duration::from_secs(15));
+        loop {
+            let sent = tokio::select! {
+                event = subscription.recv() => match event {
+                    Ok(event) => tx.send(Ok(discovery_stream_event(&event))),
+                    // Dropped events: the client refetches lists and the unread count.
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
+                        tx.send(Ok(Event::default().event("replay").data("missed_events")))
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                },
+                _ = heartbeat.tick() => {
+                    tx.send(Ok(Event::default().event("ping").data("keep-alive")))
+                }
+            };
+            if sent.is_err() {
+                break;
+            }
+        }
+    });
+
+    Ok(Sse::new(UnboundedReceiverStream::new(rx))
+        .keep_alive(KeepAlive::new().interval(Duration::from_secs(15)))
+        .into_response())
+}
+
+async fn stream_discovery_events_ws(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    let (subscription, unread) = open_discovery_stream(&state, &auth).await?;
+    Ok(ws.on_upgrade(move |socket| handle_discovery_websocket(socket, subscription, unread)))
+}
+
+async fn handle_discovery_websocket(
+    socket: WebSocket,
+    mut subscription: DiscoveryRealtimeSubscription,
+    unread: DiscoveryEvent,
+) {
+    let (mut sender, mut incoming) = socket.split();
+    let payload = |event: &DiscoveryEvent| {
+        serde_json::to_string(event)
+            .unwrap_or_else(|_| "{\"event_type\":\"error\",\"message\":{}}".to_string())
+    };
+    if sender.send(Message::Text(payload(&unread))).await.is_err() {
+        return;
+    }
+
+    let mut heartbeat = interval(Duration::from_secs(15));
+    loop {
+        tokio::select! {
+            event = subscription.recv() => {
+                let text = match event {
+                    Ok(event) => payload(&event),
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
+                        "{\"event_type\":\"error\",\"message\":\"missed_events_refetch\"}"
+                            .to_string()
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                        let _ = sender
+                            .send(Message::Close(Some(CloseFrame {
+                                code: close_code::AWAY,
+                                reason: "stream closed".into(),
+                            })))
+                            .await;
+                        return;
+                    }
+                };
+                if sender.send(Message::Text(text)).await.is_err() {
+                    return;
+                }
+            }
+            incoming = incoming.next() => {
+                match incoming {
+                    Some(Ok(Message::Close(_))) => return,
+                    Some(Ok(_)) => {}
+                    Some(Err(_)) | None => return,
+                }
+            }
+            _ = heartbeat.tick() => {
+                if sender.send(Message::Ping(Vec::new())).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
 async fn mark_notification_read(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
@@ -7184,7 +7920,8 @@ async fn mark_notification_read(
     let service = DiscoveryService::new(
         request_repos::feed_repo(&state, &auth),
         request_repos::notification_repo(&state, &auth),
-    );
+    )
+    .with_event_publisher(Arc::new(state.discovery_realtime.clone()));
     let response = service
         .mark_notification_read(&actor.user_id, &notification_id)
         .await
@@ -7227,6 +7964,174 @@ async fn discovery_weekly_digest(
     Ok(Json(response))
 }
 
+async fn get_digest_settings(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<DigestSettingsResponse>, ApiError> {
+    let actor = actor_identity(&auth)?;
+    let service = DigestService::new(request_repos::digest_subscription_repo(&state, &auth));
+    let subscription = service
+        .get_settings(&actor.user_id)
+        .await
+        .map_err(map_domain_error)?;
+    Ok(Json(subscription.into()))
+}
+
+async fn update_digest_settings(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Json(payload): Json<DigestSettingsUpdate>,
+) -> Result<Json<DigestSettingsResponse>, ApiError> {
+    let actor = actor_identity(&auth)?;
+    let service = DigestService::new(request_repos::digest_subscription_repo(&state, &auth));
+    let subscription = service
+        .update_settings(&actor.user_id, payload)
+        .await
+        .map_err(map_domain_error)?;
+    Ok(Json(Some(subscription).into()))
+}
+
+async fn get_notification_preferences(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<NotificationPreferences>, ApiError> {
+    let actor = actor_identity(&auth)?;
+    let service = NotificationPreferenceService::new(request_repos::notification_preference_repo(
+        &state, &auth,
+    ));
+    let preferences = service
+        .get(&actor.user_id)
+        .await
+        .map_err(map_domain_error)?;
+    Ok(Json(preferences))
+}
+
+async fn update_notification_preferences(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Json(payload): Json<NotificationPreferencesUpdate>,
+) -> Result<Json<NotificationPreferences>, ApiError> {
+    let actor = actor_identity(&auth)?;
+    let service = NotificationPreferenceService::new(request_repos::notification_preference_repo(
+        &state, &auth,
+    ));
+    let preferences = service
+        .update(&actor.user_id, payload)
+        .await
+        .map_err(map_domain_error)?;
+    Ok(Json(preferences))
+}
+
+#[derive(Debug, Serialize)]
+struct WebPushPublicKeyResponse {
+    public_key: String,
+}
+
+/// The VAPID `applicationServerKey` browsers need before they can subscribe.
+async fn get_web_push_public_key(
+    State(state): State<AppState>,
+) -> Result<Json<WebPushPublicKeyResponse>, ApiError> {
+    let public_key = state.config.web_push_vapid_public_key.trim();
+    if public_key.is_empty() {
+        return Err(ApiError::NotFound);
+    }
+    Ok(Json(WebPushPublicKeyResponse {
+        public_key: public_key.to_string(),
+    }))
+}
+
+async fn list_push_subscriptions(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<Vec<PushSubscription>>, ApiError> {
+    let actor = actor_identity(&auth)?;
+    let service =
+        PushSubscriptionService::new(request_repos::push_subscription_repo(&state, &auth));
+    let subscriptions = service
+        .list(&actor.user_id)
+        .await
+        .map_err(map_domain_error)?;
+    Ok(Json(subscriptions))
+}
+
+async fn create_push_subscription(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Json(payload): Json<PushSubscriptionInput>,
+) -> Result<(StatusCode, Json<PushSubscription>), ApiError> {
+    let actor = actor_identity(&auth)?;
+    let service =
+        PushSubscriptionService::new(request_repos::push_subscription_repo(&state, &auth));
+    let subscription = service
+        .subscribe(&actor.user_id, payload)
+        .await
+        .map_err(map_domain_error)?;
+    Ok((StatusCode::CREATED, Json(subscription)))
+}
+
+async fn delete_push_subscription(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(subscription_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let actor = actor_identity(&auth)?;
+    let service =
+        PushSubscriptionService::new(request_repos::push_subscription_repo(&state, &auth));
+    service
+        .unsubscribe(&actor.user_id, &subscription_id)
+        .await
+        .map_err(map_domain_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Landing page of the signed link in digest emails. Link scanners and prefetchers issue GETs,
+/// so this only asks for confirmation; the form posts back to [`digest_unsubscribe`].
+async fn digest_unsubscribe_confirmation(
+    State(state): State<AppState>,
+    Query(query): Query<DigestUnsubscribeQuery>,
+) -> Result<Html<String>, ApiError> {
+    let service = DigestService::new(state.digest_subscription_repo.clone());
+    let subscription = service
+        .subscription_for_token(&state.config.digest_unsubscribe_secret, &query.token)
+        .await
+        .map_err(map_domain_error)?;
+    // A verified token is hex on both sides of the dot, so it is safe to embed as is.
+    let token = query.token.trim();
+    let (lang, prompt, button) = match subscription.locale {
+        DigestLocale::Id => (
+            "id",
+            "Berhenti menerima ringkasan Gotong lewat email?",
+            "Berhenti berlangganan",
+        ),
+        DigestLocale::En => ("en", "Stop receiving Gotong digest emails?", "Unsubscribe"),
+    };
+    Ok(Html(format!(
+        "<!doctype html><html lang=\"{lang}\"><body><form method=\"post\" action=\"/v1/digest/unsubscribe?token={token}\"><p>{prompt}</p><button type=\"submit\">{button}</button></form></body></html>"
+    )))
+}
+
+/// Unsubscribes the token's owner: the confirmation form and RFC 8058 one-click
+/// `List-Unsubscribe-Post` requests from mail clients both land here. Public, because mail
+/// clients post without a session; the token is the credential.
+async fn digest_unsubscribe(
+    State(state): State<AppState>,
+    Query(query): Query<DigestUnsubscribeQuery>,
+) -> Result<Html<&'static str>, ApiError> {
+    let service = DigestService::new(state.digest_subscription_repo.clone());
+    let subscription = service
+        .unsubscribe(&state.config.digest_unsubscribe_secret, &query.token)
+        .await
+        .map_err(map_domain_error)?;
+    Ok(Html(match subscription.locale {
+        DigestLocale::Id => {
+            "<!doctype html><html lang=\"id\"><body><p>Anda tidak akan menerima ringkasan Gotong lagi.</p></body></html>"
+        }
+        DigestLocale::En => {
+            "<!doctype html><html lang=\"en\"><body><p>You will no longer receive Gotong digests.</p></body></html>"
+        }
+    }))
+}
+
 async fn get_contribution(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
@@ -7432,6 +8337,20 @@ struct UpdateAdaptivePathPlanRequest {
     pub request_ts_ms: Option<i64>,
 }
 
+#[derive(Debug, Deserialize)]
+struct RollbackAdaptivePathPlanRequest {
+    pub expected_version: u64,
+    pub target_version: u64,
+    #[serde(default)]
+    pub request_ts_ms: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdaptivePathDiffQuery {
+    pub from: u64,
+    pub to: u64,
+}
+
 #[derive(Debug, Deserialize)]
 struct SuggestAdaptivePathPlanRequest {
     pub base_version: u64,
@@ -7927,7 +8846,6 @@ async fn create_adaptive_path_plan(
 ) -> Result<Response, ApiError> {
     let actor = actor_identity(&auth)?;
     let token_role = auth.role.clone();
-    let editor_roles = trusted_editor_roles(&token_role);
     let request_id = request_id_from_headers(&headers)?;
     let correlation_id = correlation_id_from_headers(&headers)?;
 
@@ -7950,12 +8868,10 @@ async fn create_adaptive_path_plan(
         BeginOutcome::Replay(response) => Ok(to_response(response)),
         BeginOutcome::InProgress => Err(ApiError::Conflict),
         BeginOutcome::Started => {
-            let service =
-                AdaptivePathService::new(request_repos::adaptive_path_repo(&state, &auth));
+            let service = adaptive_path_service(&state, &auth);
             let input = CreateAdaptivePathInput {
                 entity_id,
                 payload: into_adaptive_path_payload_draft(payload.payload),
-                editor_roles,
                 request_id,
                 correlation_id,
                 request_ts_ms: payload.request_ts_ms,
@@ -7986,7 +8902,7 @@ async fn get_adaptive_path_plan(
     Extension(auth): Extension<AuthContext>,
     Path(plan_id): Path<String>,
 ) -> Result<Json<AdaptivePathPlan>, ApiError> {
-    let service = AdaptivePathService::new(request_repos::adaptive_path_repo(&state, &auth));
+    let service = adaptive_path_service(&state, &auth);
     let plan = service.get_plan(&plan_id).await.map_err(map_domain_error)?;
     let plan = plan.ok_or(ApiError::NotFound)?;
     Ok(Json(plan))
@@ -7997,7 +8913,7 @@ async fn get_adaptive_path_plan_by_entity(
     Extension(auth): Extension<AuthContext>,
     Path(entity_id): Path<String>,
 ) -> Result<Json<AdaptivePathPlan>, ApiError> {
-    let service = AdaptivePathService::new(request_repos::adaptive_path_repo(&state, &auth));
+    let service = adaptive_path_service(&state, &auth);
     let plan = service
         .get_plan_by_entity(&entity_id)
         .await
@@ -8006,21 +8922,117 @@ async fn get_adaptive_path_plan_by_entity(
     Ok(Json(plan))
 }
 
-async fn update_adaptive_path_plan(
+async fn update_adaptive_path_plan(
+    State(state): State<AppState>,
+    Path(plan_id): Path<String>,
+    headers: HeaderMap,
+    Extension(auth): Extension<AuthContext>,
+    Json(payload): Json<UpdateAdaptivePathPlanRequest>,
+) -> Result<Response, ApiError> {
+    let actor = actor_identity(&auth)?;
+    let token_role = auth.role.clone();
+    let request_id = request_id_from_headers(&headers)?;
+    let correlation_id = correlation_id_from_headers(&headers)?;
+
+    let key = IdempotencyKey::new(
+        "adaptive_path_update",
+        format!("{}:{plan_id}", actor.user_id),
+        request_id.clone(),
+    );
+    let outcome = state.idempotency.begin(&key).await.map_err(|err| {
+        tracing::error!(error = %err, "idempotency begin failed");
+        ApiError::Internal
+    })?;
+
+    match outcome {
+        BeginOutcome::Replay(response) => Ok(to_response(response)),
+        BeginOutcome::InProgress => Err(ApiError::Conflict),
+        BeginOutcome::Started => {
+            let service = adaptive_path_service(&state, &auth);
+            let input = UpdateAdaptivePathInput {
+                plan_id,
+                expected_version: payload.expected_version,
+                payload: into_adaptive_path_payload_draft(payload.payload),
+                request_id,
+                correlation_id,
+                request_ts_ms: payload.request_ts_ms,
+            };
+            let plan = service
+                .update_plan(&actor, &token_role, input)
+                .await
+                .map_err(map_domain_error)?;
+            let response = IdempotencyResponse {
+                status_code: StatusCode::OK.as_u16(),
+                body: serde_json::to_value(&plan).map_err(|_| ApiError::Internal)?,
+            };
+            state
+                .idempotency
+                .complete(&key, response.clone())
+                .await
+                .map_err(|err| {
+                    tracing::error!(error = %err, "idempotency complete failed");
+                    ApiError::Internal
+                })?;
+            Ok(to_response(response))
+        }
+    }
+}
+
+async fn list_adaptive_path_plan_versions(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(plan_id): Path<String>,
+) -> Result<Json<Vec<AdaptivePathPlanVersionSummary>>, ApiError> {
+    let service = adaptive_path_service(&state, &auth);
+    let versions = service
+        .list_plan_versions(&plan_id)
+        .await
+        .map_err(map_domain_error)?;
+    Ok(Json(versions))
+}
+
+async fn get_adaptive_path_plan_version(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((plan_id, version)): Path<(String, u64)>,
+) -> Result<Json<AdaptivePathPlan>, ApiError> {
+    let service = adaptive_path_service(&state, &auth);
+    let plan = service
+        .get_plan_version(&plan_id, version)
+        .await
+        .map_err(map_domain_error)?;
+    let plan = plan.ok_or(ApiError::NotFound)?;
+    Ok(Json(plan))
+}
+
+async fn diff_adaptive_path_plan_versions(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(plan_id): Path<String>,
+    Query(query): Query<AdaptivePathDiffQuery>,
+) -> Result<Json<AdaptivePathPlanDiff>, ApiError> {
+    let service = adaptive_path_service(&state, &auth);
+    let diff = service
+        .diff_plan_versions(&plan_id, query.from, query.to)
+        .await
+        .map_err(map_domain_error)?;
+    Ok(Json(diff))
+}
+
+async fn rollback_adaptive_path_plan(
     State(state): State<AppState>,
     Path(plan_id): Path<String>,
     headers: HeaderMap,
     Extension(auth): Extension<AuthContext>,
-    Json(payload): Json<UpdateAdaptivePathPlanRequest>,
+    Json(payload): Json<RollbackAdaptivePathPlanRequest>,
 ) -> Result<Response, ApiError> {
     let actor = actor_identity(&auth)?;
     let token_role = auth.role.clone();
     let request_id = request_id_from_headers(&headers)?;
     let correlation_id = correlation_id_from_headers(&headers)?;
-    let editor_roles = trusted_editor_roles(&token_role);
 
     let key = IdempotencyKey::new(
-        "adaptive_path_update",
+        "adaptive_path_rollback",
         format!("{}:{plan_id}", actor.user_id),
         request_id.clone(),
     );
@@ -8033,19 +9045,17 @@ async fn update_adaptive_path_plan(
         BeginOutcome::Replay(response) => Ok(to_response(response)),
         BeginOutcome::InProgress => Err(ApiError::Conflict),
         BeginOutcome::Started => {
-            let service =
-                AdaptivePathService::new(request_repos::adaptive_path_repo(&state, &auth));
-            let input = UpdateAdaptivePathInput {
+            let service = adaptive_path_service(&state, &auth);
+            let input = RollbackAdaptivePathInput {
                 plan_id,
                 expected_version: payload.expected_version,
-                payload: into_adaptive_path_payload_draft(payload.payload),
-                editor_roles,
+                target_version: payload.target_version,
                 request_id,
                 correlation_id,
                 request_ts_ms: payload.request_ts_ms,
             };
             let plan = service
-                .update_plan(&actor, &token_role, input)
+                .rollback_plan(&actor, &token_role, input)
                 .await
                 .map_err(map_domain_error)?;
             let response = IdempotencyResponse {
@@ -8076,7 +9086,6 @@ async fn propose_adaptive_path_suggestion(
     let token_role = auth.role.clone();
     let request_id = request_id_from_headers(&headers)?;
     let correlation_id = correlation_id_from_headers(&headers)?;
-    let editor_roles = trusted_editor_roles(&token_role);
 
     let key = IdempotencyKey::new(
         "adaptive_path_suggest",
@@ -8092,8 +9101,7 @@ async fn propose_adaptive_path_suggestion(
         BeginOutcome::Replay(response) => Ok(to_response(response)),
         BeginOutcome::InProgress => Err(ApiError::Conflict),
         BeginOutcome::Started => {
-            let service =
-                AdaptivePathService::new(request_repos::adaptive_path_repo(&state, &auth));
+            let service = adaptive_path_service(&state, &auth);
             let input = SuggestAdaptivePathInput {
                 plan_id,
                 base_version: payload.base_version,
@@ -8101,7 +9109,6 @@ async fn propose_adaptive_path_suggestion(
                 rationale: payload.rationale,
                 model_id: payload.model_id,
                 prompt_version: payload.prompt_version,
-                editor_roles,
                 request_id,
                 correlation_id,
                 request_ts_ms: payload.request_ts_ms,
@@ -8138,7 +9145,6 @@ async fn accept_adaptive_path_suggestion(
     let token_role = auth.role.clone();
     let request_id = request_id_from_headers(&headers)?;
     let correlation_id = correlation_id_from_headers(&headers)?;
-    let editor_roles = trusted_editor_roles(&token_role);
 
     let key = IdempotencyKey::new(
         "adaptive_path_accept",
@@ -8154,11 +9160,9 @@ async fn accept_adaptive_path_suggestion(
         BeginOutcome::Replay(response) => Ok(to_response(response)),
         BeginOutcome::InProgress => Err(ApiError::Conflict),
         BeginOutcome::Started => {
-            let service =
-                AdaptivePathService::new(request_repos::adaptive_path_repo(&state, &auth));
+            let service = adaptive_path_service(&state, &auth);
             let input = SuggestionReviewInput {
                 suggestion_id,
-                editor_roles,
                 request_id,
                 correlation_id,
                 request_ts_ms: payload.request_ts_ms,
@@ -8195,7 +9199,6 @@ async fn reject_adaptive_path_suggestion(
     let token_role = auth.role.clone();
     let request_id = request_id_from_headers(&headers)?;
     let correlation_id = correlation_id_from_headers(&headers)?;
-    let editor_roles = trusted_editor_roles(&token_role);
 
     let key = IdempotencyKey::new(
         "adaptive_path_reject",
@@ -8211,11 +9214,9 @@ async fn reject_adaptive_path_suggestion(
         BeginOutcome::Replay(response) => Ok(to_response(response)),
         BeginOutcome::InProgress => Err(ApiError::Conflict),
         BeginOutcome::Started => {
-            let service =
-                AdaptivePathService::new(request_repos::adaptive_path_repo(&state, &auth));
+            let service = adaptive_path_service(&state, &auth);
             let input = SuggestionReviewInput {
                 suggestion_id,
-                editor_roles,
                 request_id,
                 correlation_id,
                 request_ts_ms: payload.request_ts_ms,
@@ -8246,7 +9247,7 @@ async fn list_adaptive_path_events(
     Extension(auth): Extension<AuthContext>,
     Path(plan_id): Path<String>,
 ) -> Result<Json<Vec<AdaptivePathEvent>>, ApiError> {
-    let service = AdaptivePathService::new(request_repos::adaptive_path_repo(&state, &auth));
+    let service = adaptive_path_service(&state, &auth);
     let events = service
         .list_events(&plan_id)
         .await
@@ -8259,7 +9260,7 @@ async fn list_adaptive_path_suggestions(
     Extension(auth): Extension<AuthContext>,
     Path(plan_id): Path<String>,
 ) -> Result<Json<Vec<AdaptivePathSuggestion>>, ApiError> {
-    let service = AdaptivePathService::new(request_repos::adaptive_path_repo(&state, &auth));
+    let service = adaptive_path_service(&state, &auth);
     let suggestions = service
         .list_suggestions(&plan_id)
         .await
@@ -8830,7 +9831,14 @@ async fn ingest_discovery_contribution_feed(
     privacy_level: Option<String>,
     payload: Option<Value>,
 ) -> Result<gotong_domain::discovery::FeedItem, ApiError> {
-    let service = DiscoveryService::new(state.feed_repo.clone(), state.notification_repo.clone());
+    let service = DiscoveryService::new(state.feed_repo.clone(), state.notification_repo.clone())
+        .with_event_publisher(Arc::new(state.discovery_realtime.clone()));
+    // `metadata.location` is free-form; only a `{lat, lng}` object locates the item.
+    let location = contribution
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get("location"))
+        .and_then(|location| GeoPoint::from_json(location).ok());
     let metadata_payload = payload.or_else(|| {
         contribution.metadata.as_ref().map(|metadata| {
             let mut object = serde_json::Map::new();
@@ -8854,6 +9862,7 @@ async fn ingest_discovery_contribution_feed(
         request_ts_ms: Some(contribution.created_at_ms),
         participant_ids: vec![],
         payload: metadata_payload,
+        location,
     };
     service.ingest_feed(input).await.map_err(map_domain_error)
 }
@@ -8865,7 +9874,8 @@ async fn ingest_discovery_vouch_feed(
     correlation_id: String,
     vouch: &Vouch,
 ) -> Result<(), ApiError> {
-    let service = DiscoveryService::new(state.feed_repo.clone(), state.notification_repo.clone());
+    let service = DiscoveryService::new(state.feed_repo.clone(), state.notification_repo.clone())
+        .with_event_publisher(Arc::new(state.discovery_realtime.clone()));
     let summary = vouch
         .message
         .clone()
@@ -8889,6 +9899,7 @@ async fn ingest_discovery_vouch_feed(
             "weight_hint": vouch.weight_hint,
             "message": vouch.message,
         })),
+        location: None,
     };
     service.ingest_feed(input).await.map_err(map_domain_error)?;
     Ok(())
@@ -8924,25 +9935,70 @@ struct ChatAttachmentStoredMetadata {
     media_type: String,
     uploaded_by: String,
     created_at_ms: i64,
+    #[serde(default)]
+    thread_id: Option<String>,
+    #[serde(default)]
+    width: Option<u32>,
+    #[serde(default)]
+    height: Option<u32>,
+    #[serde(default)]
+    thumbnail_size_bytes: Option<usize>,
 }
 
 #[derive(Debug, Serialize)]
-struct ChatAttachmentUploadResponse {
+struct ChatAttachmentResponse {
     attachment_id: String,
     file_name: String,
     mime_type: String,
-    size_bytes: usize,
+    size_bytes: u64,
     media_type: String,
+    thread_id: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
     url: String,
+    thumbnail_url: Option<String>,
     expires_at_ms: i64,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ChatAttachmentVariant {
+    Original,
+    Thumbnail,
+}
+
+/// Where the original bytes come from when an attachment is persisted.
+#[derive(Debug)]
+enum ChatAttachmentOriginal {
+    /// Bytes held in memory, written to the backend as-is.
+    Bytes(Vec<u8>),
+    /// A completed resumable upload on local disk, moved into place.
+    LocalFile(PathBuf),
+    /// A direct-to-S3 upload under its staging key, copied into place server-side.
+    S3Staged(String),
+}
+
+/// An upload whose content has been sniffed and is ready to be persisted and recorded.
+#[derive(Debug)]
+struct PendingChatAttachment {
+    attachment_id: String,
+    thread_id: Option<String>,
+    raw_file_name: String,
+    sniffed: chat_media::SniffedMedia,
+    size_bytes: usize,
+}
+
 #[derive(Serialize)]
 struct ChatStreamEnvelope {
     event_type: &'static str,
     message: ChatMessage,
 }
 
+#[derive(Serialize)]
+struct ChatReadCursorStreamEnvelope {
+    event_type: &'static str,
+    read_cursor: ChatReadCursor,
+}
+
 #[derive(Clone, Debug, Serialize)]
 struct ChatAuthorSnapshot {
     user_id: String,
@@ -8955,6 +10011,18 @@ struct ChatAuthorSnapshot {
     role: Option<String>,
 }
 
+/// Thread list entry. Counts are only computed for the actor's own thread list,
+/// not for scope listings.
+#[derive(Debug, Serialize)]
+struct ChatThreadListItem {
+    #[serde(flatten)]
+    thread: ChatThread,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    member_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unread_count: Option<usize>,
+}
+
 #[derive(Clone, Debug, Serialize)]
 struct ChatMessageView {
     #[serde(flatten)]
@@ -8978,6 +10046,11 @@ struct CreateChatThreadRequest {
     privacy_level: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct SetChatThreadRetentionRequest {
+    retention: String,
+}
+
 #[derive(Debug, Deserialize, Validate)]
 struct SendChatMessageRequest {
     #[validate(length(min = 1, max = 2_000))]
@@ -9020,6 +10093,28 @@ fn chat_message_stream_events(message: ChatMessage) -> Event {
         })
 }
 
+fn chat_read_cursor_stream_event(read_cursor: ChatReadCursor) -> Event {
+    Event::default()
+        .event("read_cursor")
+        .json_data(ChatReadCursorStreamEnvelope {
+            event_type: "read_cursor",
+            read_cursor,
+        })
+        .unwrap_or_else(|_| {
+            Event::default()
+                .event("error")
+                .data("failed-to-serialize-read-cursor")
+        })
+}
+
+fn websocket_read_cursor_payload(read_cursor: &ChatReadCursor) -> String {
+    serde_json::to_string(&ChatReadCursorStreamEnvelope {
+        event_type: "read_cursor",
+        read_cursor: read_cursor.clone(),
+    })
+    .unwrap_or_else(|_| "{\"event_type\":\"error\",\"message\":{}}".to_string())
+}
+
 fn websocket_payload(message: &ChatMessage) -> String {
     serde_json::to_string(&ChatStreamEnvelope {
         event_type: "message",
@@ -9036,6 +10131,10 @@ fn chat_attachment_meta_path(root: &FsPath, attachment_id: &str) -> PathBuf {
     root.join(format!("{attachment_id}.json"))
 }
 
+fn chat_attachment_thumbnail_path(root: &FsPath, attachment_id: &str) -> PathBuf {
+    root.join(format!("{attachment_id}.thumb.jpg"))
+}
+
 fn chat_attachment_s3_file_key(key_prefix: &str, attachment_id: &str) -> String {
     format!("{key_prefix}/{attachment_id}.bin")
 }
@@ -9044,11 +10143,77 @@ fn chat_attachment_s3_meta_key(key_prefix: &str, attachment_id: &str) -> String
     format!("{key_prefix}/{attachment_id}.json")
 }
 
+fn chat_attachment_s3_thumbnail_key(key_prefix: &str, attachment_id: &str) -> String {
+    format!("{key_prefix}/{attachment_id}.thumb.jpg")
+}
+
+fn chat_attachment_thumbnail_file_name(file_name: &str) -> String {
+    let stem = file_name
+        .rsplit_once('.')
+        .map(|(stem, _)| stem)
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or(file_name);
+    format!("{stem}-thumb.jpg")
+}
+
+async fn put_chat_attachment_s3_object(
+    client: &reqwest::Client,
+    bucket: &rusty_s3::Bucket,
+    credentials: &rusty_s3::Credentials,
+    key: &str,
+    bytes: Vec<u8>,
+) -> Result<(), ApiError> {
+    let put_url = bucket
+        .put_object(Some(credentials), key)
+        .sign(Duration::from_secs(300));
+    let put_response = client
+        .put(put_url)
+        .body(bytes)
+        .send()
+        .await
+        .map_err(|_| ApiError::Internal)?;
+    if !put_response.status().is_success() {
+        return Err(ApiError::Internal);
+    }
+    Ok(())
+}
+
+/// Server-side `CopyObject`, so direct uploads never pass through the API.
+async fn copy_chat_attachment_s3_object(
+    client: &reqwest::Client,
+    bucket: &rusty_s3::Bucket,
+    credentials: &rusty_s3::Credentials,
+    source_key: &str,
+    key: &str,
+) -> Result<(), ApiError> {
+    let copy_source = format!("/{}/{source_key}", bucket.name());
+    let mut action = bucket.put_object(Some(credentials), key);
+    action
+        .headers_mut()
+        .insert("x-amz-copy-source", copy_source.clone());
+    let copy_url = action.sign(Duration::from_secs(300));
+    let copy_response = client
+        .put(copy_url)
+        .header("x-amz-copy-source", copy_source)
+        .send()
+        .await
+        .map_err(|_| ApiError::Internal)?;
+    if !copy_response.status().is_success() {
+        return Err(ApiError::Internal);
+    }
+    // S3 can report a failed copy in the body of a 200 response.
+    let body = copy_response.text().await.map_err(|_| ApiError::Internal)?;
+    if body.contains("<Error>") {
+        return Err(ApiError::Internal);
+    }
+    Ok(())
+}
+
 async fn save_chat_attachment_artifacts(
     state: &AppState,
     attachment_id: &str,
-    _mime_type: &str,
-    file_bytes: Vec<u8>,
+    original: ChatAttachmentOriginal,
+    thumbnail_bytes: Option<Vec<u8>>,
     metadata_bytes: Vec<u8>,
 ) -> Result<(), ApiError> {
     match &state.chat_attachment_storage {
@@ -9057,9 +10222,26 @@ async fn save_chat_attachment_artifacts(
                 .await
                 .map_err(|_| ApiError::Internal)?;
             let file_path = chat_attachment_file_path(root, attachment_id);
-            tokio::fs::write(file_path, file_bytes)
-                .await
-                .map_err(|_| ApiError::Internal)?;
+            match original {
+                ChatAttachmentOriginal::Bytes(file_bytes) => {
+                    tokio::fs::write(file_path, file_bytes)
+                        .await
+                        .map_err(|_| ApiError::Internal)?
+                }
+                ChatAttachmentOriginal::LocalFile(source_path) => {
+                    tokio::fs::rename(source_path, file_path)
+                        .await
+                        .map_err(|_| ApiError::Internal)?
+                }
+                // Direct uploads only exist with the S3 backend.
+                ChatAttachmentOriginal::S3Staged(_) => return Err(ApiError::Internal),
+            }
+            if let Some(thumbnail_bytes) = thumbnail_bytes {
+                let thumbnail_path = chat_attachment_thumbnail_path(root, attachment_id);
+                tokio::fs::write(thumbnail_path, thumbnail_bytes)
+                    .await
+                    .map_err(|_| ApiError::Internal)?;
+            }
             let metadata_path = chat_attachment_meta_path(root, attachment_id);
             tokio::fs::write(metadata_path, metadata_bytes)
                 .await
@@ -9073,51 +10255,54 @@ async fn save_chat_attachment_artifacts(
             key_prefix,
         } => {
             let file_key = chat_attachment_s3_file_key(key_prefix, attachment_id);
-            let metadata_key = chat_attachment_s3_meta_key(key_prefix, attachment_id);
-            let file_put_url = bucket
-                .put_object(Some(credentials), &file_key)
-                .sign(Duration::from_secs(300));
-            let file_put_response = client
-                .put(file_put_url)
-                .body(file_bytes)
-                .send()
-                .await
-                .map_err(|_| ApiError::Internal)?;
-            if !file_put_response.status().is_success() {
-                return Err(ApiError::Internal);
+            let file_bytes = match original {
+                ChatAttachmentOriginal::Bytes(file_bytes) => Some(file_bytes),
+                ChatAttachmentOriginal::LocalFile(source_path) => Some(
+                    tokio::fs::read(source_path)
+                        .await
+                        .map_err(|_| ApiError::Internal)?,
+                ),
+                ChatAttachmentOriginal::S3Staged(staging_key) => {
+                    copy_chat_attachment_s3_object(
+                        client,
+                        bucket,
+                        credentials,
+                        &staging_key,
+                        &file_key,
+                    )
+                    .await?;
+                    None
+                }
+            };
+            if let Some(file_bytes) = file_bytes {
+                put_chat_attachment_s3_object(client, bucket, credentials, &file_key, file_bytes)
+                    .await?;
             }
-
-            let metadata_put_url = bucket
-                .put_object(Some(credentials), &metadata_key)
-                .sign(Duration::from_secs(300));
-            let metadata_put_response = client
-                .put(metadata_put_url)
-                .body(metadata_bytes)
-                .send()
-                .await
-                .map_err(|_| ApiError::Internal)?;
-            if !metadata_put_response.status().is_success() {
-                return Err(ApiError::Internal);
+            if let Some(thumbnail_bytes) = thumbnail_bytes {
+                let thumbnail_key = chat_attachment_s3_thumbnail_key(key_prefix, attachment_id);
+                put_chat_attachment_s3_object(
+                    client,
+                    bucket,
+                    credentials,
+                    &thumbnail_key,
+                    thumbnail_bytes,
+                )
+                .await?;
             }
-            Ok(())
+            // Metadata goes last so downloads never see a record without its bytes.
+            let metadata_key = chat_attachment_s3_meta_key(key_prefix, attachment_id);
+            put_chat_attachment_s3_object(
+                client,
+                bucket,
+                credentials,
+                &metadata_key,
+                metadata_bytes,
+            )
+            .await
         }
     }
 }
 
-fn infer_chat_media_type(mime_type: &str) -> Option<&'static str> {
-    let normalized = mime_type.trim().to_ascii_lowercase();
-    if normalized.starts_with("image/") {
-        return Some("image");
-    }
-    if normalized.starts_with("video/") {
-        return Some("video");
-    }
-    if normalized.starts_with("audio/") {
-        return Some("audio");
-    }
-    None
-}
-
 fn sanitize_chat_attachment_filename(file_name: &str, media_type: &str) -> String {
     let trimmed = file_name.trim();
     if trimmed.is_empty() {
@@ -9167,6 +10352,37 @@ fn verify_chat_attachment_signature(
     diff == 0
 }
 
+fn chat_attachment_response(
+    state: &AppState,
+    attachment: ChatAttachment,
+) -> ChatAttachmentResponse {
+    let expires_at_ms = gotong_domain::jobs::now_ms() + CHAT_ATTACHMENT_URL_TTL_MS;
+    let signature = chat_attachment_signature(
+        &state.config.jwt_secret,
+        &attachment.attachment_id,
+        expires_at_ms,
+    );
+    let base_path = format!("/v1/chat/attachments/{}", attachment.attachment_id);
+    let url = format!("{base_path}/download?exp={expires_at_ms}&sig={signature}");
+    let thumbnail_url = attachment
+        .thumbnail_size_bytes
+        .map(|_| format!("{base_path}/thumbnail?exp={expires_at_ms}&sig={signature}"));
+
+    ChatAttachmentResponse {
+        attachment_id: attachment.attachment_id,
+        file_name: attachment.file_name,
+        mime_type: attachment.mime_type,
+        size_bytes: attachment.size_bytes,
+        media_type: attachment.media_type,
+        thread_id: attachment.thread_id,
+        width: attachment.width,
+        height: attachment.height,
+        url,
+        thumbnail_url,
+        expires_at_ms,
+    }
+}
+
 fn raw_record_id(value: &str) -> &str {
     value.split_once(':').map(|(_, id)| id).unwrap_or(value)
 }
@@ -9309,7 +10525,7 @@ async fn list_chat_threads(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
     Query(query): Query<ChatThreadsQuery>,
-) -> Result<Json<Vec<ChatThread>>, ApiError> {
+) -> Result<Json<Vec<ChatThreadListItem>>, ApiError> {
     let actor = actor_identity(&auth)?;
     let service = ChatService::new(request_repos::chat_repo(&state, &auth));
     let threads = if let Some(scope_id) = query.scope_id {
@@ -9317,15 +10533,72 @@ async fn list_chat_threads(
             .list_threads_by_scope(&actor, &scope_id)
             .await
             .map_err(map_domain_error)?
+            .into_iter()
+            .map(|thread| ChatThreadListItem {
+                thread,
+                member_count: None,
+                unread_count: None,
+            })
+            .collect()
     } else {
         service
-            .list_threads_by_user(&actor)
+            .list_threads_by_user_with_members(&actor)
             .await
             .map_err(map_domain_error)?
+            .into_iter()
+            .map(|item| ChatThreadListItem {
+                thread: item.thread,
+                member_count: Some(item.member_count),
+                unread_count: Some(item.unread_count),
+            })
+            .collect()
     };
     Ok(Json(threads))
 }
 
+async fn get_chat_unread_summary(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<ChatUnreadSummary>, ApiError> {
+    let actor = actor_identity(&auth)?;
+    let service = ChatService::new(request_repos::chat_repo(&state, &auth));
+    let summary = service
+        .unread_summary(&actor)
+        .await
+        .map_err(map_domain_error)?;
+    Ok(Json(summary))
+}
+
+async fn list_chat_message_read_receipts(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((thread_id, message_id)): Path<(String, String)>,
+) -> Result<Json<Vec<ChatReadReceipt>>, ApiError> {
+    let actor = actor_identity(&auth)?;
+    let service = ChatService::new(request_repos::chat_repo(&state, &auth));
+    let receipts = service
+        .list_read_receipts(&actor, &thread_id, &message_id)
+        .await
+        .map_err(map_domain_error)?;
+    Ok(Json(receipts))
+}
+
+async fn set_chat_thread_retention(
+    State(state): State<AppState>,
+    Path(thread_id): Path<String>,
+    Extension(auth): Extension<AuthContext>,
+    Json(payload): Json<SetChatThreadRetentionRequest>,
+) -> Result<Json<ChatThread>, ApiError> {
+    let actor = actor_identity(&auth)?;
+    let retention = ChatRetentionPolicy::parse(&payload.retention).map_err(map_domain_error)?;
+    let service = ChatService::new(request_repos::chat_repo(&state, &auth));
+    let thread = service
+        .set_thread_retention(&actor, &thread_id, retention)
+        .await
+        .map_err(map_domain_error)?;
+    Ok(Json(thread))
+}
+
 async fn join_chat_thread(
     State(state): State<AppState>,
     Path(thread_id): Path<String>,
@@ -9498,22 +10771,33 @@ async fn upload_chat_attachment(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
     mut multipart: Multipart,
-) -> Result<Json<ChatAttachmentUploadResponse>, ApiError> {
+) -> Result<Json<ChatAttachmentResponse>, ApiError> {
     let actor = actor_identity(&auth)?;
-    let mut uploaded_file: Option<(Vec<u8>, String, String)> = None;
+    let mut uploaded_file: Option<(Vec<u8>, String)> = None;
+    let mut thread_id: Option<String> = None;
 
     while let Some(field) = multipart
         .next_field()
         .await
         .map_err(|_| ApiError::Validation("invalid multipart payload".into()))?
     {
+        if field.name() == Some("thread_id") {
+            let value = field
+                .text()
+                .await
+                .map_err(|_| ApiError::Validation("invalid thread_id field".into()))?;
+            let value = value.trim();
+            if !value.is_empty() {
+                thread_id = Some(value.to_string());
+            }
+            continue;
+        }
+        if uploaded_file.is_some() {
+            continue;
+        }
         let Some(file_name) = field.file_name().map(str::to_string) else {
             continue;
         };
-        let mime_type = field
-            .content_type()
-            .map(str::to_string)
-            .ok_or_else(|| ApiError::Validation("attachment content_type is required".into()))?;
         let bytes = field
             .bytes()
             .await
@@ -9521,11 +10805,10 @@ async fn upload_chat_attachment(
         if bytes.is_empty() {
             continue;
         }
-        uploaded_file = Some((bytes.to_vec(), file_name, mime_type));
-        break;
+        uploaded_file = Some((bytes.to_vec(), file_name));
     }
 
-    let Some((file_bytes, raw_file_name, mime_type)) = uploaded_file else {
+    let Some((file_bytes, raw_file_name)) = uploaded_file else {
         return Err(ApiError::Validation(
             "multipart form file is required".into(),
         ));
@@ -9537,63 +10820,232 @@ async fn upload_chat_attachment(
         )));
     }
 
-    let Some(media_type) = infer_chat_media_type(&mime_type) else {
+    // The declared multipart content type is not trusted; the stored type comes from
+    // the file's own signature.
+    let Some(sniffed) = chat_media::sniff_chat_media(&file_bytes) else {
+        return Err(ApiError::Validation(
+            "unsupported attachment content; only image/video/audio files are allowed".into(),
+        ));
+    };
+
+    let (file_bytes, preview) = if sniffed.media_type == "image" {
+        let (file_bytes, preview) = prepare_chat_image(sniffed, file_bytes).await?;
+        (file_bytes, Some(preview))
+    } else {
+        (file_bytes, None)
+    };
+
+    let service = ChatService::new(request_repos::chat_repo(&state, &auth));
+    let pending = PendingChatAttachment {
+        attachment_id: gotong_domain::util::uuid_v7_without_dashes(),
+        thread_id,
+        raw_file_name,
+        sniffed,
+        size_bytes: file_bytes.len(),
+    };
+    let attachment = persist_chat_attachment(
+        &state,
+        &service,
+        &actor,
+        pending,
+        ChatAttachmentOriginal::Bytes(file_bytes),
+        preview,
+    )
+    .await?;
+
+    Ok(Json(chat_attachment_response(&state, attachment)))
+}
+
+fn chat_attachment_quota(state: &AppState) -> ChatAttachmentQuota {
+    ChatAttachmentQuota {
+        per_user_bytes: state.config.chat_attachment_user_quota_bytes,
+        per_thread_bytes: state.config.chat_attachment_thread_quota_bytes,
+    }
+}
+
+/// Strips GPS tags and renders the thumbnail off the async runtime. Images that do not
+/// decode are rejected rather than stored without a preview.
+async fn prepare_chat_image(
+    sniffed: chat_media::SniffedMedia,
+    mut file_bytes: Vec<u8>,
+) -> Result<(Vec<u8>, chat_media::ImagePreview), ApiError> {
+    let (file_bytes, preview) = tokio::task::spawn_blocking(move || {
+        chat_media::strip_exif_gps(sniffed.mime_type, &mut file_bytes);
+        let preview = chat_media::build_image_preview(sniffed.mime_type, &file_bytes);
+        (file_bytes, preview)
+    })
+    .await
+    .map_err(|_| ApiError::Internal)?;
+    let Some(preview) = preview else {
         return Err(ApiError::Validation(
-            "unsupported attachment mime type; only image/video/audio are allowed".into(),
+            "attachment image could not be decoded".into(),
         ));
     };
+    Ok((file_bytes, preview))
+}
 
-    let size_bytes = file_bytes.len();
-    let attachment_id = gotong_domain::util::uuid_v7_without_dashes();
-    let file_name = sanitize_chat_attachment_filename(&raw_file_name, media_type);
+/// Records the attachment, which charges the actor's and thread's quotas, then stores
+/// the original, thumbnail, and metadata. The record is dropped again if storage fails
+/// so the reserved quota is released.
+async fn persist_chat_attachment(
+    state: &AppState,
+    service: &ChatService,
+    actor: &ActorIdentity,
+    pending: PendingChatAttachment,
+    original: ChatAttachmentOriginal,
+    preview: Option<chat_media::ImagePreview>,
+) -> Result<ChatAttachment, ApiError> {
+    let PendingChatAttachment {
+        attachment_id,
+        thread_id,
+        raw_file_name,
+        sniffed,
+        size_bytes,
+    } = pending;
+    let thumbnail_size_bytes = preview.as_ref().map(|preview| preview.thumbnail.len());
+    let file_name = sanitize_chat_attachment_filename(&raw_file_name, sniffed.media_type);
     let created_at_ms = gotong_domain::jobs::now_ms();
+    let (width, height, thumbnail_bytes) = match preview {
+        Some(preview) => (
+            Some(preview.width),
+            Some(preview.height),
+            Some(preview.thumbnail),
+        ),
+        None => (None, None, None),
+    };
     let metadata = ChatAttachmentStoredMetadata {
         attachment_id: attachment_id.clone(),
         file_name: file_name.clone(),
-        mime_type: mime_type.clone(),
+        mime_type: sniffed.mime_type.to_string(),
         size_bytes,
-        media_type: media_type.to_string(),
-        uploaded_by: actor.user_id,
+        media_type: sniffed.media_type.to_string(),
+        uploaded_by: actor.user_id.clone(),
         created_at_ms,
+        thread_id: thread_id.clone(),
+        width,
+        height,
+        thumbnail_size_bytes,
     };
     let metadata_bytes = serde_json::to_vec(&metadata).map_err(|_| ApiError::Internal)?;
-    save_chat_attachment_artifacts(
-        &state,
+
+    let attachment = service
+        .record_attachment(
+            actor,
+            ChatAttachment {
+                attachment_id: attachment_id.clone(),
+                uploaded_by: actor.user_id.clone(),
+                thread_id,
+                file_name,
+                mime_type: sniffed.mime_type.to_string(),
+                media_type: sniffed.media_type.to_string(),
+                size_bytes: size_bytes as u64,
+                width,
+                height,
+                thumbnail_size_bytes: thumbnail_size_bytes.map(|bytes| bytes as u64),
+                created_at_ms,
+            },
+            &chat_attachment_quota(state),
+        )
+        .await
+        .map_err(map_domain_error)?;
+
+    if let Err(err) = save_chat_attachment_artifacts(
+        state,
         &attachment_id,
-        &mime_type,
-        file_bytes,
+        original,
+        thumbnail_bytes,
         metadata_bytes,
     )
-    .await?;
+    .await
+    {
+        if let Err(release_err) = service.release_attachment(actor, &attachment_id).await {
+            tracing::warn!(
+                attachment_id = %attachment_id,
+                error = %release_err,
+                "failed to release chat attachment after storage error"
+            );
+        }
+        return Err(err);
+    }
+    Ok(attachment)
+}
 
-    let expires_at_ms = created_at_ms + CHAT_ATTACHMENT_URL_TTL_MS;
-    let signature =
-        chat_attachment_signature(&state.config.jwt_secret, &attachment_id, expires_at_ms);
-    let url = format!(
-        "/v1/chat/attachments/{attachment_id}/download?exp={expires_at_ms}&sig={signature}"
-    );
+async fn get_chat_attachment(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(attachment_id): Path<String>,
+) -> Result<Json<ChatAttachmentResponse>, ApiError> {
+    let actor = actor_identity(&auth)?;
+    let service = ChatService::new(request_repos::chat_repo(&state, &auth));
+    let attachment = service
+        .get_attachment(&actor, &attachment_id)
+        .await
+        .map_err(map_domain_error)?;
+    Ok(Json(chat_attachment_response(&state, attachment)))
+}
 
-    Ok(Json(ChatAttachmentUploadResponse {
+async fn download_chat_attachment(
+    State(state): State<AppState>,
+    Path(attachment_id): Path<String>,
+    Query(query): Query<ChatAttachmentDownloadQuery>,
+) -> Result<Response, ApiError> {
+    serve_chat_attachment(
+        &state,
         attachment_id,
-        file_name,
-        mime_type,
-        size_bytes,
-        media_type: media_type.to_string(),
-        url,
-        expires_at_ms,
-    }))
+        query,
+        ChatAttachmentVariant::Original,
+    )
+    .await
 }
 
-async fn download_chat_attachment(
+async fn download_chat_attachment_thumbnail(
     State(state): State<AppState>,
     Path(attachment_id): Path<String>,
     Query(query): Query<ChatAttachmentDownloadQuery>,
 ) -> Result<Response, ApiError> {
-    if attachment_id.trim().is_empty()
-        || !attachment_id
+    serve_chat_attachment(
+        &state,
+        attachment_id,
+        query,
+        ChatAttachmentVariant::Thumbnail,
+    )
+    .await
+}
+
+/// Resolves the content type and file name served for one stored variant of an
+/// attachment. Attachments without a generated thumbnail have no thumbnail variant.
+fn chat_attachment_variant_headers(
+    metadata: ChatAttachmentStoredMetadata,
+    variant: ChatAttachmentVariant,
+) -> Result<(String, String), ApiError> {
+    match variant {
+        ChatAttachmentVariant::Original => Ok((metadata.mime_type, metadata.file_name)),
+        ChatAttachmentVariant::Thumbnail => {
+            if metadata.thumbnail_size_bytes.is_none() {
+                return Err(ApiError::NotFound);
+            }
+            Ok((
+                "image/jpeg".to_string(),
+                chat_attachment_thumbnail_file_name(&metadata.file_name),
+            ))
+        }
+    }
+}
+
+fn is_valid_chat_attachment_id(attachment_id: &str) -> bool {
+    !attachment_id.trim().is_empty()
+        && attachment_id
             .chars()
             .all(|character| character.is_ascii_alphanumeric() || character == '-')
-    {
+}
+
+async fn serve_chat_attachment(
+    state: &AppState,
+    attachment_id: String,
+    query: ChatAttachmentDownloadQuery,
+    variant: ChatAttachmentVariant,
+) -> Result<Response, ApiError> {
+    if !is_valid_chat_attachment_id(&attachment_id) {
         return Err(ApiError::Validation("invalid attachment_id".into()));
     }
 
@@ -9616,12 +11068,18 @@ async fn download_chat_attachment(
     match &state.chat_attachment_storage {
         ChatAttachmentStorage::Local { root } => {
             let metadata_path = chat_attachment_meta_path(root, &attachment_id);
-            let file_path = chat_attachment_file_path(root, &attachment_id);
             let metadata_bytes = tokio::fs::read(metadata_path)
                 .await
                 .map_err(|_| ApiError::NotFound)?;
             let metadata: ChatAttachmentStoredMetadata =
                 serde_json::from_slice(&metadata_bytes).map_err(|_| ApiError::Internal)?;
+            let (mime_type, file_name) = chat_attachment_variant_headers(metadata, variant)?;
+            let file_path = match variant {
+                ChatAttachmentVariant::Original => chat_attachment_file_path(root, &attachment_id),
+                ChatAttachmentVariant::Thumbnail => {
+                    chat_attachment_thumbnail_path(root, &attachment_id)
+                }
+            };
             let file_bytes = tokio::fs::read(file_path)
                 .await
                 .map_err(|_| ApiError::NotFound)?;
@@ -9629,7 +11087,7 @@ async fn download_chat_attachment(
             let mut response = (StatusCode::OK, file_bytes).into_response();
             response.headers_mut().insert(
                 CONTENT_TYPE,
-                HeaderValue::from_str(&metadata.mime_type)
+                HeaderValue::from_str(&mime_type)
                     .unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream")),
             );
             if let Ok(cache_control) =
@@ -9638,7 +11096,7 @@ async fn download_chat_attachment(
                 response.headers_mut().insert(CACHE_CONTROL, cache_control);
             }
             if let Ok(content_disposition) =
-                HeaderValue::from_str(&format!("inline; filename=\"{}\"", metadata.file_name))
+                HeaderValue::from_str(&format!("inline; filename=\"{file_name}\""))
             {
                 response
                     .headers_mut()
@@ -9673,7 +11131,16 @@ async fn download_chat_attachment(
                 .map_err(|_| ApiError::Internal)?;
             let metadata: ChatAttachmentStoredMetadata =
                 serde_json::from_slice(&metadata_bytes).map_err(|_| ApiError::Internal)?;
-            let file_key = chat_attachment_s3_file_key(key_prefix, &attachment_id);
+            let (response_content_type, file_name) =
+                chat_attachment_variant_headers(metadata, variant)?;
+            let file_key = match variant {
+                ChatAttachmentVariant::Original => {
+                    chat_attachment_s3_file_key(key_prefix, &attachment_id)
+                }
+                ChatAttachmentVariant::Thumbnail => {
+                    chat_attachment_s3_thumbnail_key(key_prefix, &attachment_id)
+                }
+            };
             let file_head_url = bucket
                 .head_object(Some(credentials), &file_key)
                 .sign(Duration::from_secs(60));
@@ -9693,9 +11160,7 @@ async fn download_chat_attachment(
                 .map(|seconds| seconds.min(7 * 24 * 60 * 60))
                 .unwrap_or(60);
             let mut signed_get = bucket.get_object(Some(credentials), &file_key);
-            let response_content_type = metadata.mime_type;
-            let response_content_disposition =
-                format!("inline; filename=\"{}\"", metadata.file_name);
+            let response_content_disposition = format!("inline; filename=\"{file_name}\"");
             signed_get
                 .query_mut()
                 .insert("response-content-type", response_content_type.as_str());
@@ -9811,6 +11276,10 @@ async fn mark_chat_read_cursor(
                 .mark_read(&actor, &thread_id, payload.message_id)
                 .await
                 .map_err(map_domain_error)?;
+            state
+                .chat_realtime
+                .publish_read_cursor(&thread_id, cursor.clone())
+                .await;
             let response = IdempotencyResponse {
                 status_code: StatusCode::OK.as_u16(),
                 body: serde_json::to_value(&cursor).map_err(|_| ApiError::Internal)?,
@@ -9894,8 +11363,21 @@ async fn stream_chat_messages_sse(
             tokio::select! {
                 event = receiver.recv() => {
                     match event {
-                        Ok(message) => {
-                            if !seen_messages.insert(message.message_id.clone()) {
+                        Ok(ChatRealtimeEvent::ReadCursor(cursor)) => {
+                            if assert_chat_stream_access(chat_repo.clone(), &thread_id, &actor_identity)
+                                .await
+                                .is_err()
+                            {
+                                let _ = sender.send(Ok(Event::default().event("closed").data("permission_lost")));
+                                break;
+                            }
+                            let _ = sender.send(Ok(chat_read_cursor_stream_event(cursor)));
+                        }
+                        Ok(ChatRealtimeEvent::Message(message)) => {
+                            // Tombstones reuse the id of a message the client already has.
+                            if !seen_messages.insert(message.message_id.clone())
+                                && message.deleted_at_ms.is_none()
+                            {
                                 continue;
                             }
                             if assert_chat_stream_access(chat_repo.clone(), &thread_id, &actor_identity)
@@ -9905,7 +11387,9 @@ async fn stream_chat_messages_sse(
                                 let _ = sender.send(Ok(Event::default().event("closed").data("permission_lost")));
                                 break;
                             }
-                            replay_cursor = Some((message.created_at_ms, message.message_id.clone()));
+                            if message.deleted_at_ms.is_none() {
+                                replay_cursor = Some((message.created_at_ms, message.message_id.clone()));
+                            }
                             let _ = sender.send(Ok(chat_message_stream_events(message)));
                         }
                         Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
@@ -9987,7 +11471,7 @@ async fn handle_chat_websocket(
     thread_id: String,
     actor: ActorIdentity,
     mut backlog: Vec<ChatMessage>,
-    mut receiver: tokio::sync::broadcast::Receiver<ChatMessage>,
+    mut receiver: tokio::sync::broadcast::Receiver<ChatRealtimeEvent>,
 ) {
     let (mut sender, mut incoming) = socket.split();
     let mut seen = HashSet::new();
@@ -10010,7 +11494,7 @@ async fn handle_chat_websocket(
         tokio::select! {
             event = receiver.recv() => {
                 match event {
-                    Ok(message) => {
+                    Ok(event) => {
                         if assert_chat_stream_access(chat_repo.clone(), &thread_id, &actor)
                             .await
                             .is_err()
@@ -10023,11 +11507,25 @@ async fn handle_chat_websocket(
                                 .await;
                             return;
                         }
-                        if !seen.insert(message.message_id.clone()) {
-                            continue;
-                        }
-                        replay_cursor = Some((message.created_at_ms, message.message_id.clone()));
-                        if sender.send(Message::Text(websocket_payload(&message))).await.is_err() {
+                        let payload = match event {
+                            ChatRealtimeEvent::ReadCursor(cursor) => {
+                                websocket_read_cursor_payload(&cursor)
+                            }
+                            ChatRealtimeEvent::Message(message) => {
+                                // Tombstones reuse the id of a message the client already has.
+                                if !seen.insert(message.message_id.clone())
+                                    && message.deleted_at_ms.is_none()
+                                {
+                                    continue;
+                                }
+                                if message.deleted_at_ms.is_none() {
+                                    replay_cursor =
+                                        Some((message.created_at_ms, message.message_id.clone()));
+                                }
+                                websocket_payload(&message)
+                            }
+                        };
+                        if sender.send(Message::Text(payload)).await.is_err() {
                             return;
                         }
                     }
@@ -10208,14 +11706,14 @@ fn into_adaptive_path_payload_draft(
     }
 }
 
-fn trusted_editor_roles(token_role: &gotong_domain::auth::Role) -> Vec<AdaptivePathEditorRole> {
-    match token_role {
-        gotong_domain::auth::Role::Admin
-        | gotong_domain::auth::Role::Moderator
-        | gotong_domain::auth::Role::System => vec![AdaptivePathEditorRole::ProjectManager],
-        gotong_domain::auth::Role::User => vec![AdaptivePathEditorRole::Author],
-        gotong_domain::auth::Role::Anonymous => Vec::new(),
-    }
+/// Editor roles come from group membership and Markov reputation around the
+/// plan's entity, never from the request.
+fn adaptive_path_service(state: &AppState, auth: &AuthContext) -> AdaptivePathService {
+    AdaptivePathService::new(request_repos::adaptive_path_repo(state, auth)).with_role_resolver(
+        AdaptivePathRoleResolver::new()
+            .with_groups(state.group_repo.clone())
+            .with_reputation(state.markov_client.clone()),
+    )
 }
 
 #[derive(Debug, Deserialize)]
@@ -10659,6 +12157,7 @@ fn map_domain_error(err: DomainError) -> ApiError {
         DomainError::NotFound => ApiError::NotFound,
         DomainError::Conflict => ApiError::Conflict,
         DomainError::Forbidden(_) => ApiError::Forbidden,
+        DomainError::Internal(_) => ApiError::Internal,
     }
 }
 
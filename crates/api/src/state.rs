@@ -1,28 +1,40 @@
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::future::Future;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::observability;
 use futures_util::StreamExt;
-use gotong_domain::chat::ChatMessage;
+use gotong_domain::chat::{ChatMessage, ChatReadCursor};
+use gotong_domain::discovery::DiscoveryEvent;
 use gotong_domain::idempotency::{IdempotencyConfig, IdempotencyService};
 use gotong_domain::ports::idempotency::IdempotencyStore;
 use gotong_domain::ports::{
     adaptive_path::AdaptivePathRepository,
     chat::ChatRepository,
     contributions::ContributionRepository,
-    discovery::{FeedRepository, NotificationRepository},
+    digest::DigestSubscriptionRepository,
+    discovery::{
+        DiscoveryEventPublisher, FeedPreferenceRepository, FeedRepository, FeedSearchIndex,
+        FeedSearchIndexQueue, NotificationRepository,
+    },
     evidence::EvidenceRepository,
     group::GroupRepository,
     jobs::JobQueue,
+    notification_preferences::NotificationPreferenceRepository,
     ontology::OntologyRepository,
+    push::PushSubscriptionRepository,
     siaga::SiagaRepository,
+    trending::TrendingSnapshotStore,
     vault::VaultRepository,
     vouches::VouchRepository,
     webhook::WebhookOutboxRepository,
 };
+use gotong_domain::trending::InMemoryTrendingSnapshotStore;
 use gotong_domain::util::uuid_v7_without_dashes;
 use gotong_infra::auth::SurrealAuthService;
+pub use gotong_infra::chat_attachment_storage::ChatAttachmentStorage;
+use gotong_infra::concept_label_index::{IndexedOntologyRepository, TrieConceptLabelIndex};
 use gotong_infra::config::AppConfig;
 use gotong_infra::db::DbConfig;
 use gotong_infra::idempotency::RedisIdempotencyStore;
@@ -30,23 +42,30 @@ use gotong_infra::jobs::RedisJobQueue;
 use gotong_infra::markov_client::MarkovReadClient;
 use gotong_infra::repositories::{
     InMemoryAdaptivePathRepository, InMemoryChatRepository, InMemoryContributionRepository,
-    InMemoryDiscoveryFeedRepository, InMemoryDiscoveryNotificationRepository,
-    InMemoryEvidenceRepository, InMemoryGroupRepository, InMemoryModerationRepository,
-    InMemoryOntologyRepository, InMemorySiagaRepository, InMemoryVaultRepository,
+    InMemoryDigestSubscriptionRepository, InMemoryDiscoveryFeedRepository,
+    InMemoryDiscoveryNotificationRepository, InMemoryEvidenceRepository,
+    InMemoryFeedPreferenceRepository, InMemoryGroupRepository, InMemoryModerationRepository,
+    InMemoryNotificationPreferenceRepository, InMemoryOntologyRepository,
+    InMemoryPushSubscriptionRepository, InMemorySiagaRepository, InMemoryVaultRepository,
     InMemoryVouchRepository, InMemoryWebhookOutboxRepository, SurrealAdaptivePathRepository,
-    SurrealChatRepository, SurrealContributionRepository, SurrealDiscoveryFeedRepository,
-    SurrealDiscoveryFeedRepositoryOptions, SurrealDiscoveryNotificationRepository,
-    SurrealEvidenceRepository, SurrealGroupRepository, SurrealModerationRepository,
-    SurrealOntologyRepository, SurrealSiagaRepository, SurrealVaultRepository,
+    SurrealChatRepository, SurrealContributionRepository, SurrealDigestSubscriptionRepository,
+    SurrealDiscoveryFeedRepository, SurrealDiscoveryFeedRepositoryOptions,
+    SurrealDiscoveryNotificationRepository, SurrealEvidenceRepository,
+    SurrealFeedPreferenceRepository, SurrealGroupRepository, SurrealModerationRepository,
+    SurrealNotificationPreferenceRepository, SurrealOntologyRepository,
+    SurrealPushSubscriptionRepository, SurrealSiagaRepository, SurrealVaultRepository,
     SurrealVouchRepository, SurrealWebhookOutboxRepository,
 };
+use gotong_infra::search_index::{
+    FeedSearchIndexer, IndexedFeedRepository, JobFeedSearchIndexQueue, TantivyFeedSearchIndex,
+};
+use gotong_infra::trending::RedisTrendingSnapshotStore;
 use redis::Client;
-use reqwest::Client as HttpClient;
-use rusty_s3::{Bucket as S3Bucket, Credentials as S3Credentials, S3Action, UrlStyle};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::sync::{RwLock, broadcast};
-use tracing::warn;
+use tracing::{info, warn};
 
 type RepositoryBundle = (
     Arc<dyn AdaptivePathRepository>,
@@ -59,12 +78,15 @@ type RepositoryBundle = (
     Arc<dyn OntologyRepository>,
     Arc<dyn SiagaRepository>,
     Arc<dyn FeedRepository>,
+    Arc<dyn FeedPreferenceRepository>,
     Arc<dyn NotificationRepository>,
     Arc<dyn WebhookOutboxRepository>,
     Arc<dyn GroupRepository>,
+    Arc<dyn DigestSubscriptionRepository>,
+    Arc<dyn NotificationPreferenceRepository>,
+    Arc<dyn PushSubscriptionRepository>,
 );
 type SharedJobQueue = Option<Arc<dyn JobQueue>>;
-const CHAT_ATTACHMENT_STORAGE_DIR: &str = "gotong-chat-attachments";
 
 #[derive(Clone)]
 pub struct AppState {
@@ -80,16 +102,25 @@ pub struct AppState {
     pub chat_repo: Arc<dyn ChatRepository>,
     pub moderation_repo: Arc<dyn gotong_domain::ports::moderation::ModerationRepository>,
     pub ontology_repo: Arc<dyn OntologyRepository>,
+    pub concept_label_index: Arc<TrieConceptLabelIndex>,
     #[allow(dead_code)]
     pub siaga_repo: Arc<dyn SiagaRepository>,
     pub feed_repo: Arc<dyn FeedRepository>,
+    pub feed_search_index: Option<Arc<dyn FeedSearchIndex>>,
+    pub feed_search_index_queue: Option<Arc<dyn FeedSearchIndexQueue>>,
+    pub feed_preference_repo: Arc<dyn FeedPreferenceRepository>,
     pub notification_repo: Arc<dyn NotificationRepository>,
     pub webhook_outbox_repo: Arc<dyn WebhookOutboxRepository>,
     pub group_repo: Arc<dyn GroupRepository>,
-    pub feed_monitor_preferences: Arc<RwLock<HashMap<String, bool>>>,
-    pub feed_follow_preferences: Arc<RwLock<HashMap<String, bool>>>,
+    pub digest_subscription_repo: Arc<dyn DigestSubscriptionRepository>,
+    pub notification_preference_repo: Arc<dyn NotificationPreferenceRepository>,
+    pub push_subscription_repo: Arc<dyn PushSubscriptionRepository>,
+    pub trending_store: Arc<dyn TrendingSnapshotStore>,
     pub chat_realtime: ChatRealtimeBus,
+    pub discovery_realtime: DiscoveryRealtimeBus,
     pub chat_attachment_storage: ChatAttachmentStorage,
+    /// tus uploads with a PATCH or DELETE in progress; see `TusUploadClaim`.
+    pub tus_uploads_in_flight: Arc<std::sync::Mutex<HashSet<String>>>,
     pub triage_sessions: Arc<RwLock<HashMap<String, TriageSessionState>>>,
     pub witness_signals: Arc<RwLock<HashMap<String, WitnessSignalState>>>,
     pub witness_stempel: Arc<RwLock<HashMap<String, WitnessStempelState>>>,
@@ -190,181 +221,139 @@ impl Default for WitnessImpactVerificationState {
     }
 }
 
+/// Cross-instance fan-out shared by the realtime buses. With
+/// `CHAT_REALTIME_TRANSPORT=redis`, payloads are published as JSON to
+/// `<prefix>:<topic>` and relayed to the matching bus on every other instance;
+/// with `local` the bridge is inert and events stay on this instance.
 #[derive(Clone)]
-pub enum ChatAttachmentStorage {
-    Local {
-        root: PathBuf,
-    },
-    S3 {
-        client: HttpClient,
-        bucket: S3Bucket,
-        credentials: S3Credentials,
-        key_prefix: String,
-    },
-}
-
-#[derive(Clone)]
-pub struct ChatRealtimeBus {
-    senders: Arc<RwLock<HashMap<String, broadcast::Sender<ChatMessage>>>>,
-    active_bridges: Arc<RwLock<HashSet<String>>>,
-    buffer_size: usize,
-    transport: ChatRealtimeTransport,
+struct RedisPubSubBridge {
+    client: Option<Client>,
+    channel_prefix: String,
     instance_id: String,
+    /// Names the bus in log lines.
+    label: &'static str,
+    /// Prepended to bridge metric event names so buses stay distinguishable.
+    metric_prefix: &'static str,
 }
 
-#[derive(Clone)]
-enum ChatRealtimeTransport {
-    Local,
-    Redis {
-        channel_prefix: String,
-        client: Option<Client>,
-    },
-}
-
-#[derive(Clone, Serialize, Deserialize)]
-struct ChatRealtimeEnvelope {
-    thread_id: String,
+/// Wire format: the bus payload's fields plus the publishing instance, so a
+/// bridge can skip its own events. The worker publishes chat envelopes in
+/// the same shape.
+#[derive(Serialize, Deserialize)]
+struct RedisBridgeEnvelope<T> {
     sender_id: String,
-    message: ChatMessage,
+    #[serde(flatten)]
+    payload: T,
 }
 
-impl ChatRealtimeBus {
-    pub fn new(config: &gotong_infra::config::AppConfig) -> Self {
+impl RedisPubSubBridge {
+    fn new(config: &AppConfig, label: &'static str, metric_prefix: &'static str) -> Self {
+        let mut bridge = Self {
+            client: None,
+            channel_prefix: config.chat_realtime_channel_prefix.clone(),
+            instance_id: uuid_v7_without_dashes(),
+            label,
+            metric_prefix,
+        };
         let transport = config.chat_realtime_transport.trim().to_ascii_lowercase();
-        let transport = transport.as_str();
-        let transport = match transport {
-            "local" => ChatRealtimeTransport::Local,
-            "redis" => {
-                let redis_url = config.redis_url.clone();
-                let client = Client::open(redis_url.clone()).ok();
-                if client.is_none() {
-                    observability::register_chat_realtime_bridge_event(
-                        "transport_init_fallback",
-                        "redis",
-                        "invalid_url",
-                    );
+        match transport.as_str() {
+            "local" => {}
+            "redis" => match Client::open(config.redis_url.clone()) {
+                Ok(client) => bridge.client = Some(client),
+                Err(_) => {
+                    bridge.record("transport_init_fallback", "redis", "invalid_url");
                     warn!(
-                        redis_url = %redis_url,
+                        bus = label,
+                        redis_url = %config.redis_url,
                         "invalid redis url for redis realtime transport; using local transport fallback"
                     );
-                    ChatRealtimeTransport::Local
-                } else {
-                    ChatRealtimeTransport::Redis {
-                        channel_prefix: config.chat_realtime_channel_prefix.clone(),
-                        client,
-                    }
                 }
-            }
+            },
             other => {
                 warn!(
+                    bus = label,
                     transport = %other,
                     "unsupported CHAT_REALTIME_TRANSPORT value; falling back to local transport"
                 );
-                observability::register_chat_realtime_bridge_event(
-                    "transport_init_fallback",
-                    "unsupported",
-                    other,
-                );
-                ChatRealtimeTransport::Local
+                bridge.record("transport_init_fallback", "unsupported", other);
             }
-        };
-
-        Self {
-            senders: Arc::new(RwLock::new(HashMap::new())),
-            buffer_size: 64,
-            active_bridges: Arc::new(RwLock::new(HashSet::new())),
-            transport,
-            instance_id: uuid_v7_without_dashes(),
         }
+        bridge
     }
 
-    async fn sender_for(&self, thread_id: &str) -> broadcast::Sender<ChatMessage> {
-        let mut senders = self.senders.write().await;
-        if let Some(sender) = senders.get(thread_id) {
-            return sender.clone();
-        }
-        let sender = broadcast::channel(self.buffer_size).0;
-        senders.insert(thread_id.to_string(), sender.clone());
-        sender
+    fn is_enabled(&self) -> bool {
+        self.client.is_some()
     }
 
-    fn channel_name(&self, thread_id: &str) -> Option<String> {
-        match &self.transport {
-            ChatRealtimeTransport::Redis { channel_prefix, .. } => {
-                Some(format!("{channel_prefix}:{thread_id}"))
-            }
-            ChatRealtimeTransport::Local => None,
-        }
+    fn record(&self, event: &str, transport: &str, reason: &str) {
+        observability::register_chat_realtime_bridge_event(
+            &format!("{}{event}", self.metric_prefix),
+            transport,
+            reason,
+        );
     }
 
-    fn channel_redis_client(&self) -> Option<Client> {
-        match &self.transport {
-            ChatRealtimeTransport::Redis { client, .. } => client.clone(),
-            ChatRealtimeTransport::Local => None,
-        }
+    fn channel(&self, topic: &str) -> String {
+        format!("{}:{topic}", self.channel_prefix)
     }
 
-    async fn publish_to_redis(&self, envelope: &ChatRealtimeEnvelope) {
-        let Some(redis_conn) = self.channel_redis_client() else {
-            return;
-        };
-        let Some(channel) = self.channel_name(&envelope.thread_id) else {
+    /// Publishes in the background; failures are logged and counted, never
+    /// surfaced, since local subscribers already have the event.
+    fn publish<T>(&self, topic: &str, payload: T)
+    where
+        T: Serialize + Send + 'static,
+    {
+        let Some(client) = self.client.clone() else {
             return;
         };
-
-        let serialized = match serde_json::to_string(envelope) {
-            Ok(value) => value,
-            Err(err) => {
-                warn!(error = %err, "chat realtime envelope serialization failed");
-                return;
-            }
+        let channel = self.channel(topic);
+        let envelope = RedisBridgeEnvelope {
+            sender_id: self.instance_id.clone(),
+            payload,
         };
-
-        let mut redis_conn = match redis_conn.get_multiplexed_async_connection().await {
-            Ok(connection) => connection,
-            Err(err) => {
-                observability::register_chat_realtime_bridge_event(
-                    "publish_connection_failed",
-                    "redis",
-                    "connection",
-                );
-                warn!(error = %err, "chat realtime redis connection failed");
-                return;
+        let bridge = self.clone();
+        tokio::spawn(async move {
+            let serialized = match serde_json::to_string(&envelope) {
+                Ok(value) => value,
+                Err(err) => {
+                    warn!(bus = bridge.label, error = %err, "realtime envelope serialization failed");
+                    return;
+                }
+            };
+            let mut redis_conn = match client.get_multiplexed_async_connection().await {
+                Ok(connection) => connection,
+                Err(err) => {
+                    bridge.record("publish_connection_failed", "redis", "connection");
+                    warn!(bus = bridge.label, error = %err, "realtime redis connection failed");
+                    return;
+                }
+            };
+            if let Err(err) = redis::cmd("PUBLISH")
+                .arg(channel)
+                .arg(serialized)
+                .query_async::<_, i64>(&mut redis_conn)
+                .await
+            {
+                bridge.record("publish_command_failed", "redis", "publish");
+                warn!(bus = bridge.label, error = %err, "realtime redis publish failed");
             }
-        };
-
-        if let Err(err) = redis::cmd("PUBLISH")
-            .arg(channel)
-            .arg(serialized)
-            .query_async::<_, i64>(&mut redis_conn)
-            .await
-        {
-            observability::register_chat_realtime_bridge_event(
-                "publish_command_failed",
-                "redis",
-                "publish",
-            );
-            warn!(error = %err, "chat realtime redis publish failed");
-        }
+        });
     }
 
-    async fn spawn_redis_bridge(&self, thread_id: String) {
-        let (channel, client) = match &self.transport {
-            ChatRealtimeTransport::Redis {
-                client,
-                channel_prefix,
-                ..
-            } if client.is_some() => {
-                let Some(url_client) = client.clone() else {
-                    return;
-                };
-                (format!("{channel_prefix}:{thread_id}"), url_client)
-            }
-            _ => return,
+    /// Subscribes to `<prefix>:<topic>` in the background, reconnecting with
+    /// backoff, and hands every payload published by another instance to
+    /// `deliver`.
+    fn spawn_subscriber<T, F, Fut>(&self, topic: &str, deliver: F)
+    where
+        T: DeserializeOwned + Send + 'static,
+        F: Fn(T) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        let Some(client) = self.client.clone() else {
+            return;
         };
-
-        let sender_map = self.senders.clone();
-        let local_instance = self.instance_id.clone();
+        let channel = self.channel(topic);
+        let bridge = self.clone();
         tokio::spawn(async move {
             use tokio::time::{Duration, sleep};
 
@@ -375,104 +364,115 @@ impl ChatRealtimeBus {
                 let mut pubsub = match client.clone().get_async_pubsub().await {
                     Ok(pubsub) => pubsub,
                     Err(err) => {
-                        observability::register_chat_realtime_bridge_event(
-                            "subscription_connect_failed",
-                            "redis",
-                            "connect",
-                        );
-                        warn!(error = %err, "chat realtime redis subscription failed");
+                        bridge.record("subscription_connect_failed", "redis", "connect");
+                        warn!(bus = bridge.label, error = %err, "realtime redis subscription failed");
                         sleep(Duration::from_millis(backoff_ms)).await;
                         backoff_ms = (backoff_ms * 2).min(max_backoff_ms);
                         continue;
                     }
                 };
-                backoff_ms = 250_u64;
                 if let Err(err) = pubsub.subscribe(channel.clone()).await {
-                    observability::register_chat_realtime_bridge_event(
-                        "subscription_subscribe_failed",
-                        "redis",
-                        "subscribe",
-                    );
-                    warn!(error = %err, "chat realtime redis channel subscribe failed");
+                    bridge.record("subscription_subscribe_failed", "redis", "subscribe");
+                    warn!(bus = bridge.label, error = %err, "realtime redis channel subscribe failed");
                     sleep(Duration::from_millis(backoff_ms)).await;
                     backoff_ms = (backoff_ms * 2).min(max_backoff_ms);
                     continue;
                 }
+                backoff_ms = 250_u64;
 
                 let mut stream = pubsub.on_message();
-                loop {
-                    match stream.next().await {
-                        Some(message) => {
-                            let payload: String = match message.get_payload() {
-                                Ok(payload) => payload,
-                                Err(err) => {
-                                    observability::register_chat_realtime_bridge_event(
-                                        "message_payload_decode_failed",
-                                        "redis",
-                                        "decode",
-                                    );
-                                    warn!(
-                                        error = %err,
-                                        "chat realtime redis payload decode failed"
-                                    );
-                                    continue;
-                                }
-                            };
-
-                            let envelope: ChatRealtimeEnvelope =
-                                match serde_json::from_str(&payload) {
-                                    Ok(envelope) => envelope,
-                                    Err(err) => {
-                                        observability::register_chat_realtime_bridge_event(
-                                            "message_payload_parse_failed",
-                                            "redis",
-                                            "parse",
-                                        );
-                                        warn!(error = %err, "chat realtime envelope parse failed");
-                                        continue;
-                                    }
-                                };
-
-                            if envelope.sender_id == local_instance {
-                                continue;
-                            }
-
-                            let sender = {
-                                let senders = sender_map.read().await;
-                                senders.get(&envelope.thread_id).cloned()
-                            };
-                            let Some(sender) = sender else {
-                                continue;
-                            };
-                            if sender.send(envelope.message).is_err() {
-                                warn!(
-                                    "chat realtime broadcast failed for thread {}",
-                                    envelope.thread_id
-                                );
-                            }
+                while let Some(message) = stream.next().await {
+                    let payload: String = match message.get_payload() {
+                        Ok(payload) => payload,
+                        Err(err) => {
+                            bridge.record("message_payload_decode_failed", "redis", "decode");
+                            warn!(bus = bridge.label, error = %err, "realtime redis payload decode failed");
+                            continue;
                         }
-                        None => {
-                            observability::register_chat_realtime_bridge_event(
-                                "stream_ended",
-                                "redis",
-                                "reconnect",
-                            );
-                            warn!("chat realtime redis stream ended");
-                            break;
+                    };
+                    let envelope: RedisBridgeEnvelope<T> = match serde_json::from_str(&payload) {
+                        Ok(envelope) => envelope,
+                        Err(err) => {
+                            bridge.record("message_payload_parse_failed", "redis", "parse");
+                            warn!(bus = bridge.label, error = %err, "realtime envelope parse failed");
+                            continue;
                         }
+                    };
+                    if envelope.sender_id == bridge.instance_id {
+                        continue;
                     }
+                    deliver(envelope.payload).await;
                 }
-                observability::register_chat_realtime_bridge_event(
-                    "stream_reconnect_backoff",
-                    "redis",
-                    "reconnect",
+                bridge.record("stream_reconnect_backoff", "redis", "reconnect");
+                warn!(
+                    bus = bridge.label,
+                    "realtime redis stream ended; reconnecting"
                 );
-                warn!("chat realtime redis stream ended; reconnecting");
                 sleep(Duration::from_millis(backoff_ms)).await;
                 backoff_ms = (backoff_ms * 2).min(max_backoff_ms);
             }
         });
     }
+}
+
+/// What a thread's realtime subscribers receive: new or changed messages, and
+/// members' read cursors moving (for "seen by" and unread badges).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChatRealtimeEvent {
+    Message(ChatMessage),
+    ReadCursor(ChatReadCursor),
+}
+
+/// Per-thread realtime fan-out. With `redis`, each instance subscribes to
+/// `<prefix>:<thread_id>` for the threads it has local subscribers on.
+#[derive(Clone)]
+pub struct ChatRealtimeBus {
+    senders: Arc<RwLock<HashMap<String, broadcast::Sender<ChatRealtimeEvent>>>>,
+    active_bridges: Arc<RwLock<HashSet<String>>>,
+    buffer_size: usize,
+    bridge: RedisPubSubBridge,
+}
+
+/// Cross-instance payload. Exactly one of `message` / `read_cursor` is set; the
+/// worker publishes message envelopes too, so both stay optional on the wire.
+#[derive(Clone, Serialize, Deserialize)]
+struct ChatRealtimeEnvelope {
+    thread_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    message: Option<ChatMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    read_cursor: Option<ChatReadCursor>,
+}
+
+impl ChatRealtimeEnvelope {
+    fn into_event(self) -> Option<ChatRealtimeEvent> {
+        match (self.message, self.read_cursor) {
+            (Some(message), _) => Some(ChatRealtimeEvent::Message(message)),
+            (None, Some(cursor)) => Some(ChatRealtimeEvent::ReadCursor(cursor)),
+            (None, None) => None,
+        }
+    }
+}
+
+impl ChatRealtimeBus {
+    pub fn new(config: &gotong_infra::config::AppConfig) -> Self {
+        Self {
+            senders: Arc::new(RwLock::new(HashMap::new())),
+            buffer_size: 64,
+            active_bridges: Arc::new(RwLock::new(HashSet::new())),
+            bridge: RedisPubSubBridge::new(config, "chat", ""),
+        }
+    }
+
+    async fn sender_for(&self, thread_id: &str) -> broadcast::Sender<ChatRealtimeEvent> {
+        let mut senders = self.senders.write().await;
+        if let Some(sender) = senders.get(thread_id) {
+            return sender.clone();
+        }
+        let sender = broadcast::channel(self.buffer_size).0;
+        senders.insert(thread_id.to_string(), sender.clone());
+        sender
+    }
 
     async fn ensure_redis_bridge(&self, thread_id: &str) {
         let mut active = self.active_bridges.write().await;
@@ -480,42 +480,189 @@ impl ChatRealtimeBus {
             return;
         }
         drop(active);
-        self.spawn_redis_bridge(thread_id.to_string()).await;
+
+        let senders = self.senders.clone();
+        self.bridge
+            .spawn_subscriber(thread_id, move |envelope: ChatRealtimeEnvelope| {
+                let senders = senders.clone();
+                async move {
+                    let sender = senders.read().await.get(&envelope.thread_id).cloned();
+                    let Some(sender) = sender else {
+                        return;
+                    };
+                    let thread_id = envelope.thread_id.clone();
+                    let Some(event) = envelope.into_event() else {
+                        return;
+                    };
+                    if sender.send(event).is_err() {
+                        warn!("chat realtime broadcast failed for thread {}", thread_id);
+                    }
+                }
+            });
     }
 
     pub async fn publish(&self, thread_id: &str, message: ChatMessage) {
-        let message_for_redis = message.clone();
+        self.publish_event(thread_id, ChatRealtimeEvent::Message(message))
+            .await;
+    }
+
+    pub async fn publish_read_cursor(&self, thread_id: &str, cursor: ChatReadCursor) {
+        self.publish_event(thread_id, ChatRealtimeEvent::ReadCursor(cursor))
+            .await;
+    }
+
+    async fn publish_event(&self, thread_id: &str, event: ChatRealtimeEvent) {
+        let event_for_redis = event.clone();
         let sender = self.sender_for(thread_id).await;
-        if sender.send(message).is_err() {
+        if sender.send(event).is_err() {
             let mut senders = self.senders.write().await;
             senders.remove(thread_id);
             let mut active_bridges = self.active_bridges.write().await;
             active_bridges.remove(thread_id);
         }
 
-        let envelope = ChatRealtimeEnvelope {
-            thread_id: thread_id.to_string(),
-            sender_id: self.instance_id.clone(),
-            message: message_for_redis,
+        let (message, read_cursor) = match event_for_redis {
+            ChatRealtimeEvent::Message(message) => (Some(message), None),
+            ChatRealtimeEvent::ReadCursor(cursor) => (None, Some(cursor)),
         };
+        self.bridge.publish(
+            thread_id,
+            ChatRealtimeEnvelope {
+                thread_id: thread_id.to_string(),
+                message,
+                read_cursor,
+            },
+        );
+    }
+
+    pub async fn subscribe(&self, thread_id: &str) -> broadcast::Receiver<ChatRealtimeEvent> {
+        if self.bridge.is_enabled() {
+            self.ensure_redis_bridge(thread_id).await;
+        }
+        self.sender_for(thread_id).await.subscribe()
+    }
+}
+
+/// Per-user realtime fan-out for notifications, unread counts and feed items.
+/// It shares the chat transport settings: with `redis`, every instance
+/// subscribes to one `<prefix>:discovery` channel and relays remote events to
+/// its local streams.
+#[derive(Clone)]
+pub struct DiscoveryRealtimeBus {
+    users: Arc<RwLock<HashMap<String, broadcast::Sender<DiscoveryEvent>>>>,
+    feed: broadcast::Sender<DiscoveryEvent>,
+    bridge_started: Arc<AtomicBool>,
+    buffer_size: usize,
+    bridge: RedisPubSubBridge,
+}
 
-        match &self.transport {
-            ChatRealtimeTransport::Redis { .. } => {
-                let bus = self.clone();
-                let envelope = envelope.clone();
-                tokio::spawn(async move {
-                    bus.publish_to_redis(&envelope).await;
-                });
+const DISCOVERY_REALTIME_TOPIC: &str = "discovery";
+
+#[derive(Clone, Serialize, Deserialize)]
+struct DiscoveryRealtimeEnvelope {
+    event: DiscoveryEvent,
+}
+
+/// One actor's stream: their own notifications and unread counts, plus the
+/// feed items they may see.
+pub struct DiscoveryRealtimeSubscription {
+    actor_id: String,
+    user: broadcast::Receiver<DiscoveryEvent>,
+    feed: broadcast::Receiver<DiscoveryEvent>,
+}
+
+impl DiscoveryRealtimeSubscription {
+    /// Next event visible to the actor. `Lagged` means events were dropped and
+    /// the client should refetch.
+    pub async fn recv(&mut self) -> Result<DiscoveryEvent, broadcast::error::RecvError> {
+        loop {
+            let event = tokio::select! {
+                event = self.user.recv() => event?,
+                event = self.feed.recv() => event?,
+            };
+            if event.is_visible_to(&self.actor_id) {
+                return Ok(event);
             }
-            ChatRealtimeTransport::Local => {}
         }
     }
+}
 
-    pub async fn subscribe(&self, thread_id: &str) -> broadcast::Receiver<ChatMessage> {
-        if matches!(self.transport, ChatRealtimeTransport::Redis { .. }) {
-            self.ensure_redis_bridge(thread_id).await;
+impl DiscoveryRealtimeBus {
+    pub fn new(config: &gotong_infra::config::AppConfig) -> Self {
+        let buffer_size = 256;
+        Self {
+            users: Arc::new(RwLock::new(HashMap::new())),
+            feed: broadcast::channel(buffer_size).0,
+            bridge_started: Arc::new(AtomicBool::new(false)),
+            buffer_size,
+            bridge: RedisPubSubBridge::new(config, "discovery", "discovery_"),
+        }
+    }
+
+    pub async fn subscribe(&self, actor_id: &str) -> DiscoveryRealtimeSubscription {
+        if self.bridge.is_enabled() && !self.bridge_started.swap(true, Ordering::SeqCst) {
+            let users = self.users.clone();
+            let feed = self.feed.clone();
+            self.bridge.spawn_subscriber(
+                DISCOVERY_REALTIME_TOPIC,
+                move |envelope: DiscoveryRealtimeEnvelope| {
+                    let users = users.clone();
+                    let feed = feed.clone();
+                    async move { Self::deliver_local(&users, &feed, envelope.event).await }
+                },
+            );
+        }
+        let user = {
+            let mut users = self.users.write().await;
+            users
+                .entry(actor_id.to_string())
+                .or_insert_with(|| broadcast::channel(self.buffer_size).0)
+                .subscribe()
+        };
+        DiscoveryRealtimeSubscription {
+            actor_id: actor_id.to_string(),
+            user,
+            feed: self.feed.subscribe(),
+        }
+    }
+
+    async fn deliver_local(
+        users: &RwLock<HashMap<String, broadcast::Sender<DiscoveryEvent>>>,
+        feed: &broadcast::Sender<DiscoveryEvent>,
+        event: DiscoveryEvent,
+    ) {
+        let Some(user_id) = event.recipient_id().map(str::to_string) else {
+            // No subscribers is not an error for feed fan-out.
+            let _ = feed.send(event);
+            return;
+        };
+        let sender = users.read().await.get(&user_id).cloned();
+        let Some(sender) = sender else {
+            return;
+        };
+        if sender.send(event).is_err() {
+            let mut users = users.write().await;
+            if users
+                .get(&user_id)
+                .is_some_and(|sender| sender.receiver_count() == 0)
+            {
+                users.remove(&user_id);
+            }
         }
-        self.sender_for(thread_id).await.subscribe()
+    }
+
+    async fn publish_event(&self, event: DiscoveryEvent) {
+        Self::deliver_local(&self.users, &self.feed, event.clone()).await;
+        self.bridge.publish(
+            DISCOVERY_REALTIME_TOPIC,
+            DiscoveryRealtimeEnvelope { event },
+        );
+    }
+}
+
+impl DiscoveryEventPublisher for DiscoveryRealtimeBus {
+    fn publish(&self, event: DiscoveryEvent) -> gotong_domain::ports::BoxFuture<'_, ()> {
+        Box::pin(self.publish_event(event))
     }
 }
 
@@ -540,21 +687,35 @@ impl AppState {
             ontology_repo,
             siaga_repo,
             feed_repo,
+            feed_preference_repo,
             notification_repo,
             webhook_outbox_repo,
             group_repo,
+            digest_subscription_repo,
+            notification_preference_repo,
+            push_subscription_repo,
         ) = repositories_for_config(&config).await?;
         let job_queue = job_queue_for_config(&config).await?;
+        let (feed_search_index, feed_search_index_queue) =
+            feed_search_index_for_config(&config, &feed_repo, job_queue.as_ref())?.unzip();
+        let feed_repo = indexed_feed_repo(feed_repo, feed_search_index_queue.as_ref());
+        let concept_label_index = Arc::new(TrieConceptLabelIndex::new());
+        let ontology_repo = indexed_ontology_repo(ontology_repo, &concept_label_index);
+        spawn_concept_label_refresh(
+            concept_label_index.clone(),
+            ontology_repo.clone(),
+            config.ontology_autocomplete_refresh_ms,
+        );
+        let trending_store = trending_store_for_config(&config).await?;
         let idempotency = IdempotencyService::new(Arc::new(store), IdempotencyConfig::default());
         let chat_realtime = ChatRealtimeBus::new(&config);
-        let chat_attachment_storage = chat_attachment_storage_for_config(&config).await?;
+        let discovery_realtime = DiscoveryRealtimeBus::new(&config);
+        let chat_attachment_storage = ChatAttachmentStorage::from_config(&config).await?;
         let markov_client = Arc::new(MarkovReadClient::from_config(&config));
         let triage_sessions = Arc::new(RwLock::new(HashMap::new()));
         let witness_signals = Arc::new(RwLock::new(HashMap::new()));
         let witness_stempel = Arc::new(RwLock::new(HashMap::new()));
         let witness_impact_verifications = Arc::new(RwLock::new(HashMap::new()));
-        let feed_monitor_preferences = Arc::new(RwLock::new(HashMap::new()));
-        let feed_follow_preferences = Arc::new(RwLock::new(HashMap::new()));
         Ok(Self {
             config,
             markov_client,
@@ -568,15 +729,23 @@ impl AppState {
             chat_repo,
             moderation_repo,
             ontology_repo,
+            concept_label_index,
             siaga_repo,
             feed_repo,
+            feed_search_index,
+            feed_search_index_queue,
+            feed_preference_repo,
             notification_repo,
             webhook_outbox_repo,
             group_repo,
-            feed_monitor_preferences,
-            feed_follow_preferences,
+            digest_subscription_repo,
+            notification_preference_repo,
+            push_subscription_repo,
+            trending_store,
             chat_realtime,
+            discovery_realtime,
             chat_attachment_storage,
+            tus_uploads_in_flight: Arc::default(),
             triage_sessions,
             witness_signals,
             witness_stempel,
@@ -598,19 +767,24 @@ impl AppState {
             ontology_repo,
             siaga_repo,
             feed_repo,
+            feed_preference_repo,
             notification_repo,
             webhook_outbox_repo,
             group_repo,
+            digest_subscription_repo,
+            notification_preference_repo,
+            push_subscription_repo,
         ) = memory_repositories();
+        let concept_label_index = Arc::new(TrieConceptLabelIndex::new());
+        let ontology_repo = indexed_ontology_repo(ontology_repo, &concept_label_index);
         let chat_realtime = ChatRealtimeBus::new(&config);
-        let chat_attachment_storage = chat_attachment_local_storage(&config);
+        let discovery_realtime = DiscoveryRealtimeBus::new(&config);
+        let chat_attachment_storage = ChatAttachmentStorage::local(&config);
         let markov_client = Arc::new(MarkovReadClient::from_config(&config));
         let triage_sessions = Arc::new(RwLock::new(HashMap::new()));
         let witness_signals = Arc::new(RwLock::new(HashMap::new()));
         let witness_stempel = Arc::new(RwLock::new(HashMap::new()));
         let witness_impact_verifications = Arc::new(RwLock::new(HashMap::new()));
-        let feed_monitor_preferences = Arc::new(RwLock::new(HashMap::new()));
-        let feed_follow_preferences = Arc::new(RwLock::new(HashMap::new()));
         Self {
             config,
             markov_client,
@@ -624,15 +798,23 @@ impl AppState {
             chat_repo,
             moderation_repo,
             ontology_repo,
+            concept_label_index,
             siaga_repo,
             feed_repo,
+            feed_search_index: None,
+            feed_search_index_queue: None,
+            feed_preference_repo,
             notification_repo,
             webhook_outbox_repo,
             group_repo,
-            feed_monitor_preferences,
-            feed_follow_preferences,
+            digest_subscription_repo,
+            notification_preference_repo,
+            push_subscription_repo,
+            trending_store: Arc::new(InMemoryTrendingSnapshotStore::new()),
             chat_realtime,
+            discovery_realtime,
             chat_attachment_storage,
+            tus_uploads_in_flight: Arc::default(),
             triage_sessions,
             witness_signals,
             witness_stempel,
@@ -656,20 +838,25 @@ impl AppState {
         ontology_repo: Arc<dyn OntologyRepository>,
         siaga_repo: Arc<dyn SiagaRepository>,
         feed_repo: Arc<dyn FeedRepository>,
+        feed_preference_repo: Arc<dyn FeedPreferenceRepository>,
         notification_repo: Arc<dyn NotificationRepository>,
         webhook_outbox_repo: Arc<dyn WebhookOutboxRepository>,
         group_repo: Arc<dyn GroupRepository>,
+        digest_subscription_repo: Arc<dyn DigestSubscriptionRepository>,
+        notification_preference_repo: Arc<dyn NotificationPreferenceRepository>,
+        push_subscription_repo: Arc<dyn PushSubscriptionRepository>,
     ) -> Self {
         let idempotency = IdempotencyService::new(store, IdempotencyConfig::default());
+        let concept_label_index = Arc::new(TrieConceptLabelIndex::new());
+        let ontology_repo = indexed_ontology_repo(ontology_repo, &concept_label_index);
         let chat_realtime = ChatRealtimeBus::new(&config);
-        let chat_attachment_storage = chat_attachment_local_storage(&config);
+        let discovery_realtime = DiscoveryRealtimeBus::new(&config);
+        let chat_attachment_storage = ChatAttachmentStorage::local(&config);
         let markov_client = Arc::new(MarkovReadClient::from_config(&config));
         let triage_sessions = Arc::new(RwLock::new(HashMap::new()));
         let witness_signals = Arc::new(RwLock::new(HashMap::new()));
         let witness_stempel = Arc::new(RwLock::new(HashMap::new()));
         let witness_impact_verifications = Arc::new(RwLock::new(HashMap::new()));
-        let feed_monitor_preferences = Arc::new(RwLock::new(HashMap::new()));
-        let feed_follow_preferences = Arc::new(RwLock::new(HashMap::new()));
         Self {
             config,
             markov_client,
@@ -683,15 +870,23 @@ impl AppState {
             chat_repo,
             moderation_repo,
             ontology_repo,
+            concept_label_index,
             siaga_repo,
             feed_repo,
+            feed_search_index: None,
+            feed_search_index_queue: None,
+            feed_preference_repo,
             notification_repo,
             webhook_outbox_repo,
             group_repo,
-            feed_monitor_preferences,
-            feed_follow_preferences,
+            digest_subscription_repo,
+            notification_preference_repo,
+            push_subscription_repo,
+            trending_store: Arc::new(InMemoryTrendingSnapshotStore::new()),
             chat_realtime,
+            discovery_realtime,
             chat_attachment_storage,
+            tus_uploads_in_flight: Arc::default(),
             triage_sessions,
             witness_signals,
             witness_stempel,
@@ -699,104 +894,82 @@ impl AppState {
             job_queue: None,
         }
     }
-}
 
-fn chat_attachment_local_storage(config: &AppConfig) -> ChatAttachmentStorage {
-    ChatAttachmentStorage::Local {
-        root: std::env::temp_dir()
-            .join(CHAT_ATTACHMENT_STORAGE_DIR)
-            .join(config.app_env.trim().to_ascii_lowercase()),
+    #[allow(dead_code)]
+    pub fn with_feed_search_index(
+        mut self,
+        index: Arc<dyn FeedSearchIndex>,
+        queue: Arc<dyn FeedSearchIndexQueue>,
+    ) -> Self {
+        self.feed_repo = indexed_feed_repo(self.feed_repo, Some(&queue));
+        self.feed_search_index = Some(index);
+        self.feed_search_index_queue = Some(queue);
+        self
     }
 }
 
-fn normalized_chat_attachment_prefix(config: &AppConfig) -> String {
-    let base_prefix = config.chat_attachment_s3_prefix.trim().trim_matches('/');
-    let base_prefix = if base_prefix.is_empty() {
-        "chat-attachments"
-    } else {
-        base_prefix
-    };
-    format!(
-        "{base_prefix}/{}",
-        config.app_env.trim().to_ascii_lowercase()
-    )
-}
+type FeedSearchIndexHandles = (Arc<dyn FeedSearchIndex>, Arc<dyn FeedSearchIndexQueue>);
 
-async fn build_chat_attachment_s3_storage(
+/// Opens the index for searching. With a job queue the worker owns the index
+/// writer and feed writes reach it as jobs; without one an in-process indexer
+/// writes instead.
+fn feed_search_index_for_config(
     config: &AppConfig,
-) -> anyhow::Result<ChatAttachmentStorage> {
-    let bucket_name = config.s3_bucket.trim();
-    if bucket_name.is_empty() {
-        anyhow::bail!("s3_bucket is empty");
-    }
-    if config.s3_endpoint.trim().is_empty() {
-        anyhow::bail!("s3_endpoint is empty");
+    feed_repo: &Arc<dyn FeedRepository>,
+    job_queue: Option<&Arc<dyn JobQueue>>,
+) -> anyhow::Result<Option<FeedSearchIndexHandles>> {
+    let dir = config.discovery_search_index_dir.trim();
+    if dir.is_empty() {
+        return Ok(None);
     }
+    let index = TantivyFeedSearchIndex::open(dir)
+        .map_err(|err| anyhow::anyhow!("failed to open discovery search index at {dir}: {err}"))?;
+    let queue: Arc<dyn FeedSearchIndexQueue> = match job_queue {
+        Some(job_queue) => Arc::new(JobFeedSearchIndexQueue::new(job_queue.clone())),
+        None => Arc::new(FeedSearchIndexer::spawn(index.clone(), feed_repo.clone())),
+    };
+    Ok(Some((Arc::new(index), queue)))
+}
 
-    let endpoint = config
-        .s3_endpoint
-        .trim()
-        .parse::<reqwest::Url>()
-        .map_err(|err| anyhow::anyhow!("invalid s3_endpoint '{}': {err}", config.s3_endpoint))?;
-    let bucket = S3Bucket::new(
-        endpoint,
-        UrlStyle::Path,
-        bucket_name.to_string(),
-        config.s3_region.clone(),
-    )
-    .map_err(|err| anyhow::anyhow!("invalid S3 bucket configuration: {err}"))?;
-    let credentials =
-        S3Credentials::new(config.s3_access_key.clone(), config.s3_secret_key.clone());
-    let client = HttpClient::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()?;
-    let head_bucket_url = bucket
-        .head_bucket(Some(&credentials))
-        .sign(std::time::Duration::from_secs(30));
-    let head_bucket_response = client.head(head_bucket_url).send().await?;
-    if !head_bucket_response.status().is_success() {
-        anyhow::bail!(
-            "S3 head bucket failed with status {}",
-            head_bucket_response.status()
-        );
+pub(crate) fn indexed_feed_repo(
+    feed_repo: Arc<dyn FeedRepository>,
+    feed_search_index_queue: Option<&Arc<dyn FeedSearchIndexQueue>>,
+) -> Arc<dyn FeedRepository> {
+    match feed_search_index_queue {
+        Some(queue) => Arc::new(IndexedFeedRepository::new(feed_repo, queue.clone())),
+        None => feed_repo,
     }
-
-    Ok(ChatAttachmentStorage::S3 {
-        client,
-        bucket,
-        credentials,
-        key_prefix: normalized_chat_attachment_prefix(config),
-    })
 }
 
-async fn chat_attachment_storage_for_config(
-    config: &AppConfig,
-) -> anyhow::Result<ChatAttachmentStorage> {
-    let requested_backend = config
-        .chat_attachment_storage_backend
-        .trim()
-        .to_ascii_lowercase();
-    if requested_backend == "local"
-        || (requested_backend == "auto" && config.app_env.eq_ignore_ascii_case("test"))
-    {
-        return Ok(chat_attachment_local_storage(config));
-    }
+pub(crate) fn indexed_ontology_repo(
+    ontology_repo: Arc<dyn OntologyRepository>,
+    concept_label_index: &Arc<TrieConceptLabelIndex>,
+) -> Arc<dyn OntologyRepository> {
+    Arc::new(IndexedOntologyRepository::new(
+        ontology_repo,
+        concept_label_index.clone(),
+    ))
+}
 
-    match build_chat_attachment_s3_storage(config).await {
-        Ok(storage) => Ok(storage),
-        Err(err) => {
-            if requested_backend == "s3" || config.is_production() {
-                return Err(anyhow::anyhow!(
-                    "failed to initialize S3 chat attachment storage: {err}"
-                ));
+/// Builds the autocomplete label index from the database and keeps rebuilding
+/// it, so labels written by the worker or by other API instances show up.
+fn spawn_concept_label_refresh(
+    index: Arc<TrieConceptLabelIndex>,
+    ontology_repo: Arc<dyn OntologyRepository>,
+    refresh_ms: u64,
+) {
+    tokio::spawn(async move {
+        loop {
+            match index.rebuild_from(ontology_repo.as_ref()).await {
+                Ok(concepts) => info!(concepts, "rebuilt concept label index"),
+                Err(err) => warn!(error = %err, "concept label index rebuild failed"),
             }
-            warn!(
-                error = %err,
-                "falling back to local chat attachment storage (set CHAT_ATTACHMENT_STORAGE_BACKEND=s3 to fail hard)"
-            );
-            Ok(chat_attachment_local_storage(config))
+            if refresh_ms == 0 {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(refresh_ms)).await;
         }
-    }
+    });
 }
 
 async fn repositories_for_config(config: &AppConfig) -> anyhow::Result<RepositoryBundle> {
@@ -829,9 +1002,15 @@ async fn repositories_for_config(config: &AppConfig) -> anyhow::Result<Repositor
                 },
             )
             .await?;
+            let feed_preference_repo = SurrealFeedPreferenceRepository::new(&db_config).await?;
             let notification_repo = SurrealDiscoveryNotificationRepository::new(&db_config).await?;
             let webhook_outbox_repo = SurrealWebhookOutboxRepository::new(&db_config).await?;
             let group_repo = SurrealGroupRepository::new(&db_config).await?;
+            let digest_subscription_repo =
+                SurrealDigestSubscriptionRepository::new(&db_config).await?;
+            let notification_preference_repo =
+                SurrealNotificationPreferenceRepository::new(&db_config).await?;
+            let push_subscription_repo = SurrealPushSubscriptionRepository::new(&db_config).await?;
             Ok((
                 Arc::new(adaptive_path_repo),
                 Arc::new(contribution_repo),
@@ -843,9 +1022,13 @@ async fn repositories_for_config(config: &AppConfig) -> anyhow::Result<Repositor
                 Arc::new(ontology_repo),
                 Arc::new(siaga_repo),
                 Arc::new(feed_repo),
+                Arc::new(feed_preference_repo),
                 Arc::new(notification_repo),
                 Arc::new(webhook_outbox_repo),
                 Arc::new(group_repo),
+                Arc::new(digest_subscription_repo),
+                Arc::new(notification_preference_repo),
+                Arc::new(push_subscription_repo),
             ))
         }
         _ => anyhow::bail!("unsupported DATA_BACKEND '{}'", config.data_backend),
@@ -864,12 +1047,27 @@ fn memory_repositories() -> RepositoryBundle {
         Arc::new(InMemoryOntologyRepository::new()),
         Arc::new(InMemorySiagaRepository::new()),
         Arc::new(InMemoryDiscoveryFeedRepository::new()),
+        Arc::new(InMemoryFeedPreferenceRepository::new()),
         Arc::new(InMemoryDiscoveryNotificationRepository::new()),
         Arc::new(InMemoryWebhookOutboxRepository::new()),
         Arc::new(InMemoryGroupRepository::new()),
+        Arc::new(InMemoryDigestSubscriptionRepository::new()),
+        Arc::new(InMemoryNotificationPreferenceRepository::new()),
+        Arc::new(InMemoryPushSubscriptionRepository::new()),
     )
 }
 
+async fn trending_store_for_config(
+    config: &AppConfig,
+) -> anyhow::Result<Arc<dyn TrendingSnapshotStore>> {
+    if config.app_env.eq_ignore_ascii_case("test") {
+        return Ok(Arc::new(InMemoryTrendingSnapshotStore::new()));
+    }
+    Ok(Arc::new(
+        RedisTrendingSnapshotStore::connect(&config.redis_url).await?,
+    ))
+}
+
 async fn job_queue_for_config(config: &AppConfig) -> anyhow::Result<SharedJobQueue> {
     if config.app_env.eq_ignore_ascii_case("test") {
         return Ok(None);
@@ -894,6 +1092,7 @@ async fn job_queue_for_config(config: &AppConfig) -> anyhow::Result<SharedJobQue
 #[cfg(test)]
 mod tests {
     use super::*;
+    use gotong_domain::discovery::{FeedItem, InAppNotification};
     use tokio::time::Duration;
 
     fn app_config(app_env: &str, data_backend: &str) -> AppConfig {
@@ -917,6 +1116,8 @@ mod tests {
             s3_secret_key: "test-secret-key".to_string(),
             chat_attachment_storage_backend: "local".to_string(),
             chat_attachment_s3_prefix: "chat-attachments".to_string(),
+            chat_attachment_user_quota_bytes: 512 * 1024 * 1024,
+            chat_attachment_thread_quota_bytes: 2 * 1024 * 1024 * 1024,
             chat_realtime_transport: "local".to_string(),
             chat_realtime_channel_prefix: "gotong:chat:realtime:test".to_string(),
             worker_queue_prefix: "gotong:jobs".to_string(),
@@ -927,6 +1128,14 @@ mod tests {
             worker_ttl_cleanup_interval_ms: 3_600_000,
             worker_concept_verification_interval_ms: 86_400_000,
             worker_concept_verification_qids: "Q2095".to_string(),
+            worker_chat_retention_interval_ms: 300_000,
+            worker_digest_interval_ms: 900_000,
+            worker_trending_interval_ms: 900_000,
+            trending_window_ms: 21_600_000,
+            trending_baseline_windows: 28,
+            ontology_export_base_iri: "https://gotong-royong.app/ontology/".to_string(),
+            wikidata_api_url: String::new(),
+            ontology_autocomplete_refresh_ms: 0,
             webhook_enabled: false,
             webhook_markov_url: "http://127.0.0.1:5000/webhook".to_string(),
             webhook_secret: "test-webhook-secret-32-chars-minimum".to_string(),
@@ -947,6 +1156,27 @@ mod tests {
             markov_cache_gameplay_ttl_ms: 45_000,
             markov_cache_gameplay_stale_while_revalidate_ms: 180_000,
             discovery_feed_involvement_fallback_enabled: true,
+            discovery_feed_rank_recency_weight: 1.0,
+            discovery_feed_rank_involvement_weight: 0.6,
+            discovery_feed_rank_followed_weight: 0.5,
+            discovery_feed_rank_feedback_weight: 0.4,
+            discovery_feed_rank_severity_weight: 0.8,
+            discovery_feed_rank_recency_half_life_ms: 86_400_000,
+            discovery_search_index_dir: String::new(),
+            mail_transport: "capture".to_string(),
+            mail_from: "Gotong Royong <noreply@gotong.local>".to_string(),
+            mail_smtp_host: String::new(),
+            mail_smtp_port: 587,
+            mail_smtp_username: String::new(),
+            mail_smtp_password: String::new(),
+            mail_smtp_tls: "starttls".to_string(),
+            mail_file_sink_dir: String::new(),
+            digest_unsubscribe_secret: "test_digest_unsubscribe_secret".to_string(),
+            digest_public_base_url: "http://127.0.0.1:3000".to_string(),
+            web_push_vapid_public_key: String::new(),
+            web_push_vapid_private_key: String::new(),
+            web_push_vapid_subject: "mailto:ops@gotong.local".to_string(),
+            web_push_ttl_seconds: 86_400,
             triage_operator_stub_enabled: false,
         }
     }
@@ -975,7 +1205,7 @@ mod tests {
     async fn chat_attachment_storage_auto_uses_local_for_test_env() {
         let mut config = app_config("test", "memory");
         config.chat_attachment_storage_backend = "auto".to_string();
-        let storage = chat_attachment_storage_for_config(&config)
+        let storage = ChatAttachmentStorage::from_config(&config)
             .await
             .expect("storage");
         assert!(matches!(storage, ChatAttachmentStorage::Local { .. }));
@@ -985,12 +1215,103 @@ mod tests {
     async fn chat_attachment_storage_allows_explicit_local_backend() {
         let mut config = app_config("production", "surreal");
         config.chat_attachment_storage_backend = "local".to_string();
-        let storage = chat_attachment_storage_for_config(&config)
+        let storage = ChatAttachmentStorage::from_config(&config)
             .await
             .expect("storage");
         assert!(matches!(storage, ChatAttachmentStorage::Local { .. }));
     }
 
+    #[tokio::test]
+    async fn discovery_realtime_bus_streams_own_notifications_and_visible_feed_items() {
+        let config = app_config("test", "memory");
+        let bus = DiscoveryRealtimeBus::new(&config);
+        let mut stream = bus.subscribe("warga-1").await;
+
+        let notification = |user_id: &str| InAppNotification {
+            notification_id: format!("notif-{user_id}"),
+            user_id: user_id.to_string(),
+            actor_id: "user-2".to_string(),
+            actor_username: "budi".to_string(),
+            notification_type: "vouch".to_string(),
+            source_type: "contribution".to_string(),
+            source_id: "contrib-1".to_string(),
+            title: "Vouch".to_string(),
+            body: "budi vouched".to_string(),
+            payload: None,
+            created_at_ms: 1,
+            read_at_ms: None,
+            deliver_at_ms: None,
+            privacy_level: None,
+            request_id: "req-1".to_string(),
+            correlation_id: "corr-1".to_string(),
+            dedupe_key: format!("dedupe-{user_id}"),
+            group_key: None,
+            in_app_hidden: false,
+        };
+        let feed_item = |feed_id: &str, privacy_level: &str| FeedItem {
+            feed_id: feed_id.to_string(),
+            source_type: "contribution".to_string(),
+            source_id: "contrib-1".to_string(),
+            actor_id: "user-2".to_string(),
+            actor_username: "budi".to_string(),
+            title: "Kerja bakti".to_string(),
+            summary: None,
+            scope_id: None,
+            privacy_level: Some(privacy_level.to_string()),
+            occurred_at_ms: 1,
+            created_at_ms: 1,
+            request_id: "req-1".to_string(),
+            correlation_id: "corr-1".to_string(),
+            participant_ids: Vec::new(),
+            payload: None,
+            location: None,
+            distance_m: None,
+        };
+
+        bus.publish(DiscoveryEvent::Notification {
+            notification: notification("warga-2"),
+        })
+        .await;
+        bus.publish(DiscoveryEvent::FeedItem {
+            item: feed_item("feed-private", "l3"),
+        })
+        .await;
+        bus.publish(DiscoveryEvent::FeedItem {
+            item: feed_item("feed-public", "public"),
+        })
+        .await;
+        bus.publish(DiscoveryEvent::UnreadCount {
+            user_id: "warga-1".to_string(),
+            unread_count: 3,
+        })
+        .await;
+
+        let mut received = Vec::new();
+        for _ in 0..2 {
+            let event = tokio::time::timeout(Duration::from_secs(2), stream.recv())
+                .await
+                .expect("event timed out")
+                .expect("stream closed");
+            received.push(event);
+        }
+        assert!(received.iter().any(
+            |event| matches!(event, DiscoveryEvent::FeedItem { item } if item.feed_id == "feed-public")
+        ));
+        assert!(received.iter().any(|event| matches!(
+            event,
+            DiscoveryEvent::UnreadCount {
+                unread_count: 3,
+                ..
+            }
+        )));
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), stream.recv())
+                .await
+                .is_err(),
+            "other users' notifications and hidden feed items are not streamed"
+        );
+    }
+
     #[tokio::test]
     async fn chat_realtime_bus_local_mode_delivers_to_its_subscriber() {
         let config = AppConfig {
@@ -1018,7 +1339,7 @@ mod tests {
             .await
             .expect("message timed out")
             .expect("stream closed");
-        assert_eq!(received, message);
+        assert_eq!(received, ChatRealtimeEvent::Message(message));
     }
 
     #[tokio::test]
@@ -1067,8 +1388,8 @@ mod tests {
             .await
             .expect("message timed out")
             .expect("stream closed");
-        assert_eq!(first, message);
-        assert_eq!(second, message);
+        assert_eq!(first, ChatRealtimeEvent::Message(message.clone()));
+        assert_eq!(second, ChatRealtimeEvent::Message(message));
     }
 
     async fn redis_is_available(redis_url: &str) -> bool {
@@ -14,12 +14,16 @@ use axum::{
 };
 use gotong_domain::discovery::{
     DiscoveryService, FEED_SOURCE_CONTRIBUTION, FEED_SOURCE_ONTOLOGY_NOTE, FeedIngestInput,
-    FeedListQuery, NOTIF_TYPE_SYSTEM, NotificationIngestInput, SearchListQuery,
+    FeedListQuery, FeedMode, NOTIF_TYPE_SYSTEM, NOTIF_TYPE_VOUCH, NotificationIngestInput,
+    SearchListQuery,
 };
+use gotong_domain::geo::GeoPoint;
 use gotong_domain::idempotency::InMemoryIdempotencyStore;
 use gotong_domain::identity::ActorIdentity;
 use gotong_domain::ontology::OntologyEdgeKind;
+use gotong_domain::ontology_gazetteer::AdminArea;
 use gotong_domain::ranking::wilson_score;
+use gotong_domain::trending::{TrendingSnapshot, TrendingTopic, TrendingTopicKind};
 use gotong_domain::webhook::WebhookOutboxListQuery;
 use jsonwebtoken::{EncodingKey, Header, encode};
 use serde::Serialize;
@@ -31,6 +35,7 @@ use crate::observability;
 use crate::routes;
 use crate::state::AppState;
 use gotong_infra::config::AppConfig;
+use gotong_infra::search_index::{FeedSearchIndexer, TantivyFeedSearchIndex};
 
 #[derive(Serialize)]
 struct Claims {
@@ -60,6 +65,8 @@ fn test_config() -> AppConfig {
         s3_secret_key: "test-secret-key".to_string(),
         chat_attachment_storage_backend: "local".to_string(),
         chat_attachment_s3_prefix: "chat-attachments".to_string(),
+        chat_attachment_user_quota_bytes: 512 * 1024 * 1024,
+        chat_attachment_thread_quota_bytes: 2 * 1024 * 1024 * 1024,
         chat_realtime_transport: "local".to_string(),
         chat_realtime_channel_prefix: "gotong:chat:realtime:test".to_string(),
         worker_queue_prefix: "gotong:jobs".to_string(),
@@ -70,6 +77,14 @@ fn test_config() -> AppConfig {
         worker_ttl_cleanup_interval_ms: 3_600_000,
         worker_concept_verification_interval_ms: 86_400_000,
         worker_concept_verification_qids: "Q2095".to_string(),
+        worker_chat_retention_interval_ms: 300_000,
+        worker_digest_interval_ms: 900_000,
+        worker_trending_interval_ms: 900_000,
+        trending_window_ms: 21_600_000,
+        trending_baseline_windows: 28,
+        ontology_export_base_iri: "https://gotong-royong.app/ontology/".to_string(),
+        wikidata_api_url: String::new(),
+        ontology_autocomplete_refresh_ms: 0,
         webhook_enabled: false,
         webhook_markov_url: "http://127.0.0.1:8080/webhook".to_string(),
         webhook_secret: "dev_webhook_secret_32_chars_minimum".to_string(),
@@ -90,6 +105,27 @@ fn test_config() -> AppConfig {
         markov_cache_gameplay_ttl_ms: 45_000,
         markov_cache_gameplay_stale_while_revalidate_ms: 180_000,
         discovery_feed_involvement_fallback_enabled: true,
+        discovery_feed_rank_recency_weight: 1.0,
+        discovery_feed_rank_involvement_weight: 0.6,
+        discovery_feed_rank_followed_weight: 0.5,
+        discovery_feed_rank_feedback_weight: 0.4,
+        discovery_feed_rank_severity_weight: 0.8,
+        discovery_feed_rank_recency_half_life_ms: 86_400_000,
+        discovery_search_index_dir: String::new(),
+        mail_transport: "capture".to_string(),
+        mail_from: "Gotong Royong <noreply@gotong.local>".to_string(),
+        mail_smtp_host: String::new(),
+        mail_smtp_port: 587,
+        mail_smtp_username: String::new(),
+        mail_smtp_password: String::new(),
+        mail_smtp_tls: "starttls".to_string(),
+        mail_file_sink_dir: String::new(),
+        digest_unsubscribe_secret: "test_digest_unsubscribe_secret".to_string(),
+        digest_public_base_url: "http://127.0.0.1:3000".to_string(),
+        web_push_vapid_public_key: String::new(),
+        web_push_vapid_private_key: String::new(),
+        web_push_vapid_subject: "mailto:ops@gotong.local".to_string(),
+        web_push_ttl_seconds: 86_400,
         triage_operator_stub_enabled: false,
     }
 }
@@ -578,42 +614,658 @@ async fn ontology_routes_support_feed_hierarchy_feedback_and_ranking() {
         ranked_vouch_count + ranked_challenge_count,
     );
     assert!((score - expected_score).abs() < 1e-12);
+    // No Markov engine is reachable here, so ranking falls back to Wilson.
+    assert_eq!(
+        ranked.pointer("/explanation/method"),
+        Some(&json!("wilson"))
+    );
+    assert_eq!(
+        ranked.pointer("/explanation/fallback_reason"),
+        Some(&json!("reputation_unavailable"))
+    );
+}
+
+#[tokio::test]
+async fn ontology_gazetteer_reverse_geocodes_and_tags_new_notes() {
+    let (state, app) = test_app_state_router();
+    let token = test_token("test-secret");
+    let point = |lat: f64, lng: f64| GeoPoint { lat, lng };
+    let square = |south: f64, west: f64, size: f64| {
+        Some(vec![vec![
+            point(south, west),
+            point(south, west + size),
+            point(south + size, west + size),
+            point(south + size, west),
+        ]])
+    };
+    state
+        .ontology_repo
+        .upsert_admin_areas(&[
+            AdminArea::new("32", "Jawa Barat", point(-6.9, 107.6), None).unwrap(),
+            AdminArea::new("32.73", "Kota Bandung", point(-6.9, 107.6), None).unwrap(),
+            AdminArea::new(
+                "32.73.01",
+                "Sukasari",
+                point(-6.88, 107.58),
+                square(-6.9, 107.56, 0.04),
+            )
+            .unwrap(),
+            AdminArea::new(
+                "32.73.01.1001",
+                "Sarijadi",
+                point(-6.875, 107.575),
+                square(-6.88, 107.57, 0.01),
+            )
+            .unwrap(),
+        ])
+        .await
+        .expect("seed gazetteer");
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/v1/ontology/places/reverse?lat=-6.875&lng=107.575")
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let reverse: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(
+        reverse.pointer("/area/place_id"),
+        Some(&json!("place:adm_32_73_01_1001"))
+    );
+    assert_eq!(
+        reverse.pointer("/area/level"),
+        Some(&json!("desa_kelurahan"))
+    );
+    assert_eq!(reverse.get("method"), Some(&json!("boundary")));
+    assert_eq!(
+        reverse
+            .get("hierarchy")
+            .and_then(|hierarchy| hierarchy.as_array())
+            .map(Vec::len),
+        Some(4)
+    );
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/v1/ontology/places/reverse?lat=1.0&lng=100.0")
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_error_envelope(response, StatusCode::NOT_FOUND, "not_found").await;
+
+    let feed_payload = json!({
+        "content": "Jalan berlubang depan SD Sarijadi",
+        "community_id": "rt05",
+        "temporal_class": "persistent",
+        "location": { "lat": -6.876, "lng": 107.574 }
+    });
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/ontology/feed")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::from(feed_payload.to_string()))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let created: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(created.get("triple_count"), Some(&json!(1)));
+    assert_eq!(
+        created.pointer("/located_at/code"),
+        Some(&json!("32.73.01.1001"))
+    );
+    let note_id = created
+        .pointer("/note/note_id")
+        .and_then(|value| value.as_str())
+        .expect("note_id")
+        .to_string();
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/v1/ontology/graph/notes?located_at=32.73.01.1001")
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let page: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(page.pointer("/items/0/note/note_id"), Some(&json!(note_id)));
+}
+
+#[tokio::test]
+async fn ontology_note_ranking_weights_votes_by_markov_reputation() {
+    let markov_base_url = spawn_markov_stub_base_url().await;
+    let app = test_app_with_markov_base(markov_base_url);
+    let token = test_token("test-secret");
+
+    let feed_payload = json!({
+        "content": "Antrean gas elpiji di pangkalan RT 05",
+        "community_id": "rt05",
+        "temporal_class": "ephemeral",
+        "ttl_expires_ms": 1_893_456_000_000i64
+    });
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/ontology/feed")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::from(feed_payload.to_string()))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let feed_response: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    let note_id = feed_response
+        .pointer("/note/note_id")
+        .and_then(|value| value.as_str())
+        .expect("note_id")
+        .to_string();
+
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/v1/ontology/notes/{note_id}/vouches"))
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::from("{}"))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/v1/ontology/notes/{note_id}/ranked"))
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let ranked: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(ranked.get("vouch_count"), Some(&json!(1)));
+    let explanation = ranked.get("explanation").expect("explanation");
+    assert_eq!(
+        explanation.get("method"),
+        Some(&json!("reputation_weighted"))
+    );
+    assert_eq!(explanation.get("temporal_class"), Some(&json!("ephemeral")));
+    assert_eq!(explanation.get("voters_with_reputation"), Some(&json!(1)));
+    assert_eq!(explanation.get("fallback_reason"), Some(&json!(null)));
+    // The stub reports reputation 0.77, so the fresh vouch outweighs a
+    // neutral one and beats plain Wilson for a single vote.
+    let score = ranked
+        .get("score")
+        .and_then(|value| value.as_f64())
+        .expect("score");
+    assert!(score > wilson_score(1, 1));
+}
+
+#[tokio::test]
+async fn ontology_feed_validation_rejects_invalid_temporal_privacy_and_confidence() {
+    let app = test_app();
+    let token = test_token("test-secret");
+    let payload = json!({
+        "content": "Invalid ontology feed",
+        "community_id": "rt05",
+        "temporal_class": "ephemeral",
+        "rahasia_level": 5,
+        "confidence": 1.5
+    });
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/ontology/feed")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::from(payload.to_string()))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn ontology_concept_by_qid_returns_not_found_when_unknown() {
+    let app = test_app();
+    let token = test_token("test-secret");
+    let request = Request::builder()
+        .method("GET")
+        .uri("/v1/ontology/concepts/Q_NOT_FOUND_999")
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .expect("request");
+    let response = app.oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn ontology_export_serves_public_notes_only() {
+    let app = test_app();
+    let token = test_token("test-secret");
+    for (content, rahasia_level) in [
+        ("Banjir di RT 05", 0),
+        ("Rahasia warga RT 05", 2),
+        ("Jalan rusak di RT 05", 0),
+    ] {
+        let payload = json!({
+            "content": content,
+            "community_id": "rt05",
+            "temporal_class": "persistent",
+            "rahasia_level": rahasia_level,
+            "triples": [{ "edge": "About", "to_id": "concept:Q8068" }]
+        });
+        let request = Request::builder()
+            .method("POST")
+            .uri("/v1/ontology/feed")
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {token}"))
+            .body(Body::from(payload.to_string()))
+            .expect("request");
+        let response = app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/v1/ontology/export")
+        .header("accept", "text/turtle")
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/turtle; charset=utf-8"
+    );
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let turtle = String::from_utf8(body.to_vec()).expect("utf8");
+    assert!(turtle.contains("schema:text \"Banjir di RT 05\""));
+    assert!(turtle.contains("schema:about <https://gotong-royong.app/ontology/concept/Q8068>"));
+    assert!(!turtle.contains("Rahasia"));
+
+    // Pages of one note: the cursor walks both public notes, then stops.
+    let mut texts = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..2 {
+        let uri = match &cursor {
+            Some(cursor) => format!("/v1/ontology/export?format=turtle&limit=1&cursor={cursor}"),
+            None => "/v1/ontology/export?format=turtle&limit=1".to_string(),
+        };
+        let request = Request::builder()
+            .method("GET")
+            .uri(uri)
+            .header("authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .expect("request");
+        let response = app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::OK);
+        cursor = response
+            .headers()
+            .get("x-next-cursor")
+            .map(|value| value.to_str().expect("cursor").to_string());
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let page = String::from_utf8(body.to_vec()).expect("utf8");
+        assert_eq!(page.matches("schema:text").count(), 1);
+        texts.extend(
+            ["Banjir di RT 05", "Jalan rusak di RT 05"]
+                .into_iter()
+                .filter(|text| page.contains(text)),
+        );
+    }
+    assert_eq!(cursor, None);
+    texts.sort();
+    assert_eq!(texts, vec!["Banjir di RT 05", "Jalan rusak di RT 05"]);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/v1/ontology/export?format=jsonld&community_id=rt06")
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let document: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    let graph = document["@graph"].as_array().expect("graph");
+    assert!(
+        graph
+            .iter()
+            .all(|node| node["@type"] != json!(["schema:CreativeWork"]))
+    );
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/v1/ontology/export?format=rdfxml")
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .expect("request");
+    let response = app.oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn ontology_graph_query_expands_narrower_concepts_and_hides_private_notes() {
+    let app = test_app();
+    let author_token = test_token("test-secret");
+    let other_token = test_token_with_identity("test-secret", "user", "warga-lain");
+    for (content, rahasia_level) in [("Banjir di RT 05", 0), ("Rahasia warga RT 05", 2)] {
+        let payload = json!({
+            "content": content,
+            "community_id": "rt05",
+            "temporal_class": "persistent",
+            "rahasia_level": rahasia_level,
+            "triples": [
+                { "edge": "About", "to_id": "concept:Q8068" },
+                { "edge": "LocatedAt", "to_id": "place:rt-05" }
+            ]
+        });
+        let request = Request::builder()
+            .method("POST")
+            .uri("/v1/ontology/feed")
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {author_token}"))
+            .body(Body::from(payload.to_string()))
+            .expect("request");
+        let response = app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/ontology/concepts/Q8068/broader/Q3839081")
+        .header("authorization", format!("Bearer {author_token}"))
+        .body(Body::empty())
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    for (token, expected) in [(&other_token, 1), (&author_token, 2)] {
+        let request = Request::builder()
+            .method("GET")
+            .uri("/v1/ontology/graph/notes?about=Q3839081&located_at=rt-05&limit=10")
+            .header("authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .expect("request");
+        let response = app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let page: serde_json::Value = serde_json::from_slice(&body).expect("json");
+        assert_eq!(page["expanded_concept_qids"], json!(["Q3839081", "Q8068"]));
+        let items = page["items"].as_array().expect("items");
+        assert_eq!(items.len(), expected);
+        assert_eq!(items[0]["located_at"], json!(["place:rt-05"]));
+        if expected == 1 {
+            assert_eq!(items[0]["note"]["content"], "Banjir di RT 05");
+        }
+    }
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/v1/ontology/graph/notes?about=Q3839081&narrower_depth=0")
+        .header("authorization", format!("Bearer {other_token}"))
+        .body(Body::empty())
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let page: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(page["items"], json!([]));
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/v1/ontology/graph/notes?community_id=rt05")
+        .header("authorization", format!("Bearer {other_token}"))
+        .body(Body::empty())
+        .expect("request");
+    let response = app.oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn ontology_concept_merge_is_admin_only_and_redirects_old_qid() {
+    let app = test_app();
+    let user_token = test_token("test-secret");
+    let admin_token = test_token_with_identity("test-secret", "admin", "admin-ontology");
+    for (qid, label) in [("Q100", "Banjir bandang"), ("Q42", "Banjir")] {
+        let request = Request::builder()
+            .method("POST")
+            .uri("/v1/ontology/concepts")
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {user_token}"))
+            .body(Body::from(
+                json!({ "qid": qid, "label_id": label, "verified": true }).to_string(),
+            ))
+            .expect("request");
+        let response = app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+    let payload = json!({
+        "content": "Banjir bandang di RT 05",
+        "community_id": "rt05",
+        "temporal_class": "persistent",
+        "triples": [{ "edge": "About", "to_id": "concept:Q100" }]
+    });
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/ontology/feed")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {user_token}"))
+        .body(Body::from(payload.to_string()))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let merge_body = json!({ "into_qid": "Q42" }).to_string();
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/ontology/concepts/Q100/merge")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {user_token}"))
+        .body(Body::from(merge_body.clone()))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/ontology/concepts/Q100/merge")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {admin_token}"))
+        .body(Body::from(merge_body))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let merged: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(merged["surviving"]["qid"], "Q42");
+    assert_eq!(merged["redirect"]["from_qid"], "Q100");
+    assert_eq!(merged["redirect"]["reason"], "admin");
+    assert_eq!(merged["moved_edge_count"], 1);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/v1/ontology/concepts/Q100")
+        .header("authorization", format!("Bearer {user_token}"))
+        .body(Body::empty())
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let concept: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(concept["qid"], "Q42");
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/v1/ontology/graph/notes?about=Q42&narrower_depth=0")
+        .header("authorization", format!("Bearer {user_token}"))
+        .body(Body::empty())
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let page: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(page["items"].as_array().expect("items").len(), 1);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/ontology/concepts/Q42/merge")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {admin_token}"))
+        .body(Body::from(json!({ "into_qid": "Q100" }).to_string()))
+        .expect("request");
+    let response = app.oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
-#[tokio::test]
-async fn ontology_feed_validation_rejects_invalid_temporal_privacy_and_confidence() {
-    let app = test_app();
-    let token = test_token("test-secret");
+#[tokio::test]
+async fn ontology_autocomplete_matches_regional_labels_and_ranks_community_usage() {
+    let app = test_app();
+    let user_token = test_token("test-secret");
+    let admin_token = test_token_with_identity("test-secret", "admin", "admin-ontology");
+    for (qid, label) in [("Q8068", "Banjir"), ("Q1", "Banjir bandang")] {
+        let request = Request::builder()
+            .method("POST")
+            .uri("/v1/ontology/concepts")
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {user_token}"))
+            .body(Body::from(
+                json!({ "qid": qid, "label_id": label, "verified": true }).to_string(),
+            ))
+            .expect("request");
+        let response = app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let labels_body = json!({
+        "labels": [
+            { "lang": "jv", "label": "Banjir bandhang" },
+            { "lang": "su", "label": "Caah", "alias": false },
+            { "lang": "su", "label": "caah" }
+        ]
+    })
+    .to_string();
+    let request = Request::builder()
+        .method("PUT")
+        .uri("/v1/ontology/concepts/Q1/labels")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {user_token}"))
+        .body(Body::from(labels_body.clone()))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let request = Request::builder()
+        .method("PUT")
+        .uri("/v1/ontology/concepts/Q404/labels")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {admin_token}"))
+        .body(Body::from(labels_body.clone()))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let request = Request::builder()
+        .method("PUT")
+        .uri("/v1/ontology/concepts/Q1/labels")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {admin_token}"))
+        .body(Body::from(labels_body))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let stored: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    // The preferred label comes first; the duplicate Sundanese label is dropped.
+    assert_eq!(stored["labels"].as_array().expect("labels").len(), 3);
+
     let payload = json!({
-        "content": "Invalid ontology feed",
+        "content": "Banjir bandang di RT 05",
         "community_id": "rt05",
-        "temporal_class": "ephemeral",
-        "rahasia_level": 5,
-        "confidence": 1.5
+        "temporal_class": "persistent",
+        "triples": [{ "edge": "About", "to_id": "concept:Q1" }]
     });
     let request = Request::builder()
         .method("POST")
         .uri("/v1/ontology/feed")
         .header("content-type", "application/json")
-        .header("authorization", format!("Bearer {token}"))
+        .header("authorization", format!("Bearer {user_token}"))
         .body(Body::from(payload.to_string()))
         .expect("request");
     let response = app.clone().oneshot(request).await.expect("response");
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
-}
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let autocomplete = |uri: &'static str| {
+        let app = app.clone();
+        let user_token = user_token.clone();
+        async move {
+            let request = Request::builder()
+                .method("GET")
+                .uri(uri)
+                .header("authorization", format!("Bearer {user_token}"))
+                .body(Body::empty())
+                .expect("request");
+            let response = app.oneshot(request).await.expect("response");
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("body");
+            serde_json::from_slice::<serde_json::Value>(&body).expect("json")
+        }
+    };
+
+    let plain = autocomplete("/v1/ontology/autocomplete?q=banj").await;
+    assert_eq!(plain[0]["qid"], "Q8068");
+    assert_eq!(plain[1]["qid"], "Q1");
+    let ranked = autocomplete("/v1/ontology/autocomplete?q=banj&community_id=rt05").await;
+    assert_eq!(ranked[0]["qid"], "Q1");
+    assert_eq!(ranked[0]["community_note_count"], 1);
+    let sundanese = autocomplete("/v1/ontology/autocomplete?q=Caa&lang=su").await;
+    assert_eq!(sundanese[0]["qid"], "Q1");
+    assert_eq!(sundanese[0]["matched_lang"], "su");
+    assert_eq!(sundanese[0]["label_id"], "Banjir bandang");
+    let javanese = autocomplete("/v1/ontology/autocomplete?q=bandh").await;
+    assert_eq!(javanese[0]["matched_label"], "Banjir bandhang");
+    let typo = autocomplete("/v1/ontology/autocomplete?q=banjri").await;
+    assert_eq!(typo[0]["qid"], "Q8068");
+    assert_eq!(typo[0]["distance"], 1);
 
-#[tokio::test]
-async fn ontology_concept_by_qid_returns_not_found_when_unknown() {
-    let app = test_app();
-    let token = test_token("test-secret");
     let request = Request::builder()
         .method("GET")
-        .uri("/v1/ontology/concepts/Q_NOT_FOUND_999")
-        .header("authorization", format!("Bearer {token}"))
+        .uri("/v1/ontology/autocomplete?q=%20")
+        .header("authorization", format!("Bearer {user_token}"))
         .body(Body::empty())
         .expect("request");
     let response = app.oneshot(request).await.expect("response");
-    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
@@ -1173,6 +1825,179 @@ async fn ontology_note_vouch_is_idempotent_and_patches_feed_payload() {
     );
 }
 
+#[tokio::test]
+async fn ontology_note_edits_keep_history_and_retraction_hides_feed_item() {
+    let (_state, app) = test_app_state_router();
+    let author_token = test_token("test-secret");
+    let other_token = test_token_with_identity("test-secret", "user", "user-456");
+
+    let payload = json!({
+        "content": "Posko banjir di balai RW",
+        "community_id": "rt05",
+        "temporal_class": "persistent"
+    });
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/ontology/feed")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {author_token}"))
+        .header("x-request-id", "ontology-edit-1")
+        .body(Body::from(payload.to_string()))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: serde_json::Value = serde_json::from_slice(
+        &to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body"),
+    )
+    .expect("json");
+    let note_id = created
+        .get("note")
+        .and_then(|note| note.get("note_id"))
+        .and_then(|value| value.as_str())
+        .expect("note_id")
+        .to_string();
+    assert_eq!(
+        created
+            .get("note")
+            .and_then(|note| note.get("version"))
+            .and_then(|value| value.as_i64()),
+        Some(1)
+    );
+
+    let vouch_request = Request::builder()
+        .method("POST")
+        .uri(format!("/v1/ontology/notes/{note_id}/vouches"))
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {other_token}"))
+        .body(Body::from("{}"))
+        .expect("request");
+    let response = app.clone().oneshot(vouch_request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let edit = json!({ "expected_version": 1, "content": "Posko banjir pindah ke masjid" });
+    let request = Request::builder()
+        .method("PATCH")
+        .uri(format!("/v1/ontology/notes/{note_id}"))
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {other_token}"))
+        .body(Body::from(edit.to_string()))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let request = Request::builder()
+        .method("PATCH")
+        .uri(format!("/v1/ontology/notes/{note_id}"))
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {author_token}"))
+        .body(Body::from(edit.to_string()))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let edited: serde_json::Value = serde_json::from_slice(
+        &to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body"),
+    )
+    .expect("json");
+    assert_eq!(edited["note"]["version"], json!(2));
+    assert_eq!(
+        edited["note"]["content"],
+        json!("Posko banjir pindah ke masjid")
+    );
+    assert_eq!(edited["feedback"]["vouch_count"], json!(1));
+    assert_eq!(edited["feedback"]["earlier_version_vouch_count"], json!(1));
+
+    // The author still holds version 1: the second edit is stale.
+    let request = Request::builder()
+        .method("PATCH")
+        .uri(format!("/v1/ontology/notes/{note_id}"))
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {author_token}"))
+        .body(Body::from(edit.to_string()))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/v1/ontology/notes/{note_id}/versions"))
+        .header("authorization", format!("Bearer {other_token}"))
+        .body(Body::empty())
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let history: serde_json::Value = serde_json::from_slice(
+        &to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body"),
+    )
+    .expect("json");
+    let versions = history["versions"].as_array().expect("versions");
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[0]["version"], json!(2));
+    assert_eq!(versions[1]["content"], json!("Posko banjir di balai RW"));
+
+    let mut retracted_at_ms = None;
+    for _ in 0..2 {
+        let request = Request::builder()
+            .method("POST")
+            .uri(format!("/v1/ontology/notes/{note_id}/retract"))
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {author_token}"))
+            .body(Body::from(r#"{"reason":"posko sudah tutup"}"#))
+            .expect("request");
+        let response = app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::OK);
+        let note: serde_json::Value = serde_json::from_slice(
+            &to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("body"),
+        )
+        .expect("json");
+        assert_eq!(note["retraction_reason"], json!("posko sudah tutup"));
+        let at = note["retracted_at_ms"].as_i64().expect("retracted_at_ms");
+        assert_eq!(*retracted_at_ms.get_or_insert(at), at);
+    }
+
+    let request = Request::builder()
+        .method("PATCH")
+        .uri(format!("/v1/ontology/notes/{note_id}"))
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {author_token}"))
+        .body(Body::from(
+            json!({ "expected_version": 2, "content": "Dibuka lagi" }).to_string(),
+        ))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let feed_request = Request::builder()
+        .method("GET")
+        .uri("/v1/feed?scope_id=rt05&limit=10")
+        .header("authorization", format!("Bearer {other_token}"))
+        .body(Body::empty())
+        .expect("request");
+    let response = app.clone().oneshot(feed_request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let feed: serde_json::Value = serde_json::from_slice(
+        &to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body"),
+    )
+    .expect("json");
+    let items = feed
+        .get("items")
+        .and_then(|value| value.as_array())
+        .expect("items");
+    assert!(!items.iter().any(|row| {
+        row.get("source_type").and_then(|value| value.as_str()) == Some(FEED_SOURCE_ONTOLOGY_NOTE)
+            && row.get("source_id").and_then(|value| value.as_str()) == Some(note_id.as_str())
+    }));
+}
+
 #[tokio::test]
 async fn ontology_note_challenge_is_idempotent_and_patches_feed_payload() {
     let (_state, app) = test_app_state_router();
@@ -1837,109 +2662,296 @@ async fn adaptive_path_plan_flow_works() {
                 {
                     "branch_id": "main",
                     "label": "Utama",
-                    "parent_checkpoint_id": null,
+                    "parent_checkpoint_id": null,
+                    "order": 0,
+                    "phases": [
+                        {
+                            "phase_id": "phase-1",
+                            "title": "Analisis lanjutan",
+                            "objective": "Kumpulkan konteks",
+                            "status": "active",
+                            "order": 0,
+                            "source": "ai",
+                            "checkpoints": [
+                                {
+                                    "checkpoint_id": "checkpoint-1",
+                                    "title": "Validasi data",
+                                    "status": "open",
+                                    "order": 0,
+                                    "source": "ai"
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }
+    });
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/v1/adaptive-path/plans/{plan_id}/suggestions"))
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {token}"))
+        .header("x-request-id", "adaptive-suggest-1")
+        .header("x-correlation-id", "adaptive-corr-3")
+        .body(Body::from(suggest_payload.to_string()))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let suggestion: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    let suggestion_id = suggestion
+        .get("suggestion_id")
+        .and_then(|value| value.as_str())
+        .expect("suggestion_id")
+        .to_string();
+
+    let accept_payload = json!({
+        "editor_roles": ["project_manager"]
+    });
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!(
+            "/v1/adaptive-path/suggestions/{suggestion_id}/accept"
+        ))
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {token}"))
+        .header("x-request-id", "adaptive-accept-1")
+        .header("x-correlation-id", "adaptive-corr-4")
+        .body(Body::from(accept_payload.to_string()))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let accepted_plan: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(accepted_plan.get("version"), Some(&json!(3)));
+
+    let events_request = Request::builder()
+        .method("GET")
+        .uri(format!("/v1/adaptive-path/plans/{plan_id}/events"))
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .expect("request");
+    let response = app.clone().oneshot(events_request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let events: Vec<serde_json::Value> = serde_json::from_slice(&body).expect("json");
+    assert!(
+        events.len() >= 4,
+        "expected at least create/update/suggest/accept events"
+    );
+
+    let suggestions_request = Request::builder()
+        .method("GET")
+        .uri(format!("/v1/adaptive-path/plans/{plan_id}/suggestions"))
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .expect("request");
+    let response = app
+        .clone()
+        .oneshot(suggestions_request)
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let suggestions: Vec<serde_json::Value> = serde_json::from_slice(&body).expect("json");
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0].get("status"), Some(&json!("accepted")));
+}
+
+#[tokio::test]
+async fn adaptive_path_plan_versions_diff_and_rollback() {
+    let app = test_app();
+    let token = test_token_with_identity("test-secret", "admin", "admin-rollback");
+    let payload = |phase_title: &str, checkpoints: serde_json::Value| {
+        json!({
+            "title": "Rencana posko",
+            "action_type": "schema:InformAction",
+            "branches": [
+                {
+                    "branch_id": "main",
+                    "label": "Utama",
                     "order": 0,
                     "phases": [
                         {
                             "phase_id": "phase-1",
-                            "title": "Analisis lanjutan",
-                            "objective": "Kumpulkan konteks",
+                            "title": phase_title,
+                            "objective": "Siapkan posko",
                             "status": "active",
                             "order": 0,
                             "source": "ai",
-                            "checkpoints": [
-                                {
-                                    "checkpoint_id": "checkpoint-1",
-                                    "title": "Validasi data",
-                                    "status": "open",
-                                    "order": 0,
-                                    "source": "ai"
-                                }
-                            ]
+                            "checkpoints": checkpoints
                         }
                     ]
                 }
             ]
-        }
+        })
+    };
+    let first_checkpoint = json!({
+        "checkpoint_id": "checkpoint-1",
+        "title": "Cari lokasi",
+        "status": "open",
+        "order": 0,
+        "source": "ai"
     });
+
     let request = Request::builder()
         .method("POST")
-        .uri(format!("/v1/adaptive-path/plans/{plan_id}/suggestions"))
+        .uri("/v1/adaptive-path/plans")
         .header("content-type", "application/json")
         .header("authorization", format!("Bearer {token}"))
-        .header("x-request-id", "adaptive-suggest-1")
-        .header("x-correlation-id", "adaptive-corr-3")
-        .body(Body::from(suggest_payload.to_string()))
+        .header("x-request-id", "adaptive-rollback-create")
+        .header("x-correlation-id", "adaptive-rollback-corr")
+        .body(Body::from(
+            json!({
+                "entity_id": "case-adaptive-rollback",
+                "payload": payload("Persiapan", json!([first_checkpoint.clone()]))
+            })
+            .to_string(),
+        ))
         .expect("request");
     let response = app.clone().oneshot(request).await.expect("response");
     assert_eq!(response.status(), StatusCode::CREATED);
     let body = to_bytes(response.into_body(), usize::MAX)
         .await
         .expect("body");
-    let suggestion: serde_json::Value = serde_json::from_slice(&body).expect("json");
-    let suggestion_id = suggestion
-        .get("suggestion_id")
-        .and_then(|value| value.as_str())
-        .expect("suggestion_id")
-        .to_string();
+    let created: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    let plan_id = created["plan_id"].as_str().expect("plan_id").to_string();
 
-    let accept_payload = json!({
-        "editor_roles": ["project_manager"]
-    });
+    // Version 2 retitles the phase (locking its title) and adds a checkpoint.
     let request = Request::builder()
         .method("POST")
-        .uri(format!(
-            "/v1/adaptive-path/suggestions/{suggestion_id}/accept"
-        ))
+        .uri(format!("/v1/adaptive-path/plans/{plan_id}/update"))
         .header("content-type", "application/json")
         .header("authorization", format!("Bearer {token}"))
-        .header("x-request-id", "adaptive-accept-1")
-        .header("x-correlation-id", "adaptive-corr-4")
-        .body(Body::from(accept_payload.to_string()))
+        .header("x-request-id", "adaptive-rollback-update")
+        .header("x-correlation-id", "adaptive-rollback-corr")
+        .body(Body::from(
+            json!({
+                "expected_version": 1,
+                "payload": payload(
+                    "Persiapan posko",
+                    json!([
+                        first_checkpoint,
+                        {
+                            "checkpoint_id": "checkpoint-2",
+                            "title": "Kumpulkan relawan",
+                            "status": "open",
+                            "order": 1,
+                            "source": "human"
+                        }
+                    ])
+                )
+            })
+            .to_string(),
+        ))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/v1/adaptive-path/plans/{plan_id}/versions"))
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::empty())
         .expect("request");
     let response = app.clone().oneshot(request).await.expect("response");
     assert_eq!(response.status(), StatusCode::OK);
     let body = to_bytes(response.into_body(), usize::MAX)
         .await
         .expect("body");
-    let accepted_plan: serde_json::Value = serde_json::from_slice(&body).expect("json");
-    assert_eq!(accepted_plan.get("version"), Some(&json!(3)));
+    let versions: Vec<serde_json::Value> = serde_json::from_slice(&body).expect("json");
+    assert_eq!(
+        versions
+            .iter()
+            .map(|version| version["version"].clone())
+            .collect::<Vec<_>>(),
+        vec![json!(2), json!(1)]
+    );
 
-    let events_request = Request::builder()
+    let request = Request::builder()
         .method("GET")
-        .uri(format!("/v1/adaptive-path/plans/{plan_id}/events"))
+        .uri(format!("/v1/adaptive-path/plans/{plan_id}/versions/1"))
         .header("authorization", format!("Bearer {token}"))
         .body(Body::empty())
         .expect("request");
-    let response = app.clone().oneshot(events_request).await.expect("response");
+    let response = app.clone().oneshot(request).await.expect("response");
     assert_eq!(response.status(), StatusCode::OK);
     let body = to_bytes(response.into_body(), usize::MAX)
         .await
         .expect("body");
-    let events: Vec<serde_json::Value> = serde_json::from_slice(&body).expect("json");
-    assert!(
-        events.len() >= 4,
-        "expected at least create/update/suggest/accept events"
+    let first: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(
+        first["branches"][0]["phases"][0]["title"],
+        json!("Persiapan")
     );
 
-    let suggestions_request = Request::builder()
+    let request = Request::builder()
         .method("GET")
-        .uri(format!("/v1/adaptive-path/plans/{plan_id}/suggestions"))
+        .uri(format!(
+            "/v1/adaptive-path/plans/{plan_id}/diff?from=1&to=2"
+        ))
         .header("authorization", format!("Bearer {token}"))
         .body(Body::empty())
         .expect("request");
-    let response = app
-        .clone()
-        .oneshot(suggestions_request)
-        .await
-        .expect("response");
+    let response = app.clone().oneshot(request).await.expect("response");
     assert_eq!(response.status(), StatusCode::OK);
     let body = to_bytes(response.into_body(), usize::MAX)
         .await
         .expect("body");
-    let suggestions: Vec<serde_json::Value> = serde_json::from_slice(&body).expect("json");
-    assert_eq!(suggestions.len(), 1);
-    assert_eq!(suggestions[0].get("status"), Some(&json!("accepted")));
+    let diff: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    let entries = diff["entries"].as_array().expect("entries");
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["id"], json!("phase-1"));
+    assert_eq!(entries[0]["changes"], json!(["retitled"]));
+    assert_eq!(entries[1]["id"], json!("checkpoint-2"));
+    assert_eq!(entries[1]["changes"], json!(["added"]));
+
+    for _ in 0..2 {
+        let request = Request::builder()
+            .method("POST")
+            .uri(format!("/v1/adaptive-path/plans/{plan_id}/rollback"))
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {token}"))
+            .header("x-request-id", "adaptive-rollback-1")
+            .header("x-correlation-id", "adaptive-rollback-corr")
+            .body(Body::from(
+                json!({ "expected_version": 2, "target_version": 1 }).to_string(),
+            ))
+            .expect("request");
+        let response = app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let rolled_back: serde_json::Value = serde_json::from_slice(&body).expect("json");
+        assert_eq!(rolled_back["version"], json!(3));
+        let phase = &rolled_back["branches"][0]["phases"][0];
+        assert_eq!(phase["title"], json!("Persiapan posko"));
+        assert_eq!(phase["checkpoints"].as_array().map(Vec::len), Some(1));
+    }
+
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/v1/adaptive-path/plans/{plan_id}/rollback"))
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {token}"))
+        .header("x-request-id", "adaptive-rollback-stale")
+        .header("x-correlation-id", "adaptive-rollback-corr")
+        .body(Body::from(
+            json!({ "expected_version": 2, "target_version": 1 }).to_string(),
+        ))
+        .expect("request");
+    let response = app.clone().oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::CONFLICT);
 }
 
 #[tokio::test]
@@ -2096,61 +3108,238 @@ async fn adaptive_path_user_cannot_spoof_privileged_editor_roles() {
     assert_eq!(response.status(), StatusCode::CREATED);
     let body = to_bytes(response.into_body(), usize::MAX)
         .await
-        .expect("body");
-    let created_plan: serde_json::Value = serde_json::from_slice(&body).expect("json");
-    let plan_id = created_plan
-        .get("plan_id")
-        .and_then(|value| value.as_str())
-        .expect("plan_id")
-        .to_string();
+        .expect("body");
+    let created_plan: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    let plan_id = created_plan
+        .get("plan_id")
+        .and_then(|value| value.as_str())
+        .expect("plan_id")
+        .to_string();
+
+    let update_payload = json!({
+        "expected_version": 1,
+        "editor_roles": ["project_manager"],
+        "payload": {
+            "title": "Rencana awal",
+            "summary": "Ringkas",
+            "action_type": "schema:InformAction",
+            "branches": [
+                {
+                    "branch_id": "main",
+                    "label": "Utama",
+                    "parent_checkpoint_id": null,
+                    "order": 0,
+                    "phases": [
+                        {
+                            "phase_id": "phase-1",
+                            "title": "Analisis",
+                            "objective": "Kumpulkan konteks terbaru",
+                            "status": "active",
+                            "order": 0,
+                            "source": "human",
+                            "checkpoints": [
+                                {
+                                    "checkpoint_id": "checkpoint-1",
+                                    "title": "Validasi data",
+                                    "status": "open",
+                                    "order": 0,
+                                    "source": "ai"
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }
+    });
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/v1/adaptive-path/plans/{plan_id}/update"))
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {user_token}"))
+        .header("x-request-id", "adaptive-user-update-1")
+        .header("x-correlation-id", "adaptive-user-corr-2")
+        .body(Body::from(update_payload.to_string()))
+        .expect("request");
+    let response = app.oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn adaptive_path_group_admin_gets_project_manager_role() {
+    let app = test_app();
+    let manager_token = test_token_with_identity("test-secret", "user", "user-pm");
+    let outsider_token = test_token_with_identity("test-secret", "user", "user-outsider");
+    let send =
+        |method: &str, uri: String, token: &str, request_id: &str, body: serde_json::Value| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {token}"))
+                .header("x-request-id", request_id)
+                .header("x-correlation-id", "adaptive-roles-corr")
+                .body(Body::from(body.to_string()))
+                .expect("request")
+        };
+    let payload = |objective: &str| {
+        json!({
+            "title": "Rencana kerja bakti",
+            "action_type": "schema:InformAction",
+            "branches": [
+                {
+                    "branch_id": "main",
+                    "label": "Utama",
+                    "order": 0,
+                    "phases": [
+                        {
+                            "phase_id": "phase-1",
+                            "title": "Persiapan",
+                            "objective": objective,
+                            "status": "active",
+                            "order": 0,
+                            "source": "ai",
+                            "checkpoints": [
+                                {
+                                    "checkpoint_id": "checkpoint-1",
+                                    "title": "Kumpulkan alat",
+                                    "status": "open",
+                                    "order": 0,
+                                    "source": "ai"
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        })
+    };
+
+    // The group's creator is its admin.
+    let response = app
+        .clone()
+        .oneshot(send(
+            "POST",
+            "/v1/groups".to_string(),
+            &manager_token,
+            "adaptive-roles-group",
+            json!({
+                "name": "Kerja Bakti RW 03",
+                "description": "Koordinasi kerja bakti bulanan.",
+                "entity_type": "kelompok",
+                "join_policy": "terbuka"
+            }),
+        ))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let group: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    let group_id = group["group_id"].as_str().expect("group_id").to_string();
+
+    let response = app
+        .clone()
+        .oneshot(send(
+            "POST",
+            "/v1/adaptive-path/plans".to_string(),
+            &manager_token,
+            "adaptive-roles-create",
+            json!({ "entity_id": group_id, "payload": payload("Kumpulkan alat") }),
+        ))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let created: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    let plan_id = created["plan_id"].as_str().expect("plan_id").to_string();
+
+    // Claimed roles are ignored; outsiders stay forbidden.
+    let response = app
+        .clone()
+        .oneshot(send(
+            "POST",
+            format!("/v1/adaptive-path/plans/{plan_id}/update"),
+            &outsider_token,
+            "adaptive-roles-outsider",
+            json!({
+                "expected_version": 1,
+                "editor_roles": ["project_manager"],
+                "payload": payload("Bukan anggota")
+            }),
+        ))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Owning a chat thread scoped to the plan's entity grants nothing.
+    let response = app
+        .clone()
+        .oneshot(send(
+            "POST",
+            "/v1/chat/threads".to_string(),
+            &outsider_token,
+            "adaptive-roles-outsider-thread",
+            json!({ "scope_id": group_id, "privacy_level": "public" }),
+        ))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let response = app
+        .clone()
+        .oneshot(send(
+            "POST",
+            format!("/v1/adaptive-path/plans/{plan_id}/update"),
+            &outsider_token,
+            "adaptive-roles-outsider-thread-update",
+            json!({ "expected_version": 1, "payload": payload("Ambil alih rencana") }),
+        ))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = app
+        .clone()
+        .oneshot(send(
+            "POST",
+            format!("/v1/adaptive-path/plans/{plan_id}/update"),
+            &manager_token,
+            "adaptive-roles-update",
+            json!({ "expected_version": 1, "payload": payload("Kumpulkan alat dan relawan") }),
+        ))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
 
-    let update_payload = json!({
-        "expected_version": 1,
-        "editor_roles": ["project_manager"],
-        "payload": {
-            "title": "Rencana awal",
-            "summary": "Ringkas",
-            "action_type": "schema:InformAction",
-            "branches": [
-                {
-                    "branch_id": "main",
-                    "label": "Utama",
-                    "parent_checkpoint_id": null,
-                    "order": 0,
-                    "phases": [
-                        {
-                            "phase_id": "phase-1",
-                            "title": "Analisis",
-                            "objective": "Kumpulkan konteks terbaru",
-                            "status": "active",
-                            "order": 0,
-                            "source": "human",
-                            "checkpoints": [
-                                {
-                                    "checkpoint_id": "checkpoint-1",
-                                    "title": "Validasi data",
-                                    "status": "open",
-                                    "order": 0,
-                                    "source": "ai"
-                                }
-                            ]
-                        }
-                    ]
-                }
-            ]
-        }
-    });
     let request = Request::builder()
-        .method("POST")
-        .uri(format!("/v1/adaptive-path/plans/{plan_id}/update"))
-        .header("content-type", "application/json")
-        .header("authorization", format!("Bearer {user_token}"))
-        .header("x-request-id", "adaptive-user-update-1")
-        .header("x-correlation-id", "adaptive-user-corr-2")
-        .body(Body::from(update_payload.to_string()))
+        .method("GET")
+        .uri(format!("/v1/adaptive-path/plans/{plan_id}/events"))
+        .header("authorization", format!("Bearer {manager_token}"))
+        .body(Body::empty())
         .expect("request");
     let response = app.oneshot(request).await.expect("response");
-    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let events: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    let update_actor = &events.as_array().expect("events")[1]["actor"];
+    assert_eq!(
+        update_actor["editor_roles"],
+        json!(["author", "participant", "project_manager"])
+    );
+    let manager_grant = update_actor["role_grants"]
+        .as_array()
+        .expect("role_grants")
+        .iter()
+        .find(|grant| grant["rule"] == "group_manager")
+        .expect("group manager grant");
+    assert_eq!(manager_grant["role"], "project_manager");
+    assert_eq!(manager_grant["source_id"], json!(group_id));
+    assert_eq!(manager_grant["source_role"], "admin");
 }
 
 #[tokio::test]
@@ -2719,43 +3908,307 @@ async fn chat_list_messages_defaults_to_latest_limit() {
         );
     }
 
-    let list_messages_request = Request::builder()
-        .method("GET")
-        .uri(format!("/v1/chat/threads/{thread_id}/messages?limit=2"))
-        .header("authorization", format!("Bearer {token}"))
-        .body(Body::empty())
-        .unwrap();
+    let list_messages_request = Request::builder()
+        .method("GET")
+        .uri(format!("/v1/chat/threads/{thread_id}/messages?limit=2"))
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+    let response = app
+        .clone()
+        .oneshot(list_messages_request)
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let messages: Vec<serde_json::Value> = serde_json::from_slice(&body).expect("json");
+    assert_eq!(messages.len(), 2);
+    let listed_ids: Vec<String> = messages
+        .iter()
+        .map(|item| {
+            item.get("message_id")
+                .and_then(|value| value.as_str())
+                .expect("message_id")
+                .to_string()
+        })
+        .collect();
+    assert_eq!(listed_ids, vec![sent_ids[1].clone(), sent_ids[2].clone()]);
+}
+
+#[tokio::test]
+async fn chat_read_cursor_is_member_only() {
+    let app = test_app();
+    let owner_token = test_token("test-secret");
+    let outsider_token = test_token_with_identity("test-secret", "user", "user-456");
+
+    let thread_request = json!({
+        "scope_id": "scope-private-chat",
+        "privacy_level": "private",
+    });
+    let create_request = Request::builder()
+        .method("POST")
+        .uri("/v1/chat/threads")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {owner_token}"))
+        .header("x-request-id", "chat-priv-1")
+        .header("x-correlation-id", "corr-chat-priv-1")
+        .body(Body::from(thread_request.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(create_request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let thread: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    let thread_id = thread
+        .get("thread_id")
+        .and_then(|value| value.as_str())
+        .expect("thread_id");
+
+    let read_cursor_request = Request::builder()
+        .method("GET")
+        .uri(format!("/v1/chat/threads/{thread_id}/read-cursor"))
+        .header("authorization", format!("Bearer {outsider_token}"))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(read_cursor_request).await.expect("response");
+    assert_ne!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn chat_thread_retention_is_owner_managed() {
+    let app = test_app();
+    let owner_token = test_token("test-secret");
+    let outsider_token = test_token_with_identity("test-secret", "user", "user-456");
+
+    let thread_request = json!({
+        "scope_id": "scope-retention-chat",
+        "privacy_level": "private",
+    });
+    let create_request = Request::builder()
+        .method("POST")
+        .uri("/v1/chat/threads")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {owner_token}"))
+        .header("x-request-id", "chat-retention-1")
+        .header("x-correlation-id", "corr-chat-retention-1")
+        .body(Body::from(thread_request.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(create_request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let thread: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(thread.get("retention"), Some(&json!("forever")));
+    let thread_id = thread
+        .get("thread_id")
+        .and_then(|value| value.as_str())
+        .expect("thread_id");
+
+    let retention_request = |token: &str, retention: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/v1/chat/threads/{thread_id}/retention"))
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {token}"))
+            .body(Body::from(json!({ "retention": retention }).to_string()))
+            .unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(retention_request(&outsider_token, "24h"))
+        .await
+        .expect("response");
+    assert_ne!(response.status(), StatusCode::OK);
+
+    let response = app
+        .clone()
+        .oneshot(retention_request(&owner_token, "1y"))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = app
+        .oneshot(retention_request(&owner_token, "24h"))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let updated: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(updated.get("retention"), Some(&json!("24h")));
+}
+
+#[tokio::test]
+async fn chat_unread_counts_and_seen_by_follow_read_cursors() {
+    let app = test_app();
+    let owner_token = test_token("test-secret");
+    let member_token = test_token_with_identity("test-secret", "user", "user-456");
+    let json_request =
+        |method: &str, uri: String, token: &str, request_id: &str, body: serde_json::Value| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {token}"))
+                .header("x-request-id", request_id)
+                .header("x-correlation-id", format!("corr-{request_id}"))
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+    let get_request = |uri: String, token: &str| {
+        Request::builder()
+            .method("GET")
+            .uri(uri)
+            .header("authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            "/v1/chat/threads".to_string(),
+            &owner_token,
+            "chat-unread-thread",
+            json!({ "scope_id": "scope-unread-chat", "privacy_level": "public" }),
+        ))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let thread: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    let thread_id = thread
+        .get("thread_id")
+        .and_then(|value| value.as_str())
+        .expect("thread_id")
+        .to_string();
+
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            format!("/v1/chat/threads/{thread_id}/join"),
+            &member_token,
+            "chat-unread-join",
+            json!({}),
+        ))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            format!("/v1/chat/threads/{thread_id}/messages/send"),
+            &owner_token,
+            "chat-unread-msg",
+            json!({ "body": "kerja bakti besok", "attachments": [] }),
+        ))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let message: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    let message_id = message
+        .get("message_id")
+        .and_then(|value| value.as_str())
+        .expect("message_id")
+        .to_string();
+
+    let response = app
+        .clone()
+        .oneshot(get_request("/v1/chat/threads".to_string(), &member_token))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let threads: Vec<serde_json::Value> = serde_json::from_slice(&body).expect("json");
+    let listed = threads
+        .iter()
+        .find(|item| item.get("thread_id") == Some(&json!(thread_id)))
+        .expect("thread listed");
+    assert_eq!(listed.get("unread_count"), Some(&json!(1)));
+
+    let response = app
+        .clone()
+        .oneshot(get_request(
+            "/v1/chat/unread-count".to_string(),
+            &member_token,
+        ))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let summary: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(summary.get("total_unread"), Some(&json!(1)));
+
     let response = app
         .clone()
-        .oneshot(list_messages_request)
+        .oneshot(json_request(
+            "POST",
+            format!("/v1/chat/threads/{thread_id}/read-cursor"),
+            &member_token,
+            "chat-unread-cursor",
+            json!({ "message_id": message_id }),
+        ))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .clone()
+        .oneshot(get_request(
+            format!("/v1/chat/threads/{thread_id}/messages/{message_id}/seen-by"),
+            &owner_token,
+        ))
         .await
         .expect("response");
     assert_eq!(response.status(), StatusCode::OK);
     let body = to_bytes(response.into_body(), usize::MAX)
         .await
         .expect("body");
-    let messages: Vec<serde_json::Value> = serde_json::from_slice(&body).expect("json");
-    assert_eq!(messages.len(), 2);
-    let listed_ids: Vec<String> = messages
-        .iter()
-        .map(|item| {
-            item.get("message_id")
-                .and_then(|value| value.as_str())
-                .expect("message_id")
-                .to_string()
-        })
-        .collect();
-    assert_eq!(listed_ids, vec![sent_ids[1].clone(), sent_ids[2].clone()]);
+    let receipts: Vec<serde_json::Value> = serde_json::from_slice(&body).expect("json");
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].get("user_id"), Some(&json!("user-456")));
+
+    let response = app
+        .oneshot(get_request(
+            "/v1/chat/unread-count".to_string(),
+            &member_token,
+        ))
+        .await
+        .expect("response");
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let summary: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(summary.get("total_unread"), Some(&json!(0)));
 }
 
 #[tokio::test]
-async fn chat_read_cursor_is_member_only() {
+async fn chat_thread_export_renders_transcript_for_members_only() {
     let app = test_app();
     let owner_token = test_token("test-secret");
     let outsider_token = test_token_with_identity("test-secret", "user", "user-456");
 
     let thread_request = json!({
-        "scope_id": "scope-private-chat",
+        "scope_id": "scope-export-chat",
         "privacy_level": "private",
     });
     let create_request = Request::builder()
@@ -2763,8 +4216,8 @@ async fn chat_read_cursor_is_member_only() {
         .uri("/v1/chat/threads")
         .header("content-type", "application/json")
         .header("authorization", format!("Bearer {owner_token}"))
-        .header("x-request-id", "chat-priv-1")
-        .header("x-correlation-id", "corr-chat-priv-1")
+        .header("x-request-id", "chat-export-1")
+        .header("x-correlation-id", "corr-chat-export-1")
         .body(Body::from(thread_request.to_string()))
         .unwrap();
     let response = app.clone().oneshot(create_request).await.expect("response");
@@ -2778,14 +4231,80 @@ async fn chat_read_cursor_is_member_only() {
         .and_then(|value| value.as_str())
         .expect("thread_id");
 
-    let read_cursor_request = Request::builder()
-        .method("GET")
-        .uri(format!("/v1/chat/threads/{thread_id}/read-cursor"))
-        .header("authorization", format!("Bearer {outsider_token}"))
-        .body(Body::empty())
+    let message_request = json!({
+        "body": "rapat warga jam 7",
+        "attachments": [],
+    });
+    let send_request = Request::builder()
+        .method("POST")
+        .uri(format!("/v1/chat/threads/{thread_id}/messages/send"))
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {owner_token}"))
+        .header("x-request-id", "chat-export-msg-1")
+        .body(Body::from(message_request.to_string()))
         .unwrap();
-    let response = app.oneshot(read_cursor_request).await.expect("response");
-    assert_ne!(response.status(), StatusCode::OK);
+    let response = app.clone().oneshot(send_request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let export_request = |token: &str, query: &str| {
+        Request::builder()
+            .method("GET")
+            .uri(format!("/v1/chat/threads/{thread_id}/export?{query}"))
+            .header("authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(export_request(&owner_token, "format=markdown"))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response
+            .headers()
+            .get("content-type")
+            .and_then(|value| value.to_str().ok()),
+        Some("text/markdown; charset=utf-8")
+    );
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let markdown = String::from_utf8(body.to_vec()).expect("utf8");
+    assert!(markdown.starts_with(&format!("# Chat transcript: thread {thread_id}")));
+    assert!(markdown.contains("- Messages: 1"));
+    assert!(markdown.contains("> rapat warga jam 7"));
+
+    let response = app
+        .clone()
+        .oneshot(export_request(&owner_token, "format=json"))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let export: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(export.get("message_count"), Some(&json!(1)));
+    assert_eq!(
+        export.pointer("/messages/0/body"),
+        Some(&json!("rapat warga jam 7"))
+    );
+    assert!(export.pointer("/messages/0/author_name").is_some());
+
+    let response = app
+        .clone()
+        .oneshot(export_request(&owner_token, "format=pdf"))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = app
+        .oneshot(export_request(&outsider_token, "format=text"))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
 }
 
 #[tokio::test]
@@ -2855,69 +4374,445 @@ async fn chat_poll_messages_endpoint() {
     );
 }
 
+fn test_png_bytes(width: u32, height: u32) -> Vec<u8> {
+    let image = image::RgbImage::from_pixel(width, height, image::Rgb([12, 120, 200]));
+    let mut bytes = Vec::new();
+    image
+        .write_to(
+            &mut std::io::Cursor::new(&mut bytes),
+            image::ImageFormat::Png,
+        )
+        .expect("encode png");
+    bytes
+}
+
+fn chat_attachment_multipart_body(
+    boundary: &str,
+    declared_content_type: &str,
+    file_bytes: &[u8],
+    thread_id: Option<&str>,
+) -> Vec<u8> {
+    let mut body = Vec::new();
+    if let Some(thread_id) = thread_id {
+        body.extend_from_slice(
+            format!(
+                "--{boundary}\r\n\
+Content-Disposition: form-data; name=\"thread_id\"\r\n\
+\r\n\
+{thread_id}\r\n"
+            )
+            .as_bytes(),
+        );
+    }
+    body.extend_from_slice(
+        format!(
+            "--{boundary}\r\n\
+Content-Disposition: form-data; name=\"file\"; filename=\"foto.png\"\r\n\
+Content-Type: {declared_content_type}\r\n\
+\r\n"
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(file_bytes);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    body
+}
+
+fn chat_attachment_upload_request(token: &str, body: Vec<u8>) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri("/v1/chat/attachments/upload")
+        .header(
+            CONTENT_TYPE,
+            "multipart/form-data; boundary=----gotong-chat-upload-test",
+        )
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::from(body))
+        .expect("upload request")
+}
+
+#[tokio::test]
+async fn chat_attachment_upload_and_signed_download_flow() {
+    let app = test_app();
+    let token = test_token("test-secret");
+    let png = test_png_bytes(640, 480);
+    let multipart_body = chat_attachment_multipart_body(
+        "----gotong-chat-upload-test",
+        "application/octet-stream",
+        &png,
+        None,
+    );
+
+    let upload_response = app
+        .clone()
+        .oneshot(chat_attachment_upload_request(&token, multipart_body))
+        .await
+        .expect("upload response");
+    assert_eq!(upload_response.status(), StatusCode::OK);
+    let upload_body = to_bytes(upload_response.into_body(), usize::MAX)
+        .await
+        .expect("upload body");
+    let uploaded: serde_json::Value = serde_json::from_slice(&upload_body).expect("upload json");
+    let download_url = uploaded
+        .get("url")
+        .and_then(|value| value.as_str())
+        .expect("download url");
+    assert_eq!(uploaded.get("media_type"), Some(&json!("image")));
+    assert_eq!(uploaded.get("mime_type"), Some(&json!("image/png")));
+    assert_eq!(uploaded.get("width"), Some(&json!(640)));
+    assert_eq!(uploaded.get("height"), Some(&json!(480)));
+
+    let download_request = Request::builder()
+        .method("GET")
+        .uri(download_url)
+        .body(Body::empty())
+        .expect("download request");
+    let download_response = app
+        .clone()
+        .oneshot(download_request)
+        .await
+        .expect("download response");
+    assert_eq!(download_response.status(), StatusCode::OK);
+    assert_eq!(
+        download_response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok()),
+        Some("image/png")
+    );
+    let download_body = to_bytes(download_response.into_body(), usize::MAX)
+        .await
+        .expect("download body");
+    assert_eq!(download_body.as_ref(), png.as_slice());
+
+    let thumbnail_url = uploaded
+        .get("thumbnail_url")
+        .and_then(|value| value.as_str())
+        .expect("thumbnail url");
+    let thumbnail_request = Request::builder()
+        .method("GET")
+        .uri(thumbnail_url)
+        .body(Body::empty())
+        .expect("thumbnail request");
+    let thumbnail_response = app
+        .clone()
+        .oneshot(thumbnail_request)
+        .await
+        .expect("thumbnail response");
+    assert_eq!(thumbnail_response.status(), StatusCode::OK);
+    assert_eq!(
+        thumbnail_response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok()),
+        Some("image/jpeg")
+    );
+    let thumbnail_body = to_bytes(thumbnail_response.into_body(), usize::MAX)
+        .await
+        .expect("thumbnail body");
+    let thumbnail = image::load_from_memory(&thumbnail_body).expect("thumbnail image");
+    assert_eq!((thumbnail.width(), thumbnail.height()), (320, 240));
+
+    let attachment_id = uploaded
+        .get("attachment_id")
+        .and_then(|value| value.as_str())
+        .expect("attachment id");
+    let record_request = Request::builder()
+        .method("GET")
+        .uri(format!("/v1/chat/attachments/{attachment_id}"))
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .expect("record request");
+    let record_response = app
+        .clone()
+        .oneshot(record_request)
+        .await
+        .expect("record response");
+    assert_eq!(record_response.status(), StatusCode::OK);
+    let record_body = to_bytes(record_response.into_body(), usize::MAX)
+        .await
+        .expect("record body");
+    let record: serde_json::Value = serde_json::from_slice(&record_body).expect("record json");
+    assert_eq!(record.get("width"), Some(&json!(640)));
+    assert!(
+        record
+            .get("thumbnail_url")
+            .and_then(|value| value.as_str())
+            .is_some()
+    );
+}
+
+#[tokio::test]
+async fn chat_attachment_upload_rejects_bytes_outside_allowlist() {
+    let app = test_app();
+    let token = test_token("test-secret");
+    let multipart_body = chat_attachment_multipart_body(
+        "----gotong-chat-upload-test",
+        "image/png",
+        b"<html><script>alert(1)</script></html>",
+        None,
+    );
+
+    let response = app
+        .oneshot(chat_attachment_upload_request(&token, multipart_body))
+        .await
+        .expect("upload response");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn chat_attachment_upload_enforces_thread_quota() {
+    let mut config = test_config();
+    config.chat_attachment_thread_quota_bytes = 64;
+    let store = InMemoryIdempotencyStore::new("test");
+    let state = AppState::with_idempotency_store(config, Arc::new(store));
+    let app = routes::router(state);
+    let token = test_token("test-secret");
+
+    let create_request = Request::builder()
+        .method("POST")
+        .uri("/v1/chat/threads")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {token}"))
+        .header("x-request-id", "chat-attachment-quota-1")
+        .body(Body::from(
+            json!({
+                "scope_id": "scope-chat-attachment-quota",
+                "privacy_level": "public",
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let response = app.clone().oneshot(create_request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let thread: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    let thread_id = thread
+        .get("thread_id")
+        .and_then(|value| value.as_str())
+        .expect("thread_id");
+
+    let png = test_png_bytes(32, 32);
+    let over_quota = app
+        .clone()
+        .oneshot(chat_attachment_upload_request(
+            &token,
+            chat_attachment_multipart_body(
+                "----gotong-chat-upload-test",
+                "image/png",
+                &png,
+                Some(thread_id),
+            ),
+        ))
+        .await
+        .expect("upload response");
+    assert_eq!(over_quota.status(), StatusCode::BAD_REQUEST);
+
+    let without_thread = app
+        .oneshot(chat_attachment_upload_request(
+            &token,
+            chat_attachment_multipart_body("----gotong-chat-upload-test", "image/png", &png, None),
+        ))
+        .await
+        .expect("upload response");
+    assert_eq!(without_thread.status(), StatusCode::OK);
+}
+
+fn tus_request(method: &str, uri: &str, token: &str) -> axum::http::request::Builder {
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("tus-resumable", "1.0.0")
+        .header("authorization", format!("Bearer {token}"))
+}
+
+async fn tus_upload_offset(app: &axum::Router, uri: &str, token: &str) -> u64 {
+    let response = app
+        .clone()
+        .oneshot(tus_request("HEAD", uri, token).body(Body::empty()).unwrap())
+        .await
+        .expect("head response");
+    assert_eq!(response.status(), StatusCode::OK);
+    response
+        .headers()
+        .get("upload-offset")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .expect("upload offset")
+}
+
 #[tokio::test]
-async fn chat_attachment_upload_and_signed_download_flow() {
+async fn chat_attachment_tus_upload_resumes_and_records_attachment() {
     let app = test_app();
     let token = test_token("test-secret");
-    let boundary = "----gotong-chat-upload-test";
-    let multipart_body = format!(
-        "--{boundary}\r\n\
-Content-Disposition: form-data; name=\"file\"; filename=\"foto.png\"\r\n\
-Content-Type: image/png\r\n\
-\r\n\
-PNGDATA\r\n\
---{boundary}--\r\n"
+    let png = test_png_bytes(400, 300);
+
+    let create_response = app
+        .clone()
+        .oneshot(
+            tus_request("POST", "/v1/chat/attachments/tus", &token)
+                .header("upload-length", png.len().to_string())
+                // "photo.png"
+                .header("upload-metadata", "filename cGhvdG8ucG5n")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .expect("create response");
+    assert_eq!(create_response.status(), StatusCode::CREATED);
+    let location = create_response
+        .headers()
+        .get("location")
+        .and_then(|value| value.to_str().ok())
+        .expect("location")
+        .to_string();
+    assert_eq!(tus_upload_offset(&app, &location, &token).await, 0);
+
+    let split = png.len() / 2;
+    let first_chunk = app
+        .clone()
+        .oneshot(
+            tus_request("PATCH", &location, &token)
+                .header(CONTENT_TYPE, "application/offset+octet-stream")
+                .header("upload-offset", "0")
+                .body(Body::from(png[..split].to_vec()))
+                .unwrap(),
+        )
+        .await
+        .expect("patch response");
+    assert_eq!(first_chunk.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        tus_upload_offset(&app, &location, &token).await,
+        split as u64
     );
 
-    let upload_request = Request::builder()
-        .method("POST")
-        .uri("/v1/chat/attachments/upload")
-        .header(
-            CONTENT_TYPE,
-            format!("multipart/form-data; boundary={boundary}"),
+    let stale_offset = app
+        .clone()
+        .oneshot(
+            tus_request("PATCH", &location, &token)
+                .header(CONTENT_TYPE, "application/offset+octet-stream")
+                .header("upload-offset", "0")
+                .body(Body::from(png[split..].to_vec()))
+                .unwrap(),
         )
-        .header("authorization", format!("Bearer {token}"))
-        .body(Body::from(multipart_body))
-        .expect("upload request");
-    let upload_response = app
+        .await
+        .expect("patch response");
+    assert_eq!(stale_offset.status(), StatusCode::CONFLICT);
+
+    let last_chunk = app
         .clone()
-        .oneshot(upload_request)
+        .oneshot(
+            tus_request("PATCH", &location, &token)
+                .header(CONTENT_TYPE, "application/offset+octet-stream")
+                .header("upload-offset", split.to_string())
+                .body(Body::from(png[split..].to_vec()))
+                .unwrap(),
+        )
         .await
-        .expect("upload response");
-    assert_eq!(upload_response.status(), StatusCode::OK);
-    let upload_body = to_bytes(upload_response.into_body(), usize::MAX)
+        .expect("patch response");
+    assert_eq!(last_chunk.status(), StatusCode::NO_CONTENT);
+
+    let attachment_id = location.rsplit('/').next().expect("upload id");
+    let record_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/v1/chat/attachments/{attachment_id}"))
+                .header("authorization", format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
         .await
-        .expect("upload body");
-    let uploaded: serde_json::Value = serde_json::from_slice(&upload_body).expect("upload json");
-    let download_url = uploaded
-        .get("url")
-        .and_then(|value| value.as_str())
-        .expect("download url");
-    assert_eq!(uploaded.get("media_type"), Some(&json!("image")));
-    assert_eq!(uploaded.get("mime_type"), Some(&json!("image/png")));
+        .expect("record response");
+    assert_eq!(record_response.status(), StatusCode::OK);
+    let body = to_bytes(record_response.into_body(), usize::MAX)
+        .await
+        .expect("record body");
+    let record: serde_json::Value = serde_json::from_slice(&body).expect("record json");
+    assert_eq!(record.get("file_name"), Some(&json!("photo.png")));
+    assert_eq!(record.get("mime_type"), Some(&json!("image/png")));
+    assert_eq!(record.get("width"), Some(&json!(400)));
+
+    let finished = app
+        .oneshot(
+            tus_request("HEAD", &location, &token)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .expect("head response");
+    assert_eq!(finished.status(), StatusCode::NOT_FOUND);
+}
 
-    let download_request = Request::builder()
-        .method("GET")
-        .uri(download_url)
-        .body(Body::empty())
-        .expect("download request");
-    let download_response = app
+#[tokio::test]
+async fn chat_attachment_tus_concurrent_patches_append_once() {
+    let app = test_app();
+    let token = test_token("test-secret");
+    let png = test_png_bytes(400, 300);
+
+    let create_response = app
         .clone()
-        .oneshot(download_request)
+        .oneshot(
+            tus_request("POST", "/v1/chat/attachments/tus", &token)
+                .header("upload-length", png.len().to_string())
+                .body(Body::empty())
+                .unwrap(),
+        )
         .await
-        .expect("download response");
-    assert_eq!(download_response.status(), StatusCode::OK);
+        .expect("create response");
+    assert_eq!(create_response.status(), StatusCode::CREATED);
+    let location = create_response
+        .headers()
+        .get("location")
+        .and_then(|value| value.to_str().ok())
+        .expect("location")
+        .to_string();
+
+    let split = png.len() / 2;
+    let patch = |app: axum::Router| {
+        app.oneshot(
+            tus_request("PATCH", &location, &token)
+                .header(CONTENT_TYPE, "application/offset+octet-stream")
+                .header("upload-offset", "0")
+                .body(Body::from(png[..split].to_vec()))
+                .unwrap(),
+        )
+    };
+    let (first, second) = tokio::join!(patch(app.clone()), patch(app.clone()));
+    let mut statuses = [
+        first.expect("patch response").status(),
+        second.expect("patch response").status(),
+    ];
+    statuses.sort();
+    assert_eq!(statuses, [StatusCode::NO_CONTENT, StatusCode::CONFLICT]);
     assert_eq!(
-        download_response
-            .headers()
-            .get(CONTENT_TYPE)
-            .and_then(|value| value.to_str().ok()),
-        Some("image/png")
+        tus_upload_offset(&app, &location, &token).await,
+        split as u64
     );
-    let download_body = to_bytes(download_response.into_body(), usize::MAX)
+}
+
+#[tokio::test]
+async fn chat_attachment_direct_upload_requires_s3_backend() {
+    let app = test_app();
+    let token = test_token("test-secret");
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/chat/attachments/direct-uploads")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {token}"))
+                .body(Body::from(
+                    json!({ "file_name": "clip.mp4", "size_bytes": 1024 }).to_string(),
+                ))
+                .unwrap(),
+        )
         .await
-        .expect("download body");
-    assert_eq!(download_body.as_ref(), b"PNGDATA");
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
@@ -2999,6 +4894,7 @@ async fn discovery_feed_and_search_endpoints() {
             request_ts_ms: Some(1_000),
             participant_ids: vec!["user-456".into()],
             payload: None,
+            location: None,
         })
         .await
         .expect("seed feed-a");
@@ -3018,6 +4914,7 @@ async fn discovery_feed_and_search_endpoints() {
             request_ts_ms: Some(2_000),
             participant_ids: vec!["user-789".into()],
             payload: None,
+            location: None,
         })
         .await
         .expect("seed feed-b");
@@ -3037,6 +4934,7 @@ async fn discovery_feed_and_search_endpoints() {
             request_ts_ms: Some(3_000),
             participant_ids: vec!["user-123".into()],
             payload: None,
+            location: None,
         })
         .await
         .expect("seed feed-c");
@@ -3155,6 +5053,168 @@ async fn discovery_feed_and_search_endpoints() {
     assert!(private_feed_ids.contains(&feed_c.feed_id.as_str()));
 }
 
+#[tokio::test]
+async fn discovery_feed_and_search_filter_by_distance() {
+    let app = test_app();
+    let token = test_token("test-secret");
+
+    for (request_id, title, location) in [
+        (
+            "geo-near",
+            "Posko banjir Menteng",
+            json!({ "lat": -6.1950, "lng": 106.8231 }),
+        ),
+        (
+            "geo-far",
+            "Posko banjir Bandung",
+            json!({ "lat": -6.9175, "lng": 107.6191 }),
+        ),
+        ("geo-none", "Posko banjir tanpa lokasi", json!("RT 05")),
+    ] {
+        let request = Request::builder()
+            .method("POST")
+            .uri("/v1/contributions")
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {token}"))
+            .header("x-request-id", request_id)
+            .body(Body::from(
+                json!({
+                    "mode": "komunitas",
+                    "contribution_type": "task_completion",
+                    "title": title,
+                    "description": "Butuh relawan",
+                    "skill_ids": [],
+                    "metadata": { "location": location }
+                })
+                .to_string(),
+            ))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let get_json = |uri: String| {
+        let app = app.clone();
+        let token = token.clone();
+        async move {
+            let request = Request::builder()
+                .method("GET")
+                .uri(uri)
+                .header("authorization", format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap();
+            let response = app.oneshot(request).await.expect("response");
+            let status = response.status();
+            let body = to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("body");
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap_or_default(),
+            )
+        }
+    };
+
+    let (status, feed) =
+        get_json("/v1/feed?near_lat=-6.175392&near_lng=106.827153&radius_m=5000".to_string()).await;
+    assert_eq!(status, StatusCode::OK);
+    let items = feed["items"].as_array().expect("items");
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["title"], "Posko banjir Menteng");
+    let distance_m = items[0]["distance_m"].as_f64().expect("distance_m");
+    assert!((2_000.0..2_400.0).contains(&distance_m), "{distance_m}");
+    assert_eq!(items[0]["location"]["lng"], json!(106.8231));
+
+    let (status, search) = get_json(
+        "/v1/search?query_text=posko&near_lat=-6.9&near_lng=107.6&radius_m=10000".to_string(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let results = search["items"].as_array().expect("items");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["item"]["title"], "Posko banjir Bandung");
+    assert!(results[0]["item"]["distance_m"].as_f64().is_some());
+
+    let (status, _) = get_json("/v1/feed".to_string()).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = get_json("/v1/feed?near_lat=-6.2".to_string()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) =
+        get_json("/v1/feed?near_lat=-6.2&near_lng=106.8&radius_m=80000".to_string()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn discovery_feed_trending_serves_cached_snapshot() {
+    let (state, app) = test_app_state_router();
+    let token = test_token("test-secret");
+    let topic = |kind, entity_id: &str, burst_score| TrendingTopic {
+        kind,
+        entity_id: entity_id.to_string(),
+        label: entity_id.to_string(),
+        window_count: 5,
+        baseline_count: 0.25,
+        burst_score,
+    };
+    state
+        .trending_store
+        .put_snapshots(
+            &[TrendingSnapshot {
+                scope_id: Some("rw-07".to_string()),
+                computed_at_ms: 42,
+                window_ms: 21_600_000,
+                baseline_windows: 28,
+                item_count: 9,
+                topics: vec![
+                    topic(TrendingTopicKind::Concept, "Q8068", 9.5),
+                    topic(TrendingTopicKind::Place, "place:rt-03", 7.0),
+                    topic(TrendingTopicKind::Concept, "Q1", 3.0),
+                ],
+            }],
+            60_000,
+        )
+        .await
+        .expect("snapshot");
+
+    let get_json = |uri: &'static str| {
+        let app = app.clone();
+        let token = token.clone();
+        async move {
+            let request = Request::builder()
+                .method("GET")
+                .uri(uri)
+                .header("authorization", format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap();
+            let response = app.oneshot(request).await.expect("response");
+            let status = response.status();
+            let body = to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("body");
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap_or_default(),
+            )
+        }
+    };
+
+    let (status, body) = get_json("/v1/feed/trending?scope_id=rw-07&kind=concept&limit=1").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["computed_at_ms"], 42);
+    let topics = body["topics"].as_array().expect("topics");
+    assert_eq!(topics.len(), 1);
+    assert_eq!(topics[0]["entity_id"], "Q8068");
+    assert_eq!(topics[0]["kind"], "concept");
+
+    let (status, body) = get_json("/v1/feed/trending").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["computed_at_ms"].is_null());
+    assert_eq!(body["topics"], json!([]));
+
+    let (status, _) = get_json("/v1/feed/trending?limit=0").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn discovery_feed_suggestions_endpoint_returns_aggregated_entities() {
     let (state, app) = test_app_state_router();
@@ -3192,6 +5252,7 @@ async fn discovery_feed_suggestions_endpoint_returns_aggregated_entities() {
                     ]
                 }
             })),
+            location: None,
         })
         .await
         .expect("seed suggestion row one");
@@ -3227,6 +5288,7 @@ async fn discovery_feed_suggestions_endpoint_returns_aggregated_entities() {
                     ]
                 }
             })),
+            location: None,
         })
         .await
         .expect("seed suggestion row two");
@@ -3256,6 +5318,7 @@ async fn discovery_feed_suggestions_endpoint_returns_aggregated_entities() {
                     ]
                 }
             })),
+            location: None,
         })
         .await
         .expect("seed suggestion hidden row");
@@ -3330,6 +5393,7 @@ async fn discovery_feed_preference_endpoints_persist_monitor_and_follow_state()
                     ]
                 }
             })),
+            location: None,
         })
         .await
         .expect("seed feed row");
@@ -3411,31 +5475,544 @@ async fn discovery_feed_preference_endpoints_persist_monitor_and_follow_state()
         .expect("entity tag");
     assert_eq!(first_entity_tag.get("followed"), Some(&json!(true)));
 
-    let suggestions_request = Request::builder()
-        .method("GET")
-        .uri("/v1/feed/suggestions?scope_id=scope-rw-01&limit=10")
-        .header(
-            "authorization",
-            format!("Bearer {}", test_token("test-secret")),
-        )
-        .body(Body::empty())
-        .unwrap();
-    let suggestions_response = app
-        .clone()
-        .oneshot(suggestions_request)
+    let suggestions_request = Request::builder()
+        .method("GET")
+        .uri("/v1/feed/suggestions?scope_id=scope-rw-01&limit=10")
+        .header(
+            "authorization",
+            format!("Bearer {}", test_token("test-secret")),
+        )
+        .body(Body::empty())
+        .unwrap();
+    let suggestions_response = app
+        .clone()
+        .oneshot(suggestions_request)
+        .await
+        .expect("suggestions response");
+    assert_eq!(suggestions_response.status(), StatusCode::OK);
+    let suggestions_body = to_bytes(suggestions_response.into_body(), usize::MAX)
+        .await
+        .expect("suggestions body");
+    let suggestions: Vec<serde_json::Value> =
+        serde_json::from_slice(&suggestions_body).expect("suggestions json");
+    let rt05 = suggestions
+        .iter()
+        .find(|item| item.get("entity_id") == Some(&json!("ent-rt05")))
+        .expect("rt05 suggestion");
+    assert_eq!(rt05.get("followed"), Some(&json!(true)));
+
+    let list_follows = |app: axum::Router| async move {
+        let request = Request::builder()
+            .method("GET")
+            .uri("/v1/feed/preferences/follow")
+            .header(
+                "authorization",
+                format!("Bearer {}", test_token("test-secret")),
+            )
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.expect("follow list response");
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("follow list body");
+        let list: serde_json::Value = serde_json::from_slice(&body).expect("follow list json");
+        list["items"]
+            .as_array()
+            .expect("follow items")
+            .iter()
+            .map(|item| item["entity_id"].as_str().unwrap_or_default().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        list_follows(app.clone()).await,
+        vec!["ent-rt05".to_string()]
+    );
+
+    let unfollow_request = Request::builder()
+        .method("POST")
+        .uri("/v1/feed/preferences/follow/ent-rt05")
+        .header(CONTENT_TYPE, "application/json")
+        .header(
+            "authorization",
+            format!("Bearer {}", test_token("test-secret")),
+        )
+        .body(Body::from(r#"{"followed":false}"#))
+        .unwrap();
+    let unfollow_response = app
+        .clone()
+        .oneshot(unfollow_request)
+        .await
+        .expect("unfollow response");
+    assert_eq!(unfollow_response.status(), StatusCode::OK);
+    assert!(list_follows(app.clone()).await.is_empty());
+}
+
+#[tokio::test]
+async fn involvement_feed_includes_items_tagged_with_followed_entities() {
+    let (state, app) = test_app_state_router();
+    let service = DiscoveryService::new(state.feed_repo.clone(), state.notification_repo.clone());
+    for (request_id, entity_id) in [("followed", "ent-rt05"), ("unfollowed", "ent-rt06")] {
+        service
+            .ingest_feed(FeedIngestInput {
+                source_type: FEED_SOURCE_CONTRIBUTION.to_string(),
+                source_id: format!("witness-{request_id}"),
+                actor: actor_identity_for_tests("user-456"),
+                title: format!("Tagged {request_id}"),
+                summary: None,
+                scope_id: Some("scope-rw-01".into()),
+                privacy_level: Some("public".into()),
+                occurred_at_ms: Some(1_000),
+                request_id: format!("feed-{request_id}"),
+                correlation_id: format!("corr-{request_id}"),
+                request_ts_ms: Some(1_000),
+                participant_ids: vec![],
+                payload: Some(json!({
+                    "enrichment": {
+                        "entity_tags": [
+                            { "entity_id": entity_id, "entity_type": "lingkungan", "label": entity_id }
+                        ]
+                    }
+                })),
+                location: None,
+            })
+            .await
+            .expect("seed feed row");
+    }
+
+    let follow_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/feed/preferences/follow/ent-rt05")
+                .header(CONTENT_TYPE, "application/json")
+                .header(
+                    "authorization",
+                    format!("Bearer {}", test_token("test-secret")),
+                )
+                .body(Body::from(r#"{"followed":true}"#))
+                .unwrap(),
+        )
+        .await
+        .expect("follow response");
+    assert_eq!(follow_response.status(), StatusCode::OK);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/v1/feed?involvement_only=true")
+                .header(
+                    "authorization",
+                    format!("Bearer {}", test_token("test-secret")),
+                )
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .expect("feed response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("feed body");
+    let feed: serde_json::Value = serde_json::from_slice(&body).expect("feed json");
+    let titles: Vec<&str> = feed["items"]
+        .as_array()
+        .expect("items")
+        .iter()
+        .filter_map(|item| item["title"].as_str())
+        .collect();
+    assert_eq!(titles, vec!["Tagged followed"]);
+}
+
+#[tokio::test]
+async fn digest_settings_round_trip_and_signed_unsubscribe_link_turns_digests_off() {
+    let (state, app) = test_app_state_router();
+    let auth_header = format!("Bearer {}", test_token("test-secret"));
+    let get_settings = |app: axum::Router, auth_header: String| async move {
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/v1/notifications/digest-settings")
+                    .header("authorization", auth_header)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("digest settings response");
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("digest settings body");
+        serde_json::from_slice::<serde_json::Value>(&body).expect("digest settings json")
+    };
+
+    let initial = get_settings(app.clone(), auth_header.clone()).await;
+    assert_eq!(initial["configured"], json!(false));
+    assert_eq!(initial["frequency"], json!("off"));
+
+    let update_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri("/v1/notifications/digest-settings")
+                .header(CONTENT_TYPE, "application/json")
+                .header("authorization", auth_header.clone())
+                .body(Body::from(
+                    r#"{"email":"Warga@Example.org","locale":"en","frequency":"daily","timezone":"Asia/Makassar","send_hour_local":6}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .expect("update digest settings response");
+    assert_eq!(update_response.status(), StatusCode::OK);
+    let updated = get_settings(app.clone(), auth_header.clone()).await;
+    assert_eq!(updated["configured"], json!(true));
+    assert_eq!(updated["email"], json!("warga@example.org"));
+    assert_eq!(updated["locale"], json!("en"));
+    assert_eq!(updated["frequency"], json!("daily"));
+    assert_eq!(updated["timezone"], json!("Asia/Makassar"));
+    assert_eq!(updated["send_hour_local"], json!(6));
+
+    let invalid_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri("/v1/notifications/digest-settings")
+                .header(CONTENT_TYPE, "application/json")
+                .header("authorization", auth_header.clone())
+                .body(Body::from(r#"{"timezone":"Mars/Olympus"}"#))
+                .unwrap(),
+        )
+        .await
+        .expect("invalid digest settings response");
+    assert_eq!(invalid_response.status(), StatusCode::BAD_REQUEST);
+
+    let token = gotong_domain::digest::sign_unsubscribe_token(
+        &state.config.digest_unsubscribe_secret,
+        "user-123",
+    )
+    .expect("unsubscribe token");
+    let forged_token =
+        gotong_domain::digest::sign_unsubscribe_token("some-other-digest-secret", "user-123")
+            .expect("forged token");
+    let forged_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/v1/digest/unsubscribe?token={forged_token}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .expect("forged unsubscribe response");
+    assert_eq!(forged_response.status(), StatusCode::FORBIDDEN);
+
+    let confirmation_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/v1/digest/unsubscribe?token={token}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .expect("unsubscribe confirmation response");
+    assert_eq!(confirmation_response.status(), StatusCode::OK);
+    let confirmation_body = to_bytes(confirmation_response.into_body(), usize::MAX)
+        .await
+        .expect("unsubscribe confirmation body");
+    let confirmation_page = String::from_utf8_lossy(&confirmation_body);
+    assert!(confirmation_page.contains("method=\"post\""));
+    assert!(confirmation_page.contains(&format!("?token={token}")));
+    let still_subscribed = get_settings(app.clone(), auth_header.clone()).await;
+    assert_eq!(still_subscribed["frequency"], json!("daily"));
+
+    let unsubscribe_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/v1/digest/unsubscribe?token={token}"))
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::from("List-Unsubscribe=One-Click"))
+                .unwrap(),
+        )
+        .await
+        .expect("unsubscribe response");
+    assert_eq!(unsubscribe_response.status(), StatusCode::OK);
+    let unsubscribe_body = to_bytes(unsubscribe_response.into_body(), usize::MAX)
+        .await
+        .expect("unsubscribe body");
+    assert!(
+        String::from_utf8_lossy(&unsubscribe_body).contains("no longer receive Gotong digests")
+    );
+
+    let after = get_settings(app, auth_header).await;
+    assert_eq!(after["frequency"], json!("off"));
+    assert_eq!(after["email"], json!("warga@example.org"));
+}
+
+#[tokio::test]
+async fn notification_preferences_opt_out_and_quiet_hours_gate_ingested_notifications() {
+    let (state, app) = test_app_state_router();
+    let auth_header = format!("Bearer {}", test_token("test-secret"));
+    let send = |app: axum::Router, method: &str, uri: &str, body: Option<String>| {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("authorization", auth_header.clone());
+        if body.is_some() {
+            request = request.header(CONTENT_TYPE, "application/json");
+        }
+        let request = request
+            .body(body.map(Body::from).unwrap_or_else(Body::empty))
+            .unwrap();
+        async move {
+            let response = app.oneshot(request).await.expect("response");
+            let status = response.status();
+            let body = to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("body");
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap_or_default(),
+            )
+        }
+    };
+
+    let (status, defaults) = send(app.clone(), "GET", "/v1/notifications/preferences", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(defaults["types"], json!({}));
+    assert_eq!(defaults["quiet_hours"], serde_json::Value::Null);
+
+    let (status, _) = send(
+        app.clone(),
+        "PUT",
+        "/v1/notifications/preferences",
+        Some(
+            r#"{"quiet_hours":{"start_local":"25:00","end_local":"06:00","timezone":"UTC"}}"#
+                .into(),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // A UTC window around the current time so the hold is deterministic.
+    let now = time::OffsetDateTime::now_utc();
+    let hh_mm = |at: time::OffsetDateTime| format!("{:02}:{:02}", at.hour(), at.minute());
+    let update = json!({
+        "types": { "Vouch": { "in_app": false } },
+        "quiet_hours": {
+            "start_local": hh_mm(now - time::Duration::hours(1)),
+            "end_local": hh_mm(now + time::Duration::hours(1)),
+            "timezone": "UTC"
+        }
+    });
+    let (status, saved) = send(
+        app.clone(),
+        "PUT",
+        "/v1/notifications/preferences",
+        Some(update.to_string()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        saved["types"]["vouch"],
+        json!({ "in_app": false, "email": true, "push": true })
+    );
+    assert_eq!(saved["siaga_bypass_min_severity"], json!(0));
+
+    let service = DiscoveryService::new(state.feed_repo.clone(), state.notification_repo.clone())
+        .with_notification_preferences(state.notification_preference_repo.clone());
+    let actor = actor_identity_for_tests("user-456");
+    let input = |notification_type: &str, source_id: &str| NotificationIngestInput {
+        recipient_id: "user-123".to_string(),
+        actor: actor.clone(),
+        notification_type: notification_type.to_string(),
+        source_type: FEED_SOURCE_CONTRIBUTION.to_string(),
+        source_id: source_id.to_string(),
+        title: "Kabar baru".into(),
+        body: "Ada pembaruan".into(),
+        payload: None,
+        privacy_level: Some("public".into()),
+        request_id: format!("req-{source_id}"),
+        correlation_id: format!("corr-{source_id}"),
+        request_ts_ms: None,
+        dedupe_key: None,
+    };
+    let vouch = service
+        .ingest_notification(input("vouch", "pref-vouch"))
+        .await
+        .expect("vouch");
+    assert!(!vouch.channels.in_app);
+    let system = service
+        .ingest_notification(input(NOTIF_TYPE_SYSTEM, "pref-system"))
+        .await
+        .expect("system");
+    assert!(system.notification.deliver_at_ms.is_some());
+
+    let (_, list) = send(
+        app.clone(),
+        "GET",
+        "/v1/notifications?include_read=true",
+        None,
+    )
+    .await;
+    assert_eq!(list["items"], json!([]));
+    let (_, unread) = send(app.clone(), "GET", "/v1/notifications/unread-count", None).await;
+    assert_eq!(unread["unread_count"], json!(0));
+
+    // Email stays on for vouches, so the digest still carries the hidden row.
+    let now_ms = gotong_domain::jobs::now_ms();
+    let digest_sources = |digest: gotong_domain::discovery::WeeklyDigest| {
+        digest
+            .events
+            .into_iter()
+            .map(|event| event.item.source_id)
+            .collect::<Vec<_>>()
+    };
+    let email_digest = service
+        .email_digest("user-123", now_ms - 60_000, now_ms + 60_000)
         .await
-        .expect("suggestions response");
-    assert_eq!(suggestions_response.status(), StatusCode::OK);
-    let suggestions_body = to_bytes(suggestions_response.into_body(), usize::MAX)
+        .expect("email digest");
+    assert!(digest_sources(email_digest).contains(&"pref-vouch".to_string()));
+    let in_app_digest = service
+        .weekly_digest("user-123", Some(now_ms - 60_000), Some(now_ms + 60_000))
         .await
-        .expect("suggestions body");
-    let suggestions: Vec<serde_json::Value> =
-        serde_json::from_slice(&suggestions_body).expect("suggestions json");
-    let rt05 = suggestions
-        .iter()
-        .find(|item| item.get("entity_id") == Some(&json!("ent-rt05")))
-        .expect("rt05 suggestion");
-    assert_eq!(rt05.get("followed"), Some(&json!(true)));
+        .expect("in-app digest");
+    assert!(!digest_sources(in_app_digest).contains(&"pref-vouch".to_string()));
+}
+
+#[tokio::test]
+async fn push_subscription_endpoints_register_list_and_remove_browser_subscriptions() {
+    let mut config = test_config();
+    config.web_push_vapid_public_key =
+        "BO_I1oiFSQoJwtXhjtrPQCMD7BIvENnHad23_mZ-G3NGEm87OP5wX8TABR7aoyyhhlGRBAprPbpe3wac_waSXAA"
+            .to_string();
+    let state =
+        AppState::with_idempotency_store(config, Arc::new(InMemoryIdempotencyStore::new("test")));
+    let app = routes::router(state);
+    let auth_header = format!("Bearer {}", test_token("test-secret"));
+    let send = |app: axum::Router, method: &str, uri: &str, body: Option<serde_json::Value>| {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("authorization", auth_header.clone());
+        if body.is_some() {
+            request = request.header(CONTENT_TYPE, "application/json");
+        }
+        let request = request
+            .body(
+                body.map(|body| Body::from(body.to_string()))
+                    .unwrap_or_else(Body::empty),
+            )
+            .unwrap();
+        async move {
+            let response = app.oneshot(request).await.expect("response");
+            let status = response.status();
+            let body = to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("body");
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap_or_default(),
+            )
+        }
+    };
+
+    let (status, _) = send(test_app(), "GET", "/v1/notifications/push/public-key", None).await;
+    assert_eq!(
+        status,
+        StatusCode::NOT_FOUND,
+        "push disabled without VAPID keys"
+    );
+    let (status, key) = send(
+        app.clone(),
+        "GET",
+        "/v1/notifications/push/public-key",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(key["public_key"].as_str().unwrap().starts_with("BO_I1oiF"));
+
+    let subscription = |endpoint: &str| {
+        json!({
+            "endpoint": endpoint,
+            "keys": {
+                "p256dh": "BIWEv2yLVUTvMla2rS_0l3nqwXXYPI9Gd-_DqnM2O02xD2nzSMPvh6XYpZ4KH9-6Z21R2teti-x8ZMJPdC7IGWI",
+                "auth": "BTBZMqHH6r4Tts7J_aSIgg=="
+            }
+        })
+    };
+    let (status, _) = send(
+        app.clone(),
+        "POST",
+        "/v1/notifications/push/subscriptions",
+        Some(subscription("http://push.example/insecure")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let endpoint = "https://fcm.googleapis.com/fcm/send/device-1";
+    let (status, created) = send(
+        app.clone(),
+        "POST",
+        "/v1/notifications/push/subscriptions",
+        Some(subscription(endpoint)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(created["user_id"], "user-123");
+    assert_eq!(created["auth"], "BTBZMqHH6r4Tts7J_aSIgg");
+    let subscription_id = created["subscription_id"].as_str().unwrap().to_string();
+
+    let (status, refreshed) = send(
+        app.clone(),
+        "POST",
+        "/v1/notifications/push/subscriptions",
+        Some(subscription(endpoint)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(refreshed["subscription_id"], subscription_id.as_str());
+    assert_eq!(refreshed["created_at_ms"], created["created_at_ms"]);
+
+    let (status, listed) = send(
+        app.clone(),
+        "GET",
+        "/v1/notifications/push/subscriptions",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(listed.as_array().map(Vec::len), Some(1));
+
+    let (status, _) = send(
+        app.clone(),
+        "DELETE",
+        "/v1/notifications/push/subscriptions/unknown",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(
+        app.clone(),
+        "DELETE",
+        &format!("/v1/notifications/push/subscriptions/{subscription_id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (_, listed) = send(app, "GET", "/v1/notifications/push/subscriptions", None).await;
+    assert_eq!(listed, json!([]));
 }
 
 #[tokio::test]
@@ -3467,6 +6044,7 @@ async fn discovery_feed_pagination_skips_hidden_rows_for_actor_visibility() {
                 request_ts_ms: Some(occurred_at_ms),
                 participant_ids: Vec::new(),
                 payload: None,
+                location: None,
             })
             .await
             .expect("seed feed row");
@@ -3482,6 +6060,9 @@ async fn discovery_feed_pagination_skips_hidden_rows_for_actor_visibility() {
             from_ms: None,
             to_ms: None,
             involvement_only: false,
+            mode: FeedMode::Chronological,
+            include_ranking_debug: false,
+            near: None,
         })
         .await
         .expect("first page");
@@ -3505,6 +6086,9 @@ async fn discovery_feed_pagination_skips_hidden_rows_for_actor_visibility() {
             from_ms: None,
             to_ms: None,
             involvement_only: false,
+            mode: FeedMode::Chronological,
+            include_ranking_debug: false,
+            near: None,
         })
         .await
         .expect("second page");
@@ -3536,6 +6120,115 @@ async fn discovery_feed_pagination_skips_hidden_rows_for_actor_visibility() {
     assert_eq!(count, 2);
 }
 
+#[tokio::test]
+async fn discovery_feed_ranked_mode_boosts_followed_entities_with_debug_scores() {
+    let (state, app) = test_app_state_router();
+    let service = DiscoveryService::new(state.feed_repo.clone(), state.notification_repo.clone());
+    let author = actor_identity_for_tests("ranked-author");
+    let now = gotong_domain::jobs::now_ms();
+    for (idx, (occurred_at_ms, payload)) in [
+        (now - 1_000, None),
+        (
+            now - 2 * 60 * 60 * 1000,
+            Some(json!({
+                "enrichment": {
+                    "entity_tags": [
+                        { "entity_id": "ent-ranked", "entity_type": "topik", "label": "Ronda" }
+                    ]
+                }
+            })),
+        ),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        service
+            .ingest_feed(FeedIngestInput {
+                source_type: FEED_SOURCE_CONTRIBUTION.to_string(),
+                source_id: format!("seed-ranked-{idx}"),
+                actor: author.clone(),
+                title: format!("ranked {idx}"),
+                summary: None,
+                scope_id: None,
+                privacy_level: Some("public".to_string()),
+                occurred_at_ms: Some(occurred_at_ms),
+                request_id: format!("ranked-feed-{idx}"),
+                correlation_id: format!("ranked-corr-{idx}"),
+                request_ts_ms: Some(occurred_at_ms),
+                participant_ids: Vec::new(),
+                payload,
+                location: None,
+            })
+            .await
+            .expect("seed feed row");
+    }
+
+    let token = test_token("test-secret");
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/feed/preferences/follow/ent-ranked")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {token}"))
+                .body(Body::from(json!({ "followed": true }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let get_feed = |uri: String| {
+        Request::builder()
+            .method("GET")
+            .uri(uri)
+            .header("authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app
+        .clone()
+        .oneshot(get_feed(
+            "/v1/feed?mode=ranked&debug=true&limit=1".to_string(),
+        ))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let first_page: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(first_page["items"][0]["source_id"], json!("seed-ranked-1"));
+    assert_eq!(first_page["ranking"]["scores"][0]["followed"], json!(1.0));
+    assert_eq!(first_page["ranking"]["weights"]["followed"], json!(0.5));
+    let cursor = first_page["next_cursor"]
+        .as_str()
+        .expect("ranked cursor")
+        .to_string();
+
+    let response = app
+        .clone()
+        .oneshot(get_feed(format!(
+            "/v1/feed?mode=ranked&limit=1&cursor={cursor}"
+        )))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let second_page: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(second_page["items"][0]["source_id"], json!("seed-ranked-0"));
+    assert!(second_page.get("ranking").is_none());
+
+    let response = app
+        .oneshot(get_feed("/v1/feed?mode=trending".to_string()))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn discovery_search_pagination_skips_hidden_rows_for_actor_visibility() {
     let (state, app) = test_app_state_router();
@@ -3565,6 +6258,7 @@ async fn discovery_search_pagination_skips_hidden_rows_for_actor_visibility() {
                 request_ts_ms: Some(occurred_at_ms),
                 participant_ids: Vec::new(),
                 payload: None,
+                location: None,
             })
             .await
             .expect("seed feed row");
@@ -3582,6 +6276,7 @@ async fn discovery_search_pagination_skips_hidden_rows_for_actor_visibility() {
             to_ms: None,
             involvement_only: false,
             exclude_vault: false,
+            near: None,
         })
         .await
         .expect("first page");
@@ -3607,6 +6302,7 @@ async fn discovery_search_pagination_skips_hidden_rows_for_actor_visibility() {
             to_ms: None,
             involvement_only: false,
             exclude_vault: false,
+            near: None,
         })
         .await
         .expect("second page");
@@ -3638,6 +6334,71 @@ async fn discovery_search_pagination_skips_hidden_rows_for_actor_visibility() {
     assert_eq!(count, 2);
 }
 
+#[tokio::test]
+async fn discovery_search_uses_full_text_index_with_stemming_and_privacy() {
+    let index = TantivyFeedSearchIndex::open("").expect("in-memory search index");
+    let state = test_app_state();
+    let indexer = FeedSearchIndexer::spawn(index.clone(), state.feed_repo.clone());
+    let state = state.with_feed_search_index(Arc::new(index), Arc::new(indexer.clone()));
+    let app = routes::router(state.clone());
+    let service = DiscoveryService::new(state.feed_repo.clone(), state.notification_repo.clone());
+    let reader = actor_identity_for_tests("index-reader");
+    let stranger = actor_identity_for_tests("index-stranger");
+
+    for (idx, (actor, title, privacy_level)) in [
+        (&stranger, "Pembangunan saluran air RT 05", "public"),
+        (&stranger, "Pembangunan saluran rahasia", "private"),
+        (&reader, "Kerja bakti taman", "public"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        service
+            .ingest_feed(FeedIngestInput {
+                source_type: FEED_SOURCE_CONTRIBUTION.to_string(),
+                source_id: format!("index-search-{idx}"),
+                actor: actor.clone(),
+                title: title.to_string(),
+                summary: None,
+                scope_id: None,
+                privacy_level: Some(privacy_level.to_string()),
+                occurred_at_ms: Some(1_000 + idx as i64),
+                request_id: format!("index-search-feed-{idx}"),
+                correlation_id: format!("index-search-corr-{idx}"),
+                request_ts_ms: Some(1_000 + idx as i64),
+                participant_ids: Vec::new(),
+                payload: None,
+                location: None,
+            })
+            .await
+            .expect("seed feed row");
+    }
+    indexer.flush().await;
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/v1/search?query_text=membangun%20salurn&limit=10")
+        .header(
+            "authorization",
+            format!(
+                "Bearer {}",
+                test_token_with_identity("test-secret", "user", &reader.user_id)
+            ),
+        )
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let page: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    let items = page["items"].as_array().expect("items");
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["item"]["source_id"], json!("index-search-0"));
+    assert!(items[0]["score"].as_i64().expect("score") > 0);
+}
+
 #[tokio::test]
 async fn discovery_notifications_endpoints() {
     let (state, app) = test_app_state_router();
@@ -3663,7 +6424,8 @@ async fn discovery_notifications_endpoints() {
             dedupe_key: Some("notif-uniq-1".into()),
         })
         .await
-        .expect("seed notification");
+        .expect("seed notification")
+        .notification;
 
     let list_request = Request::builder()
         .method("GET")
@@ -3802,12 +6564,17 @@ async fn discovery_notifications_pagination_cursor_does_not_skip_items() {
             cursor: None,
             limit: Some(2),
             include_read: Some(true),
+            grouped: None,
         })
         .await
         .expect("first page");
 
     assert_eq!(first_page.items.len(), 2);
-    let first_ts: Vec<i64> = first_page.items.iter().map(|n| n.created_at_ms).collect();
+    let first_ts: Vec<i64> = first_page
+        .items
+        .iter()
+        .map(|item| item.notification.created_at_ms)
+        .collect();
     assert_eq!(first_ts, vec![3_000, 2_000]);
     let cursor = first_page.next_cursor.expect("cursor present");
 
@@ -3817,15 +6584,174 @@ async fn discovery_notifications_pagination_cursor_does_not_skip_items() {
             cursor: Some(cursor),
             limit: Some(2),
             include_read: Some(true),
+            grouped: None,
         })
         .await
         .expect("second page");
 
     assert_eq!(second_page.items.len(), 1);
-    assert_eq!(second_page.items[0].created_at_ms, 1_000);
+    assert_eq!(second_page.items[0].notification.created_at_ms, 1_000);
     assert!(second_page.next_cursor.is_none());
 }
 
+#[tokio::test]
+async fn discovery_notifications_collapse_groups_and_cascade_mark_read() {
+    let (state, app) = test_app_state_router();
+    let service = DiscoveryService::new(state.feed_repo.clone(), state.notification_repo.clone());
+    let recipient = actor_identity_for_tests("user-notif-group");
+    let token = test_token_with_identity("test-secret", "user", &recipient.user_id);
+
+    let seeds = [
+        ("ani", NOTIF_TYPE_VOUCH, "seed-group-a", 1_000_i64),
+        ("budi", NOTIF_TYPE_VOUCH, "seed-group-a", 2_000),
+        ("citra", NOTIF_TYPE_VOUCH, "seed-group-a", 3_000),
+        ("budi", NOTIF_TYPE_SYSTEM, "seed-group-a", 2_500),
+    ];
+    for (idx, (actor, notification_type, source_id, ts)) in seeds.into_iter().enumerate() {
+        service
+            .ingest_notification(NotificationIngestInput {
+                recipient_id: recipient.user_id.clone(),
+                actor: actor_identity_for_tests(actor),
+                notification_type: notification_type.to_string(),
+                source_type: FEED_SOURCE_CONTRIBUTION.to_string(),
+                source_id: source_id.to_string(),
+                title: format!("{actor} {notification_type}"),
+                body: "grouping check".into(),
+                payload: None,
+                privacy_level: Some("public".into()),
+                request_id: format!("notif-group-req-{idx}"),
+                correlation_id: format!("notif-group-corr-{idx}"),
+                request_ts_ms: Some(ts),
+                dedupe_key: None,
+            })
+            .await
+            .expect("seed notification");
+    }
+
+    let list = |uri: &'static str| {
+        let app = app.clone();
+        let token = token.clone();
+        async move {
+            let request = Request::builder()
+                .method("GET")
+                .uri(uri)
+                .header("authorization", format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap();
+            let response = app.oneshot(request).await.expect("response");
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("body");
+            let list: serde_json::Value = serde_json::from_slice(&body).expect("json");
+            list["items"].as_array().expect("items").clone()
+        }
+    };
+
+    let grouped = list("/v1/notifications?limit=10").await;
+    assert_eq!(grouped.len(), 2);
+    let vouches = &grouped[0];
+    assert_eq!(vouches["actor_id"], json!("citra"));
+    assert_eq!(vouches["group"]["count"], json!(3));
+    assert_eq!(vouches["group"]["unread_count"], json!(3));
+    assert_eq!(vouches["group"]["actor_count"], json!(3));
+    let sample: Vec<&str> = vouches["group"]["actors"]
+        .as_array()
+        .expect("actors")
+        .iter()
+        .map(|actor| actor["actor_username"].as_str().expect("username"))
+        .collect();
+    assert_eq!(sample, vec!["citra-name", "budi-name", "ani-name"]);
+    assert_eq!(grouped[1]["group"]["count"], json!(1));
+
+    let flat = list("/v1/notifications?limit=10&grouped=false").await;
+    assert_eq!(flat.len(), 4);
+    assert!(flat.iter().all(|item| item.get("group").is_none()));
+
+    let read_request = Request::builder()
+        .method("POST")
+        .uri(format!(
+            "/v1/notifications/{}/read",
+            vouches["notification_id"]
+                .as_str()
+                .expect("notification id")
+        ))
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(read_request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let unread = list("/v1/notifications?limit=10&grouped=false").await;
+    assert_eq!(unread.len(), 1);
+    assert_eq!(unread[0]["notification_type"], json!(NOTIF_TYPE_SYSTEM));
+}
+
+#[tokio::test]
+async fn realtime_stream_sends_unread_count_then_new_feed_items() {
+    let app = test_app();
+    let token = test_token("test-secret");
+    let stream_request = Request::builder()
+        .method("GET")
+        .uri("/v1/realtime/stream")
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(stream_request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(
+        response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"))
+    );
+    let mut body = response.into_body().into_data_stream();
+    let mut received = String::new();
+    read_sse_until(&mut body, "event: unread_count", &mut received).await;
+    assert!(received.contains("\"unread_count\":0"));
+
+    let contribution_request = json!({
+        "mode": "komunitas",
+        "contribution_type": "task_completion",
+        "title": "Realtime feed check",
+        "description": "Streams to the author",
+        "skill_ids": ["skill-1"]
+    });
+    let create_request = Request::builder()
+        .method("POST")
+        .uri("/v1/contributions")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {token}"))
+        .header("x-request-id", "realtime-feed-1")
+        .body(Body::from(contribution_request.to_string()))
+        .unwrap();
+    // Keep `app` alive: dropping the last state handle closes the bus and can
+    // end the stream before the buffered feed item is read.
+    let create_response = app.clone().oneshot(create_request).await.expect("response");
+    assert_eq!(create_response.status(), StatusCode::CREATED);
+
+    read_sse_until(&mut body, "event: feed_item", &mut received).await;
+    assert!(received.contains("Realtime feed check"));
+}
+
+async fn read_sse_until(
+    body: &mut axum::body::BodyDataStream,
+    needle: &str,
+    received: &mut String,
+) {
+    use futures_util::StreamExt;
+
+    while !received.contains(needle) {
+        let chunk = tokio::time::timeout(std::time::Duration::from_secs(10), body.next())
+            .await
+            .expect("stream event timed out")
+            .expect("stream ended")
+            .expect("stream chunk");
+        received.push_str(&String::from_utf8_lossy(&chunk));
+    }
+}
+
 #[tokio::test]
 async fn triage_sessions_start_and_continue_flow() {
     let app = test_app();
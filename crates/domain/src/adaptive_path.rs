@@ -4,6 +4,9 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
+use crate::adaptive_path_roles::{
+    AdaptivePathRoleGrant, AdaptivePathRoleResolver, ResolvedEditorRoles,
+};
 use crate::auth::Role;
 use crate::error::DomainError;
 use crate::identity::ActorIdentity;
@@ -63,6 +66,7 @@ pub enum AdaptivePathStatus {
 pub enum AdaptivePathEventType {
     PlanCreated,
     PlanUpdated,
+    PlanRolledBack,
     SuggestionProposed,
     SuggestionAccepted,
     SuggestionRejected,
@@ -73,6 +77,7 @@ impl AdaptivePathEventType {
         match self {
             Self::PlanCreated => "plan_created",
             Self::PlanUpdated => "plan_updated",
+            Self::PlanRolledBack => "plan_rolled_back",
             Self::SuggestionProposed => "suggestion_proposed",
             Self::SuggestionAccepted => "suggestion_accepted",
             Self::SuggestionRejected => "suggestion_rejected",
@@ -123,7 +128,7 @@ pub enum AdaptivePathEditorRole {
 }
 
 impl AdaptivePathEditorRole {
-    fn as_str(&self) -> &'static str {
+    pub(crate) fn as_str(&self) -> &'static str {
         match self {
             Self::Author => "author",
             Self::ProjectManager => "project_manager",
@@ -159,6 +164,9 @@ pub struct AdaptivePathActorSnapshot {
     pub username: String,
     pub token_role: String,
     pub editor_roles: Vec<String>,
+    /// Which rule granted each of `editor_roles`.
+    #[serde(default)]
+    pub role_grants: Vec<AdaptivePathRoleGrant>,
     pub request_id: String,
     pub correlation_id: String,
     pub request_ts_ms: i64,
@@ -168,7 +176,7 @@ impl AdaptivePathActorSnapshot {
     fn new(
         actor: &ActorIdentity,
         token_role: &Role,
-        editor_roles: &[AdaptivePathEditorRole],
+        editor_roles: &ResolvedEditorRoles,
         request_id: impl Into<String>,
         correlation_id: impl Into<String>,
         request_ts_ms: i64,
@@ -178,9 +186,11 @@ impl AdaptivePathActorSnapshot {
             username: actor.username.clone(),
             token_role: token_role.as_str().to_string(),
             editor_roles: editor_roles
+                .roles
                 .iter()
                 .map(|role| role.as_str().to_string())
                 .collect(),
+            role_grants: editor_roles.grants.clone(),
             request_id: request_id.into(),
             correlation_id: correlation_id.into(),
             request_ts_ms,
@@ -258,6 +268,78 @@ impl AdaptivePathPlan {
             branches: self.branches.clone(),
         }
     }
+
+    pub fn version_summary(&self) -> AdaptivePathPlanVersionSummary {
+        AdaptivePathPlanVersionSummary {
+            plan_id: self.plan_id.clone(),
+            version: self.version,
+            title: self.title.clone(),
+            request_id: self.request_id.clone(),
+            updated_at_ms: self.updated_at_ms,
+            event_hash: self.event_hash.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AdaptivePathPlanVersionSummary {
+    pub plan_id: String,
+    pub version: PlanVersion,
+    pub title: String,
+    pub request_id: String,
+    pub updated_at_ms: i64,
+    pub event_hash: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AdaptivePathDiffNode {
+    Branch,
+    Phase,
+    Checkpoint,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AdaptivePathChangeKind {
+    Added,
+    Removed,
+    /// New parent or new order.
+    Moved,
+    /// New branch label, phase title or checkpoint title.
+    Retitled,
+    /// Any other field (status, objective, source).
+    Updated,
+}
+
+/// Where a node sits and what it is called. `parent_id` is the parent
+/// checkpoint of a branch, the branch of a phase and the phase of a
+/// checkpoint.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AdaptivePathDiffPosition {
+    pub parent_id: Option<String>,
+    pub order: i64,
+    pub title: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AdaptivePathDiffEntry {
+    pub node: AdaptivePathDiffNode,
+    pub id: String,
+    pub changes: Vec<AdaptivePathChangeKind>,
+    pub changed_fields: Vec<String>,
+    pub before: Option<AdaptivePathDiffPosition>,
+    pub after: Option<AdaptivePathDiffPosition>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AdaptivePathPlanDiff {
+    pub plan_id: String,
+    pub from_version: PlanVersion,
+    pub to_version: PlanVersion,
+    /// Changed plan-level fields: `title`, `summary`, `action_type`.
+    pub plan_fields: Vec<String>,
+    pub entries: Vec<AdaptivePathDiffEntry>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -337,7 +419,6 @@ pub struct AdaptivePathPlanPayloadDraft {
 pub struct CreateAdaptivePathInput {
     pub entity_id: String,
     pub payload: AdaptivePathPlanPayloadDraft,
-    pub editor_roles: Vec<AdaptivePathEditorRole>,
     pub request_id: String,
     pub correlation_id: String,
     pub request_ts_ms: Option<i64>,
@@ -348,7 +429,16 @@ pub struct UpdateAdaptivePathInput {
     pub plan_id: String,
     pub expected_version: PlanVersion,
     pub payload: AdaptivePathPlanPayloadDraft,
-    pub editor_roles: Vec<AdaptivePathEditorRole>,
+    pub request_id: String,
+    pub correlation_id: String,
+    pub request_ts_ms: Option<i64>,
+}
+
+#[derive(Clone)]
+pub struct RollbackAdaptivePathInput {
+    pub plan_id: String,
+    pub expected_version: PlanVersion,
+    pub target_version: PlanVersion,
     pub request_id: String,
     pub correlation_id: String,
     pub request_ts_ms: Option<i64>,
@@ -362,7 +452,6 @@ pub struct SuggestAdaptivePathInput {
     pub rationale: Option<String>,
     pub model_id: Option<String>,
     pub prompt_version: Option<String>,
-    pub editor_roles: Vec<AdaptivePathEditorRole>,
     pub request_id: String,
     pub correlation_id: String,
     pub request_ts_ms: Option<i64>,
@@ -371,7 +460,6 @@ pub struct SuggestAdaptivePathInput {
 #[derive(Clone)]
 pub struct SuggestionReviewInput {
     pub suggestion_id: String,
-    pub editor_roles: Vec<AdaptivePathEditorRole>,
     pub request_id: String,
     pub correlation_id: String,
     pub request_ts_ms: Option<i64>,
@@ -380,11 +468,22 @@ pub struct SuggestionReviewInput {
 #[derive(Clone)]
 pub struct AdaptivePathService {
     repository: Arc<dyn AdaptivePathRepository>,
+    role_resolver: AdaptivePathRoleResolver,
 }
 
 impl AdaptivePathService {
     pub fn new(repository: Arc<dyn AdaptivePathRepository>) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            role_resolver: AdaptivePathRoleResolver::default(),
+        }
+    }
+
+    /// Editor roles are always resolved server-side; without a configured
+    /// resolver only the plan author is recognised.
+    pub fn with_role_resolver(mut self, role_resolver: AdaptivePathRoleResolver) -> Self {
+        self.role_resolver = role_resolver;
+        self
     }
 
     pub async fn create_plan(
@@ -395,14 +494,16 @@ impl AdaptivePathService {
     ) -> DomainResult<AdaptivePathPlan> {
         ensure_actor_can_initiate(token_role, actor)?;
         let request_ts_ms = input.request_ts_ms.unwrap_or_else(now_ms);
-        let editor_roles = dedupe_editor_roles(&input.editor_roles);
+        let editor_roles = self
+            .role_resolver
+            .resolve(&input.entity_id, &actor.user_id, &actor.user_id)
+            .await?;
         let payload = validate_and_normalize_payload(&input.payload)?;
         let plan = build_plan(
             &input.entity_id,
             crate::util::uuid_v7_without_dashes(),
             None,
             actor,
-            &editor_roles,
             1,
             payload,
             &input.request_id,
@@ -479,8 +580,6 @@ impl AdaptivePathService {
         input: UpdateAdaptivePathInput,
     ) -> DomainResult<AdaptivePathPlan> {
         let request_ts_ms = input.request_ts_ms.unwrap_or_else(now_ms);
-        let editor_roles = dedupe_editor_roles(&input.editor_roles);
-
         let plan = self
             .repository
             .get_plan(&input.plan_id)
@@ -491,7 +590,8 @@ impl AdaptivePathService {
             return Err(DomainError::Conflict);
         }
 
-        ensure_editor_can_modify(token_role, &editor_roles)?;
+        let editor_roles = self.resolve_editor_roles(&plan, actor).await?;
+        ensure_editor_can_modify(token_role, &editor_roles.roles)?;
 
         let normalized_payload = validate_and_normalize_payload(&input.payload)?;
         let payload = apply_editorial_locks(&plan.payload(), normalized_payload)?;
@@ -500,7 +600,6 @@ impl AdaptivePathService {
             plan.plan_id.clone(),
             Some(&plan),
             actor,
-            &editor_roles,
             plan.version + 1,
             payload,
             &input.request_id,
@@ -535,6 +634,149 @@ impl AdaptivePathService {
         }
     }
 
+    /// Plans written before snapshots were kept start their history at the
+    /// current version, which is listed even without a snapshot.
+    pub async fn list_plan_versions(
+        &self,
+        plan_id: &str,
+    ) -> DomainResult<Vec<AdaptivePathPlanVersionSummary>> {
+        let plan = self
+            .repository
+            .get_plan(plan_id)
+            .await?
+            .ok_or(DomainError::NotFound)?;
+        let mut versions = self.repository.list_plan_versions(plan_id).await?;
+        if !versions
+            .iter()
+            .any(|version| version.version == plan.version)
+        {
+            versions.push(plan.version_summary());
+        }
+        versions.sort_by_key(|version| std::cmp::Reverse(version.version));
+        Ok(versions)
+    }
+
+    pub async fn get_plan_version(
+        &self,
+        plan_id: &str,
+        version: PlanVersion,
+    ) -> DomainResult<Option<AdaptivePathPlan>> {
+        if let Some(snapshot) = self.repository.get_plan_version(plan_id, version).await? {
+            return Ok(Some(snapshot));
+        }
+        Ok(self
+            .repository
+            .get_plan(plan_id)
+            .await?
+            .filter(|plan| plan.version == version))
+    }
+
+    pub async fn diff_plan_versions(
+        &self,
+        plan_id: &str,
+        from_version: PlanVersion,
+        to_version: PlanVersion,
+    ) -> DomainResult<AdaptivePathPlanDiff> {
+        let from = self
+            .get_plan_version(plan_id, from_version)
+            .await?
+            .ok_or(DomainError::NotFound)?;
+        let to = self
+            .get_plan_version(plan_id, to_version)
+            .await?
+            .ok_or(DomainError::NotFound)?;
+        Ok(diff_plans(&from, &to))
+    }
+
+    /// Writes the content of `target_version` as a new version. Fields locked
+    /// on the current plan keep their current value, and fields the rollback
+    /// changes become locked like any other editor change. Nodes with locked
+    /// fields that the target version lacks are not dropped; the rollback is
+    /// refused instead.
+    pub async fn rollback_plan(
+        &self,
+        actor: &ActorIdentity,
+        token_role: &Role,
+        input: RollbackAdaptivePathInput,
+    ) -> DomainResult<AdaptivePathPlan> {
+        let request_ts_ms = input.request_ts_ms.unwrap_or_else(now_ms);
+        let plan = self
+            .repository
+            .get_plan(&input.plan_id)
+            .await?
+            .ok_or(DomainError::NotFound)?;
+
+        if plan.version != input.expected_version {
+            return Err(DomainError::Conflict);
+        }
+        if input.target_version >= plan.version {
+            return Err(DomainError::Validation(format!(
+                "target_version must be below the current version {}",
+                plan.version
+            )));
+        }
+
+        let editor_roles = self.resolve_editor_roles(&plan, actor).await?;
+        ensure_editor_can_modify(token_role, &editor_roles.roles)?;
+
+        let target = self
+            .repository
+            .get_plan_version(&plan.plan_id, input.target_version)
+            .await?
+            .ok_or(DomainError::NotFound)?;
+        let removed = locked_nodes_missing(&plan, &target.payload());
+        if !removed.is_empty() {
+            return Err(DomainError::Validation(format!(
+                "rollback would remove nodes with locked fields: {}",
+                removed.join(", ")
+            )));
+        }
+        let restored = enforce_locked_fields(&plan, target.payload())?;
+        let kept_locked_fields = locked_fields_kept(&target.payload(), &restored);
+        let payload = apply_editorial_locks(&plan.payload(), restored)?;
+        let updated_plan = build_plan(
+            &plan.entity_id,
+            plan.plan_id.clone(),
+            Some(&plan),
+            actor,
+            plan.version + 1,
+            payload,
+            &input.request_id,
+            &input.correlation_id,
+            request_ts_ms,
+            request_ts_ms,
+        )?;
+        let mut event = build_plan_event(
+            &updated_plan,
+            actor,
+            token_role,
+            &editor_roles,
+            &input.request_id,
+            &input.correlation_id,
+            request_ts_ms,
+            plan.version,
+            updated_plan.version,
+            AdaptivePathEventType::PlanRolledBack,
+        )?;
+        if let Some(metadata) = event.metadata.as_mut() {
+            metadata["target_version"] = serde_json::json!(input.target_version);
+            metadata["kept_locked_fields"] = serde_json::json!(kept_locked_fields);
+        }
+
+        match self.repository.update_plan(&updated_plan).await {
+            Ok(plan) => {
+                let _ = self.repository.create_event(&event).await;
+                Ok(plan)
+            }
+            Err(DomainError::Conflict) => self
+                .repository
+                .get_plan_by_request_id(&updated_plan.entity_id, &input.request_id)
+                .await?
+                .ok_or(DomainError::Conflict),
+            Err(err) => Err(err),
+        }
+    }
+
     pub async fn suggest_plan(
         &self,
         actor: &ActorIdentity,
@@ -542,9 +784,6 @@ impl AdaptivePathService {
         input: SuggestAdaptivePathInput,
     ) -> DomainResult<AdaptivePathSuggestion> {
         let request_ts_ms = input.request_ts_ms.unwrap_or_else(now_ms);
-        let editor_roles = dedupe_editor_roles(&input.editor_roles);
-        ensure_editor_can_modify(token_role, &editor_roles)?;
-
         let plan = self
             .repository
             .get_plan(&input.plan_id)
@@ -554,6 +793,8 @@ impl AdaptivePathService {
         if plan.version != input.base_version {
             return Err(DomainError::Conflict);
         }
+        let editor_roles = self.resolve_editor_roles(&plan, actor).await?;
+        ensure_editor_can_modify(token_role, &editor_roles.roles)?;
 
         let normalized = validate_and_normalize_payload(&input.payload)?;
         let proposal = enforce_locked_fields(&plan, normalized)?;
@@ -580,6 +821,7 @@ impl AdaptivePathService {
             &plan,
             actor,
             token_role,
+            &editor_roles,
             &input.request_id,
             &input.correlation_id,
             request_ts_ms,
@@ -609,8 +851,6 @@ impl AdaptivePathService {
         input: SuggestionReviewInput,
     ) -> DomainResult<AdaptivePathPlan> {
         let request_ts_ms = input.request_ts_ms.unwrap_or_else(now_ms);
-        let editor_roles = dedupe_editor_roles(&input.editor_roles);
-
         let suggestion = self
             .repository
             .get_suggestion(&input.suggestion_id)
@@ -627,14 +867,14 @@ impl AdaptivePathService {
             .await?
             .ok_or(DomainError::NotFound)?;
 
-        ensure_editor_can_modify(token_role, &editor_roles)?;
+        let editor_roles = self.resolve_editor_roles(&plan, actor).await?;
+        ensure_editor_can_modify(token_role, &editor_roles.roles)?;
         let updated_payload = enforce_locked_fields(&plan, suggestion.proposal.clone())?;
         let updated_plan = build_plan(
             &plan.entity_id,
             plan.plan_id.clone(),
             Some(&plan),
             actor,
-            &editor_roles,
             plan.version + 1,
             updated_payload,
             &input.request_id,
@@ -685,8 +925,6 @@ impl AdaptivePathService {
         input: SuggestionReviewInput,
     ) -> DomainResult<AdaptivePathSuggestion> {
         let request_ts_ms = input.request_ts_ms.unwrap_or_else(now_ms);
-        let editor_roles = dedupe_editor_roles(&input.editor_roles);
-
         let suggestion = self
             .repository
             .get_suggestion(&input.suggestion_id)
@@ -702,7 +940,8 @@ impl AdaptivePathService {
             .get_plan(&suggestion.plan_id)
             .await?
             .ok_or(DomainError::NotFound)?;
-        ensure_editor_can_modify(token_role, &editor_roles)?;
+        let editor_roles = self.resolve_editor_roles(&plan, actor).await?;
+        ensure_editor_can_modify(token_role, &editor_roles.roles)?;
 
         let event = build_plan_event(
             &plan,
@@ -723,6 +962,16 @@ impl AdaptivePathService {
         let _ = self.repository.create_event(&event).await;
         Ok(suggestion)
     }
+
+    async fn resolve_editor_roles(
+        &self,
+        plan: &AdaptivePathPlan,
+        actor: &ActorIdentity,
+    ) -> DomainResult<ResolvedEditorRoles> {
+        self.role_resolver
+            .resolve(&plan.entity_id, &plan.author_id, &actor.user_id)
+            .await
+    }
 }
 
 fn ensure_actor_can_initiate(token_role: &Role, actor: &ActorIdentity) -> DomainResult<()> {
@@ -757,20 +1006,12 @@ fn ensure_editor_can_modify(
     ))
 }
 
-fn dedupe_editor_roles(roles: &[AdaptivePathEditorRole]) -> Vec<AdaptivePathEditorRole> {
-    let mut roles: Vec<_> = roles.to_vec();
-    roles.sort_by_key(AdaptivePathEditorRole::as_str);
-    roles.dedup_by_key(|role| role.as_str());
-    roles
-}
-
 #[allow(clippy::too_many_arguments)]
 fn build_plan(
     entity_id: &str,
     plan_id: String,
     existing: Option<&AdaptivePathPlan>,
     actor: &ActorIdentity,
-    editor_roles: &[AdaptivePathEditorRole],
     version: PlanVersion,
     payload: AdaptivePathPlanPayload,
     request_id: &str,
@@ -828,7 +1069,6 @@ fn build_plan(
             .then_with(|| left.branch_id.cmp(&right.branch_id))
     });
 
-    let _ = editor_roles;
     plan.event_hash = adaptive_path_plan_audit_hash(&AdaptivePathPlanAuditPayload {
         plan_id: plan.plan_id.clone(),
         entity_id: plan.entity_id.clone(),
@@ -851,7 +1091,7 @@ fn build_plan_event(
     plan: &AdaptivePathPlan,
     actor: &ActorIdentity,
     token_role: &Role,
-    editor_roles: &[AdaptivePathEditorRole],
+    editor_roles: &ResolvedEditorRoles,
     request_id: &str,
     correlation_id: &str,
     occurred_at_ms: i64,
@@ -906,6 +1146,7 @@ fn build_suggestion_event(
     plan: &AdaptivePathPlan,
     actor: &ActorIdentity,
     token_role: &Role,
+    editor_roles: &ResolvedEditorRoles,
     request_id: &str,
     correlation_id: &str,
     occurred_at_ms: i64,
@@ -916,7 +1157,7 @@ fn build_suggestion_event(
     let actor = AdaptivePathActorSnapshot::new(
         actor,
         token_role,
-        &[AdaptivePathEditorRole::HighestProfileUser],
+        editor_roles,
         request_id,
         correlation_id,
         occurred_at_ms,
@@ -1360,6 +1601,267 @@ fn changed_checkpoint_fields(
     changed
 }
 
+/// Structural diff between two versions. Entries run branches, then phases,
+/// then checkpoints, each in the order of `to` with removed nodes last.
+pub fn diff_plans(from: &AdaptivePathPlan, to: &AdaptivePathPlan) -> AdaptivePathPlanDiff {
+    let mut plan_fields = Vec::new();
+    if from.title != to.title {
+        plan_fields.push("title".to_string());
+    }
+    if from.summary != to.summary {
+        plan_fields.push("summary".to_string());
+    }
+    if from.action_type != to.action_type {
+        plan_fields.push("action_type".to_string());
+    }
+
+    let mut entries = Vec::new();
+    let from_branches = map_plan_branches_by_id(from);
+    let to_branches = map_plan_branches_by_id(to);
+    for branch in &to.branches {
+        let before = from_branches.get(&branch.branch_id);
+        let changed = before.map(|before| changed_branch_fields(before, branch));
+        push_diff_entry(
+            &mut entries,
+            AdaptivePathDiffNode::Branch,
+            &branch.branch_id,
+            changed,
+            before.map(branch_position),
+            Some(branch_position(branch)),
+        );
+    }
+    for branch in &from.branches {
+        if !to_branches.contains_key(&branch.branch_id) {
+            push_diff_entry(
+                &mut entries,
+                AdaptivePathDiffNode::Branch,
+                &branch.branch_id,
+                None,
+                Some(branch_position(branch)),
+                None,
+            );
+        }
+    }
+
+    let from_phases = map_plan_phases_by_id(from);
+    let to_phases = map_plan_phases_by_id(to);
+    for phase in to.branches.iter().flat_map(|branch| branch.phases.iter()) {
+        let before = from_phases.get(&phase.phase_id);
+        let changed = before.map(|before| {
+            let mut changed = changed_phase_fields(before, phase);
+            if before.branch_id != phase.branch_id {
+                changed.push("branch_id");
+            }
+            changed
+        });
+        push_diff_entry(
+            &mut entries,
+            AdaptivePathDiffNode::Phase,
+            &phase.phase_id,
+            changed,
+            before.map(phase_position),
+            Some(phase_position(phase)),
+        );
+    }
+    for phase in from.branches.iter().flat_map(|branch| branch.phases.iter()) {
+        if !to_phases.contains_key(&phase.phase_id) {
+            push_diff_entry(
+                &mut entries,
+                AdaptivePathDiffNode::Phase,
+                &phase.phase_id,
+                None,
+                Some(phase_position(phase)),
+                None,
+            );
+        }
+    }
+
+    let from_checkpoints = map_plan_checkpoints_by_id(from);
+    let to_checkpoints = map_plan_checkpoints_by_id(to);
+    let checkpoints_of = |plan: &AdaptivePathPlan| {
+        plan.branches
+            .iter()
+            .flat_map(|branch| branch.phases.iter())
+            .flat_map(|phase| phase.checkpoints.iter())
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    for checkpoint in checkpoints_of(to) {
+        let before = from_checkpoints.get(&checkpoint.checkpoint_id);
+        let changed = before.map(|before| {
+            let mut changed = changed_checkpoint_fields(before, &checkpoint);
+            if before.phase_id != checkpoint.phase_id {
+                changed.push("phase_id");
+            }
+            changed
+        });
+        push_diff_entry(
+            &mut entries,
+            AdaptivePathDiffNode::Checkpoint,
+            &checkpoint.checkpoint_id,
+            changed,
+            before.map(checkpoint_position),
+            Some(checkpoint_position(&checkpoint)),
+        );
+    }
+    for checkpoint in checkpoints_of(from) {
+        if !to_checkpoints.contains_key(&checkpoint.checkpoint_id) {
+            push_diff_entry(
+                &mut entries,
+                AdaptivePathDiffNode::Checkpoint,
+                &checkpoint.checkpoint_id,
+                None,
+                Some(checkpoint_position(&checkpoint)),
+                None,
+            );
+        }
+    }
+
+    AdaptivePathPlanDiff {
+        plan_id: to.plan_id.clone(),
+        from_version: from.version,
+        to_version: to.version,
+        plan_fields,
+        entries,
+    }
+}
+
+/// `changed` is `None` when the node exists on one side only.
+fn push_diff_entry(
+    entries: &mut Vec<AdaptivePathDiffEntry>,
+    node: AdaptivePathDiffNode,
+    id: &str,
+    changed: Option<Vec<&'static str>>,
+    before: Option<AdaptivePathDiffPosition>,
+    after: Option<AdaptivePathDiffPosition>,
+) {
+    let (changes, changed_fields) = match changed {
+        None if before.is_none() => (vec![AdaptivePathChangeKind::Added], Vec::new()),
+        None => (vec![AdaptivePathChangeKind::Removed], Vec::new()),
+        Some(changed) if changed.is_empty() => return,
+        Some(changed) => {
+            let mut changes = Vec::new();
+            if changed.iter().any(|field| {
+                matches!(
+                    *field,
+                    "order" | "parent_checkpoint_id" | "branch_id" | "phase_id"
+                )
+            }) {
+                changes.push(AdaptivePathChangeKind::Moved);
+            }
+            if changed
+                .iter()
+                .any(|field| matches!(*field, "label" | "title"))
+            {
+                changes.push(AdaptivePathChangeKind::Retitled);
+            }
+            if changed
+                .iter()
+                .any(|field| matches!(*field, "objective" | "status" | "source"))
+            {
+                changes.push(AdaptivePathChangeKind::Updated);
+            }
+            (
+                changes,
+                changed.into_iter().map(ToString::to_string).collect(),
+            )
+        }
+    };
+    entries.push(AdaptivePathDiffEntry {
+        node,
+        id: id.to_string(),
+        changes,
+        changed_fields,
+        before,
+        after,
+    });
+}
+
+fn branch_position(branch: &AdaptivePathBranch) -> AdaptivePathDiffPosition {
+    AdaptivePathDiffPosition {
+        parent_id: branch.parent_checkpoint_id.clone(),
+        order: branch.order,
+        title: branch.label.clone(),
+    }
+}
+
+fn phase_position(phase: &AdaptivePathPhase) -> AdaptivePathDiffPosition {
+    AdaptivePathDiffPosition {
+        parent_id: Some(phase.branch_id.clone()),
+        order: phase.order,
+        title: phase.title.clone(),
+    }
+}
+
+fn checkpoint_position(checkpoint: &AdaptivePathCheckpoint) -> AdaptivePathDiffPosition {
+    AdaptivePathDiffPosition {
+        parent_id: Some(checkpoint.phase_id.clone()),
+        order: checkpoint.order,
+        title: checkpoint.title.clone(),
+    }
+}
+
+/// Fields where `enforced` kept the current value over `requested`, as
+/// `branch:<id>.<field>` style paths.
+fn locked_fields_kept(
+    requested: &AdaptivePathPlanPayload,
+    enforced: &AdaptivePathPlanPayload,
+) -> Vec<String> {
+    let mut kept = Vec::new();
+    let requested_branches = map_branches_by_id(requested);
+    let requested_phases = map_phases_by_id(requested);
+    let requested_checkpoints = map_checkpoints_by_id(requested);
+    for branch in &enforced.branches {
+        if let Some(requested) = requested_branches.get(&branch.branch_id) {
+            for field in changed_branch_fields(requested, branch) {
+                kept.push(format!("branch:{}.{field}", branch.branch_id));
+            }
+        }
+        for phase in &branch.phases {
+            if let Some(requested) = requested_phases.get(&phase.phase_id) {
+                for field in changed_phase_fields(requested, phase) {
+                    kept.push(format!("phase:{}.{field}", phase.phase_id));
+                }
+            }
+            for checkpoint in &phase.checkpoints {
+                if let Some(requested) = requested_checkpoints.get(&checkpoint.checkpoint_id) {
+                    for field in changed_checkpoint_fields(requested, checkpoint) {
+                        kept.push(format!("checkpoint:{}.{field}", checkpoint.checkpoint_id));
+                    }
+                }
+            }
+        }
+    }
+    kept
+}
+
+/// Nodes of `plan` carrying locked fields that `payload` does not contain, as
+/// `branch:<id>` style paths.
+fn locked_nodes_missing(plan: &AdaptivePathPlan, payload: &AdaptivePathPlanPayload) -> Vec<String> {
+    let branches = map_branches_by_id(payload);
+    let phases = map_phases_by_id(payload);
+    let checkpoints = map_checkpoints_by_id(payload);
+    let mut missing = Vec::new();
+    for branch in &plan.branches {
+        if !branch.locked_fields.is_empty() && !branches.contains_key(&branch.branch_id) {
+            missing.push(format!("branch:{}", branch.branch_id));
+        }
+        for phase in &branch.phases {
+            if !phase.locked_fields.is_empty() && !phases.contains_key(&phase.phase_id) {
+                missing.push(format!("phase:{}", phase.phase_id));
+            }
+            for checkpoint in &phase.checkpoints {
+                if !checkpoint.locked_fields.is_empty()
+                    && !checkpoints.contains_key(&checkpoint.checkpoint_id)
+                {
+                    missing.push(format!("checkpoint:{}", checkpoint.checkpoint_id));
+                }
+            }
+        }
+    }
+    missing
+}
+
 fn map_plan_branches_by_id(plan: &AdaptivePathPlan) -> HashMap<String, AdaptivePathBranch> {
     plan.branches
         .iter()
@@ -1572,13 +2074,16 @@ struct AdaptivePathSuggestionAuditPayload {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::adaptive_path_roles::AdaptivePathRoleRule;
     use crate::ports;
+    use crate::ports::group::{GroupMemberRecord, GroupRecord};
     use std::sync::Arc;
     use tokio::sync::RwLock;
 
     #[derive(Default)]
     struct MockAdaptivePathRepository {
         plans: Arc<RwLock<HashMap<String, AdaptivePathPlan>>>,
+        plan_versions: Arc<RwLock<HashMap<(String, PlanVersion), AdaptivePathPlan>>>,
         by_entity: Arc<RwLock<HashMap<String, String>>>,
         by_request: Arc<RwLock<HashMap<(String, String), String>>>,
         events: Arc<RwLock<HashMap<String, Vec<AdaptivePathEvent>>>>,
@@ -1603,6 +2108,7 @@ mod tests {
         ) -> ports::BoxFuture<'_, DomainResult<AdaptivePathPlan>> {
             let plan = plan.clone();
             let plans = self.plans.clone();
+            let plan_versions = self.plan_versions.clone();
             let by_entity = self.by_entity.clone();
             let by_request = self.by_request.clone();
             Box::pin(async move {
@@ -1627,6 +2133,10 @@ mod tests {
                 by_entity.insert(plan.entity_id.clone(), plan.plan_id.clone());
                 by_request.insert(request_key, plan.plan_id.clone());
                 plans.insert(plan.plan_id.clone(), plan.clone());
+                plan_versions
+                    .write()
+                    .await
+                    .insert((plan.plan_id.clone(), plan.version), plan.clone());
                 Ok(plan)
             })
         }
@@ -1685,6 +2195,7 @@ mod tests {
         ) -> ports::BoxFuture<'_, DomainResult<AdaptivePathPlan>> {
             let plan = plan.clone();
             let plans = self.plans.clone();
+            let plan_versions = self.plan_versions.clone();
             let by_request = self.by_request.clone();
             Box::pin(async move {
                 let request_key = Self::plan_request_key(&plan.entity_id, &plan.request_id);
@@ -1701,10 +2212,41 @@ mod tests {
                     return Err(DomainError::NotFound);
                 }
                 plans.insert(plan.plan_id.clone(), plan.clone());
+                plan_versions
+                    .write()
+                    .await
+                    .insert((plan.plan_id.clone(), plan.version), plan.clone());
                 Ok(plan)
             })
         }
 
+        fn get_plan_version(
+            &self,
+            plan_id: &str,
+            version: PlanVersion,
+        ) -> ports::BoxFuture<'_, DomainResult<Option<AdaptivePathPlan>>> {
+            let key = (plan_id.to_string(), version);
+            let plan_versions = self.plan_versions.clone();
+            Box::pin(async move { Ok(plan_versions.read().await.get(&key).cloned()) })
+        }
+
+        fn list_plan_versions(
+            &self,
+            plan_id: &str,
+        ) -> ports::BoxFuture<'_, DomainResult<Vec<AdaptivePathPlanVersionSummary>>> {
+            let plan_id = plan_id.to_string();
+            let plan_versions = self.plan_versions.clone();
+            Box::pin(async move {
+                Ok(plan_versions
+                    .read()
+                    .await
+                    .values()
+                    .filter(|plan| plan.plan_id == plan_id)
+                    .map(AdaptivePathPlan::version_summary)
+                    .collect())
+            })
+        }
+
         fn create_event(
             &self,
             event: &AdaptivePathEvent,
@@ -1875,6 +2417,70 @@ mod tests {
         }
     }
 
+    fn participant() -> ActorIdentity {
+        ActorIdentity {
+            user_id: "user-2".to_string(),
+            username: "budi".to_string(),
+        }
+    }
+
+    /// Every entity is a group where `user-1` is admin and `user-2` a member.
+    struct MockGroupRepository;
+
+    impl ports::group::GroupRepository for MockGroupRepository {
+        fn create_group(
+            &self,
+            group: &GroupRecord,
+        ) -> ports::BoxFuture<'_, DomainResult<GroupRecord>> {
+            let group = group.clone();
+            Box::pin(async move { Ok(group) })
+        }
+
+        fn get_group(
+            &self,
+            group_id: &str,
+        ) -> ports::BoxFuture<'_, DomainResult<Option<GroupRecord>>> {
+            let member = |user_id: &str, role: &str| GroupMemberRecord {
+                user_id: user_id.to_string(),
+                name: user_id.to_string(),
+                avatar_url: None,
+                role: role.to_string(),
+                joined_at_ms: 0,
+            };
+            let group = GroupRecord {
+                group_id: group_id.to_string(),
+                name: group_id.to_string(),
+                description: String::new(),
+                entity_type: "kelompok".to_string(),
+                join_policy: "terbuka".to_string(),
+                member_count: 2,
+                witness_count: 0,
+                members: vec![member("user-1", "admin"), member("user-2", "anggota")],
+                pending_requests: Vec::new(),
+                updated_at_ms: 0,
+            };
+            Box::pin(async move { Ok(Some(group)) })
+        }
+
+        fn list_groups(&self) -> ports::BoxFuture<'_, DomainResult<Vec<GroupRecord>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn update_group(
+            &self,
+            group: &GroupRecord,
+        ) -> ports::BoxFuture<'_, DomainResult<GroupRecord>> {
+            let group = group.clone();
+            Box::pin(async move { Ok(group) })
+        }
+    }
+
+    fn managed_service(repo: Arc<MockAdaptivePathRepository>) -> AdaptivePathService {
+        AdaptivePathService::new(repo).with_role_resolver(
+            AdaptivePathRoleResolver::new().with_groups(Arc::new(MockGroupRepository)),
+        )
+    }
+
     fn sample_payload() -> AdaptivePathPlanPayloadDraft {
         AdaptivePathPlanPayloadDraft {
             title: "Plan title".to_string(),
@@ -1907,11 +2513,10 @@ mod tests {
     #[tokio::test]
     async fn create_plan_is_idempotent() {
         let repo = Arc::new(MockAdaptivePathRepository::default());
-        let service = AdaptivePathService::new(repo);
+        let service = managed_service(repo);
         let command = CreateAdaptivePathInput {
             entity_id: "entity-1".to_string(),
             payload: sample_payload(),
-            editor_roles: vec![AdaptivePathEditorRole::ProjectManager],
             request_id: "req-1".to_string(),
             correlation_id: "corr-1".to_string(),
             request_ts_ms: Some(1),
@@ -1931,7 +2536,7 @@ mod tests {
     #[tokio::test]
     async fn update_plan_applies_editor_locks() {
         let repo = Arc::new(MockAdaptivePathRepository::default());
-        let service = AdaptivePathService::new(repo);
+        let service = managed_service(repo);
         let create = service
             .create_plan(
                 &actor(),
@@ -1939,7 +2544,6 @@ mod tests {
                 CreateAdaptivePathInput {
                     entity_id: "entity-1".to_string(),
                     payload: sample_payload(),
-                    editor_roles: vec![AdaptivePathEditorRole::ProjectManager],
                     request_id: "req-create".to_string(),
                     correlation_id: "corr-1".to_string(),
                     request_ts_ms: Some(1),
@@ -1965,7 +2569,6 @@ mod tests {
                     plan_id: create.plan_id,
                     expected_version: 1,
                     payload: updated_payload,
-                    editor_roles: vec![AdaptivePathEditorRole::ProjectManager],
                     request_id: "req-update".to_string(),
                     correlation_id: "corr-2".to_string(),
                     request_ts_ms: Some(2),
@@ -1989,10 +2592,36 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn authors_need_a_resolved_privileged_role_to_edit() {
+        let repo = Arc::new(MockAdaptivePathRepository::default());
+        let service = AdaptivePathService::new(repo);
+        let plan = create_sample_plan(&service, "entity-unmanaged").await;
+
+        let update = service
+            .update_plan(
+                &actor(),
+                &Role::User,
+                UpdateAdaptivePathInput {
+                    plan_id: plan.plan_id.clone(),
+                    expected_version: plan.version,
+                    payload: sample_payload(),
+                    request_id: "req-unmanaged".to_string(),
+                    correlation_id: "corr-2".to_string(),
+                    request_ts_ms: Some(2),
+                },
+            )
+            .await;
+        assert!(matches!(update, Err(DomainError::Forbidden(_))));
+
+        let events = service.list_events(&plan.plan_id).await.expect("events");
+        assert_eq!(events[0].actor.editor_roles, vec!["author"]);
+    }
+
     #[tokio::test]
     async fn suggestion_respects_locked_fields() {
         let repo = Arc::new(MockAdaptivePathRepository::default());
-        let service = AdaptivePathService::new(repo.clone());
+        let service = managed_service(repo.clone());
         let plan = service
             .create_plan(
                 &actor(),
@@ -2000,7 +2629,6 @@ mod tests {
                 CreateAdaptivePathInput {
                     entity_id: "entity-2".to_string(),
                     payload: sample_payload(),
-                    editor_roles: vec![AdaptivePathEditorRole::ProjectManager],
                     request_id: "req-create-2".to_string(),
                     correlation_id: "corr-1".to_string(),
                     request_ts_ms: Some(1),
@@ -2026,7 +2654,6 @@ mod tests {
                     plan_id: plan.plan_id.clone(),
                     expected_version: 1,
                     payload: update_payload,
-                    editor_roles: vec![AdaptivePathEditorRole::ProjectManager],
                     request_id: "req-update-2".to_string(),
                     correlation_id: "corr-2".to_string(),
                     request_ts_ms: Some(2),
@@ -2055,7 +2682,6 @@ mod tests {
                     rationale: Some("no".to_string()),
                     model_id: Some("model-x".to_string()),
                     prompt_version: Some("1.0".to_string()),
-                    editor_roles: vec![AdaptivePathEditorRole::HighestProfileUser],
                     request_id: "req-suggest".to_string(),
                     correlation_id: "corr-3".to_string(),
                     request_ts_ms: Some(3),
@@ -2065,4 +2691,245 @@ mod tests {
             .expect("suggestion");
         assert_eq!(suggestion.status, SuggestionDecisionStatus::Pending);
     }
+
+    async fn create_sample_plan(
+        service: &AdaptivePathService,
+        entity_id: &str,
+    ) -> AdaptivePathPlan {
+        service
+            .create_plan(
+                &actor(),
+                &Role::User,
+                CreateAdaptivePathInput {
+                    entity_id: entity_id.to_string(),
+                    payload: sample_payload(),
+                    request_id: format!("req-create-{entity_id}"),
+                    correlation_id: "corr-1".to_string(),
+                    request_ts_ms: Some(1),
+                },
+            )
+            .await
+            .expect("create")
+    }
+
+    async fn update_sample_plan(
+        service: &AdaptivePathService,
+        plan: &AdaptivePathPlan,
+        payload: AdaptivePathPlanPayloadDraft,
+        request_id: &str,
+    ) -> AdaptivePathPlan {
+        service
+            .update_plan(
+                &actor(),
+                &Role::User,
+                UpdateAdaptivePathInput {
+                    plan_id: plan.plan_id.clone(),
+                    expected_version: plan.version,
+                    payload,
+                    request_id: request_id.to_string(),
+                    correlation_id: "corr-2".to_string(),
+                    request_ts_ms: Some(2),
+                },
+            )
+            .await
+            .expect("update")
+    }
+
+    #[tokio::test]
+    async fn diff_reports_added_removed_moved_and_retitled_nodes() {
+        let repo = Arc::new(MockAdaptivePathRepository::default());
+        let service = managed_service(repo);
+        let plan = create_sample_plan(&service, "entity-diff").await;
+
+        let mut payload = sample_payload();
+        let phase = &mut payload.branches[0].phases[0];
+        phase.title = "Fase persiapan".to_string();
+        phase.order = 1;
+        phase.checkpoints[0].checkpoint_id = Some("checkpoint-main-2".to_string());
+        phase.checkpoints[0].title = "Kumpulkan relawan".to_string();
+        let updated = update_sample_plan(&service, &plan, payload, "req-diff").await;
+
+        let versions = service
+            .list_plan_versions(&plan.plan_id)
+            .await
+            .expect("versions");
+        assert_eq!(
+            versions
+                .iter()
+                .map(|version| version.version)
+                .collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+
+        let diff = service
+            .diff_plan_versions(&plan.plan_id, 1, updated.version)
+            .await
+            .expect("diff");
+        assert!(diff.plan_fields.is_empty());
+        let changes = diff
+            .entries
+            .iter()
+            .map(|entry| (entry.node.clone(), entry.id.as_str(), entry.changes.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    AdaptivePathDiffNode::Phase,
+                    "phase-main",
+                    vec![
+                        AdaptivePathChangeKind::Moved,
+                        AdaptivePathChangeKind::Retitled
+                    ]
+                ),
+                (
+                    AdaptivePathDiffNode::Checkpoint,
+                    "checkpoint-main-2",
+                    vec![AdaptivePathChangeKind::Added]
+                ),
+                (
+                    AdaptivePathDiffNode::Checkpoint,
+                    "checkpoint-main-1",
+                    vec![AdaptivePathChangeKind::Removed]
+                ),
+            ]
+        );
+
+        let missing = service.diff_plan_versions(&plan.plan_id, 1, 9).await;
+        assert!(matches!(missing, Err(DomainError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn rollback_creates_new_version_and_keeps_locked_fields() {
+        let repo = Arc::new(MockAdaptivePathRepository::default());
+        let service = managed_service(repo);
+        let plan = create_sample_plan(&service, "entity-rollback").await;
+
+        // Version 2 retitles the phase, which locks its title, and adds a
+        // checkpoint.
+        let mut payload = sample_payload();
+        let phase = &mut payload.branches[0].phases[0];
+        phase.title = "Judul terkunci".to_string();
+        phase.checkpoints.push(AdaptivePathCheckpointDraftInput {
+            checkpoint_id: Some("checkpoint-main-2".to_string()),
+            title: "Checkpoint baru".to_string(),
+            status: AdaptivePathStatus::Open,
+            order: 1,
+            source: AdaptivePathSource::Human,
+        });
+        let updated = update_sample_plan(&service, &plan, payload, "req-rollback-update").await;
+
+        let rollback = RollbackAdaptivePathInput {
+            plan_id: plan.plan_id.clone(),
+            expected_version: updated.version,
+            target_version: 1,
+            request_id: "req-rollback".to_string(),
+            correlation_id: "corr-3".to_string(),
+            request_ts_ms: Some(3),
+        };
+        let participant = service
+            .rollback_plan(&participant(), &Role::User, rollback.clone())
+            .await;
+        assert!(matches!(participant, Err(DomainError::Forbidden(_))));
+
+        let rolled_back = service
+            .rollback_plan(&actor(), &Role::User, rollback.clone())
+            .await
+            .expect("rollback");
+        assert_eq!(rolled_back.version, 3);
+        let phase = &rolled_back.branches[0].phases[0];
+        assert_eq!(phase.title, "Judul terkunci");
+        assert_eq!(
+            phase
+                .checkpoints
+                .iter()
+                .map(|checkpoint| checkpoint.checkpoint_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["checkpoint-main-1"]
+        );
+
+        let stale = service
+            .rollback_plan(&actor(), &Role::User, rollback.clone())
+            .await;
+        assert!(matches!(stale, Err(DomainError::Conflict)));
+
+        let events = service.list_events(&plan.plan_id).await.expect("events");
+        let event = events.last().expect("rollback event");
+        assert_eq!(event.event_type, AdaptivePathEventType::PlanRolledBack);
+        assert_eq!((event.base_version, event.next_version), (2, 3));
+        let metadata = event.metadata.as_ref().expect("metadata");
+        assert_eq!(metadata["target_version"], serde_json::json!(1));
+        assert_eq!(
+            metadata["kept_locked_fields"],
+            serde_json::json!(["phase:phase-main.title"])
+        );
+        assert_eq!(
+            event.actor.editor_roles,
+            vec!["author", "participant", "project_manager"]
+        );
+        assert_eq!(
+            event
+                .actor
+                .role_grants
+                .iter()
+                .map(|grant| grant.rule)
+                .collect::<Vec<_>>(),
+            vec![
+                AdaptivePathRoleRule::PlanAuthor,
+                AdaptivePathRoleRule::GroupManager,
+                AdaptivePathRoleRule::GroupMember,
+            ]
+        );
+
+        let current = service
+            .rollback_plan(
+                &actor(),
+                &Role::User,
+                RollbackAdaptivePathInput {
+                    expected_version: 3,
+                    target_version: 3,
+                    request_id: "req-rollback-current".to_string(),
+                    ..rollback.clone()
+                },
+            )
+            .await;
+        assert!(matches!(current, Err(DomainError::Validation(_))));
+
+        // A checkpoint added after version 3 and then retitled carries a lock,
+        // so rolling back to 3 must not silently drop it.
+        let mut payload = sample_payload();
+        payload.branches[0].phases[0]
+            .checkpoints
+            .push(AdaptivePathCheckpointDraftInput {
+                checkpoint_id: Some("checkpoint-main-3".to_string()),
+                title: "Checkpoint baru".to_string(),
+                status: AdaptivePathStatus::Open,
+                order: 1,
+                source: AdaptivePathSource::Human,
+            });
+        let added = update_sample_plan(&service, &rolled_back, payload.clone(), "req-add").await;
+        payload.branches[0].phases[0].checkpoints[1].title = "Checkpoint terkunci".to_string();
+        let locked = update_sample_plan(&service, &added, payload, "req-lock").await;
+        let blocked = service
+            .rollback_plan(
+                &actor(),
+                &Role::User,
+                RollbackAdaptivePathInput {
+                    expected_version: locked.version,
+                    target_version: rolled_back.version,
+                    request_id: "req-rollback-locked".to_string(),
+                    ..rollback
+                },
+            )
+            .await;
+        match blocked {
+            Err(DomainError::Validation(message)) => {
+                assert!(
+                    message.contains("checkpoint:checkpoint-main-3"),
+                    "{message}"
+                );
+            }
+            other => panic!("expected validation error, got {other:?}"),
+        }
+    }
 }
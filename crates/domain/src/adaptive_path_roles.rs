@@ -0,0 +1,348 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::DomainResult;
+use crate::adaptive_path::AdaptivePathEditorRole;
+use crate::ports::group::GroupRepository;
+use crate::ports::reputation::ReputationLookup;
+
+/// Participants compared for the highest-profile role; the rest are ignored.
+const MAX_REPUTATION_LOOKUPS: usize = 200;
+
+/// The rule that granted an editor role.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AdaptivePathRoleRule {
+    PlanAuthor,
+    GroupManager,
+    GroupMember,
+    TopReputation,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AdaptivePathRoleGrant {
+    pub role: AdaptivePathEditorRole,
+    pub rule: AdaptivePathRoleRule,
+    /// Group or entity the rule matched on.
+    pub source_id: Option<String>,
+    /// Membership role the rule matched, e.g. `admin` or `moderator`.
+    pub source_role: Option<String>,
+    pub reputation: Option<f64>,
+}
+
+impl AdaptivePathRoleGrant {
+    fn new(role: AdaptivePathEditorRole, rule: AdaptivePathRoleRule) -> Self {
+        Self {
+            role,
+            rule,
+            source_id: None,
+            source_role: None,
+            reputation: None,
+        }
+    }
+
+    fn with_source(mut self, source_id: &str, source_role: Option<&str>) -> Self {
+        self.source_id = Some(source_id.to_string());
+        self.source_role = source_role.map(str::to_string);
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ResolvedEditorRoles {
+    pub roles: Vec<AdaptivePathEditorRole>,
+    pub grants: Vec<AdaptivePathRoleGrant>,
+}
+
+impl ResolvedEditorRoles {
+    fn from_grants(grants: Vec<AdaptivePathRoleGrant>) -> Self {
+        let mut roles: Vec<_> = grants.iter().map(|grant| grant.role.clone()).collect();
+        roles.sort_by_key(AdaptivePathEditorRole::as_str);
+        roles.dedup();
+        Self { roles, grants }
+    }
+}
+
+/// Resolves the editor roles of an actor on a plan from server-side state,
+/// never from the request:
+///
+/// - `author`: the actor wrote the plan.
+/// - `project_manager`: the actor is an admin or moderator of the group whose
+///   id is the plan's entity.
+/// - `participant`: the actor is a member of that group.
+///
+/// Chat threads are not a source: anyone can open a thread scoped to any
+/// entity and own it, so thread roles prove nothing about the plan.
+/// - `highest_profile_user`: the actor has the highest Markov reputation among
+///   the author and the members; not granted when reputation is unavailable.
+///
+/// Sources left unconfigured grant nothing.
+#[derive(Clone, Default)]
+pub struct AdaptivePathRoleResolver {
+    groups: Option<Arc<dyn GroupRepository>>,
+    reputation: Option<Arc<dyn ReputationLookup>>,
+}
+
+impl AdaptivePathRoleResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_groups(mut self, groups: Arc<dyn GroupRepository>) -> Self {
+        self.groups = Some(groups);
+        self
+    }
+
+    pub fn with_reputation(mut self, reputation: Arc<dyn ReputationLookup>) -> Self {
+        self.reputation = Some(reputation);
+        self
+    }
+
+    pub async fn resolve(
+        &self,
+        entity_id: &str,
+        author_id: &str,
+        actor_id: &str,
+    ) -> DomainResult<ResolvedEditorRoles> {
+        let mut grants = Vec::new();
+        if actor_id == author_id {
+            grants.push(AdaptivePathRoleGrant::new(
+                AdaptivePathEditorRole::Author,
+                AdaptivePathRoleRule::PlanAuthor,
+            ));
+        }
+
+        let mut seen = HashSet::from([author_id.to_string()]);
+        let mut participants = vec![author_id.to_string()];
+        let mut add_participant = |user_id: &str| {
+            if seen.insert(user_id.to_string()) {
+                participants.push(user_id.to_string());
+            }
+        };
+
+        if let Some(groups) = &self.groups
+            && let Some(group) = groups.get_group(entity_id).await?
+        {
+            for member in &group.members {
+                add_participant(&member.user_id);
+                if member.user_id != actor_id {
+                    continue;
+                }
+                if matches!(member.role.as_str(), "admin" | "moderator") {
+                    grants.push(
+                        AdaptivePathRoleGrant::new(
+                            AdaptivePathEditorRole::ProjectManager,
+                            AdaptivePathRoleRule::GroupManager,
+                        )
+                        .with_source(&group.group_id, Some(&member.role)),
+                    );
+                }
+                grants.push(
+                    AdaptivePathRoleGrant::new(
+                        AdaptivePathEditorRole::Participant,
+                        AdaptivePathRoleRule::GroupMember,
+                    )
+                    .with_source(&group.group_id, Some(&member.role)),
+                );
+            }
+        }
+
+        if let Some(grant) = self
+            .top_reputation_grant(entity_id, actor_id, participants)
+            .await
+        {
+            grants.push(grant);
+        }
+        Ok(ResolvedEditorRoles::from_grants(grants))
+    }
+
+    /// The actor must be a participant and compare against at least one other
+    /// participant the Markov engine knows; ties share the role.
+    async fn top_reputation_grant(
+        &self,
+        entity_id: &str,
+        actor_id: &str,
+        mut participants: Vec<String>,
+    ) -> Option<AdaptivePathRoleGrant> {
+        let lookup = self.reputation.as_ref()?;
+        let actor_index = participants
+            .iter()
+            .position(|user_id| user_id == actor_id)?;
+        let actor = participants.remove(actor_index);
+        participants.insert(0, actor);
+        participants.truncate(MAX_REPUTATION_LOOKUPS);
+
+        let reputations = lookup.reputations(&participants).await.ok()?;
+        let actor_reputation = *reputations.get(actor_id)?;
+        let mut others = reputations
+            .iter()
+            .filter(|(user_id, _)| user_id.as_str() != actor_id)
+            .map(|(_, reputation)| *reputation)
+            .peekable();
+        others.peek()?;
+        if others.any(|reputation| reputation > actor_reputation) {
+            return None;
+        }
+        let mut grant = AdaptivePathRoleGrant::new(
+            AdaptivePathEditorRole::HighestProfileUser,
+            AdaptivePathRoleRule::TopReputation,
+        )
+        .with_source(entity_id, None);
+        grant.reputation = Some(actor_reputation);
+        Some(grant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::error::DomainError;
+    use crate::ports::BoxFuture;
+    use crate::ports::group::{GroupMemberRecord, GroupRecord};
+
+    struct MockGroups(GroupRecord);
+
+    impl GroupRepository for MockGroups {
+        fn create_group(&self, group: &GroupRecord) -> BoxFuture<'_, DomainResult<GroupRecord>> {
+            let group = group.clone();
+            Box::pin(async move { Ok(group) })
+        }
+
+        fn get_group(&self, group_id: &str) -> BoxFuture<'_, DomainResult<Option<GroupRecord>>> {
+            let group = (self.0.group_id == group_id).then(|| self.0.clone());
+            Box::pin(async move { Ok(group) })
+        }
+
+        fn list_groups(&self) -> BoxFuture<'_, DomainResult<Vec<GroupRecord>>> {
+            let group = self.0.clone();
+            Box::pin(async move { Ok(vec![group]) })
+        }
+
+        fn update_group(&self, group: &GroupRecord) -> BoxFuture<'_, DomainResult<GroupRecord>> {
+            let group = group.clone();
+            Box::pin(async move { Ok(group) })
+        }
+    }
+
+    struct MockReputation(Option<HashMap<String, f64>>);
+
+    impl ReputationLookup for MockReputation {
+        fn reputations(
+            &self,
+            user_ids: &[String],
+        ) -> BoxFuture<'_, DomainResult<HashMap<String, f64>>> {
+            let result = match &self.0 {
+                Some(known) => Ok(user_ids
+                    .iter()
+                    .filter_map(|user_id| known.get(user_id).map(|value| (user_id.clone(), *value)))
+                    .collect()),
+                None => Err(DomainError::Validation("markov unavailable".into())),
+            };
+            Box::pin(async move { result })
+        }
+    }
+
+    fn member(user_id: &str, role: &str) -> GroupMemberRecord {
+        GroupMemberRecord {
+            user_id: user_id.to_string(),
+            name: user_id.to_string(),
+            avatar_url: None,
+            role: role.to_string(),
+            joined_at_ms: 0,
+        }
+    }
+
+    fn resolver(reputation: Option<HashMap<String, f64>>) -> AdaptivePathRoleResolver {
+        AdaptivePathRoleResolver::new()
+            .with_groups(Arc::new(MockGroups(GroupRecord {
+                group_id: "group-1".to_string(),
+                name: "RT 05".to_string(),
+                description: String::new(),
+                entity_type: "kelompok".to_string(),
+                join_policy: "terbuka".to_string(),
+                member_count: 3,
+                witness_count: 0,
+                members: vec![
+                    member("ketua", "admin"),
+                    member("warga", "anggota"),
+                    member("tokoh", "anggota"),
+                ],
+                pending_requests: Vec::new(),
+                updated_at_ms: 0,
+            })))
+            .with_reputation(Arc::new(MockReputation(reputation)))
+    }
+
+    fn reputations() -> HashMap<String, f64> {
+        HashMap::from([
+            ("author".to_string(), 0.4),
+            ("ketua".to_string(), 0.5),
+            ("warga".to_string(), 0.2),
+            ("tokoh".to_string(), 0.9),
+        ])
+    }
+
+    #[tokio::test]
+    async fn roles_come_from_membership_and_reputation() {
+        let resolver = resolver(Some(reputations()));
+
+        let manager = resolver
+            .resolve("group-1", "author", "ketua")
+            .await
+            .expect("resolve");
+        assert_eq!(
+            manager.roles,
+            vec![
+                AdaptivePathEditorRole::Participant,
+                AdaptivePathEditorRole::ProjectManager,
+            ]
+        );
+        assert_eq!(manager.grants[0].rule, AdaptivePathRoleRule::GroupManager);
+        assert_eq!(manager.grants[0].source_id.as_deref(), Some("group-1"));
+        assert_eq!(manager.grants[0].source_role.as_deref(), Some("admin"));
+
+        let top = resolver
+            .resolve("group-1", "author", "tokoh")
+            .await
+            .expect("resolve");
+        let grant = top
+            .grants
+            .iter()
+            .find(|grant| grant.rule == AdaptivePathRoleRule::TopReputation)
+            .expect("top reputation grant");
+        assert_eq!(grant.role, AdaptivePathEditorRole::HighestProfileUser);
+        assert_eq!(grant.reputation, Some(0.9));
+
+        let author = resolver
+            .resolve("group-1", "author", "author")
+            .await
+            .expect("resolve");
+        assert_eq!(author.roles, vec![AdaptivePathEditorRole::Author]);
+
+        let outsider = resolver
+            .resolve("group-1", "author", "outsider")
+            .await
+            .expect("resolve");
+        assert!(outsider.roles.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unavailable_reputation_grants_no_profile_role() {
+        let resolved = resolver(None)
+            .resolve("group-1", "author", "tokoh")
+            .await
+            .expect("resolve");
+        assert_eq!(resolved.roles, vec![AdaptivePathEditorRole::Participant]);
+
+        // Only unknown participants to compare against.
+        let lonely = resolver(Some(HashMap::from([("tokoh".to_string(), 0.1)])))
+            .resolve("group-1", "author", "tokoh")
+            .await
+            .expect("resolve");
+        assert_eq!(lonely.roles, vec![AdaptivePathEditorRole::Participant]);
+    }
+}
@@ -11,6 +11,7 @@ use crate::ports::chat::ChatRepository;
 const MAX_BODY_LENGTH: usize = 2_000;
 const MAX_ATTACHMENT_COUNT: usize = 20;
 const MAX_MESSAGES_PER_REQUEST: usize = 200;
+const MAX_TRANSCRIPT_MESSAGES: usize = 5_000;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -20,6 +21,48 @@ pub enum ChatMemberRole {
     Member,
 }
 
+/// How long messages in a thread are kept before the retention sweep tombstones them.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ChatRetentionPolicy {
+    #[default]
+    #[serde(rename = "forever")]
+    Forever,
+    #[serde(rename = "24h")]
+    Day,
+    #[serde(rename = "7d")]
+    Week,
+}
+
+impl ChatRetentionPolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Forever => "forever",
+            Self::Day => "24h",
+            Self::Week => "7d",
+        }
+    }
+
+    pub fn parse(value: &str) -> DomainResult<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "forever" => Ok(Self::Forever),
+            "24h" => Ok(Self::Day),
+            "7d" => Ok(Self::Week),
+            _ => Err(DomainError::Validation(
+                "retention must be one of: 24h, 7d, forever".into(),
+            )),
+        }
+    }
+
+    /// Age after which a message expires; `None` keeps messages indefinitely.
+    pub fn max_age_ms(self) -> Option<i64> {
+        match self {
+            Self::Forever => None,
+            Self::Day => Some(24 * 60 * 60 * 1000),
+            Self::Week => Some(7 * 24 * 60 * 60 * 1000),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub struct ChatThread {
@@ -27,6 +70,8 @@ pub struct ChatThread {
     pub scope_id: String,
     pub created_by: String,
     pub privacy_level: String,
+    #[serde(default)]
+    pub retention: ChatRetentionPolicy,
     pub created_at_ms: i64,
     pub updated_at_ms: i64,
 }
@@ -35,6 +80,9 @@ pub struct ChatThread {
 pub struct ChatThreadWithMembers {
     pub thread: ChatThread,
     pub member_count: usize,
+    /// Messages from other members after the actor's read cursor.
+    #[serde(default)]
+    pub unread_count: usize,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -69,6 +117,21 @@ pub struct ChatReadCursor {
     pub last_read_at_ms: i64,
 }
 
+/// Unread totals across every thread the actor belongs to, for the app-wide badge.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChatUnreadSummary {
+    pub total_unread: usize,
+    pub threads_with_unread: usize,
+}
+
+/// A member whose read cursor is at or past a given message.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChatReadReceipt {
+    pub user_id: String,
+    pub last_read_message_id: String,
+    pub read_at_ms: i64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ChatDeliveryEvent {
     pub event_id: String,
@@ -80,6 +143,84 @@ pub struct ChatDeliveryEvent {
     pub correlation_id: String,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChatAttachment {
+    pub attachment_id: String,
+    pub uploaded_by: String,
+    pub thread_id: Option<String>,
+    pub file_name: String,
+    pub mime_type: String,
+    pub media_type: String,
+    pub size_bytes: u64,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub thumbnail_size_bytes: Option<u64>,
+    pub created_at_ms: i64,
+}
+
+impl ChatAttachment {
+    /// Bytes charged against storage quotas: the original plus its generated thumbnail.
+    pub fn stored_bytes(&self) -> u64 {
+        self.size_bytes
+            .saturating_add(self.thumbnail_size_bytes.unwrap_or_default())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChatAttachmentQuota {
+    pub per_user_bytes: u64,
+    pub per_thread_bytes: u64,
+}
+
+impl ChatAttachmentQuota {
+    /// Rejects `incoming_bytes` when it would push the uploader's usage, or the
+    /// thread's usage when the attachment belongs to one, over its limit.
+    pub fn check(
+        &self,
+        user_usage: u64,
+        thread_usage: Option<u64>,
+        incoming_bytes: u64,
+    ) -> DomainResult<()> {
+        if user_usage.saturating_add(incoming_bytes) > self.per_user_bytes {
+            return Err(DomainError::Validation(self.user_exceeded_message()));
+        }
+        if thread_usage
+            .is_some_and(|usage| usage.saturating_add(incoming_bytes) > self.per_thread_bytes)
+        {
+            return Err(DomainError::Validation(self.thread_exceeded_message()));
+        }
+        Ok(())
+    }
+
+    pub fn user_exceeded_message(&self) -> String {
+        format!(
+            "attachment storage quota of {} bytes exceeded for user",
+            self.per_user_bytes
+        )
+    }
+
+    pub fn thread_exceeded_message(&self) -> String {
+        format!(
+            "attachment storage quota of {} bytes exceeded for thread",
+            self.per_thread_bytes
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ChatAttachmentUsageQuery {
+    pub uploaded_by: Option<String>,
+    pub thread_id: Option<String>,
+}
+
+/// A message past its thread's retention window, with the stored attachments that
+/// must be removed from storage before the message is tombstoned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExpiredChatMessage {
+    pub message: ChatMessage,
+    pub attachments: Vec<ChatAttachment>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ChatThreadCreate {
     pub scope_id: String,
@@ -109,6 +250,26 @@ pub struct MessageCatchup {
     pub limit: usize,
 }
 
+/// Optional inclusive `created_at_ms` bounds for a transcript export.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChatTranscriptRange {
+    pub from_ms: Option<i64>,
+    pub to_ms: Option<i64>,
+}
+
+/// Messages of a thread, oldest first, as seen by a current member at export time.
+/// `truncated` is set when the range held more than the export cap.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChatTranscript {
+    pub thread: ChatThread,
+    pub members: Vec<ChatMember>,
+    pub messages: Vec<ChatMessage>,
+    pub range: ChatTranscriptRange,
+    pub truncated: bool,
+    pub generated_by: String,
+    pub generated_at_ms: i64,
+}
+
 #[derive(Clone)]
 pub struct ChatService {
     repository: Arc<dyn ChatRepository>,
@@ -135,6 +296,7 @@ impl ChatService {
             scope_id: input.scope_id,
             created_by: actor.user_id.clone(),
             privacy_level: input.privacy_level,
+            retention: ChatRetentionPolicy::Forever,
             created_at_ms: now,
             updated_at_ms: now,
         };
@@ -186,7 +348,21 @@ impl ChatService {
         &self,
         actor: &ActorIdentity,
     ) -> DomainResult<Vec<ChatThreadWithMembers>> {
-        self.repository.list_threads_by_user(&actor.user_id).await
+        let mut threads = self.repository.list_threads_by_user(&actor.user_id).await?;
+        for item in &mut threads {
+            item.unread_count = self
+                .unread_count(&item.thread.thread_id, &actor.user_id)
+                .await?;
+        }
+        Ok(threads)
+    }
+
+    pub async fn unread_summary(&self, actor: &ActorIdentity) -> DomainResult<ChatUnreadSummary> {
+        let threads = self.list_threads_by_user_with_members(actor).await?;
+        Ok(ChatUnreadSummary {
+            total_unread: threads.iter().map(|item| item.unread_count).sum(),
+            threads_with_unread: threads.iter().filter(|item| item.unread_count > 0).count(),
+        })
     }
 
     pub async fn list_threads_by_scope(
@@ -260,6 +436,121 @@ impl ChatService {
         self.repository.create_member(&member).await
     }
 
+    /// Only active owners and admins may change how long a thread keeps its messages.
+    pub async fn set_thread_retention(
+        &self,
+        actor: &ActorIdentity,
+        thread_id: &str,
+        retention: ChatRetentionPolicy,
+    ) -> DomainResult<ChatThread> {
+        let mut thread = self.get_thread(thread_id).await?;
+        let member = self
+            .repository
+            .get_member(thread_id, &actor.user_id)
+            .await?
+            .filter(|member| member.left_at_ms.is_none())
+            .ok_or_else(|| DomainError::Validation("user is not a member of this thread".into()))?;
+        if !matches!(member.role, ChatMemberRole::Owner | ChatMemberRole::Admin) {
+            return Err(DomainError::Forbidden(
+                "only thread owners and admins can change retention".into(),
+            ));
+        }
+        if thread.retention == retention {
+            return Ok(thread);
+        }
+        thread.retention = retention;
+        thread.updated_at_ms = now_ms();
+        self.repository.update_thread(&thread).await
+    }
+
+    pub async fn list_threads_with_retention(&self) -> DomainResult<Vec<ChatThread>> {
+        self.repository.list_threads_with_retention().await
+    }
+
+    /// Returns up to `limit` live messages older than the thread's retention window,
+    /// oldest first, together with the attachments they own. Attachments referenced
+    /// by a message are only included when they were uploaded into this thread, so a
+    /// message cannot cause a file shared elsewhere to be removed.
+    pub async fn list_expired_messages(
+        &self,
+        thread: &ChatThread,
+        now_ms: i64,
+        limit: usize,
+    ) -> DomainResult<Vec<ExpiredChatMessage>> {
+        let Some(max_age_ms) = thread.retention.max_age_ms() else {
+            return Ok(Vec::new());
+        };
+        let messages = self
+            .repository
+            .list_messages_created_before(&thread.thread_id, now_ms - max_age_ms, limit)
+            .await?;
+        let mut expired = Vec::with_capacity(messages.len());
+        for message in messages {
+            let mut attachments = Vec::new();
+            for attachment_id in message_attachment_ids(&message) {
+                let Some(attachment) = self.repository.get_attachment(&attachment_id).await? else {
+                    continue;
+                };
+                if attachment.thread_id.as_deref() == Some(thread.thread_id.as_str()) {
+                    attachments.push(attachment);
+                }
+            }
+            expired.push(ExpiredChatMessage {
+                message,
+                attachments,
+            });
+        }
+        Ok(expired)
+    }
+
+    /// Soft-deletes an expired message once its attachment objects are gone: the
+    /// attachment records are dropped, the message keeps its id and timestamp with an
+    /// empty body so read cursors and catch-up cursors pointing at it stay valid, and
+    /// a `message_deleted` delivery event is recorded once per message.
+    pub async fn expire_message(
+        &self,
+        expired: &ExpiredChatMessage,
+        deleted_at_ms: i64,
+    ) -> DomainResult<ChatMessage> {
+        for attachment in &expired.attachments {
+            self.repository
+                .delete_attachment(&attachment.attachment_id)
+                .await?;
+        }
+
+        let mut tombstone = expired.message.clone();
+        tombstone.body = String::new();
+        tombstone.attachments = Vec::new();
+        tombstone.deleted_at_ms = Some(deleted_at_ms);
+        let tombstone = self.repository.update_message(&tombstone).await?;
+
+        let request_id = format!("retention:{}", tombstone.message_id);
+        if self
+            .repository
+            .get_delivery_event_by_request(&tombstone.thread_id, &request_id)
+            .await?
+            .is_none()
+        {
+            let created = self
+                .repository
+                .create_delivery_event(&ChatDeliveryEvent {
+                    event_id: crate::util::uuid_v7_without_dashes(),
+                    thread_id: tombstone.thread_id.clone(),
+                    message_id: tombstone.message_id.clone(),
+                    event_type: "message_deleted".to_string(),
+                    occurred_at_ms: deleted_at_ms,
+                    request_id,
+                    correlation_id: tombstone.correlation_id.clone(),
+                })
+                .await;
+            match created {
+                Ok(_) | Err(DomainError::Conflict) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(tombstone)
+    }
+
     pub async fn list_members(&self, thread_id: &str) -> DomainResult<Vec<ChatMember>> {
         self.repository.list_members(thread_id).await
     }
@@ -358,6 +649,269 @@ impl ChatService {
             .ok_or(DomainError::NotFound)
     }
 
+    /// Collects the messages of a thread for export. Only members who have not left
+    /// (`left_at_ms` unset) may export; tombstoned messages are kept so the transcript
+    /// can mark them as deleted.
+    pub async fn export_transcript(
+        &self,
+        actor: &ActorIdentity,
+        thread_id: &str,
+        range: ChatTranscriptRange,
+    ) -> DomainResult<ChatTranscript> {
+        if range.from_ms.is_some_and(|from_ms| from_ms < 0)
+            || range.to_ms.is_some_and(|to_ms| to_ms < 0)
+        {
+            return Err(DomainError::Validation(
+                "transcript range must be non-negative".into(),
+            ));
+        }
+        if let (Some(from_ms), Some(to_ms)) = (range.from_ms, range.to_ms) {
+            if from_ms > to_ms {
+                return Err(DomainError::Validation(
+                    "from_ms must not be after to_ms".into(),
+                ));
+            }
+        }
+        let thread = self.get_thread(thread_id).await?;
+        let member = self
+            .repository
+            .get_member(thread_id, &actor.user_id)
+            .await?
+            .ok_or_else(|| DomainError::Forbidden("only thread members may export".into()))?;
+        if member.left_at_ms.is_some() {
+            return Err(DomainError::Forbidden(
+                "only current thread members may export".into(),
+            ));
+        }
+
+        let mut cursor = MessageCatchup {
+            since_created_at_ms: Some(range.from_ms.unwrap_or(0).saturating_sub(1)),
+            since_message_id: None,
+            limit: MAX_MESSAGES_PER_REQUEST,
+        };
+        let mut messages = Vec::new();
+        let mut truncated = false;
+        'pages: loop {
+            let page = self.repository.list_messages(thread_id, &cursor).await?;
+            let page_len = page.len();
+            for message in page {
+                if range
+                    .to_ms
+                    .is_some_and(|to_ms| message.created_at_ms > to_ms)
+                {
+                    break 'pages;
+                }
+                cursor.since_created_at_ms = Some(message.created_at_ms);
+                cursor.since_message_id = Some(message.message_id.clone());
+                if range
+                    .from_ms
+                    .is_some_and(|from_ms| message.created_at_ms < from_ms)
+                {
+                    continue;
+                }
+                if messages.len() == MAX_TRANSCRIPT_MESSAGES {
+                    truncated = true;
+                    break 'pages;
+                }
+                messages.push(message);
+            }
+            if page_len < cursor.limit {
+                break;
+            }
+        }
+
+        let members = self.repository.list_members(thread_id).await?;
+        Ok(ChatTranscript {
+            thread,
+            members,
+            messages,
+            range,
+            truncated,
+            generated_by: actor.user_id.clone(),
+            generated_at_ms: now_ms(),
+        })
+    }
+
+    /// Current members whose read cursor has reached `message_id`, earliest reader
+    /// first. The author is left out; a cursor pointing at a message that no longer
+    /// exists does not count as a read.
+    pub async fn list_read_receipts(
+        &self,
+        actor: &ActorIdentity,
+        thread_id: &str,
+        message_id: &str,
+    ) -> DomainResult<Vec<ChatReadReceipt>> {
+        self.assert_actor_can_send_message(thread_id, actor).await?;
+        let message = self
+            .repository
+            .get_message(thread_id, message_id)
+            .await?
+            .ok_or(DomainError::NotFound)?;
+        let active_members: std::collections::HashSet<String> = self
+            .repository
+            .list_members(thread_id)
+            .await?
+            .into_iter()
+            .map(|member| member.user_id)
+            .collect();
+        let target = (message.created_at_ms, message.message_id.clone());
+
+        let mut positions = std::collections::HashMap::<String, Option<(i64, String)>>::new();
+        let mut receipts = Vec::new();
+        for cursor in self.repository.list_read_cursors(thread_id).await? {
+            if cursor.user_id == message.author_id || !active_members.contains(&cursor.user_id) {
+                continue;
+            }
+            let position = match positions.get(&cursor.last_read_message_id) {
+                Some(position) => position.clone(),
+                None => {
+                    let position = self
+                        .read_position(thread_id, &cursor.last_read_message_id)
+                        .await?;
+                    positions.insert(cursor.last_read_message_id.clone(), position.clone());
+                    position
+                }
+            };
+            if position.is_some_and(|position| position >= target) {
+                receipts.push(ChatReadReceipt {
+                    user_id: cursor.user_id,
+                    last_read_message_id: cursor.last_read_message_id,
+                    read_at_ms: cursor.last_read_at_ms,
+                });
+            }
+        }
+        receipts.sort_by(|a, b| {
+            a.read_at_ms
+                .cmp(&b.read_at_ms)
+                .then_with(|| a.user_id.cmp(&b.user_id))
+        });
+        Ok(receipts)
+    }
+
+    async fn unread_count(&self, thread_id: &str, user_id: &str) -> DomainResult<usize> {
+        let after = match self.repository.get_read_cursor(thread_id, user_id).await? {
+            Some(cursor) => {
+                self.read_position(thread_id, &cursor.last_read_message_id)
+                    .await?
+            }
+            None => None,
+        };
+        self.repository
+            .count_unread_messages(thread_id, user_id, after)
+            .await
+    }
+
+    /// Sort position of the message a read cursor points at.
+    async fn read_position(
+        &self,
+        thread_id: &str,
+        message_id: &str,
+    ) -> DomainResult<Option<(i64, String)>> {
+        Ok(self
+            .repository
+            .get_message(thread_id, message_id)
+            .await?
+            .map(|message| (message.created_at_ms, message.message_id)))
+    }
+
+    /// Rejects an upload of `incoming_bytes` when it would push the uploader, or the
+    /// target thread when one is given, over its attachment storage quota. This is an
+    /// early rejection before bytes are transferred; `record_attachment` charges the
+    /// quota for real.
+    pub async fn check_attachment_quota(
+        &self,
+        actor: &ActorIdentity,
+        thread_id: Option<&str>,
+        incoming_bytes: u64,
+        quota: &ChatAttachmentQuota,
+    ) -> DomainResult<()> {
+        if let Some(thread_id) = thread_id {
+            self.get_thread(thread_id).await?;
+            self.assert_actor_can_send_message(thread_id, actor).await?;
+        }
+
+        let user_usage = self
+            .repository
+            .sum_attachment_bytes(&ChatAttachmentUsageQuery {
+                uploaded_by: Some(actor.user_id.clone()),
+                thread_id: None,
+            })
+            .await?;
+        let thread_usage = match thread_id {
+            Some(thread_id) => Some(
+                self.repository
+                    .sum_attachment_bytes(&ChatAttachmentUsageQuery {
+                        uploaded_by: None,
+                        thread_id: Some(thread_id.to_string()),
+                    })
+                    .await?,
+            ),
+            None => None,
+        };
+        quota.check(user_usage, thread_usage, incoming_bytes)
+    }
+
+    /// Records the attachment and charges it against the uploader's and thread's
+    /// quotas. The repository re-checks usage and inserts in one atomic write, so
+    /// concurrent uploads cannot both fit under a limit only one of them fits.
+    pub async fn record_attachment(
+        &self,
+        actor: &ActorIdentity,
+        attachment: ChatAttachment,
+        quota: &ChatAttachmentQuota,
+    ) -> DomainResult<ChatAttachment> {
+        if attachment.uploaded_by != actor.user_id {
+            return Err(DomainError::Forbidden(
+                "attachment uploader must match actor".into(),
+            ));
+        }
+        if let Some(thread_id) = attachment.thread_id.as_deref() {
+            self.get_thread(thread_id).await?;
+            self.assert_actor_can_send_message(thread_id, actor).await?;
+        }
+        self.repository.create_attachment(&attachment, quota).await
+    }
+
+    /// Drops an attachment record whose stored objects could not be written, which
+    /// releases the quota it reserved.
+    pub async fn release_attachment(
+        &self,
+        actor: &ActorIdentity,
+        attachment_id: &str,
+    ) -> DomainResult<()> {
+        let Some(attachment) = self.repository.get_attachment(attachment_id).await? else {
+            return Ok(());
+        };
+        if attachment.uploaded_by != actor.user_id {
+            return Err(DomainError::Forbidden(
+                "attachment uploader must match actor".into(),
+            ));
+        }
+        self.repository.delete_attachment(attachment_id).await
+    }
+
+    /// Attachments are visible to their uploader and to active members of the thread
+    /// they were uploaded into.
+    pub async fn get_attachment(
+        &self,
+        actor: &ActorIdentity,
+        attachment_id: &str,
+    ) -> DomainResult<ChatAttachment> {
+        let attachment = self
+            .repository
+            .get_attachment(attachment_id)
+            .await?
+            .ok_or(DomainError::NotFound)?;
+        if attachment.uploaded_by == actor.user_id {
+            return Ok(attachment);
+        }
+        let Some(thread_id) = attachment.thread_id.as_deref() else {
+            return Err(DomainError::NotFound);
+        };
+        self.assert_actor_is_member(actor, thread_id).await?;
+        Ok(attachment)
+    }
+
     pub async fn assert_actor_is_member(
         &self,
         actor: &ActorIdentity,
@@ -430,6 +984,25 @@ fn validate_message_input(body: &str, attachments: &[serde_json::Value]) -> Doma
     Ok(())
 }
 
+/// Attachment ids referenced by a message payload (`{"attachment_id": ...}` entries).
+fn message_attachment_ids(message: &ChatMessage) -> Vec<String> {
+    let mut ids = Vec::new();
+    for attachment in &message.attachments {
+        let Some(attachment_id) = attachment
+            .get("attachment_id")
+            .and_then(|value| value.as_str())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+        else {
+            continue;
+        };
+        if !ids.iter().any(|existing| existing == attachment_id) {
+            ids.push(attachment_id.to_string());
+        }
+    }
+    ids
+}
+
 pub fn build_message_catchup(
     limit: Option<usize>,
     since_created_at_ms: Option<i64>,
@@ -460,6 +1033,7 @@ mod tests {
         by_request: Arc<RwLock<HashMap<(String, String), String>>>,
         cursors: Arc<RwLock<HashMap<(String, String), ChatReadCursor>>>,
         events: Arc<RwLock<HashMap<(String, String), ChatDeliveryEvent>>>,
+        attachments: Arc<RwLock<HashMap<String, ChatAttachment>>>,
     }
 
     impl ChatRepository for MockChatRepo {
@@ -485,6 +1059,32 @@ mod tests {
             })
         }
 
+        fn update_thread(&self, thread: &ChatThread) -> BoxFuture<'_, DomainResult<ChatThread>> {
+            let thread = thread.clone();
+            let threads = self.threads.clone();
+            Box::pin(async move {
+                let mut threads = threads.write().await;
+                if !threads.contains_key(&thread.thread_id) {
+                    return Err(DomainError::NotFound);
+                }
+                threads.insert(thread.thread_id.clone(), thread.clone());
+                Ok(thread)
+            })
+        }
+
+        fn list_threads_with_retention(&self) -> BoxFuture<'_, DomainResult<Vec<ChatThread>>> {
+            let threads = self.threads.clone();
+            Box::pin(async move {
+                Ok(threads
+                    .read()
+                    .await
+                    .values()
+                    .filter(|thread| thread.retention != ChatRetentionPolicy::Forever)
+                    .cloned()
+                    .collect())
+            })
+        }
+
         fn list_threads_by_scope(
             &self,
             query: &ChatThreadQuery,
@@ -569,6 +1169,7 @@ mod tests {
                     thread_list.push(ChatThreadWithMembers {
                         thread,
                         member_count,
+                        unread_count: 0,
                     });
                 }
                 thread_list.sort_by(|a, b| b.thread.created_at_ms.cmp(&a.thread.created_at_ms));
@@ -717,6 +1318,53 @@ mod tests {
             })
         }
 
+        fn list_messages_created_before(
+            &self,
+            thread_id: &str,
+            cutoff_ms: i64,
+            limit: usize,
+        ) -> BoxFuture<'_, DomainResult<Vec<ChatMessage>>> {
+            let thread_id = thread_id.to_string();
+            let messages = self.messages.clone();
+            Box::pin(async move {
+                let mut messages: Vec<_> = messages
+                    .read()
+                    .await
+                    .values()
+                    .filter(|message| {
+                        message.thread_id == thread_id
+                            && message.deleted_at_ms.is_none()
+                            && message.created_at_ms < cutoff_ms
+                    })
+                    .cloned()
+                    .collect();
+                messages.sort_by(|a, b| {
+                    a.created_at_ms
+                        .cmp(&b.created_at_ms)
+                        .then_with(|| a.message_id.cmp(&b.message_id))
+                });
+                messages.truncate(limit);
+                Ok(messages)
+            })
+        }
+
+        fn update_message(
+            &self,
+            message: &ChatMessage,
+        ) -> BoxFuture<'_, DomainResult<ChatMessage>> {
+            let message = message.clone();
+            let messages = self.messages.clone();
+            Box::pin(async move {
+                let mut messages = messages.write().await;
+                let key = (message.thread_id.clone(), message.message_id.clone());
+                if !messages.contains_key(&key) {
+                    return Err(DomainError::NotFound);
+                }
+                messages.insert(key, message.clone());
+                Ok(message)
+            })
+        }
+
         fn set_read_cursor(
             &self,
             cursor: &ChatReadCursor,
@@ -746,6 +1394,50 @@ mod tests {
             })
         }
 
+        fn list_read_cursors(
+            &self,
+            thread_id: &str,
+        ) -> BoxFuture<'_, DomainResult<Vec<ChatReadCursor>>> {
+            let thread_id = thread_id.to_string();
+            let cursors = self.cursors.clone();
+            Box::pin(async move {
+                Ok(cursors
+                    .read()
+                    .await
+                    .values()
+                    .filter(|cursor| cursor.thread_id == thread_id)
+                    .cloned()
+                    .collect())
+            })
+        }
+
+        fn count_unread_messages(
+            &self,
+            thread_id: &str,
+            user_id: &str,
+            after: Option<(i64, String)>,
+        ) -> BoxFuture<'_, DomainResult<usize>> {
+            let thread_id = thread_id.to_string();
+            let user_id = user_id.to_string();
+            let messages = self.messages.clone();
+            Box::pin(async move {
+                Ok(messages
+                    .read()
+                    .await
+                    .values()
+                    .filter(|message| {
+                        message.thread_id == thread_id
+                            && message.author_id != user_id
+                            && message.deleted_at_ms.is_none()
+                            && after.as_ref().is_none_or(|(created_at_ms, message_id)| {
+                                (message.created_at_ms, &message.message_id)
+                                    > (*created_at_ms, message_id)
+                            })
+                    })
+                    .count())
+            })
+        }
+
         fn create_delivery_event(
             &self,
             event: &ChatDeliveryEvent,
@@ -775,6 +1467,81 @@ mod tests {
                 Ok(events.get(&key).cloned())
             })
         }
+
+        fn create_attachment(
+            &self,
+            attachment: &ChatAttachment,
+            quota: &ChatAttachmentQuota,
+        ) -> BoxFuture<'_, DomainResult<ChatAttachment>> {
+            let attachment = attachment.clone();
+            let quota = quota.clone();
+            let attachments = self.attachments.clone();
+            Box::pin(async move {
+                let mut attachments = attachments.write().await;
+                if attachments.contains_key(&attachment.attachment_id) {
+                    return Err(DomainError::Conflict);
+                }
+                let user_usage = attachments
+                    .values()
+                    .filter(|item| item.uploaded_by == attachment.uploaded_by)
+                    .map(ChatAttachment::stored_bytes)
+                    .sum();
+                let thread_usage = attachment.thread_id.as_ref().map(|thread_id| {
+                    attachments
+                        .values()
+                        .filter(|item| item.thread_id.as_ref() == Some(thread_id))
+                        .map(ChatAttachment::stored_bytes)
+                        .sum()
+                });
+                quota.check(user_usage, thread_usage, attachment.stored_bytes())?;
+                attachments.insert(attachment.attachment_id.clone(), attachment.clone());
+                Ok(attachment)
+            })
+        }
+
+        fn get_attachment(
+            &self,
+            attachment_id: &str,
+        ) -> BoxFuture<'_, DomainResult<Option<ChatAttachment>>> {
+            let attachment_id = attachment_id.to_string();
+            let attachments = self.attachments.clone();
+            Box::pin(async move { Ok(attachments.read().await.get(&attachment_id).cloned()) })
+        }
+
+        fn delete_attachment(&self, attachment_id: &str) -> BoxFuture<'_, DomainResult<()>> {
+            let attachment_id = attachment_id.to_string();
+            let attachments = self.attachments.clone();
+            Box::pin(async move {
+                attachments.write().await.remove(&attachment_id);
+                Ok(())
+            })
+        }
+
+        fn sum_attachment_bytes(
+            &self,
+            query: &ChatAttachmentUsageQuery,
+        ) -> BoxFuture<'_, DomainResult<u64>> {
+            let query = query.clone();
+            let attachments = self.attachments.clone();
+            Box::pin(async move {
+                Ok(attachments
+                    .read()
+                    .await
+                    .values()
+                    .filter(|item| {
+                        query
+                            .uploaded_by
+                            .as_ref()
+                            .is_none_or(|user_id| &item.uploaded_by == user_id)
+                            && query
+                                .thread_id
+                                .as_ref()
+                                .is_none_or(|thread_id| item.thread_id.as_ref() == Some(thread_id))
+                    })
+                    .map(ChatAttachment::stored_bytes)
+                    .sum())
+            })
+        }
     }
 
     fn message_cursor_filter(
@@ -890,6 +1657,533 @@ mod tests {
         assert_eq!(public_threads.len(), 2);
     }
 
+    fn attachment(attachment_id: &str, uploaded_by: &str, thread_id: &str) -> ChatAttachment {
+        ChatAttachment {
+            attachment_id: attachment_id.to_string(),
+            uploaded_by: uploaded_by.to_string(),
+            thread_id: Some(thread_id.to_string()),
+            file_name: "foto.jpg".to_string(),
+            mime_type: "image/jpeg".to_string(),
+            media_type: "image".to_string(),
+            size_bytes: 600,
+            width: Some(40),
+            height: Some(30),
+            thumbnail_size_bytes: Some(100),
+            created_at_ms: 1_000,
+        }
+    }
+
+    fn unlimited_quota() -> ChatAttachmentQuota {
+        ChatAttachmentQuota {
+            per_user_bytes: u64::MAX,
+            per_thread_bytes: u64::MAX,
+        }
+    }
+
+    #[tokio::test]
+    async fn attachment_quota_counts_user_and_thread_usage() {
+        let repo = Arc::new(MockChatRepo::default());
+        let service = ChatService::new(repo);
+        let alice = ActorIdentity {
+            user_id: "alice".to_string(),
+            username: "alice".to_string(),
+        };
+        let bob = ActorIdentity {
+            user_id: "bob".to_string(),
+            username: "bob".to_string(),
+        };
+        let thread = service
+            .create_thread(
+                &alice,
+                "req-quota".to_string(),
+                "corr-quota".to_string(),
+                ChatThreadCreate {
+                    scope_id: "scope-1".to_string(),
+                    privacy_level: "public".to_string(),
+                },
+            )
+            .await
+            .expect("thread");
+        service
+            .join_thread(&bob, &thread.thread_id)
+            .await
+            .expect("join");
+        let quota = ChatAttachmentQuota {
+            per_user_bytes: 1_000,
+            per_thread_bytes: 1_200,
+        };
+        service
+            .record_attachment(
+                &alice,
+                attachment("att-1", "alice", &thread.thread_id),
+                &quota,
+            )
+            .await
+            .expect("record");
+
+        assert!(
+            service
+                .check_attachment_quota(&alice, Some(&thread.thread_id), 300, &quota)
+                .await
+                .is_ok()
+        );
+        let user_err = service
+            .check_attachment_quota(&alice, None, 301, &quota)
+            .await
+            .unwrap_err();
+        assert!(matches!(user_err, DomainError::Validation(msg) if msg.ends_with("for user")));
+        let thread_err = service
+            .check_attachment_quota(&bob, Some(&thread.thread_id), 501, &quota)
+            .await
+            .unwrap_err();
+        assert!(matches!(thread_err, DomainError::Validation(msg) if msg.ends_with("for thread")));
+    }
+
+    #[tokio::test]
+    async fn attachment_record_charges_quota_atomically() {
+        let repo = Arc::new(MockChatRepo::default());
+        let service = ChatService::new(repo.clone());
+        let alice = ActorIdentity {
+            user_id: "alice".to_string(),
+            username: "alice".to_string(),
+        };
+        let thread = service
+            .create_thread(
+                &alice,
+                "req-quota-record".to_string(),
+                "corr-quota-record".to_string(),
+                ChatThreadCreate {
+                    scope_id: "scope-1".to_string(),
+                    privacy_level: "public".to_string(),
+                },
+            )
+            .await
+            .expect("thread");
+        let quota = ChatAttachmentQuota {
+            per_user_bytes: 1_000,
+            per_thread_bytes: 1_000,
+        };
+
+        // Both uploads pass the early check, but only one fits once charged.
+        for _ in 0..2 {
+            service
+                .check_attachment_quota(&alice, Some(&thread.thread_id), 700, &quota)
+                .await
+                .expect("early check");
+        }
+        service
+            .record_attachment(
+                &alice,
+                attachment("att-1", "alice", &thread.thread_id),
+                &quota,
+            )
+            .await
+            .expect("first record");
+        let err = service
+            .record_attachment(
+                &alice,
+                attachment("att-2", "alice", &thread.thread_id),
+                &quota,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Validation(msg) if msg.ends_with("for user")));
+        assert!(repo.get_attachment("att-2").await.expect("get").is_none());
+
+        service
+            .release_attachment(&alice, "att-1")
+            .await
+            .expect("release");
+        service
+            .record_attachment(
+                &alice,
+                attachment("att-2", "alice", &thread.thread_id),
+                &quota,
+            )
+            .await
+            .expect("record after release");
+    }
+
+    #[tokio::test]
+    async fn attachment_record_rejects_foreign_uploader() {
+        let service = ChatService::new(Arc::new(MockChatRepo::default()));
+        let actor = ActorIdentity {
+            user_id: "alice".to_string(),
+            username: "alice".to_string(),
+        };
+        let err = service
+            .record_attachment(
+                &actor,
+                attachment("att-1", "mallory", "thread-1"),
+                &unlimited_quota(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Forbidden(_)));
+    }
+
+    #[tokio::test]
+    async fn retention_requires_owner_or_admin() {
+        let repo = Arc::new(MockChatRepo::default());
+        let service = ChatService::new(repo);
+        let owner = ActorIdentity {
+            user_id: "u-owner".to_string(),
+            username: "owner".to_string(),
+        };
+        let member = ActorIdentity {
+            user_id: "u-member".to_string(),
+            username: "member".to_string(),
+        };
+        let thread = service
+            .create_thread(
+                &owner,
+                "req-thread".to_string(),
+                "corr-1".to_string(),
+                ChatThreadCreate {
+                    scope_id: "scope-1".to_string(),
+                    privacy_level: "private".to_string(),
+                },
+            )
+            .await
+            .expect("thread");
+        service
+            .join_thread(&member, &thread.thread_id)
+            .await
+            .expect("join");
+
+        let err = service
+            .set_thread_retention(&member, &thread.thread_id, ChatRetentionPolicy::Day)
+            .await
+            .expect_err("member cannot change retention");
+        assert!(matches!(err, DomainError::Forbidden(_)));
+
+        let updated = service
+            .set_thread_retention(&owner, &thread.thread_id, ChatRetentionPolicy::Week)
+            .await
+            .expect("owner sets retention");
+        assert_eq!(updated.retention, ChatRetentionPolicy::Week);
+        assert_eq!(
+            service
+                .list_threads_with_retention()
+                .await
+                .expect("threads")
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn expired_messages_are_tombstoned_with_owned_attachments() {
+        let repo = Arc::new(MockChatRepo::default());
+        let service = ChatService::new(repo.clone());
+        let actor = ActorIdentity {
+            user_id: "u-1".to_string(),
+            username: "alice".to_string(),
+        };
+        let thread = service
+            .create_thread(
+                &actor,
+                "req-thread".to_string(),
+                "corr-1".to_string(),
+                ChatThreadCreate {
+                    scope_id: "scope-1".to_string(),
+                    privacy_level: "private".to_string(),
+                },
+            )
+            .await
+            .expect("thread");
+        let thread = service
+            .set_thread_retention(&actor, &thread.thread_id, ChatRetentionPolicy::Day)
+            .await
+            .expect("retention");
+        service
+            .record_attachment(
+                &actor,
+                attachment("att-own", "u-1", &thread.thread_id),
+                &unlimited_quota(),
+            )
+            .await
+            .expect("own attachment");
+        repo.attachments.write().await.insert(
+            "att-foreign".to_string(),
+            attachment("att-foreign", "u-2", "other-thread"),
+        );
+        // The author's own upload into another thread still belongs to that thread.
+        repo.attachments.write().await.insert(
+            "att-author-elsewhere".to_string(),
+            attachment("att-author-elsewhere", "u-1", "other-thread"),
+        );
+
+        let day_ms = ChatRetentionPolicy::Day.max_age_ms().expect("day");
+        let now = 10 * day_ms;
+        let old = service
+            .send_message(
+                &actor,
+                SendMessageInput {
+                    thread_id: thread.thread_id.clone(),
+                    body: "old".to_string(),
+                    attachments: vec![
+                        serde_json::json!({"attachment_id": "att-own"}),
+                        serde_json::json!({"attachment_id": "att-foreign"}),
+                        serde_json::json!({"attachment_id": "att-author-elsewhere"}),
+                    ],
+                    request_id: "msg-old".to_string(),
+                    correlation_id: "corr-old".to_string(),
+                    occurred_at_ms: Some(now - day_ms - 1),
+                },
+            )
+            .await
+            .expect("old message");
+        service
+            .send_message(
+                &actor,
+                SendMessageInput {
+                    thread_id: thread.thread_id.clone(),
+                    body: "recent".to_string(),
+                    attachments: vec![],
+                    request_id: "msg-recent".to_string(),
+                    correlation_id: "corr-recent".to_string(),
+                    occurred_at_ms: Some(now - 1_000),
+                },
+            )
+            .await
+            .expect("recent message");
+        service
+            .mark_read(&actor, &thread.thread_id, old.message_id.clone())
+            .await
+            .expect("read cursor");
+
+        let expired = service
+            .list_expired_messages(&thread, now, 100)
+            .await
+            .expect("expired");
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].message.message_id, old.message_id);
+        let attachment_ids: Vec<_> = expired[0]
+            .attachments
+            .iter()
+            .map(|item| item.attachment_id.as_str())
+            .collect();
+        assert_eq!(attachment_ids, vec!["att-own"]);
+
+        let tombstone = service
+            .expire_message(&expired[0], now)
+            .await
+            .expect("expire");
+        assert_eq!(tombstone.deleted_at_ms, Some(now));
+        assert!(tombstone.body.is_empty());
+        assert!(tombstone.attachments.is_empty());
+        service
+            .expire_message(&expired[0], now)
+            .await
+            .expect("expire is idempotent");
+
+        let attachments = repo.attachments.read().await;
+        assert!(!attachments.contains_key("att-own"));
+        assert!(attachments.contains_key("att-foreign"));
+        drop(attachments);
+        let event = repo
+            .get_delivery_event_by_request(
+                &thread.thread_id,
+                &format!("retention:{}", old.message_id),
+            )
+            .await
+            .expect("event lookup")
+            .expect("deletion event");
+        assert_eq!(event.event_type, "message_deleted");
+        let cursor = service
+            .get_read_cursor(&actor, &thread.thread_id)
+            .await
+            .expect("cursor survives");
+        assert_eq!(cursor.last_read_message_id, old.message_id);
+        assert!(
+            service
+                .list_expired_messages(&thread, now, 100)
+                .await
+                .expect("expired")
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn transcript_export_pages_range_and_requires_current_membership() {
+        let repo = Arc::new(MockChatRepo::default());
+        let service = ChatService::new(repo.clone());
+        let owner = ActorIdentity {
+            user_id: "u-1".to_string(),
+            username: "alice".to_string(),
+        };
+        let leaver = ActorIdentity {
+            user_id: "u-2".to_string(),
+            username: "budi".to_string(),
+        };
+        let thread = service
+            .create_thread(
+                &owner,
+                "req-thread".to_string(),
+                "corr-1".to_string(),
+                ChatThreadCreate {
+                    scope_id: "scope-1".to_string(),
+                    privacy_level: "public".to_string(),
+                },
+            )
+            .await
+            .expect("thread");
+        service
+            .join_thread(&leaver, &thread.thread_id)
+            .await
+            .expect("join");
+        for index in 0..250_i64 {
+            service
+                .send_message(
+                    &owner,
+                    SendMessageInput {
+                        thread_id: thread.thread_id.clone(),
+                        body: format!("pesan {index}"),
+                        attachments: vec![],
+                        request_id: format!("msg-{index}"),
+                        correlation_id: format!("corr-{index}"),
+                        occurred_at_ms: Some(1_000 + index),
+                    },
+                )
+                .await
+                .expect("message");
+        }
+        repo.members
+            .write()
+            .await
+            .get_mut(&(thread.thread_id.clone(), leaver.user_id.clone()))
+            .expect("leaver membership")
+            .left_at_ms = Some(2_000);
+
+        let full = service
+            .export_transcript(&owner, &thread.thread_id, ChatTranscriptRange::default())
+            .await
+            .expect("full export");
+        assert_eq!(full.messages.len(), 250);
+        assert!(!full.truncated);
+        assert_eq!(full.members.len(), 1);
+
+        let ranged = service
+            .export_transcript(
+                &owner,
+                &thread.thread_id,
+                ChatTranscriptRange {
+                    from_ms: Some(1_010),
+                    to_ms: Some(1_019),
+                },
+            )
+            .await
+            .expect("ranged export");
+        let bodies: Vec<_> = ranged
+            .messages
+            .iter()
+            .map(|message| message.body.as_str())
+            .collect();
+        assert_eq!(bodies.len(), 10);
+        assert_eq!(bodies.first(), Some(&"pesan 10"));
+        assert_eq!(bodies.last(), Some(&"pesan 19"));
+
+        let err = service
+            .export_transcript(&leaver, &thread.thread_id, ChatTranscriptRange::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Forbidden(_)));
+        let err = service
+            .export_transcript(
+                &owner,
+                &thread.thread_id,
+                ChatTranscriptRange {
+                    from_ms: Some(2_000),
+                    to_ms: Some(1_000),
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn unread_counts_and_receipts_follow_read_cursors() {
+        let repo = Arc::new(MockChatRepo::default());
+        let service = ChatService::new(repo.clone());
+        let owner = ActorIdentity {
+            user_id: "u-1".to_string(),
+            username: "alice".to_string(),
+        };
+        let reader = ActorIdentity {
+            user_id: "u-2".to_string(),
+            username: "budi".to_string(),
+        };
+        let thread = service
+            .create_thread(
+                &owner,
+                "req-thread".to_string(),
+                "corr-1".to_string(),
+                ChatThreadCreate {
+                    scope_id: "scope-1".to_string(),
+                    privacy_level: "public".to_string(),
+                },
+            )
+            .await
+            .expect("thread");
+        service
+            .join_thread(&reader, &thread.thread_id)
+            .await
+            .expect("join");
+        let mut sent = Vec::new();
+        for index in 0..3_i64 {
+            let message = service
+                .send_message(
+                    &owner,
+                    SendMessageInput {
+                        thread_id: thread.thread_id.clone(),
+                        body: format!("pesan {index}"),
+                        attachments: vec![],
+                        request_id: format!("msg-{index}"),
+                        correlation_id: format!("corr-{index}"),
+                        occurred_at_ms: Some(1_000 + index),
+                    },
+                )
+                .await
+                .expect("message");
+            sent.push(message);
+        }
+
+        let summary = service.unread_summary(&reader).await.expect("summary");
+        assert_eq!(summary.total_unread, 3);
+        assert_eq!(summary.threads_with_unread, 1);
+        let owner_threads = service
+            .list_threads_by_user_with_members(&owner)
+            .await
+            .expect("owner threads");
+        assert_eq!(owner_threads[0].unread_count, 0);
+
+        service
+            .mark_read(&reader, &thread.thread_id, sent[1].message_id.clone())
+            .await
+            .expect("mark read");
+        let reader_threads = service
+            .list_threads_by_user_with_members(&reader)
+            .await
+            .expect("reader threads");
+        assert_eq!(reader_threads[0].unread_count, 1);
+
+        let receipts = service
+            .list_read_receipts(&owner, &thread.thread_id, &sent[0].message_id)
+            .await
+            .expect("receipts");
+        let readers: Vec<_> = receipts.iter().map(|item| item.user_id.as_str()).collect();
+        assert_eq!(readers, vec!["u-2"]);
+        assert!(
+            service
+                .list_read_receipts(&owner, &thread.thread_id, &sent[2].message_id)
+                .await
+                .expect("receipts")
+                .is_empty()
+        );
+    }
+
     #[test]
     fn message_validation_rejects_empty_body() {
         assert!(validate_message_input("", &[]).is_err());
@@ -0,0 +1,649 @@
+use std::sync::Arc;
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use time::{Duration, OffsetDateTime, Time, UtcOffset};
+
+use crate::DomainResult;
+use crate::discovery::WeeklyDigest;
+use crate::error::DomainError;
+use crate::jobs::now_ms;
+use crate::ports::digest::DigestSubscriptionRepository;
+
+const DEFAULT_SEND_HOUR_LOCAL: u8 = 7;
+const DEFAULT_SEND_WEEKDAY: u8 = 1;
+const DEFAULT_TIMEZONE: &str = "Asia/Jakarta";
+const MAX_EMAIL_LENGTH: usize = 254;
+const MAX_RENDERED_EVENTS: usize = 20;
+const UNSUBSCRIBE_TOKEN_CONTEXT: &[u8] = b"gotong:digest-unsubscribe:";
+const ONE_DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestFrequency {
+    #[default]
+    Weekly,
+    Daily,
+    Off,
+}
+
+impl DigestFrequency {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Weekly => "weekly",
+            Self::Daily => "daily",
+            Self::Off => "off",
+        }
+    }
+
+    pub fn parse(value: &str) -> DomainResult<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "weekly" => Ok(Self::Weekly),
+            "daily" => Ok(Self::Daily),
+            "off" => Ok(Self::Off),
+            other => Err(DomainError::Validation(format!(
+                "unsupported digest frequency '{other}'"
+            ))),
+        }
+    }
+
+    fn period_ms(self) -> Option<i64> {
+        match self {
+            Self::Weekly => Some(7 * ONE_DAY_MS),
+            Self::Daily => Some(ONE_DAY_MS),
+            Self::Off => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestLocale {
+    #[default]
+    Id,
+    En,
+}
+
+impl DigestLocale {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::En => "en",
+        }
+    }
+
+    pub fn parse(value: &str) -> DomainResult<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "id" | "id-id" => Ok(Self::Id),
+            "en" | "en-us" | "en-gb" => Ok(Self::En),
+            other => Err(DomainError::Validation(format!(
+                "unsupported digest locale '{other}'"
+            ))),
+        }
+    }
+}
+
+/// Per-user email digest settings and delivery bookkeeping.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DigestSubscription {
+    pub user_id: String,
+    pub email: String,
+    pub locale: DigestLocale,
+    /// IANA zone name (Indonesian zones and `UTC`) or a fixed offset such as `+07:00`.
+    pub timezone: String,
+    pub frequency: DigestFrequency,
+    pub send_hour_local: u8,
+    /// ISO weekday, 1 = Monday. Ignored for daily digests.
+    pub send_weekday: u8,
+    pub last_sent_at_ms: Option<i64>,
+    pub created_at_ms: i64,
+    pub updated_at_ms: i64,
+}
+
+impl DigestSubscription {
+    /// Most recent scheduled send instant at or before `now_ms`, if digests are enabled.
+    pub fn latest_slot_ms(&self, now_ms: i64) -> DomainResult<Option<i64>> {
+        let Some(period_ms) = self.frequency.period_ms() else {
+            return Ok(None);
+        };
+        let offset = resolve_timezone(&self.timezone)?;
+        let send_time = Time::from_hms(self.send_hour_local, 0, 0)
+            .map_err(|_| DomainError::Validation("send_hour_local must be 0..=23".into()))?;
+        let local_now = datetime_from_ms(now_ms)?.to_offset(offset);
+        let days_back = match self.frequency {
+            DigestFrequency::Weekly => {
+                let today = i64::from(local_now.weekday().number_from_monday());
+                (today - i64::from(self.send_weekday)).rem_euclid(7)
+            }
+            _ => 0,
+        };
+        let candidate = local_now.replace_time(send_time) - Duration::days(days_back);
+        let mut slot_ms = datetime_to_ms(candidate);
+        if slot_ms > now_ms {
+            slot_ms -= period_ms;
+        }
+        Ok(Some(slot_ms))
+    }
+
+    /// The digest window `[start, end)` for the latest slot when it has not been sent yet.
+    pub fn due_window(&self, now_ms: i64) -> DomainResult<Option<(i64, i64)>> {
+        let Some(slot_ms) = self.latest_slot_ms(now_ms)? else {
+            return Ok(None);
+        };
+        let watermark_ms = self.last_sent_at_ms.unwrap_or(self.created_at_ms);
+        if self.email.is_empty() || watermark_ms >= slot_ms {
+            return Ok(None);
+        }
+        let period_ms = self.frequency.period_ms().unwrap_or(ONE_DAY_MS);
+        Ok(Some((slot_ms - period_ms, slot_ms)))
+    }
+}
+
+/// Fields a user may change on their digest settings; `None` keeps the stored value.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DigestSettingsUpdate {
+    pub email: Option<String>,
+    pub locale: Option<DigestLocale>,
+    pub timezone: Option<String>,
+    pub frequency: Option<DigestFrequency>,
+    pub send_hour_local: Option<u8>,
+    pub send_weekday: Option<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenderedDigest {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+#[derive(Clone)]
+pub struct DigestService {
+    repository: Arc<dyn DigestSubscriptionRepository>,
+}
+
+impl DigestService {
+    pub fn new(repository: Arc<dyn DigestSubscriptionRepository>) -> Self {
+        Self { repository }
+    }
+
+    pub async fn get_settings(&self, user_id: &str) -> DomainResult<Option<DigestSubscription>> {
+        validate_user_id(user_id)?;
+        self.repository.get_subscription(user_id).await
+    }
+
+    pub async fn update_settings(
+        &self,
+        user_id: &str,
+        update: DigestSettingsUpdate,
+    ) -> DomainResult<DigestSubscription> {
+        validate_user_id(user_id)?;
+        let now_ms = now_ms();
+        let mut subscription = match self.repository.get_subscription(user_id).await? {
+            Some(existing) => existing,
+            None => DigestSubscription {
+                user_id: user_id.to_string(),
+                email: String::new(),
+                locale: DigestLocale::default(),
+                timezone: DEFAULT_TIMEZONE.to_string(),
+                frequency: DigestFrequency::default(),
+                send_hour_local: DEFAULT_SEND_HOUR_LOCAL,
+                send_weekday: DEFAULT_SEND_WEEKDAY,
+                last_sent_at_ms: None,
+                created_at_ms: now_ms,
+                updated_at_ms: now_ms,
+            },
+        };
+        if let Some(email) = update.email {
+            subscription.email = normalize_email(&email)?;
+        }
+        if let Some(locale) = update.locale {
+            subscription.locale = locale;
+        }
+        if let Some(timezone) = update.timezone {
+            let timezone = timezone.trim().to_string();
+            resolve_timezone(&timezone)?;
+            subscription.timezone = timezone;
+        }
+        if let Some(frequency) = update.frequency {
+            subscription.frequency = frequency;
+        }
+        if let Some(hour) = update.send_hour_local {
+            if hour > 23 {
+                return Err(DomainError::Validation(
+                    "send_hour_local must be 0..=23".into(),
+                ));
+            }
+            subscription.send_hour_local = hour;
+        }
+        if let Some(weekday) = update.send_weekday {
+            if !(1..=7).contains(&weekday) {
+                return Err(DomainError::Validation(
+                    "send_weekday must be 1 (Monday) ..= 7 (Sunday)".into(),
+                ));
+            }
+            subscription.send_weekday = weekday;
+        }
+        if subscription.frequency != DigestFrequency::Off && subscription.email.is_empty() {
+            return Err(DomainError::Validation(
+                "email is required to enable digests".into(),
+            ));
+        }
+        subscription.updated_at_ms = now_ms;
+        self.repository.upsert_subscription(&subscription).await
+    }
+
+    /// Loads the subscription named by a signed unsubscribe token without changing it.
+    pub async fn subscription_for_token(
+        &self,
+        secret: &str,
+        token: &str,
+    ) -> DomainResult<DigestSubscription> {
+        let user_id = verify_unsubscribe_token(secret, token)?;
+        self.repository
+            .get_subscription(&user_id)
+            .await?
+            .ok_or(DomainError::NotFound)
+    }
+
+    /// Turns digests off for the user named by a signed unsubscribe token.
+    pub async fn unsubscribe(&self, secret: &str, token: &str) -> DomainResult<DigestSubscription> {
+        let mut subscription = self.subscription_for_token(secret, token).await?;
+        subscription.frequency = DigestFrequency::Off;
+        subscription.updated_at_ms = now_ms();
+        self.repository.upsert_subscription(&subscription).await
+    }
+}
+
+/// Resolves the zones we schedule for. Indonesia observes no DST, so fixed offsets are exact.
+pub fn resolve_timezone(timezone: &str) -> DomainResult<UtcOffset> {
+    let trimmed = timezone.trim();
+    let hours = match trimmed {
+        "Asia/Jakarta" | "Asia/Pontianak" | "WIB" => Some(7),
+        "Asia/Makassar" | "Asia/Ujung_Pandang" | "WITA" => Some(8),
+        "Asia/Jayapura" | "WIT" => Some(9),
+        "UTC" | "Etc/UTC" | "Z" => Some(0),
+        _ => None,
+    };
+    if let Some(hours) = hours {
+        return UtcOffset::from_hms(hours, 0, 0)
+            .map_err(|_| DomainError::Validation("invalid timezone offset".into()));
+    }
+    parse_fixed_offset(trimmed).ok_or_else(|| {
+        DomainError::Validation(format!(
+            "unsupported timezone '{trimmed}'; use an Indonesian IANA zone, UTC or an offset like +07:00"
+        ))
+    })
+}
+
+fn parse_fixed_offset(value: &str) -> Option<UtcOffset> {
+    let value = value.strip_prefix("UTC").unwrap_or(value);
+    let (sign, rest) = match value.as_bytes().first()? {
+        b'+' => (1i8, &value[1..]),
+        b'-' => (-1i8, &value[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((hours, minutes)) => (hours.parse::<i8>().ok()?, minutes.parse::<i8>().ok()?),
+        None => (rest.parse::<i8>().ok()?, 0),
+    };
+    if !(0..=14).contains(&hours) || !(0..60).contains(&minutes) {
+        return None;
+    }
+    UtcOffset::from_hms(sign * hours, sign * minutes, 0).ok()
+}
+
+pub fn sign_unsubscribe_token(secret: &str, user_id: &str) -> DomainResult<String> {
+    let mac = unsubscribe_mac(secret, user_id)?;
+    Ok(format!(
+        "{}.{}",
+        hex::encode(user_id.as_bytes()),
+        hex::encode(mac.finalize().into_bytes())
+    ))
+}
+
+/// Returns the user id carried by a token produced by [`sign_unsubscribe_token`].
+pub fn verify_unsubscribe_token(secret: &str, token: &str) -> DomainResult<String> {
+    let invalid = || DomainError::Forbidden("invalid unsubscribe token".into());
+    let (user_hex, signature_hex) = token.trim().split_once('.').ok_or_else(invalid)?;
+    let user_id = hex::decode(user_hex)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or_else(invalid)?;
+    let signature = hex::decode(signature_hex).map_err(|_| invalid())?;
+    unsubscribe_mac(secret, &user_id)?
+        .verify_slice(&signature)
+        .map_err(|_| invalid())?;
+    Ok(user_id)
+}
+
+fn unsubscribe_mac(secret: &str, user_id: &str) -> DomainResult<HmacSha256> {
+    if secret.is_empty() {
+        return Err(DomainError::Validation(
+            "digest unsubscribe secret is not configured".into(),
+        ));
+    }
+    validate_user_id(user_id)?;
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .map_err(|_| DomainError::Validation("invalid digest unsubscribe secret".into()))?;
+    mac.update(UNSUBSCRIBE_TOKEN_CONTEXT);
+    mac.update(user_id.as_bytes());
+    Ok(mac)
+}
+
+pub fn render_digest(
+    digest: &WeeklyDigest,
+    subscription: &DigestSubscription,
+    unsubscribe_url: &str,
+) -> DomainResult<RenderedDigest> {
+    let offset = resolve_timezone(&subscription.timezone)?;
+    let copy = DigestCopy::for_locale(subscription.locale, subscription.frequency);
+    let start = local_date(digest.window_start_ms, offset)?;
+    let end = local_date(digest.window_end_ms - 1, offset)?;
+    let event_count = digest.events.len();
+    let subject = format!("{} ({start} – {end})", copy.subject);
+
+    let mut text = format!(
+        "{}\n\n{}\n{}\n",
+        copy.heading,
+        (copy.summary)(event_count, digest.unread_count),
+        "=".repeat(40)
+    );
+    let mut html = format!(
+        "<!doctype html>\n<html lang=\"{}\">\n<body>\n<h1>{}</h1>\n<p>{}</p>\n",
+        subscription.locale.as_str(),
+        escape_html(copy.heading),
+        escape_html(&(copy.summary)(event_count, digest.unread_count)),
+    );
+    if event_count == 0 {
+        text.push_str(copy.empty);
+        text.push('\n');
+        html.push_str(&format!("<p>{}</p>\n", escape_html(copy.empty)));
+    } else {
+        html.push_str("<ul>\n");
+        for event in digest.events.iter().take(MAX_RENDERED_EVENTS) {
+            let item = &event.item;
+            let date = local_date(item.occurred_at_ms, offset)?;
+            let summary = item.summary.as_deref().unwrap_or_default();
+            text.push_str(&format!("- [{date}] {}\n", item.title));
+            if !summary.is_empty() {
+                text.push_str(&format!("  {summary}\n"));
+            }
+            html.push_str(&format!(
+                "<li><strong>{}</strong> <small>{date}</small>",
+                escape_html(&item.title)
+            ));
+            if !summary.is_empty() {
+                html.push_str(&format!("<br>{}", escape_html(summary)));
+            }
+            html.push_str("</li>\n");
+        }
+        html.push_str("</ul>\n");
+        if event_count > MAX_RENDERED_EVENTS {
+            let more = (copy.more)(event_count - MAX_RENDERED_EVENTS);
+            text.push_str(&format!("{more}\n"));
+            html.push_str(&format!("<p>{}</p>\n", escape_html(&more)));
+        }
+    }
+    text.push_str(&format!(
+        "\n{}\n{}: {unsubscribe_url}\n",
+        copy.footer, copy.unsubscribe
+    ));
+    html.push_str(&format!(
+        "<hr>\n<p><small>{} <a href=\"{}\">{}</a></small></p>\n</body>\n</html>\n",
+        escape_html(copy.footer),
+        escape_html(unsubscribe_url),
+        escape_html(copy.unsubscribe),
+    ));
+
+    Ok(RenderedDigest {
+        subject,
+        text,
+        html,
+    })
+}
+
+struct DigestCopy {
+    subject: &'static str,
+    heading: &'static str,
+    summary: fn(usize, usize) -> String,
+    more: fn(usize) -> String,
+    empty: &'static str,
+    footer: &'static str,
+    unsubscribe: &'static str,
+}
+
+impl DigestCopy {
+    fn for_locale(locale: DigestLocale, frequency: DigestFrequency) -> Self {
+        let daily = frequency == DigestFrequency::Daily;
+        match locale {
+            DigestLocale::Id => Self {
+                subject: if daily {
+                    "Ringkasan harian Gotong"
+                } else {
+                    "Ringkasan mingguan Gotong"
+                },
+                heading: if daily {
+                    "Kabar hari ini"
+                } else {
+                    "Kabar minggu ini"
+                },
+                summary: |events, unread| {
+                    format!("{events} kabar baru, {unread} notifikasi belum dibaca.")
+                },
+                more: |count| format!("…dan {count} kabar lainnya di aplikasi."),
+                empty: "Belum ada kabar baru untuk periode ini.",
+                footer: "Anda menerima email ini karena mengaktifkan ringkasan Gotong.",
+                unsubscribe: "Berhenti berlangganan",
+            },
+            DigestLocale::En => Self {
+                subject: if daily {
+                    "Your Gotong daily digest"
+                } else {
+                    "Your Gotong weekly digest"
+                },
+                heading: if daily {
+                    "Today's updates"
+                } else {
+                    "This week's updates"
+                },
+                summary: |events, unread| {
+                    format!("{events} new updates, {unread} unread notifications.")
+                },
+                more: |count| format!("…and {count} more in the app."),
+                empty: "No new updates for this period.",
+                footer: "You are receiving this email because Gotong digests are enabled.",
+                unsubscribe: "Unsubscribe",
+            },
+        }
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn local_date(ms: i64, offset: UtcOffset) -> DomainResult<String> {
+    let date = datetime_from_ms(ms)?.to_offset(offset).date();
+    Ok(format!(
+        "{:04}-{:02}-{:02}",
+        date.year(),
+        u8::from(date.month()),
+        date.day()
+    ))
+}
+
+fn datetime_from_ms(ms: i64) -> DomainResult<OffsetDateTime> {
+    OffsetDateTime::from_unix_timestamp_nanos(i128::from(ms) * 1_000_000)
+        .map_err(|_| DomainError::Validation(format!("timestamp {ms} is out of range")))
+}
+
+fn datetime_to_ms(value: OffsetDateTime) -> i64 {
+    (value.unix_timestamp_nanos() / 1_000_000) as i64
+}
+
+fn normalize_email(email: &str) -> DomainResult<String> {
+    let email = email.trim().to_ascii_lowercase();
+    if email.is_empty() {
+        return Ok(email);
+    }
+    let valid = email.len() <= MAX_EMAIL_LENGTH
+        && !email
+            .chars()
+            .any(|ch| ch.is_whitespace() || ch.is_control())
+        && email
+            .split_once('@')
+            .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.'));
+    if !valid {
+        return Err(DomainError::Validation("email is invalid".into()));
+    }
+    Ok(email)
+}
+
+fn validate_user_id(user_id: &str) -> DomainResult<()> {
+    if user_id.trim().is_empty() {
+        return Err(DomainError::Validation("user_id is required".into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{FeedItem, SearchResult};
+
+    fn subscription(frequency: DigestFrequency) -> DigestSubscription {
+        DigestSubscription {
+            user_id: "user-1".into(),
+            email: "warga@example.org".into(),
+            locale: DigestLocale::Id,
+            timezone: "Asia/Jakarta".into(),
+            frequency,
+            send_hour_local: 7,
+            send_weekday: 1,
+            last_sent_at_ms: None,
+            created_at_ms: 0,
+            updated_at_ms: 0,
+        }
+    }
+
+    // 2026-03-04 (Wednesday) 10:00 WIB == 03:00 UTC.
+    const WEDNESDAY_10_WIB_MS: i64 = 1_772_593_200_000;
+    // 2026-03-02 (Monday) 07:00 WIB == 00:00 UTC.
+    const MONDAY_07_WIB_MS: i64 = 1_772_409_600_000;
+
+    #[test]
+    fn weekly_slot_is_previous_monday_morning_in_user_timezone() {
+        let sub = subscription(DigestFrequency::Weekly);
+        assert_eq!(
+            sub.latest_slot_ms(WEDNESDAY_10_WIB_MS).unwrap(),
+            Some(MONDAY_07_WIB_MS)
+        );
+        // One millisecond before the slot falls back a full week.
+        assert_eq!(
+            sub.latest_slot_ms(MONDAY_07_WIB_MS - 1).unwrap(),
+            Some(MONDAY_07_WIB_MS - 7 * ONE_DAY_MS)
+        );
+
+        let mut makassar = sub.clone();
+        makassar.timezone = "Asia/Makassar".into();
+        assert_eq!(
+            makassar.latest_slot_ms(WEDNESDAY_10_WIB_MS).unwrap(),
+            Some(MONDAY_07_WIB_MS - 60 * 60 * 1000)
+        );
+    }
+
+    #[test]
+    fn due_window_respects_frequency_and_last_sent() {
+        let mut sub = subscription(DigestFrequency::Daily);
+        let (start, end) = sub.due_window(WEDNESDAY_10_WIB_MS).unwrap().unwrap();
+        assert_eq!(end - start, ONE_DAY_MS);
+        assert_eq!(end, MONDAY_07_WIB_MS + 2 * ONE_DAY_MS);
+
+        sub.last_sent_at_ms = Some(end);
+        assert_eq!(sub.due_window(WEDNESDAY_10_WIB_MS).unwrap(), None);
+
+        sub.last_sent_at_ms = None;
+        sub.frequency = DigestFrequency::Off;
+        assert_eq!(sub.due_window(WEDNESDAY_10_WIB_MS).unwrap(), None);
+    }
+
+    #[test]
+    fn unsubscribe_token_round_trips_and_rejects_tampering() {
+        let token = sign_unsubscribe_token("secret", "user-1").unwrap();
+        assert_eq!(
+            verify_unsubscribe_token("secret", &token).unwrap(),
+            "user-1"
+        );
+        assert!(verify_unsubscribe_token("other-secret", &token).is_err());
+        let forged = format!(
+            "{}.{}",
+            hex::encode("user-2"),
+            token.split_once('.').unwrap().1
+        );
+        assert!(verify_unsubscribe_token("secret", &forged).is_err());
+    }
+
+    #[test]
+    fn render_digest_localizes_and_escapes() {
+        let digest = WeeklyDigest {
+            user_id: "user-1".into(),
+            window_start_ms: MONDAY_07_WIB_MS - 7 * ONE_DAY_MS,
+            window_end_ms: MONDAY_07_WIB_MS,
+            generated_at_ms: MONDAY_07_WIB_MS,
+            unread_count: 2,
+            events: vec![SearchResult {
+                item: FeedItem {
+                    feed_id: "n-1".into(),
+                    source_type: "contribution".into(),
+                    source_id: "c-1".into(),
+                    actor_id: "user-2".into(),
+                    actor_username: "budi".into(),
+                    title: "Kerja bakti <RT 03>".into(),
+                    summary: Some("Saluran air & jalan".into()),
+                    scope_id: None,
+                    privacy_level: None,
+                    occurred_at_ms: MONDAY_07_WIB_MS - ONE_DAY_MS,
+                    created_at_ms: MONDAY_07_WIB_MS - ONE_DAY_MS,
+                    request_id: "req-1".into(),
+                    correlation_id: "corr-1".into(),
+                    participant_ids: Vec::new(),
+                    payload: None,
+                    location: None,
+                    distance_m: None,
+                },
+                score: 0,
+            }],
+        };
+        let mut sub = subscription(DigestFrequency::Weekly);
+        let rendered = render_digest(&digest, &sub, "https://example.org/u?t=a&b").unwrap();
+        assert!(rendered.subject.starts_with("Ringkasan mingguan Gotong"));
+        assert!(rendered.subject.contains("2026-02-23 – 2026-03-02"));
+        assert!(rendered.text.contains("- [2026-03-01] Kerja bakti <RT 03>"));
+        assert!(rendered.html.contains("Kerja bakti &lt;RT 03&gt;"));
+        assert!(
+            rendered
+                .html
+                .contains("href=\"https://example.org/u?t=a&amp;b\"")
+        );
+
+        sub.locale = DigestLocale::En;
+        let rendered = render_digest(&digest, &sub, "https://example.org/u").unwrap();
+        assert!(rendered.subject.starts_with("Your Gotong weekly digest"));
+        assert!(rendered.text.contains("Unsubscribe: https://example.org/u"));
+    }
+}
@@ -3,11 +3,18 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
-use crate::jobs::now_ms;
+use crate::geo::{GeoPoint, GeoRadius};
+use crate::jobs::{JobDefaults, now_ms};
+use crate::notification_preferences::{NotificationChannel, NotificationChannels};
 use crate::ports::discovery::{
-    FeedRepository, FeedRepositoryQuery, FeedRepositorySearchQuery, NotificationRepository,
+    DiscoveryEventPublisher, FeedPreferenceRepository, FeedRepository, FeedRepositoryQuery,
+    FeedRepositorySearchQuery, FeedSearchIndex, FeedSearchIndexQuery, NotificationRepository,
     NotificationRepositoryListQuery,
 };
+use crate::ports::jobs::JobQueue;
+use crate::ports::notification_preferences::NotificationPreferenceRepository;
+use crate::push::web_push_job;
+use crate::ranking::wilson_score;
 use crate::{DomainResult, error::DomainError, identity::ActorIdentity};
 
 const DEFAULT_LIMIT: usize = 20;
@@ -18,6 +25,14 @@ const MAX_SUGGESTION_LIMIT: usize = 20;
 const SUGGESTION_FETCH_MULTIPLIER: usize = 8;
 const SUGGESTION_FETCH_CAP: usize = 200;
 const ONE_WEEK_MS: i64 = 7 * 24 * 60 * 60 * 1000;
+const RANKED_CANDIDATE_LIMIT: usize = 400;
+const RANKED_CURSOR_PREFIX: &str = "ranked";
+const INDEX_SEARCH_CURSOR_PREFIX: &str = "index";
+const MAX_INDEX_SEARCH_OFFSET: usize = 1_000;
+/// Notifications of one type about one source collapse when they fall in the
+/// same fixed window of this length.
+pub const NOTIFICATION_GROUP_WINDOW_MS: i64 = 6 * 60 * 60 * 1000;
+const NOTIFICATION_GROUP_ACTOR_SAMPLE: usize = 3;
 
 pub const FEED_SOURCE_CONTRIBUTION: &str = "contribution";
 pub const FEED_SOURCE_VAULT: &str = "vault";
@@ -50,6 +65,12 @@ pub struct FeedItem {
     pub correlation_id: String,
     pub participant_ids: Vec<String>,
     pub payload: Option<serde_json::Value>,
+    /// Where it happened, for sources that carry a location.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<GeoPoint>,
+    /// Metres from the centre of a `near` query; not stored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance_m: Option<f64>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -66,10 +87,106 @@ pub struct InAppNotification {
     pub payload: Option<serde_json::Value>,
     pub created_at_ms: i64,
     pub read_at_ms: Option<i64>,
+    /// Set when the recipient's quiet hours held the notification; it stays out of
+    /// lists and unread counts until then.
+    #[serde(default)]
+    pub deliver_at_ms: Option<i64>,
     pub privacy_level: Option<String>,
     pub request_id: String,
     pub correlation_id: String,
     pub dedupe_key: String,
+    /// Shared by notifications that list as one grouped item. `None` on rows
+    /// stored before grouping existed; those list on their own.
+    #[serde(default)]
+    pub group_key: Option<String>,
+    /// Kept only for the email digest: the recipient turned this type off in-app,
+    /// so the row never lists, counts as unread or reaches streams.
+    #[serde(default)]
+    pub in_app_hidden: bool,
+}
+
+impl InAppNotification {
+    /// The key the row groups under; ungrouped rows form a group of one.
+    pub fn group_key_or_id(&self) -> &str {
+        self.group_key.as_deref().unwrap_or(&self.notification_id)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NotificationGroupActor {
+    pub actor_id: String,
+    pub actor_username: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NotificationGroupSummary {
+    pub group_key: String,
+    pub count: usize,
+    pub unread_count: usize,
+    pub actor_count: usize,
+    /// Most recent distinct actors first, enough to render "Ani and 12 others".
+    pub actors: Vec<NotificationGroupActor>,
+}
+
+/// A listed notification. Grouped listings show the group's newest row with a
+/// summary of the rest.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NotificationListItem {
+    #[serde(flatten)]
+    pub notification: InAppNotification,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<NotificationGroupSummary>,
+}
+
+/// Pushed to realtime streams as things happen, so clients need not poll
+/// `/v1/notifications/unread-count` and `/v1/feed`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "event_type", rename_all = "snake_case")]
+pub enum DiscoveryEvent {
+    Notification {
+        notification: InAppNotification,
+    },
+    UnreadCount {
+        user_id: String,
+        unread_count: usize,
+    },
+    FeedItem {
+        item: FeedItem,
+    },
+}
+
+impl DiscoveryEvent {
+    /// The single user the event is addressed to; feed items go to every
+    /// stream and are filtered per actor.
+    pub fn recipient_id(&self) -> Option<&str> {
+        match self {
+            Self::Notification { notification } => Some(&notification.user_id),
+            Self::UnreadCount { user_id, .. } => Some(user_id),
+            Self::FeedItem { .. } => None,
+        }
+    }
+
+    pub fn is_visible_to(&self, actor_id: &str) -> bool {
+        match self {
+            Self::FeedItem { item } => is_visible_to_actor(actor_id, item),
+            _ => self.recipient_id() == Some(actor_id),
+        }
+    }
+}
+
+/// Outcome of `ingest_notification` after the recipient's preferences were applied.
+#[derive(Clone, Debug, Serialize)]
+pub struct NotificationDelivery {
+    pub notification: InAppNotification,
+    /// Channels the recipient accepts for this type; when `in_app` is off the
+    /// notification was not stored.
+    pub channels: NotificationChannels,
+}
+
+impl NotificationDelivery {
+    pub fn delivers_to(&self, channel: NotificationChannel) -> bool {
+        self.channels.allows(channel)
+    }
 }
 
 #[derive(Clone)]
@@ -87,6 +204,7 @@ pub struct FeedIngestInput {
     pub request_ts_ms: Option<i64>,
     pub participant_ids: Vec<String>,
     pub payload: Option<serde_json::Value>,
+    pub location: Option<GeoPoint>,
 }
 
 #[derive(Clone)]
@@ -106,6 +224,55 @@ pub struct NotificationIngestInput {
     pub dedupe_key: Option<String>,
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedMode {
+    #[default]
+    Chronological,
+    Ranked,
+}
+
+impl FeedMode {
+    pub fn parse(value: Option<&str>) -> DomainResult<Self> {
+        match value
+            .map(|value| value.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            None | Some("") | Some("chronological") | Some("latest") => Ok(Self::Chronological),
+            Some("ranked") | Some("for_you") => Ok(Self::Ranked),
+            Some(_) => Err(DomainError::Validation(
+                "mode must be one of: chronological, ranked".into(),
+            )),
+        }
+    }
+}
+
+/// Weights for the ranked feed. Each signal is normalised to `0..=1` before it
+/// is multiplied by its weight, so the weights are directly comparable.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FeedRankingWeights {
+    pub recency: f64,
+    pub involvement: f64,
+    pub followed: f64,
+    pub feedback: f64,
+    pub severity: f64,
+    /// Age at which the recency signal has decayed to one half.
+    pub recency_half_life_ms: i64,
+}
+
+impl Default for FeedRankingWeights {
+    fn default() -> Self {
+        Self {
+            recency: 1.0,
+            involvement: 0.6,
+            followed: 0.5,
+            feedback: 0.4,
+            severity: 0.8,
+            recency_half_life_ms: 24 * 60 * 60 * 1000,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct FeedListQuery {
     pub actor_id: String,
@@ -116,6 +283,10 @@ pub struct FeedListQuery {
     pub from_ms: Option<i64>,
     pub to_ms: Option<i64>,
     pub involvement_only: bool,
+    /// Keeps located items within the radius, nearest distance reported.
+    pub near: Option<GeoRadius>,
+    pub mode: FeedMode,
+    pub include_ranking_debug: bool,
 }
 
 #[derive(Clone)]
@@ -139,6 +310,22 @@ pub struct FeedSuggestion {
     pub follower_count: usize,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FeedFollowPreference {
+    pub user_id: String,
+    pub entity_id: String,
+    pub followed: bool,
+    pub updated_at_ms: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FeedMonitorPreference {
+    pub user_id: String,
+    pub witness_id: String,
+    pub monitored: bool,
+    pub updated_at_ms: i64,
+}
+
 #[derive(Clone)]
 pub struct SearchListQuery {
     pub actor_id: String,
@@ -151,6 +338,7 @@ pub struct SearchListQuery {
     pub to_ms: Option<i64>,
     pub involvement_only: bool,
     pub exclude_vault: bool,
+    pub near: Option<GeoRadius>,
 }
 
 #[derive(Clone)]
@@ -159,17 +347,42 @@ pub struct NotificationListQuery {
     pub cursor: Option<String>,
     pub limit: Option<usize>,
     pub include_read: Option<bool>,
+    /// Collapses notifications sharing a group key; defaults to `true`.
+    pub grouped: Option<bool>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PagedFeed {
     pub items: Vec<FeedItem>,
     pub next_cursor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ranking: Option<FeedRankingDebug>,
+}
+
+/// Per-signal breakdown of a ranked item. Signals are the unweighted `0..=1`
+/// values; `score` is their weighted sum.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FeedItemScore {
+    pub feed_id: String,
+    pub score: f64,
+    pub recency: f64,
+    pub involvement: f64,
+    pub followed: f64,
+    pub feedback: f64,
+    pub severity: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FeedRankingDebug {
+    pub weights: FeedRankingWeights,
+    pub as_of_ms: i64,
+    pub candidate_count: usize,
+    pub scores: Vec<FeedItemScore>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PagedNotifications {
-    pub items: Vec<InAppNotification>,
+    pub items: Vec<NotificationListItem>,
     pub next_cursor: Option<String>,
 }
 
@@ -199,6 +412,12 @@ pub struct WeeklyDigest {
 pub struct DiscoveryService {
     feed_repo: Arc<dyn FeedRepository>,
     notification_repo: Arc<dyn NotificationRepository>,
+    ranking_weights: FeedRankingWeights,
+    search_index: Option<Arc<dyn FeedSearchIndex>>,
+    feed_preferences: Option<Arc<dyn FeedPreferenceRepository>>,
+    notification_preferences: Option<Arc<dyn NotificationPreferenceRepository>>,
+    push_queue: Option<Arc<dyn JobQueue>>,
+    event_publisher: Option<Arc<dyn DiscoveryEventPublisher>>,
 }
 
 impl DiscoveryService {
@@ -209,9 +428,62 @@ impl DiscoveryService {
         Self {
             feed_repo,
             notification_repo,
+            ranking_weights: FeedRankingWeights::default(),
+            search_index: None,
+            feed_preferences: None,
+            notification_preferences: None,
+            push_queue: None,
+            event_publisher: None,
         }
     }
 
+    /// Lets suggestions and ranked mode read the actor's stored follows.
+    pub fn with_feed_preferences(
+        mut self,
+        feed_preferences: Arc<dyn FeedPreferenceRepository>,
+    ) -> Self {
+        self.feed_preferences = Some(feed_preferences);
+        self
+    }
+
+    /// Applies recipients' per-type channel opt-outs and quiet hours on ingest.
+    pub fn with_notification_preferences(
+        mut self,
+        notification_preferences: Arc<dyn NotificationPreferenceRepository>,
+    ) -> Self {
+        self.notification_preferences = Some(notification_preferences);
+        self
+    }
+
+    /// Enqueues a Web Push job for each newly ingested notification the recipient
+    /// accepts by push.
+    pub fn with_push_queue(mut self, push_queue: Arc<dyn JobQueue>) -> Self {
+        self.push_queue = Some(push_queue);
+        self
+    }
+
+    /// Publishes new feed items, newly delivered notifications and unread-count
+    /// changes for realtime streams.
+    pub fn with_event_publisher(
+        mut self,
+        event_publisher: Arc<dyn DiscoveryEventPublisher>,
+    ) -> Self {
+        self.event_publisher = Some(event_publisher);
+        self
+    }
+
+    /// Routes `search` through the full-text index instead of scanning the
+    /// feed repository.
+    pub fn with_search_index(mut self, search_index: Arc<dyn FeedSearchIndex>) -> Self {
+        self.search_index = Some(search_index);
+        self
+    }
+
+    pub fn with_ranking_weights(mut self, ranking_weights: FeedRankingWeights) -> Self {
+        self.ranking_weights = ranking_weights;
+        self
+    }
+
     pub async fn ingest_feed(&self, input: FeedIngestInput) -> DomainResult<FeedItem> {
         validate_feed_input(&input)?;
         let item = FeedItem {
@@ -230,15 +502,19 @@ impl DiscoveryService {
             correlation_id: input.correlation_id,
             participant_ids: dedupe_vec(input.participant_ids),
             payload: input.payload,
+            location: input.location,
+            distance_m: None,
         };
 
-        let persisted_item = match self.feed_repo.create_feed_item(&item).await {
-            Ok(item) => item,
-            Err(DomainError::Conflict) => self
-                .feed_repo
-                .get_by_source_request(&item.source_type, &item.source_id, &item.request_id)
-                .await?
-                .ok_or(DomainError::Conflict)?,
+        let (persisted_item, created) = match self.feed_repo.create_feed_item(&item).await {
+            Ok(item) => (item, true),
+            Err(DomainError::Conflict) => (
+                self.feed_repo
+                    .get_by_source_request(&item.source_type, &item.source_id, &item.request_id)
+                    .await?
+                    .ok_or(DomainError::Conflict)?,
+                false,
+            ),
             Err(err) => return Err(err),
         };
 
@@ -246,14 +522,28 @@ impl DiscoveryService {
             .upsert_participant_edges_for_item(&persisted_item)
             .await?;
 
+        if created {
+            self.publish(DiscoveryEvent::FeedItem {
+                item: persisted_item.clone(),
+            })
+            .await;
+        }
         Ok(persisted_item)
     }
 
     pub async fn list_feed(&self, query: FeedListQuery) -> DomainResult<PagedFeed> {
         validate_actor_id(&query.actor_id)?;
+        if query.mode == FeedMode::Ranked {
+            return self.list_ranked_feed(query).await;
+        }
         let limit = normalize_limit(query.limit)?;
         let (mut cursor_ms, mut cursor_feed_id) = parse_feed_cursor(query.cursor.as_deref())?;
         let actor_id = query.actor_id.clone();
+        let followed_entity_ids = if query.involvement_only {
+            self.followed_entity_ids(&actor_id).await?
+        } else {
+            Vec::new()
+        };
         let mut items = Vec::new();
         let fetch_limit = limit + 1;
 
@@ -268,11 +558,16 @@ impl DiscoveryService {
                 from_ms: query.from_ms,
                 to_ms: query.to_ms,
                 involvement_only: query.involvement_only,
+                followed_entity_ids: followed_entity_ids.clone(),
+                near: query.near,
             };
             let rows = self.feed_repo.list_feed(&repo_query).await?;
             for item in rows.iter() {
-                if is_visible_to_actor(&actor_id, item) {
-                    items.push(item.clone());
+                if !is_visible_to_actor(&actor_id, item) {
+                    continue;
+                }
+                if let Some(item) = locate_near(query.near.as_ref(), item) {
+                    items.push(item);
                 }
             }
 
@@ -300,7 +595,122 @@ impl DiscoveryService {
         if items.len() > limit {
             items.truncate(limit);
         }
-        Ok(PagedFeed { items, next_cursor })
+        Ok(PagedFeed {
+            items,
+            next_cursor,
+            ranking: None,
+        })
+    }
+
+    /// Ranked mode scores the newest `RANKED_CANDIDATE_LIMIT` visible items.
+    /// The cursor pins the snapshot instant: items created after it are left
+    /// out and recency is measured against it, so later pages keep their order
+    /// while new items arrive.
+    async fn list_ranked_feed(&self, query: FeedListQuery) -> DomainResult<PagedFeed> {
+        let limit = normalize_limit(query.limit)?;
+        let (as_of_ms, offset) = match query.cursor.as_deref().filter(|value| !value.is_empty()) {
+            Some(cursor) => parse_ranked_cursor(cursor)?,
+            None => (now_ms(), 0),
+        };
+        let weights = self.ranking_weights;
+        let actor_id = query.actor_id.clone();
+        let followed_entity_ids = self.followed_entity_ids(&actor_id).await?;
+        let followed: HashSet<String> = followed_entity_ids.iter().cloned().collect();
+
+        let mut candidates = Vec::new();
+        let mut cursor_ms = None;
+        let mut cursor_feed_id: Option<String> = None;
+        let fetch_limit = MAX_LIMIT + 1;
+        loop {
+            let repo_query = FeedRepositoryQuery {
+                actor_id: actor_id.clone(),
+                cursor_occurred_at_ms: cursor_ms,
+                cursor_feed_id: cursor_feed_id.clone(),
+                limit: fetch_limit,
+                scope_id: query.scope_id.clone(),
+                privacy_level: query.privacy_level.clone(),
+                from_ms: query.from_ms,
+                to_ms: query.to_ms,
+                involvement_only: query.involvement_only,
+                followed_entity_ids: followed_entity_ids.clone(),
+                near: query.near,
+            };
+            let rows = self.feed_repo.list_feed(&repo_query).await?;
+            for item in rows.iter() {
+                if item.created_at_ms > as_of_ms || !is_visible_to_actor(&actor_id, item) {
+                    continue;
+                }
+                if let Some(item) = locate_near(query.near.as_ref(), item) {
+                    candidates.push(item);
+                }
+            }
+            if candidates.len() >= RANKED_CANDIDATE_LIMIT || rows.len() < fetch_limit {
+                break;
+            }
+            let Some(last_row) = rows.last() else {
+                break;
+            };
+            if cursor_ms == Some(last_row.occurred_at_ms)
+                && cursor_feed_id.as_deref() == Some(last_row.feed_id.as_str())
+            {
+                break;
+            }
+            cursor_ms = Some(last_row.occurred_at_ms);
+            cursor_feed_id = Some(last_row.feed_id.clone());
+        }
+        candidates.truncate(RANKED_CANDIDATE_LIMIT);
+
+        let mut scored: Vec<(FeedItemScore, FeedItem)> = candidates
+            .into_iter()
+            .map(|item| {
+                (
+                    score_feed_item(&item, &actor_id, &followed, &weights, as_of_ms),
+                    item,
+                )
+            })
+            .collect();
+        scored.sort_by(|(left_score, left), (right_score, right)| {
+            right_score
+                .score
+                .total_cmp(&left_score.score)
+                .then_with(|| right.occurred_at_ms.cmp(&left.occurred_at_ms))
+                .then_with(|| right.feed_id.cmp(&left.feed_id))
+        });
+
+        let candidate_count = scored.len();
+        let end = offset.saturating_add(limit).min(candidate_count);
+        let page = if offset < candidate_count {
+            scored.drain(offset..end).collect::<Vec<_>>()
+        } else {
+            Vec::new()
+        };
+        let next_cursor = (end < candidate_count).then(|| make_ranked_cursor(as_of_ms, end));
+        let (scores, items): (Vec<_>, Vec<_>) = page.into_iter().unzip();
+        let ranking = query.include_ranking_debug.then_some(FeedRankingDebug {
+            weights,
+            as_of_ms,
+            candidate_count,
+            scores,
+        });
+        Ok(PagedFeed {
+            items,
+            next_cursor,
+            ranking,
+        })
+    }
+
+    /// Entities the actor follows, from the stored feed preferences.
+    async fn followed_entity_ids(&self, actor_id: &str) -> DomainResult<Vec<String>> {
+        let Some(feed_preferences) = &self.feed_preferences else {
+            return Ok(Vec::new());
+        };
+        Ok(feed_preferences
+            .list_follows(actor_id)
+            .await?
+            .into_iter()
+            .filter(|preference| preference.followed)
+            .map(|preference| preference.entity_id)
+            .collect())
     }
 
     pub async fn list_feed_suggestions(
@@ -323,6 +733,8 @@ impl DiscoveryService {
             from_ms: query.from_ms,
             to_ms: query.to_ms,
             involvement_only: false,
+            followed_entity_ids: Vec::new(),
+            near: None,
         };
         let rows = self.feed_repo.list_feed(&repo_query).await?;
         let mut grouped: HashMap<String, SuggestionAggregate> = HashMap::new();
@@ -389,6 +801,22 @@ impl DiscoveryService {
             })
             .collect();
 
+        if let Some(feed_preferences) = &self.feed_preferences {
+            let entity_ids: Vec<String> = suggestions
+                .iter()
+                .map(|suggestion| suggestion.entity_id.clone())
+                .collect();
+            let follow_states = feed_preferences
+                .follow_states(&query.actor_id, &entity_ids)
+                .await?;
+            for suggestion in &mut suggestions {
+                suggestion.followed = follow_states
+                    .get(&suggestion.entity_id)
+                    .copied()
+                    .unwrap_or(false);
+            }
+        }
+
         suggestions.sort_by(|left, right| {
             right
                 .witness_count
@@ -407,6 +835,11 @@ impl DiscoveryService {
         }
 
         let limit = normalize_limit(query.limit)?;
+        if let Some(search_index) = self.search_index.clone() {
+            return self
+                .search_index_page(search_index.as_ref(), query, limit)
+                .await;
+        }
         let query_text = query.query_text.trim().to_string();
         let search_cursor = parse_search_cursor(query.cursor.as_deref())?;
         let mut results = Vec::new();
@@ -425,6 +858,7 @@ impl DiscoveryService {
                 involvement_only: query.involvement_only,
                 exclude_vault: query.exclude_vault,
                 query_text: query_text.clone(),
+                near: query.near,
             };
 
             let rows = self.feed_repo.search_feed(&repo_query).await?;
@@ -438,14 +872,14 @@ impl DiscoveryService {
                 if !matches_search_text(item, &query_text) {
                     continue;
                 }
+                let Some(item) = locate_near(query.near.as_ref(), item) else {
+                    continue;
+                };
                 if !seen_feed_ids.insert(item.feed_id.clone()) {
                     continue;
                 }
-                let score = score_query_match(item, &query_text);
-                results.push(SearchResult {
-                    item: item.clone(),
-                    score,
-                });
+                let score = score_query_match(&item, &query_text);
+                results.push(SearchResult { item, score });
             }
 
             if results.len() > limit || rows.len() < fetch_limit {
@@ -492,17 +926,95 @@ impl DiscoveryService {
         })
     }
 
+    /// Hits come back already filtered and BM25-ordered; rows are re-read from
+    /// the repository so stale index entries (hidden or removed items) drop out.
+    async fn search_index_page(
+        &self,
+        search_index: &dyn FeedSearchIndex,
+        query: SearchListQuery,
+        limit: usize,
+    ) -> DomainResult<SearchPage> {
+        let offset = match query.cursor.as_deref().filter(|value| !value.is_empty()) {
+            Some(cursor) => parse_index_search_cursor(cursor)?,
+            None => 0,
+        };
+        let hits = search_index
+            .search(&FeedSearchIndexQuery {
+                actor_id: query.actor_id.clone(),
+                query_text: query.query_text.trim().to_string(),
+                limit: limit + 1,
+                offset,
+                scope_id: query.scope_id,
+                privacy_level: query.privacy_level,
+                from_ms: query.from_ms,
+                to_ms: query.to_ms,
+                involvement_only: query.involvement_only,
+                exclude_vault: query.exclude_vault,
+                near: query.near,
+            })
+            .await?;
+        let next_cursor = (hits.len() > limit && offset + limit < MAX_INDEX_SEARCH_OFFSET)
+            .then(|| make_index_search_cursor(offset + limit));
+
+        let mut items = Vec::with_capacity(limit);
+        for hit in hits.into_iter().take(limit) {
+            let Some(item) = self.feed_repo.get_by_feed_id(&hit.feed_id).await? else {
+                continue;
+            };
+            if !is_visible_to_actor(&query.actor_id, &item)
+                || (query.exclude_vault && item.source_type == FEED_SOURCE_VAULT)
+            {
+                continue;
+            }
+            let Some(item) = locate_near(query.near.as_ref(), &item) else {
+                continue;
+            };
+            items.push(SearchResult {
+                item,
+                score: (f64::from(hit.score) * 1_000.0).round() as i64,
+            });
+        }
+        Ok(SearchPage { items, next_cursor })
+    }
+
+    /// Stores an in-app notification unless the recipient opted out of the type,
+    /// holding it until quiet hours end when needed. Siaga alerts are never held
+    /// unless the recipient opted into a bypass severity floor they fall below.
+    /// Rows for types kept only on email or push are stored hidden, so a replayed
+    /// ingest finds the dedupe key and does not deliver twice. With a push queue attached,
+    /// new notifications also schedule a Web Push delivery for the same instant.
     pub async fn ingest_notification(
         &self,
         input: NotificationIngestInput,
-    ) -> DomainResult<InAppNotification> {
+    ) -> DomainResult<NotificationDelivery> {
         validate_notification_input(&input)?;
+        let preferences = match &self.notification_preferences {
+            Some(repository) => repository.get_preferences(&input.recipient_id).await?,
+            None => None,
+        };
+        let (channels, deliver_at_ms) = match preferences {
+            Some(preferences) => {
+                let severity =
+                    notification_siaga_severity(&input.notification_type, input.payload.as_ref());
+                let plan =
+                    preferences.plan_delivery(&input.notification_type, severity, now_ms())?;
+                (plan.channels, plan.deliver_at_ms)
+            }
+            None => (NotificationChannels::default(), None),
+        };
         let dedupe_key = input.dedupe_key.unwrap_or_else(|| {
             format!(
                 "{}:{}:{}",
                 input.source_type, input.source_id, input.request_id
             )
         });
+        let created_at_ms = input.request_ts_ms.unwrap_or_else(now_ms);
+        let group_key = notification_group_key(
+            &input.notification_type,
+            &input.source_type,
+            &input.source_id,
+            created_at_ms,
+        );
         let notification = InAppNotification {
             notification_id: crate::util::uuid_v7_without_dashes(),
             user_id: input.recipient_id,
@@ -514,29 +1026,74 @@ impl DiscoveryService {
             title: input.title,
             body: input.body,
             payload: input.payload,
-            created_at_ms: input.request_ts_ms.unwrap_or_else(now_ms),
+            created_at_ms,
             read_at_ms: None,
+            deliver_at_ms,
             privacy_level: input.privacy_level,
             request_id: input.request_id,
             correlation_id: input.correlation_id,
             dedupe_key,
+            group_key: Some(group_key),
+            in_app_hidden: !channels.in_app,
         };
+        if !channels.in_app && !channels.email && !channels.push {
+            return Ok(NotificationDelivery {
+                notification,
+                channels,
+            });
+        }
 
         match self
             .notification_repo
             .create_notification(&notification)
             .await
         {
-            Ok(notification) => Ok(notification),
-            Err(DomainError::Conflict) => self
-                .notification_repo
-                .get_by_dedupe_key(&notification.user_id, &notification.dedupe_key)
-                .await?
-                .ok_or(DomainError::Conflict),
+            Ok(notification) => {
+                let delivery = NotificationDelivery {
+                    notification,
+                    channels,
+                };
+                self.enqueue_push(&delivery).await?;
+                // Held notifications reach streams on the client's next resync.
+                if delivery.notification.deliver_at_ms.is_none()
+                    && !delivery.notification.in_app_hidden
+                {
+                    self.publish(DiscoveryEvent::Notification {
+                        notification: delivery.notification.clone(),
+                    })
+                    .await;
+                    self.publish_unread_count(&delivery.notification.user_id)
+                        .await;
+                }
+                Ok(delivery)
+            }
+            // A replayed ingest returns the stored row without pushing again.
+            Err(DomainError::Conflict) => Ok(NotificationDelivery {
+                notification: self
+                    .notification_repo
+                    .get_by_dedupe_key(&notification.user_id, &notification.dedupe_key)
+                    .await?
+                    .ok_or(DomainError::Conflict)?,
+                channels,
+            }),
             Err(err) => Err(err),
         }
     }
 
+    async fn enqueue_push(&self, delivery: &NotificationDelivery) -> DomainResult<()> {
+        let Some(queue) = &self.push_queue else {
+            return Ok(());
+        };
+        let Some(job) = web_push_job(delivery, JobDefaults::default()) else {
+            return Ok(());
+        };
+        queue.enqueue(&job).await.map_err(|err| {
+            DomainError::Validation(format!("failed to enqueue web push job: {err}"))
+        })
+    }
+
+    /// Lists the actor's delivered notifications newest first. Grouped listings
+    /// page by each group's newest row.
     pub async fn list_notifications(
         &self,
         query: NotificationListQuery,
@@ -544,6 +1101,7 @@ impl DiscoveryService {
         validate_actor_id(&query.actor_id)?;
         let limit = normalize_limit(query.limit)?;
         let include_read = query.include_read.unwrap_or(false);
+        let grouped = query.grouped.unwrap_or(true);
         let (cursor_ms, cursor_notification_id) =
             parse_notification_cursor(query.cursor.as_deref())?;
         let actor_id = query.actor_id.clone();
@@ -554,44 +1112,92 @@ impl DiscoveryService {
             cursor_notification_id,
             limit: limit + 1,
             include_read,
+            delivered_by_ms: Some(now_ms()),
         };
-        let mut items = self
-            .notification_repo
-            .list_notifications(&repo_query)
-            .await?;
-        items.retain(|notification| is_visible_notification(&actor_id, notification));
+        let mut items = if grouped {
+            self.notification_repo
+                .list_notification_groups(&repo_query)
+                .await?
+        } else {
+            self.notification_repo
+                .list_notifications(&repo_query)
+                .await?
+                .into_iter()
+                .map(|notification| NotificationListItem {
+                    notification,
+                    group: None,
+                })
+                .collect()
+        };
+        items.retain(|item| is_visible_notification(&actor_id, &item.notification));
 
         let next_cursor = items
             .get(limit.saturating_sub(1))
             .filter(|_| items.len() > limit)
-            .map(|item| make_notification_cursor(item.created_at_ms, &item.notification_id));
+            .map(|item| {
+                make_notification_cursor(
+                    item.notification.created_at_ms,
+                    &item.notification.notification_id,
+                )
+            });
         if items.len() > limit {
             items.truncate(limit);
         }
         Ok(PagedNotifications { items, next_cursor })
     }
 
+    /// Marks the notification read along with the rest of its group, since the
+    /// list showed them as one item.
     pub async fn mark_notification_read(
         &self,
         actor_id: &str,
         notification_id: &str,
     ) -> DomainResult<InAppNotification> {
         validate_actor_id(actor_id)?;
+        let read_at_ms = now_ms();
         let notification = self
             .notification_repo
-            .mark_as_read(actor_id, notification_id, now_ms())
+            .mark_as_read(actor_id, notification_id, read_at_ms)
             .await?;
         if notification.user_id != actor_id {
             return Err(DomainError::Forbidden(
                 "notification belongs to another user".into(),
             ));
         }
+        if let Some(group_key) = notification.group_key.as_deref() {
+            self.notification_repo
+                .mark_group_as_read(actor_id, group_key, read_at_ms)
+                .await?;
+        }
+        self.publish_unread_count(actor_id).await;
         Ok(notification)
     }
 
+    async fn publish(&self, event: DiscoveryEvent) {
+        if let Some(publisher) = &self.event_publisher {
+            publisher.publish(event).await;
+        }
+    }
+
+    /// Best effort: a failed count leaves streams to resync on reconnect.
+    async fn publish_unread_count(&self, user_id: &str) {
+        if self.event_publisher.is_none() {
+            return;
+        }
+        if let Ok(unread_count) = self.notification_repo.unread_count(user_id, now_ms()).await {
+            self.publish(DiscoveryEvent::UnreadCount {
+                user_id: user_id.to_string(),
+                unread_count,
+            })
+            .await;
+        }
+    }
+
     pub async fn unread_notification_count(&self, actor_id: &str) -> DomainResult<usize> {
         validate_actor_id(actor_id)?;
-        self.notification_repo.unread_count(actor_id).await
+        self.notification_repo
+            .unread_count(actor_id, now_ms())
+            .await
     }
 
     pub async fn weekly_digest(
@@ -599,6 +1205,34 @@ impl DiscoveryService {
         actor_id: &str,
         window_start_ms: Option<i64>,
         window_end_ms: Option<i64>,
+    ) -> DomainResult<WeeklyDigest> {
+        self.digest_for_channel(actor_id, window_start_ms, window_end_ms, None)
+            .await
+    }
+
+    /// The digest mailed by the worker: like `weekly_digest`, minus notification
+    /// types the recipient opted out of by email.
+    pub async fn email_digest(
+        &self,
+        actor_id: &str,
+        window_start_ms: i64,
+        window_end_ms: i64,
+    ) -> DomainResult<WeeklyDigest> {
+        self.digest_for_channel(
+            actor_id,
+            Some(window_start_ms),
+            Some(window_end_ms),
+            Some(NotificationChannel::Email),
+        )
+        .await
+    }
+
+    async fn digest_for_channel(
+        &self,
+        actor_id: &str,
+        window_start_ms: Option<i64>,
+        window_end_ms: Option<i64>,
+        channel: Option<NotificationChannel>,
     ) -> DomainResult<WeeklyDigest> {
         validate_actor_id(actor_id)?;
         let now_ms = now_ms();
@@ -615,9 +1249,20 @@ impl DiscoveryService {
             .list_notifications_in_window(actor_id, window_start_ms, window_end_ms)
             .await?;
         let unread_count = self.unread_notification_count(actor_id).await?;
+        let preferences = match (channel, &self.notification_preferences) {
+            (Some(_), Some(repository)) => repository.get_preferences(actor_id).await?,
+            _ => None,
+        };
         let events = notifications
             .into_iter()
             .filter(|notification| is_visible_notification(actor_id, notification))
+            .filter(|notification| channel.is_some() || !notification.in_app_hidden)
+            .filter(|notification| match (channel, &preferences) {
+                (Some(channel), Some(preferences)) => preferences
+                    .channels_for(&notification.notification_type)
+                    .allows(channel),
+                _ => true,
+            })
             .map(|notification| SearchResult {
                 item: FeedItem {
                     feed_id: notification.notification_id,
@@ -635,6 +1280,8 @@ impl DiscoveryService {
                     correlation_id: notification.correlation_id,
                     participant_ids: Vec::new(),
                     payload: notification.payload,
+                    location: None,
+                    distance_m: None,
                 },
                 score: 0,
             })
@@ -684,6 +1331,9 @@ fn validate_feed_input(input: &FeedIngestInput) -> DomainResult<()> {
         return Err(DomainError::Validation("title is required".into()));
     }
     validate_actor_id(&input.actor.user_id)?;
+    if let Some(location) = input.location.as_ref() {
+        location.validate()?;
+    }
     Ok(())
 }
 
@@ -796,6 +1446,48 @@ fn make_feed_cursor(occurred_at_ms: i64, feed_id: &str) -> String {
     format!("{occurred_at_ms}:{feed_id}")
 }
 
+/// Ranked cursors are hex-encoded so clients treat them as opaque.
+fn make_ranked_cursor(as_of_ms: i64, offset: usize) -> String {
+    hex::encode(format!("{RANKED_CURSOR_PREFIX}:{as_of_ms}:{offset}"))
+}
+
+fn parse_ranked_cursor(value: &str) -> DomainResult<(i64, usize)> {
+    let invalid = || DomainError::Validation("invalid ranked feed cursor".into());
+    let decoded = hex::decode(value.trim()).map_err(|_| invalid())?;
+    let decoded = String::from_utf8(decoded).map_err(|_| invalid())?;
+    let mut parts = decoded.splitn(3, ':');
+    if parts.next() != Some(RANKED_CURSOR_PREFIX) {
+        return Err(invalid());
+    }
+    let as_of_ms = parts
+        .next()
+        .and_then(|raw| raw.parse::<i64>().ok())
+        .filter(|as_of_ms| *as_of_ms >= 0)
+        .ok_or_else(invalid)?;
+    let offset = parts
+        .next()
+        .and_then(|raw| raw.parse::<usize>().ok())
+        .filter(|offset| *offset <= RANKED_CANDIDATE_LIMIT)
+        .ok_or_else(invalid)?;
+    Ok((as_of_ms, offset))
+}
+
+fn make_index_search_cursor(offset: usize) -> String {
+    hex::encode(format!("{INDEX_SEARCH_CURSOR_PREFIX}:{offset}"))
+}
+
+fn parse_index_search_cursor(value: &str) -> DomainResult<usize> {
+    let invalid = || DomainError::Validation("invalid search cursor".into());
+    let decoded = hex::decode(value.trim()).map_err(|_| invalid())?;
+    let decoded = String::from_utf8(decoded).map_err(|_| invalid())?;
+    decoded
+        .strip_prefix(INDEX_SEARCH_CURSOR_PREFIX)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .and_then(|raw| raw.parse::<usize>().ok())
+        .filter(|offset| *offset <= MAX_INDEX_SEARCH_OFFSET)
+        .ok_or_else(invalid)
+}
+
 fn make_search_cursor(score: i64, occurred_at_ms: i64, feed_id: &str) -> String {
     format!("{score}:{occurred_at_ms}:{feed_id}")
 }
@@ -856,12 +1548,12 @@ fn score_query_match(item: &FeedItem, query: &str) -> i64 {
     score
 }
 
-fn is_open_privacy_level(level: Option<&str>) -> bool {
+pub fn is_open_privacy_level(level: Option<&str>) -> bool {
     let level = level.unwrap_or("public").trim().to_ascii_lowercase();
     OPEN_PRIVACY_LEVELS.contains(&level.as_str())
 }
 
-fn is_hidden_feed_item(item: &FeedItem) -> bool {
+pub fn is_hidden_feed_item(item: &FeedItem) -> bool {
     item.payload
         .as_ref()
         .and_then(|payload| payload.get("lifecycle"))
@@ -879,34 +1571,277 @@ fn is_visible_to_actor(actor_id: &str, item: &FeedItem) -> bool {
         || item.participant_ids.iter().any(|id| id == actor_id)
 }
 
-fn is_visible_notification(actor_id: &str, notification: &InAppNotification) -> bool {
-    is_open_privacy_level(notification.privacy_level.as_deref())
-        || actor_id == notification.user_id
-        || actor_id == notification.actor_id
+/// Without `near` every item passes unchanged. With it, only located items
+/// inside the radius pass, carrying their distance from the centre.
+fn locate_near(near: Option<&GeoRadius>, item: &FeedItem) -> Option<FeedItem> {
+    let Some(near) = near else {
+        return Some(item.clone());
+    };
+    let distance_m = near.distance_within(item.location.as_ref()?)?;
+    Some(FeedItem {
+        distance_m: Some(distance_m),
+        ..item.clone()
+    })
 }
 
-#[derive(Clone, Debug)]
-struct SuggestionCandidate {
-    entity_id: Option<String>,
-    entity_type: String,
-    label: String,
-    followed: bool,
-    description: Option<String>,
-    follower_count: Option<usize>,
+fn score_feed_item(
+    item: &FeedItem,
+    actor_id: &str,
+    followed: &HashSet<String>,
+    weights: &FeedRankingWeights,
+    as_of_ms: i64,
+) -> FeedItemScore {
+    let age_ms = as_of_ms.saturating_sub(item.occurred_at_ms).max(0) as f64;
+    let half_life_ms = weights.recency_half_life_ms.max(1) as f64;
+    let recency = 0.5_f64.powf(age_ms / half_life_ms);
+    let involvement =
+        signal(item.actor_id == actor_id || item.participant_ids.iter().any(|id| id == actor_id));
+    let followed = signal(
+        !followed.is_empty()
+            && feed_item_entity_ids(item)
+                .iter()
+                .any(|entity_id| followed.contains(entity_id)),
+    );
+    let feedback = feedback_signal(item);
+    let severity = siaga_severity_signal(item);
+    let score = weights.recency * recency
+        + weights.involvement * involvement
+        + weights.followed * followed
+        + weights.feedback * feedback
+        + weights.severity * severity;
+    FeedItemScore {
+        feed_id: item.feed_id.clone(),
+        score,
+        recency,
+        involvement,
+        followed,
+        feedback,
+        severity,
+    }
 }
 
-#[derive(Clone, Debug)]
-struct SuggestionAggregate {
-    suggestion: FeedSuggestion,
-    seen_sources: HashSet<String>,
+/// Entity ids tagged on a feed item, in the form follow preferences store them.
+pub fn feed_item_entity_ids(item: &FeedItem) -> Vec<String> {
+    extract_suggestion_candidates(item.payload.as_ref())
+        .iter()
+        .map(|candidate| {
+            normalized_entity_id(
+                candidate.entity_id.as_deref(),
+                &candidate.entity_type,
+                &candidate.label,
+            )
+        })
+        .filter(|entity_id| !entity_id.is_empty())
+        .collect()
 }
 
-fn extract_suggestion_candidates(payload: Option<&serde_json::Value>) -> Vec<SuggestionCandidate> {
-    let Some(payload) = payload else {
-        return Vec::new();
+/// Whether an item belongs in the actor's involvement feed: they wrote it, take
+/// part in it, or follow one of its tagged entities.
+pub fn is_involved_in_feed_item(
+    item: &FeedItem,
+    actor_id: &str,
+    followed_entity_ids: &[String],
+) -> bool {
+    item.actor_id == actor_id
+        || item.participant_ids.iter().any(|id| id == actor_id)
+        || (!followed_entity_ids.is_empty()
+            && feed_item_entity_ids(item)
+                .iter()
+                .any(|entity_id| followed_entity_ids.contains(entity_id)))
+}
+
+fn signal(value: bool) -> f64 {
+    if value { 1.0 } else { 0.0 }
+}
+
+/// Lower bound of the vouch ratio on enriched ontology notes, so a note with
+/// two vouches does not outrank one with two hundred and a few challenges.
+fn feedback_signal(item: &FeedItem) -> f64 {
+    let Some(feedback) = item
+        .payload
+        .as_ref()
+        .and_then(|payload| payload.pointer("/enrichment/feedback"))
+    else {
+        return 0.0;
     };
-    let raw_tags = payload
-        .get("enrichment")
+    let count = |key: &str| {
+        feedback
+            .get(key)
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(0)
+    };
+    let vouches = count("vouch_count");
+    let challenges = count("challenge_count");
+    wilson_score(vouches, vouches.saturating_add(challenges))
+}
+
+/// Siaga severity arrives either as a broadcast level (`1..=5`) or as a triage
+/// label (`waspada`, `siaga`, `darurat`).
+fn siaga_severity_signal(item: &FeedItem) -> f64 {
+    let Some(payload) = item.payload.as_ref() else {
+        return 0.0;
+    };
+    let is_siaga = item.source_type == FEED_SOURCE_SIAGA
+        || payload.get("route").and_then(serde_json::Value::as_str) == Some("siaga");
+    if !is_siaga {
+        return 0.0;
+    }
+    let Some(severity) = siaga_severity_value(payload) else {
+        return 0.0;
+    };
+    if let Some(level) = severity.as_u64() {
+        return (level.min(5) as f64) / 5.0;
+    }
+    match severity
+        .as_str()
+        .map(|label| label.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("darurat") => 1.0,
+        Some("siaga") => 0.7,
+        Some("waspada") => 0.4,
+        _ => 0.0,
+    }
+}
+
+fn siaga_severity_value(payload: &serde_json::Value) -> Option<&serde_json::Value> {
+    [
+        "/severity",
+        "/siaga/severity",
+        "/triage_result/payload/severity",
+    ]
+    .iter()
+    .find_map(|pointer| payload.pointer(pointer))
+}
+
+/// Severity level (`1..=5`, `0` when unknown) of a siaga notification; `None` for
+/// every other type. Used to bypass quiet hours.
+fn notification_siaga_severity(
+    notification_type: &str,
+    payload: Option<&serde_json::Value>,
+) -> Option<u8> {
+    if !notification_type
+        .trim()
+        .eq_ignore_ascii_case(NOTIF_TYPE_SIAGA)
+    {
+        return None;
+    }
+    let Some(severity) = payload.and_then(siaga_severity_value) else {
+        return Some(0);
+    };
+    if let Some(level) = severity.as_u64() {
+        return Some(level.clamp(1, 5) as u8);
+    }
+    let level = severity
+        .as_str()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    match level.as_str() {
+        "darurat" => Some(5),
+        "siaga" => Some(4),
+        "waspada" => Some(2),
+        _ => Some(0),
+    }
+}
+
+/// `{type}:{source_type}:{source_id}:{window}`; the window index keeps a
+/// long-running source from collapsing into one item forever.
+pub fn notification_group_key(
+    notification_type: &str,
+    source_type: &str,
+    source_id: &str,
+    created_at_ms: i64,
+) -> String {
+    let window = created_at_ms.div_euclid(NOTIFICATION_GROUP_WINDOW_MS);
+    format!("{notification_type}:{source_type}:{source_id}:{window}")
+}
+
+/// Collapses rows by group key into list items led by each group's newest row,
+/// newest group first. Repositories hand in every matching row of the groups
+/// they return so counts and actor samples are complete.
+pub fn group_notifications(rows: Vec<InAppNotification>) -> Vec<NotificationListItem> {
+    let mut groups: HashMap<String, Vec<InAppNotification>> = HashMap::new();
+    for row in rows {
+        groups
+            .entry(row.group_key_or_id().to_string())
+            .or_default()
+            .push(row);
+    }
+    let mut items: Vec<NotificationListItem> = groups
+        .into_iter()
+        .filter_map(|(group_key, mut rows)| {
+            rows.sort_by(notification_newest_first);
+            let unread_count = rows.iter().filter(|row| row.read_at_ms.is_none()).count();
+            let mut seen_actors = HashSet::new();
+            let actors: Vec<NotificationGroupActor> = rows
+                .iter()
+                .filter(|row| seen_actors.insert(row.actor_id.clone()))
+                .map(|row| NotificationGroupActor {
+                    actor_id: row.actor_id.clone(),
+                    actor_username: row.actor_username.clone(),
+                })
+                .collect();
+            let summary = NotificationGroupSummary {
+                group_key,
+                count: rows.len(),
+                unread_count,
+                actor_count: actors.len(),
+                actors: actors
+                    .into_iter()
+                    .take(NOTIFICATION_GROUP_ACTOR_SAMPLE)
+                    .collect(),
+            };
+            rows.into_iter()
+                .next()
+                .map(|notification| NotificationListItem {
+                    notification,
+                    group: Some(summary),
+                })
+        })
+        .collect();
+    items.sort_by(|left, right| notification_newest_first(&left.notification, &right.notification));
+    items
+}
+
+fn notification_newest_first(
+    left: &InAppNotification,
+    right: &InAppNotification,
+) -> std::cmp::Ordering {
+    right
+        .created_at_ms
+        .cmp(&left.created_at_ms)
+        .then_with(|| right.notification_id.cmp(&left.notification_id))
+}
+
+fn is_visible_notification(actor_id: &str, notification: &InAppNotification) -> bool {
+    is_open_privacy_level(notification.privacy_level.as_deref())
+        || actor_id == notification.user_id
+        || actor_id == notification.actor_id
+}
+
+#[derive(Clone, Debug)]
+struct SuggestionCandidate {
+    entity_id: Option<String>,
+    entity_type: String,
+    label: String,
+    followed: bool,
+    description: Option<String>,
+    follower_count: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+struct SuggestionAggregate {
+    suggestion: FeedSuggestion,
+    seen_sources: HashSet<String>,
+}
+
+fn extract_suggestion_candidates(payload: Option<&serde_json::Value>) -> Vec<SuggestionCandidate> {
+    let Some(payload) = payload else {
+        return Vec::new();
+    };
+    let raw_tags = payload
+        .get("enrichment")
         .and_then(|value| value.get("entity_tags"))
         .or_else(|| payload.get("entity_tags"))
         .and_then(serde_json::Value::as_array);
@@ -1026,12 +1961,16 @@ fn normalized_entity_id(entity_id: Option<&str>, entity_type: &str, label: &str)
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::notification_preferences::{NotificationPreferences, QuietHours};
     use crate::ports::BoxFuture;
     use crate::ports::discovery::{
-        FeedRepository, FeedRepositoryQuery, FeedRepositorySearchQuery, NotificationRepository,
+        FeedPreferenceRepository, FeedRepository, FeedRepositoryQuery, FeedRepositorySearchQuery,
+        FeedSearchHit, FeedSearchIndex, FeedSearchIndexQuery, NotificationRepository,
         NotificationRepositoryListQuery,
     };
+    use crate::ports::jobs::{JobEnvelope, JobQueueError};
     use std::sync::{Arc, Mutex};
+    use time::OffsetDateTime;
 
     struct MockFeedRepository {
         persisted_item: Arc<Mutex<Option<FeedItem>>>,
@@ -1119,13 +2058,22 @@ mod tests {
         fn get_by_feed_id(&self, feed_id: &str) -> BoxFuture<'_, DomainResult<Option<FeedItem>>> {
             let feed_id = feed_id.to_string();
             let persisted_item = self.persisted_item.clone();
+            let feed_rows = self.feed_rows.clone();
             Box::pin(async move {
-                Ok(persisted_item
+                let persisted = persisted_item
                     .lock()
                     .expect("persisted_item mutex")
                     .as_ref()
                     .filter(|item| item.feed_id == feed_id)
-                    .cloned())
+                    .cloned();
+                Ok(persisted.or_else(|| {
+                    feed_rows
+                        .lock()
+                        .expect("feed_rows mutex")
+                        .iter()
+                        .find(|item| item.feed_id == feed_id)
+                        .cloned()
+                }))
             })
         }
 
@@ -1167,6 +2115,24 @@ mod tests {
             })
         }
 
+        fn update_title(
+            &self,
+            feed_id: &str,
+            title: &str,
+        ) -> BoxFuture<'_, DomainResult<FeedItem>> {
+            let feed_id = feed_id.to_string();
+            let title = title.to_string();
+            let persisted_item = self.persisted_item.clone();
+            Box::pin(async move {
+                let mut guard = persisted_item.lock().expect("persisted_item mutex");
+                let Some(item) = guard.as_mut().filter(|item| item.feed_id == feed_id) else {
+                    return Err(DomainError::NotFound);
+                };
+                item.title = title;
+                Ok(item.clone())
+            })
+        }
+
         fn list_feed(
             &self,
             _query: &FeedRepositoryQuery,
@@ -1209,6 +2175,13 @@ mod tests {
             Box::pin(async move { Ok(Vec::new()) })
         }
 
+        fn list_notification_groups(
+            &self,
+            _query: &NotificationRepositoryListQuery,
+        ) -> BoxFuture<'_, DomainResult<Vec<NotificationListItem>>> {
+            Box::pin(async move { Ok(Vec::new()) })
+        }
+
         fn list_notifications_in_window(
             &self,
             _user_id: &str,
@@ -1227,11 +2200,148 @@ mod tests {
             Box::pin(async move { Err(DomainError::NotFound) })
         }
 
-        fn unread_count(&self, _user_id: &str) -> BoxFuture<'_, DomainResult<usize>> {
+        fn mark_group_as_read(
+            &self,
+            _user_id: &str,
+            _group_key: &str,
+            _read_at_ms: i64,
+        ) -> BoxFuture<'_, DomainResult<usize>> {
+            Box::pin(async move { Ok(0) })
+        }
+
+        fn unread_count(
+            &self,
+            _user_id: &str,
+            _as_of_ms: i64,
+        ) -> BoxFuture<'_, DomainResult<usize>> {
             Box::pin(async move { Ok(0) })
         }
     }
 
+    /// Rejects a second row with the same dedupe key, like the real stores.
+    #[derive(Default)]
+    struct DedupingNotificationRepository {
+        rows: Mutex<Vec<InAppNotification>>,
+    }
+
+    impl NotificationRepository for DedupingNotificationRepository {
+        fn create_notification(
+            &self,
+            notification: &InAppNotification,
+        ) -> BoxFuture<'_, DomainResult<InAppNotification>> {
+            let notification = notification.clone();
+            let mut rows = self.rows.lock().expect("rows mutex");
+            let result = if rows.iter().any(|row| {
+                row.user_id == notification.user_id && row.dedupe_key == notification.dedupe_key
+            }) {
+                Err(DomainError::Conflict)
+            } else {
+                rows.push(notification.clone());
+                Ok(notification)
+            };
+            Box::pin(async move { result })
+        }
+
+        fn get_by_dedupe_key(
+            &self,
+            user_id: &str,
+            dedupe_key: &str,
+        ) -> BoxFuture<'_, DomainResult<Option<InAppNotification>>> {
+            let row = self
+                .rows
+                .lock()
+                .expect("rows mutex")
+                .iter()
+                .find(|row| row.user_id == user_id && row.dedupe_key == dedupe_key)
+                .cloned();
+            Box::pin(async move { Ok(row) })
+        }
+
+        fn list_notifications(
+            &self,
+            query: &NotificationRepositoryListQuery,
+        ) -> BoxFuture<'_, DomainResult<Vec<InAppNotification>>> {
+            MockNotificationRepository.list_notifications(query)
+        }
+
+        fn list_notification_groups(
+            &self,
+            query: &NotificationRepositoryListQuery,
+        ) -> BoxFuture<'_, DomainResult<Vec<NotificationListItem>>> {
+            MockNotificationRepository.list_notification_groups(query)
+        }
+
+        fn list_notifications_in_window(
+            &self,
+            user_id: &str,
+            window_start_ms: i64,
+            window_end_ms: i64,
+        ) -> BoxFuture<'_, DomainResult<Vec<InAppNotification>>> {
+            MockNotificationRepository.list_notifications_in_window(
+                user_id,
+                window_start_ms,
+                window_end_ms,
+            )
+        }
+
+        fn mark_as_read(
+            &self,
+            user_id: &str,
+            notification_id: &str,
+            read_at_ms: i64,
+        ) -> BoxFuture<'_, DomainResult<InAppNotification>> {
+            MockNotificationRepository.mark_as_read(user_id, notification_id, read_at_ms)
+        }
+
+        fn mark_group_as_read(
+            &self,
+            user_id: &str,
+            group_key: &str,
+            read_at_ms: i64,
+        ) -> BoxFuture<'_, DomainResult<usize>> {
+            MockNotificationRepository.mark_group_as_read(user_id, group_key, read_at_ms)
+        }
+
+        fn unread_count(&self, user_id: &str, as_of_ms: i64) -> BoxFuture<'_, DomainResult<usize>> {
+            MockNotificationRepository.unread_count(user_id, as_of_ms)
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingQueue {
+        jobs: Mutex<Vec<JobEnvelope>>,
+    }
+
+    impl JobQueue for RecordingQueue {
+        fn enqueue(&self, job: &JobEnvelope) -> BoxFuture<'_, Result<(), JobQueueError>> {
+            self.jobs.lock().expect("jobs mutex").push(job.clone());
+            Box::pin(async { Ok(()) })
+        }
+
+        fn dequeue(
+            &self,
+            _timeout: std::time::Duration,
+        ) -> BoxFuture<'_, Result<Option<JobEnvelope>, JobQueueError>> {
+            Box::pin(async { Ok(None) })
+        }
+
+        fn ack(&self, _job_id: &str) -> BoxFuture<'_, Result<(), JobQueueError>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn promote_due(
+            &self,
+            _now_ms: i64,
+            _limit: usize,
+        ) -> BoxFuture<'_, Result<usize, JobQueueError>> {
+            Box::pin(async { Ok(0) })
+        }
+
+        fn requeue_processing(&self, _limit: usize) -> BoxFuture<'_, Result<usize, JobQueueError>> {
+            Box::pin(async { Ok(0) })
+        }
+    }
+
     #[test]
     fn parse_feed_cursor_rejects_invalid_shape() {
         assert!(parse_feed_cursor(Some("bad")).is_err());
@@ -1262,6 +2372,7 @@ mod tests {
             request_ts_ms: None,
             participant_ids: vec![],
             payload: None,
+            location: None,
         };
         assert!(validate_feed_input(&input).is_err());
     }
@@ -1284,6 +2395,8 @@ mod tests {
             correlation_id: "c1".into(),
             participant_ids: vec![],
             payload: None,
+            location: None,
+            distance_m: None,
         };
         assert!(is_visible_to_actor("somebody", &item));
     }
@@ -1311,6 +2424,8 @@ mod tests {
                     "hidden_reason": "ontology_ttl_expired"
                 }
             })),
+            location: None,
+            distance_m: None,
         };
         assert!(!is_visible_to_actor("somebody", &item));
     }
@@ -1335,6 +2450,8 @@ mod tests {
                     correlation_id: "c".into(),
                     participant_ids: vec![],
                     payload: None,
+                    location: None,
+                    distance_m: None,
                 },
                 score: 3,
             },
@@ -1355,6 +2472,8 @@ mod tests {
                     correlation_id: "c".into(),
                     participant_ids: vec![],
                     payload: None,
+                    location: None,
+                    distance_m: None,
                 },
                 score: 2,
             },
@@ -1387,6 +2506,7 @@ mod tests {
                 request_ts_ms: Some(1_000),
                 participant_ids: vec!["participant-1".to_string()],
                 payload: None,
+                location: None,
             })
             .await
             .expect("ingest feed should succeed");
@@ -1415,6 +2535,8 @@ mod tests {
             correlation_id: "corr-existing".to_string(),
             participant_ids: vec!["participant-existing".to_string()],
             payload: None,
+            location: None,
+            distance_m: None,
         });
         let service =
             DiscoveryService::new(feed_repo.clone(), Arc::new(MockNotificationRepository));
@@ -1437,6 +2559,7 @@ mod tests {
                 request_ts_ms: Some(1_000),
                 participant_ids: vec!["participant-1".to_string()],
                 payload: None,
+                location: None,
             })
             .await
             .expect("ingest feed replay should succeed");
@@ -1482,6 +2605,8 @@ mod tests {
                         ]
                     }
                 })),
+                location: None,
+                distance_m: None,
             },
             FeedItem {
                 feed_id: "feed-public-2".to_string(),
@@ -1515,6 +2640,8 @@ mod tests {
                         ]
                     }
                 })),
+                location: None,
+                distance_m: None,
             },
             FeedItem {
                 feed_id: "feed-private".to_string(),
@@ -1542,6 +2669,8 @@ mod tests {
                         ]
                     }
                 })),
+                location: None,
+                distance_m: None,
             },
         ]);
         let service = DiscoveryService::new(feed_repo, Arc::new(MockNotificationRepository));
@@ -1573,4 +2702,663 @@ mod tests {
                 .all(|item| item.entity_id != "ent-hidden")
         );
     }
+
+    fn ranked_item(feed_id: &str, occurred_at_ms: i64, payload: serde_json::Value) -> FeedItem {
+        FeedItem {
+            feed_id: feed_id.to_string(),
+            source_type: FEED_SOURCE_CONTRIBUTION.to_string(),
+            source_id: format!("src-{feed_id}"),
+            actor_id: "author-1".to_string(),
+            actor_username: "author-1".to_string(),
+            title: feed_id.to_string(),
+            summary: None,
+            scope_id: None,
+            privacy_level: Some("public".to_string()),
+            occurred_at_ms,
+            created_at_ms: occurred_at_ms,
+            request_id: format!("req-{feed_id}"),
+            correlation_id: format!("corr-{feed_id}"),
+            participant_ids: vec![],
+            payload: Some(payload),
+            location: None,
+            distance_m: None,
+        }
+    }
+
+    fn ranked_query(cursor: Option<String>) -> FeedListQuery {
+        FeedListQuery {
+            actor_id: "reader-1".to_string(),
+            cursor,
+            limit: Some(2),
+            scope_id: None,
+            privacy_level: None,
+            from_ms: None,
+            to_ms: None,
+            involvement_only: false,
+            mode: FeedMode::Ranked,
+            include_ranking_debug: true,
+            near: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn ranked_feed_scores_signals_and_keeps_cursor_stable() {
+        let now = now_ms();
+        let hour_ms = 60 * 60 * 1000;
+        let mut involved = ranked_item("feed-involved", now - 3 * hour_ms, serde_json::json!({}));
+        involved.participant_ids = vec!["reader-1".to_string()];
+        let mut siaga = ranked_item(
+            "feed-siaga",
+            now - 6 * hour_ms,
+            serde_json::json!({ "route": "siaga", "severity": "darurat" }),
+        );
+        siaga.source_type = FEED_SOURCE_SIAGA.to_string();
+        let rows = vec![
+            ranked_item("feed-fresh", now - 1_000, serde_json::json!({})),
+            involved,
+            siaga,
+            ranked_item(
+                "feed-followed",
+                now - 12 * hour_ms,
+                serde_json::json!({
+                    "enrichment": {
+                        "entity_tags": [
+                            { "entity_id": "ent-air", "entity_type": "topik", "label": "Saluran Air" }
+                        ],
+                        "feedback": { "vouch_count": 40, "challenge_count": 2 }
+                    }
+                }),
+            ),
+        ];
+        let feed_repo = Arc::new(MockFeedRepository::new(false));
+        feed_repo.set_feed_rows(rows.clone());
+        let service =
+            DiscoveryService::new(feed_repo.clone(), Arc::new(MockNotificationRepository))
+                .with_feed_preferences(Arc::new(MockFeedPreferenceRepository {
+                    follows: vec![FeedFollowPreference {
+                        user_id: "reader-1".to_string(),
+                        entity_id: "ent-air".to_string(),
+                        followed: true,
+                        updated_at_ms: now,
+                    }],
+                }));
+
+        let first = service
+            .list_feed(ranked_query(None))
+            .await
+            .expect("first ranked page");
+        let ranking = first.ranking.expect("debug ranking");
+        assert_eq!(ranking.candidate_count, 4);
+        assert_eq!(ranking.weights, FeedRankingWeights::default());
+        let siaga_score = &ranking.scores[0];
+        assert_eq!(siaga_score.feed_id, "feed-siaga");
+        assert_eq!(siaga_score.severity, 1.0);
+        assert_eq!(first.items[0].feed_id, "feed-siaga");
+        assert_eq!(first.items[1].feed_id, "feed-followed");
+        assert!(ranking.scores[1].feedback > 0.8);
+        assert_eq!(ranking.scores[1].followed, 1.0);
+        let cursor = first.next_cursor.expect("ranked cursor");
+        assert!(!cursor.contains(':'));
+
+        let mut grown = rows;
+        grown.push(ranked_item(
+            "feed-arrived-later",
+            now + 60_000,
+            serde_json::json!({ "route": "siaga", "severity": "darurat" }),
+        ));
+        feed_repo.set_feed_rows(grown);
+        let second = service
+            .list_feed(ranked_query(Some(cursor)))
+            .await
+            .expect("second ranked page");
+        let ids: Vec<&str> = second
+            .items
+            .iter()
+            .map(|item| item.feed_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["feed-involved", "feed-fresh"]);
+        assert!(second.next_cursor.is_none());
+
+        assert!(
+            service
+                .list_feed(ranked_query(Some("1000:feed-fresh".to_string())))
+                .await
+                .is_err()
+        );
+    }
+
+    struct MockSearchIndex {
+        hits: Vec<FeedSearchHit>,
+        queries: Mutex<Vec<FeedSearchIndexQuery>>,
+    }
+
+    impl FeedSearchIndex for MockSearchIndex {
+        fn search(
+            &self,
+            query: &FeedSearchIndexQuery,
+        ) -> BoxFuture<'_, DomainResult<Vec<FeedSearchHit>>> {
+            self.queries
+                .lock()
+                .expect("queries mutex")
+                .push(query.clone());
+            let hits = self
+                .hits
+                .iter()
+                .skip(query.offset)
+                .take(query.limit)
+                .cloned()
+                .collect();
+            Box::pin(async move { Ok(hits) })
+        }
+    }
+
+    #[tokio::test]
+    async fn search_uses_index_hits_and_drops_stale_rows() {
+        let mut hidden = ranked_item(
+            "feed-hidden",
+            300,
+            serde_json::json!({ "lifecycle": { "hidden": true } }),
+        );
+        hidden.title = "kerja bakti".to_string();
+        let feed_repo = Arc::new(MockFeedRepository::new(false));
+        feed_repo.set_feed_rows(vec![
+            ranked_item("feed-a", 100, serde_json::json!({})),
+            hidden,
+            ranked_item("feed-b", 200, serde_json::json!({})),
+        ]);
+        let index = Arc::new(MockSearchIndex {
+            hits: ["feed-a", "feed-hidden", "feed-missing", "feed-b"]
+                .iter()
+                .enumerate()
+                .map(|(idx, feed_id)| FeedSearchHit {
+                    feed_id: feed_id.to_string(),
+                    score: 4.0 - idx as f32,
+                })
+                .collect(),
+            queries: Mutex::new(Vec::new()),
+        });
+        let service = DiscoveryService::new(feed_repo, Arc::new(MockNotificationRepository))
+            .with_search_index(index.clone());
+        let query = |cursor: Option<String>| SearchListQuery {
+            actor_id: "reader-1".to_string(),
+            query_text: " kerja bakti ".to_string(),
+            cursor,
+            limit: Some(3),
+            scope_id: None,
+            privacy_level: None,
+            from_ms: None,
+            to_ms: None,
+            involvement_only: false,
+            exclude_vault: true,
+            near: None,
+        };
+
+        let first = service.search(query(None)).await.expect("first page");
+        let ids: Vec<&str> = first
+            .items
+            .iter()
+            .map(|result| result.item.feed_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["feed-a"]);
+        assert_eq!(first.items[0].score, 4_000);
+        let cursor = first.next_cursor.expect("index cursor");
+
+        let second = service
+            .search(query(Some(cursor)))
+            .await
+            .expect("second page");
+        assert_eq!(second.items.len(), 1);
+        assert_eq!(second.items[0].item.feed_id, "feed-b");
+        assert!(second.next_cursor.is_none());
+
+        {
+            let queries = index.queries.lock().expect("queries mutex");
+            assert_eq!(queries[0].query_text, "kerja bakti");
+            assert_eq!((queries[0].limit, queries[0].offset), (4, 0));
+            assert_eq!(queries[1].offset, 3);
+            assert!(queries[1].exclude_vault);
+        }
+
+        assert!(
+            service
+                .search(query(Some("3:100:feed-a".to_string())))
+                .await
+                .is_err()
+        );
+    }
+
+    struct MockFeedPreferenceRepository {
+        follows: Vec<FeedFollowPreference>,
+    }
+
+    impl FeedPreferenceRepository for MockFeedPreferenceRepository {
+        fn set_monitor_preference(
+            &self,
+            _preference: &FeedMonitorPreference,
+        ) -> BoxFuture<'_, DomainResult<()>> {
+            Box::pin(async move { Ok(()) })
+        }
+
+        fn set_follow_preference(
+            &self,
+            _preference: &FeedFollowPreference,
+        ) -> BoxFuture<'_, DomainResult<()>> {
+            Box::pin(async move { Ok(()) })
+        }
+
+        fn monitor_states(
+            &self,
+            _user_id: &str,
+            _witness_ids: &[String],
+        ) -> BoxFuture<'_, DomainResult<HashMap<String, bool>>> {
+            Box::pin(async move { Ok(HashMap::new()) })
+        }
+
+        fn follow_states(
+            &self,
+            user_id: &str,
+            entity_ids: &[String],
+        ) -> BoxFuture<'_, DomainResult<HashMap<String, bool>>> {
+            let states = self
+                .follows
+                .iter()
+                .filter(|preference| {
+                    preference.user_id == user_id && entity_ids.contains(&preference.entity_id)
+                })
+                .map(|preference| (preference.entity_id.clone(), preference.followed))
+                .collect();
+            Box::pin(async move { Ok(states) })
+        }
+
+        fn list_follows(
+            &self,
+            user_id: &str,
+        ) -> BoxFuture<'_, DomainResult<Vec<FeedFollowPreference>>> {
+            let follows = self
+                .follows
+                .iter()
+                .filter(|preference| preference.user_id == user_id)
+                .cloned()
+                .collect();
+            Box::pin(async move { Ok(follows) })
+        }
+    }
+
+    #[tokio::test]
+    async fn stored_follows_drive_suggestions_and_ranked_boost() {
+        let now = now_ms();
+        let tagged = |feed_id: &str, occurred_at_ms: i64, entity_id: &str| {
+            ranked_item(
+                feed_id,
+                occurred_at_ms,
+                serde_json::json!({
+                    "enrichment": {
+                        "entity_tags": [
+                            { "entity_id": entity_id, "entity_type": "topik", "label": entity_id }
+                        ]
+                    }
+                }),
+            )
+        };
+        let feed_repo = Arc::new(MockFeedRepository::new(false));
+        feed_repo.set_feed_rows(vec![
+            tagged("feed-newer", now - 1_000, "ent-jalan"),
+            tagged("feed-followed", now - 60 * 60 * 1000, "ent-air"),
+        ]);
+        let service = DiscoveryService::new(feed_repo, Arc::new(MockNotificationRepository))
+            .with_feed_preferences(Arc::new(MockFeedPreferenceRepository {
+                follows: vec![FeedFollowPreference {
+                    user_id: "reader-1".to_string(),
+                    entity_id: "ent-air".to_string(),
+                    followed: true,
+                    updated_at_ms: now,
+                }],
+            }));
+
+        let suggestions = service
+            .list_feed_suggestions(FeedSuggestionsQuery {
+                actor_id: "reader-1".to_string(),
+                limit: Some(6),
+                scope_id: None,
+                privacy_level: None,
+                from_ms: None,
+                to_ms: None,
+            })
+            .await
+            .expect("suggestions");
+        let suggested: Vec<(&str, bool)> = suggestions
+            .iter()
+            .map(|suggestion| (suggestion.entity_id.as_str(), suggestion.followed))
+            .collect();
+        assert_eq!(suggested, vec![("ent-air", true), ("ent-jalan", false)]);
+
+        let page = service
+            .list_feed(ranked_query(None))
+            .await
+            .expect("ranked page");
+        assert_eq!(page.items[0].feed_id, "feed-followed");
+        let ranking = page.ranking.expect("ranking debug");
+        assert_eq!(ranking.scores[0].followed, 1.0);
+        assert_eq!(ranking.scores[1].followed, 0.0);
+    }
+
+    struct MockNotificationPreferenceRepository {
+        preferences: NotificationPreferences,
+    }
+
+    impl NotificationPreferenceRepository for MockNotificationPreferenceRepository {
+        fn get_preferences(
+            &self,
+            user_id: &str,
+        ) -> BoxFuture<'_, DomainResult<Option<NotificationPreferences>>> {
+            let preferences =
+                (self.preferences.user_id == user_id).then(|| self.preferences.clone());
+            Box::pin(async move { Ok(preferences) })
+        }
+
+        fn upsert_preferences(
+            &self,
+            preferences: &NotificationPreferences,
+        ) -> BoxFuture<'_, DomainResult<NotificationPreferences>> {
+            let preferences = preferences.clone();
+            Box::pin(async move { Ok(preferences) })
+        }
+    }
+
+    #[tokio::test]
+    async fn ingest_notification_applies_type_opt_out_quiet_hours_and_siaga_bypass() {
+        // A UTC quiet window that always contains "now".
+        let now = OffsetDateTime::now_utc();
+        let hh_mm = |at: OffsetDateTime| format!("{:02}:{:02}", at.hour(), at.minute());
+        let mut preferences = NotificationPreferences::default_for("warga-1");
+        preferences.quiet_hours = Some(QuietHours {
+            start_local: hh_mm(now - time::Duration::hours(1)),
+            end_local: hh_mm(now + time::Duration::hours(1)),
+            timezone: "UTC".to_string(),
+        });
+        preferences.types.insert(
+            NOTIF_TYPE_VOUCH.to_string(),
+            NotificationChannels {
+                in_app: false,
+                email: true,
+                push: true,
+            },
+        );
+        let service = DiscoveryService::new(
+            Arc::new(MockFeedRepository::new(false)),
+            Arc::new(MockNotificationRepository),
+        )
+        .with_notification_preferences(Arc::new(MockNotificationPreferenceRepository {
+            preferences,
+        }));
+        let input =
+            |notification_type: &str, payload: Option<serde_json::Value>| NotificationIngestInput {
+                recipient_id: "warga-1".to_string(),
+                actor: ActorIdentity {
+                    user_id: "user-2".to_string(),
+                    username: "budi".to_string(),
+                },
+                notification_type: notification_type.to_string(),
+                source_type: FEED_SOURCE_SIAGA.to_string(),
+                source_id: "src-1".to_string(),
+                title: "Kabar".to_string(),
+                body: "Isi".to_string(),
+                payload,
+                privacy_level: None,
+                request_id: "req-1".to_string(),
+                correlation_id: "corr-1".to_string(),
+                request_ts_ms: None,
+                dedupe_key: None,
+            };
+
+        let vouch = service
+            .ingest_notification(input(NOTIF_TYPE_VOUCH, None))
+            .await
+            .expect("vouch");
+        assert!(!vouch.delivers_to(NotificationChannel::InApp));
+        assert!(vouch.delivers_to(NotificationChannel::Push));
+
+        let system = service
+            .ingest_notification(input(NOTIF_TYPE_SYSTEM, None))
+            .await
+            .expect("system");
+        assert!(system.delivers_to(NotificationChannel::InApp));
+        let held_until = system.notification.deliver_at_ms.expect("held");
+        assert!(held_until > now_ms());
+
+        let waspada = service
+            .ingest_notification(input(
+                NOTIF_TYPE_SIAGA,
+                Some(serde_json::json!({ "severity": "waspada" })),
+            ))
+            .await
+            .expect("waspada");
+        assert_eq!(waspada.notification.deliver_at_ms, None);
+
+        let unrated = service
+            .ingest_notification(input(NOTIF_TYPE_SIAGA, None))
+            .await
+            .expect("unrated siaga");
+        assert_eq!(unrated.notification.deliver_at_ms, None);
+
+        let darurat = service
+            .ingest_notification(input(
+                NOTIF_TYPE_SIAGA,
+                Some(serde_json::json!({ "siaga": { "severity": 5 } })),
+            ))
+            .await
+            .expect("darurat");
+        assert_eq!(darurat.notification.deliver_at_ms, None);
+    }
+
+    #[tokio::test]
+    async fn ingest_notification_push_only_replay_does_not_push_twice() {
+        let mut preferences = NotificationPreferences::default_for("warga-1");
+        preferences.types.insert(
+            NOTIF_TYPE_VOUCH.to_string(),
+            NotificationChannels {
+                in_app: false,
+                email: false,
+                push: true,
+            },
+        );
+        let queue = Arc::new(RecordingQueue::default());
+        let service = DiscoveryService::new(
+            Arc::new(MockFeedRepository::new(false)),
+            Arc::new(DedupingNotificationRepository::default()),
+        )
+        .with_notification_preferences(Arc::new(MockNotificationPreferenceRepository {
+            preferences,
+        }))
+        .with_push_queue(queue.clone());
+        let input = || NotificationIngestInput {
+            recipient_id: "warga-1".to_string(),
+            actor: ActorIdentity {
+                user_id: "user-2".to_string(),
+                username: "budi".to_string(),
+            },
+            notification_type: NOTIF_TYPE_VOUCH.to_string(),
+            source_type: FEED_SOURCE_CONTRIBUTION.to_string(),
+            source_id: "src-1".to_string(),
+            title: "Kabar".to_string(),
+            body: "Isi".to_string(),
+            payload: None,
+            privacy_level: None,
+            request_id: "req-1".to_string(),
+            correlation_id: "corr-1".to_string(),
+            request_ts_ms: None,
+            dedupe_key: Some("vouch:src-1".to_string()),
+        };
+
+        let first = service.ingest_notification(input()).await.expect("first");
+        assert!(first.notification.in_app_hidden);
+        let replay = service.ingest_notification(input()).await.expect("replay");
+        assert_eq!(
+            replay.notification.notification_id,
+            first.notification.notification_id
+        );
+
+        let jobs = queue.jobs.lock().expect("jobs mutex");
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(
+            jobs[0].job_id,
+            format!("web_push:{}", first.notification.notification_id)
+        );
+    }
+
+    fn vouch_row(notification_id: &str, actor: &str, created_at_ms: i64) -> InAppNotification {
+        InAppNotification {
+            notification_id: notification_id.to_string(),
+            user_id: "warga-1".to_string(),
+            actor_id: format!("user-{actor}"),
+            actor_username: actor.to_string(),
+            notification_type: NOTIF_TYPE_VOUCH.to_string(),
+            source_type: FEED_SOURCE_CONTRIBUTION.to_string(),
+            source_id: "contrib-1".to_string(),
+            title: "Vouch".to_string(),
+            body: "vouched".to_string(),
+            payload: None,
+            created_at_ms,
+            read_at_ms: None,
+            deliver_at_ms: None,
+            privacy_level: None,
+            request_id: format!("req-{notification_id}"),
+            correlation_id: "corr-1".to_string(),
+            dedupe_key: format!("dedupe-{notification_id}"),
+            group_key: Some(notification_group_key(
+                NOTIF_TYPE_VOUCH,
+                FEED_SOURCE_CONTRIBUTION,
+                "contrib-1",
+                created_at_ms,
+            )),
+            in_app_hidden: false,
+        }
+    }
+
+    #[test]
+    fn group_notifications_collapses_rows_and_samples_recent_actors() {
+        let window = NOTIFICATION_GROUP_WINDOW_MS;
+        let mut rows = vec![
+            vouch_row("n1", "ani", window + 1_000),
+            vouch_row("n2", "budi", window + 2_000),
+            vouch_row("n3", "ani", window + 3_000),
+            vouch_row("n4", "citra", window + 4_000),
+            vouch_row("n5", "dedi", window + 5_000),
+            // Previous window: a separate group.
+            vouch_row("n0", "eka", window - 1),
+        ];
+        rows[0].read_at_ms = Some(window + 9_000);
+        let mut legacy = vouch_row("legacy", "fajar", 10);
+        legacy.group_key = None;
+        rows.push(legacy);
+
+        let items = group_notifications(rows);
+        let keys: Vec<&str> = items
+            .iter()
+            .map(|item| item.notification.notification_id.as_str())
+            .collect();
+        assert_eq!(keys, vec!["n5", "n0", "legacy"]);
+
+        let group = items[0].group.as_ref().expect("group");
+        assert_eq!(group.count, 5);
+        assert_eq!(group.unread_count, 4);
+        assert_eq!(group.actor_count, 4);
+        let sample: Vec<&str> = group
+            .actors
+            .iter()
+            .map(|actor| actor.actor_username.as_str())
+            .collect();
+        assert_eq!(sample, vec!["dedi", "citra", "ani"]);
+
+        let legacy = items[2].group.as_ref().expect("legacy group");
+        assert_eq!(legacy.group_key, "legacy");
+        assert_eq!(legacy.count, 1);
+    }
+
+    #[derive(Default)]
+    struct RecordingEventPublisher {
+        events: Mutex<Vec<DiscoveryEvent>>,
+    }
+
+    impl DiscoveryEventPublisher for RecordingEventPublisher {
+        fn publish(&self, event: DiscoveryEvent) -> BoxFuture<'_, ()> {
+            self.events.lock().expect("events mutex").push(event);
+            Box::pin(async {})
+        }
+    }
+
+    #[tokio::test]
+    async fn ingest_publishes_new_feed_items_and_notifications_to_streams() {
+        let publisher = Arc::new(RecordingEventPublisher::default());
+        let actor = ActorIdentity {
+            user_id: "user-2".to_string(),
+            username: "budi".to_string(),
+        };
+        let feed_input = FeedIngestInput {
+            source_type: FEED_SOURCE_CONTRIBUTION.to_string(),
+            source_id: "contrib-1".to_string(),
+            actor: actor.clone(),
+            title: "Kerja bakti".to_string(),
+            summary: None,
+            scope_id: None,
+            privacy_level: Some("l3".to_string()),
+            occurred_at_ms: Some(1_000),
+            request_id: "req-feed".to_string(),
+            correlation_id: "corr-feed".to_string(),
+            request_ts_ms: Some(1_000),
+            participant_ids: vec!["warga-1".to_string()],
+            payload: None,
+            location: None,
+        };
+        let service = DiscoveryService::new(
+            Arc::new(MockFeedRepository::new(false)),
+            Arc::new(MockNotificationRepository),
+        )
+        .with_event_publisher(publisher.clone());
+        service
+            .ingest_feed(feed_input.clone())
+            .await
+            .expect("feed item");
+        service
+            .ingest_notification(NotificationIngestInput {
+                recipient_id: "warga-1".to_string(),
+                actor: actor.clone(),
+                notification_type: NOTIF_TYPE_VOUCH.to_string(),
+                source_type: FEED_SOURCE_CONTRIBUTION.to_string(),
+                source_id: "contrib-1".to_string(),
+                title: "Vouch".to_string(),
+                body: "budi vouched".to_string(),
+                payload: None,
+                privacy_level: None,
+                request_id: "req-notif".to_string(),
+                correlation_id: "corr-notif".to_string(),
+                request_ts_ms: None,
+                dedupe_key: None,
+            })
+            .await
+            .expect("notification");
+
+        let events = publisher.events.lock().expect("events mutex").clone();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(&events[0], DiscoveryEvent::FeedItem { .. }));
+        assert!(events[0].is_visible_to("warga-1"));
+        assert!(!events[0].is_visible_to("stranger"));
+        assert!(
+            matches!(&events[1], DiscoveryEvent::Notification { notification } if notification.user_id == "warga-1")
+        );
+        assert!(matches!(
+            &events[2],
+            DiscoveryEvent::UnreadCount { user_id, unread_count: 0 } if user_id == "warga-1"
+        ));
+        assert!(!events[1].is_visible_to("user-2"));
+
+        // A replayed feed ingest is not pushed again.
+        let replay = DiscoveryService::new(
+            Arc::new(MockFeedRepository::new(true)),
+            Arc::new(MockNotificationRepository),
+        )
+        .with_event_publisher(publisher.clone());
+        let _ = replay.ingest_feed(feed_input).await;
+        assert_eq!(publisher.events.lock().expect("events mutex").len(), 3);
+    }
 }
@@ -10,4 +10,7 @@ pub enum DomainError {
     Conflict,
     #[error("forbidden: {0}")]
     Forbidden(String),
+    /// An adapter failed for reasons the caller cannot fix (I/O, a stopped task).
+    #[error("internal error: {0}")]
+    Internal(String),
 }
@@ -0,0 +1,255 @@
+use serde::{Deserialize, Serialize};
+
+use crate::DomainResult;
+use crate::error::DomainError;
+
+const EARTH_RADIUS_M: f64 = 6_371_008.8;
+const METERS_PER_DEGREE: f64 = 111_320.0;
+const GEOHASH_ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+/// Stored geohashes are about 4.8 m × 4.8 m; queries only ever use prefixes.
+pub const GEOHASH_STORE_PRECISION: usize = 9;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GeoPoint {
+    pub lat: f64,
+    pub lng: f64,
+}
+
+impl GeoPoint {
+    pub fn new(lat: f64, lng: f64) -> DomainResult<Self> {
+        let point = Self { lat, lng };
+        point.validate()?;
+        Ok(point)
+    }
+
+    pub fn validate(&self) -> DomainResult<()> {
+        if !self.lat.is_finite() || !(-90.0..=90.0).contains(&self.lat) {
+            return Err(DomainError::Validation(
+                "lat must be between -90 and 90".into(),
+            ));
+        }
+        if !self.lng.is_finite() || !(-180.0..=180.0).contains(&self.lng) {
+            return Err(DomainError::Validation(
+                "lng must be between -180 and 180".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Reads `{lat, lng}` or the evidence-style `{lat, lon}`.
+    pub fn from_json(value: &serde_json::Value) -> DomainResult<Self> {
+        let lat = value.get("lat").and_then(serde_json::Value::as_f64);
+        let lng = value
+            .get("lng")
+            .or_else(|| value.get("lon"))
+            .and_then(serde_json::Value::as_f64);
+        match (lat, lng) {
+            (Some(lat), Some(lng)) => Self::new(lat, lng),
+            _ => Err(DomainError::Validation(
+                "location requires numeric lat and lng".into(),
+            )),
+        }
+    }
+
+    pub fn geohash(&self) -> String {
+        geohash_encode(self, GEOHASH_STORE_PRECISION)
+    }
+}
+
+/// A "near me" filter: everything within `radius_m` metres of `center`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GeoRadius {
+    pub center: GeoPoint,
+    pub radius_m: f64,
+}
+
+impl GeoRadius {
+    pub fn new(center: GeoPoint, radius_m: f64, max_radius_m: f64) -> DomainResult<Self> {
+        center.validate()?;
+        if !radius_m.is_finite() || radius_m <= 0.0 || radius_m > max_radius_m {
+            return Err(DomainError::Validation(format!(
+                "radius_m must be greater than 0 and at most {max_radius_m}"
+            )));
+        }
+        Ok(Self { center, radius_m })
+    }
+
+    pub fn distance_to(&self, point: &GeoPoint) -> f64 {
+        haversine_m(&self.center, point)
+    }
+
+    /// Distance to `point` when it lies inside the radius.
+    pub fn distance_within(&self, point: &GeoPoint) -> Option<f64> {
+        let distance = self.distance_to(point);
+        (distance <= self.radius_m).then_some(distance)
+    }
+
+    /// Geohash prefixes whose cells together cover the circle: the centre cell
+    /// and its eight neighbours at the finest precision whose cells are at
+    /// least `radius_m` across. Stores filter on these before the exact
+    /// distance check. Empty when no precision is coarse enough (near the
+    /// poles), meaning no prefilter applies.
+    pub fn covering_geohashes(&self) -> Vec<String> {
+        let max_abs_lat = (self.center.lat.abs() + self.radius_m / METERS_PER_DEGREE).min(90.0);
+        let lng_scale = max_abs_lat.to_radians().cos();
+        if lng_scale <= f64::EPSILON {
+            return Vec::new();
+        }
+        let radius_lat_deg = self.radius_m / METERS_PER_DEGREE;
+        let radius_lng_deg = radius_lat_deg / lng_scale;
+
+        let Some((precision, cell_lat_deg, cell_lng_deg)) = (1..=GEOHASH_STORE_PRECISION)
+            .rev()
+            .map(|precision| {
+                let (lat_deg, lng_deg) = geohash_cell_size_deg(precision);
+                (precision, lat_deg, lng_deg)
+            })
+            .find(|(_, lat_deg, lng_deg)| *lat_deg >= radius_lat_deg && *lng_deg >= radius_lng_deg)
+        else {
+            return Vec::new();
+        };
+
+        let mut cells = Vec::with_capacity(9);
+        for lat_step in [-1.0, 0.0, 1.0] {
+            for lng_step in [-1.0, 0.0, 1.0] {
+                let lat = (self.center.lat + lat_step * cell_lat_deg).clamp(-90.0, 90.0);
+                let mut lng = self.center.lng + lng_step * cell_lng_deg;
+                if lng > 180.0 {
+                    lng -= 360.0;
+                } else if lng < -180.0 {
+                    lng += 360.0;
+                }
+                let cell = geohash_encode(&GeoPoint { lat, lng }, precision);
+                if !cells.contains(&cell) {
+                    cells.push(cell);
+                }
+            }
+        }
+        cells.sort();
+        cells
+    }
+}
+
+/// Great-circle distance in metres.
+pub fn haversine_m(from: &GeoPoint, to: &GeoPoint) -> f64 {
+    let lat1 = from.lat.to_radians();
+    let lat2 = to.lat.to_radians();
+    let dlat = (to.lat - from.lat).to_radians();
+    let dlng = (to.lng - from.lng).to_radians();
+    let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlng / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().min(1.0).asin()
+}
+
+pub fn geohash_encode(point: &GeoPoint, precision: usize) -> String {
+    let (mut lat_min, mut lat_max) = (-90.0, 90.0);
+    let (mut lng_min, mut lng_max) = (-180.0, 180.0);
+    let mut hash = String::with_capacity(precision);
+    let mut even_bit = true;
+    let mut bits = 0u8;
+    let mut index = 0usize;
+    while hash.len() < precision {
+        if even_bit {
+            let mid = (lng_min + lng_max) / 2.0;
+            if point.lng >= mid {
+                index = index * 2 + 1;
+                lng_min = mid;
+            } else {
+                index *= 2;
+                lng_max = mid;
+            }
+        } else {
+            let mid = (lat_min + lat_max) / 2.0;
+            if point.lat >= mid {
+                index = index * 2 + 1;
+                lat_min = mid;
+            } else {
+                index *= 2;
+                lat_max = mid;
+            }
+        }
+        even_bit = !even_bit;
+        bits += 1;
+        if bits == 5 {
+            hash.push(GEOHASH_ALPHABET[index] as char);
+            bits = 0;
+            index = 0;
+        }
+    }
+    hash
+}
+
+/// Every prefix of a stored geohash, shortest first; indexed so any covering
+/// cell matches with one term lookup.
+pub fn geohash_prefixes(geohash: &str) -> Vec<String> {
+    (1..=geohash.len())
+        .map(|len| geohash[..len].to_string())
+        .collect()
+}
+
+fn geohash_cell_size_deg(precision: usize) -> (f64, f64) {
+    let bits = 5 * precision;
+    let lng_bits = bits.div_ceil(2);
+    let lat_bits = bits / 2;
+    (
+        180.0 / f64::powi(2.0, lat_bits as i32),
+        360.0 / f64::powi(2.0, lng_bits as i32),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(lat: f64, lng: f64) -> GeoPoint {
+        GeoPoint::new(lat, lng).expect("point")
+    }
+
+    #[test]
+    fn geohash_matches_reference_values() {
+        assert_eq!(
+            geohash_encode(&point(57.64911, 10.40744), 11),
+            "u4pruydqqvj"
+        );
+    }
+
+    #[test]
+    fn haversine_measures_known_distance() {
+        // Monas to Bundaran HI, Jakarta: about 2.2 km.
+        let distance = haversine_m(&point(-6.175392, 106.827153), &point(-6.194951, 106.823056));
+        assert!((2_150.0..2_250.0).contains(&distance), "{distance}");
+    }
+
+    #[test]
+    fn covering_cells_contain_every_point_in_radius() {
+        let radius = GeoRadius::new(point(-6.175392, 106.827153), 2_000.0, 50_000.0).unwrap();
+        let cells = radius.covering_geohashes();
+        assert!(!cells.is_empty() && cells.len() <= 9);
+        for bearing in 0..16 {
+            let angle = f64::from(bearing) * std::f64::consts::TAU / 16.0;
+            let edge = GeoPoint {
+                lat: radius.center.lat + angle.sin() * 1_990.0 / METERS_PER_DEGREE,
+                lng: radius.center.lng
+                    + angle.cos() * 1_990.0
+                        / (METERS_PER_DEGREE * radius.center.lat.to_radians().cos()),
+            };
+            assert!(radius.distance_within(&edge).is_some());
+            let hash = edge.geohash();
+            assert!(
+                cells.iter().any(|cell| hash.starts_with(cell.as_str())),
+                "{hash} not covered by {cells:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_out_of_range_input() {
+        assert!(GeoPoint::new(91.0, 0.0).is_err());
+        assert!(GeoPoint::from_json(&serde_json::json!({ "lat": 1.0 })).is_err());
+        assert_eq!(
+            GeoPoint::from_json(&serde_json::json!({ "lat": 1.0, "lon": 2.0 })).unwrap(),
+            point(1.0, 2.0)
+        );
+        assert!(GeoRadius::new(point(0.0, 0.0), 60_000.0, 50_000.0).is_err());
+        assert!(GeoRadius::new(point(0.0, 0.0), 0.0, 50_000.0).is_err());
+    }
+}
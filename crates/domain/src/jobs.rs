@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::ports::jobs::{JobEnvelope, JobType};
+use crate::push::{PushUrgency, WebPushMessage};
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct JobPayload {
@@ -29,12 +30,51 @@ pub struct TTLCleanupPayload {
     pub cutoff_ms: i64,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ChatRetentionSweepPayload {
+    pub scheduled_ms: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DigestSendPayload {
+    pub scheduled_ms: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TrendingComputePayload {
+    pub scheduled_ms: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct WebPushSendPayload {
+    pub user_id: String,
+    pub message: WebPushMessage,
+    pub urgency: PushUrgency,
+    /// Narrows a retry to the subscriptions that failed; `None` targets all of the user's.
+    #[serde(default)]
+    pub subscription_ids: Option<Vec<String>>,
+}
+
+/// Feed items the API changed; the worker refreshes their search documents.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FeedSearchIndexSyncPayload {
+    pub feed_ids: Vec<String>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct ConceptVerificationPayload {
     pub qid: String,
     pub scheduled_ms: i64,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ConceptMergePayload {
+    pub from_qid: String,
+    pub into_qid: String,
+    pub reason: String,
+    pub scheduled_ms: i64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct OntologyNoteEnrichPayload {
     pub note_id: String,
@@ -1,19 +1,32 @@
 pub mod adaptive_path;
+pub mod adaptive_path_roles;
 pub mod auth;
 pub mod chat;
 pub mod contributions;
+pub mod digest;
 pub mod discovery;
 pub mod error;
 pub mod evidence;
+pub mod geo;
 pub mod idempotency;
 pub mod identity;
 pub mod jobs;
 pub mod mode;
 pub mod moderation;
+pub mod notification_preferences;
 pub mod ontology;
+pub mod ontology_autocomplete;
+pub mod ontology_export;
+pub mod ontology_gazetteer;
+pub mod ontology_merge;
+pub mod ontology_query;
+pub mod ontology_ranking;
+pub mod ontology_revision;
 pub mod ports;
+pub mod push;
 pub mod ranking;
 pub mod siaga;
+pub mod trending;
 pub mod util;
 pub mod vault;
 pub mod vouches;
@@ -0,0 +1,339 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime, Time};
+
+use crate::DomainResult;
+use crate::digest::resolve_timezone;
+use crate::error::DomainError;
+use crate::jobs::now_ms;
+use crate::ports::notification_preferences::NotificationPreferenceRepository;
+
+const DEFAULT_SIAGA_BYPASS_MIN_SEVERITY: u8 = 0;
+const MAX_NOTIFICATION_TYPE_LENGTH: usize = 64;
+const MAX_NOTIFICATION_TYPES: usize = 64;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationChannel {
+    InApp,
+    Email,
+    Push,
+}
+
+/// Channel toggles for one notification type. Omitted channels stay enabled.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct NotificationChannels {
+    pub in_app: bool,
+    pub email: bool,
+    pub push: bool,
+}
+
+impl Default for NotificationChannels {
+    fn default() -> Self {
+        Self {
+            in_app: true,
+            email: true,
+            push: true,
+        }
+    }
+}
+
+impl NotificationChannels {
+    pub fn allows(self, channel: NotificationChannel) -> bool {
+        match channel {
+            NotificationChannel::InApp => self.in_app,
+            NotificationChannel::Email => self.email,
+            NotificationChannel::Push => self.push,
+        }
+    }
+}
+
+/// A daily local-time window, e.g. `22:00`–`06:00`, during which deliveries are held.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct QuietHours {
+    /// Local `HH:MM`; the window may wrap past midnight.
+    pub start_local: String,
+    pub end_local: String,
+    /// Same zones as digest settings: Indonesian IANA names, `UTC` or `+07:00`.
+    pub timezone: String,
+}
+
+impl QuietHours {
+    /// When `now_ms` falls inside the window, the instant the window ends.
+    pub fn release_at_ms(&self, now_ms: i64) -> DomainResult<Option<i64>> {
+        let offset = resolve_timezone(&self.timezone)?;
+        let start = parse_local_time(&self.start_local)?;
+        let end = parse_local_time(&self.end_local)?;
+        if start == end {
+            return Ok(None);
+        }
+        let local_now = OffsetDateTime::from_unix_timestamp_nanos(i128::from(now_ms) * 1_000_000)
+            .map_err(|_| DomainError::Validation(format!("timestamp {now_ms} is out of range")))?
+            .to_offset(offset);
+        let now_time = local_now.time();
+        let inside = if start < end {
+            now_time >= start && now_time < end
+        } else {
+            now_time >= start || now_time < end
+        };
+        if !inside {
+            return Ok(None);
+        }
+        let mut release = local_now.replace_time(end);
+        if release <= local_now {
+            release += Duration::days(1);
+        }
+        Ok(Some((release.unix_timestamp_nanos() / 1_000_000) as i64))
+    }
+
+    fn validate(&self) -> DomainResult<()> {
+        parse_local_time(&self.start_local)?;
+        parse_local_time(&self.end_local)?;
+        resolve_timezone(&self.timezone)?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NotificationPreferences {
+    pub user_id: String,
+    /// Channel toggles keyed by `notification_type`; unlisted types use every channel.
+    pub types: BTreeMap<String, NotificationChannels>,
+    pub quiet_hours: Option<QuietHours>,
+    /// Opt-in floor (0..=5) for siaga alerts delivered during quiet hours; `0` lets
+    /// every siaga alert through, including ones without a severity.
+    pub siaga_bypass_min_severity: u8,
+    pub updated_at_ms: i64,
+}
+
+impl NotificationPreferences {
+    pub fn default_for(user_id: &str) -> Self {
+        Self {
+            user_id: user_id.to_string(),
+            types: BTreeMap::new(),
+            quiet_hours: None,
+            siaga_bypass_min_severity: DEFAULT_SIAGA_BYPASS_MIN_SEVERITY,
+            updated_at_ms: 0,
+        }
+    }
+
+    pub fn channels_for(&self, notification_type: &str) -> NotificationChannels {
+        self.types
+            .get(&notification_type.trim().to_ascii_lowercase())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Decides which channels receive a notification and whether quiet hours hold it.
+    /// Siaga alerts (`siaga_severity` is `Some`, `0` when unknown) bypass quiet hours
+    /// unless they fall below the recipient's opt-in severity floor.
+    pub fn plan_delivery(
+        &self,
+        notification_type: &str,
+        siaga_severity: Option<u8>,
+        now_ms: i64,
+    ) -> DomainResult<DeliveryPlan> {
+        let channels = self.channels_for(notification_type);
+        let bypass = siaga_severity.is_some_and(|level| level >= self.siaga_bypass_min_severity);
+        let deliver_at_ms = match (&self.quiet_hours, bypass) {
+            (Some(quiet_hours), false) => quiet_hours.release_at_ms(now_ms)?,
+            _ => None,
+        };
+        Ok(DeliveryPlan {
+            channels,
+            deliver_at_ms,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeliveryPlan {
+    pub channels: NotificationChannels,
+    /// Set when quiet hours hold the delivery until this instant.
+    pub deliver_at_ms: Option<i64>,
+}
+
+/// Replaces the stored preferences; omitted fields reset to their defaults.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct NotificationPreferencesUpdate {
+    #[serde(default)]
+    pub types: BTreeMap<String, NotificationChannels>,
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+    #[serde(default)]
+    pub siaga_bypass_min_severity: Option<u8>,
+}
+
+#[derive(Clone)]
+pub struct NotificationPreferenceService {
+    repository: Arc<dyn NotificationPreferenceRepository>,
+}
+
+impl NotificationPreferenceService {
+    pub fn new(repository: Arc<dyn NotificationPreferenceRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Stored preferences, or the defaults when the user never saved any.
+    pub async fn get(&self, user_id: &str) -> DomainResult<NotificationPreferences> {
+        validate_user_id(user_id)?;
+        Ok(self
+            .repository
+            .get_preferences(user_id)
+            .await?
+            .unwrap_or_else(|| NotificationPreferences::default_for(user_id)))
+    }
+
+    pub async fn update(
+        &self,
+        user_id: &str,
+        update: NotificationPreferencesUpdate,
+    ) -> DomainResult<NotificationPreferences> {
+        validate_user_id(user_id)?;
+        if update.types.len() > MAX_NOTIFICATION_TYPES {
+            return Err(DomainError::Validation(format!(
+                "at most {MAX_NOTIFICATION_TYPES} notification types may be configured"
+            )));
+        }
+        let mut types = BTreeMap::new();
+        for (notification_type, channels) in update.types {
+            types.insert(normalize_notification_type(&notification_type)?, channels);
+        }
+        let quiet_hours = match update.quiet_hours {
+            Some(quiet_hours) => {
+                let quiet_hours = QuietHours {
+                    start_local: quiet_hours.start_local.trim().to_string(),
+                    end_local: quiet_hours.end_local.trim().to_string(),
+                    timezone: quiet_hours.timezone.trim().to_string(),
+                };
+                quiet_hours.validate()?;
+                Some(quiet_hours)
+            }
+            None => None,
+        };
+        let siaga_bypass_min_severity = update
+            .siaga_bypass_min_severity
+            .unwrap_or(DEFAULT_SIAGA_BYPASS_MIN_SEVERITY);
+        if siaga_bypass_min_severity > 5 {
+            return Err(DomainError::Validation(
+                "siaga_bypass_min_severity must be 0..=5".into(),
+            ));
+        }
+        let preferences = NotificationPreferences {
+            user_id: user_id.to_string(),
+            types,
+            quiet_hours,
+            siaga_bypass_min_severity,
+            updated_at_ms: now_ms(),
+        };
+        self.repository.upsert_preferences(&preferences).await
+    }
+}
+
+fn normalize_notification_type(value: &str) -> DomainResult<String> {
+    let normalized = value.trim().to_ascii_lowercase();
+    let valid = !normalized.is_empty()
+        && normalized.len() <= MAX_NOTIFICATION_TYPE_LENGTH
+        && normalized
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' || ch == '.');
+    if !valid {
+        return Err(DomainError::Validation(format!(
+            "invalid notification type '{value}'"
+        )));
+    }
+    Ok(normalized)
+}
+
+fn parse_local_time(value: &str) -> DomainResult<Time> {
+    let invalid = || DomainError::Validation(format!("invalid local time '{value}'; use HH:MM"));
+    let (hours, minutes) = value.trim().split_once(':').ok_or_else(invalid)?;
+    let hours = hours.parse::<u8>().map_err(|_| invalid())?;
+    let minutes = minutes.parse::<u8>().map_err(|_| invalid())?;
+    Time::from_hms(hours, minutes, 0).map_err(|_| invalid())
+}
+
+fn validate_user_id(user_id: &str) -> DomainResult<()> {
+    if user_id.trim().is_empty() {
+        return Err(DomainError::Validation("user_id is required".into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2026-03-04 23:30 WIB == 16:30 UTC.
+    const WEDNESDAY_2330_WIB_MS: i64 = 1_772_641_800_000;
+    // 2026-03-05 06:00 WIB == 2026-03-04 23:00 UTC.
+    const THURSDAY_0600_WIB_MS: i64 = 1_772_665_200_000;
+
+    fn preferences() -> NotificationPreferences {
+        let mut preferences = NotificationPreferences::default_for("user-1");
+        preferences.quiet_hours = Some(QuietHours {
+            start_local: "22:00".into(),
+            end_local: "06:00".into(),
+            timezone: "Asia/Jakarta".into(),
+        });
+        preferences
+    }
+
+    #[test]
+    fn quiet_hours_wrap_midnight_and_release_at_local_end() {
+        let quiet_hours = preferences().quiet_hours.unwrap();
+        assert_eq!(
+            quiet_hours.release_at_ms(WEDNESDAY_2330_WIB_MS).unwrap(),
+            Some(THURSDAY_0600_WIB_MS)
+        );
+        assert_eq!(
+            quiet_hours
+                .release_at_ms(THURSDAY_0600_WIB_MS - 60_000)
+                .unwrap(),
+            Some(THURSDAY_0600_WIB_MS)
+        );
+        assert_eq!(
+            quiet_hours.release_at_ms(THURSDAY_0600_WIB_MS).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn plan_delivery_applies_type_opt_out_and_siaga_bypass() {
+        let mut preferences = preferences();
+        preferences.types.insert(
+            "vouch".into(),
+            NotificationChannels {
+                in_app: true,
+                email: false,
+                push: false,
+            },
+        );
+
+        let vouch = preferences
+            .plan_delivery("Vouch", None, WEDNESDAY_2330_WIB_MS)
+            .unwrap();
+        assert!(!vouch.channels.allows(NotificationChannel::Push));
+        assert_eq!(vouch.deliver_at_ms, Some(THURSDAY_0600_WIB_MS));
+
+        let darurat = preferences
+            .plan_delivery("siaga", Some(5), WEDNESDAY_2330_WIB_MS)
+            .unwrap();
+        assert_eq!(darurat.channels, NotificationChannels::default());
+        assert_eq!(darurat.deliver_at_ms, None);
+
+        let unrated = preferences
+            .plan_delivery("siaga", Some(0), WEDNESDAY_2330_WIB_MS)
+            .unwrap();
+        assert_eq!(unrated.deliver_at_ms, None);
+
+        preferences.siaga_bypass_min_severity = 4;
+        let waspada = preferences
+            .plan_delivery("siaga", Some(2), WEDNESDAY_2330_WIB_MS)
+            .unwrap();
+        assert_eq!(waspada.deliver_at_ms, Some(THURSDAY_0600_WIB_MS));
+    }
+}
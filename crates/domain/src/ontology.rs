@@ -20,6 +20,16 @@ pub enum ActionType {
 }
 
 impl ActionType {
+    pub const ALL: [Self; 7] = [
+        Self::InformAction,
+        Self::RepairAction,
+        Self::CreateAction,
+        Self::SearchAction,
+        Self::AchieveAction,
+        Self::AssessAction,
+        Self::AlertAction,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::InformAction => "schema:InformAction",
@@ -92,6 +102,28 @@ pub struct OntologyNote {
     pub rahasia_level: i64,
     pub confidence: f64,
     pub created_at_ms: i64,
+    /// Starts at 1; every author edit bumps it.
+    #[serde(default = "first_note_version")]
+    pub version: i64,
+    #[serde(default)]
+    pub edited_at_ms: Option<i64>,
+    /// Retracted notes stay readable with this marker but leave the feed.
+    #[serde(default)]
+    pub retracted_at_ms: Option<i64>,
+    #[serde(default)]
+    pub retraction_reason: Option<String>,
+}
+
+pub const FIRST_NOTE_VERSION: i64 = 1;
+
+fn first_note_version() -> i64 {
+    FIRST_NOTE_VERSION
+}
+
+impl OntologyNote {
+    pub fn is_retracted(&self) -> bool {
+        self.retracted_at_ms.is_some()
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -107,6 +139,29 @@ pub struct OntologyNoteCreate {
     pub confidence: f64,
 }
 
+/// An author edit. `expected_version` guards against overwriting an edit the
+/// author has not seen.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct OntologyNoteUpdate {
+    pub note_id: String,
+    pub expected_version: i64,
+    pub content: String,
+    pub confidence: f64,
+    pub edited_at_ms: i64,
+}
+
+/// The text of one note version. `created_at_ms` is when it was written and
+/// `superseded_at_ms` when an edit replaced it (`None` for the current one).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct OntologyNoteVersion {
+    pub note_id: String,
+    pub version: i64,
+    pub content: String,
+    pub confidence: f64,
+    pub created_at_ms: i64,
+    pub superseded_at_ms: Option<i64>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct OntologyTripleCreate {
     pub edge: OntologyEdgeKind,
@@ -116,10 +171,78 @@ pub struct OntologyTripleCreate {
     pub metadata: Option<Value>,
 }
 
+pub const CONCEPT_MERGE_REASON_ADMIN: &str = "admin";
+pub const CONCEPT_MERGE_REASON_WIKIDATA_REDIRECT: &str = "wikidata_redirect";
+
+/// `from_qid` was merged into `to_qid`; lookups of the old QID land on the
+/// surviving concept.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OntologyConceptRedirect {
+    pub from_qid: String,
+    pub to_qid: String,
+    pub reason: String,
+    pub created_at_ms: i64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OntologyConceptMergeResult {
+    pub surviving: OntologyConcept,
+    pub redirect: OntologyConceptRedirect,
+    /// `ABOUT`, `INSTANCE_OF` and `BROADER` edges re-pointed at the survivor.
+    pub moved_edge_count: usize,
+}
+
+/// One label of a concept in one language. `label_id` and `label_en` on the
+/// concept are the preferred labels; these add aliases and other languages
+/// (for example `jv` Javanese and `su` Sundanese).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct OntologyConceptLabel {
+    pub lang: String,
+    pub label: String,
+    #[serde(default)]
+    pub alias: bool,
+}
+
+/// Every label of one concept, preferred labels included.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OntologyConceptLabels {
+    pub qid: String,
+    pub labels: Vec<OntologyConceptLabel>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OntologyConceptUsage {
+    pub qid: String,
+    pub note_count: usize,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct NoteFeedbackCounts {
     pub vouch_count: usize,
     pub challenge_count: usize,
+    /// Of the counts above, those cast before the note's latest edit.
+    #[serde(default)]
+    pub earlier_version_vouch_count: usize,
+    #[serde(default)]
+    pub earlier_version_challenge_count: usize,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteFeedbackKind {
+    Vouch,
+    Challenge,
+}
+
+/// One `VOUCHES` or `CHALLENGES` edge on a note. Edges written before feedback
+/// was timestamped have no `created_at_ms`; edges written before notes could
+/// be edited count as cast on version 1.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NoteFeedbackVote {
+    pub voter_id: String,
+    pub kind: NoteFeedbackKind,
+    pub created_at_ms: Option<i64>,
+    pub note_version: i64,
 }
 
 #[cfg(test)]
@@ -0,0 +1,349 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::DomainResult;
+use crate::error::DomainError;
+use crate::ontology::OntologyConceptLabel;
+use crate::ports::ontology::{ConceptLabelIndex, OntologyRepository};
+
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 25;
+const MAX_QUERY_CHARS: usize = 100;
+/// Index matches fetched per requested suggestion, so community usage has
+/// room to reorder them.
+const CANDIDATE_FACTOR: usize = 5;
+pub const MAX_LABELS_PER_CONCEPT: usize = 64;
+const MAX_LABEL_CHARS: usize = 200;
+const MAX_LANG_CHARS: usize = 12;
+
+/// One indexed label matching a query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConceptLabelMatch {
+    pub qid: String,
+    pub label: String,
+    pub lang: String,
+    pub alias: bool,
+    /// Edits between the query and the closest prefix of the label; 0 for a
+    /// plain prefix match.
+    pub distance: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConceptAutocompleteQuery {
+    pub q: String,
+    /// Concepts that notes of this community are `ABOUT` rank first.
+    pub community_id: Option<String>,
+    /// Preferred label language; other languages still match.
+    pub lang: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ConceptSuggestion {
+    pub qid: String,
+    pub label_id: Option<String>,
+    pub label_en: Option<String>,
+    /// The label the query matched, which may be an alias or another language.
+    pub matched_label: String,
+    pub matched_lang: String,
+    pub matched_alias: bool,
+    pub distance: usize,
+    pub community_note_count: usize,
+}
+
+/// Lowercases, folds common Latin diacritics (`é` in Javanese and Sundanese
+/// spelling, `ñ`, ...) and collapses punctuation and whitespace to single spaces.
+pub fn normalize_label(value: &str) -> String {
+    let mut normalized = String::with_capacity(value.len());
+    let mut pending_space = false;
+    for ch in value.chars().flat_map(char::to_lowercase) {
+        let ch = fold_diacritic(ch);
+        if ch.is_alphanumeric() {
+            if pending_space && !normalized.is_empty() {
+                normalized.push(' ');
+            }
+            pending_space = false;
+            normalized.push(ch);
+        } else if ch != '\'' && ch != '’' {
+            pending_space = true;
+        }
+    }
+    normalized
+}
+
+fn fold_diacritic(ch: char) -> char {
+    match ch {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' => 'a',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ě' => 'e',
+        'ì' | 'í' | 'î' | 'ï' | 'ī' => 'i',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ō' => 'o',
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' => 'u',
+        'ñ' => 'n',
+        'ç' => 'c',
+        other => other,
+    }
+}
+
+/// Validates labels written through the API or the importer: languages are
+/// lowercase tags such as `id`, `jv`, `su` or `zh-hant`, and labels that
+/// normalize to the same text in one language are kept once.
+pub fn normalize_concept_labels(
+    labels: &[OntologyConceptLabel],
+) -> DomainResult<Vec<OntologyConceptLabel>> {
+    if labels.len() > MAX_LABELS_PER_CONCEPT {
+        return Err(DomainError::Validation(format!(
+            "at most {MAX_LABELS_PER_CONCEPT} labels are allowed per concept"
+        )));
+    }
+    let mut seen = HashSet::new();
+    let mut normalized = Vec::with_capacity(labels.len());
+    for label in labels {
+        let lang = label.lang.trim().to_ascii_lowercase();
+        let lang_valid = !lang.is_empty()
+            && lang.len() <= MAX_LANG_CHARS
+            && lang
+                .bytes()
+                .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-');
+        if !lang_valid {
+            return Err(DomainError::Validation(format!(
+                "invalid label language: {}",
+                label.lang
+            )));
+        }
+        let text = label.label.trim();
+        if text.chars().count() > MAX_LABEL_CHARS {
+            return Err(DomainError::Validation(format!(
+                "labels must be at most {MAX_LABEL_CHARS} characters"
+            )));
+        }
+        let key = normalize_label(text);
+        if key.is_empty() {
+            return Err(DomainError::Validation(
+                "labels must contain a letter or digit".into(),
+            ));
+        }
+        if seen.insert((lang.clone(), key)) {
+            normalized.push(OntologyConceptLabel {
+                lang,
+                label: text.to_string(),
+                alias: label.alias,
+            });
+        }
+    }
+    Ok(normalized)
+}
+
+/// Prefix and typo-tolerant lookup over every concept label, ranked by match
+/// distance, then by how many notes of the caller's community are about the
+/// concept, then by language and preferred-over-alias.
+pub async fn autocomplete_concepts(
+    index: &dyn ConceptLabelIndex,
+    repo: &dyn OntologyRepository,
+    query: &ConceptAutocompleteQuery,
+) -> DomainResult<Vec<ConceptSuggestion>> {
+    let needle = normalize_label(&query.q);
+    if needle.is_empty() {
+        return Err(DomainError::Validation("q is required".into()));
+    }
+    if needle.chars().count() > MAX_QUERY_CHARS {
+        return Err(DomainError::Validation(format!(
+            "q must be at most {MAX_QUERY_CHARS} characters"
+        )));
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(DomainError::Validation(format!(
+            "limit must be between 1 and {MAX_LIMIT}"
+        )));
+    }
+
+    let matches = index.search(&needle, limit * CANDIDATE_FACTOR).await?;
+    let mut qids = Vec::new();
+    for item in &matches {
+        if !qids.contains(&item.qid) {
+            qids.push(item.qid.clone());
+        }
+    }
+    if qids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let community_id = query
+        .community_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|community_id| !community_id.is_empty());
+    let usage = match community_id {
+        Some(community_id) => repo
+            .concept_usage_counts(community_id, &qids)
+            .await?
+            .into_iter()
+            .map(|usage| (usage.qid, usage.note_count))
+            .collect(),
+        None => HashMap::new(),
+    };
+    // Merged-away QIDs resolve to their survivor here; only concepts that
+    // still exist under the matched QID are suggested.
+    let concepts = repo
+        .get_concepts_by_qids(&qids)
+        .await?
+        .into_iter()
+        .map(|concept| (concept.qid.clone(), concept))
+        .collect::<HashMap<_, _>>();
+
+    let lang = query
+        .lang
+        .as_deref()
+        .map(|lang| lang.trim().to_ascii_lowercase());
+    let mut suggestions = rank_suggestions(matches, &usage, lang.as_deref());
+    suggestions.retain_mut(|suggestion| match concepts.get(&suggestion.qid) {
+        Some(concept) => {
+            suggestion.label_id = concept.label_id.clone();
+            suggestion.label_en = concept.label_en.clone();
+            true
+        }
+        None => false,
+    });
+    suggestions.truncate(limit);
+    Ok(suggestions)
+}
+
+/// Keeps the best match per concept and orders concepts best first. Display
+/// labels are left empty for the caller to fill in.
+pub fn rank_suggestions(
+    matches: Vec<ConceptLabelMatch>,
+    usage: &HashMap<String, usize>,
+    lang: Option<&str>,
+) -> Vec<ConceptSuggestion> {
+    let other_lang = |item: &ConceptLabelMatch| lang.is_some_and(|lang| item.lang != lang);
+    let match_key = |item: &ConceptLabelMatch| {
+        (
+            item.distance,
+            other_lang(item),
+            item.alias,
+            item.label.chars().count(),
+        )
+    };
+    let mut best: HashMap<String, ConceptLabelMatch> = HashMap::new();
+    for item in matches {
+        match best.get(&item.qid) {
+            Some(current) if match_key(current) <= match_key(&item) => {}
+            _ => {
+                best.insert(item.qid.clone(), item);
+            }
+        }
+    }
+    let mut ranked = best.into_values().collect::<Vec<_>>();
+    ranked.sort_by(|left, right| {
+        let usage_of = |item: &ConceptLabelMatch| usage.get(&item.qid).copied().unwrap_or(0);
+        left.distance
+            .cmp(&right.distance)
+            .then_with(|| usage_of(right).cmp(&usage_of(left)))
+            .then_with(|| match_key(left).cmp(&match_key(right)))
+            .then_with(|| left.qid.cmp(&right.qid))
+    });
+    ranked
+        .into_iter()
+        .map(|item| ConceptSuggestion {
+            community_note_count: usage.get(&item.qid).copied().unwrap_or(0),
+            qid: item.qid,
+            label_id: None,
+            label_en: None,
+            matched_label: item.label,
+            matched_lang: item.lang,
+            matched_alias: item.alias,
+            distance: item.distance,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label_match(
+        qid: &str,
+        label: &str,
+        lang: &str,
+        alias: bool,
+        distance: usize,
+    ) -> ConceptLabelMatch {
+        ConceptLabelMatch {
+            qid: qid.to_string(),
+            label: label.to_string(),
+            lang: lang.to_string(),
+            alias,
+            distance,
+        }
+    }
+
+    #[test]
+    fn normalize_label_folds_case_diacritics_and_punctuation() {
+        assert_eq!(normalize_label("  Banjir   Bandang! "), "banjir bandang");
+        assert_eq!(normalize_label("Lèmah longsor"), "lemah longsor");
+        assert_eq!(normalize_label("Jum'at"), "jumat");
+        assert_eq!(normalize_label("--"), "");
+    }
+
+    #[test]
+    fn normalize_concept_labels_validates_and_dedupes() {
+        let labels = normalize_concept_labels(&[
+            OntologyConceptLabel {
+                lang: "JV".to_string(),
+                label: " Banjir ".to_string(),
+                alias: false,
+            },
+            OntologyConceptLabel {
+                lang: "jv".to_string(),
+                label: "banjir".to_string(),
+                alias: true,
+            },
+            OntologyConceptLabel {
+                lang: "su".to_string(),
+                label: "Caah".to_string(),
+                alias: false,
+            },
+        ])
+        .expect("valid labels");
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels[0].lang, "jv");
+        assert_eq!(labels[0].label, "Banjir");
+
+        let invalid_lang = normalize_concept_labels(&[OntologyConceptLabel {
+            lang: "jv_ID".to_string(),
+            label: "Banjir".to_string(),
+            alias: false,
+        }]);
+        assert!(matches!(invalid_lang, Err(DomainError::Validation(_))));
+        let empty = normalize_concept_labels(&[OntologyConceptLabel {
+            lang: "id".to_string(),
+            label: " ?! ".to_string(),
+            alias: false,
+        }]);
+        assert!(matches!(empty, Err(DomainError::Validation(_))));
+    }
+
+    #[test]
+    fn rank_suggestions_prefers_close_matches_then_community_usage() {
+        let usage = HashMap::from([("Q2".to_string(), 4usize)]);
+        let ranked = rank_suggestions(
+            vec![
+                label_match("Q1", "banjir", "id", false, 0),
+                label_match("Q2", "banjir bandang", "id", false, 0),
+                label_match("Q2", "banjir gede", "su", true, 0),
+                label_match("Q3", "banyu", "jv", false, 1),
+            ],
+            &usage,
+            Some("su"),
+        );
+        let qids = ranked
+            .iter()
+            .map(|item| item.qid.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(qids, vec!["Q2", "Q1", "Q3"]);
+        // The caller's language wins over a preferred label in another one.
+        assert_eq!(ranked[0].matched_label, "banjir gede");
+        assert_eq!(ranked[0].community_note_count, 4);
+        assert_eq!(ranked[2].distance, 1);
+    }
+}
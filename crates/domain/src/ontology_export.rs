@@ -0,0 +1,813 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::DomainResult;
+use crate::error::DomainError;
+use crate::ontology::{
+    ActionType, OntologyActionRef, OntologyConcept, OntologyEdgeKind, OntologyNote,
+    OntologyPlaceRef, OntologyTripleCreate,
+};
+use crate::ports::ontology::OntologyRepository;
+
+pub const SKOS_NS: &str = "http://www.w3.org/2004/02/skos/core#";
+pub const SCHEMA_NS: &str = "https://schema.org/";
+pub const WIKIDATA_ENTITY_NS: &str = "http://www.wikidata.org/entity/";
+pub const XSD_NS: &str = "http://www.w3.org/2001/XMLSchema#";
+pub const MAX_RAHASIA_LEVEL: i64 = 3;
+pub const DEFAULT_EXPORT_PAGE_SIZE: usize = 200;
+pub const MAX_EXPORT_PAGE_SIZE: usize = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OntologyExportFormat {
+    Turtle,
+    JsonLd,
+}
+
+impl OntologyExportFormat {
+    pub fn parse(value: &str) -> DomainResult<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "turtle" | "ttl" => Ok(Self::Turtle),
+            "jsonld" | "json-ld" | "json_ld" => Ok(Self::JsonLd),
+            other => Err(DomainError::Validation(format!(
+                "unsupported export format '{other}', expected turtle or jsonld"
+            ))),
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Turtle => "text/turtle; charset=utf-8",
+            Self::JsonLd => "application/ld+json",
+        }
+    }
+
+    pub fn file_extension(self) -> &'static str {
+        match self {
+            Self::Turtle => "ttl",
+            Self::JsonLd => "jsonld",
+        }
+    }
+}
+
+/// Which notes may leave the community. The default is the public export:
+/// `rahasia_level` 0 and `ai_readable` only.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OntologyExportFilter {
+    pub community_id: Option<String>,
+    pub max_rahasia_level: i64,
+    pub ai_readable_only: bool,
+    /// Page cursor: only notes whose `note_id` sorts after it.
+    pub after_note_id: Option<String>,
+    /// Notes per page; `None` exports every note and is left to the worker CLI.
+    pub limit: Option<usize>,
+}
+
+impl Default for OntologyExportFilter {
+    fn default() -> Self {
+        Self {
+            community_id: None,
+            max_rahasia_level: 0,
+            ai_readable_only: true,
+            after_note_id: None,
+            limit: None,
+        }
+    }
+}
+
+impl OntologyExportFilter {
+    pub fn validate(&self) -> DomainResult<()> {
+        if !(0..=MAX_RAHASIA_LEVEL).contains(&self.max_rahasia_level) {
+            return Err(DomainError::Validation(format!(
+                "max_rahasia_level must be between 0 and {MAX_RAHASIA_LEVEL}"
+            )));
+        }
+        if self
+            .community_id
+            .as_deref()
+            .is_some_and(|community_id| community_id.trim().is_empty())
+        {
+            return Err(DomainError::Validation(
+                "community_id must not be empty".into(),
+            ));
+        }
+        if self
+            .limit
+            .is_some_and(|limit| !(1..=MAX_EXPORT_PAGE_SIZE).contains(&limit))
+        {
+            return Err(DomainError::Validation(format!(
+                "limit must be between 1 and {MAX_EXPORT_PAGE_SIZE}"
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn is_paged(&self) -> bool {
+        self.limit.is_some()
+    }
+
+    pub fn allows(&self, note: &OntologyNote, now_ms: i64) -> bool {
+        note.rahasia_level >= 0
+            && note.rahasia_level <= self.max_rahasia_level
+            && (note.ai_readable || !self.ai_readable_only)
+            && !note.is_retracted()
+            && note
+                .ttl_expires_ms
+                .is_none_or(|expires_ms| expires_ms > now_ms)
+            && self
+                .community_id
+                .as_deref()
+                .is_none_or(|community_id| note.community_id == community_id.trim())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OntologyBroaderEdge {
+    pub narrower_qid: String,
+    pub broader_qid: String,
+}
+
+/// Everything one export serializes. Repositories fill concepts, edges, notes
+/// and note triples; [`load_ontology_export`] adds action and place labels.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OntologyExportGraph {
+    pub concepts: Vec<OntologyConcept>,
+    pub broader_edges: Vec<OntologyBroaderEdge>,
+    pub notes: Vec<OntologyNote>,
+    pub triples: Vec<OntologyTripleCreate>,
+    pub actions: Vec<OntologyActionRef>,
+    pub places: Vec<OntologyPlaceRef>,
+    /// Set on paged exports when more notes follow this page.
+    pub next_cursor: Option<String>,
+}
+
+impl OntologyExportGraph {
+    /// Drops notes the filter rejects and every triple that does not start at a
+    /// kept note. Vouch and challenge edges name members, so they never export.
+    pub fn retain_exportable(&mut self, filter: &OntologyExportFilter, now_ms: i64) {
+        self.notes.retain(|note| filter.allows(note, now_ms));
+        let note_ids = self
+            .notes
+            .iter()
+            .map(|note| note.note_id.as_str())
+            .collect::<HashSet<_>>();
+        self.triples.retain(|triple| {
+            edge_predicate(&triple.edge).is_some()
+                && note_record_id(&triple.from_id).is_some_and(|id| note_ids.contains(id))
+        });
+    }
+}
+
+/// Reads the graph from `repo` and applies `filter` again on this side, so a
+/// repository that over-fetches still cannot leak a note.
+pub async fn load_ontology_export(
+    repo: &dyn OntologyRepository,
+    filter: &OntologyExportFilter,
+    now_ms: i64,
+) -> DomainResult<OntologyExportGraph> {
+    filter.validate()?;
+    let mut graph = repo.export_graph(filter).await?;
+    if let Some(limit) = filter.limit {
+        // The repository reads one note past the page to tell whether more follow.
+        graph
+            .notes
+            .sort_by(|left, right| left.note_id.cmp(&right.note_id));
+        if graph.notes.len() > limit {
+            graph.notes.truncate(limit);
+            graph.next_cursor = graph.notes.last().map(|note| note.note_id.clone());
+        }
+    }
+    graph.retain_exportable(filter, now_ms);
+
+    let action_types = ActionType::ALL
+        .iter()
+        .map(|action| action.as_str().to_string())
+        .collect::<Vec<_>>();
+    graph.actions = repo.get_actions_by_types(&action_types).await?;
+
+    let mut place_ids = graph
+        .triples
+        .iter()
+        .filter(|triple| triple.edge == OntologyEdgeKind::LocatedAt)
+        .map(|triple| triple.to_id.trim().to_string())
+        .filter(|id| id.starts_with("place:"))
+        .collect::<Vec<_>>();
+    place_ids.sort();
+    place_ids.dedup();
+    if !place_ids.is_empty() {
+        graph.places = repo.get_places_by_ids(&place_ids).await?;
+    }
+    Ok(graph)
+}
+
+pub fn render_ontology_export(
+    graph: &OntologyExportGraph,
+    format: OntologyExportFormat,
+    base_iri: &str,
+) -> String {
+    let iris = ExportIris::new(base_iri);
+    let subjects = build_subjects(graph, &iris);
+    match format {
+        OntologyExportFormat::Turtle => render_turtle(&subjects, &iris),
+        OntologyExportFormat::JsonLd => {
+            let document = render_json_ld(&subjects, &iris);
+            serde_json::to_string_pretty(&document).unwrap_or_else(|_| "{}".to_string())
+        }
+    }
+}
+
+fn edge_predicate(edge: &OntologyEdgeKind) -> Option<&'static str> {
+    match edge {
+        OntologyEdgeKind::About => Some("schema:about"),
+        OntologyEdgeKind::LocatedAt => Some("schema:contentLocation"),
+        OntologyEdgeKind::HasAction => Some("gotong:hasAction"),
+        OntologyEdgeKind::InstanceOf => Some("gotong:instanceOf"),
+        OntologyEdgeKind::Broader | OntologyEdgeKind::Vouches | OntologyEdgeKind::Challenges => {
+            None
+        }
+    }
+}
+
+/// `note:abc` or bare `abc`; any other table is not a note.
+fn note_record_id(raw: &str) -> Option<&str> {
+    let raw = raw.trim();
+    match raw.split_once(':') {
+        Some(("note", id)) => Some(id),
+        Some(_) => None,
+        None => Some(raw),
+    }
+}
+
+fn is_qid(value: &str) -> bool {
+    value
+        .strip_prefix('Q')
+        .is_some_and(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Stable IRIs: local resources live under `{base}{table}/{id}`, terms under
+/// `{base}vocab#`.
+struct ExportIris {
+    base: String,
+    prefixes: [(&'static str, String); 5],
+}
+
+impl ExportIris {
+    fn new(base_iri: &str) -> Self {
+        let base = base_iri.trim().to_string();
+        let vocab = format!("{base}vocab#");
+        Self {
+            prefixes: [
+                ("skos", SKOS_NS.to_string()),
+                ("schema", SCHEMA_NS.to_string()),
+                ("wd", WIKIDATA_ENTITY_NS.to_string()),
+                ("xsd", XSD_NS.to_string()),
+                ("gotong", vocab),
+            ],
+            base,
+        }
+    }
+
+    fn scheme(&self) -> String {
+        format!("{}scheme", self.base)
+    }
+
+    fn resource(&self, table: &str, id: &str) -> String {
+        format!("{}{}/{}", self.base, table, iri_segment(id))
+    }
+
+    /// Record ids as stored on triples: `table:id`, or a bare concept id.
+    fn record(&self, raw: &str) -> String {
+        match raw.trim().split_once(':') {
+            Some((table, id)) => self.resource(table, id),
+            None => self.resource("concept", raw.trim()),
+        }
+    }
+
+    /// `prefix:term` → full IRI; anything else is already one.
+    fn expand(&self, term: &str) -> String {
+        if let Some((prefix, local)) = term.split_once(':')
+            && let Some((_, namespace)) = self.prefixes.iter().find(|(name, _)| *name == prefix)
+        {
+            return format!("{namespace}{local}");
+        }
+        term.to_string()
+    }
+
+    fn compact(&self, iri: &str) -> Option<String> {
+        self.prefixes.iter().find_map(|(prefix, namespace)| {
+            let local = iri.strip_prefix(namespace.as_str())?;
+            (!local.is_empty()
+                && local
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'_'))
+            .then(|| format!("{prefix}:{local}"))
+        })
+    }
+}
+
+fn iri_segment(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum RdfObject {
+    Iri(String),
+    Text(String),
+    LangText(String, &'static str),
+    Typed(String, &'static str),
+}
+
+struct RdfSubject {
+    iri: String,
+    types: Vec<&'static str>,
+    properties: Vec<(&'static str, RdfObject)>,
+}
+
+fn datetime_literal(ms: i64) -> Option<RdfObject> {
+    let datetime = OffsetDateTime::from_unix_timestamp_nanos(i128::from(ms) * 1_000_000).ok()?;
+    let formatted = datetime.format(&Rfc3339).ok()?;
+    Some(RdfObject::Typed(formatted, "xsd:dateTime"))
+}
+
+fn build_subjects(graph: &OntologyExportGraph, iris: &ExportIris) -> Vec<RdfSubject> {
+    let scheme = iris.scheme();
+    let mut subjects = vec![RdfSubject {
+        iri: scheme.clone(),
+        types: vec!["skos:ConceptScheme"],
+        properties: vec![],
+    }];
+
+    let mut broader_by_qid: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for edge in &graph.broader_edges {
+        let targets = broader_by_qid
+            .entry(edge.narrower_qid.as_str())
+            .or_default();
+        if !targets.contains(&edge.broader_qid.as_str()) {
+            targets.push(edge.broader_qid.as_str());
+        }
+    }
+    let mut concepts = graph.concepts.iter().collect::<Vec<_>>();
+    concepts.sort_by(|left, right| left.qid.cmp(&right.qid));
+    concepts.dedup_by(|left, right| left.qid == right.qid);
+    for concept in concepts {
+        let mut properties = vec![("skos:inScheme", RdfObject::Iri(scheme.clone()))];
+        if let Some(label) = concept.label_id.as_deref() {
+            properties.push(("skos:prefLabel", RdfObject::LangText(label.into(), "id")));
+        }
+        if let Some(label) = concept.label_en.as_deref() {
+            properties.push(("skos:prefLabel", RdfObject::LangText(label.into(), "en")));
+        }
+        if is_qid(&concept.qid) {
+            properties.push((
+                "skos:exactMatch",
+                RdfObject::Iri(format!("{WIKIDATA_ENTITY_NS}{}", concept.qid)),
+            ));
+        }
+        let mut broader = broader_by_qid
+            .get(concept.qid.as_str())
+            .cloned()
+            .unwrap_or_default();
+        broader.sort_unstable();
+        for qid in broader {
+            properties.push((
+                "skos:broader",
+                RdfObject::Iri(iris.resource("concept", qid)),
+            ));
+        }
+        properties.push((
+            "gotong:verified",
+            RdfObject::Typed(concept.verified.to_string(), "xsd:boolean"),
+        ));
+        subjects.push(RdfSubject {
+            iri: iris.resource("concept", &concept.qid),
+            types: vec!["skos:Concept"],
+            properties,
+        });
+    }
+
+    let mut actions = graph.actions.iter().collect::<Vec<_>>();
+    actions.sort_by(|left, right| left.action_type.cmp(&right.action_type));
+    for action in actions {
+        let name = action
+            .action_type
+            .strip_prefix("schema:")
+            .unwrap_or(&action.action_type);
+        let mut properties = vec![
+            ("skos:inScheme", RdfObject::Iri(scheme.clone())),
+            (
+                "skos:exactMatch",
+                RdfObject::Iri(iris.expand(&action.action_type)),
+            ),
+            (
+                "gotong:mapsToMode",
+                RdfObject::Text(action.maps_to_mode.clone()),
+            ),
+        ];
+        if let Some(label) = action.display_label.as_deref() {
+            properties.insert(
+                1,
+                ("skos:prefLabel", RdfObject::LangText(label.into(), "id")),
+            );
+        }
+        subjects.push(RdfSubject {
+            iri: iris.resource("action", name),
+            types: vec!["skos:Concept"],
+            properties,
+        });
+    }
+
+    let mut places = graph.places.iter().collect::<Vec<_>>();
+    places.sort_by(|left, right| left.place_id.cmp(&right.place_id));
+    for place in places {
+        subjects.push(RdfSubject {
+            iri: iris.record(&place.place_id),
+            types: vec!["schema:Place"],
+            properties: vec![("schema:name", RdfObject::Text(place.name.clone()))],
+        });
+    }
+
+    let mut triples_by_note: BTreeMap<&str, Vec<&OntologyTripleCreate>> = BTreeMap::new();
+    for triple in &graph.triples {
+        if let Some(note_id) = note_record_id(&triple.from_id) {
+            triples_by_note.entry(note_id).or_default().push(triple);
+        }
+    }
+    let mut notes = graph.notes.iter().collect::<Vec<_>>();
+    notes.sort_by(|left, right| left.note_id.cmp(&right.note_id));
+    for note in notes {
+        let mut properties = vec![
+            ("schema:text", RdfObject::Text(note.content.clone())),
+            (
+                "gotong:community",
+                RdfObject::Text(note.community_id.clone()),
+            ),
+            (
+                "gotong:temporalClass",
+                RdfObject::Text(note.temporal_class.clone()),
+            ),
+            (
+                "gotong:rahasiaLevel",
+                RdfObject::Typed(note.rahasia_level.to_string(), "xsd:integer"),
+            ),
+            (
+                "gotong:confidence",
+                RdfObject::Typed(note.confidence.to_string(), "xsd:double"),
+            ),
+        ];
+        if let Some(created) = datetime_literal(note.created_at_ms) {
+            properties.push(("schema:dateCreated", created));
+        }
+        if let Some(expires) = note.ttl_expires_ms.and_then(datetime_literal) {
+            properties.push(("schema:expires", expires));
+        }
+        let mut links = triples_by_note
+            .get(note.note_id.as_str())
+            .into_iter()
+            .flatten()
+            .filter_map(|triple| {
+                let predicate = edge_predicate(&triple.edge)?;
+                Some((predicate, iris.record(&triple.to_id)))
+            })
+            .collect::<Vec<_>>();
+        links.sort();
+        links.dedup();
+        properties.extend(
+            links
+                .into_iter()
+                .map(|(predicate, target)| (predicate, RdfObject::Iri(target))),
+        );
+        subjects.push(RdfSubject {
+            iri: iris.resource("note", &note.note_id),
+            types: vec!["schema:CreativeWork"],
+            properties,
+        });
+    }
+    subjects
+}
+
+fn turtle_iri(iris: &ExportIris, iri: &str) -> String {
+    iris.compact(iri).unwrap_or_else(|| format!("<{iri}>"))
+}
+
+fn turtle_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            ch if ch.is_control() => {
+                let _ = write!(escaped, "\\u{:04X}", ch as u32);
+            }
+            ch => escaped.push(ch),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn render_turtle(subjects: &[RdfSubject], iris: &ExportIris) -> String {
+    let mut out = String::new();
+    for (prefix, namespace) in &iris.prefixes {
+        let _ = writeln!(out, "@prefix {prefix}: <{namespace}> .");
+    }
+    for subject in subjects {
+        out.push('\n');
+        let mut lines = subject
+            .types
+            .iter()
+            .map(|rdf_type| format!("a {rdf_type}"))
+            .collect::<Vec<_>>();
+        for (predicate, object) in &subject.properties {
+            let object = match object {
+                RdfObject::Iri(iri) => turtle_iri(iris, iri),
+                RdfObject::Text(value) => turtle_string(value),
+                RdfObject::LangText(value, lang) => format!("{}@{lang}", turtle_string(value)),
+                RdfObject::Typed(value, datatype) => {
+                    format!("{}^^{datatype}", turtle_string(value))
+                }
+            };
+            lines.push(format!("{predicate} {object}"));
+        }
+        let _ = writeln!(
+            out,
+            "{} {} .",
+            turtle_iri(iris, &subject.iri),
+            lines.join(" ;\n    ")
+        );
+    }
+    out
+}
+
+fn render_json_ld(subjects: &[RdfSubject], iris: &ExportIris) -> Value {
+    let context = iris
+        .prefixes
+        .iter()
+        .map(|(prefix, namespace)| (prefix.to_string(), Value::String(namespace.clone())))
+        .collect::<Map<_, _>>();
+    let compact = |iri: &str| iris.compact(iri).unwrap_or_else(|| iri.to_string());
+    let graph = subjects
+        .iter()
+        .map(|subject| {
+            let mut node = Map::new();
+            node.insert("@id".into(), Value::String(compact(&subject.iri)));
+            node.insert("@type".into(), json!(subject.types));
+            for (predicate, object) in &subject.properties {
+                let value = match object {
+                    RdfObject::Iri(iri) => json!({ "@id": compact(iri) }),
+                    RdfObject::Text(value) => json!({ "@value": value }),
+                    RdfObject::LangText(value, lang) => {
+                        json!({ "@value": value, "@language": lang })
+                    }
+                    RdfObject::Typed(value, datatype) => {
+                        json!({ "@value": value, "@type": datatype })
+                    }
+                };
+                match node
+                    .entry(predicate.to_string())
+                    .or_insert_with(|| Value::Array(Vec::new()))
+                {
+                    Value::Array(values) => values.push(value),
+                    _ => unreachable!("properties are always arrays"),
+                }
+            }
+            Value::Object(node)
+        })
+        .collect::<Vec<_>>();
+    json!({ "@context": context, "@graph": graph })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://gotong-royong.app/ontology/";
+
+    fn note(note_id: &str, rahasia_level: i64, ai_readable: bool) -> OntologyNote {
+        OntologyNote {
+            note_id: note_id.to_string(),
+            content: format!("Banjir \"setinggi\" lutut di {note_id}"),
+            author_id: "warga-rahasia".to_string(),
+            community_id: "rw-07".to_string(),
+            temporal_class: "persistent".to_string(),
+            ttl_expires_ms: None,
+            ai_readable,
+            rahasia_level,
+            confidence: 0.75,
+            created_at_ms: 1_700_000_000_000,
+            version: 1,
+            edited_at_ms: None,
+            retracted_at_ms: None,
+            retraction_reason: None,
+        }
+    }
+
+    fn triple(edge: OntologyEdgeKind, from_id: &str, to_id: &str) -> OntologyTripleCreate {
+        OntologyTripleCreate {
+            edge,
+            from_id: from_id.to_string(),
+            to_id: to_id.to_string(),
+            predicate: None,
+            metadata: None,
+        }
+    }
+
+    fn graph() -> OntologyExportGraph {
+        OntologyExportGraph {
+            concepts: vec![
+                OntologyConcept {
+                    concept_id: "Q8068".to_string(),
+                    qid: "Q8068".to_string(),
+                    label_id: Some("banjir".to_string()),
+                    label_en: Some("flood".to_string()),
+                    verified: true,
+                },
+                OntologyConcept {
+                    concept_id: "Q8065".to_string(),
+                    qid: "Q8065".to_string(),
+                    label_id: Some("bencana alam".to_string()),
+                    label_en: None,
+                    verified: false,
+                },
+            ],
+            broader_edges: vec![OntologyBroaderEdge {
+                narrower_qid: "Q8068".to_string(),
+                broader_qid: "Q8065".to_string(),
+            }],
+            notes: vec![
+                note("public-1", 0, true),
+                note("secret-1", 2, true),
+                note("no-ai-1", 0, false),
+            ],
+            triples: vec![
+                triple(OntologyEdgeKind::About, "note:public-1", "Q8068"),
+                triple(OntologyEdgeKind::LocatedAt, "note:public-1", "place:rt 03"),
+                triple(
+                    OntologyEdgeKind::HasAction,
+                    "note:public-1",
+                    "action:RepairAction",
+                ),
+                triple(OntologyEdgeKind::About, "note:secret-1", "Q8065"),
+                triple(OntologyEdgeKind::Vouches, "warga:warga-1", "note:public-1"),
+            ],
+            actions: vec![OntologyActionRef {
+                action_type: "schema:RepairAction".to_string(),
+                maps_to_mode: "komunitas".to_string(),
+                display_label: Some("Tuntaskan".to_string()),
+            }],
+            places: vec![OntologyPlaceRef {
+                place_id: "place:rt 03".to_string(),
+                name: "RT 03".to_string(),
+            }],
+            next_cursor: None,
+        }
+    }
+
+    #[test]
+    fn public_filter_drops_private_notes_and_member_edges() {
+        let mut graph = graph();
+        graph.retain_exportable(&OntologyExportFilter::default(), 0);
+        let note_ids = graph
+            .notes
+            .iter()
+            .map(|note| note.note_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(note_ids, vec!["public-1"]);
+        assert_eq!(graph.triples.len(), 3);
+        assert!(
+            graph
+                .triples
+                .iter()
+                .all(|triple| triple.from_id == "note:public-1"
+                    && triple.edge != OntologyEdgeKind::Vouches)
+        );
+
+        let turtle = render_ontology_export(&graph, OntologyExportFormat::Turtle, BASE);
+        assert!(!turtle.contains("secret-1"));
+        assert!(!turtle.contains("warga"));
+        assert!(!turtle.contains("no-ai-1"));
+    }
+
+    #[test]
+    fn turtle_uses_stable_iris_and_skos_terms() {
+        let mut graph = graph();
+        graph.retain_exportable(&OntologyExportFilter::default(), 0);
+        let turtle = render_ontology_export(&graph, OntologyExportFormat::Turtle, BASE);
+
+        assert!(turtle.contains("@prefix skos: <http://www.w3.org/2004/02/skos/core#> ."));
+        assert!(turtle.contains(
+            "<https://gotong-royong.app/ontology/concept/Q8068> a skos:Concept ;\n    \
+             skos:inScheme <https://gotong-royong.app/ontology/scheme> ;\n    \
+             skos:prefLabel \"banjir\"@id ;\n    \
+             skos:prefLabel \"flood\"@en ;\n    \
+             skos:exactMatch wd:Q8068 ;\n    \
+             skos:broader <https://gotong-royong.app/ontology/concept/Q8065> ;\n    \
+             gotong:verified \"true\"^^xsd:boolean ."
+        ));
+        assert!(turtle.contains("skos:exactMatch schema:RepairAction"));
+        assert!(turtle.contains("gotong:mapsToMode \"komunitas\""));
+        assert!(turtle.contains("schema:about <https://gotong-royong.app/ontology/concept/Q8068>"));
+        assert!(
+            turtle.contains(
+                "schema:contentLocation <https://gotong-royong.app/ontology/place/rt%2003>"
+            )
+        );
+        assert!(
+            turtle.contains(
+                "gotong:hasAction <https://gotong-royong.app/ontology/action/RepairAction>"
+            )
+        );
+        assert!(turtle.contains("schema:text \"Banjir \\\"setinggi\\\" lutut di public-1\""));
+        assert!(turtle.contains("\"2023-11-14T22:13:20Z\"^^xsd:dateTime"));
+    }
+
+    #[test]
+    fn json_ld_carries_the_same_graph() {
+        let mut graph = graph();
+        graph.retain_exportable(&OntologyExportFilter::default(), 0);
+        let rendered = render_ontology_export(&graph, OntologyExportFormat::JsonLd, BASE);
+        let document: Value = serde_json::from_str(&rendered).expect("json");
+
+        assert_eq!(
+            document["@context"]["gotong"],
+            "https://gotong-royong.app/ontology/vocab#"
+        );
+        let nodes = document["@graph"].as_array().expect("graph");
+        let concept = nodes
+            .iter()
+            .find(|node| node["@id"] == "https://gotong-royong.app/ontology/concept/Q8068")
+            .expect("concept node");
+        assert_eq!(concept["@type"], json!(["skos:Concept"]));
+        assert_eq!(concept["skos:exactMatch"], json!([{ "@id": "wd:Q8068" }]));
+        assert_eq!(
+            concept["skos:prefLabel"],
+            json!([
+                { "@value": "banjir", "@language": "id" },
+                { "@value": "flood", "@language": "en" }
+            ])
+        );
+        let note = nodes
+            .iter()
+            .find(|node| node["@type"] == json!(["schema:CreativeWork"]))
+            .expect("note node");
+        assert_eq!(
+            note["schema:about"],
+            json!([{ "@id": "https://gotong-royong.app/ontology/concept/Q8068" }])
+        );
+        assert!(!rendered.contains("secret-1"));
+    }
+
+    #[test]
+    fn filter_validates_and_respects_scope_and_expiry() {
+        assert!(
+            OntologyExportFilter {
+                max_rahasia_level: 4,
+                ..OntologyExportFilter::default()
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            OntologyExportFilter {
+                limit: Some(MAX_EXPORT_PAGE_SIZE + 1),
+                ..OntologyExportFilter::default()
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(OntologyExportFormat::parse("xml").is_err());
+        assert_eq!(
+            OntologyExportFormat::parse("TTL").unwrap(),
+            OntologyExportFormat::Turtle
+        );
+
+        let filter = OntologyExportFilter {
+            community_id: Some("rw-08".to_string()),
+            ..OntologyExportFilter::default()
+        };
+        assert!(!filter.allows(&note("public-1", 0, true), 0));
+        let mut ephemeral = note("public-1", 0, true);
+        ephemeral.ttl_expires_ms = Some(10);
+        assert!(!OntologyExportFilter::default().allows(&ephemeral, 10));
+        assert!(OntologyExportFilter::default().allows(&ephemeral, 9));
+        let mut retracted = note("public-1", 0, true);
+        retracted.retracted_at_ms = Some(5);
+        assert!(!OntologyExportFilter::default().allows(&retracted, 9));
+    }
+}
@@ -0,0 +1,492 @@
+use serde::{Deserialize, Serialize};
+
+use crate::DomainResult;
+use crate::error::DomainError;
+use crate::geo::{GeoPoint, GeoRadius, haversine_m};
+use crate::ontology::{OntologyEdgeKind, OntologyTripleCreate};
+use crate::ports::ontology::OntologyRepository;
+
+const MAX_NAME_LENGTH: usize = 200;
+/// Vertices accepted per area across all of its rings.
+pub const MAX_BOUNDARY_POINTS: usize = 50_000;
+pub const ADMIN_AREA_PLACE_SOURCE: &str = "kemendagri";
+const ADMIN_PLACE_ID_PREFIX: &str = "adm_";
+
+/// Tier of the Kemendagri administrative hierarchy, read off the code:
+/// `32` → `32.73` → `32.73.01` → `32.73.01.1001`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminLevel {
+    Provinsi,
+    KabupatenKota,
+    Kecamatan,
+    DesaKelurahan,
+}
+
+impl AdminLevel {
+    pub const ALL: [Self; 4] = [
+        Self::Provinsi,
+        Self::KabupatenKota,
+        Self::Kecamatan,
+        Self::DesaKelurahan,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Provinsi => "provinsi",
+            Self::KabupatenKota => "kabupaten_kota",
+            Self::Kecamatan => "kecamatan",
+            Self::DesaKelurahan => "desa_kelurahan",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|level| level.as_str() == value.trim())
+    }
+
+    fn from_segments(segments: usize) -> Option<Self> {
+        Self::ALL.get(segments.checked_sub(1)?).copied()
+    }
+
+    /// How far from its centroid a point may lie and still be placed in an
+    /// area that has no boundary polygon.
+    fn centroid_radius_m(self) -> Option<f64> {
+        match self {
+            Self::DesaKelurahan => Some(5_000.0),
+            Self::Kecamatan => Some(20_000.0),
+            Self::KabupatenKota | Self::Provinsi => None,
+        }
+    }
+}
+
+/// One area of the gazetteer, with a place record named by [`admin_place_id`].
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AdminArea {
+    /// Dotted Kemendagri code, e.g. `32.73.01.1001`.
+    pub code: String,
+    pub name: String,
+    pub level: AdminLevel,
+    pub parent_code: Option<String>,
+    pub centroid: GeoPoint,
+    /// Outer rings of the area's polygons; holes are not kept, so enclaves
+    /// are told apart by preferring the smaller of two containing areas.
+    pub boundary: Option<Vec<Vec<GeoPoint>>>,
+}
+
+impl AdminArea {
+    /// Builds a validated area; level and parent follow from the code.
+    pub fn new(
+        code: &str,
+        name: &str,
+        centroid: GeoPoint,
+        boundary: Option<Vec<Vec<GeoPoint>>>,
+    ) -> DomainResult<Self> {
+        let code = normalize_admin_code(code)?;
+        let area = Self {
+            level: admin_level_of(&code)?,
+            parent_code: parent_admin_code(&code),
+            code,
+            name: name.trim().to_string(),
+            centroid,
+            boundary,
+        };
+        area.validate()?;
+        Ok(area)
+    }
+
+    pub fn validate(&self) -> DomainResult<()> {
+        if normalize_admin_code(&self.code)? != self.code {
+            return Err(DomainError::Validation(format!(
+                "admin area code {} is not normalized",
+                self.code
+            )));
+        }
+        if admin_level_of(&self.code)? != self.level
+            || parent_admin_code(&self.code) != self.parent_code
+        {
+            return Err(DomainError::Validation(format!(
+                "admin area {} has a level or parent that does not match its code",
+                self.code
+            )));
+        }
+        let name_length = self.name.chars().count();
+        if self.name.trim().is_empty() || name_length > MAX_NAME_LENGTH {
+            return Err(DomainError::Validation(format!(
+                "admin area name must be between 1 and {MAX_NAME_LENGTH} characters"
+            )));
+        }
+        self.centroid.validate()?;
+        if let Some(rings) = self.boundary.as_ref() {
+            if rings.is_empty() {
+                return Err(DomainError::Validation(
+                    "boundary must hold at least one ring; omit it instead".into(),
+                ));
+            }
+            let mut points = 0usize;
+            for ring in rings {
+                if ring.len() < 3 {
+                    return Err(DomainError::Validation(
+                        "boundary rings need at least 3 points".into(),
+                    ));
+                }
+                for point in ring {
+                    point.validate()?;
+                }
+                points += ring.len();
+            }
+            if points > MAX_BOUNDARY_POINTS {
+                return Err(DomainError::Validation(format!(
+                    "boundary may hold at most {MAX_BOUNDARY_POINTS} points"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn place_id(&self) -> String {
+        admin_place_id(&self.code)
+    }
+
+    /// `(south, west, north, east)` of the boundary.
+    pub fn bounding_box(&self) -> Option<(f64, f64, f64, f64)> {
+        let mut points = self.boundary.as_ref()?.iter().flatten();
+        let first = points.next()?;
+        Some(points.fold(
+            (first.lat, first.lng, first.lat, first.lng),
+            |(south, west, north, east), point| {
+                (
+                    south.min(point.lat),
+                    west.min(point.lng),
+                    north.max(point.lat),
+                    east.max(point.lng),
+                )
+            },
+        ))
+    }
+
+    /// Longest geohash whose cell holds the whole bounding box, so a point can
+    /// only be inside when its geohash starts with it. Stores index this to
+    /// find candidate areas with one lookup per prefix of the point's geohash.
+    pub fn cover_geohash(&self) -> Option<String> {
+        let (south, west, north, east) = self.bounding_box()?;
+        let south_west = GeoPoint {
+            lat: south,
+            lng: west,
+        }
+        .geohash();
+        let north_east = GeoPoint {
+            lat: north,
+            lng: east,
+        }
+        .geohash();
+        Some(
+            south_west
+                .chars()
+                .zip(north_east.chars())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect(),
+        )
+    }
+
+    /// Even-odd test against every ring; points on an edge may fall either way.
+    pub fn contains(&self, point: &GeoPoint) -> bool {
+        self.boundary
+            .as_ref()
+            .is_some_and(|rings| rings.iter().any(|ring| ring_contains(ring, point)))
+    }
+
+    /// Planar area in square degrees; only used to compare overlapping areas.
+    fn boundary_area(&self) -> f64 {
+        self.boundary
+            .as_ref()
+            .map(|rings| rings.iter().map(|ring| ring_area(ring)).sum())
+            .unwrap_or(f64::INFINITY)
+    }
+
+    pub fn to_ref(&self) -> AdminAreaRef {
+        AdminAreaRef {
+            code: self.code.clone(),
+            name: self.name.clone(),
+            level: self.level,
+            place_id: self.place_id(),
+        }
+    }
+}
+
+/// An area without its boundary, as returned to clients.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AdminAreaRef {
+    pub code: String,
+    pub name: String,
+    pub level: AdminLevel,
+    pub place_id: String,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReverseGeocodeMethod {
+    /// The point is inside the area's boundary.
+    Boundary,
+    /// The area has no boundary; its centroid is the nearest within range.
+    NearestCentroid,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ReverseGeocodeResult {
+    pub area: AdminAreaRef,
+    /// Provinsi first, ending with `area`.
+    pub hierarchy: Vec<AdminAreaRef>,
+    pub method: ReverseGeocodeMethod,
+    /// Distance to the centroid for `nearest_centroid` matches.
+    pub distance_m: Option<f64>,
+}
+
+/// Accepts dotted Kemendagri codes, the same digits without dots
+/// (`3273011001`) and place ids (`place:adm_32_73_01_1001`).
+pub fn normalize_admin_code(raw: &str) -> DomainResult<String> {
+    let raw = raw.trim();
+    let place_code = raw
+        .strip_prefix("place:")
+        .unwrap_or(raw)
+        .strip_prefix(ADMIN_PLACE_ID_PREFIX)
+        .map(|code| code.replace('_', "."));
+    let raw = place_code.as_deref().unwrap_or(raw);
+    let invalid = || {
+        DomainError::Validation(format!(
+            "admin area code {raw:?} must look like 32, 32.73, 32.73.01 or 32.73.01.1001"
+        ))
+    };
+    let segments: Vec<&str> = if raw.contains('.') {
+        raw.split('.').collect()
+    } else {
+        match raw.len() {
+            2 => vec![&raw[..2]],
+            4 => vec![&raw[..2], &raw[2..4]],
+            6 => vec![&raw[..2], &raw[2..4], &raw[4..6]],
+            10 => vec![&raw[..2], &raw[2..4], &raw[4..6], &raw[6..10]],
+            _ => return Err(invalid()),
+        }
+    };
+    if segments.len() > 4 {
+        return Err(invalid());
+    }
+    for (index, segment) in segments.iter().enumerate() {
+        let expected = if index == 3 { 4 } else { 2 };
+        if segment.len() != expected || !segment.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+    }
+    Ok(segments.join("."))
+}
+
+/// The place record notes are `LOCATED_AT`: `place:adm_32_73_01_1001`.
+/// Dots would make SurrealDB quote the record id.
+pub fn admin_place_id(code: &str) -> String {
+    format!("place:{ADMIN_PLACE_ID_PREFIX}{}", code.replace('.', "_"))
+}
+
+pub fn parent_admin_code(code: &str) -> Option<String> {
+    code.rsplit_once('.').map(|(parent, _)| parent.to_string())
+}
+
+fn admin_level_of(code: &str) -> DomainResult<AdminLevel> {
+    AdminLevel::from_segments(code.split('.').count())
+        .ok_or_else(|| DomainError::Validation(format!("admin area code {code} has no level")))
+}
+
+/// Codes of every area above `code`, provinsi first.
+fn ancestor_codes(code: &str) -> Vec<String> {
+    let mut codes = Vec::new();
+    let mut current = parent_admin_code(code);
+    while let Some(code) = current {
+        current = parent_admin_code(&code);
+        codes.push(code);
+    }
+    codes.reverse();
+    codes
+}
+
+/// The smallest area holding `point`: the deepest level whose boundary
+/// contains it, the smaller polygon between overlapping areas of one level.
+/// Where the deepest containing area has children without boundaries, the
+/// nearest child centroid within range refines the match. `None` when the
+/// point is outside the gazetteer.
+pub async fn reverse_geocode(
+    repo: &dyn OntologyRepository,
+    point: &GeoPoint,
+) -> DomainResult<Option<ReverseGeocodeResult>> {
+    point.validate()?;
+    let containing = repo
+        .list_admin_areas_covering(&point.geohash())
+        .await?
+        .into_iter()
+        .filter(|area| area.contains(point))
+        .min_by(|a, b| {
+            b.level
+                .cmp(&a.level)
+                .then_with(|| a.boundary_area().total_cmp(&b.boundary_area()))
+                .then_with(|| a.code.cmp(&b.code))
+        });
+
+    let mut matched = containing.map(|area| (area, ReverseGeocodeMethod::Boundary, None));
+    for level in AdminLevel::ALL.into_iter().rev() {
+        if matched
+            .as_ref()
+            .is_some_and(|(area, _, _)| area.level >= level)
+        {
+            break;
+        }
+        let Some(radius_m) = level.centroid_radius_m() else {
+            continue;
+        };
+        let near = GeoRadius::new(*point, radius_m, radius_m)?;
+        let within = matched
+            .as_ref()
+            .map(|(area, _, _)| format!("{}.", area.code));
+        let nearest = repo
+            .list_admin_areas_near(&near, level)
+            .await?
+            .into_iter()
+            .filter(|area| area.boundary.is_none())
+            .filter(|area| {
+                within
+                    .as_deref()
+                    .is_none_or(|prefix| area.code.starts_with(prefix))
+            })
+            .filter_map(|area| {
+                let distance = haversine_m(point, &area.centroid);
+                (distance <= radius_m).then_some((area, distance))
+            })
+            .min_by(|(a, a_distance), (b, b_distance)| {
+                a_distance
+                    .total_cmp(b_distance)
+                    .then_with(|| a.code.cmp(&b.code))
+            });
+        if let Some((area, distance)) = nearest {
+            matched = Some((area, ReverseGeocodeMethod::NearestCentroid, Some(distance)));
+            break;
+        }
+    }
+
+    let Some((area, method, distance_m)) = matched else {
+        return Ok(None);
+    };
+    let ancestors = ancestor_codes(&area.code);
+    let mut found = repo.get_admin_areas(&ancestors).await?;
+    let mut hierarchy = Vec::with_capacity(ancestors.len() + 1);
+    for code in &ancestors {
+        // Areas missing from a partial import are skipped, not invented.
+        if let Some(index) = found.iter().position(|area| &area.code == code) {
+            hierarchy.push(found.swap_remove(index).to_ref());
+        }
+    }
+    let area = area.to_ref();
+    hierarchy.push(area.clone());
+    Ok(Some(ReverseGeocodeResult {
+        area,
+        hierarchy,
+        method,
+        distance_m,
+    }))
+}
+
+/// The `LOCATED_AT` edge tagging `from_id` (e.g. `note:<id>`) with a
+/// reverse-geocoded area.
+pub fn located_at_triple(from_id: &str, result: &ReverseGeocodeResult) -> OntologyTripleCreate {
+    OntologyTripleCreate {
+        edge: OntologyEdgeKind::LocatedAt,
+        from_id: from_id.to_string(),
+        to_id: result.area.place_id.clone(),
+        predicate: None,
+        metadata: Some(serde_json::json!({
+            "source": "gazetteer",
+            "admin_level": result.area.level,
+            "method": result.method,
+        })),
+    }
+}
+
+fn ring_contains(ring: &[GeoPoint], point: &GeoPoint) -> bool {
+    let mut inside = false;
+    let mut previous = ring[ring.len() - 1];
+    for current in ring {
+        if (current.lat > point.lat) != (previous.lat > point.lat) {
+            let crossing_lng = current.lng
+                + (point.lat - current.lat) * (previous.lng - current.lng)
+                    / (previous.lat - current.lat);
+            if point.lng < crossing_lng {
+                inside = !inside;
+            }
+        }
+        previous = *current;
+    }
+    inside
+}
+
+fn ring_area(ring: &[GeoPoint]) -> f64 {
+    let mut twice_area = 0.0;
+    let mut previous = ring[ring.len() - 1];
+    for current in ring {
+        twice_area += previous.lng * current.lat - current.lng * previous.lat;
+        previous = *current;
+    }
+    (twice_area / 2.0).abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(lat: f64, lng: f64) -> GeoPoint {
+        GeoPoint { lat, lng }
+    }
+
+    fn square(south: f64, west: f64, size: f64) -> Vec<Vec<GeoPoint>> {
+        vec![vec![
+            point(south, west),
+            point(south, west + size),
+            point(south + size, west + size),
+            point(south + size, west),
+            point(south, west),
+        ]]
+    }
+
+    #[test]
+    fn codes_normalize_to_the_dotted_form() {
+        assert_eq!(normalize_admin_code("3273011001").unwrap(), "32.73.01.1001");
+        assert_eq!(normalize_admin_code("place:adm_32_73").unwrap(), "32.73");
+        assert!(normalize_admin_code("32.7").is_err());
+        assert!(normalize_admin_code("32.73.01.1001.5").is_err());
+        assert_eq!(
+            ancestor_codes("32.73.01.1001"),
+            vec!["32", "32.73", "32.73.01"]
+        );
+
+        let desa = AdminArea::new("3273011001", "Braga", point(-6.91, 107.61), None).unwrap();
+        assert_eq!(desa.level, AdminLevel::DesaKelurahan);
+        assert_eq!(desa.parent_code.as_deref(), Some("32.73.01"));
+        assert_eq!(desa.place_id(), "place:adm_32_73_01_1001");
+        assert!(AdminArea::new("32", " ", point(0.0, 0.0), None).is_err());
+    }
+
+    #[test]
+    fn boundaries_contain_points_and_cover_their_geohash() {
+        let area = AdminArea::new(
+            "32.73",
+            "Kota Bandung",
+            point(-6.91, 107.61),
+            Some(square(-7.0, 107.5, 0.2)),
+        )
+        .unwrap();
+        let inside = point(-6.91, 107.61);
+        assert!(area.contains(&inside));
+        assert!(!area.contains(&point(-6.91, 107.75)));
+        let cover = area.cover_geohash().unwrap();
+        assert!(inside.geohash().starts_with(&cover));
+        assert!(area.contains(&point(-6.99, 107.51)));
+        assert!(point(-6.99, 107.51).geohash().starts_with(&cover));
+    }
+}
@@ -0,0 +1,74 @@
+use crate::DomainResult;
+use crate::error::DomainError;
+use crate::ontology::{OntologyConceptMergeResult, OntologyConceptRedirect};
+use crate::ports::ontology::OntologyRepository;
+
+const MAX_REASON_LENGTH: usize = 64;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConceptMergeCommand {
+    /// The losing concept; it is removed and redirected.
+    pub from_qid: String,
+    pub into_qid: String,
+    pub reason: String,
+}
+
+/// Merges `from_qid` into `into_qid`. A survivor that is itself redirected is
+/// followed first, so redirects never chain. Re-running a merge that already
+/// happened returns the current survivor with no edges moved.
+pub async fn merge_ontology_concepts(
+    repo: &dyn OntologyRepository,
+    command: &ConceptMergeCommand,
+    now_ms: i64,
+) -> DomainResult<OntologyConceptMergeResult> {
+    let from_qid = normalize_qid("from_qid", &command.from_qid)?;
+    let mut into_qid = normalize_qid("into_qid", &command.into_qid)?;
+    let reason = command.reason.trim();
+    if reason.is_empty() || reason.len() > MAX_REASON_LENGTH {
+        return Err(DomainError::Validation(format!(
+            "reason must be between 1 and {MAX_REASON_LENGTH} characters"
+        )));
+    }
+    if let Some(redirect) = repo.get_concept_redirect(&into_qid).await? {
+        into_qid = redirect.to_qid;
+    }
+    if from_qid == into_qid {
+        return Err(DomainError::Validation(
+            "cannot merge a concept into itself".into(),
+        ));
+    }
+
+    if let Some(existing) = repo.get_concept_redirect(&from_qid).await? {
+        if existing.to_qid != into_qid {
+            return Err(DomainError::Conflict);
+        }
+        let surviving = repo
+            .get_concept_by_qid(&into_qid)
+            .await?
+            .ok_or(DomainError::NotFound)?;
+        return Ok(OntologyConceptMergeResult {
+            surviving,
+            redirect: existing,
+            moved_edge_count: 0,
+        });
+    }
+
+    repo.merge_concepts(&OntologyConceptRedirect {
+        from_qid,
+        to_qid: into_qid,
+        reason: reason.to_string(),
+        created_at_ms: now_ms,
+    })
+    .await
+}
+
+fn normalize_qid(field: &str, value: &str) -> DomainResult<String> {
+    let value = value.trim();
+    let qid = value.strip_prefix("concept:").unwrap_or(value);
+    if qid.is_empty() || qid.contains(':') {
+        return Err(DomainError::Validation(format!(
+            "{field} must be a concept QID"
+        )));
+    }
+    Ok(qid.to_string())
+}
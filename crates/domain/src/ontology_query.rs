@@ -0,0 +1,730 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::DomainResult;
+use crate::error::DomainError;
+use crate::ontology::{ActionType, OntologyNote};
+use crate::ontology_gazetteer::{admin_place_id, normalize_admin_code};
+use crate::ports::ontology::OntologyRepository;
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 100;
+pub const DEFAULT_NARROWER_DEPTH: usize = 2;
+pub const MAX_NARROWER_DEPTH: usize = 5;
+/// Upper bound on concepts an `about` facet may expand to; deeper levels are
+/// dropped and the page says so.
+pub const MAX_EXPANDED_CONCEPTS: usize = 500;
+/// Values accepted per facet, before narrower expansion.
+pub const MAX_FACET_VALUES: usize = 20;
+
+/// Graph-pattern query over notes: values inside one facet are alternatives,
+/// facets combine with AND. At least one facet is required.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OntologyGraphQuery {
+    /// Concept QIDs the note is `ABOUT`.
+    pub about: Vec<String>,
+    /// `BROADER` levels walked downwards from `about`; 0 matches only the
+    /// named concepts.
+    pub narrower_depth: Option<usize>,
+    /// Place ids the note is `LOCATED_AT`.
+    pub located_at: Vec<String>,
+    /// Action types the note `HAS_ACTION`, with or without the `schema:` prefix.
+    pub has_action: Vec<String>,
+    pub community_id: Option<String>,
+    /// Inclusive window on note creation time.
+    pub from_ms: Option<i64>,
+    pub to_ms: Option<i64>,
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// What a repository matches. Facet values are normalized: bare QIDs,
+/// `place:<id>` and `schema:<Action>`; an empty list leaves the facet open.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OntologyNoteQuery {
+    pub viewer_id: String,
+    pub concept_qids: Vec<String>,
+    pub place_ids: Vec<String>,
+    pub action_types: Vec<String>,
+    pub community_id: Option<String>,
+    pub from_ms: Option<i64>,
+    pub to_ms: Option<i64>,
+    pub now_ms: i64,
+    pub cursor_created_at_ms: Option<i64>,
+    pub cursor_note_id: Option<String>,
+    pub limit: usize,
+}
+
+/// A matched note with its facet targets, in the same normalized shapes.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct OntologyNoteMatch {
+    pub note: OntologyNote,
+    pub about: Vec<String>,
+    pub located_at: Vec<String>,
+    pub has_action: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct OntologyGraphPage {
+    pub items: Vec<OntologyNoteMatch>,
+    pub next_cursor: Option<String>,
+    /// The `about` concepts after narrower expansion.
+    pub expanded_concept_qids: Vec<String>,
+    pub expansion_truncated: bool,
+}
+
+/// Public notes, plus the viewer's own at any `rahasia_level`, until they expire.
+pub fn note_visible_to(note: &OntologyNote, viewer_id: &str, now_ms: i64) -> bool {
+    (note.rahasia_level == 0 || (!viewer_id.is_empty() && note.author_id == viewer_id))
+        && note
+            .ttl_expires_ms
+            .is_none_or(|expires_ms| expires_ms > now_ms)
+}
+
+pub async fn query_ontology_graph(
+    repo: &dyn OntologyRepository,
+    viewer_id: &str,
+    query: &OntologyGraphQuery,
+    now_ms: i64,
+) -> DomainResult<OntologyGraphPage> {
+    let viewer_id = viewer_id.trim();
+    if viewer_id.is_empty() {
+        return Err(DomainError::Validation("viewer_id is required".into()));
+    }
+    let limit = normalize_limit(query.limit)?;
+    let narrower_depth = query.narrower_depth.unwrap_or(DEFAULT_NARROWER_DEPTH);
+    if narrower_depth > MAX_NARROWER_DEPTH {
+        return Err(DomainError::Validation(format!(
+            "narrower_depth must be between 0 and {MAX_NARROWER_DEPTH}"
+        )));
+    }
+    let about = normalize_facet("about", &query.about, normalize_concept_qid)?;
+    let place_ids = normalize_facet("located_at", &query.located_at, normalize_place_id)?;
+    let action_types = normalize_facet("has_action", &query.has_action, normalize_action_type)?;
+    if about.is_empty() && place_ids.is_empty() && action_types.is_empty() {
+        return Err(DomainError::Validation(
+            "at least one of about, located_at or has_action is required".into(),
+        ));
+    }
+    if let (Some(from_ms), Some(to_ms)) = (query.from_ms, query.to_ms)
+        && from_ms > to_ms
+    {
+        return Err(DomainError::Validation(
+            "from_ms must not be after to_ms".into(),
+        ));
+    }
+    let community_id = match query.community_id.as_deref().map(str::trim) {
+        Some("") => {
+            return Err(DomainError::Validation(
+                "community_id must not be empty".into(),
+            ));
+        }
+        other => other.map(str::to_string),
+    };
+    let (cursor_created_at_ms, cursor_note_id) = parse_note_cursor(query.cursor.as_deref())?;
+
+    let (concept_qids, expansion_truncated) =
+        expand_narrower_concepts(repo, about, narrower_depth).await?;
+    let mut items = repo
+        .query_notes(&OntologyNoteQuery {
+            viewer_id: viewer_id.to_string(),
+            concept_qids: concept_qids.clone(),
+            place_ids,
+            action_types,
+            community_id,
+            from_ms: query.from_ms,
+            to_ms: query.to_ms,
+            now_ms,
+            cursor_created_at_ms,
+            cursor_note_id,
+            limit: limit + 1,
+        })
+        .await?;
+    let next_cursor = if items.len() > limit {
+        items.truncate(limit);
+        items
+            .last()
+            .map(|item| format!("{}:{}", item.note.created_at_ms, item.note.note_id))
+    } else {
+        None
+    };
+    // Privacy is checked again here so a repository that over-fetches cannot
+    // hand out someone else's restricted note. The cursor is taken first so
+    // paging does not depend on what was dropped.
+    items.retain(|item| note_visible_to(&item.note, viewer_id, now_ms));
+    Ok(OntologyGraphPage {
+        items,
+        next_cursor,
+        expanded_concept_qids: concept_qids,
+        expansion_truncated,
+    })
+}
+
+/// Breadth-first walk down `BROADER`, one repository call per level. Cycles
+/// are harmless because every QID is visited once.
+async fn expand_narrower_concepts(
+    repo: &dyn OntologyRepository,
+    roots: Vec<String>,
+    depth: usize,
+) -> DomainResult<(Vec<String>, bool)> {
+    let mut seen = roots.iter().cloned().collect::<HashSet<_>>();
+    let mut expanded = roots.clone();
+    let mut frontier = roots;
+    for _ in 0..depth {
+        if frontier.is_empty() {
+            break;
+        }
+        let edges = repo.list_narrower_concepts(&frontier).await?;
+        let mut next = Vec::new();
+        for edge in edges {
+            if seen.contains(&edge.narrower_qid) {
+                continue;
+            }
+            if expanded.len() >= MAX_EXPANDED_CONCEPTS {
+                return Ok((expanded, true));
+            }
+            seen.insert(edge.narrower_qid.clone());
+            expanded.push(edge.narrower_qid.clone());
+            next.push(edge.narrower_qid);
+        }
+        frontier = next;
+    }
+    Ok((expanded, false))
+}
+
+fn normalize_limit(limit: Option<usize>) -> DomainResult<usize> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        Err(DomainError::Validation(format!(
+            "limit must be between 1 and {MAX_LIMIT}"
+        )))
+    } else {
+        Ok(limit)
+    }
+}
+
+fn normalize_facet(
+    name: &str,
+    values: &[String],
+    normalize: fn(&str) -> Option<String>,
+) -> DomainResult<Vec<String>> {
+    let mut normalized = Vec::new();
+    for value in values {
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        let value = normalize(value)
+            .ok_or_else(|| DomainError::Validation(format!("invalid {name} value '{value}'")))?;
+        if !normalized.contains(&value) {
+            normalized.push(value);
+        }
+    }
+    if normalized.len() > MAX_FACET_VALUES {
+        return Err(DomainError::Validation(format!(
+            "{name} accepts at most {MAX_FACET_VALUES} values"
+        )));
+    }
+    Ok(normalized)
+}
+
+fn normalize_concept_qid(value: &str) -> Option<String> {
+    let qid = value.strip_prefix("concept:").unwrap_or(value);
+    (!qid.is_empty() && !qid.contains(':')).then(|| qid.to_string())
+}
+
+/// Dotted gazetteer codes (`32.73.01.1001`) name their area's place.
+fn normalize_place_id(value: &str) -> Option<String> {
+    let id = value.strip_prefix("place:").unwrap_or(value);
+    if id.contains('.')
+        && let Ok(code) = normalize_admin_code(id)
+    {
+        return Some(admin_place_id(&code));
+    }
+    (!id.is_empty() && !id.contains(':')).then(|| format!("place:{id}"))
+}
+
+fn normalize_action_type(value: &str) -> Option<String> {
+    let name = value
+        .strip_prefix("schema:")
+        .or_else(|| value.strip_prefix("action:"))
+        .unwrap_or(value);
+    ActionType::ALL
+        .iter()
+        .map(ActionType::as_str)
+        .find(|action_type| action_type.strip_prefix("schema:") == Some(name))
+        .map(str::to_string)
+}
+
+fn parse_note_cursor(value: Option<&str>) -> DomainResult<(Option<i64>, Option<String>)> {
+    let Some(value) = value.filter(|value| !value.is_empty()) else {
+        return Ok((None, None));
+    };
+    let invalid = || {
+        DomainError::Validation("invalid cursor format; expected <created_at_ms>:<note_id>".into())
+    };
+    let (created_raw, note_id) = value.split_once(':').ok_or_else(invalid)?;
+    let created_at_ms = created_raw.parse().map_err(|_| invalid())?;
+    if note_id.trim().is_empty() {
+        return Err(invalid());
+    }
+    Ok((Some(created_at_ms), Some(note_id.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::geo::GeoRadius;
+    use crate::ontology::{
+        NoteFeedbackCounts, NoteFeedbackVote, OntologyActionRef, OntologyConcept,
+        OntologyConceptLabel, OntologyConceptLabels, OntologyConceptMergeResult,
+        OntologyConceptRedirect, OntologyConceptUsage, OntologyEdgeKind, OntologyNoteCreate,
+        OntologyNoteUpdate, OntologyNoteVersion, OntologyPlaceRef, OntologyTripleCreate,
+    };
+    use crate::ontology_export::{OntologyBroaderEdge, OntologyExportFilter, OntologyExportGraph};
+    use crate::ontology_gazetteer::{AdminArea, AdminLevel};
+    use crate::ports::BoxFuture;
+
+    /// Serves fixed edges and notes; `query_notes` ignores the facets so the
+    /// tests see what the domain layer filters on its own. Everything else is
+    /// an inert in-memory answer: writes are dropped and lookups come back
+    /// empty.
+    #[derive(Default)]
+    struct FixtureRepo {
+        edges: Vec<OntologyBroaderEdge>,
+        notes: Vec<OntologyNoteMatch>,
+        seen_queries: Mutex<Vec<OntologyNoteQuery>>,
+    }
+
+    impl OntologyRepository for FixtureRepo {
+        fn upsert_concept(
+            &self,
+            concept: &OntologyConcept,
+        ) -> BoxFuture<'_, DomainResult<OntologyConcept>> {
+            let concept = concept.clone();
+            Box::pin(async move { Ok(concept) })
+        }
+
+        fn add_broader_edge(
+            &self,
+            _narrower: &str,
+            _broader: &str,
+        ) -> BoxFuture<'_, DomainResult<()>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn create_note(
+            &self,
+            note: &OntologyNoteCreate,
+        ) -> BoxFuture<'_, DomainResult<OntologyNote>> {
+            let note = OntologyNote {
+                note_id: note
+                    .note_id
+                    .clone()
+                    .unwrap_or_else(|| "fixture-note".to_string()),
+                content: note.content.clone(),
+                author_id: note.author_id.clone(),
+                community_id: note.community_id.clone(),
+                temporal_class: note.temporal_class.clone(),
+                ttl_expires_ms: note.ttl_expires_ms,
+                ai_readable: note.ai_readable,
+                rahasia_level: note.rahasia_level,
+                confidence: note.confidence,
+                created_at_ms: 0,
+                version: 1,
+                edited_at_ms: None,
+                retracted_at_ms: None,
+                retraction_reason: None,
+            };
+            Box::pin(async move { Ok(note) })
+        }
+
+        fn write_triples(
+            &self,
+            _triples: &[OntologyTripleCreate],
+        ) -> BoxFuture<'_, DomainResult<()>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn list_note_edge_targets(
+            &self,
+            _note_id: &str,
+            _edge: OntologyEdgeKind,
+        ) -> BoxFuture<'_, DomainResult<Vec<String>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn get_concept_by_qid(
+            &self,
+            _qid: &str,
+        ) -> BoxFuture<'_, DomainResult<Option<OntologyConcept>>> {
+            Box::pin(async { Ok(None) })
+        }
+
+        fn get_concepts_by_qids(
+            &self,
+            _qids: &[String],
+        ) -> BoxFuture<'_, DomainResult<Vec<OntologyConcept>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn get_actions_by_types(
+            &self,
+            _action_types: &[String],
+        ) -> BoxFuture<'_, DomainResult<Vec<OntologyActionRef>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn get_places_by_ids(
+            &self,
+            _place_ids: &[String],
+        ) -> BoxFuture<'_, DomainResult<Vec<OntologyPlaceRef>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn upsert_admin_areas(&self, _areas: &[AdminArea]) -> BoxFuture<'_, DomainResult<()>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn get_admin_areas(
+            &self,
+            _codes: &[String],
+        ) -> BoxFuture<'_, DomainResult<Vec<AdminArea>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn list_admin_areas_covering(
+            &self,
+            _geohash: &str,
+        ) -> BoxFuture<'_, DomainResult<Vec<AdminArea>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn list_admin_areas_near(
+            &self,
+            _near: &GeoRadius,
+            _level: AdminLevel,
+        ) -> BoxFuture<'_, DomainResult<Vec<AdminArea>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn list_broader_concepts(
+            &self,
+            _concept_id: &str,
+        ) -> BoxFuture<'_, DomainResult<Vec<OntologyConcept>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn list_narrower_concepts(
+            &self,
+            broader_qids: &[String],
+        ) -> BoxFuture<'_, DomainResult<Vec<OntologyBroaderEdge>>> {
+            let edges = self
+                .edges
+                .iter()
+                .filter(|edge| broader_qids.contains(&edge.broader_qid))
+                .cloned()
+                .collect();
+            Box::pin(async move { Ok(edges) })
+        }
+
+        fn note_feedback_counts(
+            &self,
+            _note_id: &str,
+        ) -> BoxFuture<'_, DomainResult<NoteFeedbackCounts>> {
+            Box::pin(async { Ok(NoteFeedbackCounts::default()) })
+        }
+
+        fn list_note_feedback_votes(
+            &self,
+            _note_id: &str,
+        ) -> BoxFuture<'_, DomainResult<Vec<NoteFeedbackVote>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn get_note(&self, note_id: &str) -> BoxFuture<'_, DomainResult<Option<OntologyNote>>> {
+            let note = self
+                .notes
+                .iter()
+                .find(|matched| matched.note.note_id == note_id)
+                .map(|matched| matched.note.clone());
+            Box::pin(async move { Ok(note) })
+        }
+
+        fn update_note(
+            &self,
+            _update: &OntologyNoteUpdate,
+        ) -> BoxFuture<'_, DomainResult<OntologyNote>> {
+            Box::pin(async { Err(DomainError::NotFound) })
+        }
+
+        fn retract_note(
+            &self,
+            _note_id: &str,
+            _reason: Option<String>,
+            _retracted_at_ms: i64,
+        ) -> BoxFuture<'_, DomainResult<OntologyNote>> {
+            Box::pin(async { Err(DomainError::NotFound) })
+        }
+
+        fn list_note_versions(
+            &self,
+            _note_id: &str,
+        ) -> BoxFuture<'_, DomainResult<Vec<OntologyNoteVersion>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn cleanup_expired_notes(
+            &self,
+            _cutoff_ms: i64,
+        ) -> BoxFuture<'_, DomainResult<Vec<String>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn export_graph(
+            &self,
+            _filter: &OntologyExportFilter,
+        ) -> BoxFuture<'_, DomainResult<OntologyExportGraph>> {
+            Box::pin(async { Ok(OntologyExportGraph::default()) })
+        }
+
+        fn get_concept_redirect(
+            &self,
+            _qid: &str,
+        ) -> BoxFuture<'_, DomainResult<Option<OntologyConceptRedirect>>> {
+            Box::pin(async { Ok(None) })
+        }
+
+        fn merge_concepts(
+            &self,
+            _redirect: &OntologyConceptRedirect,
+        ) -> BoxFuture<'_, DomainResult<OntologyConceptMergeResult>> {
+            Box::pin(async { Err(DomainError::NotFound) })
+        }
+
+        fn replace_concept_labels(
+            &self,
+            _qid: &str,
+            _labels: &[OntologyConceptLabel],
+        ) -> BoxFuture<'_, DomainResult<()>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn get_concept_labels(
+            &self,
+            _qids: &[String],
+        ) -> BoxFuture<'_, DomainResult<Vec<OntologyConceptLabels>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn list_concept_labels(
+            &self,
+            _after_qid: Option<&str>,
+            _limit: usize,
+        ) -> BoxFuture<'_, DomainResult<Vec<OntologyConceptLabels>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn concept_usage_counts(
+            &self,
+            _community_id: &str,
+            _qids: &[String],
+        ) -> BoxFuture<'_, DomainResult<Vec<OntologyConceptUsage>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn query_notes(
+            &self,
+            query: &OntologyNoteQuery,
+        ) -> BoxFuture<'_, DomainResult<Vec<OntologyNoteMatch>>> {
+            self.seen_queries.lock().expect("lock").push(query.clone());
+            let notes = self.notes.iter().take(query.limit).cloned().collect();
+            Box::pin(async move { Ok(notes) })
+        }
+    }
+
+    fn edge(narrower_qid: &str, broader_qid: &str) -> OntologyBroaderEdge {
+        OntologyBroaderEdge {
+            narrower_qid: narrower_qid.to_string(),
+            broader_qid: broader_qid.to_string(),
+        }
+    }
+
+    fn matched(
+        note_id: &str,
+        author_id: &str,
+        rahasia_level: i64,
+        created_at_ms: i64,
+    ) -> OntologyNoteMatch {
+        OntologyNoteMatch {
+            note: OntologyNote {
+                note_id: note_id.to_string(),
+                content: format!("catatan {note_id}"),
+                author_id: author_id.to_string(),
+                community_id: "rw-07".to_string(),
+                temporal_class: "persistent".to_string(),
+                ttl_expires_ms: None,
+                ai_readable: true,
+                rahasia_level,
+                confidence: 0.5,
+                created_at_ms,
+                version: 1,
+                edited_at_ms: None,
+                retracted_at_ms: None,
+                retraction_reason: None,
+            },
+            about: vec!["Q8068".to_string()],
+            located_at: vec![],
+            has_action: vec![],
+        }
+    }
+
+    fn about(qids: &[&str]) -> OntologyGraphQuery {
+        OntologyGraphQuery {
+            about: qids.iter().map(|qid| qid.to_string()).collect(),
+            ..OntologyGraphQuery::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn expansion_walks_narrower_levels_up_to_depth_and_survives_cycles() {
+        let repo = FixtureRepo {
+            edges: vec![
+                edge("Q2", "Q1"),
+                edge("Q3", "Q2"),
+                edge("Q4", "Q3"),
+                edge("Q1", "Q3"),
+            ],
+            ..FixtureRepo::default()
+        };
+
+        let page = query_ontology_graph(&repo, "warga-1", &about(&["concept:Q1"]), 0)
+            .await
+            .expect("page");
+        assert_eq!(page.expanded_concept_qids, vec!["Q1", "Q2", "Q3"]);
+        assert!(!page.expansion_truncated);
+
+        let mut query = about(&["Q1"]);
+        query.narrower_depth = Some(MAX_NARROWER_DEPTH);
+        let page = query_ontology_graph(&repo, "warga-1", &query, 0)
+            .await
+            .expect("page");
+        assert_eq!(page.expanded_concept_qids, vec!["Q1", "Q2", "Q3", "Q4"]);
+
+        query.narrower_depth = Some(0);
+        let page = query_ontology_graph(&repo, "warga-1", &query, 0)
+            .await
+            .expect("page");
+        assert_eq!(page.expanded_concept_qids, vec!["Q1"]);
+        let seen = repo.seen_queries.lock().expect("lock");
+        assert_eq!(
+            seen.last().map(|query| query.concept_qids.clone()),
+            Some(vec!["Q1".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn query_filters_private_notes_and_pages_with_cursor() {
+        let mut expired = matched("n4", "warga-1", 0, 700);
+        expired.note.ttl_expires_ms = Some(50);
+        let repo = FixtureRepo {
+            notes: vec![
+                matched("n1", "warga-2", 0, 1_000),
+                matched("n2", "warga-2", 2, 900),
+                matched("n3", "warga-1", 3, 800),
+                expired,
+                matched("n5", "warga-2", 0, 600),
+            ],
+            ..FixtureRepo::default()
+        };
+        let mut query = about(&["Q8068"]);
+        query.limit = Some(3);
+
+        let page = query_ontology_graph(&repo, "warga-1", &query, 100)
+            .await
+            .expect("page");
+        let ids = page
+            .items
+            .iter()
+            .map(|item| item.note.note_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["n1", "n3"]);
+        assert_eq!(page.next_cursor.as_deref(), Some("800:n3"));
+
+        query.cursor = page.next_cursor;
+        query_ontology_graph(&repo, "warga-1", &query, 100)
+            .await
+            .expect("second page");
+        let seen = repo.seen_queries.lock().expect("lock");
+        let last = seen.last().expect("query");
+        assert_eq!(last.cursor_created_at_ms, Some(800));
+        assert_eq!(last.cursor_note_id.as_deref(), Some("n3"));
+        assert_eq!(last.limit, 4);
+    }
+
+    #[tokio::test]
+    async fn query_normalizes_facets_and_rejects_bad_input() {
+        let repo = FixtureRepo::default();
+        let query = OntologyGraphQuery {
+            located_at: vec![
+                "rt-03".to_string(),
+                "place:rt-03".to_string(),
+                "32.73.01.1001".to_string(),
+            ],
+            has_action: vec!["RepairAction".to_string(), "action:AlertAction".to_string()],
+            ..OntologyGraphQuery::default()
+        };
+        query_ontology_graph(&repo, "warga-1", &query, 0)
+            .await
+            .expect("page");
+        {
+            let seen = repo.seen_queries.lock().expect("lock");
+            let last = seen.last().expect("query");
+            assert_eq!(
+                last.place_ids,
+                vec!["place:rt-03", "place:adm_32_73_01_1001"]
+            );
+            assert_eq!(
+                last.action_types,
+                vec!["schema:RepairAction", "schema:AlertAction"]
+            );
+            assert!(last.concept_qids.is_empty());
+        }
+
+        let invalid = [
+            OntologyGraphQuery::default(),
+            OntologyGraphQuery {
+                has_action: vec!["schema:DanceAction".to_string()],
+                ..OntologyGraphQuery::default()
+            },
+            OntologyGraphQuery {
+                narrower_depth: Some(MAX_NARROWER_DEPTH + 1),
+                ..about(&["Q1"])
+            },
+            OntologyGraphQuery {
+                from_ms: Some(10),
+                to_ms: Some(5),
+                ..about(&["Q1"])
+            },
+            OntologyGraphQuery {
+                cursor: Some("bad".to_string()),
+                ..about(&["Q1"])
+            },
+            OntologyGraphQuery {
+                limit: Some(MAX_LIMIT + 1),
+                ..about(&["Q1"])
+            },
+        ];
+        for query in invalid {
+            assert!(
+                query_ontology_graph(&repo, "warga-1", &query, 0)
+                    .await
+                    .is_err(),
+                "{query:?} should be rejected"
+            );
+        }
+    }
+}
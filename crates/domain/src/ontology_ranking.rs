@@ -0,0 +1,334 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::DomainResult;
+use crate::ontology::{NoteFeedbackKind, NoteFeedbackVote};
+use crate::ports::ontology::OntologyRepository;
+use crate::ports::reputation::ReputationLookup;
+use crate::ranking::{wilson_score, wilson_score_weighted};
+
+const DAY_MS: i64 = 86_400_000;
+/// Voters with reputation 0.0 and 1.0; reputation 0.5, and voters the Markov
+/// engine does not know, weigh 1.0.
+const MIN_REPUTATION_WEIGHT: f64 = 0.25;
+const MAX_REPUTATION_WEIGHT: f64 = 1.75;
+const NEUTRAL_REPUTATION_WEIGHT: f64 = 1.0;
+/// Voters looked up per ranking; the rest count as unknown.
+const MAX_REPUTATION_LOOKUPS: usize = 200;
+
+/// How long a vote keeps half its weight. Ephemeral notes (prices, queues)
+/// go stale in days, periodic ones (schedules) over a season, and persistent
+/// facts keep their votes for months.
+pub fn feedback_half_life_ms(temporal_class: &str) -> i64 {
+    match temporal_class.trim() {
+        "ephemeral" => 2 * DAY_MS,
+        "periodic" => 30 * DAY_MS,
+        _ => 180 * DAY_MS,
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteRankingMethod {
+    ReputationWeighted,
+    /// Plain Wilson over raw counts, used when reputation is unavailable.
+    Wilson,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FeedbackBreakdown {
+    pub count: usize,
+    /// Sum of reputation weight × decay over the votes; the score's input.
+    pub weighted: f64,
+    pub mean_reputation_weight: f64,
+    pub mean_decay: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct NoteRankingExplanation {
+    pub method: NoteRankingMethod,
+    /// `None` when the note is unknown; the persistent half-life applies.
+    pub temporal_class: Option<String>,
+    pub half_life_ms: Option<i64>,
+    pub vouch: FeedbackBreakdown,
+    pub challenge: FeedbackBreakdown,
+    pub voters_with_reputation: usize,
+    /// Why plain Wilson was used, e.g. `reputation_unavailable`.
+    pub fallback_reason: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct NoteRanking {
+    pub note_id: String,
+    pub vouch_count: usize,
+    pub challenge_count: usize,
+    pub score: f64,
+    pub explanation: NoteRankingExplanation,
+}
+
+/// Ranks a note by its vouches and challenges. Each vote is weighted by the
+/// voter's reputation and decays with age at the note's `temporal_class`
+/// half-life; without a reputation source, or when it fails, the score is
+/// plain Wilson over the raw counts.
+pub async fn rank_ontology_note(
+    repo: &dyn OntologyRepository,
+    reputation: Option<&dyn ReputationLookup>,
+    note_id: &str,
+    now_ms: i64,
+) -> DomainResult<NoteRanking> {
+    let note = repo.get_note(note_id).await?;
+    let votes = repo.list_note_feedback_votes(note_id).await?;
+    let temporal_class = note.as_ref().map(|note| note.temporal_class.clone());
+    let note_created_at_ms = note.as_ref().map(|note| note.created_at_ms);
+
+    let reputations = match reputation {
+        None => Err("reputation_unconfigured"),
+        Some(_) if votes.is_empty() => Ok(HashMap::new()),
+        Some(lookup) => {
+            let mut seen = HashSet::new();
+            let voters = votes
+                .iter()
+                .filter(|vote| seen.insert(vote.voter_id.as_str()))
+                .take(MAX_REPUTATION_LOOKUPS)
+                .map(|vote| vote.voter_id.clone())
+                .collect::<Vec<_>>();
+            lookup
+                .reputations(&voters)
+                .await
+                .map_err(|_| "reputation_unavailable")
+        }
+    };
+
+    let (score, explanation) = match reputations {
+        Ok(reputations) => score_votes(
+            &votes,
+            &reputations,
+            temporal_class,
+            note_created_at_ms,
+            now_ms,
+        ),
+        Err(reason) => plain_wilson(&votes, temporal_class, reason),
+    };
+    Ok(NoteRanking {
+        note_id: note_id.to_string(),
+        vouch_count: explanation.vouch.count,
+        challenge_count: explanation.challenge.count,
+        score,
+        explanation,
+    })
+}
+
+/// Reputation-weighted, time-decayed Wilson score. Votes without a timestamp
+/// are dated at the note's creation.
+pub fn score_votes(
+    votes: &[NoteFeedbackVote],
+    reputations: &HashMap<String, f64>,
+    temporal_class: Option<String>,
+    note_created_at_ms: Option<i64>,
+    now_ms: i64,
+) -> (f64, NoteRankingExplanation) {
+    let half_life_ms = feedback_half_life_ms(temporal_class.as_deref().unwrap_or_default());
+    let mut vouch = Accumulator::default();
+    let mut challenge = Accumulator::default();
+    let mut known_voters = HashSet::new();
+    for vote in votes {
+        let reputation_weight = match reputations.get(&vote.voter_id) {
+            Some(reputation) => {
+                known_voters.insert(vote.voter_id.as_str());
+                reputation_weight(*reputation)
+            }
+            None => NEUTRAL_REPUTATION_WEIGHT,
+        };
+        let voted_at_ms = vote.created_at_ms.or(note_created_at_ms).unwrap_or(now_ms);
+        let age_ms = now_ms.saturating_sub(voted_at_ms).max(0);
+        let decay = 0.5_f64.powf(age_ms as f64 / half_life_ms as f64);
+        let target = match vote.kind {
+            NoteFeedbackKind::Vouch => &mut vouch,
+            NoteFeedbackKind::Challenge => &mut challenge,
+        };
+        target.add(reputation_weight, decay);
+    }
+    let vouch = vouch.finish();
+    let challenge = challenge.finish();
+    let score = wilson_score_weighted(vouch.weighted, vouch.weighted + challenge.weighted);
+    let explanation = NoteRankingExplanation {
+        method: NoteRankingMethod::ReputationWeighted,
+        temporal_class,
+        half_life_ms: Some(half_life_ms),
+        vouch,
+        challenge,
+        voters_with_reputation: known_voters.len(),
+        fallback_reason: None,
+    };
+    (score, explanation)
+}
+
+fn plain_wilson(
+    votes: &[NoteFeedbackVote],
+    temporal_class: Option<String>,
+    reason: &str,
+) -> (f64, NoteRankingExplanation) {
+    let vouch_count = votes
+        .iter()
+        .filter(|vote| vote.kind == NoteFeedbackKind::Vouch)
+        .count();
+    let challenge_count = votes.len() - vouch_count;
+    let unweighted = |count: usize| FeedbackBreakdown {
+        count,
+        weighted: count as f64,
+        mean_reputation_weight: NEUTRAL_REPUTATION_WEIGHT,
+        mean_decay: 1.0,
+    };
+    let explanation = NoteRankingExplanation {
+        method: NoteRankingMethod::Wilson,
+        temporal_class,
+        half_life_ms: None,
+        vouch: unweighted(vouch_count),
+        challenge: unweighted(challenge_count),
+        voters_with_reputation: 0,
+        fallback_reason: Some(reason.to_string()),
+    };
+    (
+        wilson_score(vouch_count as u64, votes.len() as u64),
+        explanation,
+    )
+}
+
+fn reputation_weight(reputation: f64) -> f64 {
+    if !reputation.is_finite() {
+        return NEUTRAL_REPUTATION_WEIGHT;
+    }
+    MIN_REPUTATION_WEIGHT
+        + (MAX_REPUTATION_WEIGHT - MIN_REPUTATION_WEIGHT) * reputation.clamp(0.0, 1.0)
+}
+
+#[derive(Default)]
+struct Accumulator {
+    count: usize,
+    weighted: f64,
+    reputation_weight: f64,
+    decay: f64,
+}
+
+impl Accumulator {
+    fn add(&mut self, reputation_weight: f64, decay: f64) {
+        self.count += 1;
+        self.weighted += reputation_weight * decay;
+        self.reputation_weight += reputation_weight;
+        self.decay += decay;
+    }
+
+    fn finish(self) -> FeedbackBreakdown {
+        let mean = |sum: f64| {
+            if self.count == 0 {
+                0.0
+            } else {
+                sum / self.count as f64
+            }
+        };
+        FeedbackBreakdown {
+            count: self.count,
+            weighted: self.weighted,
+            mean_reputation_weight: mean(self.reputation_weight),
+            mean_decay: mean(self.decay),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW_MS: i64 = 1_000 * DAY_MS;
+
+    fn vote(voter_id: &str, kind: NoteFeedbackKind, age_days: i64) -> NoteFeedbackVote {
+        NoteFeedbackVote {
+            voter_id: voter_id.to_string(),
+            kind,
+            created_at_ms: Some(NOW_MS - age_days * DAY_MS),
+            note_version: 1,
+        }
+    }
+
+    #[test]
+    fn trusted_voters_outweigh_new_accounts() {
+        let votes = vec![
+            vote("elder", NoteFeedbackKind::Vouch, 0),
+            vote("newcomer", NoteFeedbackKind::Challenge, 0),
+        ];
+        let reputations =
+            HashMap::from([("elder".to_string(), 1.0), ("newcomer".to_string(), 0.0)]);
+        let (score, explanation) = score_votes(
+            &votes,
+            &reputations,
+            Some("persistent".to_string()),
+            None,
+            NOW_MS,
+        );
+        assert!(score > wilson_score(1, 2));
+        assert_eq!(explanation.voters_with_reputation, 2);
+        assert_eq!(explanation.vouch.weighted, MAX_REPUTATION_WEIGHT);
+        assert_eq!(explanation.challenge.weighted, MIN_REPUTATION_WEIGHT);
+
+        // Unknown voters are neutral, so the score is plain Wilson.
+        let (neutral, _) = score_votes(&votes, &HashMap::new(), None, None, NOW_MS);
+        assert!((neutral - wilson_score(1, 2)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn votes_decay_faster_on_ephemeral_notes() {
+        let votes = vec![
+            vote("a", NoteFeedbackKind::Vouch, 4),
+            vote("b", NoteFeedbackKind::Challenge, 0),
+        ];
+        let (ephemeral, explanation) = score_votes(
+            &votes,
+            &HashMap::new(),
+            Some("ephemeral".to_string()),
+            None,
+            NOW_MS,
+        );
+        // Two half-lives old.
+        assert!((explanation.vouch.mean_decay - 0.25).abs() < 1e-9);
+        let (persistent, _) = score_votes(
+            &votes,
+            &HashMap::new(),
+            Some("persistent".to_string()),
+            None,
+            NOW_MS,
+        );
+        assert!(ephemeral < persistent);
+
+        // Untimestamped votes date from the note.
+        let legacy = vec![NoteFeedbackVote {
+            created_at_ms: None,
+            ..vote("a", NoteFeedbackKind::Vouch, 0)
+        }];
+        let (_, explanation) = score_votes(
+            &legacy,
+            &HashMap::new(),
+            Some("periodic".to_string()),
+            Some(NOW_MS - 30 * DAY_MS),
+            NOW_MS,
+        );
+        assert!((explanation.vouch.mean_decay - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn plain_wilson_reports_the_fallback_reason() {
+        let votes = vec![
+            vote("a", NoteFeedbackKind::Vouch, 400),
+            vote("b", NoteFeedbackKind::Vouch, 0),
+            vote("c", NoteFeedbackKind::Challenge, 0),
+        ];
+        let (score, explanation) = plain_wilson(&votes, None, "reputation_unavailable");
+        assert_eq!(score, wilson_score(2, 3));
+        assert_eq!(explanation.method, NoteRankingMethod::Wilson);
+        assert_eq!(explanation.vouch.count, 2);
+        assert_eq!(
+            explanation.fallback_reason.as_deref(),
+            Some("reputation_unavailable")
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+
+use crate::DomainResult;
+use crate::error::DomainError;
+use crate::ontology::{OntologyNote, OntologyNoteUpdate, OntologyNoteVersion};
+use crate::ontology_query::note_visible_to;
+use crate::ports::ontology::OntologyRepository;
+
+pub const MAX_NOTE_CONTENT_LENGTH: usize = 2_000;
+pub const MAX_RETRACTION_REASON_LENGTH: usize = 280;
+
+/// Fields left `None` keep their current value.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NoteEditCommand {
+    pub note_id: String,
+    /// The version the author was looking at; a newer one is a conflict.
+    pub expected_version: Option<i64>,
+    pub content: Option<String>,
+    pub confidence: Option<f64>,
+}
+
+/// A note with every version of its text, newest first. The first entry is
+/// the current text.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NoteHistory {
+    pub note: OntologyNote,
+    pub versions: Vec<OntologyNoteVersion>,
+}
+
+/// Applies an author edit. An edit that changes nothing returns the note as
+/// it is, without a new version.
+pub async fn edit_ontology_note(
+    repo: &dyn OntologyRepository,
+    author_id: &str,
+    command: &NoteEditCommand,
+    now_ms: i64,
+) -> DomainResult<OntologyNote> {
+    let note = authored_note(repo, author_id, &command.note_id, now_ms).await?;
+    if note.is_retracted() {
+        return Err(DomainError::Conflict);
+    }
+    if command
+        .expected_version
+        .is_some_and(|expected| expected != note.version)
+    {
+        return Err(DomainError::Conflict);
+    }
+
+    let content = match command.content.as_deref() {
+        Some(content) => {
+            let content = content.trim();
+            if content.is_empty() || content.chars().count() > MAX_NOTE_CONTENT_LENGTH {
+                return Err(DomainError::Validation(format!(
+                    "content must be between 1 and {MAX_NOTE_CONTENT_LENGTH} characters"
+                )));
+            }
+            content.to_string()
+        }
+        None => note.content.clone(),
+    };
+    let confidence = command.confidence.unwrap_or(note.confidence);
+    if !(0.0..=1.0).contains(&confidence) {
+        return Err(DomainError::Validation(
+            "confidence must be between 0.0 and 1.0".into(),
+        ));
+    }
+    if content == note.content && confidence.to_bits() == note.confidence.to_bits() {
+        return Ok(note);
+    }
+
+    repo.update_note(&OntologyNoteUpdate {
+        note_id: note.note_id.clone(),
+        expected_version: note.version,
+        content,
+        confidence,
+        edited_at_ms: now_ms,
+    })
+    .await
+}
+
+/// Retracts a note on behalf of its author. Retracting twice keeps the first
+/// marker.
+pub async fn retract_ontology_note(
+    repo: &dyn OntologyRepository,
+    author_id: &str,
+    note_id: &str,
+    reason: Option<&str>,
+    now_ms: i64,
+) -> DomainResult<OntologyNote> {
+    let note = authored_note(repo, author_id, note_id, now_ms).await?;
+    if note.is_retracted() {
+        return Ok(note);
+    }
+    let reason = reason.map(str::trim).filter(|reason| !reason.is_empty());
+    if reason.is_some_and(|reason| reason.chars().count() > MAX_RETRACTION_REASON_LENGTH) {
+        return Err(DomainError::Validation(format!(
+            "reason must be at most {MAX_RETRACTION_REASON_LENGTH} characters"
+        )));
+    }
+    repo.retract_note(&note.note_id, reason.map(ToString::to_string), now_ms)
+        .await
+}
+
+/// Version history of a note the viewer may see. Retracted notes keep their
+/// history.
+pub async fn ontology_note_history(
+    repo: &dyn OntologyRepository,
+    viewer_id: &str,
+    note_id: &str,
+    now_ms: i64,
+) -> DomainResult<NoteHistory> {
+    let note = repo
+        .get_note(note_id)
+        .await?
+        .filter(|note| note_visible_to(note, viewer_id, now_ms))
+        .ok_or(DomainError::NotFound)?;
+    let mut versions = repo
+        .list_note_versions(&note.note_id)
+        .await?
+        .into_iter()
+        .filter(|version| version.version < note.version)
+        .collect::<Vec<_>>();
+    versions.push(current_version(&note));
+    versions.sort_by_key(|version| std::cmp::Reverse(version.version));
+    Ok(NoteHistory { note, versions })
+}
+
+pub fn current_version(note: &OntologyNote) -> OntologyNoteVersion {
+    OntologyNoteVersion {
+        note_id: note.note_id.clone(),
+        version: note.version,
+        content: note.content.clone(),
+        confidence: note.confidence,
+        created_at_ms: note.edited_at_ms.unwrap_or(note.created_at_ms),
+        superseded_at_ms: None,
+    }
+}
+
+/// Notes the author can no longer see (expired, or unknown) are not found;
+/// someone else's note is forbidden.
+async fn authored_note(
+    repo: &dyn OntologyRepository,
+    author_id: &str,
+    note_id: &str,
+    now_ms: i64,
+) -> DomainResult<OntologyNote> {
+    let note = repo
+        .get_note(note_id)
+        .await?
+        .filter(|note| note_visible_to(note, author_id, now_ms))
+        .ok_or(DomainError::NotFound)?;
+    if note.author_id != author_id {
+        return Err(DomainError::Forbidden(
+            "only the author can change a note".into(),
+        ));
+    }
+    Ok(note)
+}
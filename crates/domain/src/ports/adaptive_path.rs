@@ -1,6 +1,7 @@
 use crate::DomainResult;
 use crate::adaptive_path::{
-    AdaptivePathEvent, AdaptivePathPlan, AdaptivePathSuggestion, SuggestionDecisionStatus,
+    AdaptivePathEvent, AdaptivePathPlan, AdaptivePathPlanVersionSummary, AdaptivePathSuggestion,
+    SuggestionDecisionStatus,
 };
 use crate::ports::BoxFuture;
 
@@ -25,6 +26,20 @@ pub trait AdaptivePathRepository: Send + Sync {
     fn update_plan(&self, plan: &AdaptivePathPlan)
     -> BoxFuture<'_, DomainResult<AdaptivePathPlan>>;
 
+    /// Snapshot of the plan as it was at `version`. `create_plan` and
+    /// `update_plan` store one for every version they write.
+    fn get_plan_version(
+        &self,
+        plan_id: &str,
+        version: u64,
+    ) -> BoxFuture<'_, DomainResult<Option<AdaptivePathPlan>>>;
+
+    /// Stored versions, newest first.
+    fn list_plan_versions(
+        &self,
+        plan_id: &str,
+    ) -> BoxFuture<'_, DomainResult<Vec<AdaptivePathPlanVersionSummary>>>;
+
     fn create_event(
         &self,
         event: &AdaptivePathEvent,
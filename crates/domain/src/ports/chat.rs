@@ -1,7 +1,8 @@
 use crate::DomainResult;
 use crate::chat::{
-    ChatDeliveryEvent, ChatMember, ChatMessage, ChatReadCursor, ChatThread, ChatThreadQuery,
-    ChatThreadWithMembers, MessageCatchup,
+    ChatAttachment, ChatAttachmentQuota, ChatAttachmentUsageQuery, ChatDeliveryEvent, ChatMember,
+    ChatMessage, ChatReadCursor, ChatThread, ChatThreadQuery, ChatThreadWithMembers,
+    MessageCatchup,
 };
 
 #[allow(clippy::needless_pass_by_value)]
@@ -16,6 +17,15 @@ pub trait ChatRepository: Send + Sync {
         thread_id: &str,
     ) -> crate::ports::BoxFuture<'_, DomainResult<Option<ChatThread>>>;
 
+    fn update_thread(
+        &self,
+        thread: &ChatThread,
+    ) -> crate::ports::BoxFuture<'_, DomainResult<ChatThread>>;
+
+    fn list_threads_with_retention(
+        &self,
+    ) -> crate::ports::BoxFuture<'_, DomainResult<Vec<ChatThread>>>;
+
     fn list_threads_by_scope(
         &self,
         query: &ChatThreadQuery,
@@ -65,6 +75,19 @@ pub trait ChatRepository: Send + Sync {
         cursor: &MessageCatchup,
     ) -> crate::ports::BoxFuture<'_, DomainResult<Vec<ChatMessage>>>;
 
+    /// Live (not yet deleted) messages created before `cutoff_ms`, oldest first.
+    fn list_messages_created_before(
+        &self,
+        thread_id: &str,
+        cutoff_ms: i64,
+        limit: usize,
+    ) -> crate::ports::BoxFuture<'_, DomainResult<Vec<ChatMessage>>>;
+
+    fn update_message(
+        &self,
+        message: &ChatMessage,
+    ) -> crate::ports::BoxFuture<'_, DomainResult<ChatMessage>>;
+
     fn set_read_cursor(
         &self,
         cursor: &ChatReadCursor,
@@ -76,6 +99,20 @@ pub trait ChatRepository: Send + Sync {
         user_id: &str,
     ) -> crate::ports::BoxFuture<'_, DomainResult<Option<ChatReadCursor>>>;
 
+    fn list_read_cursors(
+        &self,
+        thread_id: &str,
+    ) -> crate::ports::BoxFuture<'_, DomainResult<Vec<ChatReadCursor>>>;
+
+    /// Live messages not authored by `user_id` that sort after the given
+    /// `(created_at_ms, message_id)` position; all of them when `after` is `None`.
+    fn count_unread_messages(
+        &self,
+        thread_id: &str,
+        user_id: &str,
+        after: Option<(i64, String)>,
+    ) -> crate::ports::BoxFuture<'_, DomainResult<usize>>;
+
     fn create_delivery_event(
         &self,
         event: &ChatDeliveryEvent,
@@ -86,4 +123,28 @@ pub trait ChatRepository: Send + Sync {
         thread_id: &str,
         request_id: &str,
     ) -> crate::ports::BoxFuture<'_, DomainResult<Option<ChatDeliveryEvent>>>;
+
+    /// Inserts the attachment only if the uploader's and thread's stored bytes,
+    /// including this attachment, stay within `quota`; the usage check and the insert
+    /// must not interleave with another upload's.
+    fn create_attachment(
+        &self,
+        attachment: &ChatAttachment,
+        quota: &ChatAttachmentQuota,
+    ) -> crate::ports::BoxFuture<'_, DomainResult<ChatAttachment>>;
+
+    fn get_attachment(
+        &self,
+        attachment_id: &str,
+    ) -> crate::ports::BoxFuture<'_, DomainResult<Option<ChatAttachment>>>;
+
+    fn delete_attachment(
+        &self,
+        attachment_id: &str,
+    ) -> crate::ports::BoxFuture<'_, DomainResult<()>>;
+
+    fn sum_attachment_bytes(
+        &self,
+        query: &ChatAttachmentUsageQuery,
+    ) -> crate::ports::BoxFuture<'_, DomainResult<u64>>;
 }
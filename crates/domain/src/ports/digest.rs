@@ -0,0 +1,42 @@
+use std::collections::BTreeMap;
+
+use crate::DomainResult;
+use crate::digest::DigestSubscription;
+
+use super::BoxFuture;
+
+pub trait DigestSubscriptionRepository: Send + Sync {
+    fn get_subscription(
+        &self,
+        user_id: &str,
+    ) -> BoxFuture<'_, DomainResult<Option<DigestSubscription>>>;
+
+    fn upsert_subscription(
+        &self,
+        subscription: &DigestSubscription,
+    ) -> BoxFuture<'_, DomainResult<DigestSubscription>>;
+
+    /// Subscriptions with digests enabled, ordered by `user_id`, starting after `after_user_id`.
+    fn list_active_subscriptions(
+        &self,
+        after_user_id: Option<&str>,
+        limit: usize,
+    ) -> BoxFuture<'_, DomainResult<Vec<DigestSubscription>>>;
+
+    fn mark_sent(&self, user_id: &str, sent_at_ms: i64) -> BoxFuture<'_, DomainResult<()>>;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutboundEmail {
+    pub to: String,
+    pub subject: String,
+    pub text_body: String,
+    pub html_body: String,
+    /// Extra headers such as `List-Unsubscribe`.
+    pub headers: BTreeMap<String, String>,
+}
+
+/// Delivers rendered email; SMTP in production, file or in-memory sinks elsewhere.
+pub trait MailTransport: Send + Sync {
+    fn send(&self, email: &OutboundEmail) -> BoxFuture<'_, DomainResult<()>>;
+}
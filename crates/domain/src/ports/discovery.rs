@@ -1,5 +1,11 @@
+use std::collections::HashMap;
+
 use crate::DomainResult;
-use crate::discovery::{FeedItem, InAppNotification};
+use crate::discovery::{
+    DiscoveryEvent, FeedFollowPreference, FeedItem, FeedMonitorPreference, InAppNotification,
+    NotificationListItem,
+};
+use crate::geo::GeoRadius;
 use crate::ports::BoxFuture;
 
 #[derive(Clone, Debug)]
@@ -13,6 +19,12 @@ pub struct FeedRepositoryQuery {
     pub from_ms: Option<i64>,
     pub to_ms: Option<i64>,
     pub involvement_only: bool,
+    /// With `involvement_only`, items tagged with one of these entities count
+    /// as involving the actor too.
+    pub followed_entity_ids: Vec<String>,
+    /// Stores may prefilter on `near.covering_geohashes()`; the exact
+    /// distance check happens in the service.
+    pub near: Option<GeoRadius>,
 }
 
 #[derive(Clone, Debug)]
@@ -26,6 +38,30 @@ pub struct FeedRepositorySearchQuery {
     pub involvement_only: bool,
     pub exclude_vault: bool,
     pub query_text: String,
+    pub near: Option<GeoRadius>,
+}
+
+/// Query for the full-text index. Privacy, vault and involvement filters are
+/// applied inside the index so `limit`/`offset` count visible hits only.
+#[derive(Clone, Debug)]
+pub struct FeedSearchIndexQuery {
+    pub actor_id: String,
+    pub query_text: String,
+    pub limit: usize,
+    pub offset: usize,
+    pub scope_id: Option<String>,
+    pub privacy_level: Option<String>,
+    pub from_ms: Option<i64>,
+    pub to_ms: Option<i64>,
+    pub involvement_only: bool,
+    pub exclude_vault: bool,
+    pub near: Option<GeoRadius>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeedSearchHit {
+    pub feed_id: String,
+    pub score: f32,
 }
 
 #[derive(Clone, Debug)]
@@ -35,6 +71,8 @@ pub struct NotificationRepositoryListQuery {
     pub cursor_notification_id: Option<String>,
     pub limit: usize,
     pub include_read: bool,
+    /// Hides notifications held by quiet hours beyond this instant.
+    pub delivered_by_ms: Option<i64>,
 }
 
 #[allow(clippy::needless_pass_by_value)]
@@ -65,6 +103,9 @@ pub trait FeedRepository: Send + Sync {
         payload_patch: serde_json::Value,
     ) -> BoxFuture<'_, DomainResult<FeedItem>>;
 
+    /// Replaces the title, e.g. after the source was edited.
+    fn update_title(&self, feed_id: &str, title: &str) -> BoxFuture<'_, DomainResult<FeedItem>>;
+
     fn list_feed(&self, query: &FeedRepositoryQuery) -> BoxFuture<'_, DomainResult<Vec<FeedItem>>>;
 
     fn search_feed(
@@ -73,6 +114,19 @@ pub trait FeedRepository: Send + Sync {
     ) -> BoxFuture<'_, DomainResult<Vec<FeedItem>>>;
 }
 
+pub trait FeedSearchIndex: Send + Sync {
+    fn search(
+        &self,
+        query: &FeedSearchIndexQuery,
+    ) -> BoxFuture<'_, DomainResult<Vec<FeedSearchHit>>>;
+}
+
+/// Collects feed items whose search documents are stale. Implementations hand the
+/// ids to the single index writer, which re-reads the rows and commits in batches.
+pub trait FeedSearchIndexQueue: Send + Sync {
+    fn enqueue(&self, feed_ids: &[String]) -> BoxFuture<'_, DomainResult<()>>;
+}
+
 #[allow(clippy::needless_pass_by_value)]
 pub trait NotificationRepository: Send + Sync {
     fn create_notification(
@@ -91,6 +145,15 @@ pub trait NotificationRepository: Send + Sync {
         query: &NotificationRepositoryListQuery,
     ) -> BoxFuture<'_, DomainResult<Vec<InAppNotification>>>;
 
+    /// Like `list_notifications`, but rows sharing a group key collapse into one
+    /// item (see `discovery::group_notifications`). Filters apply to rows; the
+    /// cursor and limit apply to each group's newest row, and groups whose newest
+    /// row is at or after the cursor were already listed.
+    fn list_notification_groups(
+        &self,
+        query: &NotificationRepositoryListQuery,
+    ) -> BoxFuture<'_, DomainResult<Vec<NotificationListItem>>>;
+
     fn list_notifications_in_window(
         &self,
         user_id: &str,
@@ -105,5 +168,53 @@ pub trait NotificationRepository: Send + Sync {
         read_at_ms: i64,
     ) -> BoxFuture<'_, DomainResult<InAppNotification>>;
 
-    fn unread_count(&self, user_id: &str) -> BoxFuture<'_, DomainResult<usize>>;
+    /// Marks the group's unread rows delivered by `read_at_ms` as read and
+    /// returns how many changed.
+    fn mark_group_as_read(
+        &self,
+        user_id: &str,
+        group_key: &str,
+        read_at_ms: i64,
+    ) -> BoxFuture<'_, DomainResult<usize>>;
+
+    /// Unread notifications already delivered by `as_of_ms`.
+    fn unread_count(&self, user_id: &str, as_of_ms: i64) -> BoxFuture<'_, DomainResult<usize>>;
+}
+
+/// Fans discovery events out to realtime streams. Best effort: publishing never
+/// fails the write that produced the event.
+pub trait DiscoveryEventPublisher: Send + Sync {
+    fn publish(&self, event: DiscoveryEvent) -> BoxFuture<'_, ()>;
+}
+
+/// Per-user follow (entity) and monitor (witness) toggles. Only `true`
+/// preferences are stored; writing `false` removes the row.
+pub trait FeedPreferenceRepository: Send + Sync {
+    fn set_monitor_preference(
+        &self,
+        preference: &FeedMonitorPreference,
+    ) -> BoxFuture<'_, DomainResult<()>>;
+
+    fn set_follow_preference(
+        &self,
+        preference: &FeedFollowPreference,
+    ) -> BoxFuture<'_, DomainResult<()>>;
+
+    /// Stored monitor flags for `witness_ids`; ids without a row are absent.
+    fn monitor_states(
+        &self,
+        user_id: &str,
+        witness_ids: &[String],
+    ) -> BoxFuture<'_, DomainResult<HashMap<String, bool>>>;
+
+    /// Stored follow flags for `entity_ids`; ids without a row are absent.
+    fn follow_states(
+        &self,
+        user_id: &str,
+        entity_ids: &[String],
+    ) -> BoxFuture<'_, DomainResult<HashMap<String, bool>>>;
+
+    /// Followed entities, most recently followed first.
+    fn list_follows(&self, user_id: &str)
+    -> BoxFuture<'_, DomainResult<Vec<FeedFollowPreference>>>;
 }
@@ -22,7 +22,12 @@ pub enum JobType {
     DigestSend,
     TTLCleanup,
     ConceptVerification,
+    ConceptMerge,
     OntologyNoteEnrich,
+    ChatRetentionSweep,
+    WebPushSend,
+    TrendingCompute,
+    FeedSearchIndexSync,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -7,14 +7,19 @@ pub mod adaptive_path;
 pub mod chat;
 pub mod contributions;
 pub mod db;
+pub mod digest;
 pub mod discovery;
 pub mod evidence;
 pub mod group;
 pub mod idempotency;
 pub mod jobs;
 pub mod moderation;
+pub mod notification_preferences;
 pub mod ontology;
+pub mod push;
+pub mod reputation;
 pub mod siaga;
+pub mod trending;
 pub mod vault;
 pub mod vouches;
 pub mod webhook;
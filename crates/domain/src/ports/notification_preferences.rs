@@ -0,0 +1,16 @@
+use crate::DomainResult;
+use crate::notification_preferences::NotificationPreferences;
+
+use super::BoxFuture;
+
+pub trait NotificationPreferenceRepository: Send + Sync {
+    fn get_preferences(
+        &self,
+        user_id: &str,
+    ) -> BoxFuture<'_, DomainResult<Option<NotificationPreferences>>>;
+
+    fn upsert_preferences(
+        &self,
+        preferences: &NotificationPreferences,
+    ) -> BoxFuture<'_, DomainResult<NotificationPreferences>>;
+}
@@ -1,9 +1,16 @@
 use crate::DomainResult;
+use crate::geo::GeoRadius;
 use crate::ontology::OntologyEdgeKind;
 use crate::ontology::{
-    NoteFeedbackCounts, OntologyActionRef, OntologyConcept, OntologyNote, OntologyNoteCreate,
-    OntologyPlaceRef, OntologyTripleCreate,
+    NoteFeedbackCounts, NoteFeedbackVote, OntologyActionRef, OntologyConcept, OntologyConceptLabel,
+    OntologyConceptLabels, OntologyConceptMergeResult, OntologyConceptRedirect,
+    OntologyConceptUsage, OntologyNote, OntologyNoteCreate, OntologyNoteUpdate,
+    OntologyNoteVersion, OntologyPlaceRef, OntologyTripleCreate,
 };
+use crate::ontology_autocomplete::ConceptLabelMatch;
+use crate::ontology_export::{OntologyBroaderEdge, OntologyExportFilter, OntologyExportGraph};
+use crate::ontology_gazetteer::{AdminArea, AdminLevel};
+use crate::ontology_query::{OntologyNoteMatch, OntologyNoteQuery};
 use crate::ports::BoxFuture;
 
 #[allow(clippy::needless_pass_by_value)]
@@ -29,9 +36,12 @@ pub trait OntologyRepository: Send + Sync {
         edge: OntologyEdgeKind,
     ) -> BoxFuture<'_, DomainResult<Vec<String>>>;
 
+    /// Follows a recorded redirect, so a merged QID yields its survivor.
     fn get_concept_by_qid(&self, qid: &str)
     -> BoxFuture<'_, DomainResult<Option<OntologyConcept>>>;
 
+    /// Follows redirects like [`Self::get_concept_by_qid`]; a survivor named
+    /// more than once is returned once.
     fn get_concepts_by_qids(
         &self,
         qids: &[String],
@@ -47,15 +57,154 @@ pub trait OntologyRepository: Send + Sync {
         place_ids: &[String],
     ) -> BoxFuture<'_, DomainResult<Vec<OntologyPlaceRef>>>;
 
+    /// Upserts gazetteer areas by code, each with its place record
+    /// (`admin_place_id`).
+    fn upsert_admin_areas(&self, areas: &[AdminArea]) -> BoxFuture<'_, DomainResult<()>>;
+
+    fn get_admin_areas(&self, codes: &[String]) -> BoxFuture<'_, DomainResult<Vec<AdminArea>>>;
+
+    /// Areas with a boundary whose cover geohash is a prefix of `geohash`, i.e.
+    /// whose bounding box may hold the point.
+    fn list_admin_areas_covering(
+        &self,
+        geohash: &str,
+    ) -> BoxFuture<'_, DomainResult<Vec<AdminArea>>>;
+
+    /// Areas of `level` whose centroid may lie within `near`; callers check
+    /// the exact distance.
+    fn list_admin_areas_near(
+        &self,
+        near: &GeoRadius,
+        level: AdminLevel,
+    ) -> BoxFuture<'_, DomainResult<Vec<AdminArea>>>;
+
     fn list_broader_concepts(
         &self,
         concept_id: &str,
     ) -> BoxFuture<'_, DomainResult<Vec<OntologyConcept>>>;
 
+    /// Direct `BROADER` edges whose broader end is one of `broader_qids`.
+    fn list_narrower_concepts(
+        &self,
+        broader_qids: &[String],
+    ) -> BoxFuture<'_, DomainResult<Vec<OntologyBroaderEdge>>>;
+
     fn note_feedback_counts(
         &self,
         note_id: &str,
     ) -> BoxFuture<'_, DomainResult<NoteFeedbackCounts>>;
 
+    /// Every vouch and challenge on `note_id`, with its voter.
+    fn list_note_feedback_votes(
+        &self,
+        note_id: &str,
+    ) -> BoxFuture<'_, DomainResult<Vec<NoteFeedbackVote>>>;
+
+    fn get_note(&self, note_id: &str) -> BoxFuture<'_, DomainResult<Option<OntologyNote>>>;
+
+    /// Keeps the current text as a superseded version, then applies the edit
+    /// and bumps `version`. `Conflict` when the note has moved past
+    /// `expected_version` or was retracted.
+    fn update_note(&self, update: &OntologyNoteUpdate)
+    -> BoxFuture<'_, DomainResult<OntologyNote>>;
+
+    /// Sets the retraction marker; a note already retracted keeps its first
+    /// marker.
+    fn retract_note(
+        &self,
+        note_id: &str,
+        reason: Option<String>,
+        retracted_at_ms: i64,
+    ) -> BoxFuture<'_, DomainResult<OntologyNote>>;
+
+    /// Superseded versions of `note_id`, oldest first.
+    fn list_note_versions(
+        &self,
+        note_id: &str,
+    ) -> BoxFuture<'_, DomainResult<Vec<OntologyNoteVersion>>>;
+
     fn cleanup_expired_notes(&self, cutoff_ms: i64) -> BoxFuture<'_, DomainResult<Vec<String>>>;
+
+    /// The notes `filter` admits and the triples leaving them, plus every concept
+    /// and `BROADER` edge. Paged filters read notes in `note_id` order after the
+    /// cursor, at most `limit + 1` of them, and only the concepts their triples
+    /// point at with those concepts' `BROADER` edges. Actions and places are left
+    /// for the caller.
+    fn export_graph(
+        &self,
+        filter: &OntologyExportFilter,
+    ) -> BoxFuture<'_, DomainResult<OntologyExportGraph>>;
+
+    /// Notes matching every non-empty facet of `query` that the viewer may
+    /// see, newest first and strictly after the cursor.
+    fn query_notes(
+        &self,
+        query: &OntologyNoteQuery,
+    ) -> BoxFuture<'_, DomainResult<Vec<OntologyNoteMatch>>>;
+
+    fn get_concept_redirect(
+        &self,
+        qid: &str,
+    ) -> BoxFuture<'_, DomainResult<Option<OntologyConceptRedirect>>>;
+
+    /// Re-points every edge of `redirect.from_qid` at `redirect.to_qid`, deletes
+    /// the losing concept and stores the redirect. Redirects that targeted the
+    /// loser are moved to the survivor so lookups stay one hop.
+    fn merge_concepts(
+        &self,
+        redirect: &OntologyConceptRedirect,
+    ) -> BoxFuture<'_, DomainResult<OntologyConceptMergeResult>>;
+
+    /// Replaces the alias and extra-language labels stored for `qid`. The
+    /// preferred `label_id` / `label_en` stay on the concept itself.
+    fn replace_concept_labels(
+        &self,
+        qid: &str,
+        labels: &[OntologyConceptLabel],
+    ) -> BoxFuture<'_, DomainResult<()>>;
+
+    /// Preferred and stored labels of each existing concept in `qids`.
+    fn get_concept_labels(
+        &self,
+        qids: &[String],
+    ) -> BoxFuture<'_, DomainResult<Vec<OntologyConceptLabels>>>;
+
+    /// One page of every concept's labels, ordered by QID and strictly after
+    /// `after_qid`; used to build the autocomplete index.
+    fn list_concept_labels(
+        &self,
+        after_qid: Option<&str>,
+        limit: usize,
+    ) -> BoxFuture<'_, DomainResult<Vec<OntologyConceptLabels>>>;
+
+    /// Notes of `community_id` that are `ABOUT` each of `qids`. QIDs no note
+    /// is about are left out.
+    fn concept_usage_counts(
+        &self,
+        community_id: &str,
+        qids: &[String],
+    ) -> BoxFuture<'_, DomainResult<Vec<OntologyConceptUsage>>>;
+}
+
+/// Label index behind concept autocomplete; kept in memory and refreshed as
+/// concepts and labels are written.
+pub trait ConceptLabelIndex: Send + Sync {
+    /// Replaces every label indexed for `labels.qid`.
+    fn index_concept(&self, labels: &OntologyConceptLabels) -> BoxFuture<'_, DomainResult<()>>;
+
+    fn remove_concept(&self, qid: &str) -> BoxFuture<'_, DomainResult<()>>;
+
+    /// Matches for an already normalized query (see
+    /// `ontology_autocomplete::normalize_label`), closest first.
+    fn search(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> BoxFuture<'_, DomainResult<Vec<ConceptLabelMatch>>>;
+}
+
+/// Upstream source of QID redirects, such as Wikidata item merges.
+pub trait ConceptRedirectLookup: Send + Sync {
+    /// The QID `qid` now redirects to, or `None` when it is not a redirect.
+    fn redirect_target(&self, qid: &str) -> BoxFuture<'_, DomainResult<Option<String>>>;
 }
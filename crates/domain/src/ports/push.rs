@@ -0,0 +1,52 @@
+use crate::DomainResult;
+use crate::push::{PushSubscription, PushUrgency};
+
+use super::BoxFuture;
+
+pub trait PushSubscriptionRepository: Send + Sync {
+    fn upsert_subscription(
+        &self,
+        subscription: &PushSubscription,
+    ) -> BoxFuture<'_, DomainResult<PushSubscription>>;
+
+    fn list_for_user(&self, user_id: &str) -> BoxFuture<'_, DomainResult<Vec<PushSubscription>>>;
+
+    /// Returns `false` when no subscription with that id belongs to the user.
+    fn delete_subscription(
+        &self,
+        user_id: &str,
+        subscription_id: &str,
+    ) -> BoxFuture<'_, DomainResult<bool>>;
+}
+
+/// One encrypted delivery attempt to a push service.
+#[derive(Clone, Debug)]
+pub struct PushDelivery {
+    pub payload: Vec<u8>,
+    pub urgency: PushUrgency,
+    pub ttl_seconds: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PushDeliveryOutcome {
+    Delivered,
+    /// 404/410: the browser dropped the subscription; it should be pruned.
+    Gone,
+    /// 429, 5xx or a transport error; `retry_after_ms` echoes `Retry-After` when present.
+    Retryable {
+        retry_after_ms: Option<u64>,
+    },
+    /// Any other rejection, redirects included; retrying will not help.
+    Rejected {
+        status: u16,
+    },
+}
+
+/// Encrypts (RFC 8291) and signs (VAPID, RFC 8292) pushes for a subscription.
+pub trait PushGateway: Send + Sync {
+    fn deliver(
+        &self,
+        subscription: &PushSubscription,
+        delivery: &PushDelivery,
+    ) -> BoxFuture<'_, DomainResult<PushDeliveryOutcome>>;
+}
@@ -0,0 +1,13 @@
+use std::collections::HashMap;
+
+use crate::DomainResult;
+
+use super::BoxFuture;
+
+/// Member reputation from the Markov engine, normalized to `0.0..=1.0`.
+pub trait ReputationLookup: Send + Sync {
+    /// Reputation of each of `user_ids` the source knows; unknown users are
+    /// left out. An error means reputation is unavailable as a whole.
+    fn reputations(&self, user_ids: &[String])
+    -> BoxFuture<'_, DomainResult<HashMap<String, f64>>>;
+}
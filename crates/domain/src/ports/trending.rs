@@ -0,0 +1,21 @@
+use crate::DomainResult;
+use crate::trending::TrendingSnapshot;
+
+use super::BoxFuture;
+
+/// Cache of worker-computed trending snapshots, keyed by scope.
+pub trait TrendingSnapshotStore: Send + Sync {
+    /// Replaces each snapshot's cached copy; entries lapse after `ttl_ms` so scopes
+    /// that fall quiet stop serving old results.
+    fn put_snapshots(
+        &self,
+        snapshots: &[TrendingSnapshot],
+        ttl_ms: u64,
+    ) -> BoxFuture<'_, DomainResult<()>>;
+
+    /// `None` reads the community-wide snapshot.
+    fn get_snapshot(
+        &self,
+        scope_id: Option<&str>,
+    ) -> BoxFuture<'_, DomainResult<Option<TrendingSnapshot>>>;
+}
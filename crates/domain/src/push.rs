@@ -0,0 +1,364 @@
+use std::sync::Arc;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::DomainResult;
+use crate::discovery::{NOTIF_TYPE_SIAGA, NotificationDelivery};
+use crate::error::DomainError;
+use crate::jobs::{JobDefaults, WebPushSendPayload, new_job, now_ms};
+use crate::notification_preferences::NotificationChannel;
+use crate::ports::jobs::{JobEnvelope, JobType};
+use crate::ports::push::PushSubscriptionRepository;
+
+const MAX_ENDPOINT_LENGTH: usize = 2_048;
+const MAX_SUBSCRIPTIONS_PER_USER: usize = 20;
+const P256DH_KEY_LENGTH: usize = 65;
+const AUTH_SECRET_LENGTH: usize = 16;
+/// Keeps the encrypted record under the 4096-byte limit push services accept.
+const MAX_PUSH_BODY_CHARS: usize = 1_000;
+/// Browser push services (FCM, Mozilla autopush, Apple, WNS). Endpoints must live
+/// on one of these hosts or a subdomain, so a subscription cannot point the worker
+/// at internal addresses.
+const PUSH_SERVICE_HOSTS: [&str; 5] = [
+    "fcm.googleapis.com",
+    "android.googleapis.com",
+    "push.services.mozilla.com",
+    "push.apple.com",
+    "notify.windows.com",
+];
+
+/// RFC 8030 `Urgency` header values.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PushUrgency {
+    VeryLow,
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl PushUrgency {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::VeryLow => "very-low",
+            Self::Low => "low",
+            Self::Normal => "normal",
+            Self::High => "high",
+        }
+    }
+}
+
+/// A browser push subscription as stored; keys are base64url without padding.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PushSubscription {
+    pub subscription_id: String,
+    pub user_id: String,
+    pub endpoint: String,
+    pub p256dh: String,
+    pub auth: String,
+    pub user_agent: Option<String>,
+    pub created_at_ms: i64,
+    pub updated_at_ms: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PushSubscriptionKeys {
+    pub p256dh: String,
+    pub auth: String,
+}
+
+/// Mirrors `PushSubscription.toJSON()` from the browser.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PushSubscriptionInput {
+    pub endpoint: String,
+    pub keys: PushSubscriptionKeys,
+    #[serde(default)]
+    pub user_agent: Option<String>,
+}
+
+/// JSON the service worker receives in the `push` event.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct WebPushMessage {
+    pub notification_id: String,
+    pub notification_type: String,
+    pub title: String,
+    pub body: String,
+    pub source_type: String,
+    pub source_id: String,
+    pub created_at_ms: i64,
+}
+
+#[derive(Clone)]
+pub struct PushSubscriptionService {
+    repository: Arc<dyn PushSubscriptionRepository>,
+}
+
+impl PushSubscriptionService {
+    pub fn new(repository: Arc<dyn PushSubscriptionRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Registers or refreshes a subscription; the same endpoint always maps to the same id.
+    pub async fn subscribe(
+        &self,
+        user_id: &str,
+        input: PushSubscriptionInput,
+    ) -> DomainResult<PushSubscription> {
+        validate_user_id(user_id)?;
+        let endpoint = validate_push_endpoint(&input.endpoint)?;
+        let p256dh = validate_key(&input.keys.p256dh, P256DH_KEY_LENGTH, "p256dh")?;
+        if !p256dh.starts_with('B') {
+            return Err(DomainError::Validation(
+                "p256dh must be an uncompressed P-256 point".into(),
+            ));
+        }
+        let auth = validate_key(&input.keys.auth, AUTH_SECRET_LENGTH, "auth")?;
+        let subscription_id = subscription_id_for_endpoint(&endpoint);
+        let existing = self.repository.list_for_user(user_id).await?;
+        let previous = existing
+            .iter()
+            .find(|subscription| subscription.subscription_id == subscription_id);
+        if previous.is_none() && existing.len() >= MAX_SUBSCRIPTIONS_PER_USER {
+            return Err(DomainError::Validation(format!(
+                "at most {MAX_SUBSCRIPTIONS_PER_USER} push subscriptions per user"
+            )));
+        }
+        let now_ms = now_ms();
+        let subscription = PushSubscription {
+            subscription_id,
+            user_id: user_id.to_string(),
+            endpoint,
+            p256dh,
+            auth,
+            user_agent: input
+                .user_agent
+                .map(|agent| agent.trim().chars().take(256).collect::<String>())
+                .filter(|agent| !agent.is_empty()),
+            created_at_ms: previous.map_or(now_ms, |previous| previous.created_at_ms),
+            updated_at_ms: now_ms,
+        };
+        self.repository.upsert_subscription(&subscription).await
+    }
+
+    pub async fn list(&self, user_id: &str) -> DomainResult<Vec<PushSubscription>> {
+        validate_user_id(user_id)?;
+        self.repository.list_for_user(user_id).await
+    }
+
+    pub async fn unsubscribe(&self, user_id: &str, subscription_id: &str) -> DomainResult<()> {
+        validate_user_id(user_id)?;
+        if self
+            .repository
+            .delete_subscription(user_id, subscription_id)
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(DomainError::NotFound)
+        }
+    }
+}
+
+/// The push job for a freshly ingested notification, when the recipient accepts push.
+/// Quiet-hours holds become the job's `run_at_ms`; siaga alerts go out with high urgency.
+pub fn web_push_job(delivery: &NotificationDelivery, defaults: JobDefaults) -> Option<JobEnvelope> {
+    if !delivery.delivers_to(NotificationChannel::Push) {
+        return None;
+    }
+    let notification = &delivery.notification;
+    let urgency = if notification
+        .notification_type
+        .eq_ignore_ascii_case(NOTIF_TYPE_SIAGA)
+    {
+        PushUrgency::High
+    } else {
+        PushUrgency::Normal
+    };
+    let payload = WebPushSendPayload {
+        user_id: notification.user_id.clone(),
+        message: WebPushMessage {
+            notification_id: notification.notification_id.clone(),
+            notification_type: notification.notification_type.clone(),
+            title: notification.title.clone(),
+            body: notification
+                .body
+                .chars()
+                .take(MAX_PUSH_BODY_CHARS)
+                .collect(),
+            source_type: notification.source_type.clone(),
+            source_id: notification.source_id.clone(),
+            created_at_ms: notification.created_at_ms,
+        },
+        urgency,
+        subscription_ids: None,
+    };
+    let job = new_job(
+        format!("web_push:{}", notification.notification_id),
+        JobType::WebPushSend,
+        serde_json::to_value(payload).ok()?,
+        notification.request_id.clone(),
+        notification.correlation_id.clone(),
+        defaults,
+    );
+    Some(match notification.deliver_at_ms {
+        Some(deliver_at_ms) => job.with_run_at(deliver_at_ms),
+        None => job,
+    })
+}
+
+pub fn subscription_id_for_endpoint(endpoint: &str) -> String {
+    let digest = Sha256::digest(endpoint.as_bytes());
+    hex::encode(&digest[..16])
+}
+
+/// Checked when subscribing and again by the worker before each send, so rows
+/// stored before the host allow-list existed are never delivered to.
+pub fn validate_push_endpoint(endpoint: &str) -> DomainResult<String> {
+    let endpoint = endpoint.trim();
+    if endpoint.is_empty() || endpoint.len() > MAX_ENDPOINT_LENGTH {
+        return Err(DomainError::Validation("endpoint is invalid".into()));
+    }
+    let Some(rest) = endpoint.strip_prefix("https://") else {
+        return Err(DomainError::Validation("endpoint must use https".into()));
+    };
+    if rest.is_empty() || rest.starts_with('/') || endpoint.chars().any(char::is_whitespace) {
+        return Err(DomainError::Validation("endpoint is invalid".into()));
+    }
+    // Userinfo, explicit ports and IP literals never appear in push service URLs.
+    let authority = rest
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if authority.contains(['@', ':', '[']) {
+        return Err(DomainError::Validation("endpoint is invalid".into()));
+    }
+    let allowed = PUSH_SERVICE_HOSTS.iter().any(|host| {
+        authority == *host
+            || authority
+                .strip_suffix(host)
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    });
+    if !allowed {
+        return Err(DomainError::Validation(
+            "endpoint must belong to a known push service".into(),
+        ));
+    }
+    Ok(endpoint.to_string())
+}
+
+fn validate_key(value: &str, expected_len: usize, label: &str) -> DomainResult<String> {
+    let trimmed = value.trim().trim_end_matches('=');
+    let decoded = URL_SAFE_NO_PAD
+        .decode(trimmed)
+        .map_err(|_| DomainError::Validation(format!("{label} must be base64url")))?;
+    if decoded.len() != expected_len {
+        return Err(DomainError::Validation(format!(
+            "{label} must decode to {expected_len} bytes"
+        )));
+    }
+    Ok(URL_SAFE_NO_PAD.encode(decoded))
+}
+
+fn validate_user_id(user_id: &str) -> DomainResult<()> {
+    if user_id.trim().is_empty() {
+        return Err(DomainError::Validation("user_id is required".into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::InAppNotification;
+    use crate::notification_preferences::NotificationChannels;
+
+    fn delivery(notification_type: &str, push: bool) -> NotificationDelivery {
+        NotificationDelivery {
+            notification: InAppNotification {
+                notification_id: "notif-1".into(),
+                user_id: "warga-1".into(),
+                actor_id: "user-2".into(),
+                actor_username: "budi".into(),
+                notification_type: notification_type.into(),
+                source_type: "siaga".into(),
+                source_id: "src-1".into(),
+                title: "Banjir".into(),
+                body: "Air naik di RT 03".into(),
+                payload: None,
+                created_at_ms: 1_000,
+                read_at_ms: None,
+                deliver_at_ms: Some(9_000),
+                privacy_level: None,
+                request_id: "req-1".into(),
+                correlation_id: "corr-1".into(),
+                dedupe_key: "dedupe-1".into(),
+                group_key: None,
+                in_app_hidden: false,
+            },
+            channels: NotificationChannels {
+                push,
+                ..NotificationChannels::default()
+            },
+        }
+    }
+
+    #[test]
+    fn web_push_job_respects_opt_out_hold_and_siaga_urgency() {
+        assert!(web_push_job(&delivery("vouch", false), JobDefaults::default()).is_none());
+
+        let job = web_push_job(&delivery(NOTIF_TYPE_SIAGA, true), JobDefaults::default())
+            .expect("push job");
+        assert_eq!(job.job_type, JobType::WebPushSend);
+        assert_eq!(job.job_id, "web_push:notif-1");
+        assert_eq!(job.run_at_ms, 9_000);
+        let payload: WebPushSendPayload = serde_json::from_value(job.payload).expect("payload");
+        assert_eq!(payload.urgency, PushUrgency::High);
+        assert_eq!(payload.message.title, "Banjir");
+
+        let job = web_push_job(&delivery("vouch", true), JobDefaults::default()).expect("job");
+        let payload: WebPushSendPayload = serde_json::from_value(job.payload).expect("payload");
+        assert_eq!(payload.urgency, PushUrgency::Normal);
+    }
+
+    #[test]
+    fn subscription_input_validation_normalizes_keys() {
+        assert!(validate_push_endpoint("http://fcm.googleapis.com/fcm/send/abc").is_err());
+        assert!(validate_push_endpoint("https://").is_err());
+        for endpoint in [
+            "https://fcm.googleapis.com/fcm/send/abc",
+            "https://updates.push.services.mozilla.com/wpush/v2/abc",
+            "https://web.push.apple.com/abc",
+            "https://db5p.notify.windows.com/w/?token=abc",
+        ] {
+            assert!(validate_push_endpoint(endpoint).is_ok(), "{endpoint}");
+        }
+        for endpoint in [
+            "https://push.example/abc",
+            "https://127.0.0.1/abc",
+            "https://[::1]/abc",
+            "https://169.254.169.254/latest/meta-data",
+            "https://fcm.googleapis.com:8443/abc",
+            "https://fcm.googleapis.com@10.0.0.1/abc",
+            "https://evilfcm.googleapis.com.attacker.test/abc",
+            "https://notfcm.googleapis.com.evil/abc",
+            "https://xnotify.windows.com/abc",
+        ] {
+            assert!(validate_push_endpoint(endpoint).is_err(), "{endpoint}");
+        }
+        let p256dh = URL_SAFE_NO_PAD.encode([4u8; 65]);
+        assert_eq!(
+            validate_key(&format!("{p256dh}="), 65, "p256dh").unwrap(),
+            p256dh
+        );
+        assert!(validate_key(&URL_SAFE_NO_PAD.encode([1u8; 15]), 16, "auth").is_err());
+        assert_eq!(
+            subscription_id_for_endpoint("https://push.example/a"),
+            subscription_id_for_endpoint("https://push.example/a")
+        );
+    }
+}
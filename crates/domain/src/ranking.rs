@@ -5,13 +5,22 @@ pub fn wilson_score(positive: u64, total: u64) -> f64 {
 }
 
 pub fn wilson_score_with_z(positive: u64, total: u64, z: f64) -> f64 {
-    if total == 0 {
+    wilson_score_weighted_with_z(positive as f64, total as f64, z)
+}
+
+/// Wilson lower bound over weighted votes, where each vote may count for more
+/// or less than one.
+pub fn wilson_score_weighted(positive: f64, total: f64) -> f64 {
+    wilson_score_weighted_with_z(positive, total, WILSON_Z_95)
+}
+
+pub fn wilson_score_weighted_with_z(positive: f64, total: f64, z: f64) -> f64 {
+    if !total.is_finite() || total <= 0.0 {
         return 0.0;
     }
 
-    let clamped_positive = positive.min(total);
-    let n = total as f64;
-    let p_hat = clamped_positive as f64 / n;
+    let n = total;
+    let p_hat = positive.clamp(0.0, total) / n;
     let z2 = z * z;
 
     let denominator = 1.0 + z2 / n;
@@ -49,4 +58,11 @@ mod tests {
     fn clamps_positive_votes_to_total() {
         assert_eq!(wilson_score(12, 10), wilson_score(10, 10));
     }
+
+    #[test]
+    fn weighted_score_matches_integer_votes_and_grows_with_weight() {
+        assert_approx(wilson_score_weighted(80.0, 100.0), wilson_score(80, 100));
+        assert_eq!(wilson_score_weighted(0.0, 0.0), 0.0);
+        assert!(wilson_score_weighted(1.75, 1.75) > wilson_score(1, 1));
+    }
 }
@@ -0,0 +1,505 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::DomainResult;
+use crate::discovery::{FeedItem, is_hidden_feed_item, is_open_privacy_level};
+use crate::error::DomainError;
+use crate::ports::BoxFuture;
+use crate::ports::trending::TrendingSnapshotStore;
+
+pub const DEFAULT_TRENDING_LIMIT: usize = 10;
+pub const MAX_TRENDING_LIMIT: usize = 50;
+/// Expected per-window count assumed for topics with little or no history, so a
+/// first appearance scores as a burst without dividing by zero.
+const BASELINE_FLOOR: f64 = 0.5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrendingTopicKind {
+    Concept,
+    Place,
+    Action,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrendingTopic {
+    pub kind: TrendingTopicKind,
+    /// Wikidata QID, `place:` id or action type, as tagged on the feed payload.
+    pub entity_id: String,
+    pub label: String,
+    /// Feed items mentioning the topic in the current window.
+    pub window_count: usize,
+    /// Mean items per window over the baseline windows before it.
+    pub baseline_count: f64,
+    /// Poisson z-score of `window_count` against the baseline.
+    pub burst_score: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrendingSnapshot {
+    /// `None` is the community-wide snapshot.
+    pub scope_id: Option<String>,
+    pub computed_at_ms: i64,
+    pub window_ms: i64,
+    pub baseline_windows: u32,
+    /// Feed items that fell in the current window.
+    pub item_count: usize,
+    pub topics: Vec<TrendingTopic>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrendingConfig {
+    pub window_ms: i64,
+    pub baseline_windows: u32,
+    pub min_window_count: usize,
+    pub max_topics: usize,
+}
+
+impl Default for TrendingConfig {
+    fn default() -> Self {
+        Self {
+            window_ms: 6 * 60 * 60 * 1000,
+            baseline_windows: 28,
+            min_window_count: 3,
+            max_topics: MAX_TRENDING_LIMIT,
+        }
+    }
+}
+
+impl TrendingConfig {
+    /// Oldest `occurred_at_ms` that still counts towards a baseline.
+    pub fn horizon_start_ms(&self, now_ms: i64) -> i64 {
+        now_ms.saturating_sub(
+            self.window_ms
+                .saturating_mul(i64::from(self.baseline_windows) + 1),
+        )
+    }
+}
+
+type TopicKey = (TrendingTopicKind, String);
+
+#[derive(Default)]
+struct TopicCounts {
+    window: usize,
+    baseline: usize,
+}
+
+#[derive(Default)]
+struct ScopeCounts {
+    item_count: usize,
+    topics: HashMap<TopicKey, TopicCounts>,
+}
+
+/// Builds the community-wide snapshot followed by one snapshot per scope seen in
+/// `items`; see [`TrendingAccumulator`].
+pub fn compute_trending(
+    items: &[FeedItem],
+    now_ms: i64,
+    config: &TrendingConfig,
+) -> Vec<TrendingSnapshot> {
+    let mut accumulator = TrendingAccumulator::new(now_ms, *config);
+    for item in items {
+        accumulator.add(item);
+    }
+    accumulator.finish()
+}
+
+/// Sliding-window topic counts fed one item at a time, so callers can page
+/// through the feed without holding it. Only open, unhidden items inside the
+/// horizon count, which keeps snapshots safe to serve to anyone; each topic is
+/// counted at most once per item.
+pub struct TrendingAccumulator {
+    now_ms: i64,
+    window_start_ms: i64,
+    horizon_start_ms: i64,
+    config: TrendingConfig,
+    global: ScopeCounts,
+    scopes: BTreeMap<String, ScopeCounts>,
+    labels: HashMap<TopicKey, String>,
+}
+
+impl TrendingAccumulator {
+    pub fn new(now_ms: i64, config: TrendingConfig) -> Self {
+        Self {
+            now_ms,
+            window_start_ms: now_ms.saturating_sub(config.window_ms),
+            horizon_start_ms: config.horizon_start_ms(now_ms),
+            config,
+            global: ScopeCounts::default(),
+            scopes: BTreeMap::new(),
+            labels: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, item: &FeedItem) {
+        if item.occurred_at_ms < self.horizon_start_ms
+            || item.occurred_at_ms > self.now_ms
+            || is_hidden_feed_item(item)
+            || !is_open_privacy_level(item.privacy_level.as_deref())
+        {
+            return;
+        }
+        let in_window = item.occurred_at_ms > self.window_start_ms;
+        let topics = feed_item_topics(item, &mut self.labels);
+        let mut targets = vec![&mut self.global];
+        if let Some(scope_id) = item.scope_id.as_deref().map(str::trim)
+            && !scope_id.is_empty()
+        {
+            targets.push(self.scopes.entry(scope_id.to_string()).or_default());
+        }
+        for counts in targets {
+            if in_window {
+                counts.item_count += 1;
+            }
+            for key in &topics {
+                let topic = counts.topics.entry(key.clone()).or_default();
+                if in_window {
+                    topic.window += 1;
+                } else {
+                    topic.baseline += 1;
+                }
+            }
+        }
+    }
+
+    /// The community-wide snapshot first, then scopes in id order.
+    pub fn finish(self) -> Vec<TrendingSnapshot> {
+        let Self {
+            now_ms,
+            config,
+            global,
+            scopes,
+            labels,
+            ..
+        } = self;
+        let mut snapshots = vec![snapshot(None, global, &labels, now_ms, &config)];
+        snapshots.extend(
+            scopes.into_iter().map(|(scope_id, counts)| {
+                snapshot(Some(scope_id), counts, &labels, now_ms, &config)
+            }),
+        );
+        snapshots
+    }
+}
+
+pub fn normalize_trending_limit(limit: Option<usize>) -> DomainResult<usize> {
+    let limit = limit.unwrap_or(DEFAULT_TRENDING_LIMIT);
+    if !(1..=MAX_TRENDING_LIMIT).contains(&limit) {
+        Err(DomainError::Validation(format!(
+            "trending limit must be between 1 and {MAX_TRENDING_LIMIT}"
+        )))
+    } else {
+        Ok(limit)
+    }
+}
+
+/// Process-local store for tests and single-node development.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryTrendingSnapshotStore {
+    inner: Arc<Mutex<HashMap<Option<String>, CachedSnapshot>>>,
+}
+
+#[derive(Clone, Debug)]
+struct CachedSnapshot {
+    snapshot: TrendingSnapshot,
+    expires_at: Instant,
+}
+
+impl InMemoryTrendingSnapshotStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TrendingSnapshotStore for InMemoryTrendingSnapshotStore {
+    fn put_snapshots(
+        &self,
+        snapshots: &[TrendingSnapshot],
+        ttl_ms: u64,
+    ) -> BoxFuture<'_, DomainResult<()>> {
+        let snapshots = snapshots.to_vec();
+        let inner = self.inner.clone();
+        Box::pin(async move {
+            let expires_at = Instant::now() + Duration::from_millis(ttl_ms.max(1));
+            let mut guard = inner.lock().expect("trending store lock");
+            for snapshot in snapshots {
+                guard.insert(
+                    snapshot.scope_id.clone(),
+                    CachedSnapshot {
+                        snapshot,
+                        expires_at,
+                    },
+                );
+            }
+            Ok(())
+        })
+    }
+
+    fn get_snapshot(
+        &self,
+        scope_id: Option<&str>,
+    ) -> BoxFuture<'_, DomainResult<Option<TrendingSnapshot>>> {
+        let scope_id = scope_id.map(str::to_string);
+        let inner = self.inner.clone();
+        Box::pin(async move {
+            let mut guard = inner.lock().expect("trending store lock");
+            match guard.get(&scope_id) {
+                Some(entry) if Instant::now() >= entry.expires_at => {
+                    guard.remove(&scope_id);
+                    Ok(None)
+                }
+                Some(entry) => Ok(Some(entry.snapshot.clone())),
+                None => Ok(None),
+            }
+        })
+    }
+}
+
+fn snapshot(
+    scope_id: Option<String>,
+    counts: ScopeCounts,
+    labels: &HashMap<TopicKey, String>,
+    now_ms: i64,
+    config: &TrendingConfig,
+) -> TrendingSnapshot {
+    let baseline_windows = f64::from(config.baseline_windows.max(1));
+    let mut topics = counts
+        .topics
+        .into_iter()
+        .filter(|(_, counts)| counts.window >= config.min_window_count.max(1))
+        .filter_map(|(key, counts)| {
+            let baseline_count = counts.baseline as f64 / baseline_windows;
+            let expected = baseline_count.max(BASELINE_FLOOR);
+            let burst_score = (counts.window as f64 - expected) / expected.sqrt();
+            (burst_score > 0.0).then(|| TrendingTopic {
+                label: labels.get(&key).cloned().unwrap_or_else(|| key.1.clone()),
+                kind: key.0,
+                entity_id: key.1,
+                window_count: counts.window,
+                baseline_count,
+                burst_score,
+            })
+        })
+        .collect::<Vec<_>>();
+    topics.sort_by(|left, right| {
+        right
+            .burst_score
+            .total_cmp(&left.burst_score)
+            .then_with(|| right.window_count.cmp(&left.window_count))
+            .then_with(|| left.kind.cmp(&right.kind))
+            .then_with(|| left.entity_id.cmp(&right.entity_id))
+    });
+    topics.truncate(config.max_topics);
+    TrendingSnapshot {
+        scope_id,
+        computed_at_ms: now_ms,
+        window_ms: config.window_ms,
+        baseline_windows: config.baseline_windows,
+        item_count: counts.item_count,
+        topics,
+    }
+}
+
+/// Topics from `payload.enrichment.tags`, recording any display labels found in
+/// `payload.enrichment.labels` along the way.
+fn feed_item_topics(item: &FeedItem, labels: &mut HashMap<TopicKey, String>) -> HashSet<TopicKey> {
+    let mut topics = HashSet::new();
+    let Some(enrichment) = item
+        .payload
+        .as_ref()
+        .and_then(|payload| payload.get("enrichment"))
+    else {
+        return topics;
+    };
+    let tags = enrichment.get("tags");
+    for (kind, field) in [
+        (TrendingTopicKind::Concept, "concept_qids"),
+        (TrendingTopicKind::Place, "place_ids"),
+        (TrendingTopicKind::Action, "action_types"),
+    ] {
+        let ids = tags
+            .and_then(|tags| tags.get(field))
+            .and_then(serde_json::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(serde_json::Value::as_str)
+            .map(str::trim)
+            .filter(|id| !id.is_empty());
+        for id in ids {
+            topics.insert((kind, id.to_string()));
+        }
+    }
+
+    let label_lists = enrichment.get("labels");
+    for (kind, field, id_field, label_fields) in [
+        (
+            TrendingTopicKind::Concept,
+            "concepts",
+            "qid",
+            &["label_id", "label_en"][..],
+        ),
+        (
+            TrendingTopicKind::Place,
+            "places",
+            "place_id",
+            &["name"][..],
+        ),
+        (
+            TrendingTopicKind::Action,
+            "actions",
+            "action_type",
+            &["display_label"][..],
+        ),
+    ] {
+        let entries = label_lists
+            .and_then(|lists| lists.get(field))
+            .and_then(serde_json::Value::as_array)
+            .into_iter()
+            .flatten();
+        for entry in entries {
+            let Some(id) = entry.get(id_field).and_then(serde_json::Value::as_str) else {
+                continue;
+            };
+            let key = (kind, id.trim().to_string());
+            if !topics.contains(&key) || labels.contains_key(&key) {
+                continue;
+            }
+            let label = label_fields
+                .iter()
+                .filter_map(|field| entry.get(*field).and_then(serde_json::Value::as_str))
+                .map(str::trim)
+                .find(|label| !label.is_empty());
+            if let Some(label) = label {
+                labels.insert(key, label.to_string());
+            }
+        }
+    }
+    topics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR_MS: i64 = 60 * 60 * 1000;
+    const NOW_MS: i64 = 1_000 * HOUR_MS;
+
+    fn item(feed_id: &str, scope_id: &str, occurred_at_ms: i64, qids: &[&str]) -> FeedItem {
+        FeedItem {
+            feed_id: feed_id.to_string(),
+            source_type: "ontology_note".to_string(),
+            source_id: feed_id.to_string(),
+            actor_id: "warga-1".to_string(),
+            actor_username: "warga-1".to_string(),
+            title: feed_id.to_string(),
+            summary: None,
+            scope_id: Some(scope_id.to_string()),
+            privacy_level: Some("public".to_string()),
+            occurred_at_ms,
+            created_at_ms: occurred_at_ms,
+            request_id: format!("req-{feed_id}"),
+            correlation_id: format!("corr-{feed_id}"),
+            participant_ids: vec![],
+            payload: Some(serde_json::json!({
+                "enrichment": {
+                    "tags": {
+                        "concept_qids": qids,
+                        "action_types": [],
+                        "place_ids": [],
+                    },
+                    "labels": {
+                        "concepts": qids.iter().map(|qid| serde_json::json!({
+                            "concept_id": qid,
+                            "qid": qid,
+                            "label_id": format!("label {qid}"),
+                            "label_en": null,
+                            "verified": true,
+                        })).collect::<Vec<_>>(),
+                        "actions": [],
+                        "places": [],
+                    }
+                }
+            })),
+            location: None,
+            distance_m: None,
+        }
+    }
+
+    fn config() -> TrendingConfig {
+        TrendingConfig {
+            window_ms: 6 * HOUR_MS,
+            baseline_windows: 4,
+            min_window_count: 2,
+            max_topics: 10,
+        }
+    }
+
+    #[test]
+    fn bursts_rank_above_steady_topics() {
+        let mut items = Vec::new();
+        // Q1 (flood) is steady: two items per window across the whole horizon.
+        for window in 0..5 {
+            for offset in [1, 2] {
+                let at = NOW_MS - window * 6 * HOUR_MS - offset * HOUR_MS;
+                items.push(item(
+                    &format!("steady-{window}-{offset}"),
+                    "rt-1",
+                    at,
+                    &["Q1"],
+                ));
+            }
+        }
+        // Q2 (power outage) never appeared before and spikes now.
+        for offset in 0..4 {
+            items.push(item(
+                &format!("burst-{offset}"),
+                "rt-2",
+                NOW_MS - offset * HOUR_MS,
+                &["Q2", "Q2"],
+            ));
+        }
+
+        let snapshots = compute_trending(&items, NOW_MS, &config());
+        let global = &snapshots[0];
+        assert_eq!(global.scope_id, None);
+        assert_eq!(global.item_count, 6);
+        assert_eq!(global.topics.len(), 1, "steady topic is not trending");
+        let burst = &global.topics[0];
+        assert_eq!(burst.entity_id, "Q2");
+        assert_eq!(burst.kind, TrendingTopicKind::Concept);
+        assert_eq!(burst.label, "label Q2");
+        assert_eq!(burst.window_count, 4, "counted once per item");
+        assert!(burst.burst_score > 4.0);
+
+        let scopes = snapshots[1..]
+            .iter()
+            .map(|snapshot| (snapshot.scope_id.clone().unwrap(), snapshot.topics.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            scopes,
+            vec![("rt-1".to_string(), 0), ("rt-2".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn private_hidden_and_stale_items_are_ignored() {
+        let mut private = item("private", "rt-1", NOW_MS - HOUR_MS, &["Q9"]);
+        private.privacy_level = Some("l2".to_string());
+        let mut hidden = item("hidden", "rt-1", NOW_MS - HOUR_MS, &["Q9"]);
+        hidden.payload.as_mut().unwrap()["lifecycle"] = serde_json::json!({ "hidden": true });
+        let stale = item("stale", "rt-1", NOW_MS - 40 * HOUR_MS, &["Q9"]);
+        let visible = item("visible", "rt-1", NOW_MS - HOUR_MS, &["Q9"]);
+
+        let snapshots = compute_trending(&[private, hidden, stale, visible], NOW_MS, &config());
+        assert_eq!(snapshots[0].item_count, 1);
+        assert!(snapshots[0].topics.is_empty(), "below min_window_count");
+        assert!(normalize_trending_limit(Some(0)).is_err());
+        assert_eq!(
+            normalize_trending_limit(None).unwrap(),
+            DEFAULT_TRENDING_LIMIT
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use reqwest::Client as HttpClient;
+use rusty_s3::{Bucket as S3Bucket, Credentials as S3Credentials, S3Action, UrlStyle};
+use tracing::warn;
+
+use crate::config::AppConfig;
+
+const CHAT_ATTACHMENT_STORAGE_DIR: &str = "gotong-chat-attachments";
+/// Objects stored per attachment: metadata, original bytes and thumbnail.
+pub const CHAT_ATTACHMENT_OBJECT_SUFFIXES: [&str; 3] = ["json", "bin", "thumb.jpg"];
+
+/// Where chat attachment bytes live. The API writes and serves them; the worker's
+/// retention sweep removes them.
+#[derive(Clone)]
+pub enum ChatAttachmentStorage {
+    Local {
+        root: PathBuf,
+    },
+    S3 {
+        client: HttpClient,
+        bucket: Box<S3Bucket>,
+        credentials: S3Credentials,
+        key_prefix: String,
+    },
+}
+
+impl ChatAttachmentStorage {
+    /// Resolves `chat_attachment_storage_backend`. `auto` uses S3 outside the test
+    /// environment and falls back to local storage unless S3 was requested
+    /// explicitly or the environment is production.
+    pub async fn from_config(config: &AppConfig) -> anyhow::Result<Self> {
+        let requested_backend = config
+            .chat_attachment_storage_backend
+            .trim()
+            .to_ascii_lowercase();
+        if requested_backend == "local"
+            || (requested_backend == "auto" && config.app_env.eq_ignore_ascii_case("test"))
+        {
+            return Ok(Self::local(config));
+        }
+
+        match Self::s3(config).await {
+            Ok(storage) => Ok(storage),
+            Err(err) => {
+                if requested_backend == "s3" || config.is_production() {
+                    return Err(anyhow::anyhow!(
+                        "failed to initialize S3 chat attachment storage: {err}"
+                    ));
+                }
+                warn!(
+                    error = %err,
+                    "falling back to local chat attachment storage (set CHAT_ATTACHMENT_STORAGE_BACKEND=s3 to fail hard)"
+                );
+                Ok(Self::local(config))
+            }
+        }
+    }
+
+    pub fn local(config: &AppConfig) -> Self {
+        Self::Local {
+            root: std::env::temp_dir()
+                .join(CHAT_ATTACHMENT_STORAGE_DIR)
+                .join(config.app_env.trim().to_ascii_lowercase()),
+        }
+    }
+
+    async fn s3(config: &AppConfig) -> anyhow::Result<Self> {
+        let bucket_name = config.s3_bucket.trim();
+        if bucket_name.is_empty() {
+            anyhow::bail!("s3_bucket is empty");
+        }
+        if config.s3_endpoint.trim().is_empty() {
+            anyhow::bail!("s3_endpoint is empty");
+        }
+
+        let endpoint = config
+            .s3_endpoint
+            .trim()
+            .parse::<reqwest::Url>()
+            .map_err(|err| {
+                anyhow::anyhow!("invalid s3_endpoint '{}': {err}", config.s3_endpoint)
+            })?;
+        let bucket = S3Bucket::new(
+            endpoint,
+            UrlStyle::Path,
+            bucket_name.to_string(),
+            config.s3_region.clone(),
+        )
+        .map_err(|err| anyhow::anyhow!("invalid S3 bucket configuration: {err}"))?;
+        let credentials =
+            S3Credentials::new(config.s3_access_key.clone(), config.s3_secret_key.clone());
+        let client = HttpClient::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        let head_bucket_url = bucket
+            .head_bucket(Some(&credentials))
+            .sign(Duration::from_secs(30));
+        let head_bucket_response = client.head(head_bucket_url).send().await?;
+        if !head_bucket_response.status().is_success() {
+            anyhow::bail!(
+                "S3 head bucket failed with status {}",
+                head_bucket_response.status()
+            );
+        }
+
+        Ok(Self::S3 {
+            client,
+            bucket: Box::new(bucket),
+            credentials,
+            key_prefix: normalized_key_prefix(config),
+        })
+    }
+
+    /// Removes the metadata, original and thumbnail of an attachment. Metadata goes
+    /// first so signed download links stop resolving even if a later delete fails.
+    pub async fn remove(&self, attachment_id: &str) -> anyhow::Result<()> {
+        for suffix in CHAT_ATTACHMENT_OBJECT_SUFFIXES {
+            match self {
+                Self::Local { root } => {
+                    let path = root.join(format!("{attachment_id}.{suffix}"));
+                    match tokio::fs::remove_file(&path).await {
+                        Ok(()) => {}
+                        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                        Err(err) => return Err(err.into()),
+                    }
+                }
+                Self::S3 {
+                    client,
+                    bucket,
+                    credentials,
+                    key_prefix,
+                } => {
+                    let key = format!("{key_prefix}/{attachment_id}.{suffix}");
+                    let url = bucket
+                        .delete_object(Some(credentials), &key)
+                        .sign(Duration::from_secs(60));
+                    let status = client.delete(url).send().await?.status();
+                    if !status.is_success() && status != reqwest::StatusCode::NOT_FOUND {
+                        anyhow::bail!("S3 delete of {key} failed with status {status}");
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn normalized_key_prefix(config: &AppConfig) -> String {
+    let base_prefix = config.chat_attachment_s3_prefix.trim().trim_matches('/');
+    let base_prefix = if base_prefix.is_empty() {
+        "chat-attachments"
+    } else {
+        base_prefix
+    };
+    format!(
+        "{base_prefix}/{}",
+        config.app_env.trim().to_ascii_lowercase()
+    )
+}
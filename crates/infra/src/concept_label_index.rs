@@ -0,0 +1,701 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+
+use gotong_domain::DomainResult;
+use gotong_domain::geo::GeoRadius;
+use gotong_domain::ontology::{
+    NoteFeedbackCounts, NoteFeedbackVote, OntologyActionRef, OntologyConcept, OntologyConceptLabel,
+    OntologyConceptLabels, OntologyConceptMergeResult, OntologyConceptRedirect,
+    OntologyConceptUsage, OntologyEdgeKind, OntologyNote, OntologyNoteCreate, OntologyNoteUpdate,
+    OntologyNoteVersion, OntologyPlaceRef, OntologyTripleCreate,
+};
+use gotong_domain::ontology_autocomplete::{ConceptLabelMatch, normalize_label};
+use gotong_domain::ontology_export::{
+    OntologyBroaderEdge, OntologyExportFilter, OntologyExportGraph,
+};
+use gotong_domain::ontology_gazetteer::{AdminArea, AdminLevel};
+use gotong_domain::ontology_query::{OntologyNoteMatch, OntologyNoteQuery};
+use gotong_domain::ports::BoxFuture;
+use gotong_domain::ports::ontology::{ConceptLabelIndex, OntologyRepository};
+
+/// Concepts read per page while rebuilding the index.
+const REBUILD_PAGE_SIZE: usize = 1_000;
+/// Fuzzy candidates gathered per requested match before the walk stops; a
+/// short query with one edit would otherwise visit most of the trie.
+const FUZZY_CANDIDATE_FACTOR: usize = 4;
+
+/// Character trie over normalized labels. Every word start of a label is
+/// inserted, so "bandang" finds "banjir bandang". Replaced labels are
+/// tombstoned and the trie is rebuilt once tombstones outnumber live labels.
+#[derive(Default)]
+pub struct TrieConceptLabelIndex {
+    trie: RwLock<LabelTrie>,
+}
+
+#[derive(Default)]
+struct LabelTrie {
+    nodes: Vec<TrieNode>,
+    labels: Vec<Option<IndexedLabel>>,
+    by_qid: HashMap<String, Vec<usize>>,
+    tombstones: usize,
+}
+
+#[derive(Default)]
+struct TrieNode {
+    children: BTreeMap<char, usize>,
+    labels: Vec<usize>,
+}
+
+struct IndexedLabel {
+    qid: String,
+    label: OntologyConceptLabel,
+}
+
+impl TrieConceptLabelIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a fresh trie from every concept in `repo` and swaps it in, so
+    /// labels written by other processes (such as the Wikidata importer) show up.
+    pub async fn rebuild_from(&self, repo: &dyn OntologyRepository) -> DomainResult<usize> {
+        let mut trie = LabelTrie::default();
+        let mut after_qid: Option<String> = None;
+        let mut concepts = 0usize;
+        loop {
+            let page = repo
+                .list_concept_labels(after_qid.as_deref(), REBUILD_PAGE_SIZE)
+                .await?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            after_qid = Some(last.qid.clone());
+            let page_len = page.len();
+            for labels in page {
+                trie.replace(&labels.qid, labels.labels);
+            }
+            concepts += page_len;
+            if page_len < REBUILD_PAGE_SIZE {
+                break;
+            }
+        }
+        *self.trie.write().unwrap_or_else(|err| err.into_inner()) = trie;
+        Ok(concepts)
+    }
+
+    fn replace_blocking(&self, labels: &OntologyConceptLabels) {
+        let mut trie = self.trie.write().unwrap_or_else(|err| err.into_inner());
+        trie.replace(&labels.qid, labels.labels.clone());
+    }
+
+    fn search_blocking(&self, query: &str, limit: usize) -> Vec<ConceptLabelMatch> {
+        let trie = self.trie.read().unwrap_or_else(|err| err.into_inner());
+        trie.search(query, limit)
+    }
+}
+
+impl LabelTrie {
+    fn replace(&mut self, qid: &str, labels: Vec<OntologyConceptLabel>) {
+        self.remove(qid);
+        let mut ids = Vec::with_capacity(labels.len());
+        for label in labels {
+            let key = normalize_label(&label.label);
+            if key.is_empty() {
+                continue;
+            }
+            let id = self.labels.len();
+            self.labels.push(Some(IndexedLabel {
+                qid: qid.to_string(),
+                label,
+            }));
+            self.insert_key(&key, id);
+            ids.push(id);
+        }
+        if !ids.is_empty() {
+            self.by_qid.insert(qid.to_string(), ids);
+        }
+        if self.tombstones > 1_024 && self.tombstones > self.labels.len() / 2 {
+            self.compact();
+        }
+    }
+
+    fn remove(&mut self, qid: &str) {
+        for id in self.by_qid.remove(qid).unwrap_or_default() {
+            if self.labels[id].take().is_some() {
+                self.tombstones += 1;
+            }
+        }
+    }
+
+    fn insert_key(&mut self, key: &str, id: usize) {
+        let word_starts = std::iter::once(0).chain(
+            key.char_indices()
+                .filter(|(_, ch)| *ch == ' ')
+                .map(|(index, _)| index + 1),
+        );
+        for start in word_starts {
+            let mut node = self.root();
+            for ch in key[start..].chars() {
+                node = match self.nodes[node].children.get(&ch) {
+                    Some(child) => *child,
+                    None => {
+                        self.nodes.push(TrieNode::default());
+                        let child = self.nodes.len() - 1;
+                        self.nodes[node].children.insert(ch, child);
+                        child
+                    }
+                };
+            }
+            if !self.nodes[node].labels.contains(&id) {
+                self.nodes[node].labels.push(id);
+            }
+        }
+    }
+
+    fn root(&mut self) -> usize {
+        if self.nodes.is_empty() {
+            self.nodes.push(TrieNode::default());
+        }
+        0
+    }
+
+    fn compact(&mut self) {
+        let live = std::mem::take(&mut self.labels)
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        *self = Self::default();
+        let mut grouped: BTreeMap<String, Vec<OntologyConceptLabel>> = BTreeMap::new();
+        for item in live {
+            grouped.entry(item.qid).or_default().push(item.label);
+        }
+        for (qid, labels) in grouped {
+            self.replace(&qid, labels);
+        }
+    }
+
+    /// Prefix matches first, shortest completion first; then, when the query
+    /// is long enough to tolerate typos, labels whose closest prefix is within
+    /// one (4-6 chars) or two (7+ chars) edits.
+    fn search(&self, query: &str, limit: usize) -> Vec<ConceptLabelMatch> {
+        let mut found: HashMap<usize, usize> = HashMap::new();
+        let mut order: Vec<usize> = Vec::new();
+        let query = query.chars().collect::<Vec<_>>();
+        if self.nodes.is_empty() || query.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+
+        let mut node = Some(0usize);
+        for ch in &query {
+            node = node.and_then(|node| self.nodes[node].children.get(ch).copied());
+        }
+        if let Some(node) = node {
+            self.collect_subtree(node, 0, limit, &mut found, &mut order);
+        }
+
+        if max_fuzzy_edits(query.len()) > 0 && self.live_count(&order) < limit {
+            let first_row = (0..=query.len()).collect::<Vec<_>>();
+            let mut fuzzy: HashMap<usize, usize> = HashMap::new();
+            let cap = limit * FUZZY_CANDIDATE_FACTOR;
+            self.fuzzy_walk(0, &query, &first_row, usize::MAX, cap, &mut fuzzy);
+            let mut fuzzy = fuzzy.into_iter().collect::<Vec<_>>();
+            fuzzy.sort_by_key(|(id, distance)| (*distance, *id));
+            for (id, distance) in fuzzy {
+                if let std::collections::hash_map::Entry::Vacant(entry) = found.entry(id) {
+                    entry.insert(distance);
+                    order.push(id);
+                }
+            }
+        }
+
+        let mut matches = order
+            .into_iter()
+            .filter_map(|id| {
+                let item = self.labels[id].as_ref()?;
+                Some(ConceptLabelMatch {
+                    qid: item.qid.clone(),
+                    label: item.label.label.clone(),
+                    lang: item.label.lang.clone(),
+                    alias: item.label.alias,
+                    distance: found[&id],
+                })
+            })
+            .collect::<Vec<_>>();
+        matches.sort_by_key(|item| (item.distance, item.label.chars().count()));
+        matches.truncate(limit);
+        matches
+    }
+
+    fn live_count(&self, ids: &[usize]) -> usize {
+        ids.iter().filter(|id| self.labels[**id].is_some()).count()
+    }
+
+    /// Breadth first, so shorter completions are collected before longer ones.
+    fn collect_subtree(
+        &self,
+        start: usize,
+        distance: usize,
+        limit: usize,
+        found: &mut HashMap<usize, usize>,
+        order: &mut Vec<usize>,
+    ) {
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            for id in &self.nodes[node].labels {
+                if self.labels[*id].is_none() || found.contains_key(id) {
+                    continue;
+                }
+                found.insert(*id, distance);
+                order.push(*id);
+                if self.live_count(order) >= limit {
+                    return;
+                }
+            }
+            queue.extend(self.nodes[node].children.values().copied());
+        }
+    }
+
+    /// Levenshtein rows down the trie. `best` is the smallest distance between
+    /// the query and any prefix on the path so far; every label at or below a
+    /// node is within `best` edits.
+    fn fuzzy_walk(
+        &self,
+        node: usize,
+        query: &[char],
+        row: &[usize],
+        best: usize,
+        cap: usize,
+        out: &mut HashMap<usize, usize>,
+    ) {
+        if out.len() >= cap {
+            return;
+        }
+        let max_edits = max_fuzzy_edits(query.len());
+        let best = best.min(row[query.len()]);
+        if best <= max_edits {
+            for id in &self.nodes[node].labels {
+                if self.labels[*id].is_some() {
+                    let distance = out.entry(*id).or_insert(best);
+                    *distance = (*distance).min(best);
+                }
+            }
+        }
+        let row_min = row.iter().copied().min().unwrap_or(usize::MAX);
+        if row_min > max_edits && best > max_edits {
+            return;
+        }
+        for (ch, child) in &self.nodes[node].children {
+            let mut next = Vec::with_capacity(row.len());
+            next.push(row[0] + 1);
+            for (index, query_ch) in query.iter().enumerate() {
+                let substitution = row[index] + usize::from(query_ch != ch);
+                next.push(substitution.min(row[index + 1] + 1).min(next[index] + 1));
+            }
+            self.fuzzy_walk(*child, query, &next, best, cap, out);
+        }
+    }
+}
+
+fn max_fuzzy_edits(query_chars: usize) -> usize {
+    match query_chars {
+        0..=3 => 0,
+        4..=6 => 1,
+        _ => 2,
+    }
+}
+
+impl ConceptLabelIndex for TrieConceptLabelIndex {
+    fn index_concept(&self, labels: &OntologyConceptLabels) -> BoxFuture<'_, DomainResult<()>> {
+        self.replace_blocking(labels);
+        Box::pin(async { Ok(()) })
+    }
+
+    fn remove_concept(&self, qid: &str) -> BoxFuture<'_, DomainResult<()>> {
+        self.trie
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .remove(qid);
+        Box::pin(async { Ok(()) })
+    }
+
+    fn search(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> BoxFuture<'_, DomainResult<Vec<ConceptLabelMatch>>> {
+        let matches = self.search_blocking(query, limit);
+        Box::pin(async move { Ok(matches) })
+    }
+}
+
+/// Keeps the label index in step with concept and label writes. Index
+/// failures are logged and swallowed: the repository is the source of truth
+/// and the index is rebuilt from it.
+pub struct IndexedOntologyRepository {
+    inner: Arc<dyn OntologyRepository>,
+    index: Arc<dyn ConceptLabelIndex>,
+}
+
+impl IndexedOntologyRepository {
+    pub fn new(inner: Arc<dyn OntologyRepository>, index: Arc<dyn ConceptLabelIndex>) -> Self {
+        Self { inner, index }
+    }
+
+    async fn sync(&self, qid: &str) {
+        let result = match self.inner.get_concept_labels(&[qid.to_string()]).await {
+            Ok(labels) => match labels.into_iter().find(|labels| labels.qid == qid) {
+                Some(labels) => self.index.index_concept(&labels).await,
+                None => self.index.remove_concept(qid).await,
+            },
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            tracing::warn!(qid = %qid, error = %err, "concept label index sync failed");
+        }
+    }
+}
+
+impl OntologyRepository for IndexedOntologyRepository {
+    fn upsert_concept(
+        &self,
+        concept: &OntologyConcept,
+    ) -> BoxFuture<'_, DomainResult<OntologyConcept>> {
+        let concept = concept.clone();
+        Box::pin(async move {
+            let stored = self.inner.upsert_concept(&concept).await?;
+            self.sync(&stored.qid).await;
+            Ok(stored)
+        })
+    }
+
+    fn add_broader_edge(
+        &self,
+        narrower_concept_id: &str,
+        broader_concept_id: &str,
+    ) -> BoxFuture<'_, DomainResult<()>> {
+        self.inner
+            .add_broader_edge(narrower_concept_id, broader_concept_id)
+    }
+
+    fn create_note(&self, note: &OntologyNoteCreate) -> BoxFuture<'_, DomainResult<OntologyNote>> {
+        self.inner.create_note(note)
+    }
+
+    fn write_triples(&self, triples: &[OntologyTripleCreate]) -> BoxFuture<'_, DomainResult<()>> {
+        self.inner.write_triples(triples)
+    }
+
+    fn list_note_edge_targets(
+        &self,
+        note_id: &str,
+        edge: OntologyEdgeKind,
+    ) -> BoxFuture<'_, DomainResult<Vec<String>>> {
+        self.inner.list_note_edge_targets(note_id, edge)
+    }
+
+    fn get_concept_by_qid(
+        &self,
+        qid: &str,
+    ) -> BoxFuture<'_, DomainResult<Option<OntologyConcept>>> {
+        self.inner.get_concept_by_qid(qid)
+    }
+
+    fn get_concepts_by_qids(
+        &self,
+        qids: &[String],
+    ) -> BoxFuture<'_, DomainResult<Vec<OntologyConcept>>> {
+        self.inner.get_concepts_by_qids(qids)
+    }
+
+    fn get_actions_by_types(
+        &self,
+        action_types: &[String],
+    ) -> BoxFuture<'_, DomainResult<Vec<OntologyActionRef>>> {
+        self.inner.get_actions_by_types(action_types)
+    }
+
+    fn get_places_by_ids(
+        &self,
+        place_ids: &[String],
+    ) -> BoxFuture<'_, DomainResult<Vec<OntologyPlaceRef>>> {
+        self.inner.get_places_by_ids(place_ids)
+    }
+
+    fn upsert_admin_areas(&self, areas: &[AdminArea]) -> BoxFuture<'_, DomainResult<()>> {
+        self.inner.upsert_admin_areas(areas)
+    }
+
+    fn get_admin_areas(&self, codes: &[String]) -> BoxFuture<'_, DomainResult<Vec<AdminArea>>> {
+        self.inner.get_admin_areas(codes)
+    }
+
+    fn list_admin_areas_covering(
+        &self,
+        geohash: &str,
+    ) -> BoxFuture<'_, DomainResult<Vec<AdminArea>>> {
+        self.inner.list_admin_areas_covering(geohash)
+    }
+
+    fn list_admin_areas_near(
+        &self,
+        near: &GeoRadius,
+        level: AdminLevel,
+    ) -> BoxFuture<'_, DomainResult<Vec<AdminArea>>> {
+        self.inner.list_admin_areas_near(near, level)
+    }
+
+    fn list_broader_concepts(
+        &self,
+        concept_id: &str,
+    ) -> BoxFuture<'_, DomainResult<Vec<OntologyConcept>>> {
+        self.inner.list_broader_concepts(concept_id)
+    }
+
+    fn list_narrower_concepts(
+        &self,
+        broader_qids: &[String],
+    ) -> BoxFuture<'_, DomainResult<Vec<OntologyBroaderEdge>>> {
+        self.inner.list_narrower_concepts(broader_qids)
+    }
+
+    fn note_feedback_counts(
+        &self,
+        note_id: &str,
+    ) -> BoxFuture<'_, DomainResult<NoteFeedbackCounts>> {
+        self.inner.note_feedback_counts(note_id)
+    }
+
+    fn list_note_feedback_votes(
+        &self,
+        note_id: &str,
+    ) -> BoxFuture<'_, DomainResult<Vec<NoteFeedbackVote>>> {
+        self.inner.list_note_feedback_votes(note_id)
+    }
+
+    fn get_note(&self, note_id: &str) -> BoxFuture<'_, DomainResult<Option<OntologyNote>>> {
+        self.inner.get_note(note_id)
+    }
+
+    fn update_note(
+        &self,
+        update: &OntologyNoteUpdate,
+    ) -> BoxFuture<'_, DomainResult<OntologyNote>> {
+        self.inner.update_note(update)
+    }
+
+    fn retract_note(
+        &self,
+        note_id: &str,
+        reason: Option<String>,
+        retracted_at_ms: i64,
+    ) -> BoxFuture<'_, DomainResult<OntologyNote>> {
+        self.inner.retract_note(note_id, reason, retracted_at_ms)
+    }
+
+    fn list_note_versions(
+        &self,
+        note_id: &str,
+    ) -> BoxFuture<'_, DomainResult<Vec<OntologyNoteVersion>>> {
+        self.inner.list_note_versions(note_id)
+    }
+
+    fn cleanup_expired_notes(&self, cutoff_ms: i64) -> BoxFuture<'_, DomainResult<Vec<String>>> {
+        self.inner.cleanup_expired_notes(cutoff_ms)
+    }
+
+    fn export_graph(
+        &self,
+        filter: &OntologyExportFilter,
+    ) -> BoxFuture<'_, DomainResult<OntologyExportGraph>> {
+        self.inner.export_graph(filter)
+    }
+
+    fn query_notes(
+        &self,
+        query: &OntologyNoteQuery,
+    ) -> BoxFuture<'_, DomainResult<Vec<OntologyNoteMatch>>> {
+        self.inner.query_notes(query)
+    }
+
+    fn get_concept_redirect(
+        &self,
+        qid: &str,
+    ) -> BoxFuture<'_, DomainResult<Option<OntologyConceptRedirect>>> {
+        self.inner.get_concept_redirect(qid)
+    }
+
+    fn merge_concepts(
+        &self,
+        redirect: &OntologyConceptRedirect,
+    ) -> BoxFuture<'_, DomainResult<OntologyConceptMergeResult>> {
+        let redirect = redirect.clone();
+        Box::pin(async move {
+            let result = self.inner.merge_concepts(&redirect).await?;
+            self.sync(&redirect.from_qid).await;
+            self.sync(&result.surviving.qid).await;
+            Ok(result)
+        })
+    }
+
+    fn replace_concept_labels(
+        &self,
+        qid: &str,
+        labels: &[OntologyConceptLabel],
+    ) -> BoxFuture<'_, DomainResult<()>> {
+        let qid = qid.to_string();
+        let labels = labels.to_vec();
+        Box::pin(async move {
+            self.inner.replace_concept_labels(&qid, &labels).await?;
+            self.sync(&qid).await;
+            Ok(())
+        })
+    }
+
+    fn get_concept_labels(
+        &self,
+        qids: &[String],
+    ) -> BoxFuture<'_, DomainResult<Vec<OntologyConceptLabels>>> {
+        self.inner.get_concept_labels(qids)
+    }
+
+    fn list_concept_labels(
+        &self,
+        after_qid: Option<&str>,
+        limit: usize,
+    ) -> BoxFuture<'_, DomainResult<Vec<OntologyConceptLabels>>> {
+        self.inner.list_concept_labels(after_qid, limit)
+    }
+
+    fn concept_usage_counts(
+        &self,
+        community_id: &str,
+        qids: &[String],
+    ) -> BoxFuture<'_, DomainResult<Vec<OntologyConceptUsage>>> {
+        self.inner.concept_usage_counts(community_id, qids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::InMemoryOntologyRepository;
+
+    fn label(lang: &str, label: &str, alias: bool) -> OntologyConceptLabel {
+        OntologyConceptLabel {
+            lang: lang.to_string(),
+            label: label.to_string(),
+            alias,
+        }
+    }
+
+    fn concept_labels(qid: &str, labels: Vec<OntologyConceptLabel>) -> OntologyConceptLabels {
+        OntologyConceptLabels {
+            qid: qid.to_string(),
+            labels,
+        }
+    }
+
+    #[tokio::test]
+    async fn trie_index_matches_prefixes_word_starts_and_typos() {
+        let index = TrieConceptLabelIndex::new();
+        index
+            .index_concept(&concept_labels(
+                "Q8068",
+                vec![label("id", "Banjir", false), label("su", "Caah", false)],
+            ))
+            .await
+            .expect("index");
+        index
+            .index_concept(&concept_labels(
+                "Q1",
+                vec![
+                    label("id", "Banjir bandang", false),
+                    label("jv", "Banjir bandhang", true),
+                ],
+            ))
+            .await
+            .expect("index");
+
+        let prefix = index.search("banj", 10).await.expect("search");
+        assert_eq!(prefix[0].qid, "Q8068");
+        assert!(prefix.iter().all(|item| item.distance == 0));
+        assert_eq!(prefix.len(), 3);
+
+        let word_start = index.search("bandh", 10).await.expect("search");
+        assert_eq!(word_start[0].lang, "jv");
+        assert_eq!(word_start[0].distance, 0);
+        assert!(word_start[0].alias);
+
+        let typo = index.search("banjri", 10).await.expect("search");
+        assert!(
+            typo.iter()
+                .any(|item| item.qid == "Q8068" && item.distance == 1)
+        );
+        assert!(index.search("bnj", 10).await.expect("search").is_empty());
+
+        index
+            .index_concept(&concept_labels("Q8068", vec![label("su", "Caah", false)]))
+            .await
+            .expect("replace");
+        let replaced = index.search("banjir", 10).await.expect("search");
+        assert!(replaced.iter().all(|item| item.qid == "Q1"));
+        index.remove_concept("Q1").await.expect("remove");
+        assert!(index.search("banjir", 10).await.expect("search").is_empty());
+        assert_eq!(index.search("caah", 10).await.expect("search").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn indexed_repository_refreshes_on_upsert_labels_and_merge() {
+        let index = Arc::new(TrieConceptLabelIndex::new());
+        let inner: Arc<dyn OntologyRepository> = Arc::new(InMemoryOntologyRepository::new());
+        inner
+            .upsert_concept(&OntologyConcept {
+                concept_id: "Q7".to_string(),
+                qid: "Q7".to_string(),
+                label_id: Some("Longsor".to_string()),
+                label_en: None,
+                verified: true,
+            })
+            .await
+            .expect("seed");
+        assert_eq!(
+            index.rebuild_from(inner.as_ref()).await.expect("rebuild"),
+            1
+        );
+        let repo = IndexedOntologyRepository::new(inner, index.clone());
+
+        repo.upsert_concept(&OntologyConcept {
+            concept_id: "Q8068".to_string(),
+            qid: "Q8068".to_string(),
+            label_id: Some("Banjir".to_string()),
+            label_en: Some("Flood".to_string()),
+            verified: true,
+        })
+        .await
+        .expect("upsert");
+        repo.replace_concept_labels("Q8068", &[label("su", "Caah", false)])
+            .await
+            .expect("labels");
+        assert_eq!(
+            index.search("caah", 5).await.expect("search")[0].qid,
+            "Q8068"
+        );
+        assert_eq!(
+            index.search("flo", 5).await.expect("search")[0].qid,
+            "Q8068"
+        );
+
+        repo.merge_concepts(&OntologyConceptRedirect {
+            from_qid: "Q7".to_string(),
+            to_qid: "Q8068".to_string(),
+            reason: "admin".to_string(),
+            created_at_ms: 1,
+        })
+        .await
+        .expect("merge");
+        let merged = index.search("longsor", 5).await.expect("search");
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].qid, "Q8068");
+        assert!(merged[0].alias);
+    }
+}
@@ -21,6 +21,8 @@ pub struct AppConfig {
     pub s3_secret_key: String,
     pub chat_attachment_storage_backend: String,
     pub chat_attachment_s3_prefix: String,
+    pub chat_attachment_user_quota_bytes: u64,
+    pub chat_attachment_thread_quota_bytes: u64,
     pub chat_realtime_transport: String,
     pub chat_realtime_channel_prefix: String,
     pub worker_queue_prefix: String,
@@ -31,6 +33,14 @@ pub struct AppConfig {
     pub worker_ttl_cleanup_interval_ms: u64,
     pub worker_concept_verification_interval_ms: u64,
     pub worker_concept_verification_qids: String,
+    pub worker_chat_retention_interval_ms: u64,
+    pub worker_digest_interval_ms: u64,
+    pub worker_trending_interval_ms: u64,
+    pub trending_window_ms: u64,
+    pub trending_baseline_windows: u32,
+    pub ontology_export_base_iri: String,
+    pub wikidata_api_url: String,
+    pub ontology_autocomplete_refresh_ms: u64,
     pub webhook_enabled: bool,
     pub webhook_markov_url: String,
     pub webhook_secret: String,
@@ -51,6 +61,27 @@ pub struct AppConfig {
     pub markov_cache_gameplay_ttl_ms: u64,
     pub markov_cache_gameplay_stale_while_revalidate_ms: u64,
     pub discovery_feed_involvement_fallback_enabled: bool,
+    pub discovery_feed_rank_recency_weight: f64,
+    pub discovery_feed_rank_involvement_weight: f64,
+    pub discovery_feed_rank_followed_weight: f64,
+    pub discovery_feed_rank_feedback_weight: f64,
+    pub discovery_feed_rank_severity_weight: f64,
+    pub discovery_feed_rank_recency_half_life_ms: u64,
+    pub discovery_search_index_dir: String,
+    pub mail_transport: String,
+    pub mail_from: String,
+    pub mail_smtp_host: String,
+    pub mail_smtp_port: u16,
+    pub mail_smtp_username: String,
+    pub mail_smtp_password: String,
+    pub mail_smtp_tls: String,
+    pub mail_file_sink_dir: String,
+    pub digest_unsubscribe_secret: String,
+    pub digest_public_base_url: String,
+    pub web_push_vapid_public_key: String,
+    pub web_push_vapid_private_key: String,
+    pub web_push_vapid_subject: String,
+    pub web_push_ttl_seconds: u32,
     pub triage_operator_stub_enabled: bool,
 }
 
@@ -77,6 +108,8 @@ impl AppConfig {
             .set_default("s3_secret_key", "minioadmin")?
             .set_default("chat_attachment_storage_backend", "auto")?
             .set_default("chat_attachment_s3_prefix", "chat-attachments")?
+            .set_default("chat_attachment_user_quota_bytes", 536_870_912u64)?
+            .set_default("chat_attachment_thread_quota_bytes", 2_147_483_648u64)?
             .set_default("chat_realtime_transport", "local")?
             .set_default("chat_realtime_channel_prefix", "gotong:chat:realtime")?
             .set_default("worker_queue_prefix", "gotong:jobs")?
@@ -87,6 +120,17 @@ impl AppConfig {
             .set_default("worker_ttl_cleanup_interval_ms", 3_600_000)?
             .set_default("worker_concept_verification_interval_ms", 86_400_000)?
             .set_default("worker_concept_verification_qids", "Q2095")?
+            .set_default("worker_chat_retention_interval_ms", 300_000)?
+            .set_default("worker_digest_interval_ms", 900_000)?
+            .set_default("worker_trending_interval_ms", 900_000)?
+            .set_default("trending_window_ms", 21_600_000u64)?
+            .set_default("trending_baseline_windows", 28u32)?
+            .set_default(
+                "ontology_export_base_iri",
+                "https://gotong-royong.app/ontology/",
+            )?
+            .set_default("wikidata_api_url", "https://www.wikidata.org/w/api.php")?
+            .set_default("ontology_autocomplete_refresh_ms", 600_000u64)?
             .set_default("webhook_enabled", false)?
             .set_default(
                 "webhook_markov_url",
@@ -116,6 +160,30 @@ impl AppConfig {
                 180_000u64,
             )?
             .set_default("discovery_feed_involvement_fallback_enabled", true)?
+            .set_default("discovery_feed_rank_recency_weight", 1.0)?
+            .set_default("discovery_feed_rank_involvement_weight", 0.6)?
+            .set_default("discovery_feed_rank_followed_weight", 0.5)?
+            .set_default("discovery_feed_rank_feedback_weight", 0.4)?
+            .set_default("discovery_feed_rank_severity_weight", 0.8)?
+            .set_default("discovery_feed_rank_recency_half_life_ms", 86_400_000u64)?
+            .set_default("discovery_search_index_dir", "")?
+            .set_default("mail_transport", "file")?
+            .set_default("mail_from", "Gotong Royong <noreply@gotong.local>")?
+            .set_default("mail_smtp_host", "")?
+            .set_default("mail_smtp_port", 587u16)?
+            .set_default("mail_smtp_username", "")?
+            .set_default("mail_smtp_password", "")?
+            .set_default("mail_smtp_tls", "starttls")?
+            .set_default("mail_file_sink_dir", "")?
+            .set_default(
+                "digest_unsubscribe_secret",
+                "dev_digest_unsubscribe_secret_change_me",
+            )?
+            .set_default("digest_public_base_url", "http://127.0.0.1:3000")?
+            .set_default("web_push_vapid_public_key", "")?
+            .set_default("web_push_vapid_private_key", "")?
+            .set_default("web_push_vapid_subject", "mailto:ops@gotong.local")?
+            .set_default("web_push_ttl_seconds", 86_400u32)?
             .set_default("triage_operator_stub_enabled", false)?
             .add_source(config::Environment::default().separator("__"))
             .build()?;
@@ -169,6 +237,53 @@ impl AppConfig {
                 "markov_cache_gameplay_stale_while_revalidate_ms must be >= markov_cache_gameplay_ttl_ms".to_string(),
             ));
         }
+        if [
+            config.discovery_feed_rank_recency_weight,
+            config.discovery_feed_rank_involvement_weight,
+            config.discovery_feed_rank_followed_weight,
+            config.discovery_feed_rank_feedback_weight,
+            config.discovery_feed_rank_severity_weight,
+        ]
+        .iter()
+        .any(|weight| !weight.is_finite() || *weight < 0.0)
+        {
+            return Err(config::ConfigError::Message(
+                "discovery_feed_rank_*_weight values must be finite and >= 0".to_string(),
+            ));
+        }
+        if config.discovery_feed_rank_recency_half_life_ms == 0 {
+            return Err(config::ConfigError::Message(
+                "discovery_feed_rank_recency_half_life_ms must be >= 1".to_string(),
+            ));
+        }
+        if config.trending_window_ms < 60_000 {
+            return Err(config::ConfigError::Message(
+                "trending_window_ms must be >= 60000".to_string(),
+            ));
+        }
+        if config.trending_baseline_windows == 0 {
+            return Err(config::ConfigError::Message(
+                "trending_baseline_windows must be >= 1".to_string(),
+            ));
+        }
+        let ontology_export_base_iri = config.ontology_export_base_iri.trim();
+        if !(ontology_export_base_iri.starts_with("http://")
+            || ontology_export_base_iri.starts_with("https://"))
+            || !ontology_export_base_iri.ends_with('/')
+        {
+            return Err(config::ConfigError::Message(
+                "ontology_export_base_iri must be an http(s) IRI ending in '/'".to_string(),
+            ));
+        }
+        let wikidata_api_url = config.wikidata_api_url.trim();
+        if !(wikidata_api_url.is_empty()
+            || wikidata_api_url.starts_with("http://")
+            || wikidata_api_url.starts_with("https://"))
+        {
+            return Err(config::ConfigError::Message(
+                "wikidata_api_url must be empty or an http(s) URL".to_string(),
+            ));
+        }
         let chat_attachment_storage_backend = config
             .chat_attachment_storage_backend
             .trim()
@@ -181,6 +296,58 @@ impl AppConfig {
                 "chat_attachment_storage_backend must be one of: auto|local|s3".to_string(),
             ));
         }
+        if config.chat_attachment_user_quota_bytes == 0
+            || config.chat_attachment_thread_quota_bytes == 0
+        {
+            return Err(config::ConfigError::Message(
+                "chat attachment quotas must be > 0".to_string(),
+            ));
+        }
+        let mail_transport = config.mail_transport.trim().to_ascii_lowercase();
+        if !matches!(mail_transport.as_str(), "smtp" | "file" | "capture") {
+            return Err(config::ConfigError::Message(
+                "mail_transport must be one of: smtp|file|capture".to_string(),
+            ));
+        }
+        if mail_transport == "smtp" && config.mail_smtp_host.trim().is_empty() {
+            return Err(config::ConfigError::Message(
+                "mail_transport is smtp but mail_smtp_host is empty".to_string(),
+            ));
+        }
+        if !matches!(
+            config.mail_smtp_tls.trim().to_ascii_lowercase().as_str(),
+            "starttls" | "tls" | "none"
+        ) {
+            return Err(config::ConfigError::Message(
+                "mail_smtp_tls must be one of: starttls|tls|none".to_string(),
+            ));
+        }
+        if config.digest_unsubscribe_secret.trim().len() < 16 {
+            return Err(config::ConfigError::Message(
+                "digest_unsubscribe_secret must be at least 16 characters".to_string(),
+            ));
+        }
+        if config.web_push_vapid_public_key.trim().is_empty()
+            != config.web_push_vapid_private_key.trim().is_empty()
+        {
+            return Err(config::ConfigError::Message(
+                "web_push_vapid_public_key and web_push_vapid_private_key must be set together"
+                    .to_string(),
+            ));
+        }
+        let web_push_vapid_subject = config.web_push_vapid_subject.trim();
+        if !web_push_vapid_subject.starts_with("mailto:")
+            && !web_push_vapid_subject.starts_with("https://")
+        {
+            return Err(config::ConfigError::Message(
+                "web_push_vapid_subject must be a mailto: or https: URI".to_string(),
+            ));
+        }
+        if config.web_push_ttl_seconds == 0 || config.web_push_ttl_seconds > 2_419_200 {
+            return Err(config::ConfigError::Message(
+                "web_push_ttl_seconds must be between 1 and 2419200 (28 days)".to_string(),
+            ));
+        }
         Ok(config)
     }
 
@@ -1,8 +1,15 @@
 pub mod auth;
+pub mod chat_attachment_storage;
+pub mod concept_label_index;
 pub mod config;
 pub mod db;
 pub mod idempotency;
 pub mod jobs;
 pub mod logging;
+pub mod mail;
 pub mod markov_client;
 pub mod repositories;
+pub mod search_index;
+pub mod trending;
+pub mod web_push;
+pub mod wikidata;
@@ -0,0 +1,221 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use gotong_domain::DomainResult;
+use gotong_domain::error::DomainError;
+use gotong_domain::ports::BoxFuture;
+use gotong_domain::ports::digest::{MailTransport, OutboundEmail};
+use lettre::message::header::{HeaderName, HeaderValue};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::config::AppConfig;
+
+const MAIL_FILE_SINK_DIR: &str = "gotong-mail";
+
+/// Builds the transport selected by `mail_transport` (`smtp`, `file` or `capture`).
+pub fn mail_transport_for_config(config: &AppConfig) -> anyhow::Result<Arc<dyn MailTransport>> {
+    match config.mail_transport.trim().to_ascii_lowercase().as_str() {
+        "smtp" => Ok(Arc::new(SmtpMailTransport::from_config(config)?)),
+        "file" => Ok(Arc::new(FileMailTransport::from_config(config)?)),
+        "capture" => Ok(Arc::new(CapturedMailTransport::new())),
+        other => anyhow::bail!("unsupported mail_transport '{other}'"),
+    }
+}
+
+pub struct SmtpMailTransport {
+    mailer: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailTransport {
+    pub fn from_config(config: &AppConfig) -> anyhow::Result<Self> {
+        let host = config.mail_smtp_host.trim();
+        if host.is_empty() {
+            anyhow::bail!("mail_smtp_host is empty");
+        }
+        let builder = match config.mail_smtp_tls.trim().to_ascii_lowercase().as_str() {
+            "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?,
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+            "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+            other => anyhow::bail!("unsupported mail_smtp_tls '{other}'"),
+        };
+        let mut builder = builder.port(config.mail_smtp_port);
+        if !config.mail_smtp_username.trim().is_empty() {
+            builder = builder.credentials(Credentials::new(
+                config.mail_smtp_username.clone(),
+                config.mail_smtp_password.clone(),
+            ));
+        }
+        Ok(Self {
+            mailer: builder.build(),
+            from: parse_from(config)?,
+        })
+    }
+}
+
+impl MailTransport for SmtpMailTransport {
+    fn send(&self, email: &OutboundEmail) -> BoxFuture<'_, DomainResult<()>> {
+        let message = build_message(&self.from, email);
+        Box::pin(async move {
+            self.mailer
+                .send(message?)
+                .await
+                .map_err(|err| DomainError::Validation(format!("smtp send failed: {err}")))?;
+            Ok(())
+        })
+    }
+}
+
+/// Writes each message as an `.eml` file; for local development and staging inspection.
+pub struct FileMailTransport {
+    dir: PathBuf,
+    from: Mailbox,
+}
+
+impl FileMailTransport {
+    pub fn new(dir: PathBuf, from: Mailbox) -> Self {
+        Self { dir, from }
+    }
+
+    pub fn from_config(config: &AppConfig) -> anyhow::Result<Self> {
+        let dir = if config.mail_file_sink_dir.trim().is_empty() {
+            std::env::temp_dir()
+                .join(MAIL_FILE_SINK_DIR)
+                .join(config.app_env.trim().to_ascii_lowercase())
+        } else {
+            PathBuf::from(config.mail_file_sink_dir.trim())
+        };
+        Ok(Self::new(dir, parse_from(config)?))
+    }
+}
+
+impl MailTransport for FileMailTransport {
+    fn send(&self, email: &OutboundEmail) -> BoxFuture<'_, DomainResult<()>> {
+        let message = build_message(&self.from, email);
+        Box::pin(async move {
+            let formatted = message?.formatted();
+            let io_error = |err: std::io::Error| {
+                DomainError::Validation(format!("mail file sink write failed: {err}"))
+            };
+            tokio::fs::create_dir_all(&self.dir)
+                .await
+                .map_err(io_error)?;
+            let path = self.dir.join(format!("{}.eml", uuid::Uuid::now_v7()));
+            tokio::fs::write(path, formatted).await.map_err(io_error)?;
+            Ok(())
+        })
+    }
+}
+
+/// Keeps sent messages in memory so tests can assert on them.
+#[derive(Clone, Default)]
+pub struct CapturedMailTransport {
+    sent: Arc<Mutex<Vec<OutboundEmail>>>,
+}
+
+impl CapturedMailTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn sent(&self) -> Vec<OutboundEmail> {
+        self.sent
+            .lock()
+            .map(|sent| sent.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl MailTransport for CapturedMailTransport {
+    fn send(&self, email: &OutboundEmail) -> BoxFuture<'_, DomainResult<()>> {
+        let email = email.clone();
+        Box::pin(async move {
+            self.sent
+                .lock()
+                .map_err(|_| DomainError::Validation("captured mail store poisoned".into()))?
+                .push(email);
+            Ok(())
+        })
+    }
+}
+
+fn parse_from(config: &AppConfig) -> anyhow::Result<Mailbox> {
+    config
+        .mail_from
+        .trim()
+        .parse::<Mailbox>()
+        .map_err(|err| anyhow::anyhow!("invalid mail_from '{}': {err}", config.mail_from))
+}
+
+fn build_message(from: &Mailbox, email: &OutboundEmail) -> DomainResult<Message> {
+    let to = email
+        .to
+        .parse::<Mailbox>()
+        .map_err(|err| DomainError::Validation(format!("invalid recipient: {err}")))?;
+    let mut builder = Message::builder()
+        .from(from.clone())
+        .to(to)
+        .subject(email.subject.clone());
+    for (name, value) in &email.headers {
+        let name = HeaderName::new_from_ascii(name.clone())
+            .map_err(|err| DomainError::Validation(format!("invalid mail header: {err}")))?;
+        builder = builder.raw_header(HeaderValue::new(name, value.clone()));
+    }
+    builder
+        .multipart(MultiPart::alternative_plain_html(
+            email.text_body.clone(),
+            email.html_body.clone(),
+        ))
+        .map_err(|err| DomainError::Validation(format!("invalid mail message: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn email() -> OutboundEmail {
+        OutboundEmail {
+            to: "warga@example.org".to_string(),
+            subject: "Ringkasan mingguan Gotong".to_string(),
+            text_body: "Kabar minggu ini".to_string(),
+            html_body: "<p>Kabar minggu ini</p>".to_string(),
+            headers: BTreeMap::from([(
+                "List-Unsubscribe".to_string(),
+                "<https://example.org/v1/digest/unsubscribe?token=abc>".to_string(),
+            )]),
+        }
+    }
+
+    #[tokio::test]
+    async fn file_transport_writes_multipart_eml_with_headers() {
+        let dir = std::env::temp_dir().join(format!("gotong-mail-test-{}", uuid::Uuid::now_v7()));
+        let transport = FileMailTransport::new(
+            dir.clone(),
+            "Gotong <noreply@example.org>".parse().expect("from"),
+        );
+        transport.send(&email()).await.expect("send");
+
+        let mut entries = std::fs::read_dir(&dir).expect("sink dir");
+        let path = entries.next().expect("one file").expect("entry").path();
+        let contents = std::fs::read_to_string(path).expect("read eml");
+        assert!(contents.contains("To: warga@example.org"));
+        assert!(
+            contents.contains(
+                "List-Unsubscribe: <https://example.org/v1/digest/unsubscribe?token=abc>"
+            )
+        );
+        assert!(contents.contains("multipart/alternative"));
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn captured_transport_records_messages() {
+        let transport = CapturedMailTransport::new();
+        transport.send(&email()).await.expect("send");
+        assert_eq!(transport.sent(), vec![email()]);
+    }
+}
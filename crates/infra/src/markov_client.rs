@@ -2,12 +2,18 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use futures_util::{StreamExt, stream};
 use reqwest::StatusCode;
 use serde_json::Value;
 use tokio::sync::{Mutex, Notify, RwLock};
 use tokio::time::sleep;
 use uuid::Uuid;
 
+use gotong_domain::DomainResult;
+use gotong_domain::error::DomainError;
+use gotong_domain::ports::BoxFuture;
+use gotong_domain::ports::reputation::ReputationLookup;
+
 use crate::config::AppConfig;
 
 const PLATFORM_TOKEN_HEADER: &str = "X-Platform-Token";
@@ -16,6 +22,8 @@ const SCOPE_QUERY_KEY: &str = "view_scope";
 const SCOPE_QUERY_PLATFORM_VALUE: &str = "platform";
 const SCOPE_QUERY_PLATFORM_ID_KEY: &str = "platform_id";
 const MARKOV_CACHE_MAX_ENTRIES: usize = 4_096;
+/// Reputation lookups in flight at once when ranking by several voters.
+const REPUTATION_LOOKUP_CONCURRENCY: usize = 8;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CacheStatus {
@@ -1175,6 +1183,51 @@ impl MarkovReadClient {
     }
 }
 
+impl ReputationLookup for MarkovReadClient {
+    fn reputations(
+        &self,
+        user_ids: &[String],
+    ) -> BoxFuture<'_, DomainResult<HashMap<String, f64>>> {
+        let user_ids = user_ids.to_vec();
+        Box::pin(async move {
+            let mut lookups = stream::iter(user_ids)
+                .map(|user_id| async move {
+                    let result = self.get_user_reputation(&user_id).await;
+                    (user_id, result)
+                })
+                .buffer_unordered(REPUTATION_LOOKUP_CONCURRENCY);
+            let mut reputations = HashMap::new();
+            while let Some((user_id, result)) = lookups.next().await {
+                match result {
+                    Ok(cached) => {
+                        if let Some(reputation) = parse_reputation_value(&cached.value) {
+                            reputations.insert(user_id, reputation);
+                        }
+                    }
+                    Err(MarkovClientError::NotFound(_)) => {}
+                    Err(err) => {
+                        return Err(DomainError::Validation(format!(
+                            "reputation lookup failed: {err}"
+                        )));
+                    }
+                }
+            }
+            Ok(reputations)
+        })
+    }
+}
+
+/// Reads `total_reputation` from a Markov reputation payload, which sends it
+/// as a decimal string or a number, clamped to `0.0..=1.0`.
+pub fn parse_reputation_value(value: &Value) -> Option<f64> {
+    let total = value.get("total_reputation")?;
+    let reputation = match total {
+        Value::String(raw) => raw.trim().parse::<f64>().ok()?,
+        other => other.as_f64()?,
+    };
+    reputation.is_finite().then(|| reputation.clamp(0.0, 1.0))
+}
+
 fn uncached_json(value: Value) -> CachedJson {
     CachedJson {
         value,
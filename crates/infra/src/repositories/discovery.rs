@@ -1,5 +1,6 @@
 pub use super::impls::{
     InMemoryDiscoveryFeedRepository, InMemoryDiscoveryNotificationRepository,
-    SurrealDiscoveryFeedRepository, SurrealDiscoveryFeedRepositoryOptions,
-    SurrealDiscoveryNotificationRepository,
+    InMemoryFeedPreferenceRepository, SurrealDiscoveryFeedRepository,
+    SurrealDiscoveryFeedRepositoryOptions, SurrealDiscoveryNotificationRepository,
+    SurrealFeedPreferenceRepository,
 };
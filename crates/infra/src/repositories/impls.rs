@@ -4,43 +4,66 @@ use std::sync::Arc;
 use crate::db::DbConfig;
 use gotong_domain::DomainResult;
 use gotong_domain::adaptive_path::{
-    AdaptivePathEvent, AdaptivePathPlan, AdaptivePathSuggestion, SuggestionDecisionStatus,
+    AdaptivePathEvent, AdaptivePathPlan, AdaptivePathPlanVersionSummary, AdaptivePathSuggestion,
+    SuggestionDecisionStatus,
 };
 use gotong_domain::chat::{
-    ChatDeliveryEvent, ChatMember, ChatMemberRole, ChatMessage, ChatReadCursor, ChatThread,
-    ChatThreadQuery, ChatThreadWithMembers, MessageCatchup,
+    ChatAttachment, ChatAttachmentQuota, ChatAttachmentUsageQuery, ChatDeliveryEvent, ChatMember,
+    ChatMemberRole, ChatMessage, ChatReadCursor, ChatRetentionPolicy, ChatThread, ChatThreadQuery,
+    ChatThreadWithMembers, MessageCatchup,
 };
 use gotong_domain::contributions::{Contribution, ContributionType};
+use gotong_domain::digest::{DigestFrequency, DigestSubscription};
 use gotong_domain::discovery::FEED_SOURCE_VAULT;
-use gotong_domain::discovery::{FeedItem, InAppNotification};
+use gotong_domain::discovery::{
+    FeedFollowPreference, FeedItem, FeedMonitorPreference, InAppNotification, NotificationListItem,
+    group_notifications, is_involved_in_feed_item,
+};
 use gotong_domain::error::DomainError;
 use gotong_domain::evidence::{Evidence, EvidenceType};
+use gotong_domain::geo::{GeoPoint, GeoRadius, geohash_prefixes};
 use gotong_domain::mode::Mode;
 use gotong_domain::moderation::{
     ContentModeration, ModerationAction, ModerationActorSnapshot, ModerationDecision,
     ModerationStatus, ModerationViolation,
 };
+use gotong_domain::notification_preferences::NotificationPreferences;
 use gotong_domain::ontology::{
-    NoteFeedbackCounts, OntologyActionRef, OntologyConcept, OntologyEdgeKind, OntologyNote,
-    OntologyNoteCreate, OntologyPlaceRef, OntologyTripleCreate,
+    FIRST_NOTE_VERSION, NoteFeedbackCounts, NoteFeedbackKind, NoteFeedbackVote, OntologyActionRef,
+    OntologyConcept, OntologyConceptLabel, OntologyConceptLabels, OntologyConceptMergeResult,
+    OntologyConceptRedirect, OntologyConceptUsage, OntologyEdgeKind, OntologyNote,
+    OntologyNoteCreate, OntologyNoteUpdate, OntologyNoteVersion, OntologyPlaceRef,
+    OntologyTripleCreate,
+};
+use gotong_domain::ontology_export::{
+    OntologyBroaderEdge, OntologyExportFilter, OntologyExportGraph,
 };
+use gotong_domain::ontology_gazetteer::{
+    ADMIN_AREA_PLACE_SOURCE, AdminArea, AdminLevel, normalize_admin_code,
+};
+use gotong_domain::ontology_query::{OntologyNoteMatch, OntologyNoteQuery, note_visible_to};
+use gotong_domain::ontology_revision::current_version;
 use gotong_domain::ports::adaptive_path::AdaptivePathRepository;
 use gotong_domain::ports::chat::ChatRepository as ChatRepositoryPort;
 use gotong_domain::ports::contributions::ContributionRepository;
+use gotong_domain::ports::digest::DigestSubscriptionRepository;
 use gotong_domain::ports::discovery::{
-    FeedRepository, FeedRepositoryQuery, FeedRepositorySearchQuery, NotificationRepository,
-    NotificationRepositoryListQuery,
+    FeedPreferenceRepository, FeedRepository, FeedRepositoryQuery, FeedRepositorySearchQuery,
+    NotificationRepository, NotificationRepositoryListQuery,
 };
 use gotong_domain::ports::evidence::EvidenceRepository;
 use gotong_domain::ports::group::{
     GroupJoinRequestRecord, GroupMemberRecord, GroupRecord, GroupRepository,
 };
 use gotong_domain::ports::moderation::ModerationRepository;
+use gotong_domain::ports::notification_preferences::NotificationPreferenceRepository;
 use gotong_domain::ports::ontology::OntologyRepository;
+use gotong_domain::ports::push::PushSubscriptionRepository;
 use gotong_domain::ports::siaga::SiagaRepository;
 use gotong_domain::ports::vault::VaultRepository;
 use gotong_domain::ports::vouches::VouchRepository;
 use gotong_domain::ports::webhook::WebhookOutboxRepository;
+use gotong_domain::push::PushSubscription;
 use gotong_domain::siaga::{
     SiagaActorSnapshot, SiagaBroadcast, SiagaClosure, SiagaResponder, SiagaState,
     SiagaTimelineEvent, SiagaTimelineEventType,
@@ -61,6 +84,7 @@ use surrealdb::{
     engine::remote::ws::{Client, Ws},
     opt::auth::Root,
 };
+use surrealdb_types::RecordId;
 use time::{OffsetDateTime, format_description::well_known::Rfc3339};
 
 use tokio::sync::RwLock;
@@ -2630,6 +2654,9 @@ async fn self_recent_filtered(
     contributions.into_iter().take(limit).collect()
 }
 
+/// Edge table, voter record and note record of a vouch or challenge.
+type NoteFeedbackEdgeKey = (&'static str, String, String);
+
 #[derive(Default)]
 pub struct InMemoryOntologyRepository {
     concepts_by_id: Arc<RwLock<HashMap<String, OntologyConcept>>>,
@@ -2637,6 +2664,14 @@ pub struct InMemoryOntologyRepository {
     broader_edges: Arc<RwLock<HashMap<String, Vec<String>>>>,
     notes: Arc<RwLock<HashMap<String, OntologyNote>>>,
     triples: Arc<RwLock<Vec<OntologyTripleCreate>>>,
+    redirects: Arc<RwLock<HashMap<String, OntologyConceptRedirect>>>,
+    labels: Arc<RwLock<HashMap<String, Vec<OntologyConceptLabel>>>>,
+    /// When each vouch or challenge was written and on which note version,
+    /// keyed by edge, voter and note.
+    feedback_cast: Arc<RwLock<HashMap<NoteFeedbackEdgeKey, (i64, i64)>>>,
+    admin_areas: Arc<RwLock<HashMap<String, AdminArea>>>,
+    /// Superseded note versions by note id, oldest first.
+    note_versions: Arc<RwLock<HashMap<String, Vec<OntologyNoteVersion>>>>,
 }
 
 impl InMemoryOntologyRepository {
@@ -2658,6 +2693,43 @@ impl InMemoryOntologyRepository {
             .map(|(_, id)| id.to_string())
             .unwrap_or_else(|| raw.to_string())
     }
+
+    /// The record a concept write should target: the survivor's when the id's
+    /// QID was merged away, so writes never recreate the loser.
+    fn resolved_concept_record(
+        raw: &str,
+        redirects: &HashMap<String, OntologyConceptRedirect>,
+        concepts_by_qid: &HashMap<String, String>,
+    ) -> String {
+        match redirects.get(&Self::id_part(raw.trim())) {
+            Some(redirect) => concepts_by_qid
+                .get(&redirect.to_qid)
+                .cloned()
+                .unwrap_or_else(|| Self::normalize_record_id(&redirect.to_qid, "concept")),
+            None => Self::normalize_record_id(raw, "concept"),
+        }
+    }
+
+    fn concept_labels(
+        concept: &OntologyConcept,
+        stored: Option<&Vec<OntologyConceptLabel>>,
+    ) -> OntologyConceptLabels {
+        let preferred = [("id", &concept.label_id), ("en", &concept.label_en)]
+            .into_iter()
+            .filter_map(|(lang, label)| {
+                label.as_ref().map(|label| OntologyConceptLabel {
+                    lang: lang.to_string(),
+                    label: label.clone(),
+                    alias: false,
+                })
+            });
+        OntologyConceptLabels {
+            qid: concept.qid.clone(),
+            labels: preferred
+                .chain(stored.into_iter().flatten().cloned())
+                .collect(),
+        }
+    }
 }
 
 impl OntologyRepository for InMemoryOntologyRepository {
@@ -2668,10 +2740,23 @@ impl OntologyRepository for InMemoryOntologyRepository {
         let mut concept = concept.clone();
         let concepts_by_id = self.concepts_by_id.clone();
         let concepts_by_qid = self.concepts_by_qid.clone();
+        let redirects = self.redirects.clone();
         Box::pin(async move {
             if concept.qid.trim().is_empty() {
                 return Err(DomainError::Validation("qid is required".to_string()));
             }
+            // A re-import of a merged-away QID keeps the survivor as it is.
+            let redirect = redirects.read().await.get(&concept.qid).cloned();
+            if let Some(redirect) = redirect {
+                let survivor = concepts_by_qid.read().await.get(&redirect.to_qid).cloned();
+                if let Some(survivor) = survivor
+                    && let Some(existing) = concepts_by_id.read().await.get(&survivor)
+                {
+                    return Ok(existing.clone());
+                }
+                concept.qid = redirect.to_qid.clone();
+                concept.concept_id = redirect.to_qid.clone();
+            }
             concept.concept_id = Self::normalize_record_id(&concept.concept_id, "concept");
             concepts_by_qid
                 .write()
@@ -2691,9 +2776,22 @@ impl OntologyRepository for InMemoryOntologyRepository {
         broader_concept_id: &str,
     ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<()>> {
         let broader_edges = self.broader_edges.clone();
-        let narrower = Self::normalize_record_id(narrower_concept_id, "concept");
-        let broader = Self::normalize_record_id(broader_concept_id, "concept");
-        Box::pin(async move {
+        let redirects = self.redirects.clone();
+        let concepts_by_qid = self.concepts_by_qid.clone();
+        let narrower_concept_id = narrower_concept_id.to_string();
+        let broader_concept_id = broader_concept_id.to_string();
+        Box::pin(async move {
+            let (narrower, broader) = {
+                let by_qid = concepts_by_qid.read().await;
+                let redirects = redirects.read().await;
+                (
+                    Self::resolved_concept_record(&narrower_concept_id, &redirects, &by_qid),
+                    Self::resolved_concept_record(&broader_concept_id, &redirects, &by_qid),
+                )
+            };
+            if narrower == broader {
+                return Ok(());
+            }
             let mut broader_edges = broader_edges.write().await;
             let edges = broader_edges.entry(narrower).or_default();
             if !edges.iter().any(|item| item == &broader) {
@@ -2731,6 +2829,10 @@ impl OntologyRepository for InMemoryOntologyRepository {
                 rahasia_level: note.rahasia_level,
                 confidence: note.confidence,
                 created_at_ms: gotong_domain::jobs::now_ms(),
+                version: FIRST_NOTE_VERSION,
+                edited_at_ms: None,
+                retracted_at_ms: None,
+                retraction_reason: None,
             };
             notes
                 .write()
@@ -2746,6 +2848,10 @@ impl OntologyRepository for InMemoryOntologyRepository {
     ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<()>> {
         let triples = triples.to_vec();
         let store = self.triples.clone();
+        let notes = self.notes.clone();
+        let feedback_cast = self.feedback_cast.clone();
+        let redirects = self.redirects.clone();
+        let concepts_by_qid = self.concepts_by_qid.clone();
         Box::pin(async move {
             for triple in &triples {
                 if triple.from_id.trim().is_empty() || triple.to_id.trim().is_empty() {
@@ -2754,8 +2860,20 @@ impl OntologyRepository for InMemoryOntologyRepository {
                     ));
                 }
             }
+            let by_qid = concepts_by_qid.read().await;
+            let redirects = redirects.read().await;
+            let notes = notes.read().await;
             let mut store = store.write().await;
-            for triple in triples {
+            let mut feedback_cast = feedback_cast.write().await;
+            for mut triple in triples {
+                if matches!(
+                    triple.edge,
+                    OntologyEdgeKind::About | OntologyEdgeKind::InstanceOf
+                ) && redirects.contains_key(&Self::id_part(triple.to_id.trim()))
+                {
+                    triple.to_id =
+                        Self::resolved_concept_record(&triple.to_id, &redirects, &by_qid);
+                }
                 let is_unique_feedback = matches!(
                     triple.edge,
                     OntologyEdgeKind::Vouches | OntologyEdgeKind::Challenges
@@ -2770,6 +2888,13 @@ impl OntologyRepository for InMemoryOntologyRepository {
                     }) {
                         continue;
                     }
+                    let note_version = notes
+                        .get(&Self::id_part(&to_id))
+                        .map_or(FIRST_NOTE_VERSION, |note| note.version);
+                    feedback_cast.insert(
+                        (triple.edge.as_table_name(), from_id, to_id),
+                        (gotong_domain::jobs::now_ms(), note_version),
+                    );
                 }
                 store.push(triple);
             }
@@ -2805,7 +2930,14 @@ impl OntologyRepository for InMemoryOntologyRepository {
         let qid = qid.to_string();
         let concepts_by_qid = self.concepts_by_qid.clone();
         let concepts_by_id = self.concepts_by_id.clone();
+        let redirects = self.redirects.clone();
         Box::pin(async move {
+            let qid = redirects
+                .read()
+                .await
+                .get(&qid)
+                .map(|redirect| redirect.to_qid.clone())
+                .unwrap_or(qid);
             let concepts_by_qid = concepts_by_qid.read().await;
             let Some(concept_id) = concepts_by_qid.get(&qid) else {
                 return Ok(None);
@@ -2822,10 +2954,22 @@ impl OntologyRepository for InMemoryOntologyRepository {
         let qids = qids.to_vec();
         let concepts_by_qid = self.concepts_by_qid.clone();
         let concepts_by_id = self.concepts_by_id.clone();
-        Box::pin(async move {
+        let redirects = self.redirects.clone();
+        Box::pin(async move {
+            let redirects = redirects.read().await;
+            let mut resolved = Vec::with_capacity(qids.len());
+            for qid in qids {
+                let qid = redirects
+                    .get(&qid)
+                    .map(|redirect| redirect.to_qid.clone())
+                    .unwrap_or(qid);
+                if !resolved.contains(&qid) {
+                    resolved.push(qid);
+                }
+            }
             let by_qid = concepts_by_qid.read().await;
             let by_id = concepts_by_id.read().await;
-            Ok(qids
+            Ok(resolved
                 .into_iter()
                 .filter_map(|qid| by_qid.get(&qid).and_then(|id| by_id.get(id)).cloned())
                 .collect())
@@ -2866,9 +3010,96 @@ impl OntologyRepository for InMemoryOntologyRepository {
 
     fn get_places_by_ids(
         &self,
-        _place_ids: &[String],
+        place_ids: &[String],
     ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<OntologyPlaceRef>>> {
-        Box::pin(async move { Ok(Vec::new()) })
+        let place_ids = place_ids.to_vec();
+        let admin_areas = self.admin_areas.clone();
+        Box::pin(async move {
+            // Only gazetteer places are kept in memory.
+            let admin_areas = admin_areas.read().await;
+            Ok(place_ids
+                .iter()
+                .filter_map(|place_id| normalize_admin_code(place_id).ok())
+                .filter_map(|code| admin_areas.get(&code))
+                .map(|area| OntologyPlaceRef {
+                    place_id: area.place_id(),
+                    name: area.name.clone(),
+                })
+                .collect())
+        })
+    }
+
+    fn upsert_admin_areas(
+        &self,
+        areas: &[AdminArea],
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<()>> {
+        let areas = areas.to_vec();
+        let admin_areas = self.admin_areas.clone();
+        Box::pin(async move {
+            for area in &areas {
+                area.validate()?;
+            }
+            let mut admin_areas = admin_areas.write().await;
+            for area in areas {
+                admin_areas.insert(area.code.clone(), area);
+            }
+            Ok(())
+        })
+    }
+
+    fn get_admin_areas(
+        &self,
+        codes: &[String],
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<AdminArea>>> {
+        let codes = codes.to_vec();
+        let admin_areas = self.admin_areas.clone();
+        Box::pin(async move {
+            let admin_areas = admin_areas.read().await;
+            Ok(codes
+                .iter()
+                .filter_map(|code| admin_areas.get(code.trim()).cloned())
+                .collect())
+        })
+    }
+
+    fn list_admin_areas_covering(
+        &self,
+        geohash: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<AdminArea>>> {
+        let geohash = geohash.to_string();
+        let admin_areas = self.admin_areas.clone();
+        Box::pin(async move {
+            Ok(admin_areas
+                .read()
+                .await
+                .values()
+                .filter(|area| {
+                    area.cover_geohash()
+                        .is_some_and(|cover| geohash.starts_with(&cover))
+                })
+                .cloned()
+                .collect())
+        })
+    }
+
+    fn list_admin_areas_near(
+        &self,
+        near: &GeoRadius,
+        level: AdminLevel,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<AdminArea>>> {
+        let near = *near;
+        let admin_areas = self.admin_areas.clone();
+        Box::pin(async move {
+            Ok(admin_areas
+                .read()
+                .await
+                .values()
+                .filter(|area| {
+                    area.level == level && near.distance_within(&area.centroid).is_some()
+                })
+                .cloned()
+                .collect())
+        })
     }
 
     fn list_broader_concepts(
@@ -2895,32 +3126,198 @@ impl OntologyRepository for InMemoryOntologyRepository {
         })
     }
 
+    fn list_narrower_concepts(
+        &self,
+        broader_qids: &[String],
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<OntologyBroaderEdge>>> {
+        let broader_qids = broader_qids.iter().cloned().collect::<HashSet<_>>();
+        let concepts_by_id = self.concepts_by_id.clone();
+        let broader_edges = self.broader_edges.clone();
+        Box::pin(async move {
+            let concepts_by_id = concepts_by_id.read().await;
+            let qid_for = |record_id: &str| {
+                concepts_by_id
+                    .get(record_id)
+                    .map(|concept| concept.qid.clone())
+                    .unwrap_or_else(|| Self::id_part(record_id))
+            };
+            let mut edges = Vec::new();
+            for (narrower, targets) in broader_edges.read().await.iter() {
+                for broader in targets {
+                    let broader_qid = qid_for(broader);
+                    if broader_qids.contains(&broader_qid) {
+                        edges.push(OntologyBroaderEdge {
+                            narrower_qid: qid_for(narrower),
+                            broader_qid,
+                        });
+                    }
+                }
+            }
+            edges.sort_by(|left, right| left.narrower_qid.cmp(&right.narrower_qid));
+            Ok(edges)
+        })
+    }
+
     fn note_feedback_counts(
         &self,
         note_id: &str,
     ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<NoteFeedbackCounts>> {
         let note_record = Self::normalize_record_id(note_id, "note");
         let triples = self.triples.clone();
+        let notes = self.notes.clone();
+        let feedback_cast = self.feedback_cast.clone();
         Box::pin(async move {
+            let current_version = notes
+                .read()
+                .await
+                .get(&Self::id_part(&note_record))
+                .map_or(FIRST_NOTE_VERSION, |note| note.version);
             let triples = triples.read().await;
-            let vouch_count = triples
+            let feedback_cast = feedback_cast.read().await;
+            let mut counts = NoteFeedbackCounts::default();
+            for triple in triples
                 .iter()
-                .filter(|triple| {
-                    triple.edge == OntologyEdgeKind::Vouches
-                        && Self::normalize_record_id(&triple.to_id, "note") == note_record
-                })
-                .count();
-            let challenge_count = triples
+                .filter(|triple| Self::normalize_record_id(&triple.to_id, "note") == note_record)
+            {
+                let earlier_version = feedback_cast
+                    .get(&(
+                        triple.edge.as_table_name(),
+                        Self::normalize_record_id(&triple.from_id, "warga"),
+                        note_record.clone(),
+                    ))
+                    .map_or(FIRST_NOTE_VERSION, |(_, note_version)| *note_version)
+                    < current_version;
+                match triple.edge {
+                    OntologyEdgeKind::Vouches => {
+                        counts.vouch_count += 1;
+                        counts.earlier_version_vouch_count += usize::from(earlier_version);
+                    }
+                    OntologyEdgeKind::Challenges => {
+                        counts.challenge_count += 1;
+                        counts.earlier_version_challenge_count += usize::from(earlier_version);
+                    }
+                    _ => {}
+                }
+            }
+            Ok(counts)
+        })
+    }
+
+    fn list_note_feedback_votes(
+        &self,
+        note_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<NoteFeedbackVote>>> {
+        let note_record = Self::normalize_record_id(note_id, "note");
+        let triples = self.triples.clone();
+        let feedback_cast = self.feedback_cast.clone();
+        Box::pin(async move {
+            let triples = triples.read().await;
+            let feedback_cast = feedback_cast.read().await;
+            Ok(triples
                 .iter()
-                .filter(|triple| {
-                    triple.edge == OntologyEdgeKind::Challenges
-                        && Self::normalize_record_id(&triple.to_id, "note") == note_record
+                .filter(|triple| Self::normalize_record_id(&triple.to_id, "note") == note_record)
+                .filter_map(|triple| {
+                    let kind = match triple.edge {
+                        OntologyEdgeKind::Vouches => NoteFeedbackKind::Vouch,
+                        OntologyEdgeKind::Challenges => NoteFeedbackKind::Challenge,
+                        _ => return None,
+                    };
+                    let voter_record = Self::normalize_record_id(&triple.from_id, "warga");
+                    let cast = feedback_cast
+                        .get(&(
+                            triple.edge.as_table_name(),
+                            voter_record.clone(),
+                            note_record.clone(),
+                        ))
+                        .copied();
+                    Some(NoteFeedbackVote {
+                        voter_id: Self::id_part(&voter_record),
+                        kind,
+                        created_at_ms: cast.map(|(created_at_ms, _)| created_at_ms),
+                        note_version: cast.map_or(FIRST_NOTE_VERSION, |(_, version)| version),
+                    })
                 })
-                .count();
-            Ok(NoteFeedbackCounts {
-                vouch_count,
-                challenge_count,
-            })
+                .collect())
+        })
+    }
+
+    fn get_note(
+        &self,
+        note_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<OntologyNote>>> {
+        let note_id = Self::id_part(note_id.trim());
+        let notes = self.notes.clone();
+        Box::pin(async move { Ok(notes.read().await.get(&note_id).cloned()) })
+    }
+
+    fn update_note(
+        &self,
+        update: &OntologyNoteUpdate,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<OntologyNote>> {
+        let update = update.clone();
+        let notes = self.notes.clone();
+        let note_versions = self.note_versions.clone();
+        Box::pin(async move {
+            if update.content.trim().is_empty() {
+                return Err(DomainError::Validation(
+                    "note content is required".to_string(),
+                ));
+            }
+            let mut notes = notes.write().await;
+            let note = notes
+                .get_mut(&Self::id_part(update.note_id.trim()))
+                .ok_or(DomainError::NotFound)?;
+            if note.version != update.expected_version || note.is_retracted() {
+                return Err(DomainError::Conflict);
+            }
+            let mut superseded = current_version(note);
+            superseded.superseded_at_ms = Some(update.edited_at_ms);
+            note_versions
+                .write()
+                .await
+                .entry(note.note_id.clone())
+                .or_default()
+                .push(superseded);
+            note.content = update.content.trim().to_string();
+            note.confidence = update.confidence;
+            note.version += 1;
+            note.edited_at_ms = Some(update.edited_at_ms);
+            Ok(note.clone())
+        })
+    }
+
+    fn retract_note(
+        &self,
+        note_id: &str,
+        reason: Option<String>,
+        retracted_at_ms: i64,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<OntologyNote>> {
+        let note_id = Self::id_part(note_id.trim());
+        let notes = self.notes.clone();
+        Box::pin(async move {
+            let mut notes = notes.write().await;
+            let note = notes.get_mut(&note_id).ok_or(DomainError::NotFound)?;
+            if !note.is_retracted() {
+                note.retracted_at_ms = Some(retracted_at_ms);
+                note.retraction_reason = reason;
+            }
+            Ok(note.clone())
+        })
+    }
+
+    fn list_note_versions(
+        &self,
+        note_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<OntologyNoteVersion>>> {
+        let note_id = Self::id_part(note_id.trim());
+        let note_versions = self.note_versions.clone();
+        Box::pin(async move {
+            Ok(note_versions
+                .read()
+                .await
+                .get(&note_id)
+                .cloned()
+                .unwrap_or_default())
         })
     }
 
@@ -2930,6 +3327,7 @@ impl OntologyRepository for InMemoryOntologyRepository {
     ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<String>>> {
         let notes = self.notes.clone();
         let triples = self.triples.clone();
+        let note_versions = self.note_versions.clone();
         Box::pin(async move {
             if cutoff_ms < 0 {
                 return Err(DomainError::Validation(
@@ -2947,8 +3345,10 @@ impl OntologyRepository for InMemoryOntologyRepository {
                         keys.push(key.clone());
                     }
                 }
+                let mut note_versions = note_versions.write().await;
                 for key in keys {
                     notes.remove(&key);
+                    note_versions.remove(&key);
                 }
             }
 
@@ -2976,41 +3376,495 @@ impl OntologyRepository for InMemoryOntologyRepository {
             Ok(expired_notes)
         })
     }
-}
 
-#[derive(Clone)]
-pub struct SurrealOntologyRepository {
-    client: Arc<Surreal<Client>>,
-}
+    fn export_graph(
+        &self,
+        filter: &OntologyExportFilter,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<OntologyExportGraph>> {
+        let filter = filter.clone();
+        let concepts_by_id = self.concepts_by_id.clone();
+        let broader_edges = self.broader_edges.clone();
+        let notes = self.notes.clone();
+        let triples = self.triples.clone();
+        Box::pin(async move {
+            let now_ms = gotong_domain::jobs::now_ms();
+            let mut notes = notes
+                .read()
+                .await
+                .values()
+                .filter(|note| filter.allows(note, now_ms))
+                .filter(|note| {
+                    filter
+                        .after_note_id
+                        .as_deref()
+                        .is_none_or(|cursor| note.note_id.as_str() > cursor)
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            notes.sort_by(|left, right| left.note_id.cmp(&right.note_id));
+            if let Some(limit) = filter.limit {
+                notes.truncate(limit + 1);
+            }
+            let note_records = notes
+                .iter()
+                .map(|note| Self::normalize_record_id(&note.note_id, "note"))
+                .collect::<HashSet<_>>();
+            let triples = triples
+                .read()
+                .await
+                .iter()
+                .filter(|triple| {
+                    !matches!(
+                        triple.edge,
+                        OntologyEdgeKind::Broader
+                            | OntologyEdgeKind::Vouches
+                            | OntologyEdgeKind::Challenges
+                    ) && note_records.contains(&Self::normalize_record_id(&triple.from_id, "note"))
+                })
+                .cloned()
+                .collect::<Vec<_>>();
 
-impl SurrealOntologyRepository {
-    pub fn with_client(client: Arc<Surreal<Client>>) -> Self {
-        Self { client }
+            // A page only carries the concepts its triples point at.
+            let page_concepts = filter.is_paged().then(|| {
+                triples
+                    .iter()
+                    .filter(|triple| {
+                        matches!(
+                            triple.edge,
+                            OntologyEdgeKind::About | OntologyEdgeKind::InstanceOf
+                        )
+                    })
+                    .map(|triple| Self::normalize_record_id(&triple.to_id, "concept"))
+                    .collect::<HashSet<_>>()
+            });
+            let in_page = |record_id: &str| {
+                page_concepts
+                    .as_ref()
+                    .is_none_or(|records| records.contains(record_id))
+            };
+            let concepts_by_id = concepts_by_id.read().await;
+            let qid_for = |record_id: &str| {
+                concepts_by_id
+                    .get(record_id)
+                    .map(|concept| concept.qid.clone())
+                    .unwrap_or_else(|| Self::id_part(record_id))
+            };
+            let mut edges = Vec::new();
+            for (narrower, targets) in broader_edges.read().await.iter() {
+                if !in_page(narrower) {
+                    continue;
+                }
+                for broader in targets {
+                    edges.push(OntologyBroaderEdge {
+                        narrower_qid: qid_for(narrower),
+                        broader_qid: qid_for(broader),
+                    });
+                }
+            }
+            let mut concepts = concepts_by_id
+                .iter()
+                .filter(|(record_id, _)| in_page(record_id))
+                .map(|(_, concept)| concept.clone())
+                .collect::<Vec<_>>();
+            concepts.sort_by(|left, right| left.qid.cmp(&right.qid));
+
+            Ok(OntologyExportGraph {
+                concepts,
+                broader_edges: edges,
+                notes,
+                triples,
+                ..OntologyExportGraph::default()
+            })
+        })
     }
+    fn query_notes(
+        &self,
+        query: &OntologyNoteQuery,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<OntologyNoteMatch>>> {
+        let query = query.clone();
+        let concepts_by_id = self.concepts_by_id.clone();
+        let notes = self.notes.clone();
+        let triples = self.triples.clone();
+        Box::pin(async move {
+            let concepts_by_id = concepts_by_id.read().await;
+            let triples = triples.read().await;
+            let mut matches = Vec::new();
+            for note in notes.read().await.values() {
+                if !note_visible_to(note, &query.viewer_id, query.now_ms)
+                    || query
+                        .community_id
+                        .as_deref()
+                        .is_some_and(|community_id| note.community_id != community_id)
+                    || query
+                        .from_ms
+                        .is_some_and(|from_ms| note.created_at_ms < from_ms)
+                    || query.to_ms.is_some_and(|to_ms| note.created_at_ms > to_ms)
+                {
+                    continue;
+                }
+                if let (Some(cursor_ms), Some(cursor_id)) =
+                    (query.cursor_created_at_ms, query.cursor_note_id.as_deref())
+                    && (note.created_at_ms, note.note_id.as_str()) >= (cursor_ms, cursor_id)
+                {
+                    continue;
+                }
 
-    pub async fn new(db_config: &DbConfig) -> anyhow::Result<Self> {
-        let db = Surreal::<Client>::init();
-        db.connect::<Ws>(&db_config.endpoint).await?;
-        db.signin(Root {
-            username: db_config.username.clone(),
-            password: db_config.password.clone(),
-        })
-        .await?;
-        db.use_ns(&db_config.namespace)
-            .use_db(&db_config.database)
-            .await?;
-        Ok(Self {
-            client: Arc::new(db),
+                let note_record = Self::normalize_record_id(&note.note_id, "note");
+                let mut item = OntologyNoteMatch {
+                    note: note.clone(),
+                    about: Vec::new(),
+                    located_at: Vec::new(),
+                    has_action: Vec::new(),
+                };
+                for triple in triples.iter().filter(|triple| {
+                    Self::normalize_record_id(&triple.from_id, "note") == note_record
+                }) {
+                    match triple.edge {
+                        OntologyEdgeKind::About => {
+                            let record = Self::normalize_record_id(&triple.to_id, "concept");
+                            item.about.push(
+                                concepts_by_id
+                                    .get(&record)
+                                    .map(|concept| concept.qid.clone())
+                                    .unwrap_or_else(|| Self::id_part(&record)),
+                            );
+                        }
+                        OntologyEdgeKind::LocatedAt => item
+                            .located_at
+                            .push(Self::normalize_record_id(&triple.to_id, "place")),
+                        OntologyEdgeKind::HasAction => {
+                            item.has_action
+                                .push(triple.predicate.clone().unwrap_or_else(|| {
+                                    format!("schema:{}", Self::id_part(&triple.to_id))
+                                }))
+                        }
+                        _ => {}
+                    }
+                }
+                let facet_matches = |wanted: &[String], found: &[String]| {
+                    wanted.is_empty() || found.iter().any(|value| wanted.contains(value))
+                };
+                if facet_matches(&query.concept_qids, &item.about)
+                    && facet_matches(&query.place_ids, &item.located_at)
+                    && facet_matches(&query.action_types, &item.has_action)
+                {
+                    matches.push(item);
+                }
+            }
+            matches.sort_by(|left, right| {
+                right
+                    .note
+                    .created_at_ms
+                    .cmp(&left.note.created_at_ms)
+                    .then_with(|| right.note.note_id.cmp(&left.note.note_id))
+            });
+            matches.truncate(query.limit);
+            Ok(matches)
         })
     }
 
-    fn normalize_record_id(raw: &str, table: &str) -> (String, String) {
-        let value = raw.trim();
-        if let Some((tbl, id)) = value.split_once(':') {
-            (tbl.to_string(), id.to_string())
-        } else {
-            (table.to_string(), value.to_string())
-        }
+    fn get_concept_redirect(
+        &self,
+        qid: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<OntologyConceptRedirect>>> {
+        let qid = qid.trim().to_string();
+        let redirects = self.redirects.clone();
+        Box::pin(async move { Ok(redirects.read().await.get(&qid).cloned()) })
+    }
+
+    fn merge_concepts(
+        &self,
+        redirect: &OntologyConceptRedirect,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<OntologyConceptMergeResult>> {
+        let redirect = redirect.clone();
+        let concepts_by_id = self.concepts_by_id.clone();
+        let concepts_by_qid = self.concepts_by_qid.clone();
+        let broader_edges = self.broader_edges.clone();
+        let triples = self.triples.clone();
+        let redirects = self.redirects.clone();
+        let labels = self.labels.clone();
+        Box::pin(async move {
+            if redirect.from_qid == redirect.to_qid {
+                return Err(DomainError::Validation(
+                    "cannot merge a concept into itself".to_string(),
+                ));
+            }
+            let mut by_qid = concepts_by_qid.write().await;
+            let mut by_id = concepts_by_id.write().await;
+            let from_record = by_qid
+                .remove(&redirect.from_qid)
+                .unwrap_or_else(|| Self::normalize_record_id(&redirect.from_qid, "concept"));
+            let into_record = by_qid
+                .get(&redirect.to_qid)
+                .cloned()
+                .unwrap_or_else(|| Self::normalize_record_id(&redirect.to_qid, "concept"));
+            let loser = by_id.remove(&from_record);
+            let surviving = by_id
+                .entry(into_record.clone())
+                .or_insert_with(|| OntologyConcept {
+                    concept_id: into_record.clone(),
+                    qid: redirect.to_qid.clone(),
+                    label_id: None,
+                    label_en: None,
+                    verified: false,
+                });
+            // The loser's labels stay searchable as aliases of the survivor.
+            let mut moved_labels = labels
+                .write()
+                .await
+                .remove(&redirect.from_qid)
+                .unwrap_or_default();
+            if let Some(loser) = loser {
+                for (lang, label, current) in [
+                    ("id", loser.label_id, &mut surviving.label_id),
+                    ("en", loser.label_en, &mut surviving.label_en),
+                ] {
+                    match (label, current.as_ref()) {
+                        (Some(label), None) => *current = Some(label),
+                        (Some(label), Some(existing)) if existing != &label => {
+                            moved_labels.push(OntologyConceptLabel {
+                                lang: lang.to_string(),
+                                label,
+                                alias: true,
+                            });
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            let surviving = surviving.clone();
+            {
+                let mut labels = labels.write().await;
+                let survivor_labels = labels.entry(redirect.to_qid.clone()).or_default();
+                for mut label in moved_labels {
+                    label.alias = true;
+                    if !survivor_labels.iter().any(|existing| {
+                        existing.lang == label.lang && existing.label == label.label
+                    }) {
+                        survivor_labels.push(label);
+                    }
+                }
+            }
+            by_qid.insert(redirect.to_qid.clone(), into_record.clone());
+
+            let mut moved_edge_count = 0;
+            {
+                let mut triples = triples.write().await;
+                let mut kept: Vec<OntologyTripleCreate> = Vec::with_capacity(triples.len());
+                for mut triple in triples.drain(..) {
+                    let concept_edge = matches!(
+                        triple.edge,
+                        OntologyEdgeKind::About | OntologyEdgeKind::InstanceOf
+                    );
+                    if concept_edge
+                        && Self::normalize_record_id(&triple.to_id, "concept") == from_record
+                    {
+                        moved_edge_count += 1;
+                        triple.to_id = into_record.clone();
+                    }
+                    // Either copy of an edge now doubled on the survivor may come first.
+                    if concept_edge
+                        && Self::normalize_record_id(&triple.to_id, "concept") == into_record
+                    {
+                        let from_id = Self::normalize_record_id(&triple.from_id, "note");
+                        if kept.iter().any(|existing| {
+                            existing.edge == triple.edge
+                                && Self::normalize_record_id(&existing.from_id, "note") == from_id
+                                && Self::normalize_record_id(&existing.to_id, "concept")
+                                    == into_record
+                        }) {
+                            continue;
+                        }
+                    }
+                    kept.push(triple);
+                }
+                *triples = kept;
+            }
+            {
+                let mut broader_edges = broader_edges.write().await;
+                let loser_targets = broader_edges.remove(&from_record).unwrap_or_default();
+                moved_edge_count += loser_targets.len();
+                let survivor_targets = broader_edges.entry(into_record.clone()).or_default();
+                for target in loser_targets {
+                    if target != into_record && !survivor_targets.contains(&target) {
+                        survivor_targets.push(target);
+                    }
+                }
+                for (narrower, targets) in broader_edges.iter_mut() {
+                    if !targets.contains(&from_record) {
+                        continue;
+                    }
+                    moved_edge_count += 1;
+                    targets.retain(|target| target != &from_record);
+                    if narrower != &into_record && !targets.contains(&into_record) {
+                        targets.push(into_record.clone());
+                    }
+                }
+                broader_edges.retain(|_, targets| !targets.is_empty());
+            }
+
+            let mut redirects = redirects.write().await;
+            for existing in redirects.values_mut() {
+                if existing.to_qid == redirect.from_qid {
+                    existing.to_qid = redirect.to_qid.clone();
+                }
+            }
+            redirects.remove(&redirect.to_qid);
+            redirects.insert(redirect.from_qid.clone(), redirect.clone());
+            Ok(OntologyConceptMergeResult {
+                surviving,
+                redirect,
+                moved_edge_count,
+            })
+        })
+    }
+
+    fn replace_concept_labels(
+        &self,
+        qid: &str,
+        labels: &[OntologyConceptLabel],
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<()>> {
+        let qid = Self::id_part(qid.trim());
+        let replacement = labels.to_vec();
+        let labels = self.labels.clone();
+        Box::pin(async move {
+            let mut labels = labels.write().await;
+            if replacement.is_empty() {
+                labels.remove(&qid);
+            } else {
+                labels.insert(qid, replacement);
+            }
+            Ok(())
+        })
+    }
+
+    fn get_concept_labels(
+        &self,
+        qids: &[String],
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<OntologyConceptLabels>>> {
+        let qids = qids.to_vec();
+        let concepts_by_qid = self.concepts_by_qid.clone();
+        let concepts_by_id = self.concepts_by_id.clone();
+        let labels = self.labels.clone();
+        Box::pin(async move {
+            let by_qid = concepts_by_qid.read().await;
+            let by_id = concepts_by_id.read().await;
+            let labels = labels.read().await;
+            let mut rows = Vec::new();
+            for qid in qids {
+                let Some(concept) = by_qid.get(&qid).and_then(|id| by_id.get(id)) else {
+                    continue;
+                };
+                if rows
+                    .iter()
+                    .any(|row: &OntologyConceptLabels| row.qid == concept.qid)
+                {
+                    continue;
+                }
+                rows.push(Self::concept_labels(concept, labels.get(&concept.qid)));
+            }
+            Ok(rows)
+        })
+    }
+
+    fn list_concept_labels(
+        &self,
+        after_qid: Option<&str>,
+        limit: usize,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<OntologyConceptLabels>>> {
+        let after_qid = after_qid.map(ToString::to_string);
+        let concepts_by_id = self.concepts_by_id.clone();
+        let labels = self.labels.clone();
+        Box::pin(async move {
+            let by_id = concepts_by_id.read().await;
+            let labels = labels.read().await;
+            let mut concepts = by_id
+                .values()
+                .filter(|concept| {
+                    after_qid
+                        .as_deref()
+                        .is_none_or(|after_qid| concept.qid.as_str() > after_qid)
+                })
+                .collect::<Vec<_>>();
+            concepts.sort_by(|left, right| left.qid.cmp(&right.qid));
+            Ok(concepts
+                .into_iter()
+                .take(limit)
+                .map(|concept| Self::concept_labels(concept, labels.get(&concept.qid)))
+                .collect())
+        })
+    }
+
+    fn concept_usage_counts(
+        &self,
+        community_id: &str,
+        qids: &[String],
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<OntologyConceptUsage>>> {
+        let community_id = community_id.trim().to_string();
+        let qids = qids.to_vec();
+        let notes = self.notes.clone();
+        let triples = self.triples.clone();
+        Box::pin(async move {
+            let notes = notes.read().await;
+            let triples = triples.read().await;
+            let mut usage = Vec::new();
+            for qid in qids {
+                let concept_record = Self::normalize_record_id(&qid, "concept");
+                let note_ids = triples
+                    .iter()
+                    .filter(|triple| {
+                        triple.edge == OntologyEdgeKind::About
+                            && Self::normalize_record_id(&triple.to_id, "concept") == concept_record
+                    })
+                    .map(|triple| Self::id_part(&triple.from_id))
+                    .collect::<HashSet<_>>();
+                let note_count = note_ids
+                    .iter()
+                    .filter_map(|note_id| notes.get(note_id))
+                    .filter(|note| note.community_id == community_id && note.rahasia_level == 0)
+                    .count();
+                if note_count > 0 {
+                    usage.push(OntologyConceptUsage { qid, note_count });
+                }
+            }
+            Ok(usage)
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct SurrealOntologyRepository {
+    client: Arc<Surreal<Client>>,
+}
+
+impl SurrealOntologyRepository {
+    pub fn with_client(client: Arc<Surreal<Client>>) -> Self {
+        Self { client }
+    }
+
+    pub async fn new(db_config: &DbConfig) -> anyhow::Result<Self> {
+        let db = Surreal::<Client>::init();
+        db.connect::<Ws>(&db_config.endpoint).await?;
+        db.signin(Root {
+            username: db_config.username.clone(),
+            password: db_config.password.clone(),
+        })
+        .await?;
+        db.use_ns(&db_config.namespace)
+            .use_db(&db_config.database)
+            .await?;
+        Ok(Self {
+            client: Arc::new(db),
+        })
+    }
+
+    fn normalize_record_id(raw: &str, table: &str) -> (String, String) {
+        let value = raw.trim();
+        if let Some((tbl, id)) = value.split_once(':') {
+            (tbl.to_string(), id.to_string())
+        } else {
+            (table.to_string(), value.to_string())
+        }
     }
 
     fn normalize_id_part(raw: &str) -> String {
@@ -3073,6 +3927,179 @@ impl SurrealOntologyRepository {
         Ok(count as usize)
     }
 
+    fn admin_area_from_row(row: SurrealAdminAreaRow) -> DomainResult<AdminArea> {
+        let boundary = row.boundary.map(|rings| {
+            rings
+                .into_iter()
+                .map(|ring| {
+                    ring.into_iter()
+                        .map(|[lng, lat]| GeoPoint { lat, lng })
+                        .collect()
+                })
+                .collect()
+        });
+        AdminArea::new(&row.code, &row.name, row.centroid, boundary)
+    }
+
+    fn note_from_row(row: SurrealOntologyNoteRow) -> DomainResult<OntologyNote> {
+        Ok(OntologyNote {
+            note_id: row
+                .note_id
+                .unwrap_or_else(|| Self::normalize_id_part(&row.record_id)),
+            content: row.content,
+            author_id: row
+                .author
+                .as_deref()
+                .map(Self::normalize_id_part)
+                .unwrap_or_default(),
+            community_id: row.community_id,
+            temporal_class: row.temporal_class,
+            ttl_expires_ms: row
+                .ttl_expires
+                .as_deref()
+                .map(Self::parse_datetime_ms)
+                .transpose()?,
+            ai_readable: row.ai_readable.unwrap_or(false),
+            rahasia_level: row.rahasia_level.unwrap_or(0),
+            confidence: row.confidence.unwrap_or(0.0),
+            created_at_ms: row
+                .created_at
+                .as_deref()
+                .map(Self::parse_datetime_ms)
+                .transpose()?
+                .unwrap_or_default(),
+            version: row.version.unwrap_or(FIRST_NOTE_VERSION),
+            edited_at_ms: row
+                .edited_at
+                .as_deref()
+                .map(Self::parse_datetime_ms)
+                .transpose()?,
+            retracted_at_ms: row
+                .retracted_at
+                .as_deref()
+                .map(Self::parse_datetime_ms)
+                .transpose()?,
+            retraction_reason: row.retraction_reason,
+        })
+    }
+
+    fn note_version_from_row(row: SurrealNoteVersionRow) -> DomainResult<OntologyNoteVersion> {
+        Ok(OntologyNoteVersion {
+            note_id: row.note_id,
+            version: row.version,
+            content: row.content,
+            confidence: row.confidence,
+            created_at_ms: Self::parse_datetime_ms(&row.created_at)?,
+            superseded_at_ms: Some(Self::parse_datetime_ms(&row.superseded_at)?),
+        })
+    }
+
+    async fn fetch_note(
+        client: &Surreal<Client>,
+        note_id: &str,
+    ) -> DomainResult<Option<OntologyNote>> {
+        let mut response = client
+            .query(format!(
+                "SELECT {ONTOLOGY_NOTE_FIELDS} FROM type::record('note', $note_id);"
+            ))
+            .bind(("note_id", note_id.to_string()))
+            .await
+            .map_err(Self::map_surreal_error)?;
+        let rows: Vec<Value> = response
+            .take(0)
+            .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+        Self::decode_rows::<SurrealOntologyNoteRow>(rows, "note")?
+            .into_iter()
+            .next()
+            .map(Self::note_from_row)
+            .transpose()
+    }
+
+    /// The surviving QID when `qid` was merged away.
+    async fn concept_redirect_target(
+        client: &Surreal<Client>,
+        qid: &str,
+    ) -> DomainResult<Option<String>> {
+        let mut response = client
+            .query("SELECT VALUE to_qid FROM concept_redirect WHERE from_qid = $qid LIMIT 1")
+            .bind(("qid", qid.to_string()))
+            .await
+            .map_err(Self::map_surreal_error)?;
+        let rows: Vec<String> = response
+            .take(0)
+            .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+        Ok(rows.into_iter().next())
+    }
+
+    /// SurrealQL binding `$<name>` to the concept record for the id in
+    /// `$<param>`, following a merge redirect so writes never recreate a
+    /// merged-away concept.
+    fn resolved_concept_record(name: &str, param: &str) -> String {
+        format!(
+            "LET ${name}_to = (SELECT VALUE to_qid FROM concept_redirect WHERE from_qid = ${param} LIMIT 1)[0]; \
+             LET ${name} = IF ${name}_to = NONE THEN type::record('concept', ${param}) \
+               ELSE (SELECT VALUE id FROM concept WHERE qid = ${name}_to LIMIT 1)[0] \
+                 ?? type::record('concept', ${name}_to) END; "
+        )
+    }
+
+    /// Preferred labels from `concepts` plus their stored `concept_label` rows,
+    /// in the order of `concepts`.
+    async fn concept_labels_for(
+        client: &Surreal<Client>,
+        concepts: Vec<SurrealOntologyConceptRow>,
+    ) -> DomainResult<Vec<OntologyConceptLabels>> {
+        if concepts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let qids = concepts
+            .iter()
+            .map(|concept| concept.qid.clone())
+            .collect::<Vec<_>>();
+        let mut response = client
+            .query(
+                "SELECT qid, lang, label, alias FROM concept_label \
+                 WHERE qid IN $qids ORDER BY qid, lang, label",
+            )
+            .bind(("qids", qids))
+            .await
+            .map_err(Self::map_surreal_error)?;
+        let rows: Vec<Value> = response
+            .take(0)
+            .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+        let mut stored: HashMap<String, Vec<OntologyConceptLabel>> = HashMap::new();
+        for row in Self::decode_rows::<SurrealConceptLabelRow>(rows, "concept label")? {
+            stored
+                .entry(row.qid)
+                .or_default()
+                .push(OntologyConceptLabel {
+                    lang: row.lang,
+                    label: row.label,
+                    alias: row.alias.unwrap_or(false),
+                });
+        }
+        Ok(concepts
+            .into_iter()
+            .map(|concept| {
+                let mut labels = [("id", concept.label_id), ("en", concept.label_en)]
+                    .into_iter()
+                    .filter_map(|(lang, label)| {
+                        label.map(|label| OntologyConceptLabel {
+                            lang: lang.to_string(),
+                            label,
+                            alias: false,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                labels.extend(stored.remove(&concept.qid).unwrap_or_default());
+                OntologyConceptLabels {
+                    qid: concept.qid,
+                    labels,
+                }
+            })
+            .collect())
+    }
+
     pub async fn note_ttl_expires_ms_by_note_ids(
         &self,
         note_ids: &[String],
@@ -3165,43 +4192,188 @@ struct SurrealOntologyNoteTtlRow {
     ttl_expires: Option<String>,
 }
 
-impl OntologyRepository for SurrealOntologyRepository {
-    fn upsert_concept(
-        &self,
-        concept: &OntologyConcept,
-    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<OntologyConcept>> {
-        let client = self.client.clone();
-        let concept = concept.clone();
-        Box::pin(async move {
-            if concept.qid.trim().is_empty() {
-                return Err(DomainError::Validation("qid is required".to_string()));
-            }
-            let concept_id = if concept.concept_id.trim().is_empty() {
-                concept.qid.clone()
-            } else {
-                Self::normalize_id_part(&concept.concept_id)
-            };
-            let mut response = client
-                .query(
-                    "UPDATE type::record('concept', $concept_id) MERGE {\n\
-                       qid: $qid,\n\
-                       label_id: $label_id,\n\
-                       label_en: $label_en,\n\
-                       verified: $verified,\n\
-                       last_referenced: time::now()\n\
-                     } RETURN AFTER",
-                )
-                .bind(("concept_id", concept_id.clone()))
-                .bind(("qid", concept.qid.clone()))
-                .bind(("label_id", concept.label_id.clone()))
-                .bind(("label_en", concept.label_en.clone()))
-                .bind(("verified", concept.verified))
-                .await
-                .map_err(Self::map_surreal_error)?;
+#[derive(Debug, Deserialize, Serialize)]
+struct SurrealConceptRedirectRow {
+    from_qid: String,
+    to_qid: String,
+    reason: String,
+    created_at: String,
+}
 
-            let rows: Vec<Value> = response
-                .take(0)
-                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+#[derive(Debug, Deserialize, Serialize)]
+struct SurrealConceptLabelRow {
+    qid: String,
+    lang: String,
+    label: String,
+    alias: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SurrealConceptUsageRow {
+    qid: String,
+    note_count: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SurrealOntologyBroaderRow {
+    narrower_qid: Option<String>,
+    broader_qid: Option<String>,
+    narrower_id: String,
+    broader_id: String,
+}
+
+/// Columns decoded by [`SurrealOntologyNoteRow`].
+const ONTOLOGY_NOTE_FIELDS: &str = "note_id, type::string(id) AS record_id, content, \
+     type::string(author) AS author, community_id, temporal_class, \
+     <string>created_at AS created_at, \
+     IF ttl_expires = NONE THEN NONE ELSE <string>ttl_expires END AS ttl_expires, \
+     ai_readable, rahasia_level, confidence, version, \
+     IF edited_at = NONE THEN NONE ELSE <string>edited_at END AS edited_at, \
+     IF retracted_at = NONE THEN NONE ELSE <string>retracted_at END AS retracted_at, \
+     retraction_reason";
+
+/// Columns decoded by [`SurrealNoteVersionRow`].
+const NOTE_VERSION_FIELDS: &str = "note_id, version, content, confidence, \
+     <string>created_at AS created_at, <string>superseded_at AS superseded_at";
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SurrealNoteVersionRow {
+    note_id: String,
+    version: i64,
+    content: String,
+    confidence: f64,
+    created_at: String,
+    superseded_at: String,
+}
+
+/// Columns decoded by [`SurrealAdminAreaRow`].
+const ADMIN_AREA_FIELDS: &str = "code, name, centroid, boundary";
+
+/// Boundary rings are stored GeoJSON-style as `[lng, lat]` pairs.
+#[derive(Debug, Deserialize, Serialize)]
+struct SurrealAdminAreaRow {
+    code: String,
+    name: String,
+    centroid: GeoPoint,
+    boundary: Option<Vec<Vec<[f64; 2]>>>,
+}
+
+/// Columns decoded by [`SurrealNoteFeedbackRow`]; edges written before
+/// feedback was timestamped have no `created_at`, and edges written before
+/// notes could be edited have no `note_version`.
+const NOTE_FEEDBACK_FIELDS: &str = "<string>record::id(in) AS voter_id, \
+     IF created_at = NONE THEN NONE ELSE <string>created_at END AS created_at, note_version";
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SurrealNoteFeedbackRow {
+    voter_id: String,
+    created_at: Option<String>,
+    note_version: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SurrealOntologyNoteMatchRow {
+    #[serde(flatten)]
+    note: SurrealOntologyNoteRow,
+    #[serde(default)]
+    about: Vec<String>,
+    #[serde(default)]
+    located_at: Vec<String>,
+    #[serde(default)]
+    has_action: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SurrealOntologyNoteRow {
+    note_id: Option<String>,
+    record_id: String,
+    content: String,
+    author: Option<String>,
+    community_id: String,
+    temporal_class: String,
+    created_at: Option<String>,
+    ttl_expires: Option<String>,
+    ai_readable: Option<bool>,
+    rahasia_level: Option<i64>,
+    confidence: Option<f64>,
+    version: Option<i64>,
+    edited_at: Option<String>,
+    retracted_at: Option<String>,
+    retraction_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SurrealOntologyTripleRow {
+    from_id: String,
+    to_id: String,
+    predicate: Option<String>,
+}
+
+impl OntologyRepository for SurrealOntologyRepository {
+    fn upsert_concept(
+        &self,
+        concept: &OntologyConcept,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<OntologyConcept>> {
+        let client = self.client.clone();
+        let mut concept = concept.clone();
+        Box::pin(async move {
+            if concept.qid.trim().is_empty() {
+                return Err(DomainError::Validation("qid is required".to_string()));
+            }
+            // A re-import of a merged-away QID keeps the survivor as it is.
+            if let Some(target) = Self::concept_redirect_target(&client, &concept.qid).await? {
+                let mut response = client
+                    .query(
+                        "SELECT qid, label_id, label_en, verified FROM concept \
+                         WHERE qid = $qid LIMIT 1",
+                    )
+                    .bind(("qid", target.clone()))
+                    .await
+                    .map_err(Self::map_surreal_error)?;
+                let rows: Vec<Value> = response.take(0).map_err(|err| {
+                    DomainError::Validation(format!("invalid query result: {err}"))
+                })?;
+                if let Some(row) = Self::decode_rows::<SurrealOntologyConceptRow>(rows, "concept")?
+                    .into_iter()
+                    .next()
+                {
+                    return Ok(OntologyConcept {
+                        concept_id: row.qid.clone(),
+                        qid: row.qid,
+                        label_id: row.label_id,
+                        label_en: row.label_en,
+                        verified: row.verified.unwrap_or(false),
+                    });
+                }
+                concept.qid = target;
+                concept.concept_id = String::new();
+            }
+            let concept_id = if concept.concept_id.trim().is_empty() {
+                concept.qid.clone()
+            } else {
+                Self::normalize_id_part(&concept.concept_id)
+            };
+            let mut response = client
+                .query(
+                    "UPDATE type::record('concept', $concept_id) MERGE {\n\
+                       qid: $qid,\n\
+                       label_id: $label_id,\n\
+                       label_en: $label_en,\n\
+                       verified: $verified,\n\
+                       last_referenced: time::now()\n\
+                     } RETURN AFTER",
+                )
+                .bind(("concept_id", concept_id.clone()))
+                .bind(("qid", concept.qid.clone()))
+                .bind(("label_id", concept.label_id.clone()))
+                .bind(("label_en", concept.label_en.clone()))
+                .bind(("verified", concept.verified))
+                .await
+                .map_err(Self::map_surreal_error)?;
+
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
             let mut rows: Vec<SurrealOntologyConceptRow> = Self::decode_rows(rows, "concept")?;
             let row = rows
                 .pop()
@@ -3225,12 +4397,17 @@ impl OntologyRepository for SurrealOntologyRepository {
         let narrower_id = Self::normalize_id_part(narrower_concept_id);
         let broader_id = Self::normalize_id_part(broader_concept_id);
         Box::pin(async move {
+            // Idempotent so dump imports can be rerun over the same range.
             client
-                .query(
-                    "CREATE BROADER SET \
-                     in = type::record('concept', $narrower_id), \
-                     out = type::record('concept', $broader_id)",
-                )
+                .query(format!(
+                    "{}{}\
+                     IF $narrower != $broader AND array::len((SELECT id FROM BROADER \
+                         WHERE in = $narrower AND out = $broader LIMIT 1)) = 0 {{ \
+                         CREATE BROADER SET in = $narrower, out = $broader; \
+                     }};",
+                    Self::resolved_concept_record("narrower", "narrower_id"),
+                    Self::resolved_concept_record("broader", "broader_id"),
+                ))
                 .bind(("narrower_id", narrower_id))
                 .bind(("broader_id", broader_id))
                 .await
@@ -3275,7 +4452,8 @@ impl OntologyRepository for SurrealOntologyRepository {
                        ttl_expires = IF $ttl_expires = NONE THEN NONE ELSE <datetime>$ttl_expires END,\n\
                        ai_readable = $ai_readable,\n\
                        rahasia_level = $rahasia_level,\n\
-                       confidence = $confidence RETURN AFTER",
+                       confidence = $confidence,\n\
+                       version = $version RETURN AFTER",
                 )
                 .bind(("note_id", note_id.clone()))
                 .bind(("content", content.clone()))
@@ -3287,6 +4465,7 @@ impl OntologyRepository for SurrealOntologyRepository {
                 .bind(("ai_readable", note.ai_readable))
                 .bind(("rahasia_level", note.rahasia_level))
                 .bind(("confidence", note.confidence))
+                .bind(("version", FIRST_NOTE_VERSION))
                 .await
                 .map_err(Self::map_surreal_error)?;
 
@@ -3301,6 +4480,10 @@ impl OntologyRepository for SurrealOntologyRepository {
                 rahasia_level: note.rahasia_level,
                 confidence: note.confidence,
                 created_at_ms,
+                version: FIRST_NOTE_VERSION,
+                edited_at_ms: None,
+                retracted_at_ms: None,
+                retraction_reason: None,
             })
         })
     }
@@ -3315,12 +4498,30 @@ impl OntologyRepository for SurrealOntologyRepository {
             for triple in &triples {
                 let (from_table, from_id) = Self::normalize_record_id(&triple.from_id, "note");
                 let (to_table, to_id) = Self::normalize_record_id(&triple.to_id, "concept");
+                // Feedback is timestamped so note ranking can decay old votes,
+                // and records the note version it was cast on.
+                let feedback_fields = match triple.edge {
+                    OntologyEdgeKind::Vouches | OntologyEdgeKind::Challenges => format!(
+                        ", created_at = time::now(), \
+                         note_version = (type::record('{to_table}', $to_id)).version ?? {FIRST_NOTE_VERSION}"
+                    ),
+                    _ => String::new(),
+                };
+                // Concept targets follow merge redirects.
+                let (resolve_out, out) = if to_table == "concept" {
+                    (
+                        Self::resolved_concept_record("out", "to_id"),
+                        "$out".to_string(),
+                    )
+                } else {
+                    (String::new(), format!("type::record('{to_table}', $to_id)"))
+                };
                 let statement = format!(
-                    "CREATE {} SET \
+                    "{resolve_out}CREATE {} SET \
                      in = type::record('{from_table}', $from_id), \
-                     out = type::record('{to_table}', $to_id), \
+                     out = {out}, \
                      predicate = IF $predicate = NULL THEN NONE ELSE $predicate END, \
-                     metadata = IF $metadata = NULL THEN NONE ELSE $metadata END",
+                     metadata = IF $metadata = NULL THEN NONE ELSE $metadata END{feedback_fields}",
                     triple.edge.as_table_name()
                 );
                 client
@@ -3377,12 +4578,15 @@ impl OntologyRepository for SurrealOntologyRepository {
         let qid = qid.to_string();
         Box::pin(async move {
             let mut response = client
-                .query("SELECT qid, label_id, label_en, verified FROM concept WHERE qid = $qid LIMIT 1")
+                .query(
+                    "LET $target = (SELECT VALUE to_qid FROM concept_redirect WHERE from_qid = $qid LIMIT 1)[0] ?? $qid;\n\
+                     SELECT qid, label_id, label_en, verified FROM concept WHERE qid = $target LIMIT 1;",
+                )
                 .bind(("qid", qid.clone()))
                 .await
                 .map_err(Self::map_surreal_error)?;
             let rows: Vec<Value> = response
-                .take(0)
+                .take(1)
                 .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
             let rows: Vec<SurrealOntologyConceptRow> = Self::decode_rows(rows, "concept")?;
             Ok(rows.into_iter().next().map(|row| OntologyConcept {
@@ -3406,7 +4610,11 @@ impl OntologyRepository for SurrealOntologyRepository {
                 return Ok(Vec::new());
             }
             let mut response = client
-                .query("SELECT qid, label_id, label_en, verified FROM concept WHERE qid IN $qids")
+                .query(
+                    "SELECT qid, label_id, label_en, verified FROM concept \
+                     WHERE qid IN $qids \
+                     OR qid IN (SELECT VALUE to_qid FROM concept_redirect WHERE from_qid IN $qids)",
+                )
                 .bind(("qids", qids))
                 .await
                 .map_err(Self::map_surreal_error)?;
@@ -3495,6 +4703,165 @@ impl OntologyRepository for SurrealOntologyRepository {
         })
     }
 
+    fn upsert_admin_areas(
+        &self,
+        areas: &[AdminArea],
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<()>> {
+        let client = self.client.clone();
+        let areas = areas
+            .iter()
+            .map(|area| -> DomainResult<Value> {
+                area.validate()?;
+                let place_id = area.place_id();
+                let mut content = serde_json::json!({
+                    "code": area.code,
+                    "name": area.name,
+                    "level": area.level.as_str(),
+                    "centroid": { "lat": area.centroid.lat, "lng": area.centroid.lng },
+                    "centroid_geohash": area.centroid.geohash(),
+                });
+                if let Some(parent_code) = area.parent_code.as_ref() {
+                    content["parent_code"] = serde_json::json!(parent_code);
+                }
+                if let Some(rings) = area.boundary.as_ref() {
+                    content["boundary"] = serde_json::json!(
+                        rings
+                            .iter()
+                            .map(|ring| ring
+                                .iter()
+                                .map(|point| [point.lng, point.lat])
+                                .collect::<Vec<_>>())
+                            .collect::<Vec<_>>()
+                    );
+                    content["cover_geohash"] = serde_json::json!(area.cover_geohash());
+                }
+                Ok(serde_json::json!({
+                    "content": content,
+                    "place_key": Self::normalize_id_part(&place_id),
+                }))
+            })
+            .collect::<DomainResult<Vec<_>>>();
+        Box::pin(async move {
+            let areas = areas?;
+            if areas.is_empty() {
+                return Ok(());
+            }
+            client
+                .query(
+                    "BEGIN TRANSACTION;\n\
+                     FOR $area IN $areas {\n\
+                       UPSERT type::record('admin_area', $area.content.code) CONTENT $area.content;\n\
+                       UPSERT type::record('place', $area.place_key) SET \
+                         name = $area.content.name, source = $source, \
+                         location = type::point([$area.content.centroid.lng, $area.content.centroid.lat]);\n\
+                     };\n\
+                     COMMIT TRANSACTION;",
+                )
+                .bind(("areas", areas))
+                .bind(("source", ADMIN_AREA_PLACE_SOURCE))
+                .await
+                .map_err(Self::map_surreal_error)?
+                .check()
+                .map_err(Self::map_surreal_error)?;
+            Ok(())
+        })
+    }
+
+    fn get_admin_areas(
+        &self,
+        codes: &[String],
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<AdminArea>>> {
+        let client = self.client.clone();
+        let codes = codes
+            .iter()
+            .map(|code| code.trim().to_string())
+            .collect::<Vec<_>>();
+        Box::pin(async move {
+            if codes.is_empty() {
+                return Ok(Vec::new());
+            }
+            let mut response = client
+                .query(format!(
+                    "SELECT {ADMIN_AREA_FIELDS} FROM admin_area WHERE code IN $codes"
+                ))
+                .bind(("codes", codes))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            Self::decode_rows::<SurrealAdminAreaRow>(rows, "admin area")?
+                .into_iter()
+                .map(Self::admin_area_from_row)
+                .collect()
+        })
+    }
+
+    fn list_admin_areas_covering(
+        &self,
+        geohash: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<AdminArea>>> {
+        let client = self.client.clone();
+        // The empty prefix holds areas whose box straddles a top-level cell.
+        let mut prefixes = vec![String::new()];
+        prefixes.extend(geohash_prefixes(geohash.trim()));
+        Box::pin(async move {
+            let mut response = client
+                .query(format!(
+                    "SELECT {ADMIN_AREA_FIELDS} FROM admin_area WHERE cover_geohash IN $prefixes"
+                ))
+                .bind(("prefixes", prefixes))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            Self::decode_rows::<SurrealAdminAreaRow>(rows, "admin area")?
+                .into_iter()
+                .map(Self::admin_area_from_row)
+                .collect()
+        })
+    }
+
+    fn list_admin_areas_near(
+        &self,
+        near: &GeoRadius,
+        level: AdminLevel,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<AdminArea>>> {
+        let client = self.client.clone();
+        let cells = near.covering_geohashes();
+        Box::pin(async move {
+            // Same `[cell, cell~)` ranges as the feed's near filter.
+            let ranges = FEED_GEOHASH_BINDS
+                .iter()
+                .take(cells.len())
+                .map(|(lo, hi)| format!("(centroid_geohash >= ${lo} AND centroid_geohash < ${hi})"))
+                .collect::<Vec<_>>();
+            let geohash_clause = if ranges.is_empty() {
+                String::new()
+            } else {
+                format!(" AND ({})", ranges.join(" OR "))
+            };
+            let mut query = client
+                .query(format!(
+                    "SELECT {ADMIN_AREA_FIELDS} FROM admin_area \
+                     WHERE level = $level{geohash_clause}"
+                ))
+                .bind(("level", level.as_str()));
+            for ((lo, hi), cell) in FEED_GEOHASH_BINDS.iter().zip(cells) {
+                query = query.bind((*hi, format!("{cell}~"))).bind((*lo, cell));
+            }
+            let mut response = query.await.map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            Self::decode_rows::<SurrealAdminAreaRow>(rows, "admin area")?
+                .into_iter()
+                .map(Self::admin_area_from_row)
+                .collect()
+        })
+    }
+
     fn list_broader_concepts(
         &self,
         concept_id: &str,
@@ -3527,6 +4894,44 @@ impl OntologyRepository for SurrealOntologyRepository {
         })
     }
 
+    fn list_narrower_concepts(
+        &self,
+        broader_qids: &[String],
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<OntologyBroaderEdge>>> {
+        let client = self.client.clone();
+        let broader_qids = broader_qids.to_vec();
+        Box::pin(async move {
+            if broader_qids.is_empty() {
+                return Ok(Vec::new());
+            }
+            let mut response = client
+                .query(
+                    "SELECT in.qid AS narrower_qid, out.qid AS broader_qid, \
+                       type::string(in) AS narrower_id, type::string(out) AS broader_id \
+                     FROM BROADER WHERE out.qid IN $qids OR record::id(out) IN $qids",
+                )
+                .bind(("qids", broader_qids))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let mut edges = Self::decode_rows::<SurrealOntologyBroaderRow>(rows, "broader")?
+                .into_iter()
+                .map(|row| OntologyBroaderEdge {
+                    narrower_qid: row
+                        .narrower_qid
+                        .unwrap_or_else(|| Self::normalize_id_part(&row.narrower_id)),
+                    broader_qid: row
+                        .broader_qid
+                        .unwrap_or_else(|| Self::normalize_id_part(&row.broader_id)),
+                })
+                .collect::<Vec<_>>();
+            edges.sort_by(|left, right| left.narrower_qid.cmp(&right.narrower_qid));
+            Ok(edges)
+        })
+    }
+
     fn note_feedback_counts(
         &self,
         note_id: &str,
@@ -3536,57 +4941,224 @@ impl OntologyRepository for SurrealOntologyRepository {
         Box::pin(async move {
             let mut response = client
                 .query(
-                    "SELECT count() AS vouch_count FROM VOUCHES WHERE out = type::record('note', $note_id) GROUP ALL;\n\
-                     SELECT count() AS challenge_count FROM CHALLENGES WHERE out = type::record('note', $note_id) GROUP ALL;",
+                    "LET $note = type::record('note', $note_id);\n\
+                     LET $version = $note.version ?? $first_version;\n\
+                     SELECT count() AS vouch_count FROM VOUCHES WHERE out = $note GROUP ALL;\n\
+                     SELECT count() AS challenge_count FROM CHALLENGES WHERE out = $note GROUP ALL;\n\
+                     SELECT count() AS earlier_version_vouch_count FROM VOUCHES \
+                       WHERE out = $note AND (note_version ?? $first_version) < $version GROUP ALL;\n\
+                     SELECT count() AS earlier_version_challenge_count FROM CHALLENGES \
+                       WHERE out = $note AND (note_version ?? $first_version) < $version GROUP ALL;",
                 )
                 .bind(("note_id", note_id))
+                .bind(("first_version", FIRST_NOTE_VERSION))
                 .await
                 .map_err(Self::map_surreal_error)?;
-            let vouch_rows: Vec<Value> = response
-                .take(0)
-                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
-            let challenge_rows: Vec<Value> = response
-                .take(1)
-                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let mut count = |index: usize, key: &str| -> DomainResult<usize> {
+                let rows: Vec<Value> = response.take(index).map_err(|err| {
+                    DomainError::Validation(format!("invalid query result: {err}"))
+                })?;
+                Self::decode_count(rows, key)
+            };
             Ok(NoteFeedbackCounts {
-                vouch_count: Self::decode_count(vouch_rows, "vouch_count")?,
-                challenge_count: Self::decode_count(challenge_rows, "challenge_count")?,
+                vouch_count: count(2, "vouch_count")?,
+                challenge_count: count(3, "challenge_count")?,
+                earlier_version_vouch_count: count(4, "earlier_version_vouch_count")?,
+                earlier_version_challenge_count: count(5, "earlier_version_challenge_count")?,
             })
         })
     }
 
-    fn cleanup_expired_notes(
+    fn list_note_feedback_votes(
         &self,
-        cutoff_ms: i64,
-    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<String>>> {
+        note_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<NoteFeedbackVote>>> {
         let client = self.client.clone();
+        let note_id = Self::normalize_id_part(note_id);
         Box::pin(async move {
-            if cutoff_ms < 0 {
+            let mut response = client
+                .query(format!(
+                    "SELECT {NOTE_FEEDBACK_FIELDS} FROM VOUCHES WHERE out = type::record('note', $note_id);\n\
+                     SELECT {NOTE_FEEDBACK_FIELDS} FROM CHALLENGES WHERE out = type::record('note', $note_id);",
+                ))
+                .bind(("note_id", note_id))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let mut votes = Vec::new();
+            for (index, kind) in [NoteFeedbackKind::Vouch, NoteFeedbackKind::Challenge]
+                .into_iter()
+                .enumerate()
+            {
+                let rows: Vec<Value> = response.take(index).map_err(|err| {
+                    DomainError::Validation(format!("invalid query result: {err}"))
+                })?;
+                for row in Self::decode_rows::<SurrealNoteFeedbackRow>(rows, "note feedback")? {
+                    votes.push(NoteFeedbackVote {
+                        voter_id: row.voter_id,
+                        kind,
+                        created_at_ms: row
+                            .created_at
+                            .as_deref()
+                            .map(Self::parse_datetime_ms)
+                            .transpose()?,
+                        note_version: row.note_version.unwrap_or(FIRST_NOTE_VERSION),
+                    });
+                }
+            }
+            Ok(votes)
+        })
+    }
+
+    fn get_note(
+        &self,
+        note_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<OntologyNote>>> {
+        let client = self.client.clone();
+        let note_id = Self::normalize_id_part(note_id.trim());
+        Box::pin(async move { Self::fetch_note(&client, &note_id).await })
+    }
+
+    fn update_note(
+        &self,
+        update: &OntologyNoteUpdate,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<OntologyNote>> {
+        let client = self.client.clone();
+        let update = update.clone();
+        Box::pin(async move {
+            let content = update.content.trim().to_string();
+            if content.is_empty() {
                 return Err(DomainError::Validation(
-                    "cleanup cutoff_ms must be non-negative".to_string(),
+                    "note content is required".to_string(),
                 ));
             }
-
-            let cutoff = Self::to_rfc3339(cutoff_ms)?;
-            let expired_rows: Vec<Value> = client
+            let note_id = Self::normalize_id_part(update.note_id.trim());
+            if Self::fetch_note(&client, &note_id).await?.is_none() {
+                return Err(DomainError::NotFound);
+            }
+            // The version check and the archive row share one transaction, so
+            // two racing edits cannot both land on the same version.
+            client
                 .query(
-                    "SELECT VALUE id FROM note WHERE ttl_expires IS NOT NONE AND ttl_expires < $cutoff",
+                    "BEGIN TRANSACTION;\n\
+                     LET $note = (SELECT * FROM ONLY type::record('note', $note_id));\n\
+                     IF ($note.version ?? $first_version) != $expected_version OR $note.retracted_at != NONE {\n\
+                       THROW 'note version conflict';\n\
+                     };\n\
+                     CREATE note_version CONTENT {\n\
+                       note_id: $note_id,\n\
+                       version: $expected_version,\n\
+                       content: $note.content,\n\
+                       confidence: $note.confidence,\n\
+                       created_at: $note.edited_at ?? $note.created_at,\n\
+                       superseded_at: <datetime>$edited_at\n\
+                     };\n\
+                     UPDATE type::record('note', $note_id) SET \
+                       content = $content, confidence = $confidence, \
+                       version = $expected_version + 1, edited_at = <datetime>$edited_at;\n\
+                     COMMIT TRANSACTION;",
                 )
-                .bind(("cutoff", cutoff))
+                .bind(("note_id", note_id.clone()))
+                .bind(("first_version", FIRST_NOTE_VERSION))
+                .bind(("expected_version", update.expected_version))
+                .bind(("content", content))
+                .bind(("confidence", update.confidence))
+                .bind(("edited_at", Self::to_rfc3339(update.edited_at_ms)?))
                 .await
                 .map_err(Self::map_surreal_error)?
-                .take(0)
-                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
-            let expired_note_ids: Vec<String> = expired_rows
-                .into_iter()
-                .filter_map(|value| {
-                    value.as_str().map(ToString::to_string).or_else(|| {
-                        value
-                            .get("id")
-                            .and_then(Value::as_str)
-                            .map(ToString::to_string)
-                    })
-                })
+                .check()
+                .map_err(Self::map_surreal_error)?;
+            Self::fetch_note(&client, &note_id)
+                .await?
+                .ok_or(DomainError::NotFound)
+        })
+    }
+
+    fn retract_note(
+        &self,
+        note_id: &str,
+        reason: Option<String>,
+        retracted_at_ms: i64,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<OntologyNote>> {
+        let client = self.client.clone();
+        let note_id = Self::normalize_id_part(note_id.trim());
+        Box::pin(async move {
+            client
+                .query(
+                    "UPDATE type::record('note', $note_id) SET \
+                       retracted_at = <datetime>$retracted_at, \
+                       retraction_reason = IF $reason = NULL THEN NONE ELSE $reason END \
+                     WHERE retracted_at = NONE;",
+                )
+                .bind(("note_id", note_id.clone()))
+                .bind(("retracted_at", Self::to_rfc3339(retracted_at_ms)?))
+                .bind(("reason", reason))
+                .await
+                .map_err(Self::map_surreal_error)?
+                .check()
+                .map_err(Self::map_surreal_error)?;
+            Self::fetch_note(&client, &note_id)
+                .await?
+                .ok_or(DomainError::NotFound)
+        })
+    }
+
+    fn list_note_versions(
+        &self,
+        note_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<OntologyNoteVersion>>> {
+        let client = self.client.clone();
+        let note_id = Self::normalize_id_part(note_id.trim());
+        Box::pin(async move {
+            let mut response = client
+                .query(format!(
+                    "SELECT {NOTE_VERSION_FIELDS} FROM note_version \
+                     WHERE note_id = $note_id ORDER BY version ASC;"
+                ))
+                .bind(("note_id", note_id))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            Self::decode_rows::<SurrealNoteVersionRow>(rows, "note version")?
+                .into_iter()
+                .map(Self::note_version_from_row)
+                .collect()
+        })
+    }
+
+    fn cleanup_expired_notes(
+        &self,
+        cutoff_ms: i64,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<String>>> {
+        let client = self.client.clone();
+        Box::pin(async move {
+            if cutoff_ms < 0 {
+                return Err(DomainError::Validation(
+                    "cleanup cutoff_ms must be non-negative".to_string(),
+                ));
+            }
+
+            let cutoff = Self::to_rfc3339(cutoff_ms)?;
+            let expired_rows: Vec<Value> = client
+                .query(
+                    "SELECT VALUE id FROM note WHERE ttl_expires IS NOT NONE AND ttl_expires < $cutoff",
+                )
+                .bind(("cutoff", cutoff))
+                .await
+                .map_err(Self::map_surreal_error)?
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let expired_note_ids: Vec<String> = expired_rows
+                .into_iter()
+                .filter_map(|value| {
+                    value.as_str().map(ToString::to_string).or_else(|| {
+                        value
+                            .get("id")
+                            .and_then(Value::as_str)
+                            .map(ToString::to_string)
+                    })
+                })
                 .collect();
             if expired_note_ids.is_empty() {
                 return Ok(Vec::new());
@@ -3625,6 +5197,16 @@ impl OntologyRepository for SurrealOntologyRepository {
                         })?;
                 }
 
+                let _deleted_version_rows: Vec<Value> = client
+                    .query("DELETE note_version WHERE note_id = $note_id")
+                    .bind(("note_id", note_id.clone()))
+                    .await
+                    .map_err(Self::map_surreal_error)?
+                    .take(0)
+                    .map_err(|err| {
+                        DomainError::Validation(format!("invalid query result: {err}"))
+                    })?;
+
                 let _deleted_measurement_rows: Vec<Value> = client
                     .query("DELETE measurement WHERE note = type::record('note', $note_id)")
                     .bind(("note_id", note_id))
@@ -3645,110 +5227,679 @@ impl OntologyRepository for SurrealOntologyRepository {
             Ok(normalized_expired_note_ids)
         })
     }
-}
 
-#[cfg(test)]
-mod ontology_repository_tests {
-    use super::*;
+    fn export_graph(
+        &self,
+        filter: &OntologyExportFilter,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<OntologyExportGraph>> {
+        let client = self.client.clone();
+        let filter = filter.clone();
+        Box::pin(async move {
+            let mut note_filters = vec![
+                "rahasia_level >= 0",
+                "rahasia_level <= $max_rahasia_level",
+                "retracted_at = NONE",
+            ];
+            if filter.ai_readable_only {
+                note_filters.push("ai_readable = true");
+            }
+            if filter.community_id.is_some() {
+                note_filters.push("community_id = $community_id");
+            }
+            if filter.after_note_id.is_some() {
+                note_filters.push("note_id > $after_note_id");
+            }
+            let page_limit = filter
+                .limit
+                .map(|limit| format!(" LIMIT {}", limit + 1))
+                .unwrap_or_default();
+            let note_rows: Vec<Value> = client
+                .query(format!(
+                    "SELECT {ONTOLOGY_NOTE_FIELDS} FROM note WHERE {} ORDER BY note_id{page_limit};",
+                    note_filters.join(" AND ")
+                ))
+                .bind(("max_rahasia_level", filter.max_rahasia_level))
+                .bind((
+                    "community_id",
+                    filter.community_id.clone().unwrap_or_default(),
+                ))
+                .bind((
+                    "after_note_id",
+                    filter.after_note_id.clone().unwrap_or_default(),
+                ))
+                .await
+                .map_err(Self::map_surreal_error)?
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let notes = Self::decode_rows::<SurrealOntologyNoteRow>(note_rows, "note")?
+                .into_iter()
+                .map(Self::note_from_row)
+                .collect::<DomainResult<Vec<_>>>()?;
 
-    #[tokio::test]
-    async fn in_memory_ontology_repository_supports_core_flows() {
-        let repo = InMemoryOntologyRepository::new();
+            // Record ids, not stringified ids, so the edge tables' `in` index applies.
+            let note_records = notes
+                .iter()
+                .map(|note| RecordId::new("note", note.note_id.as_str()))
+                .collect::<Vec<_>>();
+            let mut triples = Vec::new();
+            if !note_records.is_empty() {
+                for edge in [
+                    OntologyEdgeKind::About,
+                    OntologyEdgeKind::LocatedAt,
+                    OntologyEdgeKind::HasAction,
+                    OntologyEdgeKind::InstanceOf,
+                ] {
+                    let rows: Vec<Value> = client
+                        .query(format!(
+                            "SELECT type::string(in) AS from_id, type::string(out) AS to_id, predicate \
+                             FROM {} WHERE in IN $note_records",
+                            edge.as_table_name()
+                        ))
+                        .bind(("note_records", note_records.clone()))
+                        .await
+                        .map_err(Self::map_surreal_error)?
+                        .take(0)
+                        .map_err(|err| {
+                            DomainError::Validation(format!("invalid query result: {err}"))
+                        })?;
+                    triples.extend(
+                        Self::decode_rows::<SurrealOntologyTripleRow>(rows, "triple")?
+                            .into_iter()
+                            .map(|row| OntologyTripleCreate {
+                                edge: edge.clone(),
+                                from_id: row.from_id,
+                                to_id: row.to_id,
+                                predicate: row.predicate,
+                                metadata: None,
+                            }),
+                    );
+                }
+            }
 
-        let concept_a = repo
-            .upsert_concept(&OntologyConcept {
-                concept_id: "Q93189".to_string(),
-                qid: "Q93189".to_string(),
-                label_id: Some("Telur".to_string()),
-                label_en: Some("Egg".to_string()),
-                verified: true,
-            })
-            .await
-            .expect("upsert concept A");
-        let _concept_b = repo
-            .upsert_concept(&OntologyConcept {
-                concept_id: "Q2095".to_string(),
-                qid: "Q2095".to_string(),
-                label_id: Some("Makanan".to_string()),
-                label_en: Some("Food".to_string()),
-                verified: true,
-            })
-            .await
-            .expect("upsert concept B");
+            // A page only carries the concepts its triples point at.
+            let mut response = if filter.is_paged() {
+                let mut concept_records = triples
+                    .iter()
+                    .filter(|triple| {
+                        matches!(
+                            triple.edge,
+                            OntologyEdgeKind::About | OntologyEdgeKind::InstanceOf
+                        )
+                    })
+                    .filter_map(|triple| triple.to_id.strip_prefix("concept:"))
+                    .map(|key| RecordId::new("concept", key))
+                    .collect::<Vec<_>>();
+                concept_records.sort();
+                concept_records.dedup();
+                client
+                    .query(
+                        "SELECT qid, label_id, label_en, verified FROM concept \
+                           WHERE id IN $concept_records ORDER BY qid;\n\
+                         SELECT in.qid AS narrower_qid, out.qid AS broader_qid, \
+                           type::string(in) AS narrower_id, type::string(out) AS broader_id \
+                           FROM BROADER WHERE in IN $concept_records;",
+                    )
+                    .bind(("concept_records", concept_records))
+                    .await
+            } else {
+                client
+                    .query(
+                        "SELECT qid, label_id, label_en, verified FROM concept ORDER BY qid;\n\
+                         SELECT in.qid AS narrower_qid, out.qid AS broader_qid, \
+                           type::string(in) AS narrower_id, type::string(out) AS broader_id FROM BROADER;",
+                    )
+                    .await
+            }
+            .map_err(Self::map_surreal_error)?;
+            let concept_rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let edge_rows: Vec<Value> = response
+                .take(1)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
 
-        repo.add_broader_edge(&concept_a.concept_id, "Q2095")
-            .await
-            .expect("add broader");
+            let concepts = Self::decode_rows::<SurrealOntologyConceptRow>(concept_rows, "concept")?
+                .into_iter()
+                .map(|row| OntologyConcept {
+                    concept_id: row.qid.clone(),
+                    qid: row.qid,
+                    label_id: row.label_id,
+                    label_en: row.label_en,
+                    verified: row.verified.unwrap_or(false),
+                })
+                .collect();
+            // Edges imported ahead of their target concept have no `out.qid` yet.
+            let broader_edges =
+                Self::decode_rows::<SurrealOntologyBroaderRow>(edge_rows, "broader edge")?
+                    .into_iter()
+                    .map(|row| OntologyBroaderEdge {
+                        narrower_qid: row
+                            .narrower_qid
+                            .unwrap_or_else(|| Self::normalize_id_part(&row.narrower_id)),
+                        broader_qid: row
+                            .broader_qid
+                            .unwrap_or_else(|| Self::normalize_id_part(&row.broader_id)),
+                    })
+                    .collect();
 
-        let note = repo
-            .create_note(&OntologyNoteCreate {
-                note_id: Some("note-1".to_string()),
-                content: "Telur Rp 28k".to_string(),
-                author_id: "damar".to_string(),
-                community_id: "rt05".to_string(),
-                temporal_class: "ephemeral".to_string(),
-                ttl_expires_ms: None,
-                ai_readable: true,
-                rahasia_level: 0,
-                confidence: 0.92,
+            Ok(OntologyExportGraph {
+                concepts,
+                broader_edges,
+                notes,
+                triples,
+                ..OntologyExportGraph::default()
             })
-            .await
-            .expect("create note");
+        })
+    }
+    fn query_notes(
+        &self,
+        query: &OntologyNoteQuery,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<OntologyNoteMatch>>> {
+        let client = self.client.clone();
+        let query = query.clone();
+        Box::pin(async move {
+            let mut filters = vec![
+                "(rahasia_level = 0 OR author = type::record('warga', $viewer_id))",
+                "(ttl_expires = NONE OR ttl_expires > <datetime>$now)",
+            ];
+            if query.community_id.is_some() {
+                filters.push("community_id = $community_id");
+            }
+            if query.from_ms.is_some() {
+                filters.push("created_at >= <datetime>$from");
+            }
+            if query.to_ms.is_some() {
+                filters.push("created_at <= <datetime>$to");
+            }
+            if query.cursor_created_at_ms.is_some() && query.cursor_note_id.is_some() {
+                filters.push(
+                    "(created_at < <datetime>$cursor_created_at OR \
+                     (created_at = <datetime>$cursor_created_at AND note_id < $cursor_note_id))",
+                );
+            }
+            if !query.concept_qids.is_empty() {
+                filters.push(
+                    "id IN (SELECT VALUE in FROM ABOUT \
+                     WHERE out.qid IN $concept_qids OR record::id(out) IN $concept_qids)",
+                );
+            }
+            if !query.place_ids.is_empty() {
+                filters.push(
+                    "id IN (SELECT VALUE in FROM LOCATED_AT \
+                     WHERE record::tb(out) = 'place' AND record::id(out) IN $place_keys)",
+                );
+            }
+            if !query.action_types.is_empty() {
+                filters.push(
+                    "id IN (SELECT VALUE in FROM HAS_ACTION \
+                     WHERE predicate IN $action_types OR record::id(out) IN $action_names)",
+                );
+            }
+            let statement = format!(
+                "SELECT {ONTOLOGY_NOTE_FIELDS}, \
+                   (SELECT VALUE IF out.qid = NONE THEN record::id(out) ELSE out.qid END \
+                    FROM ABOUT WHERE in = $parent.id) AS about, \
+                   (SELECT VALUE 'place:' + record::id(out) FROM LOCATED_AT WHERE in = $parent.id) AS located_at, \
+                   (SELECT VALUE IF predicate = NONE THEN 'schema:' + record::id(out) ELSE predicate END \
+                    FROM HAS_ACTION WHERE in = $parent.id) AS has_action \
+                 FROM note WHERE {} ORDER BY created_at DESC, note_id DESC LIMIT $limit",
+                filters.join(" AND ")
+            );
 
-        repo.write_triples(&[
-            OntologyTripleCreate {
-                edge: OntologyEdgeKind::About,
-                from_id: format!("note:{}", note.note_id),
-                to_id: "concept:Q93189".to_string(),
-                predicate: Some("schema:price".to_string()),
-                metadata: None,
-            },
-            OntologyTripleCreate {
-                edge: OntologyEdgeKind::Vouches,
-                from_id: "warga:u1".to_string(),
-                to_id: format!("note:{}", note.note_id),
-                predicate: None,
-                metadata: Some(serde_json::json!({"reason": "valid"})),
-            },
-            OntologyTripleCreate {
-                edge: OntologyEdgeKind::Challenges,
-                from_id: "warga:u2".to_string(),
-                to_id: format!("note:{}", note.note_id),
-                predicate: None,
-                metadata: Some(serde_json::json!({"reason": "unsure"})),
-            },
-        ])
-        .await
-        .expect("write triples");
+            let place_keys = query
+                .place_ids
+                .iter()
+                .map(|place_id| Self::normalize_id_part(place_id))
+                .collect::<Vec<_>>();
+            let action_names = query
+                .action_types
+                .iter()
+                .map(|action_type| Self::normalize_id_part(action_type))
+                .collect::<Vec<_>>();
+            let mut db_query = client
+                .query(statement)
+                .bind(("viewer_id", query.viewer_id.clone()))
+                .bind(("now", Self::to_rfc3339(query.now_ms)?))
+                .bind((
+                    "community_id",
+                    query.community_id.clone().unwrap_or_default(),
+                ))
+                .bind(("concept_qids", query.concept_qids.clone()))
+                .bind(("place_keys", place_keys))
+                .bind(("action_types", query.action_types.clone()))
+                .bind(("action_names", action_names))
+                .bind(("limit", query.limit as i64));
+            if let Some(from_ms) = query.from_ms {
+                db_query = db_query.bind(("from", Self::to_rfc3339(from_ms)?));
+            }
+            if let Some(to_ms) = query.to_ms {
+                db_query = db_query.bind(("to", Self::to_rfc3339(to_ms)?));
+            }
+            if let (Some(cursor_ms), Some(cursor_note_id)) =
+                (query.cursor_created_at_ms, query.cursor_note_id.clone())
+            {
+                db_query = db_query
+                    .bind(("cursor_created_at", Self::to_rfc3339(cursor_ms)?))
+                    .bind(("cursor_note_id", cursor_note_id));
+            }
 
-        let by_qid = repo
-            .get_concept_by_qid("Q93189")
-            .await
-            .expect("get concept")
-            .expect("concept exists");
-        assert_eq!(by_qid.qid, "Q93189");
+            let mut response = db_query.await.map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            Self::decode_rows::<SurrealOntologyNoteMatchRow>(rows, "note")?
+                .into_iter()
+                .map(|row| {
+                    Ok(OntologyNoteMatch {
+                        note: Self::note_from_row(row.note)?,
+                        about: row.about,
+                        located_at: row.located_at,
+                        has_action: row.has_action,
+                    })
+                })
+                .collect()
+        })
+    }
 
-        let broader = repo
-            .list_broader_concepts("Q93189")
+    fn get_concept_redirect(
+        &self,
+        qid: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<OntologyConceptRedirect>>> {
+        let client = self.client.clone();
+        let qid = qid.trim().to_string();
+        Box::pin(async move {
+            let mut response = client
+                .query(
+                    "SELECT from_qid, to_qid, reason, <string>created_at AS created_at \
+                     FROM concept_redirect WHERE from_qid = $qid LIMIT 1",
+                )
+                .bind(("qid", qid))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            Self::decode_rows::<SurrealConceptRedirectRow>(rows, "concept redirect")?
+                .into_iter()
+                .next()
+                .map(|row| {
+                    Ok(OntologyConceptRedirect {
+                        from_qid: row.from_qid,
+                        to_qid: row.to_qid,
+                        reason: row.reason,
+                        created_at_ms: Self::parse_datetime_ms(&row.created_at)?,
+                    })
+                })
+                .transpose()
+        })
+    }
+
+    fn merge_concepts(
+        &self,
+        redirect: &OntologyConceptRedirect,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<OntologyConceptMergeResult>> {
+        let client = self.client.clone();
+        let redirect = redirect.clone();
+        Box::pin(async move {
+            if redirect.from_qid == redirect.to_qid {
+                return Err(DomainError::Validation(
+                    "cannot merge a concept into itself".to_string(),
+                ));
+            }
+            let created_at = Self::to_rfc3339(redirect.created_at_ms)?;
+            // Edges are recreated rather than updated so `in`/`out` stay immutable;
+            // duplicates that the move would create are skipped.
+            let mut response = client
+                .query(
+                    "BEGIN TRANSACTION;\n\
+                     LET $from = (SELECT VALUE id FROM concept WHERE qid = $from_qid LIMIT 1)[0] \
+                       ?? type::record('concept', $from_qid);\n\
+                     LET $into = (SELECT VALUE id FROM concept WHERE qid = $into_qid LIMIT 1)[0] \
+                       ?? type::record('concept', $into_qid);\n\
+                     LET $loser = (SELECT label_id, label_en FROM $from)[0];\n\
+                     LET $moved = count((SELECT id FROM ABOUT WHERE out = $from)) \
+                       + count((SELECT id FROM INSTANCE_OF WHERE out = $from)) \
+                       + count((SELECT id FROM BROADER WHERE in = $from OR out = $from));\n\
+                     UPSERT $into MERGE { qid: $into_qid, last_referenced: time::now() };\n\
+                     UPDATE $into SET label_id = label_id ?? $loser.label_id, \
+                       label_en = label_en ?? $loser.label_en;\n\
+                     LET $survivor = (SELECT label_id, label_en FROM $into)[0];\n\
+                     IF $loser.label_id != NONE AND $loser.label_id != $survivor.label_id {\n\
+                       CREATE concept_label CONTENT { qid: $into_qid, lang: 'id', \
+                         label: $loser.label_id, alias: true, updated_at: time::now() };\n\
+                     };\n\
+                     IF $loser.label_en != NONE AND $loser.label_en != $survivor.label_en {\n\
+                       CREATE concept_label CONTENT { qid: $into_qid, lang: 'en', \
+                         label: $loser.label_en, alias: true, updated_at: time::now() };\n\
+                     };\n\
+                     UPDATE concept_label SET qid = $into_qid, alias = true, updated_at = time::now() \
+                       WHERE qid = $from_qid;\n\
+                     FOR $edge IN (SELECT * FROM ABOUT WHERE out = $from) {\n\
+                       IF count((SELECT id FROM ABOUT WHERE in = $edge.in AND out = $into)) = 0 {\n\
+                         CREATE ABOUT SET in = $edge.in, out = $into, \
+                           predicate = $edge.predicate, metadata = $edge.metadata;\n\
+                       };\n\
+                     };\n\
+                     FOR $edge IN (SELECT * FROM INSTANCE_OF WHERE out = $from) {\n\
+                       IF count((SELECT id FROM INSTANCE_OF WHERE in = $edge.in AND out = $into)) = 0 {\n\
+                         CREATE INSTANCE_OF SET in = $edge.in, out = $into, \
+                           predicate = $edge.predicate, metadata = $edge.metadata;\n\
+                       };\n\
+                     };\n\
+                     FOR $edge IN (SELECT * FROM BROADER WHERE in = $from AND out != $into) {\n\
+                       IF count((SELECT id FROM BROADER WHERE in = $into AND out = $edge.out)) = 0 {\n\
+                         CREATE BROADER SET in = $into, out = $edge.out;\n\
+                       };\n\
+                     };\n\
+                     FOR $edge IN (SELECT * FROM BROADER WHERE out = $from AND in != $into) {\n\
+                       IF count((SELECT id FROM BROADER WHERE in = $edge.in AND out = $into)) = 0 {\n\
+                         CREATE BROADER SET in = $edge.in, out = $into;\n\
+                       };\n\
+                     };\n\
+                     DELETE ABOUT WHERE out = $from;\n\
+                     DELETE INSTANCE_OF WHERE out = $from;\n\
+                     DELETE BROADER WHERE in = $from OR out = $from;\n\
+                     DELETE $from;\n\
+                     UPDATE concept_redirect SET to_qid = $into_qid WHERE to_qid = $from_qid;\n\
+                     DELETE concept_redirect WHERE from_qid = $into_qid;\n\
+                     UPSERT type::record('concept_redirect', $from_qid) CONTENT {\n\
+                       from_qid: $from_qid, to_qid: $into_qid, reason: $reason, \
+                       created_at: <datetime>$created_at\n\
+                     };\n\
+                     COMMIT TRANSACTION;\n\
+                     RETURN $moved;\n\
+                     SELECT qid, label_id, label_en, verified FROM concept WHERE qid = $into_qid LIMIT 1;",
+                )
+                .bind(("from_qid", redirect.from_qid.clone()))
+                .bind(("into_qid", redirect.to_qid.clone()))
+                .bind(("reason", redirect.reason.clone()))
+                .bind(("created_at", created_at))
+                .await
+                .map_err(Self::map_surreal_error)?
+                .check()
+                .map_err(Self::map_surreal_error)?;
+            let statement_count = response.num_statements();
+            let moved: Option<i64> = response
+                .take(statement_count - 2)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let rows: Vec<Value> = response
+                .take(statement_count - 1)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let row = Self::decode_rows::<SurrealOntologyConceptRow>(rows, "concept")?
+                .into_iter()
+                .next()
+                .ok_or_else(|| DomainError::Validation("merge returned no concept".to_string()))?;
+            Ok(OntologyConceptMergeResult {
+                surviving: OntologyConcept {
+                    concept_id: row.qid.clone(),
+                    qid: row.qid,
+                    label_id: row.label_id,
+                    label_en: row.label_en,
+                    verified: row.verified.unwrap_or(false),
+                },
+                redirect,
+                moved_edge_count: moved.unwrap_or_default().max(0) as usize,
+            })
+        })
+    }
+
+    fn replace_concept_labels(
+        &self,
+        qid: &str,
+        labels: &[OntologyConceptLabel],
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<()>> {
+        let client = self.client.clone();
+        let qid = Self::normalize_id_part(qid.trim());
+        let labels = labels
+            .iter()
+            .map(|label| {
+                serde_json::json!({
+                    "lang": label.lang,
+                    "label": label.label,
+                    "alias": label.alias,
+                })
+            })
+            .collect::<Vec<_>>();
+        Box::pin(async move {
+            if qid.is_empty() {
+                return Err(DomainError::Validation("qid is required".to_string()));
+            }
+            client
+                .query(
+                    "BEGIN TRANSACTION;\n\
+                     DELETE concept_label WHERE qid = $qid;\n\
+                     FOR $label IN $labels {\n\
+                       CREATE concept_label CONTENT { qid: $qid, lang: $label.lang, \
+                         label: $label.label, alias: $label.alias, updated_at: time::now() };\n\
+                     };\n\
+                     COMMIT TRANSACTION;",
+                )
+                .bind(("qid", qid))
+                .bind(("labels", labels))
+                .await
+                .map_err(Self::map_surreal_error)?
+                .check()
+                .map_err(Self::map_surreal_error)?;
+            Ok(())
+        })
+    }
+
+    fn get_concept_labels(
+        &self,
+        qids: &[String],
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<OntologyConceptLabels>>> {
+        let client = self.client.clone();
+        let qids = qids.to_vec();
+        Box::pin(async move {
+            if qids.is_empty() {
+                return Ok(Vec::new());
+            }
+            let mut response = client
+                .query(
+                    "SELECT qid, label_id, label_en, verified FROM concept \
+                     WHERE qid IN $qids ORDER BY qid",
+                )
+                .bind(("qids", qids))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let concepts = Self::decode_rows::<SurrealOntologyConceptRow>(rows, "concept")?;
+            Self::concept_labels_for(&client, concepts).await
+        })
+    }
+
+    fn list_concept_labels(
+        &self,
+        after_qid: Option<&str>,
+        limit: usize,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<OntologyConceptLabels>>> {
+        let client = self.client.clone();
+        let after_qid = after_qid.map(ToString::to_string);
+        Box::pin(async move {
+            let mut filters = Vec::new();
+            if after_qid.is_some() {
+                filters.push("qid > $after_qid");
+            }
+            let where_clause = if filters.is_empty() {
+                String::new()
+            } else {
+                format!("WHERE {} ", filters.join(" AND "))
+            };
+            let mut response = client
+                .query(format!(
+                    "SELECT qid, label_id, label_en, verified FROM concept \
+                     {where_clause}ORDER BY qid LIMIT $limit",
+                ))
+                .bind(("after_qid", after_qid))
+                .bind(("limit", limit as i64))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let concepts = Self::decode_rows::<SurrealOntologyConceptRow>(rows, "concept")?;
+            Self::concept_labels_for(&client, concepts).await
+        })
+    }
+
+    fn concept_usage_counts(
+        &self,
+        community_id: &str,
+        qids: &[String],
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<OntologyConceptUsage>>> {
+        let client = self.client.clone();
+        let community_id = community_id.trim().to_string();
+        let qids = qids.to_vec();
+        Box::pin(async move {
+            if qids.is_empty() {
+                return Ok(Vec::new());
+            }
+            // Only public notes count, so usage never hints at restricted notes.
+            let mut response = client
+                .query(
+                    "SELECT out.qid AS qid, count() AS note_count FROM ABOUT \
+                     WHERE out.qid IN $qids \
+                     AND in.community_id = $community_id \
+                     AND (in.rahasia_level ?? 0) = 0 \
+                     GROUP BY qid",
+                )
+                .bind(("qids", qids))
+                .bind(("community_id", community_id))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            Ok(
+                Self::decode_rows::<SurrealConceptUsageRow>(rows, "concept usage")?
+                    .into_iter()
+                    .filter(|row| row.note_count > 0)
+                    .map(|row| OntologyConceptUsage {
+                        qid: row.qid,
+                        note_count: row.note_count as usize,
+                    })
+                    .collect(),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod ontology_repository_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn in_memory_ontology_repository_reverse_geocodes_admin_areas() {
+        use gotong_domain::ontology_gazetteer::{ReverseGeocodeMethod, reverse_geocode};
+
+        let repo = InMemoryOntologyRepository::new();
+        let point = |lat: f64, lng: f64| GeoPoint { lat, lng };
+        let square = |south: f64, west: f64, size: f64| {
+            Some(vec![vec![
+                point(south, west),
+                point(south, west + size),
+                point(south + size, west + size),
+                point(south + size, west),
+            ]])
+        };
+        let areas = vec![
+            AdminArea::new(
+                "32",
+                "Jawa Barat",
+                point(-6.9, 107.6),
+                square(-8.0, 106.0, 2.5),
+            )
+            .unwrap(),
+            AdminArea::new(
+                "32.73",
+                "Kota Bandung",
+                point(-6.9, 107.6),
+                square(-7.0, 107.5, 0.2),
+            )
+            .unwrap(),
+            AdminArea::new(
+                "32.73.01",
+                "Sukasari",
+                point(-6.9, 107.6),
+                square(-7.0, 107.5, 0.2),
+            )
+            .unwrap(),
+            // An enclave inside its neighbour's polygon.
+            AdminArea::new(
+                "32.73.01.1001",
+                "Sarijadi",
+                point(-6.95, 107.55),
+                square(-7.0, 107.5, 0.1),
+            )
+            .unwrap(),
+            AdminArea::new(
+                "32.73.01.1002",
+                "Gegerkalong",
+                point(-6.95, 107.55),
+                square(-7.0, 107.5, 0.15),
+            )
+            .unwrap(),
+            // Centroid only.
+            AdminArea::new("32.73.01.1003", "Isola", point(-6.82, 107.62), None).unwrap(),
+        ];
+        repo.upsert_admin_areas(&areas).await.expect("upsert areas");
+
+        let enclave = reverse_geocode(&repo, &point(-6.95, 107.55))
             .await
-            .expect("list broader");
-        assert_eq!(broader.len(), 1);
-        assert_eq!(broader[0].qid, "Q2095");
+            .expect("reverse geocode")
+            .expect("match");
+        assert_eq!(enclave.area.code, "32.73.01.1001");
+        assert_eq!(enclave.method, ReverseGeocodeMethod::Boundary);
+        assert_eq!(
+            enclave
+                .hierarchy
+                .iter()
+                .map(|area| area.code.as_str())
+                .collect::<Vec<_>>(),
+            vec!["32", "32.73", "32.73.01", "32.73.01.1001"]
+        );
 
-        let counts = repo
-            .note_feedback_counts(&note.note_id)
+        let neighbour = reverse_geocode(&repo, &point(-6.9, 107.62))
             .await
-            .expect("feedback counts");
-        assert_eq!(counts.vouch_count, 1);
-        assert_eq!(counts.challenge_count, 1);
+            .expect("reverse geocode")
+            .expect("match");
+        assert_eq!(neighbour.area.code, "32.73.01.1002");
+
+        // Outside every desa polygon but inside the kecamatan: nearest centroid.
+        let by_centroid = reverse_geocode(&repo, &point(-6.81, 107.62))
+            .await
+            .expect("reverse geocode")
+            .expect("match");
+        assert_eq!(by_centroid.area.code, "32.73.01.1003");
+        assert_eq!(by_centroid.method, ReverseGeocodeMethod::NearestCentroid);
+        assert!(
+            by_centroid
+                .distance_m
+                .is_some_and(|distance| distance < 2_000.0)
+        );
+
+        assert!(
+            reverse_geocode(&repo, &point(1.0, 100.0))
+                .await
+                .expect("reverse geocode")
+                .is_none()
+        );
+
+        let places = repo
+            .get_places_by_ids(&["place:adm_32_73_01_1001".to_string()])
+            .await
+            .expect("places");
+        assert_eq!(places.len(), 1);
+        assert_eq!(places[0].name, "Sarijadi");
     }
 
     #[tokio::test]
-    async fn in_memory_ontology_repository_cleanup_expired_notes_removes_notes_and_edges() {
+    async fn in_memory_ontology_repository_supports_core_flows() {
         let repo = InMemoryOntologyRepository::new();
-        let now = gotong_domain::jobs::now_ms();
 
-        let concept = repo
+        let concept_a = repo
             .upsert_concept(&OntologyConcept {
                 concept_id: "Q93189".to_string(),
                 qid: "Q93189".to_string(),
@@ -3757,91 +5908,636 @@ mod ontology_repository_tests {
                 verified: true,
             })
             .await
-            .expect("upsert concept");
-
-        let expired_note = repo
-            .create_note(&OntologyNoteCreate {
-                note_id: Some("expired-note".to_string()),
-                content: "expired".to_string(),
-                author_id: "warga-expired".to_string(),
-                community_id: "rt05".to_string(),
-                temporal_class: "ephemeral".to_string(),
-                ttl_expires_ms: Some(now - 1),
-                ai_readable: true,
-                rahasia_level: 0,
-                confidence: 0.82,
+            .expect("upsert concept A");
+        let _concept_b = repo
+            .upsert_concept(&OntologyConcept {
+                concept_id: "Q2095".to_string(),
+                qid: "Q2095".to_string(),
+                label_id: Some("Makanan".to_string()),
+                label_en: Some("Food".to_string()),
+                verified: true,
             })
             .await
-            .expect("create expired note");
+            .expect("upsert concept B");
 
-        let active_note = repo
+        repo.add_broader_edge(&concept_a.concept_id, "Q2095")
+            .await
+            .expect("add broader");
+
+        let note = repo
             .create_note(&OntologyNoteCreate {
-                note_id: Some("active-note".to_string()),
-                content: "active".to_string(),
-                author_id: "warga-active".to_string(),
+                note_id: Some("note-1".to_string()),
+                content: "Telur Rp 28k".to_string(),
+                author_id: "damar".to_string(),
                 community_id: "rt05".to_string(),
                 temporal_class: "ephemeral".to_string(),
-                ttl_expires_ms: Some(now + 60_000),
+                ttl_expires_ms: None,
                 ai_readable: true,
                 rahasia_level: 0,
-                confidence: 0.85,
+                confidence: 0.92,
             })
             .await
-            .expect("create active note");
+            .expect("create note");
 
         repo.write_triples(&[
             OntologyTripleCreate {
                 edge: OntologyEdgeKind::About,
-                from_id: format!("note:{}", expired_note.note_id),
-                to_id: format!("concept:{}", concept.qid),
-                predicate: None,
-                metadata: None,
-            },
-            OntologyTripleCreate {
-                edge: OntologyEdgeKind::About,
-                from_id: format!("note:{}", active_note.note_id),
-                to_id: format!("concept:{}", concept.qid),
-                predicate: None,
+                from_id: format!("note:{}", note.note_id),
+                to_id: "concept:Q93189".to_string(),
+                predicate: Some("schema:price".to_string()),
                 metadata: None,
             },
             OntologyTripleCreate {
                 edge: OntologyEdgeKind::Vouches,
-                from_id: "warga:alpha".to_string(),
-                to_id: format!("note:{}", expired_note.note_id),
+                from_id: "warga:u1".to_string(),
+                to_id: format!("note:{}", note.note_id),
                 predicate: None,
-                metadata: None,
+                metadata: Some(serde_json::json!({"reason": "valid"})),
             },
             OntologyTripleCreate {
                 edge: OntologyEdgeKind::Challenges,
-                from_id: "warga:beta".to_string(),
-                to_id: format!("note:{}", active_note.note_id),
+                from_id: "warga:u2".to_string(),
+                to_id: format!("note:{}", note.note_id),
                 predicate: None,
-                metadata: None,
+                metadata: Some(serde_json::json!({"reason": "unsure"})),
             },
         ])
         .await
         .expect("write triples");
 
-        let removed_note_ids = repo
-            .cleanup_expired_notes(now)
+        let by_qid = repo
+            .get_concept_by_qid("Q93189")
             .await
-            .expect("cleanup expired notes");
-        assert_eq!(removed_note_ids, vec!["expired-note".to_string()]);
+            .expect("get concept")
+            .expect("concept exists");
+        assert_eq!(by_qid.qid, "Q93189");
 
-        let expired_feedback = repo
-            .note_feedback_counts(&expired_note.note_id)
+        let broader = repo
+            .list_broader_concepts("Q93189")
             .await
-            .expect("expired feedback");
-        assert_eq!(expired_feedback.vouch_count, 0);
-        assert_eq!(expired_feedback.challenge_count, 0);
+            .expect("list broader");
+        assert_eq!(broader.len(), 1);
+        assert_eq!(broader[0].qid, "Q2095");
 
-        let active_feedback = repo
-            .note_feedback_counts(&active_note.note_id)
+        let counts = repo
+            .note_feedback_counts(&note.note_id)
+            .await
+            .expect("feedback counts");
+        assert_eq!(counts.vouch_count, 1);
+        assert_eq!(counts.challenge_count, 1);
+
+        let mut votes = repo
+            .list_note_feedback_votes(&note.note_id)
+            .await
+            .expect("feedback votes");
+        votes.sort_by(|a, b| a.voter_id.cmp(&b.voter_id));
+        assert_eq!(votes.len(), 2);
+        assert_eq!(votes[0].voter_id, "u1");
+        assert_eq!(votes[0].kind, NoteFeedbackKind::Vouch);
+        assert_eq!(votes[1].kind, NoteFeedbackKind::Challenge);
+        assert!(votes.iter().all(|vote| vote.created_at_ms.is_some()));
+
+        let stored = repo
+            .get_note(&format!("note:{}", note.note_id))
+            .await
+            .expect("get note")
+            .expect("note exists");
+        assert_eq!(stored.temporal_class, "ephemeral");
+        assert!(repo.get_note("missing").await.expect("get note").is_none());
+    }
+
+    #[tokio::test]
+    async fn in_memory_ontology_repository_edits_and_retracts_notes_with_history() {
+        use gotong_domain::ontology_revision::{
+            NoteEditCommand, edit_ontology_note, ontology_note_history, retract_ontology_note,
+        };
+
+        let repo = InMemoryOntologyRepository::new();
+        let note = repo
+            .create_note(&OntologyNoteCreate {
+                note_id: Some("note-edit".to_string()),
+                content: "Jalan rusak di gang 3".to_string(),
+                author_id: "damar".to_string(),
+                community_id: "rt05".to_string(),
+                temporal_class: "persistent".to_string(),
+                ttl_expires_ms: None,
+                ai_readable: true,
+                rahasia_level: 0,
+                confidence: 0.6,
+            })
+            .await
+            .expect("create note");
+        assert_eq!(note.version, 1);
+        let feedback = |voter: &str, edge: OntologyEdgeKind| OntologyTripleCreate {
+            edge,
+            from_id: format!("warga:{voter}"),
+            to_id: "note:note-edit".to_string(),
+            predicate: None,
+            metadata: None,
+        };
+        repo.write_triples(&[
+            feedback("u1", OntologyEdgeKind::Vouches),
+            feedback("u2", OntologyEdgeKind::Challenges),
+        ])
+        .await
+        .expect("write feedback");
+
+        let edit = NoteEditCommand {
+            note_id: "note-edit".to_string(),
+            expected_version: Some(1),
+            content: Some("Jalan rusak di gang 4".to_string()),
+            confidence: None,
+        };
+        let forbidden = edit_ontology_note(&repo, "ayu", &edit, 1_000).await;
+        assert!(matches!(forbidden, Err(DomainError::Forbidden(_))));
+        let edited = edit_ontology_note(&repo, "damar", &edit, 1_000)
+            .await
+            .expect("edit note");
+        assert_eq!(edited.version, 2);
+        assert_eq!(edited.content, "Jalan rusak di gang 4");
+        assert_eq!(edited.edited_at_ms, Some(1_000));
+        let stale = edit_ontology_note(&repo, "damar", &edit, 2_000).await;
+        assert!(matches!(stale, Err(DomainError::Conflict)));
+        let unchanged = edit_ontology_note(
+            &repo,
+            "damar",
+            &NoteEditCommand {
+                expected_version: Some(2),
+                ..edit.clone()
+            },
+            2_000,
+        )
+        .await
+        .expect("no-op edit");
+        assert_eq!(unchanged.version, 2);
+
+        repo.write_triples(&[feedback("u3", OntologyEdgeKind::Vouches)])
+            .await
+            .expect("vouch after edit");
+        let counts = repo
+            .note_feedback_counts("note-edit")
+            .await
+            .expect("feedback counts");
+        assert_eq!(counts.vouch_count, 2);
+        assert_eq!(counts.earlier_version_vouch_count, 1);
+        assert_eq!(counts.challenge_count, 1);
+        assert_eq!(counts.earlier_version_challenge_count, 1);
+        let mut votes = repo
+            .list_note_feedback_votes("note-edit")
+            .await
+            .expect("feedback votes");
+        votes.sort_by(|a, b| a.voter_id.cmp(&b.voter_id));
+        assert_eq!(
+            votes
+                .iter()
+                .map(|vote| vote.note_version)
+                .collect::<Vec<_>>(),
+            vec![1, 1, 2]
+        );
+
+        let retracted = retract_ontology_note(&repo, "damar", "note-edit", Some(" salah "), 3_000)
+            .await
+            .expect("retract note");
+        assert_eq!(retracted.retracted_at_ms, Some(3_000));
+        assert_eq!(retracted.retraction_reason.as_deref(), Some("salah"));
+        let again = retract_ontology_note(&repo, "damar", "note-edit", None, 4_000)
+            .await
+            .expect("retract again");
+        assert_eq!(again.retracted_at_ms, Some(3_000));
+        let after_retraction = edit_ontology_note(
+            &repo,
+            "damar",
+            &NoteEditCommand {
+                expected_version: None,
+                ..edit
+            },
+            5_000,
+        )
+        .await;
+        assert!(matches!(after_retraction, Err(DomainError::Conflict)));
+
+        let history = ontology_note_history(&repo, "ayu", "note-edit", 5_000)
+            .await
+            .expect("note history");
+        assert!(history.note.is_retracted());
+        assert_eq!(
+            history
+                .versions
+                .iter()
+                .map(|version| (version.version, version.content.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(2, "Jalan rusak di gang 4"), (1, "Jalan rusak di gang 3")]
+        );
+        assert_eq!(history.versions[0].created_at_ms, 1_000);
+        assert_eq!(history.versions[1].superseded_at_ms, Some(1_000));
+    }
+
+    #[tokio::test]
+    async fn in_memory_ontology_repository_cleanup_expired_notes_removes_notes_and_edges() {
+        let repo = InMemoryOntologyRepository::new();
+        let now = gotong_domain::jobs::now_ms();
+
+        let concept = repo
+            .upsert_concept(&OntologyConcept {
+                concept_id: "Q93189".to_string(),
+                qid: "Q93189".to_string(),
+                label_id: Some("Telur".to_string()),
+                label_en: Some("Egg".to_string()),
+                verified: true,
+            })
+            .await
+            .expect("upsert concept");
+
+        let expired_note = repo
+            .create_note(&OntologyNoteCreate {
+                note_id: Some("expired-note".to_string()),
+                content: "expired".to_string(),
+                author_id: "warga-expired".to_string(),
+                community_id: "rt05".to_string(),
+                temporal_class: "ephemeral".to_string(),
+                ttl_expires_ms: Some(now - 1),
+                ai_readable: true,
+                rahasia_level: 0,
+                confidence: 0.82,
+            })
+            .await
+            .expect("create expired note");
+
+        let active_note = repo
+            .create_note(&OntologyNoteCreate {
+                note_id: Some("active-note".to_string()),
+                content: "active".to_string(),
+                author_id: "warga-active".to_string(),
+                community_id: "rt05".to_string(),
+                temporal_class: "ephemeral".to_string(),
+                ttl_expires_ms: Some(now + 60_000),
+                ai_readable: true,
+                rahasia_level: 0,
+                confidence: 0.85,
+            })
+            .await
+            .expect("create active note");
+
+        repo.write_triples(&[
+            OntologyTripleCreate {
+                edge: OntologyEdgeKind::About,
+                from_id: format!("note:{}", expired_note.note_id),
+                to_id: format!("concept:{}", concept.qid),
+                predicate: None,
+                metadata: None,
+            },
+            OntologyTripleCreate {
+                edge: OntologyEdgeKind::About,
+                from_id: format!("note:{}", active_note.note_id),
+                to_id: format!("concept:{}", concept.qid),
+                predicate: None,
+                metadata: None,
+            },
+            OntologyTripleCreate {
+                edge: OntologyEdgeKind::Vouches,
+                from_id: "warga:alpha".to_string(),
+                to_id: format!("note:{}", expired_note.note_id),
+                predicate: None,
+                metadata: None,
+            },
+            OntologyTripleCreate {
+                edge: OntologyEdgeKind::Challenges,
+                from_id: "warga:beta".to_string(),
+                to_id: format!("note:{}", active_note.note_id),
+                predicate: None,
+                metadata: None,
+            },
+        ])
+        .await
+        .expect("write triples");
+
+        let removed_note_ids = repo
+            .cleanup_expired_notes(now)
+            .await
+            .expect("cleanup expired notes");
+        assert_eq!(removed_note_ids, vec!["expired-note".to_string()]);
+
+        let expired_feedback = repo
+            .note_feedback_counts(&expired_note.note_id)
+            .await
+            .expect("expired feedback");
+        assert_eq!(expired_feedback.vouch_count, 0);
+        assert_eq!(expired_feedback.challenge_count, 0);
+
+        let active_feedback = repo
+            .note_feedback_counts(&active_note.note_id)
             .await
             .expect("active feedback");
         assert_eq!(active_feedback.vouch_count, 0);
         assert_eq!(active_feedback.challenge_count, 1);
     }
+
+    #[tokio::test]
+    async fn in_memory_ontology_repository_queries_notes_by_facets() {
+        let repo = InMemoryOntologyRepository::new();
+        repo.add_broader_edge("Q8068", "Q3839081")
+            .await
+            .expect("add broader");
+        let narrower = repo
+            .list_narrower_concepts(&["Q3839081".to_string()])
+            .await
+            .expect("list narrower");
+        assert_eq!(narrower.len(), 1);
+        assert_eq!(narrower[0].narrower_qid, "Q8068");
+
+        for (note_id, author_id, rahasia_level) in [
+            ("public-flood", "warga-a", 0),
+            ("private-flood", "warga-b", 2),
+            ("own-flood", "warga-a", 3),
+        ] {
+            repo.create_note(&OntologyNoteCreate {
+                note_id: Some(note_id.to_string()),
+                content: format!("banjir {note_id}"),
+                author_id: author_id.to_string(),
+                community_id: "rw-07".to_string(),
+                temporal_class: "persistent".to_string(),
+                ttl_expires_ms: None,
+                ai_readable: true,
+                rahasia_level,
+                confidence: 0.7,
+            })
+            .await
+            .expect("create note");
+            repo.write_triples(&[
+                OntologyTripleCreate {
+                    edge: OntologyEdgeKind::About,
+                    from_id: format!("note:{note_id}"),
+                    to_id: "concept:Q8068".to_string(),
+                    predicate: None,
+                    metadata: None,
+                },
+                OntologyTripleCreate {
+                    edge: OntologyEdgeKind::LocatedAt,
+                    from_id: format!("note:{note_id}"),
+                    to_id: "place:rt-03".to_string(),
+                    predicate: None,
+                    metadata: None,
+                },
+            ])
+            .await
+            .expect("write triples");
+        }
+        repo.write_triples(&[OntologyTripleCreate {
+            edge: OntologyEdgeKind::HasAction,
+            from_id: "note:public-flood".to_string(),
+            to_id: "action:RepairAction".to_string(),
+            predicate: Some("schema:RepairAction".to_string()),
+            metadata: None,
+        }])
+        .await
+        .expect("write action");
+
+        let query = OntologyNoteQuery {
+            viewer_id: "warga-a".to_string(),
+            concept_qids: vec!["Q8068".to_string()],
+            place_ids: vec!["place:rt-03".to_string()],
+            action_types: vec![],
+            community_id: Some("rw-07".to_string()),
+            from_ms: None,
+            to_ms: None,
+            now_ms: gotong_domain::jobs::now_ms(),
+            cursor_created_at_ms: None,
+            cursor_note_id: None,
+            limit: 10,
+        };
+        let mut ids = repo
+            .query_notes(&query)
+            .await
+            .expect("query notes")
+            .into_iter()
+            .map(|item| item.note.note_id)
+            .collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, vec!["own-flood", "public-flood"]);
+
+        let matches = repo
+            .query_notes(&OntologyNoteQuery {
+                action_types: vec!["schema:RepairAction".to_string()],
+                ..query.clone()
+            })
+            .await
+            .expect("query by action");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].about, vec!["Q8068"]);
+        assert_eq!(matches[0].located_at, vec!["place:rt-03"]);
+        assert_eq!(matches[0].has_action, vec!["schema:RepairAction"]);
+
+        let none = repo
+            .query_notes(&OntologyNoteQuery {
+                community_id: Some("rw-01".to_string()),
+                ..query
+            })
+            .await
+            .expect("query other community");
+        assert!(none.is_empty());
+    }
+
+    #[tokio::test]
+    async fn in_memory_ontology_repository_merges_concepts_and_resolves_redirects() {
+        let repo = InMemoryOntologyRepository::new();
+        for (qid, label_en) in [
+            ("Q100", Some("Flash flood")),
+            ("Q42", None),
+            ("Q8068", Some("Flood")),
+        ] {
+            repo.upsert_concept(&OntologyConcept {
+                concept_id: qid.to_string(),
+                qid: qid.to_string(),
+                label_id: Some(format!("label {qid}")),
+                label_en: label_en.map(str::to_string),
+                verified: true,
+            })
+            .await
+            .expect("upsert concept");
+        }
+        repo.add_broader_edge("Q100", "Q8068")
+            .await
+            .expect("add broader");
+        repo.write_triples(&[
+            OntologyTripleCreate {
+                edge: OntologyEdgeKind::About,
+                from_id: "note:n1".to_string(),
+                to_id: "concept:Q100".to_string(),
+                predicate: None,
+                metadata: None,
+            },
+            OntologyTripleCreate {
+                edge: OntologyEdgeKind::About,
+                from_id: "note:n1".to_string(),
+                to_id: "concept:Q42".to_string(),
+                predicate: None,
+                metadata: None,
+            },
+        ])
+        .await
+        .expect("write triples");
+
+        let result = repo
+            .merge_concepts(&OntologyConceptRedirect {
+                from_qid: "Q100".to_string(),
+                to_qid: "Q42".to_string(),
+                reason: "admin".to_string(),
+                created_at_ms: 1_000,
+            })
+            .await
+            .expect("merge");
+        assert_eq!(result.moved_edge_count, 2);
+        assert_eq!(result.surviving.qid, "Q42");
+        assert_eq!(result.surviving.label_id.as_deref(), Some("label Q42"));
+        assert_eq!(result.surviving.label_en.as_deref(), Some("Flash flood"));
+
+        let resolved = repo
+            .get_concept_by_qid("Q100")
+            .await
+            .expect("get concept")
+            .expect("redirect resolves");
+        assert_eq!(resolved.qid, "Q42");
+        let concepts = repo
+            .get_concepts_by_qids(&["Q100".to_string(), "Q42".to_string()])
+            .await
+            .expect("get concepts");
+        assert_eq!(concepts.len(), 1);
+        let broader = repo
+            .list_broader_concepts("Q42")
+            .await
+            .expect("list broader");
+        assert_eq!(broader.len(), 1);
+
+        // The duplicate ABOUT edge collapsed onto the survivor.
+        let about = repo
+            .list_note_edge_targets("n1", OntologyEdgeKind::About)
+            .await
+            .expect("list about targets");
+        assert_eq!(about, vec!["concept:Q42"]);
+
+        // Writes naming the loser land on the survivor instead of recreating it.
+        let reimported = repo
+            .upsert_concept(&OntologyConcept {
+                concept_id: "Q100".to_string(),
+                qid: "Q100".to_string(),
+                label_id: Some("banjir bandang".to_string()),
+                label_en: None,
+                verified: false,
+            })
+            .await
+            .expect("re-import loser");
+        assert_eq!(reimported, result.surviving);
+        repo.write_triples(&[OntologyTripleCreate {
+            edge: OntologyEdgeKind::About,
+            from_id: "note:n2".to_string(),
+            to_id: "concept:Q100".to_string(),
+            predicate: None,
+            metadata: None,
+        }])
+        .await
+        .expect("write triple to loser");
+        let about = repo
+            .list_note_edge_targets("n2", OntologyEdgeKind::About)
+            .await
+            .expect("list about targets");
+        assert_eq!(about, vec!["concept:Q42"]);
+        let concepts = repo
+            .get_concepts_by_qids(&["Q100".to_string(), "Q42".to_string()])
+            .await
+            .expect("get concepts");
+        assert_eq!(concepts.len(), 1);
+        assert_eq!(concepts[0].label_id.as_deref(), Some("label Q42"));
+    }
+    #[tokio::test]
+    async fn in_memory_ontology_repository_stores_labels_and_counts_public_usage() {
+        let repo = InMemoryOntologyRepository::new();
+        for (qid, label_id) in [("Q8068", "Banjir"), ("Q7", "Longsor")] {
+            repo.upsert_concept(&OntologyConcept {
+                concept_id: qid.to_string(),
+                qid: qid.to_string(),
+                label_id: Some(label_id.to_string()),
+                label_en: None,
+                verified: true,
+            })
+            .await
+            .expect("upsert concept");
+        }
+        repo.replace_concept_labels(
+            "Q8068",
+            &[
+                OntologyConceptLabel {
+                    lang: "jv".to_string(),
+                    label: "Banjir".to_string(),
+                    alias: false,
+                },
+                OntologyConceptLabel {
+                    lang: "su".to_string(),
+                    label: "Caah".to_string(),
+                    alias: false,
+                },
+            ],
+        )
+        .await
+        .expect("replace labels");
+
+        let labels = repo
+            .get_concept_labels(&["Q8068".to_string(), "Q404".to_string()])
+            .await
+            .expect("get labels");
+        assert_eq!(labels.len(), 1);
+        let langs = labels[0]
+            .labels
+            .iter()
+            .map(|label| label.lang.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(langs, vec!["id", "jv", "su"]);
+
+        let page = repo
+            .list_concept_labels(Some("Q7"), 10)
+            .await
+            .expect("list labels");
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].qid, "Q8068");
+
+        for (note_id, community_id, rahasia_level) in
+            [("n1", "rt05", 0), ("n2", "rt05", 2), ("n3", "rt06", 0)]
+        {
+            repo.create_note(&OntologyNoteCreate {
+                note_id: Some(note_id.to_string()),
+                content: "Banjir di gang".to_string(),
+                author_id: "damar".to_string(),
+                community_id: community_id.to_string(),
+                temporal_class: "ephemeral".to_string(),
+                ttl_expires_ms: None,
+                ai_readable: true,
+                rahasia_level,
+                confidence: 0.9,
+            })
+            .await
+            .expect("create note");
+            repo.write_triples(&[OntologyTripleCreate {
+                edge: OntologyEdgeKind::About,
+                from_id: format!("note:{note_id}"),
+                to_id: "concept:Q8068".to_string(),
+                predicate: None,
+                metadata: None,
+            }])
+            .await
+            .expect("write about");
+        }
+        let usage = repo
+            .concept_usage_counts("rt05", &["Q8068".to_string(), "Q7".to_string()])
+            .await
+            .expect("usage counts");
+        // Restricted notes do not count, and unused concepts are left out.
+        assert_eq!(
+            usage,
+            vec![OntologyConceptUsage {
+                qid: "Q8068".to_string(),
+                note_count: 1,
+            }]
+        );
+    }
 }
 
 #[derive(Default)]
@@ -3982,6 +6678,7 @@ impl VouchRepository for InMemoryVouchRepository {
 #[derive(Default)]
 struct InMemoryAdaptivePathState {
     plans: HashMap<String, AdaptivePathPlan>,
+    plan_versions: HashMap<(String, u64), AdaptivePathPlan>,
     plan_by_entity: HashMap<String, String>,
     plan_by_entity_request: HashMap<(String, String), String>,
     events_by_plan: HashMap<String, Vec<AdaptivePathEvent>>,
@@ -4037,6 +6734,9 @@ impl AdaptivePathRepository for InMemoryAdaptivePathRepository {
                 .plan_by_entity_request
                 .insert(request_key, plan.plan_id.clone());
             state.plans.insert(plan.plan_id.clone(), plan.clone());
+            state
+                .plan_versions
+                .insert((plan.plan_id.clone(), plan.version), plan.clone());
             Ok(plan)
         })
     }
@@ -4107,6 +6807,9 @@ impl AdaptivePathRepository for InMemoryAdaptivePathRepository {
                 return Err(DomainError::Conflict);
             }
             state.plans.insert(plan.plan_id.clone(), plan.clone());
+            state
+                .plan_versions
+                .insert((plan.plan_id.clone(), plan.version), plan.clone());
             state
                 .plan_by_entity_request
                 .insert(request_key, plan.plan_id.clone());
@@ -4114,11 +6817,44 @@ impl AdaptivePathRepository for InMemoryAdaptivePathRepository {
         })
     }
 
-    fn create_event(
+    fn get_plan_version(
         &self,
-        event: &AdaptivePathEvent,
-    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<AdaptivePathEvent>> {
-        let event = event.clone();
+        plan_id: &str,
+        version: u64,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<AdaptivePathPlan>>> {
+        let key = (plan_id.to_string(), version);
+        let state = self.state.clone();
+        Box::pin(async move {
+            let state = state.read().await;
+            Ok(state.plan_versions.get(&key).cloned())
+        })
+    }
+
+    fn list_plan_versions(
+        &self,
+        plan_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<AdaptivePathPlanVersionSummary>>>
+    {
+        let plan_id = plan_id.to_string();
+        let state = self.state.clone();
+        Box::pin(async move {
+            let state = state.read().await;
+            let mut rows: Vec<_> = state
+                .plan_versions
+                .values()
+                .filter(|plan| plan.plan_id == plan_id)
+                .map(AdaptivePathPlan::version_summary)
+                .collect();
+            rows.sort_by_key(|row| std::cmp::Reverse(row.version));
+            Ok(rows)
+        })
+    }
+
+    fn create_event(
+        &self,
+        event: &AdaptivePathEvent,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<AdaptivePathEvent>> {
+        let event = event.clone();
         let state = self.state.clone();
         Box::pin(async move {
             let mut state = state.write().await;
@@ -4358,9 +7094,16 @@ impl AdaptivePathRepository for SurrealAdaptivePathRepository {
             let payload = to_value(&plan)
                 .map_err(|err| DomainError::Validation(format!("invalid plan payload: {err}")))?;
             let mut response = client
-                .query("CREATE path_plan CONTENT $payload")
+                .query(
+                    "BEGIN TRANSACTION;\n\
+                     CREATE path_plan CONTENT $payload;\n\
+                     CREATE path_plan_version CONTENT $payload;\n\
+                     COMMIT TRANSACTION;",
+                )
                 .bind(("payload", payload))
                 .await
+                .map_err(Self::map_surreal_error)?
+                .check()
                 .map_err(Self::map_surreal_error)?;
             let rows: Vec<Value> = response
                 .take(0)
@@ -4449,21 +7192,82 @@ impl AdaptivePathRepository for SurrealAdaptivePathRepository {
             let expected_version = plan.version.saturating_sub(1);
             let payload = to_value(&plan)
                 .map_err(|err| DomainError::Validation(format!("invalid plan payload: {err}")))?;
+            // The snapshot is written in the same transaction, so a version
+            // conflict leaves no orphan snapshot behind.
             let mut response = client
                 .query(
-                    "UPDATE path_plan MERGE $payload \
-                     WHERE plan_id = $plan_id AND version = $expected_version \
-                     RETURN AFTER",
+                    "BEGIN TRANSACTION;\n\
+                     LET $updated = (UPDATE path_plan MERGE $payload \
+                       WHERE plan_id = $plan_id AND version = $expected_version \
+                       RETURN AFTER);\n\
+                     IF array::len($updated) = 0 {\n\
+                       THROW 'path plan version conflict';\n\
+                     };\n\
+                     CREATE path_plan_version CONTENT $payload;\n\
+                     COMMIT TRANSACTION;\n\
+                     SELECT * FROM path_plan WHERE plan_id = $plan_id LIMIT 1;",
                 )
                 .bind(("payload", payload))
                 .bind(("plan_id", plan.plan_id.clone()))
                 .bind(("expected_version", expected_version))
                 .await
+                .map_err(Self::map_surreal_error)?
+                .check()
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(3)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            Self::decode_one(rows, "path_plan")?.ok_or(DomainError::Conflict)
+        })
+    }
+
+    fn get_plan_version(
+        &self,
+        plan_id: &str,
+        version: u64,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<AdaptivePathPlan>>> {
+        let client = self.client.clone();
+        let plan_id = plan_id.to_string();
+        Box::pin(async move {
+            let mut response = client
+                .query(
+                    "SELECT * FROM path_plan_version \
+                     WHERE plan_id = $plan_id AND version = $version \
+                     LIMIT 1",
+                )
+                .bind(("plan_id", plan_id))
+                .bind(("version", version))
+                .await
                 .map_err(Self::map_surreal_error)?;
             let rows: Vec<Value> = response
                 .take(0)
                 .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
-            Self::decode_one(rows, "path_plan")?.ok_or(DomainError::Conflict)
+            Self::decode_one(rows, "path_plan_version")
+        })
+    }
+
+    fn list_plan_versions(
+        &self,
+        plan_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<AdaptivePathPlanVersionSummary>>>
+    {
+        let client = self.client.clone();
+        let plan_id = plan_id.to_string();
+        Box::pin(async move {
+            let mut response = client
+                .query(
+                    "SELECT plan_id, version, title, request_id, updated_at_ms, event_hash \
+                     FROM path_plan_version \
+                     WHERE plan_id = $plan_id \
+                     ORDER BY version DESC",
+                )
+                .bind(("plan_id", plan_id))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            Self::decode_many(rows, "path_plan_version")
         })
     }
 
@@ -5057,6 +7861,22 @@ impl FeedRepository for InMemoryDiscoveryFeedRepository {
         })
     }
 
+    fn update_title(
+        &self,
+        feed_id: &str,
+        title: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<FeedItem>> {
+        let feed_id = feed_id.to_string();
+        let title = title.to_string();
+        let by_id = self.by_id.clone();
+        Box::pin(async move {
+            let mut by_id = by_id.write().await;
+            let item = by_id.get_mut(&feed_id).ok_or(DomainError::NotFound)?;
+            item.title = title;
+            Ok(item.clone())
+        })
+    }
+
     fn list_feed(
         &self,
         query: &FeedRepositoryQuery,
@@ -5090,11 +7910,23 @@ impl FeedRepository for InMemoryDiscoveryFeedRepository {
                         }
                     }
                     if query.involvement_only
-                        && item.actor_id != query.actor_id
-                        && !item.participant_ids.iter().any(|id| id == &query.actor_id)
+                        && !is_involved_in_feed_item(
+                            item,
+                            &query.actor_id,
+                            &query.followed_entity_ids,
+                        )
                     {
                         return false;
                     }
+                    if let Some(near) = query.near.as_ref() {
+                        let within = item
+                            .location
+                            .as_ref()
+                            .is_some_and(|location| near.distance_within(location).is_some());
+                        if !within {
+                            return false;
+                        }
+                    }
                     true
                 })
                 .cloned()
@@ -5163,6 +7995,15 @@ impl FeedRepository for InMemoryDiscoveryFeedRepository {
                     {
                         return false;
                     }
+                    if let Some(near) = query.near.as_ref() {
+                        let within = item
+                            .location
+                            .as_ref()
+                            .is_some_and(|location| near.distance_within(location).is_some());
+                        if !within {
+                            return false;
+                        }
+                    }
                     true
                 })
                 .cloned()
@@ -5244,15 +8085,7 @@ impl NotificationRepository for InMemoryDiscoveryNotificationRepository {
                 .read()
                 .await
                 .values()
-                .filter(|notification| {
-                    if notification.user_id != query.user_id {
-                        return false;
-                    }
-                    if !query.include_read && notification.read_at_ms.is_some() {
-                        return false;
-                    }
-                    true
-                })
+                .filter(|notification| notification_matches_list_query(notification, &query))
                 .cloned()
                 .collect();
             items.sort_by(|left, right| {
@@ -5261,22 +8094,33 @@ impl NotificationRepository for InMemoryDiscoveryNotificationRepository {
                     .cmp(&left.created_at_ms)
                     .then_with(|| right.notification_id.cmp(&left.notification_id))
             });
-            let items = if let (Some(cursor_ms), Some(cursor_notification_id)) = (
-                query.cursor_created_at_ms,
-                query.cursor_notification_id.as_ref(),
-            ) {
-                items
-                    .into_iter()
-                    .filter(|item| {
-                        item.created_at_ms < cursor_ms
-                            || (item.created_at_ms == cursor_ms
-                                && item.notification_id < *cursor_notification_id)
-                    })
-                    .collect()
-            } else {
-                items
-            };
-            Ok(items.into_iter().take(query.limit).collect())
+            Ok(items
+                .into_iter()
+                .filter(|item| notification_before_cursor(item, &query))
+                .take(query.limit)
+                .collect())
+        })
+    }
+
+    fn list_notification_groups(
+        &self,
+        query: &NotificationRepositoryListQuery,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<NotificationListItem>>> {
+        let query = query.clone();
+        let by_id = self.by_id.clone();
+        Box::pin(async move {
+            let rows: Vec<InAppNotification> = by_id
+                .read()
+                .await
+                .values()
+                .filter(|notification| notification_matches_list_query(notification, &query))
+                .cloned()
+                .collect();
+            Ok(group_notifications(rows)
+                .into_iter()
+                .filter(|item| notification_before_cursor(&item.notification, &query))
+                .take(query.limit)
+                .collect())
         })
     }
 
@@ -5336,9 +8180,38 @@ impl NotificationRepository for InMemoryDiscoveryNotificationRepository {
         })
     }
 
+    fn mark_group_as_read(
+        &self,
+        user_id: &str,
+        group_key: &str,
+        read_at_ms: i64,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<usize>> {
+        let user_id = user_id.to_string();
+        let group_key = group_key.to_string();
+        let by_id = self.by_id.clone();
+        Box::pin(async move {
+            let mut marked = 0;
+            for notification in by_id.write().await.values_mut() {
+                if notification.user_id == user_id
+                    && notification.group_key.as_deref() == Some(group_key.as_str())
+                    && notification.read_at_ms.is_none()
+                    && !notification.in_app_hidden
+                    && notification
+                        .deliver_at_ms
+                        .is_none_or(|deliver_at_ms| deliver_at_ms <= read_at_ms)
+                {
+                    notification.read_at_ms = Some(read_at_ms);
+                    marked += 1;
+                }
+            }
+            Ok(marked)
+        })
+    }
+
     fn unread_count(
         &self,
         user_id: &str,
+        as_of_ms: i64,
     ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<usize>> {
         let user_id = user_id.to_string();
         let by_id = self.by_id.clone();
@@ -5348,7 +8221,12 @@ impl NotificationRepository for InMemoryDiscoveryNotificationRepository {
                 .await
                 .values()
                 .filter(|notification| {
-                    notification.user_id == user_id && notification.read_at_ms.is_none()
+                    notification.user_id == user_id
+                        && notification.read_at_ms.is_none()
+                        && !notification.in_app_hidden
+                        && notification
+                            .deliver_at_ms
+                            .is_none_or(|deliver_at_ms| deliver_at_ms <= as_of_ms)
                 })
                 .count();
             Ok(count)
@@ -5356,6 +8234,41 @@ impl NotificationRepository for InMemoryDiscoveryNotificationRepository {
     }
 }
 
+fn notification_matches_list_query(
+    notification: &InAppNotification,
+    query: &NotificationRepositoryListQuery,
+) -> bool {
+    if notification.user_id != query.user_id || notification.in_app_hidden {
+        return false;
+    }
+    if !query.include_read && notification.read_at_ms.is_some() {
+        return false;
+    }
+    if let (Some(delivered_by_ms), Some(deliver_at_ms)) =
+        (query.delivered_by_ms, notification.deliver_at_ms)
+    {
+        return deliver_at_ms <= delivered_by_ms;
+    }
+    true
+}
+
+fn notification_before_cursor(
+    notification: &InAppNotification,
+    query: &NotificationRepositoryListQuery,
+) -> bool {
+    match (
+        query.cursor_created_at_ms,
+        query.cursor_notification_id.as_deref(),
+    ) {
+        (Some(cursor_ms), Some(cursor_notification_id)) => {
+            notification.created_at_ms < cursor_ms
+                || (notification.created_at_ms == cursor_ms
+                    && notification.notification_id.as_str() < cursor_notification_id)
+        }
+        _ => true,
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct SurrealDiscoveryFeedRepositoryOptions {
     pub involvement_fallback_enabled: bool,
@@ -5369,6 +8282,20 @@ impl Default for SurrealDiscoveryFeedRepositoryOptions {
     }
 }
 
+/// Bind names for the geohash ranges of a `near` query; a radius is covered
+/// by at most nine cells.
+const FEED_GEOHASH_BINDS: [(&str, &str); 9] = [
+    ("geo_lo_0", "geo_hi_0"),
+    ("geo_lo_1", "geo_hi_1"),
+    ("geo_lo_2", "geo_hi_2"),
+    ("geo_lo_3", "geo_hi_3"),
+    ("geo_lo_4", "geo_hi_4"),
+    ("geo_lo_5", "geo_hi_5"),
+    ("geo_lo_6", "geo_hi_6"),
+    ("geo_lo_7", "geo_hi_7"),
+    ("geo_lo_8", "geo_hi_8"),
+];
+
 #[derive(Clone)]
 pub struct SurrealDiscoveryFeedRepository {
     client: Arc<Surreal<Client>>,
@@ -5445,7 +8372,24 @@ impl SurrealDiscoveryFeedRepository {
     fn feed_select_projection() -> &'static str {
         "feed_id, source_type, source_id, actor_id, actor_username, title, summary, scope_id, \
          privacy_level, <string>occurred_at AS occurred_at, <string>created_at AS created_at, \
-         request_id, correlation_id, participant_ids, payload"
+         request_id, correlation_id, participant_ids, payload, location"
+    }
+
+    /// Geohash range filter for a `near` query: one `[cell, cell~)` range per
+    /// covering cell, which the geohash index can serve. The service still
+    /// checks the exact distance. `~` sorts after every geohash character.
+    fn geohash_clause(near: Option<&GeoRadius>) -> Option<(String, Vec<String>)> {
+        let near = near?;
+        let cells = near.covering_geohashes();
+        if cells.is_empty() {
+            return Some(("geohash IS NOT NONE".to_string(), cells));
+        }
+        let ranges: Vec<String> = FEED_GEOHASH_BINDS
+            .iter()
+            .take(cells.len())
+            .map(|(lo, hi)| format!("(geohash >= ${lo} AND geohash < ${hi})"))
+            .collect();
+        Some((format!("({})", ranges.join(" OR ")), cells))
     }
 
     fn map_rows(rows: Vec<Value>) -> DomainResult<Vec<FeedItem>> {
@@ -5472,6 +8416,8 @@ impl SurrealDiscoveryFeedRepository {
                             correlation_id: row.correlation_id,
                             participant_ids: row.participant_ids,
                             payload: row.payload,
+                            location: row.location,
+                            distance_m: None,
                         })
                     })
             })
@@ -5497,6 +8443,8 @@ impl SurrealDiscoveryFeedRepository {
             correlation_id: item.correlation_id.clone(),
             participant_ids: item.participant_ids.clone(),
             payload: item.payload.clone(),
+            location: item.location,
+            geohash: item.location.as_ref().map(GeoPoint::geohash),
         })
     }
 
@@ -5684,13 +8632,23 @@ impl SurrealDiscoveryFeedRepository {
             clauses.push("occurred_at <= <datetime>$to_occurred_at");
         }
         if query.involvement_only {
-            clauses.push("(actor_id = $actor_id OR $actor_id IN participant_ids)");
+            // Follows match tags carrying an explicit entity id; label-only tags
+            // are matched by the in-memory store alone.
+            clauses.push(if query.followed_entity_ids.is_empty() {
+                "(actor_id = $actor_id OR $actor_id IN participant_ids)"
+            } else {
+                "(actor_id = $actor_id OR $actor_id IN participant_ids OR payload.enrichment.entity_tags.entity_id CONTAINSANY $followed_entity_ids OR payload.entity_tags.entity_id CONTAINSANY $followed_entity_ids)"
+            });
         }
         if query.cursor_occurred_at_ms.is_some() && query.cursor_feed_id.is_some() {
             clauses.push(
                 "(occurred_at < <datetime>$cursor_occurred_at OR (occurred_at = <datetime>$cursor_occurred_at AND feed_id < $cursor_feed_id))",
             );
         }
+        let geohash_filter = Self::geohash_clause(query.near.as_ref());
+        if let Some((clause, _)) = geohash_filter.as_ref() {
+            clauses.push(clause.as_str());
+        }
 
         let projection = Self::feed_select_projection();
         let mut statement = format!("SELECT {projection} FROM discovery_feed_item");
@@ -5720,7 +8678,9 @@ impl SurrealDiscoveryFeedRepository {
             db_query = db_query.bind(("to_occurred_at", cursor));
         }
         if query.involvement_only {
-            db_query = db_query.bind(("actor_id", query.actor_id.clone()));
+            db_query = db_query
+                .bind(("actor_id", query.actor_id.clone()))
+                .bind(("followed_entity_ids", query.followed_entity_ids.clone()));
         }
         if let (Some(cursor_ms), Some(cursor_feed_id)) =
             (query.cursor_occurred_at_ms, query.cursor_feed_id.as_deref())
@@ -5730,6 +8690,11 @@ impl SurrealDiscoveryFeedRepository {
                 .bind(("cursor_occurred_at", cursor))
                 .bind(("cursor_feed_id", cursor_feed_id.to_string()));
         }
+        if let Some((_, cells)) = geohash_filter {
+            for ((lo, hi), cell) in FEED_GEOHASH_BINDS.iter().zip(cells) {
+                db_query = db_query.bind((*hi, format!("{cell}~"))).bind((*lo, cell));
+            }
+        }
 
         let mut response = db_query.await.map_err(Self::map_surreal_error)?;
         let rows: Vec<Value> = response
@@ -5856,6 +8821,10 @@ impl SurrealDiscoveryFeedRepository {
         if query.involvement_only {
             clauses.push("(actor_id = $actor_id OR $actor_id IN participant_ids)");
         }
+        let geohash_filter = Self::geohash_clause(query.near.as_ref());
+        if let Some((clause, _)) = geohash_filter.as_ref() {
+            clauses.push(clause.as_str());
+        }
 
         let projection = Self::feed_select_projection();
         let mut statement = format!("SELECT {projection} FROM discovery_feed_item");
@@ -5876,6 +8845,11 @@ impl SurrealDiscoveryFeedRepository {
         if let Some(privacy_level) = query.privacy_level.as_deref() {
             db_query = db_query.bind(("privacy_level", privacy_level.to_string()));
         }
+        if let Some((_, cells)) = geohash_filter {
+            for ((lo, hi), cell) in FEED_GEOHASH_BINDS.iter().zip(cells) {
+                db_query = db_query.bind((*hi, format!("{cell}~"))).bind((*lo, cell));
+            }
+        }
         if query.exclude_vault {
             db_query = db_query.bind(("exclude_source_type", FEED_SOURCE_VAULT.to_string()));
         }
@@ -6045,236 +9019,1475 @@ impl FeedRepository for SurrealDiscoveryFeedRepository {
                      participant_ids = $payload.participant_ids, \
                      payload = IF $payload.payload = NULL THEN NONE ELSE $payload.payload END",
                 )
-                .bind(("payload", payload))
+                .bind(("payload", payload))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            Ok(item)
+        })
+    }
+
+    fn upsert_participant_edges_for_item(
+        &self,
+        item: &FeedItem,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<()>> {
+        let item = item.clone();
+        let actor_ids = feed_participant_actor_ids(&item);
+        let occurred_at = match Self::to_rfc3339(item.occurred_at_ms) {
+            Ok(value) => value,
+            Err(err) => return Box::pin(async move { Err(err) }),
+        };
+        let created_at = match Self::to_rfc3339(item.created_at_ms) {
+            Ok(value) => value,
+            Err(err) => return Box::pin(async move { Err(err) }),
+        };
+        let client = self.client.clone();
+        Box::pin(async move {
+            for actor_id in actor_ids {
+                let edge_id = format!("{actor_id}:{}", item.feed_id);
+                let result = client
+                    .query(
+                        "CREATE feed_participant_edge SET \
+                         edge_id = $edge_id, \
+                         actor_id = $actor_id, \
+                         feed_id = $feed_id, \
+                         occurred_at = <datetime>$occurred_at, \
+                         scope_id = IF $scope_id = NULL THEN NONE ELSE $scope_id END, \
+                         privacy_level = IF $privacy_level = NULL THEN NONE ELSE $privacy_level END, \
+                         source_type = $source_type, \
+                         source_id = $source_id, \
+                         created_at = <datetime>$created_at, \
+                         request_id = $request_id",
+                    )
+                    .bind(("edge_id", edge_id))
+                    .bind(("actor_id", actor_id))
+                    .bind(("feed_id", item.feed_id.clone()))
+                    .bind(("occurred_at", occurred_at.clone()))
+                    .bind(("scope_id", item.scope_id.clone()))
+                    .bind(("privacy_level", item.privacy_level.clone()))
+                    .bind(("source_type", item.source_type.clone()))
+                    .bind(("source_id", item.source_id.clone()))
+                    .bind(("created_at", created_at.clone()))
+                    .bind(("request_id", item.request_id.clone()))
+                    .await
+                    .map_err(Self::map_surreal_error);
+
+                match result {
+                    Ok(_) | Err(DomainError::Conflict) => {}
+                    Err(err) => return Err(err),
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn get_by_source_request(
+        &self,
+        source_type: &str,
+        source_id: &str,
+        request_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<FeedItem>>> {
+        let source_type = source_type.to_string();
+        let source_id = source_id.to_string();
+        let request_id = request_id.to_string();
+        let client = self.client.clone();
+        Box::pin(async move {
+            let projection = Self::feed_select_projection();
+            let mut response = client
+                .query(format!(
+                    "SELECT {projection} FROM discovery_feed_item \
+                     WHERE source_type = $source_type AND source_id = $source_id AND request_id = $request_id LIMIT 1",
+                ))
+                .bind(("source_type", source_type))
+                .bind(("source_id", source_id))
+                .bind(("request_id", request_id))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let mut rows = Self::map_rows(rows)?;
+            Ok(rows.pop())
+        })
+    }
+
+    fn get_by_feed_id(
+        &self,
+        feed_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<FeedItem>>> {
+        let feed_id = feed_id.to_string();
+        let client = self.client.clone();
+        Box::pin(async move {
+            let projection = Self::feed_select_projection();
+            let mut response = client
+                .query(format!(
+                    "SELECT {projection} FROM discovery_feed_item WHERE feed_id = $feed_id LIMIT 1",
+                ))
+                .bind(("feed_id", feed_id))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let mut rows = Self::map_rows(rows)?;
+            Ok(rows.pop())
+        })
+    }
+
+    fn get_latest_by_source(
+        &self,
+        source_type: &str,
+        source_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<FeedItem>>> {
+        let source_type = source_type.to_string();
+        let source_id = source_id.to_string();
+        let client = self.client.clone();
+        Box::pin(async move {
+            let projection = Self::feed_select_projection();
+            let mut response = client
+                .query(format!(
+                    "SELECT {projection} FROM discovery_feed_item \
+                     WHERE source_type = $source_type AND source_id = $source_id \
+                     ORDER BY occurred_at DESC, feed_id DESC LIMIT 1",
+                ))
+                .bind(("source_type", source_type))
+                .bind(("source_id", source_id))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let mut rows = Self::map_rows(rows)?;
+            Ok(rows.pop())
+        })
+    }
+
+    fn merge_payload(
+        &self,
+        feed_id: &str,
+        payload_patch: Value,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<FeedItem>> {
+        let feed_id = feed_id.to_string();
+        let client = self.client.clone();
+        Box::pin(async move {
+            let mut response = client
+                .query("SELECT payload FROM discovery_feed_item WHERE feed_id = $feed_id LIMIT 1")
+                .bind(("feed_id", feed_id.clone()))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let existing_payload = rows
+                .into_iter()
+                .next()
+                .and_then(|row| row.get("payload").cloned())
+                .unwrap_or(Value::Null);
+            let mut merged_payload = match existing_payload {
+                Value::Null => Value::Object(serde_json::Map::new()),
+                other => other,
+            };
+            deep_merge_json(&mut merged_payload, payload_patch);
+
+            client
+                .query(
+                    "UPDATE discovery_feed_item SET payload = $payload \
+                     WHERE feed_id = $feed_id",
+                )
+                .bind(("feed_id", feed_id.clone()))
+                .bind(("payload", merged_payload))
+                .await
+                .map_err(Self::map_surreal_error)?;
+
+            let projection = Self::feed_select_projection();
+            let mut response = client
+                .query(format!(
+                    "SELECT {projection} FROM discovery_feed_item WHERE feed_id = $feed_id LIMIT 1",
+                ))
+                .bind(("feed_id", feed_id))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let mut rows = Self::map_rows(rows)?;
+            rows.pop().ok_or(DomainError::NotFound)
+        })
+    }
+
+    fn update_title(
+        &self,
+        feed_id: &str,
+        title: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<FeedItem>> {
+        let feed_id = feed_id.to_string();
+        let title = title.to_string();
+        let client = self.client.clone();
+        Box::pin(async move {
+            let projection = Self::feed_select_projection();
+            let mut response = client
+                .query(format!(
+                    "UPDATE discovery_feed_item SET title = $title WHERE feed_id = $feed_id;\n\
+                     SELECT {projection} FROM discovery_feed_item WHERE feed_id = $feed_id LIMIT 1;",
+                ))
+                .bind(("feed_id", feed_id))
+                .bind(("title", title))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(1)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let mut rows = Self::map_rows(rows)?;
+            rows.pop().ok_or(DomainError::NotFound)
+        })
+    }
+
+    fn list_feed(
+        &self,
+        query: &FeedRepositoryQuery,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<FeedItem>>> {
+        let query = query.clone();
+        let repository = self.clone();
+        Box::pin(async move {
+            // Participant edges carry neither location nor entity tags, so `near`
+            // and followed entities go straight to the table scan.
+            if query.involvement_only
+                && query.near.is_none()
+                && query.followed_entity_ids.is_empty()
+            {
+                return repository.list_feed_involvement_edge_first(&query).await;
+            }
+            repository.list_feed_legacy(&query).await
+        })
+    }
+
+    fn search_feed(
+        &self,
+        query: &FeedRepositorySearchQuery,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<FeedItem>>> {
+        let query = query.clone();
+        let repository = self.clone();
+        Box::pin(async move {
+            if query.involvement_only && query.near.is_none() {
+                return repository.search_feed_involvement_edge_first(&query).await;
+            }
+            repository.search_feed_legacy(&query).await
+        })
+    }
+}
+
+/// Rows scanned per group-listing round trip, as a multiple of the page size.
+const NOTIFICATION_GROUP_SCAN_FACTOR: usize = 4;
+
+#[derive(Clone)]
+pub struct SurrealDiscoveryNotificationRepository {
+    client: Arc<Surreal<Client>>,
+}
+
+impl SurrealDiscoveryNotificationRepository {
+    pub fn with_client(client: Arc<Surreal<Client>>) -> Self {
+        Self { client }
+    }
+
+    pub async fn new(db_config: &DbConfig) -> anyhow::Result<Self> {
+        let db = Surreal::<Client>::init();
+        db.connect::<Ws>(&db_config.endpoint).await?;
+        db.signin(Root {
+            username: db_config.username.clone(),
+            password: db_config.password.clone(),
+        })
+        .await?;
+        db.use_ns(&db_config.namespace)
+            .use_db(&db_config.database)
+            .await?;
+        Ok(Self {
+            client: Arc::new(db),
+        })
+    }
+
+    fn parse_datetime_ms(value: &str) -> DomainResult<i64> {
+        let datetime = OffsetDateTime::parse(value, &Rfc3339)
+            .map_err(|err| DomainError::Validation(format!("invalid datetime: {err}")))?;
+        Ok((datetime.unix_timestamp_nanos() / 1_000_000) as i64)
+    }
+
+    fn to_rfc3339(timestamp_ms: i64) -> DomainResult<String> {
+        let datetime =
+            OffsetDateTime::from_unix_timestamp_nanos((timestamp_ms as i128) * 1_000_000)
+                .map_err(|err| DomainError::Validation(format!("invalid timestamp: {err}")))?;
+        Ok(datetime
+            .format(&Rfc3339)
+            .unwrap_or_else(|_| "1970-01-01T00:00:00Z".to_string()))
+    }
+
+    fn map_rows(rows: Vec<Value>) -> DomainResult<Vec<InAppNotification>> {
+        rows.into_iter()
+            .map(|row| {
+                serde_json::from_value::<SurrealDiscoveryNotificationRow>(row)
+                    .map_err(|err| {
+                        DomainError::Validation(format!("invalid notification row: {err}"))
+                    })
+                    .and_then(|row| {
+                        let read_at_ms = match row.read_at {
+                            Some(value) => Some(Self::parse_datetime_ms(&value)?),
+                            None => None,
+                        };
+                        let deliver_at_ms = match row.deliver_at {
+                            Some(value) => Some(Self::parse_datetime_ms(&value)?),
+                            None => None,
+                        };
+                        Ok(InAppNotification {
+                            notification_id: row.notification_id,
+                            user_id: row.user_id,
+                            actor_id: row.actor_id,
+                            actor_username: row.actor_username,
+                            notification_type: row.notification_type,
+                            source_type: row.source_type,
+                            source_id: row.source_id,
+                            title: row.title,
+                            body: row.body,
+                            payload: row.payload,
+                            created_at_ms: Self::parse_datetime_ms(&row.created_at)?,
+                            read_at_ms,
+                            deliver_at_ms,
+                            privacy_level: row.privacy_level,
+                            request_id: row.request_id,
+                            correlation_id: row.correlation_id,
+                            dedupe_key: row.dedupe_key,
+                            group_key: row.group_key,
+                            in_app_hidden: row.in_app_hidden.unwrap_or(false),
+                        })
+                    })
+            })
+            .collect()
+    }
+
+    fn to_create_payload(
+        notification: &InAppNotification,
+    ) -> DomainResult<SurrealDiscoveryNotificationCreateRow> {
+        let created_at = Self::to_rfc3339(notification.created_at_ms)?;
+        Ok(SurrealDiscoveryNotificationCreateRow {
+            notification_id: notification.notification_id.clone(),
+            user_id: notification.user_id.clone(),
+            actor_id: notification.actor_id.clone(),
+            actor_username: notification.actor_username.clone(),
+            notification_type: notification.notification_type.clone(),
+            source_type: notification.source_type.clone(),
+            source_id: notification.source_id.clone(),
+            title: notification.title.clone(),
+            body: notification.body.clone(),
+            payload: notification.payload.clone(),
+            created_at,
+            read_at: notification.read_at_ms.map(Self::to_rfc3339).transpose()?,
+            deliver_at: notification
+                .deliver_at_ms
+                .map(Self::to_rfc3339)
+                .transpose()?,
+            privacy_level: notification.privacy_level.clone(),
+            request_id: notification.request_id.clone(),
+            correlation_id: notification.correlation_id.clone(),
+            dedupe_key: notification.dedupe_key.clone(),
+            group_key: notification.group_key.clone(),
+            in_app_hidden: notification.in_app_hidden.then_some(true),
+        })
+    }
+
+    async fn query_notifications(
+        client: &Surreal<Client>,
+        query: &NotificationRepositoryListQuery,
+    ) -> DomainResult<Vec<InAppNotification>> {
+        let mut filters = vec![
+            "user_id = $user_id".to_string(),
+            "in_app_hidden != true".to_string(),
+        ];
+        if query.cursor_created_at_ms.is_some() && query.cursor_notification_id.is_some() {
+            filters.push(
+                "((created_at < $cursor_created_at) OR (created_at = $cursor_created_at AND notification_id < $cursor_notification_id))".to_string(),
+            );
+        }
+        if !query.include_read {
+            filters.push("read_at IS NONE".to_string());
+        }
+        if query.delivered_by_ms.is_some() {
+            filters.push("(deliver_at IS NONE OR deliver_at <= $delivered_by)".to_string());
+        }
+
+        let mut statement = String::from("SELECT * FROM discovery_notification");
+        if !filters.is_empty() {
+            statement.push_str(" WHERE ");
+            statement.push_str(&filters.join(" AND "));
+        }
+        statement.push_str(" ORDER BY created_at DESC, notification_id DESC LIMIT $limit");
+
+        let mut db_query = client
+            .query(statement)
+            .bind(("user_id", query.user_id.clone()))
+            .bind(("limit", query.limit as i64));
+
+        if let Some(cursor_ms) = query.cursor_created_at_ms {
+            if let Some(cursor_notification_id) = query.cursor_notification_id.as_deref() {
+                let cursor_created_at = Self::to_rfc3339(cursor_ms)?;
+                db_query = db_query
+                    .bind(("cursor_created_at", cursor_created_at))
+                    .bind(("cursor_notification_id", cursor_notification_id.to_string()));
+            }
+        }
+        if let Some(delivered_by_ms) = query.delivered_by_ms {
+            db_query = db_query.bind(("delivered_by", Self::to_rfc3339(delivered_by_ms)?));
+        }
+
+        let mut response = db_query.await.map_err(Self::map_surreal_error)?;
+        let rows: Vec<Value> = response
+            .take(0)
+            .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+        Self::map_rows(rows)
+    }
+
+    /// Every row of the given groups that passes the read/delivery filters,
+    /// regardless of the cursor, so group counts stay whole across pages.
+    async fn query_group_rows(
+        client: &Surreal<Client>,
+        query: &NotificationRepositoryListQuery,
+        group_keys: Vec<String>,
+    ) -> DomainResult<Vec<InAppNotification>> {
+        let mut statement = String::from(
+            "SELECT * FROM discovery_notification \
+             WHERE user_id = $user_id AND group_key IN $group_keys AND in_app_hidden != true",
+        );
+        if !query.include_read {
+            statement.push_str(" AND read_at IS NONE");
+        }
+        if query.delivered_by_ms.is_some() {
+            statement.push_str(" AND (deliver_at IS NONE OR deliver_at <= $delivered_by)");
+        }
+        let mut db_query = client
+            .query(statement)
+            .bind(("user_id", query.user_id.clone()))
+            .bind(("group_keys", group_keys));
+        if let Some(delivered_by_ms) = query.delivered_by_ms {
+            db_query = db_query.bind(("delivered_by", Self::to_rfc3339(delivered_by_ms)?));
+        }
+        let mut response = db_query.await.map_err(Self::map_surreal_error)?;
+        let rows: Vec<Value> = response
+            .take(0)
+            .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+        Self::map_rows(rows)
+    }
+
+    fn decode_count(rows: Vec<Value>, field: &str, label: &str) -> DomainResult<usize> {
+        let Some(row) = rows.into_iter().next() else {
+            return Err(DomainError::Validation(format!("missing {label}")));
+        };
+        let Some(value) = row.get(field) else {
+            return Err(DomainError::Validation(format!("{label} missing")));
+        };
+        let count = value
+            .as_u64()
+            .or_else(|| value.as_i64().and_then(|value| value.try_into().ok()))
+            .ok_or_else(|| DomainError::Validation(format!("invalid {label}")))?;
+        Ok(count as usize)
+    }
+
+    fn map_surreal_error(err: surrealdb::Error) -> DomainError {
+        let error_message = err.to_string().to_lowercase();
+        if error_message.contains("already exists")
+            || error_message.contains("duplicate")
+            || error_message.contains("unique")
+            || error_message.contains("conflict")
+        {
+            return DomainError::Conflict;
+        }
+        DomainError::Validation(format!("surreal query failed: {error_message}"))
+    }
+}
+
+impl NotificationRepository for SurrealDiscoveryNotificationRepository {
+    fn create_notification(
+        &self,
+        notification: &InAppNotification,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<InAppNotification>> {
+        let notification = notification.clone();
+        let payload = match Self::to_create_payload(&notification) {
+            Ok(payload) => payload,
+            Err(err) => return Box::pin(async move { Err(err) }),
+        };
+        let client = self.client.clone();
+        Box::pin(async move {
+            let payload = to_value(payload)
+                .map_err(|err| DomainError::Validation(format!("invalid payload: {err}")))?;
+            let mut response = client
+                .query("CREATE discovery_notification CONTENT $payload")
+                .bind(("payload", payload))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let mut rows = Self::map_rows(rows)?;
+            rows.pop()
+                .ok_or_else(|| DomainError::Validation("create returned no row".to_string()))
+        })
+    }
+
+    fn get_by_dedupe_key(
+        &self,
+        user_id: &str,
+        dedupe_key: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<InAppNotification>>> {
+        let user_id = user_id.to_string();
+        let dedupe_key = dedupe_key.to_string();
+        let client = self.client.clone();
+        Box::pin(async move {
+            let mut response = client
+                .query(
+                    "SELECT * FROM discovery_notification \
+                     WHERE user_id = $user_id AND dedupe_key = $dedupe_key LIMIT 1",
+                )
+                .bind(("user_id", user_id))
+                .bind(("dedupe_key", dedupe_key))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let mut rows = Self::map_rows(rows)?;
+            Ok(rows.pop())
+        })
+    }
+
+    fn list_notifications(
+        &self,
+        query: &NotificationRepositoryListQuery,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<InAppNotification>>> {
+        let query = query.clone();
+        let client = self.client.clone();
+        Box::pin(async move { Self::query_notifications(&client, &query).await })
+    }
+
+    /// Scans rows newest first past the cursor; each group key seen for the
+    /// first time is loaded whole. Groups whose newest row sits at or after
+    /// the cursor were listed on an earlier page and are skipped.
+    fn list_notification_groups(
+        &self,
+        query: &NotificationRepositoryListQuery,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<NotificationListItem>>> {
+        let query = query.clone();
+        let client = self.client.clone();
+        Box::pin(async move {
+            let batch_size = query.limit.max(1) * NOTIFICATION_GROUP_SCAN_FACTOR;
+            let mut scan = NotificationRepositoryListQuery {
+                limit: batch_size,
+                ..query.clone()
+            };
+            let mut seen = HashSet::new();
+            let mut items: Vec<NotificationListItem> = Vec::new();
+            loop {
+                let batch = Self::query_notifications(&client, &scan).await?;
+                let exhausted = batch.len() < batch_size;
+                if let Some(last) = batch.last() {
+                    scan.cursor_created_at_ms = Some(last.created_at_ms);
+                    scan.cursor_notification_id = Some(last.notification_id.clone());
+                }
+                let mut group_keys = Vec::new();
+                for row in batch {
+                    if !seen.insert(row.group_key_or_id().to_string()) {
+                        continue;
+                    }
+                    match row.group_key.clone() {
+                        Some(group_key) => group_keys.push(group_key),
+                        None => items.extend(group_notifications(vec![row])),
+                    }
+                }
+                if !group_keys.is_empty() {
+                    let rows = Self::query_group_rows(&client, &query, group_keys).await?;
+                    items.extend(
+                        group_notifications(rows)
+                            .into_iter()
+                            .filter(|item| notification_before_cursor(&item.notification, &query)),
+                    );
+                }
+                if exhausted || items.len() >= query.limit {
+                    break;
+                }
+            }
+            items.sort_by(|left, right| {
+                right
+                    .notification
+                    .created_at_ms
+                    .cmp(&left.notification.created_at_ms)
+                    .then_with(|| {
+                        right
+                            .notification
+                            .notification_id
+                            .cmp(&left.notification.notification_id)
+                    })
+            });
+            items.truncate(query.limit);
+            Ok(items)
+        })
+    }
+
+    fn list_notifications_in_window(
+        &self,
+        user_id: &str,
+        window_start_ms: i64,
+        window_end_ms: i64,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<InAppNotification>>> {
+        let user_id = user_id.to_string();
+        let window_start = match Self::to_rfc3339(window_start_ms) {
+            Ok(value) => value,
+            Err(err) => return Box::pin(async move { Err(err) }),
+        };
+        let window_end = match Self::to_rfc3339(window_end_ms) {
+            Ok(value) => value,
+            Err(err) => return Box::pin(async move { Err(err) }),
+        };
+        let client = self.client.clone();
+        Box::pin(async move {
+            let mut response = client
+                .query("SELECT * FROM discovery_notification \
+                     WHERE user_id = $user_id AND created_at >= $window_start AND created_at <= $window_end \
+                     ORDER BY created_at DESC, notification_id DESC")
+                .bind(("user_id", user_id))
+                .bind(("window_start", window_start))
+                .bind(("window_end", window_end))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            Self::map_rows(rows)
+        })
+    }
+
+    fn mark_as_read(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+        read_at_ms: i64,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<InAppNotification>> {
+        let user_id = user_id.to_string();
+        let notification_id = notification_id.to_string();
+        let client = self.client.clone();
+        let read_at = match Self::to_rfc3339(read_at_ms) {
+            Ok(value) => value,
+            Err(err) => return Box::pin(async move { Err(err) }),
+        };
+        Box::pin(async move {
+            let mut response = client
+                .query(
+                    "SELECT * FROM discovery_notification \
+                     WHERE notification_id = $notification_id LIMIT 1",
+                )
+                .bind(("notification_id", notification_id.clone()))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let mut rows = Self::map_rows(rows)?;
+            let Some(notification) = rows.pop() else {
+                return Err(DomainError::NotFound);
+            };
+            if notification.user_id != user_id {
+                return Err(DomainError::Forbidden(
+                    "notification belongs to another user".into(),
+                ));
+            }
+            if notification.read_at_ms.is_some() {
+                return Ok(notification);
+            }
+
+            let mut response = client
+                .query(
+                    "UPDATE discovery_notification \
+                     SET read_at = $read_at \
+                     WHERE notification_id = $notification_id \
+                     RETURN AFTER *",
+                )
+                .bind(("read_at", read_at))
+                .bind(("notification_id", notification_id.clone()))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            let mut rows = Self::map_rows(rows)?;
+            rows.pop().ok_or(DomainError::NotFound)
+        })
+    }
+
+    fn mark_group_as_read(
+        &self,
+        user_id: &str,
+        group_key: &str,
+        read_at_ms: i64,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<usize>> {
+        let user_id = user_id.to_string();
+        let group_key = group_key.to_string();
+        let client = self.client.clone();
+        let read_at = match Self::to_rfc3339(read_at_ms) {
+            Ok(value) => value,
+            Err(err) => return Box::pin(async move { Err(err) }),
+        };
+        Box::pin(async move {
+            let mut response = client
+                .query(
+                    "UPDATE discovery_notification \
+                     SET read_at = $read_at \
+                     WHERE user_id = $user_id AND group_key = $group_key AND read_at IS NONE \
+                     AND in_app_hidden != true \
+                     AND (deliver_at IS NONE OR deliver_at <= $read_at) \
+                     RETURN notification_id",
+                )
+                .bind(("read_at", read_at))
+                .bind(("user_id", user_id))
+                .bind(("group_key", group_key))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            Ok(rows.len())
+        })
+    }
+
+    fn unread_count(
+        &self,
+        user_id: &str,
+        as_of_ms: i64,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<usize>> {
+        let user_id = user_id.to_string();
+        let as_of = match Self::to_rfc3339(as_of_ms) {
+            Ok(value) => value,
+            Err(err) => return Box::pin(async move { Err(err) }),
+        };
+        let client = self.client.clone();
+        Box::pin(async move {
+            let mut response = client
+                .query(
+                    "SELECT count() AS unread_count \
+                     FROM discovery_notification \
+                     WHERE user_id = $user_id AND read_at IS NONE \
+                     AND in_app_hidden != true \
+                     AND (deliver_at IS NONE OR deliver_at <= $as_of) \
+                     GROUP ALL",
+                )
+                .bind(("user_id", user_id))
+                .bind(("as_of", as_of))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            // GROUP ALL yields no row when nothing matches.
+            if rows.is_empty() {
+                return Ok(0);
+            }
+            Self::decode_count(rows, "unread_count", "unread_count")
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryFeedPreferenceRepository {
+    monitors: Arc<RwLock<HashMap<(String, String), FeedMonitorPreference>>>,
+    follows: Arc<RwLock<HashMap<(String, String), FeedFollowPreference>>>,
+}
+
+impl InMemoryFeedPreferenceRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl FeedPreferenceRepository for InMemoryFeedPreferenceRepository {
+    fn set_monitor_preference(
+        &self,
+        preference: &FeedMonitorPreference,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<()>> {
+        let preference = preference.clone();
+        let monitors = self.monitors.clone();
+        Box::pin(async move {
+            let key = (preference.user_id.clone(), preference.witness_id.clone());
+            let mut monitors = monitors.write().await;
+            if preference.monitored {
+                monitors.insert(key, preference);
+            } else {
+                monitors.remove(&key);
+            }
+            Ok(())
+        })
+    }
+
+    fn set_follow_preference(
+        &self,
+        preference: &FeedFollowPreference,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<()>> {
+        let preference = preference.clone();
+        let follows = self.follows.clone();
+        Box::pin(async move {
+            let key = (preference.user_id.clone(), preference.entity_id.clone());
+            let mut follows = follows.write().await;
+            if preference.followed {
+                follows.insert(key, preference);
+            } else {
+                follows.remove(&key);
+            }
+            Ok(())
+        })
+    }
+
+    fn monitor_states(
+        &self,
+        user_id: &str,
+        witness_ids: &[String],
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<HashMap<String, bool>>> {
+        let user_id = user_id.to_string();
+        let witness_ids = witness_ids.to_vec();
+        let monitors = self.monitors.clone();
+        Box::pin(async move {
+            let monitors = monitors.read().await;
+            Ok(witness_ids
+                .into_iter()
+                .filter_map(|witness_id| {
+                    let monitored = monitors
+                        .get(&(user_id.clone(), witness_id.clone()))?
+                        .monitored;
+                    Some((witness_id, monitored))
+                })
+                .collect())
+        })
+    }
+
+    fn follow_states(
+        &self,
+        user_id: &str,
+        entity_ids: &[String],
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<HashMap<String, bool>>> {
+        let user_id = user_id.to_string();
+        let entity_ids = entity_ids.to_vec();
+        let follows = self.follows.clone();
+        Box::pin(async move {
+            let follows = follows.read().await;
+            Ok(entity_ids
+                .into_iter()
+                .filter_map(|entity_id| {
+                    let followed = follows.get(&(user_id.clone(), entity_id.clone()))?.followed;
+                    Some((entity_id, followed))
+                })
+                .collect())
+        })
+    }
+
+    fn list_follows(
+        &self,
+        user_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<FeedFollowPreference>>> {
+        let user_id = user_id.to_string();
+        let follows = self.follows.clone();
+        Box::pin(async move {
+            let mut rows: Vec<FeedFollowPreference> = follows
+                .read()
+                .await
+                .values()
+                .filter(|preference| preference.user_id == user_id && preference.followed)
+                .cloned()
+                .collect();
+            rows.sort_by(|left, right| {
+                right
+                    .updated_at_ms
+                    .cmp(&left.updated_at_ms)
+                    .then_with(|| left.entity_id.cmp(&right.entity_id))
+            });
+            Ok(rows)
+        })
+    }
+}
+
+#[cfg(test)]
+mod feed_preference_repository_tests {
+    use super::*;
+
+    fn follow(entity_id: &str, followed: bool, updated_at_ms: i64) -> FeedFollowPreference {
+        FeedFollowPreference {
+            user_id: "user-1".to_string(),
+            entity_id: entity_id.to_string(),
+            followed,
+            updated_at_ms,
+        }
+    }
+
+    #[tokio::test]
+    async fn in_memory_feed_preferences_toggle_and_list_newest_first() {
+        let repo = InMemoryFeedPreferenceRepository::new();
+        repo.set_follow_preference(&follow("ent-air", true, 1_000))
+            .await
+            .expect("follow air");
+        repo.set_follow_preference(&follow("ent-jalan", true, 2_000))
+            .await
+            .expect("follow jalan");
+        repo.set_monitor_preference(&FeedMonitorPreference {
+            user_id: "user-1".to_string(),
+            witness_id: "witness-1".to_string(),
+            monitored: true,
+            updated_at_ms: 1_000,
+        })
+        .await
+        .expect("monitor");
+
+        let listed = repo.list_follows("user-1").await.expect("list");
+        let listed: Vec<&str> = listed.iter().map(|row| row.entity_id.as_str()).collect();
+        assert_eq!(listed, vec!["ent-jalan", "ent-air"]);
+        assert!(repo.list_follows("user-2").await.expect("list").is_empty());
+
+        repo.set_follow_preference(&follow("ent-air", false, 3_000))
+            .await
+            .expect("unfollow air");
+        let states = repo
+            .follow_states("user-1", &["ent-air".to_string(), "ent-jalan".to_string()])
+            .await
+            .expect("states");
+        assert_eq!(states.get("ent-jalan"), Some(&true));
+        assert!(!states.contains_key("ent-air"));
+
+        let monitors = repo
+            .monitor_states("user-1", &["witness-1".to_string()])
+            .await
+            .expect("monitor states");
+        assert_eq!(monitors.get("witness-1"), Some(&true));
+    }
+}
+
+const FEED_MONITOR_PREFERENCE_TABLE: &str = "feed_monitor_preference";
+const FEED_FOLLOW_PREFERENCE_TABLE: &str = "feed_follow_preference";
+
+#[derive(Clone)]
+pub struct SurrealFeedPreferenceRepository {
+    client: Arc<Surreal<Client>>,
+}
+
+impl SurrealFeedPreferenceRepository {
+    pub fn with_client(client: Arc<Surreal<Client>>) -> Self {
+        Self { client }
+    }
+
+    pub async fn new(db_config: &DbConfig) -> anyhow::Result<Self> {
+        let db = Surreal::<Client>::init();
+        db.connect::<Ws>(&db_config.endpoint).await?;
+        db.signin(Root {
+            username: db_config.username.clone(),
+            password: db_config.password.clone(),
+        })
+        .await?;
+        db.use_ns(&db_config.namespace)
+            .use_db(&db_config.database)
+            .await?;
+        Ok(Self {
+            client: Arc::new(db),
+        })
+    }
+
+    /// Record ids are `{user_id}:{target_id}` so a toggle is a single-row upsert.
+    fn record_id(user_id: &str, target_id: &str) -> String {
+        format!("{user_id}:{target_id}")
+    }
+
+    fn map_surreal_error(err: surrealdb::Error) -> DomainError {
+        DomainError::Validation(format!("surreal query failed: {err}"))
+    }
+
+    fn decode_states(rows: Vec<Value>, id_field: &str, flag_field: &str) -> HashMap<String, bool> {
+        rows.into_iter()
+            .filter_map(|row| {
+                let id = row
+                    .get(id_field)
+                    .and_then(Value::as_str)
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())?
+                    .to_string();
+                let flag = row
+                    .get(flag_field)
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+                Some((id, flag))
+            })
+            .collect()
+    }
+}
+
+impl FeedPreferenceRepository for SurrealFeedPreferenceRepository {
+    fn set_monitor_preference(
+        &self,
+        preference: &FeedMonitorPreference,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<()>> {
+        let preference = preference.clone();
+        let client = self.client.clone();
+        Box::pin(async move {
+            let record_id = Self::record_id(&preference.user_id, &preference.witness_id);
+            let statement = if preference.monitored {
+                format!(
+                    "UPSERT type::thing('{FEED_MONITOR_PREFERENCE_TABLE}', $record_id) CONTENT {{ user_id: $user_id, witness_id: $witness_id, monitored: true, updated_at_ms: $updated_at_ms }};"
+                )
+            } else {
+                format!("DELETE type::thing('{FEED_MONITOR_PREFERENCE_TABLE}', $record_id);")
+            };
+            client
+                .query(statement)
+                .bind(("record_id", record_id))
+                .bind(("user_id", preference.user_id))
+                .bind(("witness_id", preference.witness_id))
+                .bind(("updated_at_ms", preference.updated_at_ms))
                 .await
+                .map_err(Self::map_surreal_error)?
+                .check()
                 .map_err(Self::map_surreal_error)?;
-            Ok(item)
+            Ok(())
         })
     }
 
-    fn upsert_participant_edges_for_item(
+    fn set_follow_preference(
         &self,
-        item: &FeedItem,
+        preference: &FeedFollowPreference,
     ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<()>> {
-        let item = item.clone();
-        let actor_ids = feed_participant_actor_ids(&item);
-        let occurred_at = match Self::to_rfc3339(item.occurred_at_ms) {
-            Ok(value) => value,
-            Err(err) => return Box::pin(async move { Err(err) }),
-        };
-        let created_at = match Self::to_rfc3339(item.created_at_ms) {
-            Ok(value) => value,
-            Err(err) => return Box::pin(async move { Err(err) }),
-        };
+        let preference = preference.clone();
         let client = self.client.clone();
         Box::pin(async move {
-            for actor_id in actor_ids {
-                let edge_id = format!("{actor_id}:{}", item.feed_id);
-                let result = client
-                    .query(
-                        "CREATE feed_participant_edge SET \
-                         edge_id = $edge_id, \
-                         actor_id = $actor_id, \
-                         feed_id = $feed_id, \
-                         occurred_at = <datetime>$occurred_at, \
-                         scope_id = IF $scope_id = NULL THEN NONE ELSE $scope_id END, \
-                         privacy_level = IF $privacy_level = NULL THEN NONE ELSE $privacy_level END, \
-                         source_type = $source_type, \
-                         source_id = $source_id, \
-                         created_at = <datetime>$created_at, \
-                         request_id = $request_id",
-                    )
-                    .bind(("edge_id", edge_id))
-                    .bind(("actor_id", actor_id))
-                    .bind(("feed_id", item.feed_id.clone()))
-                    .bind(("occurred_at", occurred_at.clone()))
-                    .bind(("scope_id", item.scope_id.clone()))
-                    .bind(("privacy_level", item.privacy_level.clone()))
-                    .bind(("source_type", item.source_type.clone()))
-                    .bind(("source_id", item.source_id.clone()))
-                    .bind(("created_at", created_at.clone()))
-                    .bind(("request_id", item.request_id.clone()))
-                    .await
-                    .map_err(Self::map_surreal_error);
-
-                match result {
-                    Ok(_) | Err(DomainError::Conflict) => {}
-                    Err(err) => return Err(err),
-                }
-            }
+            let record_id = Self::record_id(&preference.user_id, &preference.entity_id);
+            let statement = if preference.followed {
+                format!(
+                    "UPSERT type::thing('{FEED_FOLLOW_PREFERENCE_TABLE}', $record_id) CONTENT {{ user_id: $user_id, entity_id: $entity_id, followed: true, updated_at_ms: $updated_at_ms }};"
+                )
+            } else {
+                format!("DELETE type::thing('{FEED_FOLLOW_PREFERENCE_TABLE}', $record_id);")
+            };
+            client
+                .query(statement)
+                .bind(("record_id", record_id))
+                .bind(("user_id", preference.user_id))
+                .bind(("entity_id", preference.entity_id))
+                .bind(("updated_at_ms", preference.updated_at_ms))
+                .await
+                .map_err(Self::map_surreal_error)?
+                .check()
+                .map_err(Self::map_surreal_error)?;
             Ok(())
         })
     }
 
-    fn get_by_source_request(
+    fn monitor_states(
         &self,
-        source_type: &str,
-        source_id: &str,
-        request_id: &str,
-    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<FeedItem>>> {
-        let source_type = source_type.to_string();
-        let source_id = source_id.to_string();
-        let request_id = request_id.to_string();
+        user_id: &str,
+        witness_ids: &[String],
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<HashMap<String, bool>>> {
+        let user_id = user_id.to_string();
+        let witness_ids = witness_ids.to_vec();
         let client = self.client.clone();
         Box::pin(async move {
-            let projection = Self::feed_select_projection();
+            if witness_ids.is_empty() {
+                return Ok(HashMap::new());
+            }
             let mut response = client
                 .query(format!(
-                    "SELECT {projection} FROM discovery_feed_item \
-                     WHERE source_type = $source_type AND source_id = $source_id AND request_id = $request_id LIMIT 1",
+                    "SELECT witness_id, monitored FROM {FEED_MONITOR_PREFERENCE_TABLE} WHERE user_id = $user_id AND witness_id IN $witness_ids;"
                 ))
-                .bind(("source_type", source_type))
-                .bind(("source_id", source_id))
-                .bind(("request_id", request_id))
+                .bind(("user_id", user_id))
+                .bind(("witness_ids", witness_ids))
                 .await
                 .map_err(Self::map_surreal_error)?;
             let rows: Vec<Value> = response
                 .take(0)
                 .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
-            let mut rows = Self::map_rows(rows)?;
-            Ok(rows.pop())
+            Ok(Self::decode_states(rows, "witness_id", "monitored"))
         })
     }
 
-    fn get_by_feed_id(
+    fn follow_states(
         &self,
-        feed_id: &str,
-    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<FeedItem>>> {
-        let feed_id = feed_id.to_string();
+        user_id: &str,
+        entity_ids: &[String],
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<HashMap<String, bool>>> {
+        let user_id = user_id.to_string();
+        let entity_ids = entity_ids.to_vec();
         let client = self.client.clone();
         Box::pin(async move {
-            let projection = Self::feed_select_projection();
+            if entity_ids.is_empty() {
+                return Ok(HashMap::new());
+            }
             let mut response = client
                 .query(format!(
-                    "SELECT {projection} FROM discovery_feed_item WHERE feed_id = $feed_id LIMIT 1",
+                    "SELECT entity_id, followed FROM {FEED_FOLLOW_PREFERENCE_TABLE} WHERE user_id = $user_id AND entity_id IN $entity_ids;"
                 ))
-                .bind(("feed_id", feed_id))
+                .bind(("user_id", user_id))
+                .bind(("entity_ids", entity_ids))
                 .await
                 .map_err(Self::map_surreal_error)?;
             let rows: Vec<Value> = response
                 .take(0)
                 .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
-            let mut rows = Self::map_rows(rows)?;
-            Ok(rows.pop())
+            Ok(Self::decode_states(rows, "entity_id", "followed"))
         })
     }
 
-    fn get_latest_by_source(
+    fn list_follows(
         &self,
-        source_type: &str,
-        source_id: &str,
-    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<FeedItem>>> {
-        let source_type = source_type.to_string();
-        let source_id = source_id.to_string();
+        user_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<FeedFollowPreference>>> {
+        let user_id = user_id.to_string();
         let client = self.client.clone();
         Box::pin(async move {
-            let projection = Self::feed_select_projection();
             let mut response = client
                 .query(format!(
-                    "SELECT {projection} FROM discovery_feed_item \
-                     WHERE source_type = $source_type AND source_id = $source_id \
-                     ORDER BY occurred_at DESC, feed_id DESC LIMIT 1",
+                    "SELECT user_id, entity_id, followed, updated_at_ms FROM {FEED_FOLLOW_PREFERENCE_TABLE} \
+                     WHERE user_id = $user_id AND followed = true \
+                     ORDER BY updated_at_ms DESC, entity_id ASC;"
                 ))
-                .bind(("source_type", source_type))
-                .bind(("source_id", source_id))
+                .bind(("user_id", user_id))
                 .await
                 .map_err(Self::map_surreal_error)?;
             let rows: Vec<Value> = response
                 .take(0)
                 .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
-            let mut rows = Self::map_rows(rows)?;
-            Ok(rows.pop())
+            rows.into_iter()
+                .map(|row| {
+                    serde_json::from_value::<FeedFollowPreference>(row).map_err(|err| {
+                        DomainError::Validation(format!("invalid follow preference row: {err}"))
+                    })
+                })
+                .collect()
         })
     }
+}
 
-    fn merge_payload(
+#[derive(Default)]
+pub struct InMemoryDigestSubscriptionRepository {
+    store: Arc<RwLock<HashMap<String, DigestSubscription>>>,
+}
+
+impl InMemoryDigestSubscriptionRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl DigestSubscriptionRepository for InMemoryDigestSubscriptionRepository {
+    fn get_subscription(
         &self,
-        feed_id: &str,
-        payload_patch: Value,
-    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<FeedItem>> {
-        let feed_id = feed_id.to_string();
+        user_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<DigestSubscription>>> {
+        let user_id = user_id.to_string();
+        let store = self.store.clone();
+        Box::pin(async move { Ok(store.read().await.get(&user_id).cloned()) })
+    }
+
+    fn upsert_subscription(
+        &self,
+        subscription: &DigestSubscription,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<DigestSubscription>> {
+        let subscription = subscription.clone();
+        let store = self.store.clone();
+        Box::pin(async move {
+            store
+                .write()
+                .await
+                .insert(subscription.user_id.clone(), subscription.clone());
+            Ok(subscription)
+        })
+    }
+
+    fn list_active_subscriptions(
+        &self,
+        after_user_id: Option<&str>,
+        limit: usize,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<DigestSubscription>>> {
+        let after_user_id = after_user_id.map(str::to_string);
+        let store = self.store.clone();
+        Box::pin(async move {
+            let mut rows: Vec<DigestSubscription> = store
+                .read()
+                .await
+                .values()
+                .filter(|subscription| subscription.frequency != DigestFrequency::Off)
+                .filter(|subscription| {
+                    after_user_id
+                        .as_deref()
+                        .is_none_or(|after| subscription.user_id.as_str() > after)
+                })
+                .cloned()
+                .collect();
+            rows.sort_by(|left, right| left.user_id.cmp(&right.user_id));
+            rows.truncate(limit);
+            Ok(rows)
+        })
+    }
+
+    fn mark_sent(
+        &self,
+        user_id: &str,
+        sent_at_ms: i64,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<()>> {
+        let user_id = user_id.to_string();
+        let store = self.store.clone();
+        Box::pin(async move {
+            let mut store = store.write().await;
+            let subscription = store.get_mut(&user_id).ok_or(DomainError::NotFound)?;
+            subscription.last_sent_at_ms = Some(sent_at_ms);
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod digest_subscription_repository_tests {
+    use super::*;
+    use gotong_domain::digest::DigestLocale;
+
+    fn subscription(user_id: &str, frequency: DigestFrequency) -> DigestSubscription {
+        DigestSubscription {
+            user_id: user_id.to_string(),
+            email: format!("{user_id}@example.org"),
+            locale: DigestLocale::Id,
+            timezone: "Asia/Jakarta".to_string(),
+            frequency,
+            send_hour_local: 7,
+            send_weekday: 1,
+            last_sent_at_ms: None,
+            created_at_ms: 1_000,
+            updated_at_ms: 1_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn in_memory_digest_subscriptions_page_active_rows_and_mark_sent() {
+        let repo = InMemoryDigestSubscriptionRepository::new();
+        for (user_id, frequency) in [
+            ("user-c", DigestFrequency::Daily),
+            ("user-a", DigestFrequency::Weekly),
+            ("user-b", DigestFrequency::Off),
+            ("user-d", DigestFrequency::Weekly),
+        ] {
+            repo.upsert_subscription(&subscription(user_id, frequency))
+                .await
+                .expect("upsert");
+        }
+
+        let first = repo
+            .list_active_subscriptions(None, 2)
+            .await
+            .expect("first page");
+        let first: Vec<&str> = first.iter().map(|row| row.user_id.as_str()).collect();
+        assert_eq!(first, vec!["user-a", "user-c"]);
+        let second = repo
+            .list_active_subscriptions(Some("user-c"), 2)
+            .await
+            .expect("second page");
+        let second: Vec<&str> = second.iter().map(|row| row.user_id.as_str()).collect();
+        assert_eq!(second, vec!["user-d"]);
+
+        repo.mark_sent("user-a", 5_000).await.expect("mark sent");
+        let stored = repo
+            .get_subscription("user-a")
+            .await
+            .expect("get")
+            .expect("row");
+        assert_eq!(stored.last_sent_at_ms, Some(5_000));
+        assert!(matches!(
+            repo.mark_sent("user-z", 5_000).await,
+            Err(DomainError::NotFound)
+        ));
+    }
+}
+
+const DIGEST_SUBSCRIPTION_TABLE: &str = "digest_subscription";
+
+#[derive(Clone)]
+pub struct SurrealDigestSubscriptionRepository {
+    client: Arc<Surreal<Client>>,
+}
+
+impl SurrealDigestSubscriptionRepository {
+    pub fn with_client(client: Arc<Surreal<Client>>) -> Self {
+        Self { client }
+    }
+
+    pub async fn new(db_config: &DbConfig) -> anyhow::Result<Self> {
+        let db = Surreal::<Client>::init();
+        db.connect::<Ws>(&db_config.endpoint).await?;
+        db.signin(Root {
+            username: db_config.username.clone(),
+            password: db_config.password.clone(),
+        })
+        .await?;
+        db.use_ns(&db_config.namespace)
+            .use_db(&db_config.database)
+            .await?;
+        Ok(Self {
+            client: Arc::new(db),
+        })
+    }
+
+    fn map_surreal_error(err: surrealdb::Error) -> DomainError {
+        DomainError::Validation(format!("surreal query failed: {err}"))
+    }
+
+    fn decode_rows(rows: Vec<Value>) -> DomainResult<Vec<DigestSubscription>> {
+        rows.into_iter()
+            .map(|row| {
+                serde_json::from_value::<DigestSubscription>(row).map_err(|err| {
+                    DomainError::Validation(format!("invalid digest subscription row: {err}"))
+                })
+            })
+            .collect()
+    }
+}
+
+impl DigestSubscriptionRepository for SurrealDigestSubscriptionRepository {
+    fn get_subscription(
+        &self,
+        user_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<DigestSubscription>>> {
+        let user_id = user_id.to_string();
         let client = self.client.clone();
         Box::pin(async move {
             let mut response = client
-                .query("SELECT payload FROM discovery_feed_item WHERE feed_id = $feed_id LIMIT 1")
-                .bind(("feed_id", feed_id.clone()))
+                .query(format!(
+                    "SELECT * OMIT id FROM type::thing('{DIGEST_SUBSCRIPTION_TABLE}', $user_id);"
+                ))
+                .bind(("user_id", user_id))
                 .await
                 .map_err(Self::map_surreal_error)?;
             let rows: Vec<Value> = response
                 .take(0)
                 .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
-            let existing_payload = rows
-                .into_iter()
-                .next()
-                .and_then(|row| row.get("payload").cloned())
-                .unwrap_or(Value::Null);
-            let mut merged_payload = match existing_payload {
-                Value::Null => Value::Object(serde_json::Map::new()),
-                other => other,
-            };
-            deep_merge_json(&mut merged_payload, payload_patch);
+            Ok(Self::decode_rows(rows)?.into_iter().next())
+        })
+    }
 
+    fn upsert_subscription(
+        &self,
+        subscription: &DigestSubscription,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<DigestSubscription>> {
+        let subscription = subscription.clone();
+        let client = self.client.clone();
+        Box::pin(async move {
             client
-                .query(
-                    "UPDATE discovery_feed_item SET payload = $payload \
-                     WHERE feed_id = $feed_id",
-                )
-                .bind(("feed_id", feed_id.clone()))
-                .bind(("payload", merged_payload))
+                .query(format!(
+                    "UPSERT type::thing('{DIGEST_SUBSCRIPTION_TABLE}', $user_id) CONTENT {{ \
+                     user_id: $user_id, email: $email, locale: $locale, timezone: $timezone, \
+                     frequency: $frequency, send_hour_local: $send_hour_local, \
+                     send_weekday: $send_weekday, last_sent_at_ms: $last_sent_at_ms, \
+                     created_at_ms: $created_at_ms, updated_at_ms: $updated_at_ms }};"
+                ))
+                .bind(("user_id", subscription.user_id.clone()))
+                .bind(("email", subscription.email.clone()))
+                .bind(("locale", subscription.locale.as_str()))
+                .bind(("timezone", subscription.timezone.clone()))
+                .bind(("frequency", subscription.frequency.as_str()))
+                .bind(("send_hour_local", i64::from(subscription.send_hour_local)))
+                .bind(("send_weekday", i64::from(subscription.send_weekday)))
+                .bind(("last_sent_at_ms", subscription.last_sent_at_ms))
+                .bind(("created_at_ms", subscription.created_at_ms))
+                .bind(("updated_at_ms", subscription.updated_at_ms))
                 .await
+                .map_err(Self::map_surreal_error)?
+                .check()
                 .map_err(Self::map_surreal_error)?;
+            Ok(subscription)
+        })
+    }
 
-            let projection = Self::feed_select_projection();
+    fn list_active_subscriptions(
+        &self,
+        after_user_id: Option<&str>,
+        limit: usize,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<DigestSubscription>>> {
+        let after_user_id = after_user_id.unwrap_or_default().to_string();
+        let client = self.client.clone();
+        Box::pin(async move {
             let mut response = client
                 .query(format!(
-                    "SELECT {projection} FROM discovery_feed_item WHERE feed_id = $feed_id LIMIT 1",
+                    "SELECT * OMIT id FROM {DIGEST_SUBSCRIPTION_TABLE} \
+                     WHERE frequency != 'off' AND user_id > $after_user_id \
+                     ORDER BY user_id ASC LIMIT $limit;"
                 ))
-                .bind(("feed_id", feed_id))
+                .bind(("after_user_id", after_user_id))
+                .bind(("limit", limit as i64))
                 .await
                 .map_err(Self::map_surreal_error)?;
             let rows: Vec<Value> = response
                 .take(0)
                 .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
-            let mut rows = Self::map_rows(rows)?;
-            rows.pop().ok_or(DomainError::NotFound)
+            Self::decode_rows(rows)
         })
     }
 
-    fn list_feed(
+    fn mark_sent(
         &self,
-        query: &FeedRepositoryQuery,
-    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<FeedItem>>> {
-        let query = query.clone();
-        let repository = self.clone();
+        user_id: &str,
+        sent_at_ms: i64,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<()>> {
+        let user_id = user_id.to_string();
+        let client = self.client.clone();
         Box::pin(async move {
-            if query.involvement_only {
-                return repository.list_feed_involvement_edge_first(&query).await;
-            }
-            repository.list_feed_legacy(&query).await
+            client
+                .query(format!(
+                    "UPDATE type::thing('{DIGEST_SUBSCRIPTION_TABLE}', $user_id) SET last_sent_at_ms = $sent_at_ms;"
+                ))
+                .bind(("user_id", user_id))
+                .bind(("sent_at_ms", sent_at_ms))
+                .await
+                .map_err(Self::map_surreal_error)?
+                .check()
+                .map_err(Self::map_surreal_error)?;
+            Ok(())
         })
     }
+}
+
+#[derive(Default)]
+pub struct InMemoryNotificationPreferenceRepository {
+    store: Arc<RwLock<HashMap<String, NotificationPreferences>>>,
+}
+
+impl InMemoryNotificationPreferenceRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
 
-    fn search_feed(
+impl NotificationPreferenceRepository for InMemoryNotificationPreferenceRepository {
+    fn get_preferences(
         &self,
-        query: &FeedRepositorySearchQuery,
-    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<FeedItem>>> {
-        let query = query.clone();
-        let repository = self.clone();
+        user_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<NotificationPreferences>>> {
+        let user_id = user_id.to_string();
+        let store = self.store.clone();
+        Box::pin(async move { Ok(store.read().await.get(&user_id).cloned()) })
+    }
+
+    fn upsert_preferences(
+        &self,
+        preferences: &NotificationPreferences,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<NotificationPreferences>> {
+        let preferences = preferences.clone();
+        let store = self.store.clone();
         Box::pin(async move {
-            if query.involvement_only {
-                return repository.search_feed_involvement_edge_first(&query).await;
-            }
-            repository.search_feed_legacy(&query).await
+            store
+                .write()
+                .await
+                .insert(preferences.user_id.clone(), preferences.clone());
+            Ok(preferences)
         })
     }
 }
 
+const NOTIFICATION_PREFERENCE_TABLE: &str = "notification_preference";
+
 #[derive(Clone)]
-pub struct SurrealDiscoveryNotificationRepository {
+pub struct SurrealNotificationPreferenceRepository {
     client: Arc<Surreal<Client>>,
 }
 
-impl SurrealDiscoveryNotificationRepository {
+impl SurrealNotificationPreferenceRepository {
     pub fn with_client(client: Arc<Surreal<Client>>) -> Self {
         Self { client }
     }
@@ -6295,318 +10508,248 @@ impl SurrealDiscoveryNotificationRepository {
         })
     }
 
-    fn parse_datetime_ms(value: &str) -> DomainResult<i64> {
-        let datetime = OffsetDateTime::parse(value, &Rfc3339)
-            .map_err(|err| DomainError::Validation(format!("invalid datetime: {err}")))?;
-        Ok((datetime.unix_timestamp_nanos() / 1_000_000) as i64)
-    }
-
-    fn to_rfc3339(timestamp_ms: i64) -> DomainResult<String> {
-        let datetime =
-            OffsetDateTime::from_unix_timestamp_nanos((timestamp_ms as i128) * 1_000_000)
-                .map_err(|err| DomainError::Validation(format!("invalid timestamp: {err}")))?;
-        Ok(datetime
-            .format(&Rfc3339)
-            .unwrap_or_else(|_| "1970-01-01T00:00:00Z".to_string()))
+    fn map_surreal_error(err: surrealdb::Error) -> DomainError {
+        DomainError::Validation(format!("surreal query failed: {err}"))
     }
+}
 
-    fn map_rows(rows: Vec<Value>) -> DomainResult<Vec<InAppNotification>> {
-        rows.into_iter()
-            .map(|row| {
-                serde_json::from_value::<SurrealDiscoveryNotificationRow>(row)
-                    .map_err(|err| {
-                        DomainError::Validation(format!("invalid notification row: {err}"))
-                    })
-                    .and_then(|row| {
-                        let read_at_ms = match row.read_at {
-                            Some(value) => Some(Self::parse_datetime_ms(&value)?),
-                            None => None,
-                        };
-                        Ok(InAppNotification {
-                            notification_id: row.notification_id,
-                            user_id: row.user_id,
-                            actor_id: row.actor_id,
-                            actor_username: row.actor_username,
-                            notification_type: row.notification_type,
-                            source_type: row.source_type,
-                            source_id: row.source_id,
-                            title: row.title,
-                            body: row.body,
-                            payload: row.payload,
-                            created_at_ms: Self::parse_datetime_ms(&row.created_at)?,
-                            read_at_ms,
-                            privacy_level: row.privacy_level,
-                            request_id: row.request_id,
-                            correlation_id: row.correlation_id,
-                            dedupe_key: row.dedupe_key,
-                        })
+impl NotificationPreferenceRepository for SurrealNotificationPreferenceRepository {
+    fn get_preferences(
+        &self,
+        user_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<NotificationPreferences>>> {
+        let user_id = user_id.to_string();
+        let client = self.client.clone();
+        Box::pin(async move {
+            let mut response = client
+                .query(format!(
+                    "SELECT * OMIT id FROM type::thing('{NOTIFICATION_PREFERENCE_TABLE}', $user_id);"
+                ))
+                .bind(("user_id", user_id))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            rows.into_iter()
+                .next()
+                .map(|row| {
+                    serde_json::from_value::<NotificationPreferences>(row).map_err(|err| {
+                        DomainError::Validation(format!(
+                            "invalid notification preference row: {err}"
+                        ))
                     })
-            })
-            .collect()
+                })
+                .transpose()
+        })
     }
 
-    fn to_create_payload(
-        notification: &InAppNotification,
-    ) -> DomainResult<SurrealDiscoveryNotificationCreateRow> {
-        let created_at = Self::to_rfc3339(notification.created_at_ms)?;
-        Ok(SurrealDiscoveryNotificationCreateRow {
-            notification_id: notification.notification_id.clone(),
-            user_id: notification.user_id.clone(),
-            actor_id: notification.actor_id.clone(),
-            actor_username: notification.actor_username.clone(),
-            notification_type: notification.notification_type.clone(),
-            source_type: notification.source_type.clone(),
-            source_id: notification.source_id.clone(),
-            title: notification.title.clone(),
-            body: notification.body.clone(),
-            payload: notification.payload.clone(),
-            created_at,
-            read_at: notification.read_at_ms.map(Self::to_rfc3339).transpose()?,
-            privacy_level: notification.privacy_level.clone(),
-            request_id: notification.request_id.clone(),
-            correlation_id: notification.correlation_id.clone(),
-            dedupe_key: notification.dedupe_key.clone(),
+    fn upsert_preferences(
+        &self,
+        preferences: &NotificationPreferences,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<NotificationPreferences>> {
+        let preferences = preferences.clone();
+        let client = self.client.clone();
+        Box::pin(async move {
+            let content = to_value(&preferences).map_err(|err| {
+                DomainError::Validation(format!("invalid notification preferences: {err}"))
+            })?;
+            client
+                .query(format!(
+                    "UPSERT type::thing('{NOTIFICATION_PREFERENCE_TABLE}', $user_id) CONTENT $content;"
+                ))
+                .bind(("user_id", preferences.user_id.clone()))
+                .bind(("content", content))
+                .await
+                .map_err(Self::map_surreal_error)?
+                .check()
+                .map_err(Self::map_surreal_error)?;
+            Ok(preferences)
         })
     }
+}
 
-    fn decode_count(rows: Vec<Value>, field: &str, label: &str) -> DomainResult<usize> {
-        let Some(row) = rows.into_iter().next() else {
-            return Err(DomainError::Validation(format!("missing {label}")));
-        };
-        let Some(value) = row.get(field) else {
-            return Err(DomainError::Validation(format!("{label} missing")));
-        };
-        let count = value
-            .as_u64()
-            .or_else(|| value.as_i64().and_then(|value| value.try_into().ok()))
-            .ok_or_else(|| DomainError::Validation(format!("invalid {label}")))?;
-        Ok(count as usize)
-    }
+/// Keyed by `subscription_id`, so a browser endpoint re-registered by another
+/// account moves to that account.
+#[derive(Default)]
+pub struct InMemoryPushSubscriptionRepository {
+    store: Arc<RwLock<HashMap<String, PushSubscription>>>,
+}
 
-    fn map_surreal_error(err: surrealdb::Error) -> DomainError {
-        let error_message = err.to_string().to_lowercase();
-        if error_message.contains("already exists")
-            || error_message.contains("duplicate")
-            || error_message.contains("unique")
-            || error_message.contains("conflict")
-        {
-            return DomainError::Conflict;
-        }
-        DomainError::Validation(format!("surreal query failed: {error_message}"))
+impl InMemoryPushSubscriptionRepository {
+    pub fn new() -> Self {
+        Self::default()
     }
 }
 
-impl NotificationRepository for SurrealDiscoveryNotificationRepository {
-    fn create_notification(
+impl PushSubscriptionRepository for InMemoryPushSubscriptionRepository {
+    fn upsert_subscription(
         &self,
-        notification: &InAppNotification,
-    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<InAppNotification>> {
-        let notification = notification.clone();
-        let payload = match Self::to_create_payload(&notification) {
-            Ok(payload) => payload,
-            Err(err) => return Box::pin(async move { Err(err) }),
-        };
-        let client = self.client.clone();
+        subscription: &PushSubscription,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<PushSubscription>> {
+        let subscription = subscription.clone();
+        let store = self.store.clone();
         Box::pin(async move {
-            let payload = to_value(payload)
-                .map_err(|err| DomainError::Validation(format!("invalid payload: {err}")))?;
-            let mut response = client
-                .query("CREATE discovery_notification CONTENT $payload")
-                .bind(("payload", payload))
+            store
+                .write()
                 .await
-                .map_err(Self::map_surreal_error)?;
-            let rows: Vec<Value> = response
-                .take(0)
-                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
-            let mut rows = Self::map_rows(rows)?;
-            rows.pop()
-                .ok_or_else(|| DomainError::Validation("create returned no row".to_string()))
+                .insert(subscription.subscription_id.clone(), subscription.clone());
+            Ok(subscription)
         })
     }
 
-    fn get_by_dedupe_key(
+    fn list_for_user(
         &self,
         user_id: &str,
-        dedupe_key: &str,
-    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<InAppNotification>>> {
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<PushSubscription>>> {
         let user_id = user_id.to_string();
-        let dedupe_key = dedupe_key.to_string();
-        let client = self.client.clone();
+        let store = self.store.clone();
         Box::pin(async move {
-            let mut response = client
-                .query(
-                    "SELECT * FROM discovery_notification \
-                     WHERE user_id = $user_id AND dedupe_key = $dedupe_key LIMIT 1",
-                )
-                .bind(("user_id", user_id))
-                .bind(("dedupe_key", dedupe_key))
+            let mut rows: Vec<PushSubscription> = store
+                .read()
                 .await
-                .map_err(Self::map_surreal_error)?;
-            let rows: Vec<Value> = response
-                .take(0)
-                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
-            let mut rows = Self::map_rows(rows)?;
-            Ok(rows.pop())
+                .values()
+                .filter(|subscription| subscription.user_id == user_id)
+                .cloned()
+                .collect();
+            rows.sort_by(|left, right| {
+                left.created_at_ms
+                    .cmp(&right.created_at_ms)
+                    .then_with(|| left.subscription_id.cmp(&right.subscription_id))
+            });
+            Ok(rows)
         })
     }
 
-    fn list_notifications(
+    fn delete_subscription(
         &self,
-        query: &NotificationRepositoryListQuery,
-    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<InAppNotification>>> {
-        let query = query.clone();
-        let client = self.client.clone();
+        user_id: &str,
+        subscription_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<bool>> {
+        let user_id = user_id.to_string();
+        let subscription_id = subscription_id.to_string();
+        let store = self.store.clone();
         Box::pin(async move {
-            let mut filters = vec!["user_id = $user_id".to_string()];
-            if query.cursor_created_at_ms.is_some() && query.cursor_notification_id.is_some() {
-                filters.push(
-                    "((created_at < $cursor_created_at) OR (created_at = $cursor_created_at AND notification_id < $cursor_notification_id))".to_string(),
-                );
-            }
-            if !query.include_read {
-                filters.push("read_at IS NONE".to_string());
-            }
-
-            let mut statement = String::from("SELECT * FROM discovery_notification");
-            if !filters.is_empty() {
-                statement.push_str(" WHERE ");
-                statement.push_str(&filters.join(" AND "));
+            let mut store = store.write().await;
+            let owned = store
+                .get(&subscription_id)
+                .is_some_and(|subscription| subscription.user_id == user_id);
+            if owned {
+                store.remove(&subscription_id);
             }
-            statement.push_str(" ORDER BY created_at DESC, notification_id DESC LIMIT $limit");
+            Ok(owned)
+        })
+    }
+}
 
-            let mut db_query = client
-                .query(statement)
-                .bind(("user_id", query.user_id.clone()))
-                .bind(("limit", query.limit as i64));
+const PUSH_SUBSCRIPTION_TABLE: &str = "push_subscription";
 
-            if let Some(cursor_ms) = query.cursor_created_at_ms {
-                if let Some(cursor_notification_id) = query.cursor_notification_id.as_deref() {
-                    let cursor_created_at = Self::to_rfc3339(cursor_ms)?;
-                    db_query = db_query
-                        .bind(("cursor_created_at", cursor_created_at))
-                        .bind(("cursor_notification_id", cursor_notification_id.to_string()));
-                }
-            }
+#[derive(Clone)]
+pub struct SurrealPushSubscriptionRepository {
+    client: Arc<Surreal<Client>>,
+}
 
-            let mut response = db_query.await.map_err(Self::map_surreal_error)?;
-            let rows: Vec<Value> = response
-                .take(0)
-                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
-            let rows = Self::map_rows(rows)?;
-            Ok(rows)
+impl SurrealPushSubscriptionRepository {
+    pub fn with_client(client: Arc<Surreal<Client>>) -> Self {
+        Self { client }
+    }
+
+    pub async fn new(db_config: &DbConfig) -> anyhow::Result<Self> {
+        let db = Surreal::<Client>::init();
+        db.connect::<Ws>(&db_config.endpoint).await?;
+        db.signin(Root {
+            username: db_config.username.clone(),
+            password: db_config.password.clone(),
+        })
+        .await?;
+        db.use_ns(&db_config.namespace)
+            .use_db(&db_config.database)
+            .await?;
+        Ok(Self {
+            client: Arc::new(db),
         })
     }
 
-    fn list_notifications_in_window(
+    fn map_surreal_error(err: surrealdb::Error) -> DomainError {
+        DomainError::Validation(format!("surreal query failed: {err}"))
+    }
+}
+
+impl PushSubscriptionRepository for SurrealPushSubscriptionRepository {
+    fn upsert_subscription(
         &self,
-        user_id: &str,
-        window_start_ms: i64,
-        window_end_ms: i64,
-    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<InAppNotification>>> {
-        let user_id = user_id.to_string();
-        let window_start = match Self::to_rfc3339(window_start_ms) {
-            Ok(value) => value,
-            Err(err) => return Box::pin(async move { Err(err) }),
-        };
-        let window_end = match Self::to_rfc3339(window_end_ms) {
-            Ok(value) => value,
-            Err(err) => return Box::pin(async move { Err(err) }),
-        };
+        subscription: &PushSubscription,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<PushSubscription>> {
+        let subscription = subscription.clone();
         let client = self.client.clone();
         Box::pin(async move {
-            let mut response = client
-                .query("SELECT * FROM discovery_notification \
-                     WHERE user_id = $user_id AND created_at >= $window_start AND created_at <= $window_end \
-                     ORDER BY created_at DESC, notification_id DESC")
-                .bind(("user_id", user_id))
-                .bind(("window_start", window_start))
-                .bind(("window_end", window_end))
+            let content = to_value(&subscription).map_err(|err| {
+                DomainError::Validation(format!("invalid push subscription: {err}"))
+            })?;
+            client
+                .query(format!(
+                    "UPSERT type::thing('{PUSH_SUBSCRIPTION_TABLE}', $subscription_id) CONTENT $content;"
+                ))
+                .bind(("subscription_id", subscription.subscription_id.clone()))
+                .bind(("content", content))
                 .await
+                .map_err(Self::map_surreal_error)?
+                .check()
                 .map_err(Self::map_surreal_error)?;
-            let rows: Vec<Value> = response
-                .take(0)
-                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
-            Self::map_rows(rows)
+            Ok(subscription)
         })
     }
 
-    fn mark_as_read(
+    fn list_for_user(
         &self,
         user_id: &str,
-        notification_id: &str,
-        read_at_ms: i64,
-    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<InAppNotification>> {
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<PushSubscription>>> {
         let user_id = user_id.to_string();
-        let notification_id = notification_id.to_string();
         let client = self.client.clone();
-        let read_at = match Self::to_rfc3339(read_at_ms) {
-            Ok(value) => value,
-            Err(err) => return Box::pin(async move { Err(err) }),
-        };
         Box::pin(async move {
             let mut response = client
-                .query(
-                    "SELECT * FROM discovery_notification \
-                     WHERE notification_id = $notification_id LIMIT 1",
-                )
-                .bind(("notification_id", notification_id.clone()))
-                .await
-                .map_err(Self::map_surreal_error)?;
-            let rows: Vec<Value> = response
-                .take(0)
-                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
-            let mut rows = Self::map_rows(rows)?;
-            let Some(notification) = rows.pop() else {
-                return Err(DomainError::NotFound);
-            };
-            if notification.user_id != user_id {
-                return Err(DomainError::Forbidden(
-                    "notification belongs to another user".into(),
-                ));
-            }
-            if notification.read_at_ms.is_some() {
-                return Ok(notification);
-            }
-
-            let mut response = client
-                .query(
-                    "UPDATE discovery_notification \
-                     SET read_at = $read_at \
-                     WHERE notification_id = $notification_id \
-                     RETURN AFTER *",
-                )
-                .bind(("read_at", read_at))
-                .bind(("notification_id", notification_id.clone()))
+                .query(format!(
+                    "SELECT * OMIT id FROM {PUSH_SUBSCRIPTION_TABLE} WHERE user_id = $user_id \
+                     ORDER BY created_at_ms ASC, subscription_id ASC;"
+                ))
+                .bind(("user_id", user_id))
                 .await
                 .map_err(Self::map_surreal_error)?;
             let rows: Vec<Value> = response
                 .take(0)
                 .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
-            let mut rows = Self::map_rows(rows)?;
-            rows.pop().ok_or(DomainError::NotFound)
+            rows.into_iter()
+                .map(|row| {
+                    serde_json::from_value::<PushSubscription>(row).map_err(|err| {
+                        DomainError::Validation(format!("invalid push subscription row: {err}"))
+                    })
+                })
+                .collect()
         })
     }
 
-    fn unread_count(
+    fn delete_subscription(
         &self,
         user_id: &str,
-    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<usize>> {
+        subscription_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<bool>> {
         let user_id = user_id.to_string();
+        let subscription_id = subscription_id.to_string();
         let client = self.client.clone();
         Box::pin(async move {
             let mut response = client
-                .query(
-                    "SELECT count() AS unread_count \
-                     FROM discovery_notification \
-                     WHERE user_id = $user_id AND read_at IS NONE",
-                )
+                .query(format!(
+                    "DELETE type::thing('{PUSH_SUBSCRIPTION_TABLE}', $subscription_id) \
+                     WHERE user_id = $user_id RETURN BEFORE;"
+                ))
+                .bind(("subscription_id", subscription_id))
                 .bind(("user_id", user_id))
                 .await
                 .map_err(Self::map_surreal_error)?;
             let rows: Vec<Value> = response
                 .take(0)
                 .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
-            Self::decode_count(rows, "unread_count", "unread_count")
+            Ok(!rows.is_empty())
         })
     }
 }
@@ -6628,6 +10771,8 @@ struct SurrealDiscoveryFeedRow {
     correlation_id: String,
     participant_ids: Vec<String>,
     payload: Option<serde_json::Value>,
+    #[serde(default)]
+    location: Option<GeoPoint>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -6647,6 +10792,10 @@ struct SurrealDiscoveryFeedCreateRow {
     correlation_id: String,
     participant_ids: Vec<String>,
     payload: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<GeoPoint>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    geohash: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -6668,10 +10817,16 @@ struct SurrealDiscoveryNotificationRow {
     payload: Option<serde_json::Value>,
     created_at: String,
     read_at: Option<String>,
+    #[serde(default)]
+    deliver_at: Option<String>,
     privacy_level: Option<String>,
     request_id: String,
     correlation_id: String,
     dedupe_key: String,
+    #[serde(default)]
+    group_key: Option<String>,
+    #[serde(default)]
+    in_app_hidden: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -6688,10 +10843,15 @@ struct SurrealDiscoveryNotificationCreateRow {
     payload: Option<serde_json::Value>,
     created_at: String,
     read_at: Option<String>,
+    #[serde(default)]
+    deliver_at: Option<String>,
     privacy_level: Option<String>,
     request_id: String,
     correlation_id: String,
     dedupe_key: String,
+    group_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    in_app_hidden: Option<bool>,
 }
 
 #[derive(Clone)]
@@ -9379,6 +13539,7 @@ pub struct InMemoryChatRepository {
     message_by_request: Arc<RwLock<HashMap<(String, String), String>>>,
     cursors: Arc<RwLock<HashMap<(String, String), ChatReadCursor>>>,
     events: Arc<RwLock<HashMap<(String, String), ChatDeliveryEvent>>>,
+    attachments: Arc<RwLock<HashMap<String, ChatAttachment>>>,
 }
 
 impl InMemoryChatRepository {
@@ -9431,6 +13592,39 @@ impl ChatRepositoryPort for InMemoryChatRepository {
         })
     }
 
+    fn update_thread(
+        &self,
+        thread: &ChatThread,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<ChatThread>> {
+        let thread = thread.clone();
+        let threads = self.threads.clone();
+        Box::pin(async move {
+            let mut threads = threads.write().await;
+            if !threads.contains_key(&thread.thread_id) {
+                return Err(DomainError::NotFound);
+            }
+            threads.insert(thread.thread_id.clone(), thread.clone());
+            Ok(thread)
+        })
+    }
+
+    fn list_threads_with_retention(
+        &self,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<ChatThread>>> {
+        let threads = self.threads.clone();
+        Box::pin(async move {
+            let mut output: Vec<_> = threads
+                .read()
+                .await
+                .values()
+                .filter(|thread| thread.retention != ChatRetentionPolicy::Forever)
+                .cloned()
+                .collect();
+            output.sort_by(|a, b| a.thread_id.cmp(&b.thread_id));
+            Ok(output)
+        })
+    }
+
     fn list_threads_by_scope(
         &self,
         query: &ChatThreadQuery,
@@ -9501,6 +13695,7 @@ impl ChatRepositoryPort for InMemoryChatRepository {
                 output.push(ChatThreadWithMembers {
                     thread,
                     member_count,
+                    unread_count: 0,
                 });
             }
             output.sort_by(|a, b| b.thread.created_at_ms.cmp(&a.thread.created_at_ms));
@@ -9666,6 +13861,53 @@ impl ChatRepositoryPort for InMemoryChatRepository {
         })
     }
 
+    fn list_messages_created_before(
+        &self,
+        thread_id: &str,
+        cutoff_ms: i64,
+        limit: usize,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<ChatMessage>>> {
+        let thread_id = thread_id.to_string();
+        let messages = self.messages.clone();
+        Box::pin(async move {
+            let mut messages: Vec<_> = messages
+                .read()
+                .await
+                .values()
+                .filter(|message| {
+                    message.thread_id == thread_id
+                        && message.deleted_at_ms.is_none()
+                        && message.created_at_ms < cutoff_ms
+                })
+                .cloned()
+                .collect();
+            messages.sort_by(|a, b| {
+                a.created_at_ms
+                    .cmp(&b.created_at_ms)
+                    .then_with(|| a.message_id.cmp(&b.message_id))
+            });
+            messages.truncate(limit);
+            Ok(messages)
+        })
+    }
+
+    fn update_message(
+        &self,
+        message: &ChatMessage,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<ChatMessage>> {
+        let message = message.clone();
+        let messages = self.messages.clone();
+        Box::pin(async move {
+            let mut messages = messages.write().await;
+            let key = (message.thread_id.clone(), message.message_id.clone());
+            if !messages.contains_key(&key) {
+                return Err(DomainError::NotFound);
+            }
+            messages.insert(key, message.clone());
+            Ok(message)
+        })
+    }
+
     fn set_read_cursor(
         &self,
         cursor: &ChatReadCursor,
@@ -9695,6 +13937,50 @@ impl ChatRepositoryPort for InMemoryChatRepository {
         })
     }
 
+    fn list_read_cursors(
+        &self,
+        thread_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<ChatReadCursor>>> {
+        let thread_id = thread_id.to_string();
+        let cursors = self.cursors.clone();
+        Box::pin(async move {
+            Ok(cursors
+                .read()
+                .await
+                .values()
+                .filter(|cursor| cursor.thread_id == thread_id)
+                .cloned()
+                .collect())
+        })
+    }
+
+    fn count_unread_messages(
+        &self,
+        thread_id: &str,
+        user_id: &str,
+        after: Option<(i64, String)>,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<usize>> {
+        let thread_id = thread_id.to_string();
+        let user_id = user_id.to_string();
+        let messages = self.messages.clone();
+        Box::pin(async move {
+            Ok(messages
+                .read()
+                .await
+                .values()
+                .filter(|message| {
+                    message.thread_id == thread_id
+                        && message.author_id != user_id
+                        && message.deleted_at_ms.is_none()
+                        && after.as_ref().is_none_or(|(created_at_ms, message_id)| {
+                            (message.created_at_ms, &message.message_id)
+                                > (*created_at_ms, message_id)
+                        })
+                })
+                .count())
+        })
+    }
+
     fn create_delivery_event(
         &self,
         event: &ChatDeliveryEvent,
@@ -9724,8 +14010,94 @@ impl ChatRepositoryPort for InMemoryChatRepository {
             Ok(events.get(&key).cloned())
         })
     }
+
+    fn create_attachment(
+        &self,
+        attachment: &ChatAttachment,
+        quota: &ChatAttachmentQuota,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<ChatAttachment>> {
+        let attachment = attachment.clone();
+        let quota = quota.clone();
+        let attachments = self.attachments.clone();
+        Box::pin(async move {
+            let mut attachments = attachments.write().await;
+            if attachments.contains_key(&attachment.attachment_id) {
+                return Err(DomainError::Conflict);
+            }
+            let user_usage = attachments
+                .values()
+                .filter(|stored| stored.uploaded_by == attachment.uploaded_by)
+                .map(ChatAttachment::stored_bytes)
+                .sum();
+            let thread_usage = attachment.thread_id.as_ref().map(|thread_id| {
+                attachments
+                    .values()
+                    .filter(|stored| stored.thread_id.as_ref() == Some(thread_id))
+                    .map(ChatAttachment::stored_bytes)
+                    .sum()
+            });
+            quota.check(user_usage, thread_usage, attachment.stored_bytes())?;
+            attachments.insert(attachment.attachment_id.clone(), attachment.clone());
+            Ok(attachment)
+        })
+    }
+
+    fn get_attachment(
+        &self,
+        attachment_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<ChatAttachment>>> {
+        let attachment_id = attachment_id.to_string();
+        let attachments = self.attachments.clone();
+        Box::pin(async move {
+            let attachments = attachments.read().await;
+            Ok(attachments.get(&attachment_id).cloned())
+        })
+    }
+
+    fn delete_attachment(
+        &self,
+        attachment_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<()>> {
+        let attachment_id = attachment_id.to_string();
+        let attachments = self.attachments.clone();
+        Box::pin(async move {
+            attachments.write().await.remove(&attachment_id);
+            Ok(())
+        })
+    }
+
+    fn sum_attachment_bytes(
+        &self,
+        query: &ChatAttachmentUsageQuery,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<u64>> {
+        let query = query.clone();
+        let attachments = self.attachments.clone();
+        Box::pin(async move {
+            let attachments = attachments.read().await;
+            Ok(attachments
+                .values()
+                .filter(|attachment| {
+                    query
+                        .uploaded_by
+                        .as_ref()
+                        .is_none_or(|user_id| &attachment.uploaded_by == user_id)
+                })
+                .filter(|attachment| {
+                    query
+                        .thread_id
+                        .as_ref()
+                        .is_none_or(|thread_id| attachment.thread_id.as_ref() == Some(thread_id))
+                })
+                .map(ChatAttachment::stored_bytes)
+                .sum())
+        })
+    }
 }
 
+/// Leading text of the quota errors thrown inside `create_attachment`, used to
+/// surface them as validation errors rather than generic query failures.
+const CHAT_ATTACHMENT_QUOTA_ERROR_PREFIX: &str = "attachment storage quota of";
+
 #[derive(Clone)]
 pub struct SurrealChatRepository {
     client: Arc<Surreal<Client>>,
@@ -9784,6 +14156,7 @@ impl SurrealChatRepository {
             scope_id: row.scope_id,
             created_by: row.created_by,
             privacy_level: row.privacy_level,
+            retention: row.retention,
             created_at_ms: Self::parse_datetime(&row.created_at)?,
             updated_at_ms: Self::parse_datetime(&row.updated_at)?,
         })
@@ -9888,6 +14261,34 @@ impl SurrealChatRepository {
             .collect()
     }
 
+    fn decode_attachment_rows(rows: Vec<Value>) -> DomainResult<Vec<ChatAttachment>> {
+        rows.into_iter()
+            .map(|row| {
+                serde_json::from_value::<SurrealChatAttachmentRow>(row)
+                    .map_err(|err| {
+                        DomainError::Validation(format!("invalid chat attachment row: {err}"))
+                    })
+                    .and_then(Self::map_chat_attachment_row)
+            })
+            .collect()
+    }
+
+    fn map_chat_attachment_row(row: SurrealChatAttachmentRow) -> DomainResult<ChatAttachment> {
+        Ok(ChatAttachment {
+            attachment_id: row.attachment_id,
+            uploaded_by: row.uploaded_by,
+            thread_id: row.thread_id,
+            file_name: row.file_name,
+            mime_type: row.mime_type,
+            media_type: row.media_type,
+            size_bytes: row.size_bytes,
+            width: row.width,
+            height: row.height,
+            thumbnail_size_bytes: row.thumbnail_size_bytes,
+            created_at_ms: Self::parse_datetime(&row.created_at)?,
+        })
+    }
+
     fn map_chat_delivery_event_row(
         row: SurrealChatDeliveryEventRow,
     ) -> DomainResult<ChatDeliveryEvent> {
@@ -9909,6 +14310,8 @@ struct SurrealChatThreadRow {
     scope_id: String,
     created_by: String,
     privacy_level: String,
+    #[serde(default)]
+    retention: ChatRetentionPolicy,
     created_at: String,
     updated_at: String,
 }
@@ -9988,6 +14391,21 @@ struct SurrealChatDeliveryEventRow {
     correlation_id: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct SurrealChatAttachmentRow {
+    attachment_id: String,
+    uploaded_by: String,
+    thread_id: Option<String>,
+    file_name: String,
+    mime_type: String,
+    media_type: String,
+    size_bytes: u64,
+    width: Option<u32>,
+    height: Option<u32>,
+    thumbnail_size_bytes: Option<u64>,
+    created_at: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct SurrealChatDeliveryEventCreateRow {
     event_id: String,
@@ -10040,6 +14458,7 @@ impl ChatRepositoryPort for SurrealChatRepository {
                         scope_id: $scope_id,\n\
                         created_by: $created_by,\n\
                         privacy_level: $privacy_level,\n\
+                        retention: $retention,\n\
                         created_at: <datetime>$created_at,\n\
                         updated_at: <datetime>$updated_at\n\
                     };",
@@ -10048,6 +14467,7 @@ impl ChatRepositoryPort for SurrealChatRepository {
                 .bind(("scope_id", scope_id))
                 .bind(("created_by", created_by))
                 .bind(("privacy_level", privacy_level))
+                .bind(("retention", thread_value.retention.as_str().to_string()))
                 .bind(("created_at", created_at))
                 .bind(("updated_at", updated_at))
                 .await
@@ -10071,6 +14491,7 @@ impl ChatRepositoryPort for SurrealChatRepository {
                         scope_id,\n\
                         created_by,\n\
                         privacy_level,\n\
+                        retention ?? 'forever' AS retention,\n\
                         type::string(created_at) AS created_at,\n\
                         type::string(updated_at) AS updated_at\n\
                      FROM chat_thread\n\
@@ -10087,6 +14508,70 @@ impl ChatRepositoryPort for SurrealChatRepository {
         })
     }
 
+    fn update_thread(
+        &self,
+        thread: &ChatThread,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<ChatThread>> {
+        let updated_at = match Self::to_rfc3339(thread.updated_at_ms) {
+            Ok(value) => value,
+            Err(err) => return Box::pin(async move { Err(err) }),
+        };
+        let client = self.client.clone();
+        let thread_value = thread.clone();
+        Box::pin(async move {
+            let mut response = client
+                .query(
+                    "UPDATE chat_thread SET\n\
+                        privacy_level = $privacy_level,\n\
+                        retention = $retention,\n\
+                        updated_at = <datetime>$updated_at\n\
+                     WHERE thread_id = $thread_id\n\
+                     RETURN thread_id;",
+                )
+                .bind(("thread_id", thread_value.thread_id.clone()))
+                .bind(("privacy_level", thread_value.privacy_level.clone()))
+                .bind(("retention", thread_value.retention.as_str().to_string()))
+                .bind(("updated_at", updated_at))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            if rows.is_empty() {
+                return Err(DomainError::NotFound);
+            }
+            Ok(thread_value)
+        })
+    }
+
+    fn list_threads_with_retention(
+        &self,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<ChatThread>>> {
+        let client = self.client.clone();
+        Box::pin(async move {
+            let mut response = client
+                .query(
+                    "SELECT\n\
+                        thread_id,\n\
+                        scope_id,\n\
+                        created_by,\n\
+                        privacy_level,\n\
+                        retention ?? 'forever' AS retention,\n\
+                        type::string(created_at) AS created_at,\n\
+                        type::string(updated_at) AS updated_at\n\
+                     FROM chat_thread\n\
+                     WHERE retention IS NOT NONE AND retention != 'forever'\n\
+                     ORDER BY thread_id ASC",
+                )
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            Self::decode_thread_row(rows)
+        })
+    }
+
     fn list_threads_by_scope(
         &self,
         query: &ChatThreadQuery,
@@ -10112,6 +14597,7 @@ impl ChatRepositoryPort for SurrealChatRepository {
                     scope_id,\n\
                     created_by,\n\
                     privacy_level,\n\
+                    retention ?? 'forever' AS retention,\n\
                     type::string(created_at) AS created_at,\n\
                     type::string(updated_at) AS updated_at\n\
                  FROM chat_thread",
@@ -10151,6 +14637,7 @@ impl ChatRepositoryPort for SurrealChatRepository {
                         scope_id,\n\
                         created_by,\n\
                         privacy_level,\n\
+                        retention ?? 'forever' AS retention,\n\
                         type::string(created_at) AS created_at,\n\
                         type::string(updated_at) AS updated_at\n\
                      FROM chat_thread\n\
@@ -10193,6 +14680,7 @@ impl ChatRepositoryPort for SurrealChatRepository {
                 result.push(ChatThreadWithMembers {
                     thread,
                     member_count,
+                    unread_count: 0,
                 });
             }
             Ok(result)
@@ -10636,6 +15124,94 @@ impl ChatRepositoryPort for SurrealChatRepository {
         })
     }
 
+    fn list_messages_created_before(
+        &self,
+        thread_id: &str,
+        cutoff_ms: i64,
+        limit: usize,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<ChatMessage>>> {
+        let cutoff = match Self::to_rfc3339(cutoff_ms) {
+            Ok(value) => value,
+            Err(err) => return Box::pin(async move { Err(err) }),
+        };
+        let thread_id = thread_id.to_string();
+        let client = self.client.clone();
+        Box::pin(async move {
+            let mut response = client
+                .query(
+                    "SELECT\n\
+                        thread_id,\n\
+                        message_id,\n\
+                        author_id,\n\
+                        body,\n\
+                        attachments,\n\
+                        request_id,\n\
+                        correlation_id,\n\
+                        type::string(created_at) AS created_at,\n\
+                        IF edited_at IS NONE THEN NONE ELSE type::string(edited_at) END AS edited_at,\n\
+                        IF deleted_at IS NONE THEN NONE ELSE type::string(deleted_at) END AS deleted_at\n\
+                     FROM chat_message\n\
+                     WHERE thread_id = $thread_id\n\
+                       AND deleted_at IS NONE\n\
+                       AND created_at < <datetime>$cutoff\n\
+                     ORDER BY created_at ASC, message_id ASC\n\
+                     LIMIT $limit",
+                )
+                .bind(("thread_id", thread_id))
+                .bind(("cutoff", cutoff))
+                .bind(("limit", limit as i64))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            Self::decode_message_row(rows)
+        })
+    }
+
+    fn update_message(
+        &self,
+        message: &ChatMessage,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<ChatMessage>> {
+        let edited_at = match message.edited_at_ms.map(Self::to_rfc3339).transpose() {
+            Ok(value) => value,
+            Err(err) => return Box::pin(async move { Err(err) }),
+        };
+        let deleted_at = match message.deleted_at_ms.map(Self::to_rfc3339).transpose() {
+            Ok(value) => value,
+            Err(err) => return Box::pin(async move { Err(err) }),
+        };
+        let client = self.client.clone();
+        let message_value = message.clone();
+        Box::pin(async move {
+            let mut response = client
+                .query(
+                    "UPDATE chat_message SET\n\
+                        body = $body,\n\
+                        attachments = $attachments,\n\
+                        edited_at = IF $edited_at IS NONE THEN NONE ELSE <datetime>$edited_at END,\n\
+                        deleted_at = IF $deleted_at IS NONE THEN NONE ELSE <datetime>$deleted_at END\n\
+                     WHERE thread_id = $thread_id AND message_id = $message_id\n\
+                     RETURN message_id;",
+                )
+                .bind(("thread_id", message_value.thread_id.clone()))
+                .bind(("message_id", message_value.message_id.clone()))
+                .bind(("body", message_value.body.clone()))
+                .bind(("attachments", message_value.attachments.clone()))
+                .bind(("edited_at", edited_at))
+                .bind(("deleted_at", deleted_at))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            if rows.is_empty() {
+                return Err(DomainError::NotFound);
+            }
+            Ok(message_value)
+        })
+    }
+
     fn set_read_cursor(
         &self,
         cursor: &ChatReadCursor,
@@ -10717,6 +15293,85 @@ impl ChatRepositoryPort for SurrealChatRepository {
         })
     }
 
+    fn list_read_cursors(
+        &self,
+        thread_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Vec<ChatReadCursor>>> {
+        let thread_id = thread_id.to_string();
+        let client = self.client.clone();
+        Box::pin(async move {
+            let mut response = client
+                .query(
+                    "SELECT\n\
+                        thread_id,\n\
+                        user_id,\n\
+                        last_read_message_id,\n\
+                        type::string(last_read_at) AS last_read_at\n\
+                     FROM chat_read_cursor\n\
+                     WHERE thread_id = $thread_id",
+                )
+                .bind(("thread_id", thread_id))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            Self::decode_read_cursor_row(rows)
+        })
+    }
+
+    fn count_unread_messages(
+        &self,
+        thread_id: &str,
+        user_id: &str,
+        after: Option<(i64, String)>,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<usize>> {
+        let thread_id = thread_id.to_string();
+        let user_id = user_id.to_string();
+        let after = match after
+            .map(|(created_at_ms, message_id)| {
+                Self::to_rfc3339(created_at_ms).map(|threshold| (threshold, message_id))
+            })
+            .transpose()
+        {
+            Ok(after) => after,
+            Err(err) => return Box::pin(async move { Err(err) }),
+        };
+        let client = self.client.clone();
+        Box::pin(async move {
+            let mut statement = String::from(
+                "SELECT count() AS count FROM chat_message\n\
+                 WHERE thread_id = $thread_id\n\
+                    AND author_id != $user_id\n\
+                    AND deleted_at IS NONE",
+            );
+            if after.is_some() {
+                statement.push_str(
+                    " AND (created_at > <datetime>$threshold OR (created_at = <datetime>$threshold AND message_id > $since_message_id))",
+                );
+            }
+            statement.push_str(" GROUP ALL");
+            let (threshold, since_message_id) = after.unwrap_or_default();
+            let mut response = client
+                .query(statement)
+                .bind(("thread_id", thread_id))
+                .bind(("user_id", user_id))
+                .bind(("threshold", threshold))
+                .bind(("since_message_id", since_message_id))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            Ok(rows
+                .first()
+                .and_then(|row| row.get("count"))
+                .and_then(|value| value.as_u64())
+                .and_then(|count| usize::try_from(count).ok())
+                .unwrap_or_default())
+        })
+    }
+
     fn create_delivery_event(
         &self,
         event: &ChatDeliveryEvent,
@@ -10797,4 +15452,182 @@ impl ChatRepositoryPort for SurrealChatRepository {
             Ok(Self::decode_delivery_event_row(rows)?.into_iter().next())
         })
     }
+
+    fn create_attachment(
+        &self,
+        attachment: &ChatAttachment,
+        quota: &ChatAttachmentQuota,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<ChatAttachment>> {
+        let created_at = match Self::to_rfc3339(attachment.created_at_ms) {
+            Ok(created_at) => created_at,
+            Err(err) => return Box::pin(async move { Err(err) }),
+        };
+        let client = self.client.clone();
+        let attachment = attachment.clone();
+        let quota = quota.clone();
+        Box::pin(async move {
+            // Sums over a range do not conflict on their own, so each upload also
+            // writes the uploader's and thread's lock rows; two uploads charging the
+            // same quota then collide on commit instead of both passing the check.
+            let response = client
+                .query(
+                    "BEGIN TRANSACTION;\n\
+                     UPSERT type::record('chat_attachment_quota_lock', ['user', $uploaded_by]) \
+                       SET touched_at = time::now();\n\
+                     IF $thread_id != NONE {\n\
+                       UPSERT type::record('chat_attachment_quota_lock', ['thread', $thread_id]) \
+                         SET touched_at = time::now();\n\
+                     };\n\
+                     LET $user_usage = (SELECT math::sum(size_bytes + (thumbnail_size_bytes ?? 0)) AS total \
+                       FROM chat_attachment WHERE uploaded_by = $uploaded_by GROUP ALL)[0].total ?? 0;\n\
+                     IF $user_usage + $stored_bytes > $per_user_bytes {\n\
+                       THROW $user_quota_error;\n\
+                     };\n\
+                     IF $thread_id != NONE {\n\
+                       LET $thread_usage = (SELECT math::sum(size_bytes + (thumbnail_size_bytes ?? 0)) AS total \
+                         FROM chat_attachment WHERE thread_id = $thread_id GROUP ALL)[0].total ?? 0;\n\
+                       IF $thread_usage + $stored_bytes > $per_thread_bytes {\n\
+                         THROW $thread_quota_error;\n\
+                       };\n\
+                     };\n\
+                     CREATE chat_attachment CONTENT {\n\
+                        attachment_id: $attachment_id,\n\
+                        uploaded_by: $uploaded_by,\n\
+                        thread_id: $thread_id,\n\
+                        file_name: $file_name,\n\
+                        mime_type: $mime_type,\n\
+                        media_type: $media_type,\n\
+                        size_bytes: $size_bytes,\n\
+                        width: $width,\n\
+                        height: $height,\n\
+                        thumbnail_size_bytes: $thumbnail_size_bytes,\n\
+                        created_at: <datetime>$created_at\n\
+                     };\n\
+                     COMMIT TRANSACTION;",
+                )
+                .bind(("attachment_id", attachment.attachment_id.clone()))
+                .bind(("uploaded_by", attachment.uploaded_by.clone()))
+                .bind(("thread_id", attachment.thread_id.clone()))
+                .bind(("file_name", attachment.file_name.clone()))
+                .bind(("mime_type", attachment.mime_type.clone()))
+                .bind(("media_type", attachment.media_type.clone()))
+                .bind(("size_bytes", attachment.size_bytes as i64))
+                .bind(("width", attachment.width.map(i64::from)))
+                .bind(("height", attachment.height.map(i64::from)))
+                .bind((
+                    "thumbnail_size_bytes",
+                    attachment.thumbnail_size_bytes.map(|bytes| bytes as i64),
+                ))
+                .bind(("created_at", created_at))
+                .bind((
+                    "stored_bytes",
+                    i64::try_from(attachment.stored_bytes()).unwrap_or(i64::MAX),
+                ))
+                // Quotas above `i64::MAX` (e.g. `u64::MAX` for unlimited) clamp
+                // instead of wrapping negative and rejecting every upload.
+                .bind((
+                    "per_user_bytes",
+                    i64::try_from(quota.per_user_bytes).unwrap_or(i64::MAX),
+                ))
+                .bind((
+                    "per_thread_bytes",
+                    i64::try_from(quota.per_thread_bytes).unwrap_or(i64::MAX),
+                ))
+                .bind(("user_quota_error", quota.user_exceeded_message()))
+                .bind(("thread_quota_error", quota.thread_exceeded_message()))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            response.check().map_err(|err| {
+                let message = err.to_string();
+                match message.find(CHAT_ATTACHMENT_QUOTA_ERROR_PREFIX) {
+                    Some(start) => DomainError::Validation(message[start..].to_string()),
+                    None => Self::map_surreal_error(err),
+                }
+            })?;
+            Ok(attachment)
+        })
+    }
+
+    fn get_attachment(
+        &self,
+        attachment_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<Option<ChatAttachment>>> {
+        let attachment_id = attachment_id.to_string();
+        let client = self.client.clone();
+        Box::pin(async move {
+            let mut response = client
+                .query(
+                    "SELECT\n\
+                        attachment_id,\n\
+                        uploaded_by,\n\
+                        thread_id,\n\
+                        file_name,\n\
+                        mime_type,\n\
+                        media_type,\n\
+                        size_bytes,\n\
+                        width,\n\
+                        height,\n\
+                        thumbnail_size_bytes,\n\
+                        type::string(created_at) AS created_at\n\
+                     FROM chat_attachment\n\
+                     WHERE attachment_id = $attachment_id\n\
+                     LIMIT 1",
+                )
+                .bind(("attachment_id", attachment_id))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            Ok(Self::decode_attachment_rows(rows)?.into_iter().next())
+        })
+    }
+
+    fn delete_attachment(
+        &self,
+        attachment_id: &str,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<()>> {
+        let attachment_id = attachment_id.to_string();
+        let client = self.client.clone();
+        Box::pin(async move {
+            let response = client
+                .query("DELETE chat_attachment WHERE attachment_id = $attachment_id;")
+                .bind(("attachment_id", attachment_id))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            response.check().map_err(Self::map_surreal_error)?;
+            Ok(())
+        })
+    }
+
+    fn sum_attachment_bytes(
+        &self,
+        query: &ChatAttachmentUsageQuery,
+    ) -> gotong_domain::ports::BoxFuture<'_, DomainResult<u64>> {
+        let query = query.clone();
+        let client = self.client.clone();
+        Box::pin(async move {
+            let mut response = client
+                .query(
+                    "SELECT\n\
+                        math::sum(size_bytes + (thumbnail_size_bytes ?? 0)) AS total\n\
+                     FROM chat_attachment\n\
+                     WHERE ($uploaded_by IS NONE OR uploaded_by = $uploaded_by)\n\
+                       AND ($thread_id IS NONE OR thread_id = $thread_id)\n\
+                     GROUP ALL",
+                )
+                .bind(("uploaded_by", query.uploaded_by))
+                .bind(("thread_id", query.thread_id))
+                .await
+                .map_err(Self::map_surreal_error)?;
+            let rows: Vec<Value> = response
+                .take(0)
+                .map_err(|err| DomainError::Validation(format!("invalid query result: {err}")))?;
+            Ok(rows
+                .first()
+                .and_then(|row| row.get("total"))
+                .and_then(Value::as_u64)
+                .unwrap_or_default())
+        })
+    }
 }
@@ -3,11 +3,14 @@ mod impls;
 pub mod adaptive_path;
 pub mod chat;
 pub mod contribution;
+pub mod digest;
 pub mod discovery;
 pub mod evidence;
 pub mod group;
 pub mod moderation;
+pub mod notification_preferences;
 pub mod ontology;
+pub mod push;
 pub mod siaga;
 pub mod vault;
 pub mod vouch;
@@ -16,11 +19,14 @@ pub mod webhook;
 pub use adaptive_path::*;
 pub use chat::*;
 pub use contribution::*;
+pub use digest::*;
 pub use discovery::*;
 pub use evidence::*;
 pub use group::*;
 pub use moderation::*;
+pub use notification_preferences::*;
 pub use ontology::*;
+pub use push::*;
 pub use siaga::*;
 pub use vault::*;
 pub use vouch::*;
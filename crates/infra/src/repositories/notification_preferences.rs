@@ -0,0 +1,3 @@
+pub use super::impls::{
+    InMemoryNotificationPreferenceRepository, SurrealNotificationPreferenceRepository,
+};
@@ -0,0 +1 @@
+pub use super::impls::{InMemoryPushSubscriptionRepository, SurrealPushSubscriptionRepository};
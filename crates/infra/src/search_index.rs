@@ -0,0 +1,1078 @@
+use std::collections::BTreeSet;
+use std::ops::Bound;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use gotong_domain::DomainResult;
+use gotong_domain::discovery::{
+    FEED_SOURCE_VAULT, FeedItem, is_hidden_feed_item, is_open_privacy_level,
+};
+use gotong_domain::error::DomainError;
+use gotong_domain::geo::geohash_prefixes;
+use gotong_domain::jobs::{FeedSearchIndexSyncPayload, JobDefaults, new_job};
+use gotong_domain::ports::BoxFuture;
+use gotong_domain::ports::discovery::{
+    FeedRepository, FeedRepositoryQuery, FeedRepositorySearchQuery, FeedSearchHit, FeedSearchIndex,
+    FeedSearchIndexQuery, FeedSearchIndexQueue,
+};
+use gotong_domain::ports::jobs::{JobQueue, JobType};
+use gotong_domain::util::uuid_v7_without_dashes;
+use tantivy::collector::TopDocs;
+use tantivy::directory::MmapDirectory;
+use tantivy::query::{
+    BooleanQuery, BoostQuery, FuzzyTermQuery, Occur, PhraseQuery, Query, RangeQuery, TermQuery,
+};
+use tantivy::schema::{
+    Field, INDEXED, IndexRecordOption, STORED, STRING, Schema, TantivyDocument, TextFieldIndexing,
+    TextOptions, Value,
+};
+use tantivy::tokenizer::{
+    AsciiFoldingFilter, LowerCaser, RemoveLongFilter, SimpleTokenizer, StopWordFilter,
+    TextAnalyzer, Token, TokenFilter, TokenStream, Tokenizer,
+};
+use tantivy::{Index, IndexReader, IndexSettings, IndexWriter, ReloadPolicy, TantivyError, Term};
+use tokio::sync::{mpsc, oneshot};
+
+const ANALYZER_NAME: &str = "indonesian";
+const WRITER_MEMORY_BYTES: usize = 15_000_000;
+const MAX_QUERY_TERMS: usize = 12;
+const EXACT_TERM_BOOST: f32 = 2.0;
+const TITLE_BOOST: f32 = 2.5;
+const ACTOR_BOOST: f32 = 0.5;
+/// Most feed ids folded into one indexer commit.
+const INDEXER_BATCH: usize = 256;
+/// How long the indexer keeps collecting ids after the first one arrives.
+const INDEXER_LINGER: Duration = Duration::from_millis(200);
+
+/// Function words dropped before stemming; they carry no search signal and
+/// would otherwise dominate BM25 document frequencies.
+const INDONESIAN_STOPWORDS: &[&str] = &[
+    "ada", "adalah", "agar", "akan", "aku", "anda", "antara", "apa", "atau", "bagi", "bahwa",
+    "banyak", "belum", "beberapa", "bisa", "boleh", "dalam", "dan", "dari", "dengan", "di", "dia",
+    "hanya", "harus", "ia", "ini", "itu", "jadi", "jika", "juga", "kalau", "kami", "kamu",
+    "karena", "ke", "kepada", "ketika", "kita", "lagi", "lain", "lebih", "maka", "masih", "mereka",
+    "namun", "oleh", "pada", "para", "saat", "saja", "sambil", "sangat", "saya", "sebagai",
+    "sebelum", "sedang", "sehingga", "sejak", "selain", "serta", "setelah", "sudah", "supaya",
+    "tanpa", "telah", "tentang", "tersebut", "tetapi", "tidak", "untuk", "yaitu", "yang",
+];
+
+const MIN_STEM_CHARS: usize = 4;
+
+/// Dictionary-less stemmer following the Nazief–Adriani rule order used by
+/// Sastrawi: inflectional particle, possessive pronoun, derivational suffix,
+/// then up to two derivational prefixes with nasal recoding. Without a root
+/// dictionary every step only fires when at least `MIN_STEM_CHARS` remain, so
+/// short roots such as `makan` are left alone.
+pub fn stem_indonesian(word: &str) -> String {
+    let mut word = word.to_string();
+    if word.chars().count() <= MIN_STEM_CHARS || !word.chars().all(|c| c.is_ascii_alphabetic()) {
+        return word;
+    }
+    for suffixes in [
+        &["lah", "kah", "tah", "pun"][..],
+        &["nya", "ku", "mu"][..],
+        &["kan", "an", "i"][..],
+    ] {
+        if let Some(stripped) = strip_suffix(&word, suffixes) {
+            word = stripped;
+        }
+    }
+    for _ in 0..2 {
+        match strip_prefix(&word) {
+            Some(stripped) => word = stripped,
+            None => break,
+        }
+    }
+    word
+}
+
+fn strip_suffix(word: &str, suffixes: &[&str]) -> Option<String> {
+    suffixes.iter().find_map(|suffix| {
+        word.strip_suffix(suffix)
+            .filter(|stem| stem.len() >= MIN_STEM_CHARS)
+            .map(str::to_string)
+    })
+}
+
+fn strip_prefix(word: &str) -> Option<String> {
+    let starts_vowel = |rest: &str| rest.starts_with(['a', 'e', 'i', 'o', 'u']);
+    let candidate = if let Some(rest) = word
+        .strip_prefix("meny")
+        .or_else(|| word.strip_prefix("peny"))
+    {
+        format!("s{rest}")
+    } else if let Some(rest) = word
+        .strip_prefix("meng")
+        .or_else(|| word.strip_prefix("peng"))
+    {
+        rest.to_string()
+    } else if let Some(rest) = word
+        .strip_prefix("mem")
+        .or_else(|| word.strip_prefix("pem"))
+    {
+        if starts_vowel(rest) {
+            format!("p{rest}")
+        } else {
+            rest.to_string()
+        }
+    } else if let Some(rest) = word
+        .strip_prefix("men")
+        .or_else(|| word.strip_prefix("pen"))
+    {
+        if starts_vowel(rest) {
+            format!("t{rest}")
+        } else {
+            rest.to_string()
+        }
+    } else if let Some(rest) = ["ber", "ter", "per"]
+        .iter()
+        .find_map(|prefix| word.strip_prefix(prefix))
+    {
+        rest.to_string()
+    } else if let Some(rest) = ["me", "pe", "be", "te"]
+        .iter()
+        .find_map(|prefix| word.strip_prefix(prefix))
+        .filter(|rest| rest.starts_with(['l', 'r', 'w', 'y', 'k']))
+    {
+        rest.to_string()
+    } else if let Some(rest) = ["di", "ke", "se"]
+        .iter()
+        .find_map(|prefix| word.strip_prefix(prefix))
+    {
+        rest.to_string()
+    } else {
+        return None;
+    };
+    (candidate.len() >= MIN_STEM_CHARS).then_some(candidate)
+}
+
+#[derive(Clone)]
+struct IndonesianStemmer;
+
+impl TokenFilter for IndonesianStemmer {
+    type Tokenizer<T: Tokenizer> = IndonesianStemmerFilter<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> Self::Tokenizer<T> {
+        IndonesianStemmerFilter { inner: tokenizer }
+    }
+}
+
+#[derive(Clone)]
+struct IndonesianStemmerFilter<T> {
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for IndonesianStemmerFilter<T> {
+    type TokenStream<'a> = IndonesianStemmerStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        IndonesianStemmerStream {
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+struct IndonesianStemmerStream<T> {
+    tail: T,
+}
+
+impl<T: TokenStream> TokenStream for IndonesianStemmerStream<T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+        let stemmed = stem_indonesian(&self.tail.token().text);
+        self.tail.token_mut().text = stemmed;
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+pub fn indonesian_analyzer() -> TextAnalyzer {
+    TextAnalyzer::builder(SimpleTokenizer::default())
+        .filter(RemoveLongFilter::limit(40))
+        .filter(LowerCaser)
+        .filter(AsciiFoldingFilter)
+        .filter(StopWordFilter::remove(
+            INDONESIAN_STOPWORDS.iter().map(|word| word.to_string()),
+        ))
+        .filter(IndonesianStemmer)
+        .build()
+}
+
+#[derive(Clone, Copy)]
+struct FeedIndexFields {
+    feed_id: Field,
+    title: Field,
+    summary: Field,
+    actor_username: Field,
+    actor_id: Field,
+    participant_ids: Field,
+    source_type: Field,
+    scope_id: Field,
+    privacy_level: Field,
+    open: Field,
+    hidden: Field,
+    occurred_at_ms: Field,
+    geohash: Field,
+}
+
+fn feed_index_schema() -> (Schema, FeedIndexFields) {
+    let text = TextOptions::default().set_indexing_options(
+        TextFieldIndexing::default()
+            .set_tokenizer(ANALYZER_NAME)
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+    );
+    let mut builder = Schema::builder();
+    let fields = FeedIndexFields {
+        feed_id: builder.add_text_field("feed_id", STRING | STORED),
+        title: builder.add_text_field("title", text.clone()),
+        summary: builder.add_text_field("summary", text.clone()),
+        actor_username: builder.add_text_field("actor_username", text),
+        actor_id: builder.add_text_field("actor_id", STRING),
+        participant_ids: builder.add_text_field("participant_ids", STRING),
+        source_type: builder.add_text_field("source_type", STRING),
+        scope_id: builder.add_text_field("scope_id", STRING),
+        privacy_level: builder.add_text_field("privacy_level", STRING),
+        open: builder.add_u64_field("open", INDEXED),
+        hidden: builder.add_u64_field("hidden", INDEXED),
+        occurred_at_ms: builder.add_i64_field("occurred_at_ms", INDEXED),
+        geohash: builder.add_text_field("geohash", STRING),
+    };
+    (builder.build(), fields)
+}
+
+fn index_error(context: &str, err: impl std::fmt::Display) -> DomainError {
+    tracing::error!(error = %err, "{context}");
+    DomainError::Internal(format!("{context}: {err}"))
+}
+
+/// Tantivy-backed full-text index over discovery feed items.
+///
+/// Feed writes reach the index only through [`FeedSearchIndexer`], one per
+/// index directory. Its writer is opened per batch rather than held, so the
+/// worker's `feed-search-index-rebuild` command can take the directory lock
+/// while the worker runs; readers pick up each commit on their own.
+#[derive(Clone)]
+pub struct TantivyFeedSearchIndex {
+    index: Index,
+    reader: IndexReader,
+    fields: FeedIndexFields,
+    analyzer: TextAnalyzer,
+    write_lock: Arc<Mutex<()>>,
+}
+
+impl TantivyFeedSearchIndex {
+    /// Opens (or creates) the index under `dir`; an empty path keeps it in memory.
+    /// An index written with an older schema is recreated empty and needs a
+    /// rebuild.
+    pub fn open(dir: &str) -> anyhow::Result<Self> {
+        let (schema, fields) = feed_index_schema();
+        let dir = dir.trim();
+        let index = if dir.is_empty() {
+            Index::create_in_ram(schema)
+        } else {
+            std::fs::create_dir_all(dir)?;
+            match Index::open_or_create(MmapDirectory::open(Path::new(dir))?, schema.clone()) {
+                Ok(index) => index,
+                Err(TantivyError::SchemaError(message)) => {
+                    tracing::warn!(
+                        index_dir = dir,
+                        error = %message,
+                        "feed search index schema changed; recreating it empty, run feed-search-index-rebuild"
+                    );
+                    Index::create(
+                        MmapDirectory::open(Path::new(dir))?,
+                        schema,
+                        IndexSettings::default(),
+                    )?
+                }
+                Err(err) => return Err(err.into()),
+            }
+        };
+        let analyzer = indonesian_analyzer();
+        index.tokenizers().register(ANALYZER_NAME, analyzer.clone());
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::OnCommitWithDelay)
+            .try_into()?;
+        Ok(Self {
+            index,
+            reader,
+            fields,
+            analyzer,
+            write_lock: Arc::new(Mutex::new(())),
+        })
+    }
+
+    pub fn num_docs(&self) -> u64 {
+        self.reader.searcher().num_docs()
+    }
+
+    fn document(&self, item: &FeedItem) -> TantivyDocument {
+        let fields = self.fields;
+        let mut doc = TantivyDocument::default();
+        doc.add_text(fields.feed_id, &item.feed_id);
+        doc.add_text(fields.title, &item.title);
+        if let Some(summary) = item.summary.as_deref() {
+            doc.add_text(fields.summary, summary);
+        }
+        doc.add_text(fields.actor_username, &item.actor_username);
+        doc.add_text(fields.actor_id, &item.actor_id);
+        for participant_id in &item.participant_ids {
+            doc.add_text(fields.participant_ids, participant_id);
+        }
+        doc.add_text(fields.source_type, &item.source_type);
+        if let Some(scope_id) = item.scope_id.as_deref() {
+            doc.add_text(fields.scope_id, scope_id);
+        }
+        if let Some(privacy_level) = item.privacy_level.as_deref() {
+            doc.add_text(fields.privacy_level, privacy_level);
+        }
+        doc.add_u64(
+            fields.open,
+            u64::from(is_open_privacy_level(item.privacy_level.as_deref())),
+        );
+        doc.add_u64(fields.hidden, u64::from(is_hidden_feed_item(item)));
+        doc.add_i64(fields.occurred_at_ms, item.occurred_at_ms);
+        if let Some(location) = item.location.as_ref() {
+            for prefix in geohash_prefixes(&location.geohash()) {
+                doc.add_text(fields.geohash, &prefix);
+            }
+        }
+        doc
+    }
+
+    fn writer(&self) -> anyhow::Result<IndexWriter> {
+        Ok(self.index.writer_with_num_threads(1, WRITER_MEMORY_BYTES)?)
+    }
+
+    /// Replaces documents for `items` in one commit.
+    pub fn upsert_blocking(&self, items: &[FeedItem]) -> anyhow::Result<()> {
+        self.apply_blocking(items, &[])
+    }
+
+    /// Replaces documents for `items` and drops those for `removed_feed_ids`,
+    /// all in one commit.
+    pub fn apply_blocking(
+        &self,
+        items: &[FeedItem],
+        removed_feed_ids: &[String],
+    ) -> anyhow::Result<()> {
+        let _guard = self
+            .write_lock
+            .lock()
+            .map_err(|_| anyhow::anyhow!("search index write lock poisoned"))?;
+        let mut writer = self.writer()?;
+        for feed_id in removed_feed_ids {
+            writer.delete_term(Term::from_field_text(self.fields.feed_id, feed_id));
+        }
+        for item in items {
+            writer.delete_term(Term::from_field_text(self.fields.feed_id, &item.feed_id));
+            writer.add_document(self.document(item))?;
+        }
+        writer.commit()?;
+        self.reader.reload()?;
+        Ok(())
+    }
+
+    /// Drops every document and re-adds the rows `next_page` yields until it
+    /// returns an empty page. Nothing becomes visible until the final commit,
+    /// so searches keep using the previous index while the rebuild runs.
+    pub async fn rebuild<F, Fut>(&self, mut next_page: F) -> anyhow::Result<usize>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = anyhow::Result<Vec<FeedItem>>>,
+    {
+        let mut writer = self.writer()?;
+        writer.delete_all_documents()?;
+        let mut indexed = 0usize;
+        loop {
+            let page = next_page().await?;
+            if page.is_empty() {
+                break;
+            }
+            for item in &page {
+                writer.add_document(self.document(item))?;
+            }
+            indexed += page.len();
+        }
+        writer.commit()?;
+        self.reader.reload()?;
+        Ok(indexed)
+    }
+
+    fn analyze(&self, text: &str) -> Vec<String> {
+        let mut analyzer = self.analyzer.clone();
+        let mut stream = analyzer.token_stream(text);
+        let mut terms = Vec::new();
+        while stream.advance() {
+            terms.push(stream.token().text.clone());
+        }
+        terms
+    }
+
+    fn text_fields(&self) -> [(Field, f32); 3] {
+        [
+            (self.fields.title, TITLE_BOOST),
+            (self.fields.summary, 1.0),
+            (self.fields.actor_username, ACTOR_BOOST),
+        ]
+    }
+
+    /// One clause per query term (all required), each matching any text field
+    /// exactly or within a small edit distance. Quoted segments become phrase
+    /// clauses over title and summary.
+    fn text_query(&self, query_text: &str) -> Option<Box<dyn Query>> {
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        for (idx, segment) in query_text.split('"').enumerate() {
+            let terms = self.analyze(segment);
+            if terms.is_empty() {
+                continue;
+            }
+            let is_phrase = idx % 2 == 1 && terms.len() > 1;
+            if is_phrase {
+                let phrases = [self.fields.title, self.fields.summary]
+                    .into_iter()
+                    .map(|field| {
+                        let phrase = PhraseQuery::new(
+                            terms
+                                .iter()
+                                .map(|term| Term::from_field_text(field, term))
+                                .collect(),
+                        );
+                        (Occur::Should, Box::new(phrase) as Box<dyn Query>)
+                    })
+                    .collect();
+                clauses.push((Occur::Must, Box::new(BooleanQuery::new(phrases))));
+                continue;
+            }
+            for term in terms {
+                if clauses.len() >= MAX_QUERY_TERMS {
+                    break;
+                }
+                clauses.push((Occur::Must, self.term_query(&term)));
+            }
+        }
+        (!clauses.is_empty()).then(|| Box::new(BooleanQuery::new(clauses)) as Box<dyn Query>)
+    }
+
+    fn term_query(&self, term: &str) -> Box<dyn Query> {
+        let distance = match term.chars().count() {
+            0..=3 => 0,
+            4..=7 => 1,
+            _ => 2,
+        };
+        let mut alternatives: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        for (field, boost) in self.text_fields() {
+            let term = Term::from_field_text(field, term);
+            alternatives.push((
+                Occur::Should,
+                Box::new(BoostQuery::new(
+                    Box::new(TermQuery::new(
+                        term.clone(),
+                        IndexRecordOption::WithFreqsAndPositions,
+                    )),
+                    boost * EXACT_TERM_BOOST,
+                )),
+            ));
+            if distance > 0 {
+                alternatives.push((
+                    Occur::Should,
+                    Box::new(BoostQuery::new(
+                        Box::new(FuzzyTermQuery::new(term, distance, true)),
+                        boost,
+                    )),
+                ));
+            }
+        }
+        Box::new(BooleanQuery::new(alternatives))
+    }
+
+    fn exact(&self, field: Field, value: &str) -> Box<dyn Query> {
+        Box::new(TermQuery::new(
+            Term::from_field_text(field, value),
+            IndexRecordOption::Basic,
+        ))
+    }
+
+    fn flag(&self, field: Field, value: bool) -> Box<dyn Query> {
+        Box::new(TermQuery::new(
+            Term::from_field_u64(field, u64::from(value)),
+            IndexRecordOption::Basic,
+        ))
+    }
+
+    /// Mirrors `is_visible_to_actor` and the repository filters so paging
+    /// counts only hits the actor may see.
+    fn filtered_query(&self, query: &FeedSearchIndexQuery) -> Option<Box<dyn Query>> {
+        let fields = self.fields;
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> =
+            vec![(Occur::Must, self.text_query(&query.query_text)?)];
+        let involved = || -> Box<dyn Query> {
+            Box::new(BooleanQuery::new(vec![
+                (Occur::Should, self.exact(fields.actor_id, &query.actor_id)),
+                (
+                    Occur::Should,
+                    self.exact(fields.participant_ids, &query.actor_id),
+                ),
+            ]))
+        };
+        if query.involvement_only {
+            clauses.push((Occur::Must, involved()));
+        } else {
+            clauses.push((
+                Occur::Must,
+                Box::new(BooleanQuery::new(vec![
+                    (Occur::Should, self.flag(fields.open, true)),
+                    (Occur::Should, involved()),
+                ])),
+            ));
+        }
+        clauses.push((Occur::MustNot, self.flag(fields.hidden, true)));
+        if query.exclude_vault {
+            clauses.push((
+                Occur::MustNot,
+                self.exact(fields.source_type, FEED_SOURCE_VAULT),
+            ));
+        }
+        if let Some(scope_id) = query.scope_id.as_deref() {
+            clauses.push((Occur::Must, self.exact(fields.scope_id, scope_id)));
+        }
+        if let Some(privacy_level) = query.privacy_level.as_deref() {
+            clauses.push((Occur::Must, self.exact(fields.privacy_level, privacy_level)));
+        }
+        if query.from_ms.is_some() || query.to_ms.is_some() {
+            clauses.push((
+                Occur::Must,
+                Box::new(RangeQuery::new_i64_bounds(
+                    "occurred_at_ms".to_string(),
+                    query.from_ms.map_or(Bound::Unbounded, Bound::Included),
+                    query.to_ms.map_or(Bound::Unbounded, Bound::Included),
+                )),
+            ));
+        }
+        if let Some(near) = query.near.as_ref() {
+            let cells = near.covering_geohashes();
+            // No covering cells (near the poles) leaves the check to the service.
+            if !cells.is_empty() {
+                clauses.push((
+                    Occur::Must,
+                    Box::new(BooleanQuery::new(
+                        cells
+                            .iter()
+                            .map(|cell| (Occur::Should, self.exact(fields.geohash, cell)))
+                            .collect(),
+                    )),
+                ));
+            }
+        }
+        Some(Box::new(BooleanQuery::new(clauses)))
+    }
+
+    pub fn search_blocking(
+        &self,
+        query: &FeedSearchIndexQuery,
+    ) -> anyhow::Result<Vec<FeedSearchHit>> {
+        let Some(tantivy_query) = self.filtered_query(query) else {
+            return Ok(Vec::new());
+        };
+        let searcher = self.reader.searcher();
+        let top_docs = searcher.search(
+            &tantivy_query,
+            &TopDocs::with_limit(query.limit.max(1)).and_offset(query.offset),
+        )?;
+        let mut hits = Vec::with_capacity(top_docs.len());
+        for (score, address) in top_docs {
+            let doc: TantivyDocument = searcher.doc(address)?;
+            if let Some(feed_id) = doc
+                .get_first(self.fields.feed_id)
+                .and_then(|value| value.as_str())
+            {
+                hits.push(FeedSearchHit {
+                    feed_id: feed_id.to_string(),
+                    score,
+                });
+            }
+        }
+        Ok(hits)
+    }
+}
+
+impl FeedSearchIndex for TantivyFeedSearchIndex {
+    fn search(
+        &self,
+        query: &FeedSearchIndexQuery,
+    ) -> BoxFuture<'_, DomainResult<Vec<FeedSearchHit>>> {
+        let index = self.clone();
+        let query = query.clone();
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || index.search_blocking(&query))
+                .await
+                .map_err(|err| index_error("search index task failed", err))?
+                .map_err(|err| index_error("failed to query search index", err))
+        })
+    }
+}
+
+enum IndexerCommand {
+    Refresh(Vec<String>),
+    Flush(oneshot::Sender<()>),
+}
+
+/// The single writer of a [`TantivyFeedSearchIndex`]. Enqueued feed ids are
+/// de-duplicated, re-read from the feed repository and written in one commit
+/// per batch, so bursts of feed writes never open competing `IndexWriter`s and
+/// each commit reflects the rows as they are at flush time.
+#[derive(Clone)]
+pub struct FeedSearchIndexer {
+    sender: mpsc::UnboundedSender<IndexerCommand>,
+}
+
+impl FeedSearchIndexer {
+    /// Starts the writer task; `feed_repo` must be the unwrapped repository.
+    pub fn spawn(index: TantivyFeedSearchIndex, feed_repo: Arc<dyn FeedRepository>) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(run_indexer(index, feed_repo, receiver));
+        Self { sender }
+    }
+
+    /// Resolves once every id enqueued before the call has been committed.
+    pub async fn flush(&self) {
+        let (done, committed) = oneshot::channel();
+        if self.sender.send(IndexerCommand::Flush(done)).is_ok() {
+            let _ = committed.await;
+        }
+    }
+}
+
+impl FeedSearchIndexQueue for FeedSearchIndexer {
+    fn enqueue(&self, feed_ids: &[String]) -> BoxFuture<'_, DomainResult<()>> {
+        let sent = self
+            .sender
+            .send(IndexerCommand::Refresh(feed_ids.to_vec()))
+            .map_err(|_| DomainError::Internal("search indexer has stopped".to_string()));
+        Box::pin(async move { sent })
+    }
+}
+
+async fn run_indexer(
+    index: TantivyFeedSearchIndex,
+    feed_repo: Arc<dyn FeedRepository>,
+    mut receiver: mpsc::UnboundedReceiver<IndexerCommand>,
+) {
+    while let Some(command) = receiver.recv().await {
+        let mut pending = BTreeSet::new();
+        let mut flushed = Vec::new();
+        let mut next = Some(command);
+        let linger_until = tokio::time::Instant::now() + INDEXER_LINGER;
+        loop {
+            match next.take() {
+                Some(IndexerCommand::Refresh(feed_ids)) => pending.extend(feed_ids),
+                Some(IndexerCommand::Flush(done)) => flushed.push(done),
+                None => {}
+            }
+            if !flushed.is_empty() || pending.len() >= INDEXER_BATCH {
+                break;
+            }
+            match tokio::time::timeout_at(linger_until, receiver.recv()).await {
+                Ok(Some(command)) => next = Some(command),
+                Ok(None) | Err(_) => break,
+            }
+        }
+        if !pending.is_empty() {
+            commit_indexer_batch(&index, feed_repo.as_ref(), pending).await;
+        }
+        for done in flushed {
+            let _ = done.send(());
+        }
+    }
+}
+
+/// Failures are logged and dropped: the feed rows are the source of truth and
+/// `feed-search-index-rebuild` restores anything a lost batch missed.
+async fn commit_indexer_batch(
+    index: &TantivyFeedSearchIndex,
+    feed_repo: &dyn FeedRepository,
+    feed_ids: BTreeSet<String>,
+) {
+    let mut items = Vec::with_capacity(feed_ids.len());
+    let mut removed = Vec::new();
+    for feed_id in feed_ids {
+        match feed_repo.get_by_feed_id(&feed_id).await {
+            Ok(Some(item)) => items.push(item),
+            Ok(None) => removed.push(feed_id),
+            Err(err) => {
+                tracing::warn!(feed_id = %feed_id, error = %err, "search index could not load feed item");
+            }
+        }
+    }
+    let batch_size = items.len() + removed.len();
+    let index = index.clone();
+    let result = tokio::task::spawn_blocking(move || index.apply_blocking(&items, &removed)).await;
+    match result {
+        Ok(Ok(())) => {
+            tracing::debug!(batch_size, "search index batch committed");
+        }
+        Ok(Err(err)) => {
+            tracing::warn!(batch_size, error = %err, "search index batch commit failed");
+        }
+        Err(err) => {
+            tracing::warn!(batch_size, error = %err, "search index batch task failed");
+        }
+    }
+}
+
+/// Sends stale feed ids to the worker's [`FeedSearchIndexer`] as a job, for
+/// processes that must not write the index themselves.
+pub struct JobFeedSearchIndexQueue {
+    queue: Arc<dyn JobQueue>,
+}
+
+impl JobFeedSearchIndexQueue {
+    pub fn new(queue: Arc<dyn JobQueue>) -> Self {
+        Self { queue }
+    }
+}
+
+impl FeedSearchIndexQueue for JobFeedSearchIndexQueue {
+    fn enqueue(&self, feed_ids: &[String]) -> BoxFuture<'_, DomainResult<()>> {
+        let feed_ids = feed_ids.to_vec();
+        Box::pin(async move {
+            let job_id = format!("feed_search_index:{}", uuid_v7_without_dashes());
+            let payload = serde_json::to_value(FeedSearchIndexSyncPayload { feed_ids })
+                .map_err(|err| DomainError::Internal(format!("invalid index payload: {err}")))?;
+            let job = new_job(
+                job_id.clone(),
+                JobType::FeedSearchIndexSync,
+                payload,
+                job_id.clone(),
+                job_id,
+                JobDefaults::default(),
+            );
+            self.queue
+                .enqueue(&job)
+                .await
+                .map_err(|err| DomainError::Internal(format!("failed to queue index sync: {err}")))
+        })
+    }
+}
+
+/// Reports feed writes to the search index queue. Queue failures are logged and
+/// swallowed: the feed row is the source of truth and the index can be rebuilt
+/// from it.
+pub struct IndexedFeedRepository {
+    inner: Arc<dyn FeedRepository>,
+    queue: Arc<dyn FeedSearchIndexQueue>,
+}
+
+impl IndexedFeedRepository {
+    pub fn new(inner: Arc<dyn FeedRepository>, queue: Arc<dyn FeedSearchIndexQueue>) -> Self {
+        Self { inner, queue }
+    }
+
+    async fn sync(queue: &dyn FeedSearchIndexQueue, item: &FeedItem) {
+        if let Err(err) = queue.enqueue(std::slice::from_ref(&item.feed_id)).await {
+            tracing::warn!(feed_id = %item.feed_id, error = %err, "search index sync failed");
+        }
+    }
+}
+
+impl FeedRepository for IndexedFeedRepository {
+    fn create_feed_item(&self, item: &FeedItem) -> BoxFuture<'_, DomainResult<FeedItem>> {
+        let item = item.clone();
+        Box::pin(async move {
+            let created = self.inner.create_feed_item(&item).await?;
+            Self::sync(self.queue.as_ref(), &created).await;
+            Ok(created)
+        })
+    }
+
+    fn upsert_participant_edges_for_item(
+        &self,
+        item: &FeedItem,
+    ) -> BoxFuture<'_, DomainResult<()>> {
+        self.inner.upsert_participant_edges_for_item(item)
+    }
+
+    fn get_by_source_request(
+        &self,
+        source_type: &str,
+        source_id: &str,
+        request_id: &str,
+    ) -> BoxFuture<'_, DomainResult<Option<FeedItem>>> {
+        self.inner
+            .get_by_source_request(source_type, source_id, request_id)
+    }
+
+    fn get_by_feed_id(&self, feed_id: &str) -> BoxFuture<'_, DomainResult<Option<FeedItem>>> {
+        self.inner.get_by_feed_id(feed_id)
+    }
+
+    fn get_latest_by_source(
+        &self,
+        source_type: &str,
+        source_id: &str,
+    ) -> BoxFuture<'_, DomainResult<Option<FeedItem>>> {
+        self.inner.get_latest_by_source(source_type, source_id)
+    }
+
+    fn merge_payload(
+        &self,
+        feed_id: &str,
+        payload_patch: serde_json::Value,
+    ) -> BoxFuture<'_, DomainResult<FeedItem>> {
+        let feed_id = feed_id.to_string();
+        Box::pin(async move {
+            let merged = self.inner.merge_payload(&feed_id, payload_patch).await?;
+            Self::sync(self.queue.as_ref(), &merged).await;
+            Ok(merged)
+        })
+    }
+
+    fn update_title(&self, feed_id: &str, title: &str) -> BoxFuture<'_, DomainResult<FeedItem>> {
+        let feed_id = feed_id.to_string();
+        let title = title.to_string();
+        Box::pin(async move {
+            let updated = self.inner.update_title(&feed_id, &title).await?;
+            Self::sync(self.queue.as_ref(), &updated).await;
+            Ok(updated)
+        })
+    }
+
+    fn list_feed(&self, query: &FeedRepositoryQuery) -> BoxFuture<'_, DomainResult<Vec<FeedItem>>> {
+        self.inner.list_feed(query)
+    }
+
+    fn search_feed(
+        &self,
+        query: &FeedRepositorySearchQuery,
+    ) -> BoxFuture<'_, DomainResult<Vec<FeedItem>>> {
+        self.inner.search_feed(query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gotong_domain::geo::{GeoPoint, GeoRadius};
+
+    fn item(feed_id: &str, title: &str, privacy_level: &str, actor_id: &str) -> FeedItem {
+        FeedItem {
+            feed_id: feed_id.to_string(),
+            source_type: "contribution".to_string(),
+            source_id: format!("src-{feed_id}"),
+            actor_id: actor_id.to_string(),
+            actor_username: actor_id.to_string(),
+            title: title.to_string(),
+            summary: None,
+            scope_id: Some("rw-03".to_string()),
+            privacy_level: Some(privacy_level.to_string()),
+            occurred_at_ms: 1_000,
+            created_at_ms: 1_000,
+            request_id: format!("req-{feed_id}"),
+            correlation_id: format!("corr-{feed_id}"),
+            participant_ids: vec![],
+            payload: None,
+            location: None,
+            distance_m: None,
+        }
+    }
+
+    fn query(actor_id: &str, query_text: &str) -> FeedSearchIndexQuery {
+        FeedSearchIndexQuery {
+            actor_id: actor_id.to_string(),
+            query_text: query_text.to_string(),
+            limit: 10,
+            offset: 0,
+            scope_id: None,
+            privacy_level: None,
+            from_ms: None,
+            to_ms: None,
+            involvement_only: false,
+            exclude_vault: false,
+            near: None,
+        }
+    }
+
+    fn ids(hits: Vec<FeedSearchHit>) -> Vec<String> {
+        hits.into_iter().map(|hit| hit.feed_id).collect()
+    }
+
+    #[test]
+    fn stemmer_strips_affixes_but_keeps_short_roots() {
+        assert_eq!(stem_indonesian("pembangunan"), "bangun");
+        assert_eq!(stem_indonesian("membangun"), "bangun");
+        assert_eq!(stem_indonesian("diperbaiki"), "baik");
+        assert_eq!(stem_indonesian("menulis"), "tulis");
+        assert_eq!(stem_indonesian("menyapu"), "sapu");
+        assert_eq!(stem_indonesian("kebersihan"), "bersih");
+        assert_eq!(stem_indonesian("bukunya"), "buku");
+        assert_eq!(stem_indonesian("makan"), "makan");
+        assert_eq!(stem_indonesian("jalan"), "jalan");
+    }
+
+    #[test]
+    fn search_stems_tolerates_typos_and_matches_phrases() {
+        let index = TantivyFeedSearchIndex::open("").expect("index");
+        index
+            .upsert_blocking(&[
+                item("feed-1", "Pembangunan saluran air di RT 05", "public", "a"),
+                item("feed-2", "Kerja bakti membersihkan saluran", "public", "b"),
+                item("feed-3", "Air bersih untuk saluran warga", "public", "c"),
+            ])
+            .expect("upsert");
+
+        assert_eq!(
+            ids(index.search_blocking(&query("r", "bangun")).unwrap()),
+            vec!["feed-1"]
+        );
+        assert_eq!(
+            ids(index.search_blocking(&query("r", "kerja bkati")).unwrap()),
+            vec!["feed-2"]
+        );
+        assert_eq!(
+            ids(index
+                .search_blocking(&query("r", "\"saluran air\""))
+                .unwrap()),
+            vec!["feed-1"]
+        );
+        assert_eq!(
+            index.search_blocking(&query("r", "yang dan")).unwrap(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn search_filters_privacy_hidden_and_vault_inside_index() {
+        let index = TantivyFeedSearchIndex::open("").expect("index");
+        let mut hidden = item("feed-hidden", "Ronda malam", "public", "a");
+        hidden.payload = Some(serde_json::json!({ "lifecycle": { "hidden": true } }));
+        let mut vault = item("feed-vault", "Ronda malam", "public", "a");
+        vault.source_type = FEED_SOURCE_VAULT.to_string();
+        let mut shared = item("feed-shared", "Ronda malam", "private", "a");
+        shared.participant_ids = vec!["reader".to_string()];
+        index
+            .upsert_blocking(&[
+                item("feed-open", "Ronda malam", "public", "a"),
+                item("feed-private", "Ronda malam", "private", "a"),
+                hidden,
+                vault,
+                shared,
+            ])
+            .expect("upsert");
+
+        let mut visible = ids(index.search_blocking(&query("reader", "ronda")).unwrap());
+        visible.sort();
+        assert_eq!(visible, vec!["feed-open", "feed-shared", "feed-vault"]);
+
+        let mut without_vault = query("reader", "ronda");
+        without_vault.exclude_vault = true;
+        without_vault.involvement_only = true;
+        assert_eq!(
+            ids(index.search_blocking(&without_vault).unwrap()),
+            vec!["feed-shared"]
+        );
+
+        let mut author = ids(index.search_blocking(&query("a", "ronda")).unwrap());
+        author.sort();
+        assert_eq!(
+            author,
+            vec!["feed-open", "feed-private", "feed-shared", "feed-vault"]
+        );
+    }
+
+    #[test]
+    fn search_near_keeps_items_in_covering_cells_only() {
+        let index = TantivyFeedSearchIndex::open("").expect("index");
+        let mut near = item("feed-near", "Posko banjir", "public", "a");
+        near.location = Some(GeoPoint::new(-6.2000, 106.8166).unwrap());
+        let mut far = item("feed-far", "Posko banjir", "public", "a");
+        far.location = Some(GeoPoint::new(-6.9175, 107.6191).unwrap());
+        index
+            .upsert_blocking(&[
+                near,
+                far,
+                item("feed-unlocated", "Posko banjir", "public", "a"),
+            ])
+            .expect("upsert");
+
+        let mut nearby = query("r", "posko");
+        nearby.near = Some(
+            GeoRadius::new(GeoPoint::new(-6.2050, 106.8200).unwrap(), 2_000.0, 50_000.0).unwrap(),
+        );
+        assert_eq!(
+            ids(index.search_blocking(&nearby).unwrap()),
+            vec!["feed-near"]
+        );
+        assert_eq!(
+            index.search_blocking(&query("r", "posko")).unwrap().len(),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn indexer_commits_feed_writes_reported_by_the_repository() {
+        let index = TantivyFeedSearchIndex::open("").expect("index");
+        let inner: Arc<dyn FeedRepository> =
+            Arc::new(crate::repositories::InMemoryDiscoveryFeedRepository::new());
+        let indexer = FeedSearchIndexer::spawn(index.clone(), inner.clone());
+        let repo = IndexedFeedRepository::new(inner, Arc::new(indexer.clone()));
+
+        repo.create_feed_item(&item("feed-1", "Posyandu balita", "public", "a"))
+            .await
+            .expect("create");
+        repo.create_feed_item(&item("feed-2", "Kerja bakti", "public", "a"))
+            .await
+            .expect("create");
+        repo.update_title("feed-2", "Posyandu lansia")
+            .await
+            .expect("update");
+        indexer.flush().await;
+
+        let mut found = ids(index.search_blocking(&query("r", "posyandu")).unwrap());
+        found.sort();
+        assert_eq!(found, vec!["feed-1", "feed-2"]);
+        assert_eq!(index.num_docs(), 2);
+
+        // Ids without a feed row drop their stale document.
+        index
+            .upsert_blocking(&[item("feed-gone", "Posyandu lama", "public", "a")])
+            .expect("upsert");
+        indexer
+            .enqueue(&["feed-gone".to_string()])
+            .await
+            .expect("enqueue");
+        indexer.flush().await;
+        assert_eq!(index.num_docs(), 2);
+    }
+
+    #[tokio::test]
+    async fn rebuild_replaces_existing_documents() {
+        let index = TantivyFeedSearchIndex::open("").expect("index");
+        index
+            .upsert_blocking(&[item("feed-stale", "Posyandu balita", "public", "a")])
+            .expect("upsert");
+        let mut pages = vec![vec![item("feed-new", "Posyandu lansia", "public", "a")]];
+        let indexed = index
+            .rebuild(|| {
+                let page = pages.pop().unwrap_or_default();
+                async move { Ok(page) }
+            })
+            .await
+            .expect("rebuild");
+        assert_eq!(indexed, 1);
+        assert_eq!(
+            ids(index.search_blocking(&query("r", "posyandu")).unwrap()),
+            vec!["feed-new"]
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use gotong_domain::DomainResult;
+use gotong_domain::error::DomainError;
+use gotong_domain::ports::BoxFuture;
+use gotong_domain::ports::trending::TrendingSnapshotStore;
+use gotong_domain::trending::TrendingSnapshot;
+use redis::AsyncCommands;
+use redis::aio::ConnectionManager;
+
+const DEFAULT_PREFIX: &str = "gotong:trending";
+
+/// Trending snapshots as JSON strings under `<prefix>:global` and
+/// `<prefix>:scope:<scope_id>`, written by the worker and read by every API node.
+#[derive(Clone)]
+pub struct RedisTrendingSnapshotStore {
+    manager: ConnectionManager,
+    prefix: String,
+}
+
+impl RedisTrendingSnapshotStore {
+    pub async fn connect(redis_url: &str) -> DomainResult<Self> {
+        Self::connect_with_prefix(redis_url, DEFAULT_PREFIX).await
+    }
+
+    pub async fn connect_with_prefix(
+        redis_url: &str,
+        prefix: impl Into<String>,
+    ) -> DomainResult<Self> {
+        let client = redis::Client::open(redis_url).map_err(store_error)?;
+        let manager = ConnectionManager::new(client).await.map_err(store_error)?;
+        Ok(Self {
+            manager,
+            prefix: prefix.into(),
+        })
+    }
+
+    fn cache_key(&self, scope_id: Option<&str>) -> String {
+        match scope_id {
+            Some(scope_id) => format!("{}:scope:{scope_id}", self.prefix),
+            None => format!("{}:global", self.prefix),
+        }
+    }
+}
+
+impl TrendingSnapshotStore for RedisTrendingSnapshotStore {
+    fn put_snapshots(
+        &self,
+        snapshots: &[TrendingSnapshot],
+        ttl_ms: u64,
+    ) -> BoxFuture<'_, DomainResult<()>> {
+        let entries = snapshots
+            .iter()
+            .map(|snapshot| {
+                serde_json::to_string(snapshot)
+                    .map(|payload| (self.cache_key(snapshot.scope_id.as_deref()), payload))
+                    .map_err(|err| {
+                        DomainError::Validation(format!("trending snapshot encode failed: {err}"))
+                    })
+            })
+            .collect::<DomainResult<Vec<_>>>();
+        Box::pin(async move {
+            let entries = entries?;
+            if entries.is_empty() {
+                return Ok(());
+            }
+            let mut pipeline = redis::pipe();
+            for (key, payload) in entries {
+                pipeline
+                    .cmd("SET")
+                    .arg(key)
+                    .arg(payload)
+                    .arg("PX")
+                    .arg(ttl_ms.max(1))
+                    .ignore();
+            }
+            let mut conn = self.manager.clone();
+            let _: () = pipeline.query_async(&mut conn).await.map_err(store_error)?;
+            Ok(())
+        })
+    }
+
+    fn get_snapshot(
+        &self,
+        scope_id: Option<&str>,
+    ) -> BoxFuture<'_, DomainResult<Option<TrendingSnapshot>>> {
+        let key = self.cache_key(scope_id);
+        Box::pin(async move {
+            let mut conn = self.manager.clone();
+            let value: Option<String> = conn.get(key).await.map_err(store_error)?;
+            value
+                .map(|payload| {
+                    serde_json::from_str(&payload).map_err(|err| {
+                        DomainError::Validation(format!("trending snapshot decode failed: {err}"))
+                    })
+                })
+                .transpose()
+        })
+    }
+}
+
+fn store_error(err: redis::RedisError) -> DomainError {
+    DomainError::Validation(format!("trending snapshot store unavailable: {err}"))
+}
@@ -0,0 +1,332 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes128Gcm, Nonce};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use gotong_domain::DomainResult;
+use gotong_domain::error::DomainError;
+use gotong_domain::ports::BoxFuture;
+use gotong_domain::ports::push::{PushDelivery, PushDeliveryOutcome, PushGateway};
+use gotong_domain::push::PushSubscription;
+use hkdf::Hkdf;
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::{Signature, SigningKey};
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use p256::{PublicKey, SecretKey};
+use rand_core::{OsRng, RngCore};
+use reqwest::StatusCode;
+use reqwest::header::{AUTHORIZATION, CONTENT_ENCODING, CONTENT_TYPE, RETRY_AFTER};
+use sha2::Sha256;
+
+use crate::config::AppConfig;
+
+/// RFC 8188 record size advertised in the header; one record carries the whole payload.
+const RECORD_SIZE: u32 = 4_096;
+const SALT_LENGTH: usize = 16;
+const TAG_LENGTH: usize = 16;
+/// RFC 8292 caps the VAPID `exp` claim at 24 hours; stay well inside it.
+const VAPID_TOKEN_LIFETIME_SECS: u64 = 12 * 60 * 60;
+const PUSH_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The application server's VAPID identity (RFC 8292).
+#[derive(Clone)]
+pub struct VapidKeys {
+    signing_key: SigningKey,
+    public_key: String,
+    subject: String,
+}
+
+impl VapidKeys {
+    /// `private_key` is the base64url P-256 scalar; `public_key` must be the matching
+    /// uncompressed point, as printed by `web-push generate-vapid-keys`.
+    pub fn new(public_key: &str, private_key: &str, subject: &str) -> anyhow::Result<Self> {
+        let scalar = URL_SAFE_NO_PAD
+            .decode(private_key.trim().trim_end_matches('='))
+            .map_err(|err| anyhow::anyhow!("web push VAPID private key is not base64url: {err}"))?;
+        let secret = SecretKey::from_slice(&scalar)
+            .map_err(|_| anyhow::anyhow!("web push VAPID private key is not a P-256 scalar"))?;
+        let derived = encode_point(&secret.public_key());
+        if derived != public_key.trim().trim_end_matches('=') {
+            anyhow::bail!("web push VAPID public key does not match the private key");
+        }
+        Ok(Self {
+            signing_key: SigningKey::from(secret),
+            public_key: derived,
+            subject: subject.trim().to_string(),
+        })
+    }
+
+    /// `None` when Web Push is not configured.
+    pub fn from_config(config: &AppConfig) -> anyhow::Result<Option<Self>> {
+        if config.web_push_vapid_private_key.trim().is_empty() {
+            return Ok(None);
+        }
+        Self::new(
+            &config.web_push_vapid_public_key,
+            &config.web_push_vapid_private_key,
+            &config.web_push_vapid_subject,
+        )
+        .map(Some)
+    }
+
+    /// The `applicationServerKey` browsers pass to `pushManager.subscribe`.
+    pub fn public_key(&self) -> &str {
+        &self.public_key
+    }
+
+    fn authorization(&self, endpoint: &str, now_secs: u64) -> DomainResult<String> {
+        let audience = url::Url::parse(endpoint)
+            .map(|url| url.origin().ascii_serialization())
+            .map_err(|err| DomainError::Validation(format!("invalid push endpoint: {err}")))?;
+        let header = URL_SAFE_NO_PAD.encode(br#"{"typ":"JWT","alg":"ES256"}"#);
+        let claims = serde_json::json!({
+            "aud": audience,
+            "exp": now_secs + VAPID_TOKEN_LIFETIME_SECS,
+            "sub": self.subject,
+        });
+        let claims = URL_SAFE_NO_PAD.encode(claims.to_string());
+        let signing_input = format!("{header}.{claims}");
+        let signature: Signature = self.signing_key.sign(signing_input.as_bytes());
+        let signature = URL_SAFE_NO_PAD.encode(signature.to_bytes());
+        Ok(format!(
+            "vapid t={signing_input}.{signature}, k={}",
+            self.public_key
+        ))
+    }
+}
+
+/// Delivers pushes straight to the browser vendors' push services.
+pub struct WebPushGateway {
+    client: reqwest::Client,
+    vapid: VapidKeys,
+}
+
+impl WebPushGateway {
+    pub fn new(vapid: VapidKeys) -> anyhow::Result<Self> {
+        // Endpoints come from browsers; a redirect must not carry the signed,
+        // encrypted push to a host the subscription never named.
+        let client = reqwest::Client::builder()
+            .timeout(PUSH_REQUEST_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+        Ok(Self { client, vapid })
+    }
+}
+
+impl PushGateway for WebPushGateway {
+    fn deliver(
+        &self,
+        subscription: &PushSubscription,
+        delivery: &PushDelivery,
+    ) -> BoxFuture<'_, DomainResult<PushDeliveryOutcome>> {
+        let subscription = subscription.clone();
+        let delivery = delivery.clone();
+        Box::pin(async move {
+            let body = encrypt_for_subscription(&subscription, &delivery.payload)?;
+            let now_secs = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            let authorization = self.vapid.authorization(&subscription.endpoint, now_secs)?;
+            let response = self
+                .client
+                .post(&subscription.endpoint)
+                .header(AUTHORIZATION, authorization)
+                .header(CONTENT_ENCODING, "aes128gcm")
+                .header(CONTENT_TYPE, "application/octet-stream")
+                .header("TTL", delivery.ttl_seconds.to_string())
+                .header("Urgency", delivery.urgency.as_str())
+                .body(body)
+                .send()
+                .await;
+            let response = match response {
+                Ok(response) => response,
+                Err(err) => {
+                    tracing::warn!(error = %err, "web push request failed");
+                    return Ok(PushDeliveryOutcome::Retryable {
+                        retry_after_ms: None,
+                    });
+                }
+            };
+            let retry_after_ms = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(|secs| secs.saturating_mul(1_000));
+            Ok(classify_status(response.status(), retry_after_ms))
+        })
+    }
+}
+
+fn classify_status(status: StatusCode, retry_after_ms: Option<u64>) -> PushDeliveryOutcome {
+    match status.as_u16() {
+        200..=299 => PushDeliveryOutcome::Delivered,
+        404 | 410 => PushDeliveryOutcome::Gone,
+        429 | 500..=599 => PushDeliveryOutcome::Retryable { retry_after_ms },
+        // Redirects are not followed, so a 3xx is a failed delivery like any 4xx.
+        status => PushDeliveryOutcome::Rejected { status },
+    }
+}
+
+fn encrypt_for_subscription(
+    subscription: &PushSubscription,
+    plaintext: &[u8],
+) -> DomainResult<Vec<u8>> {
+    let decode = |value: &str, label: &str| {
+        URL_SAFE_NO_PAD
+            .decode(value)
+            .map_err(|_| DomainError::Validation(format!("stored {label} is not base64url")))
+    };
+    let ua_public = decode(&subscription.p256dh, "p256dh")?;
+    let auth_secret = decode(&subscription.auth, "auth")?;
+    let mut salt = [0u8; SALT_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    encrypt_payload(
+        &ua_public,
+        &auth_secret,
+        plaintext,
+        &SecretKey::random(&mut OsRng),
+        salt,
+    )
+}
+
+/// RFC 8291 `aes128gcm` message encryption as a single RFC 8188 record.
+pub fn encrypt_payload(
+    ua_public: &[u8],
+    auth_secret: &[u8],
+    plaintext: &[u8],
+    as_secret: &SecretKey,
+    salt: [u8; SALT_LENGTH],
+) -> DomainResult<Vec<u8>> {
+    let invalid = |message: &str| DomainError::Validation(message.to_string());
+    if plaintext.len() + 1 + TAG_LENGTH + SALT_LENGTH + 5 + 65 > RECORD_SIZE as usize {
+        return Err(invalid("push payload is too large"));
+    }
+    let ua_key = PublicKey::from_sec1_bytes(ua_public)
+        .map_err(|_| invalid("subscription p256dh is not a P-256 point"))?;
+    let as_public = as_secret.public_key().to_encoded_point(false);
+    let shared = p256::ecdh::diffie_hellman(as_secret.to_nonzero_scalar(), ua_key.as_affine());
+
+    let mut key_info = b"WebPush: info\0".to_vec();
+    key_info.extend_from_slice(ua_public);
+    key_info.extend_from_slice(as_public.as_bytes());
+    let mut ikm = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(auth_secret), shared.raw_secret_bytes())
+        .expand(&key_info, &mut ikm)
+        .map_err(|_| invalid("web push key derivation failed"))?;
+
+    let hkdf = Hkdf::<Sha256>::new(Some(&salt), &ikm);
+    let mut cek = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    hkdf.expand(b"Content-Encoding: aes128gcm\0", &mut cek)
+        .and_then(|_| hkdf.expand(b"Content-Encoding: nonce\0", &mut nonce))
+        .map_err(|_| invalid("web push key derivation failed"))?;
+
+    // A single, final record: the padding delimiter is 0x02.
+    let mut record = plaintext.to_vec();
+    record.push(0x02);
+    let ciphertext = Aes128Gcm::new_from_slice(&cek)
+        .map_err(|_| invalid("web push cipher setup failed"))?
+        .encrypt(Nonce::from_slice(&nonce), record.as_slice())
+        .map_err(|_| invalid("web push encryption failed"))?;
+
+    let mut body = Vec::with_capacity(SALT_LENGTH + 5 + 65 + ciphertext.len());
+    body.extend_from_slice(&salt);
+    body.extend_from_slice(&RECORD_SIZE.to_be_bytes());
+    body.push(as_public.as_bytes().len() as u8);
+    body.extend_from_slice(as_public.as_bytes());
+    body.extend_from_slice(&ciphertext);
+    Ok(body)
+}
+
+fn encode_point(public_key: &PublicKey) -> String {
+    URL_SAFE_NO_PAD.encode(public_key.to_encoded_point(false).as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdsa::VerifyingKey;
+    use p256::ecdsa::signature::Verifier;
+
+    fn b64(value: &str) -> Vec<u8> {
+        URL_SAFE_NO_PAD.decode(value).expect("base64url")
+    }
+
+    #[test]
+    fn encrypt_payload_matches_rfc8291_example() {
+        // RFC 8291 Appendix A.
+        let as_secret = SecretKey::from_slice(&b64("yfWPiYE-n46HLnH0KqZOF1fJJU3MYrct3AELtAQ-oRw"))
+            .expect("as private key");
+        let salt: [u8; 16] = b64("DGv6ra1nlYgDCS1FRnbzlw").try_into().expect("salt");
+        let body = encrypt_payload(
+            &b64("BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4"),
+            &b64("BTBZMqHH6r4Tts7J_aSIgg"),
+            b"When I grow up, I want to be a watermelon",
+            &as_secret,
+            salt,
+        )
+        .expect("encrypt");
+        assert_eq!(
+            URL_SAFE_NO_PAD.encode(body),
+            "DGv6ra1nlYgDCS1FRnbzlwAAEABBBP4z9KsN6nGRTbVYI_c7VJSPQTBtkgcy27mlmlMoZIIgDll6e3vCYLocInmYWAmS6TlzAC8wEqKK6PBru3jl7A_yl95bQpu6cVPTpK4Mqgkf1CXztLVBSt2Ks3oZwbuwXPXLWyouBWLVWGNWQexSgSxsj_Qulcy4a-fN"
+        );
+    }
+
+    #[test]
+    fn vapid_authorization_signs_endpoint_origin() {
+        let secret = SecretKey::random(&mut OsRng);
+        let private_key = URL_SAFE_NO_PAD.encode(secret.to_bytes());
+        let public_key = encode_point(&secret.public_key());
+        assert!(VapidKeys::new("BAAA", &private_key, "mailto:ops@example.org").is_err());
+        let keys =
+            VapidKeys::new(&public_key, &private_key, "mailto:ops@example.org").expect("keys");
+
+        let header = keys
+            .authorization("https://fcm.googleapis.com/fcm/send/abc?x=1", 1_000)
+            .expect("authorization");
+        let (token, key) = header
+            .strip_prefix("vapid t=")
+            .and_then(|rest| rest.split_once(", k="))
+            .expect("vapid header");
+        assert_eq!(key, public_key);
+        let (signing_input, signature) = token.rsplit_once('.').expect("jwt");
+        let claims: serde_json::Value =
+            serde_json::from_slice(&b64(signing_input.split_once('.').expect("claims").1))
+                .expect("claims json");
+        assert_eq!(claims["aud"], "https://fcm.googleapis.com");
+        assert_eq!(claims["exp"], 1_000 + VAPID_TOKEN_LIFETIME_SECS);
+        let signature = Signature::from_slice(&b64(signature)).expect("signature");
+        VerifyingKey::from(secret.public_key())
+            .verify(signing_input.as_bytes(), &signature)
+            .expect("valid ES256 signature");
+    }
+
+    #[test]
+    fn classify_status_prunes_gone_and_retries_throttling() {
+        assert_eq!(
+            classify_status(StatusCode::CREATED, None),
+            PushDeliveryOutcome::Delivered
+        );
+        assert_eq!(
+            classify_status(StatusCode::GONE, None),
+            PushDeliveryOutcome::Gone
+        );
+        assert_eq!(
+            classify_status(StatusCode::TOO_MANY_REQUESTS, Some(30_000)),
+            PushDeliveryOutcome::Retryable {
+                retry_after_ms: Some(30_000)
+            }
+        );
+        assert_eq!(
+            classify_status(StatusCode::PAYLOAD_TOO_LARGE, None),
+            PushDeliveryOutcome::Rejected { status: 413 }
+        );
+        assert_eq!(
+            classify_status(StatusCode::TEMPORARY_REDIRECT, None),
+            PushDeliveryOutcome::Rejected { status: 307 }
+        );
+    }
+}
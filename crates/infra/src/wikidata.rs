@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use gotong_domain::DomainResult;
+use gotong_domain::error::DomainError;
+use gotong_domain::ports::BoxFuture;
+use gotong_domain::ports::ontology::ConceptRedirectLookup;
+use serde_json::Value;
+
+use crate::config::AppConfig;
+
+const WIKIDATA_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const WIKIDATA_USER_AGENT: &str = "gotong-royong-worker/1.0 (concept verification)";
+
+/// Asks the MediaWiki API whether a QID has been merged upstream.
+pub struct WikidataRedirectClient {
+    client: reqwest::Client,
+    api_url: String,
+}
+
+impl WikidataRedirectClient {
+    pub fn new(api_url: &str) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(WIKIDATA_REQUEST_TIMEOUT)
+            .user_agent(WIKIDATA_USER_AGENT)
+            .build()?;
+        Ok(Self {
+            client,
+            api_url: api_url.trim().to_string(),
+        })
+    }
+
+    /// `None` when `WIKIDATA_API_URL` is empty.
+    pub fn from_config(config: &AppConfig) -> anyhow::Result<Option<Self>> {
+        if config.wikidata_api_url.trim().is_empty() {
+            return Ok(None);
+        }
+        Self::new(&config.wikidata_api_url).map(Some)
+    }
+}
+
+impl ConceptRedirectLookup for WikidataRedirectClient {
+    fn redirect_target(&self, qid: &str) -> BoxFuture<'_, DomainResult<Option<String>>> {
+        let qid = qid.trim().to_string();
+        Box::pin(async move {
+            let response = self
+                .client
+                .get(&self.api_url)
+                .query(&[
+                    ("action", "wbgetentities"),
+                    ("ids", qid.as_str()),
+                    ("props", "info"),
+                    ("format", "json"),
+                ])
+                .send()
+                .await
+                .map_err(|err| {
+                    DomainError::Validation(format!("wikidata request failed: {err}"))
+                })?;
+            if !response.status().is_success() {
+                return Err(DomainError::Validation(format!(
+                    "wikidata returned status {}",
+                    response.status()
+                )));
+            }
+            let body: Value = response.json().await.map_err(|err| {
+                DomainError::Validation(format!("wikidata response is not json: {err}"))
+            })?;
+            Ok(parse_redirect_target(&body, &qid))
+        })
+    }
+}
+
+/// `wbgetentities` resolves redirects itself and reports them under
+/// `entities.{target}.redirects`; a QID that was not redirected has no such entry.
+pub fn parse_redirect_target(body: &Value, qid: &str) -> Option<String> {
+    let entities = body.get("entities")?.as_object()?;
+    entities.values().find_map(|entity| {
+        let redirects = entity.get("redirects")?;
+        let from = redirects.get("from")?.as_str()?;
+        let to = redirects.get("to")?.as_str()?;
+        (from.eq_ignore_ascii_case(qid) && !to.eq_ignore_ascii_case(qid)).then(|| to.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_redirect_target_reads_wbgetentities_redirects() {
+        let redirected = serde_json::json!({
+            "entities": {
+                "Q42": {
+                    "type": "item",
+                    "id": "Q42",
+                    "redirects": { "from": "Q100", "to": "Q42" }
+                }
+            },
+            "success": 1
+        });
+        assert_eq!(
+            parse_redirect_target(&redirected, "Q100").as_deref(),
+            Some("Q42")
+        );
+        assert_eq!(parse_redirect_target(&redirected, "Q7"), None);
+
+        let plain = serde_json::json!({
+            "entities": { "Q42": { "type": "item", "id": "Q42" } },
+            "success": 1
+        });
+        assert_eq!(parse_redirect_target(&plain, "Q42"), None);
+
+        let missing = serde_json::json!({
+            "entities": { "Q999999999": { "id": "Q999999999", "missing": "" } }
+        });
+        assert_eq!(parse_redirect_target(&missing, "Q999999999"), None);
+    }
+}
@@ -0,0 +1,300 @@
+use std::sync::Arc;
+
+use gotong_domain::chat::{ChatMessage, ChatService};
+use gotong_domain::jobs::{ChatRetentionSweepPayload, now_ms};
+use gotong_domain::ports::chat::ChatRepository;
+use gotong_domain::ports::jobs::JobEnvelope;
+use gotong_infra::chat_attachment_storage::ChatAttachmentStorage;
+use gotong_infra::config::AppConfig;
+use serde::Serialize;
+use tracing::{info, warn};
+
+/// Messages expired per thread per sweep; the rest are picked up by the next run.
+const CHAT_RETENTION_BATCH: usize = 200;
+const CHAT_REALTIME_SENDER_ID: &str = "worker:chat_retention";
+
+#[derive(Serialize)]
+struct ChatRealtimeEnvelope<'a> {
+    thread_id: &'a str,
+    sender_id: &'a str,
+    message: &'a ChatMessage,
+}
+
+/// Connection for pushing tombstones to connected chat clients through the Redis
+/// realtime channel the API instances subscribe to; opened once per sweep. With the
+/// local transport there is nobody to tell.
+async fn tombstone_connection(
+    config: &AppConfig,
+) -> anyhow::Result<Option<redis::aio::MultiplexedConnection>> {
+    if !config
+        .chat_realtime_transport
+        .trim()
+        .eq_ignore_ascii_case("redis")
+    {
+        return Ok(None);
+    }
+    let client = redis::Client::open(config.redis_url.clone())?;
+    Ok(Some(client.get_multiplexed_async_connection().await?))
+}
+
+async fn publish_tombstone(
+    config: &AppConfig,
+    connection: &mut redis::aio::MultiplexedConnection,
+    message: &ChatMessage,
+) -> anyhow::Result<()> {
+    let envelope = serde_json::to_string(&ChatRealtimeEnvelope {
+        thread_id: &message.thread_id,
+        sender_id: CHAT_REALTIME_SENDER_ID,
+        message,
+    })?;
+    redis::cmd("PUBLISH")
+        .arg(format!(
+            "{}:{}",
+            config.chat_realtime_channel_prefix, message.thread_id
+        ))
+        .arg(envelope)
+        .query_async::<_, i64>(connection)
+        .await?;
+    Ok(())
+}
+
+pub fn parse_chat_retention_sweep_payload(
+    job: &JobEnvelope,
+) -> anyhow::Result<ChatRetentionSweepPayload> {
+    let payload: ChatRetentionSweepPayload = serde_json::from_value(job.payload.clone())
+        .map_err(|err| anyhow::anyhow!("invalid chat retention payload: {err}"))?;
+    if payload.scheduled_ms < 0 {
+        return Err(anyhow::anyhow!(
+            "invalid chat retention payload: scheduled_ms must be non-negative"
+        ));
+    }
+    Ok(payload)
+}
+
+/// Tombstones messages that outlived their thread's retention setting. Attachment
+/// objects are removed before the message is touched, so a storage failure leaves the
+/// message in place to be retried on the next sweep.
+pub async fn handle_chat_retention_sweep(
+    config: &AppConfig,
+    chat_repo: Option<&Arc<dyn ChatRepository>>,
+    attachment_store: Option<&ChatAttachmentStorage>,
+    job: &JobEnvelope,
+) -> anyhow::Result<()> {
+    parse_chat_retention_sweep_payload(job)?;
+    let Some(repo) = chat_repo else {
+        warn!(
+            job_id = %job.job_id,
+            "skipping chat retention sweep: chat repository is unavailable"
+        );
+        return Ok(());
+    };
+    let service = ChatService::new(repo.clone());
+    let threads = service
+        .list_threads_with_retention()
+        .await
+        .map_err(|err| anyhow::anyhow!("chat retention thread lookup failed: {err}"))?;
+
+    let mut realtime = match tombstone_connection(config).await {
+        Ok(connection) => connection,
+        Err(err) => {
+            warn!(
+                job_id = %job.job_id,
+                error = %err,
+                "chat retention cannot publish tombstones: realtime connection failed"
+            );
+            None
+        }
+    };
+
+    let mut expired_messages = 0usize;
+    let mut removed_attachments = 0usize;
+    let mut skipped_messages = 0usize;
+    for thread in &threads {
+        let now = now_ms();
+        let expired = service
+            .list_expired_messages(thread, now, CHAT_RETENTION_BATCH)
+            .await
+            .map_err(|err| anyhow::anyhow!("chat retention message lookup failed: {err}"))?;
+        'messages: for item in expired {
+            for attachment in &item.attachments {
+                let Some(store) = attachment_store else {
+                    warn!(
+                        job_id = %job.job_id,
+                        thread_id = %thread.thread_id,
+                        "chat retention cannot remove attachments: storage is unavailable"
+                    );
+                    skipped_messages += 1;
+                    continue 'messages;
+                };
+                if let Err(err) = store.remove(&attachment.attachment_id).await {
+                    warn!(
+                        job_id = %job.job_id,
+                        attachment_id = %attachment.attachment_id,
+                        error = %err,
+                        "failed to remove expired chat attachment"
+                    );
+                    skipped_messages += 1;
+                    continue 'messages;
+                }
+            }
+            let tombstone = service
+                .expire_message(&item, now)
+                .await
+                .map_err(|err| anyhow::anyhow!("chat retention expire failed: {err}"))?;
+            removed_attachments += item.attachments.len();
+            expired_messages += 1;
+            let Some(connection) = realtime.as_mut() else {
+                continue;
+            };
+            if let Err(err) = publish_tombstone(config, connection, &tombstone).await {
+                warn!(
+                    job_id = %job.job_id,
+                    message_id = %tombstone.message_id,
+                    error = %err,
+                    "failed to publish chat retention tombstone"
+                );
+            }
+        }
+    }
+
+    info!(
+        job_id = %job.job_id,
+        threads = threads.len(),
+        expired_messages,
+        removed_attachments,
+        skipped_messages,
+        "handled chat retention sweep job"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gotong_domain::chat::{ChatRetentionPolicy, ChatThreadCreate, SendMessageInput};
+    use gotong_domain::identity::ActorIdentity;
+    use gotong_domain::ports::jobs::JobType;
+    use gotong_infra::chat_attachment_storage::CHAT_ATTACHMENT_OBJECT_SUFFIXES;
+    use gotong_infra::repositories::InMemoryChatRepository;
+    use serde_json::json;
+
+    fn sweep_job() -> JobEnvelope {
+        JobEnvelope {
+            job_id: "job-retention".to_string(),
+            job_type: JobType::ChatRetentionSweep,
+            payload: json!({ "scheduled_ms": 1_000 }),
+            request_id: "req-retention".to_string(),
+            correlation_id: "corr-retention".to_string(),
+            attempt: 1,
+            max_attempts: 1,
+            run_at_ms: 1_000,
+            created_at_ms: 1_000,
+        }
+    }
+
+    #[test]
+    fn parse_chat_retention_sweep_payload_rejects_negative_scheduled_ms() {
+        let mut job = sweep_job();
+        job.payload = json!({ "scheduled_ms": -1 });
+        assert!(parse_chat_retention_sweep_payload(&job).is_err());
+    }
+
+    #[tokio::test]
+    async fn handle_chat_retention_sweep_tombstones_expired_messages_and_files() {
+        let repo: Arc<dyn ChatRepository> = Arc::new(InMemoryChatRepository::new());
+        let service = ChatService::new(repo.clone());
+        let actor = ActorIdentity {
+            user_id: "u-1".to_string(),
+            username: "alice".to_string(),
+        };
+        let thread = service
+            .create_thread(
+                &actor,
+                "req-thread".to_string(),
+                "corr-thread".to_string(),
+                ChatThreadCreate {
+                    scope_id: "scope-1".to_string(),
+                    privacy_level: "private".to_string(),
+                },
+            )
+            .await
+            .expect("thread");
+        service
+            .set_thread_retention(&actor, &thread.thread_id, ChatRetentionPolicy::Day)
+            .await
+            .expect("retention");
+
+        let root = std::env::temp_dir().join(format!(
+            "gotong-chat-retention-test-{}",
+            gotong_domain::util::uuid_v7_without_dashes()
+        ));
+        tokio::fs::create_dir_all(&root).await.expect("root");
+        for suffix in CHAT_ATTACHMENT_OBJECT_SUFFIXES {
+            tokio::fs::write(root.join(format!("att-1.{suffix}")), b"x")
+                .await
+                .expect("object");
+        }
+        service
+            .record_attachment(
+                &actor,
+                gotong_domain::chat::ChatAttachment {
+                    attachment_id: "att-1".to_string(),
+                    uploaded_by: actor.user_id.clone(),
+                    thread_id: Some(thread.thread_id.clone()),
+                    file_name: "foto.jpg".to_string(),
+                    mime_type: "image/jpeg".to_string(),
+                    media_type: "image".to_string(),
+                    size_bytes: 1,
+                    width: None,
+                    height: None,
+                    thumbnail_size_bytes: Some(1),
+                    created_at_ms: 1_000,
+                },
+                &gotong_domain::chat::ChatAttachmentQuota {
+                    per_user_bytes: 1_024,
+                    per_thread_bytes: 1_024,
+                },
+            )
+            .await
+            .expect("attachment");
+        let old = service
+            .send_message(
+                &actor,
+                SendMessageInput {
+                    thread_id: thread.thread_id.clone(),
+                    body: "rahasia".to_string(),
+                    attachments: vec![json!({ "attachment_id": "att-1" })],
+                    request_id: "msg-old".to_string(),
+                    correlation_id: "corr-old".to_string(),
+                    occurred_at_ms: Some(1_000),
+                },
+            )
+            .await
+            .expect("message");
+
+        let mut config = AppConfig::load().expect("config");
+        config.chat_realtime_transport = "local".to_string();
+        let store = ChatAttachmentStorage::Local { root: root.clone() };
+        handle_chat_retention_sweep(&config, Some(&repo), Some(&store), &sweep_job())
+            .await
+            .expect("sweep");
+
+        let tombstone = repo
+            .get_message(&thread.thread_id, &old.message_id)
+            .await
+            .expect("lookup")
+            .expect("tombstone kept");
+        assert!(tombstone.deleted_at_ms.is_some());
+        assert!(tombstone.body.is_empty());
+        assert!(
+            repo.get_attachment("att-1")
+                .await
+                .expect("lookup")
+                .is_none()
+        );
+        for suffix in CHAT_ATTACHMENT_OBJECT_SUFFIXES {
+            assert!(!root.join(format!("att-1.{suffix}")).exists());
+        }
+        let _ = tokio::fs::remove_dir_all(&root).await;
+    }
+}
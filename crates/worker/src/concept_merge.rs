@@ -0,0 +1,296 @@
+use std::sync::Arc;
+
+use gotong_domain::jobs::{ConceptMergePayload, JobDefaults, new_job};
+use gotong_domain::ontology::CONCEPT_MERGE_REASON_WIKIDATA_REDIRECT;
+use gotong_domain::ontology_merge::{ConceptMergeCommand, merge_ontology_concepts};
+use gotong_domain::ports::jobs::{JobEnvelope, JobQueue, JobType};
+use gotong_domain::ports::ontology::{ConceptRedirectLookup, OntologyRepository};
+use tracing::{info, warn};
+
+/// Detects QIDs that Wikidata has merged away; only built when `WIKIDATA_API_URL` is set.
+pub struct ConceptRedirectChecker {
+    lookup: Arc<dyn ConceptRedirectLookup>,
+    queue: Arc<dyn JobQueue>,
+}
+
+impl ConceptRedirectChecker {
+    pub fn new(lookup: Arc<dyn ConceptRedirectLookup>, queue: Arc<dyn JobQueue>) -> Self {
+        Self { lookup, queue }
+    }
+
+    /// Queues a merge when `qid` redirects upstream and returns the target. A failed
+    /// lookup is logged and treated as "not redirected" so verification still runs.
+    pub async fn queue_merge_if_redirected(
+        &self,
+        qid: &str,
+        job: &JobEnvelope,
+        now_ms: i64,
+    ) -> anyhow::Result<Option<String>> {
+        let target = match self.lookup.redirect_target(qid).await {
+            Ok(Some(target)) if target != qid => target,
+            Ok(_) => return Ok(None),
+            Err(err) => {
+                warn!(
+                    job_id = %job.job_id,
+                    qid = %qid,
+                    error = %err,
+                    "wikidata redirect lookup failed; verifying concept as-is"
+                );
+                return Ok(None);
+            }
+        };
+        let payload = ConceptMergePayload {
+            from_qid: qid.to_string(),
+            into_qid: target.clone(),
+            reason: CONCEPT_MERGE_REASON_WIKIDATA_REDIRECT.to_string(),
+            scheduled_ms: now_ms,
+        };
+        let merge_job = new_job(
+            format!("system:concept_merge:{qid}:{target}"),
+            JobType::ConceptMerge,
+            serde_json::to_value(&payload)?,
+            job.request_id.clone(),
+            job.correlation_id.clone(),
+            JobDefaults::default(),
+        );
+        self.queue
+            .enqueue(&merge_job)
+            .await
+            .map_err(|err| anyhow::anyhow!("failed to enqueue concept merge: {err}"))?;
+        info!(
+            job_id = %job.job_id,
+            qid = %qid,
+            into_qid = %target,
+            "queued concept merge for upstream redirect"
+        );
+        Ok(Some(target))
+    }
+}
+
+pub fn parse_concept_merge_payload(job: &JobEnvelope) -> anyhow::Result<ConceptMergePayload> {
+    let payload: ConceptMergePayload = serde_json::from_value(job.payload.clone())
+        .map_err(|err| anyhow::anyhow!("invalid concept merge payload: {err}"))?;
+    if payload.scheduled_ms < 0 {
+        return Err(anyhow::anyhow!(
+            "invalid concept merge payload: scheduled_ms must be non-negative"
+        ));
+    }
+    if payload.from_qid.trim().is_empty() || payload.into_qid.trim().is_empty() {
+        return Err(anyhow::anyhow!(
+            "invalid concept merge payload: from_qid and into_qid are required"
+        ));
+    }
+    Ok(payload)
+}
+
+pub async fn handle_concept_merge(
+    ontology_repo: Option<&Arc<dyn OntologyRepository>>,
+    job: &JobEnvelope,
+) -> anyhow::Result<()> {
+    let payload = parse_concept_merge_payload(job)?;
+    let Some(repo) = ontology_repo else {
+        warn!(
+            job_id = %job.job_id,
+            "skipping concept merge job: ontology repository is unavailable"
+        );
+        return Ok(());
+    };
+
+    let command = ConceptMergeCommand {
+        from_qid: payload.from_qid,
+        into_qid: payload.into_qid,
+        reason: payload.reason,
+    };
+    let result = merge_ontology_concepts(repo.as_ref(), &command, payload.scheduled_ms)
+        .await
+        .map_err(|err| anyhow::anyhow!("failed to merge concepts: {err}"))?;
+    info!(
+        job_id = %job.job_id,
+        from_qid = %result.redirect.from_qid,
+        into_qid = %result.surviving.qid,
+        moved_edges = result.moved_edge_count,
+        "handled concept merge job"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use super::*;
+    use gotong_domain::DomainResult;
+    use gotong_domain::error::DomainError;
+    use gotong_domain::ontology::OntologyConcept;
+    use gotong_domain::ports::BoxFuture;
+    use gotong_domain::ports::jobs::JobQueueError;
+    use gotong_infra::repositories::InMemoryOntologyRepository;
+
+    struct FixedLookup {
+        redirects: HashMap<String, String>,
+    }
+
+    impl ConceptRedirectLookup for FixedLookup {
+        fn redirect_target(&self, qid: &str) -> BoxFuture<'_, DomainResult<Option<String>>> {
+            let result = if qid == "Q-broken" {
+                Err(DomainError::Validation("wikidata unavailable".into()))
+            } else {
+                Ok(self.redirects.get(qid).cloned())
+            };
+            Box::pin(async move { result })
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingQueue {
+        jobs: Mutex<Vec<JobEnvelope>>,
+    }
+
+    impl JobQueue for RecordingQueue {
+        fn enqueue(&self, job: &JobEnvelope) -> BoxFuture<'_, Result<(), JobQueueError>> {
+            self.jobs.lock().unwrap().push(job.clone());
+            Box::pin(async { Ok(()) })
+        }
+
+        fn dequeue(
+            &self,
+            _timeout: Duration,
+        ) -> BoxFuture<'_, Result<Option<JobEnvelope>, JobQueueError>> {
+            Box::pin(async { Ok(None) })
+        }
+
+        fn ack(&self, _job_id: &str) -> BoxFuture<'_, Result<(), JobQueueError>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn promote_due(
+            &self,
+            _now_ms: i64,
+            _limit: usize,
+        ) -> BoxFuture<'_, Result<usize, JobQueueError>> {
+            Box::pin(async { Ok(0) })
+        }
+
+        fn requeue_processing(&self, _limit: usize) -> BoxFuture<'_, Result<usize, JobQueueError>> {
+            Box::pin(async { Ok(0) })
+        }
+    }
+
+    fn verification_job() -> JobEnvelope {
+        new_job(
+            "system:concept_verification:0:Q100".to_string(),
+            JobType::ConceptVerification,
+            serde_json::json!({ "qid": "Q100", "scheduled_ms": 1_000 }),
+            "req-1".to_string(),
+            "corr-1".to_string(),
+            JobDefaults::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn redirect_checker_queues_merge_only_for_redirected_qids() {
+        let queue = Arc::new(RecordingQueue::default());
+        let checker = ConceptRedirectChecker::new(
+            Arc::new(FixedLookup {
+                redirects: HashMap::from([("Q100".to_string(), "Q42".to_string())]),
+            }),
+            queue.clone(),
+        );
+        let job = verification_job();
+
+        let target = checker
+            .queue_merge_if_redirected("Q100", &job, 1_000)
+            .await
+            .expect("redirected");
+        assert_eq!(target.as_deref(), Some("Q42"));
+        assert_eq!(
+            checker
+                .queue_merge_if_redirected("Q42", &job, 1_000)
+                .await
+                .expect("plain"),
+            None
+        );
+        assert_eq!(
+            checker
+                .queue_merge_if_redirected("Q-broken", &job, 1_000)
+                .await
+                .expect("lookup failure is tolerated"),
+            None
+        );
+
+        let jobs = queue.jobs.lock().unwrap().clone();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].job_type, JobType::ConceptMerge);
+        assert_eq!(jobs[0].job_id, "system:concept_merge:Q100:Q42");
+        let payload = parse_concept_merge_payload(&jobs[0]).expect("payload");
+        assert_eq!(payload.from_qid, "Q100");
+        assert_eq!(payload.into_qid, "Q42");
+        assert_eq!(payload.reason, CONCEPT_MERGE_REASON_WIKIDATA_REDIRECT);
+    }
+
+    #[tokio::test]
+    async fn handle_concept_merge_redirects_losing_qid() {
+        let repo: Arc<dyn OntologyRepository> = Arc::new(InMemoryOntologyRepository::new());
+        for qid in ["Q100", "Q42"] {
+            repo.upsert_concept(&OntologyConcept {
+                concept_id: qid.to_string(),
+                qid: qid.to_string(),
+                label_id: None,
+                label_en: Some(format!("label {qid}")),
+                verified: true,
+            })
+            .await
+            .expect("upsert concept");
+        }
+        let job = new_job(
+            "system:concept_merge:Q100:Q42".to_string(),
+            JobType::ConceptMerge,
+            serde_json::json!({
+                "from_qid": "Q100",
+                "into_qid": "Q42",
+                "reason": CONCEPT_MERGE_REASON_WIKIDATA_REDIRECT,
+                "scheduled_ms": 1_000,
+            }),
+            "req-1".to_string(),
+            "corr-1".to_string(),
+            JobDefaults::default(),
+        );
+
+        handle_concept_merge(Some(&repo), &job)
+            .await
+            .expect("merge");
+        // Replaying the job is a no-op rather than a conflict.
+        handle_concept_merge(Some(&repo), &job)
+            .await
+            .expect("replayed merge");
+
+        let resolved = repo
+            .get_concept_by_qid("Q100")
+            .await
+            .expect("get concept")
+            .expect("redirect resolves");
+        assert_eq!(resolved.qid, "Q42");
+        let redirect = repo
+            .get_concept_redirect("Q100")
+            .await
+            .expect("get redirect")
+            .expect("redirect recorded");
+        assert_eq!(redirect.to_qid, "Q42");
+        assert_eq!(redirect.reason, CONCEPT_MERGE_REASON_WIKIDATA_REDIRECT);
+    }
+
+    #[test]
+    fn parse_concept_merge_payload_requires_both_qids() {
+        let mut job = verification_job();
+        job.job_type = JobType::ConceptMerge;
+        job.payload = serde_json::json!({
+            "from_qid": "Q100",
+            "into_qid": " ",
+            "reason": "admin",
+            "scheduled_ms": 0,
+        });
+        assert!(parse_concept_merge_payload(&job).is_err());
+    }
+}
@@ -0,0 +1,350 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use gotong_domain::digest::{DigestSubscription, render_digest, sign_unsubscribe_token};
+use gotong_domain::discovery::DiscoveryService;
+use gotong_domain::jobs::{DigestSendPayload, now_ms};
+use gotong_domain::ports::digest::{DigestSubscriptionRepository, MailTransport, OutboundEmail};
+use gotong_domain::ports::discovery::{FeedRepository, NotificationRepository};
+use gotong_domain::ports::jobs::JobEnvelope;
+use gotong_domain::ports::notification_preferences::NotificationPreferenceRepository;
+use gotong_infra::config::AppConfig;
+use tracing::{info, warn};
+
+/// Subscriptions fetched per page while scanning for due digests.
+const DIGEST_SCAN_PAGE_SIZE: usize = 200;
+
+/// Everything the digest job needs; only built when the data backend and mail transport exist.
+pub struct DigestSender {
+    discovery: DiscoveryService,
+    subscriptions: Arc<dyn DigestSubscriptionRepository>,
+    mail: Arc<dyn MailTransport>,
+}
+
+impl DigestSender {
+    pub fn new(
+        feed_repo: Arc<dyn FeedRepository>,
+        notification_repo: Arc<dyn NotificationRepository>,
+        notification_preferences: Arc<dyn NotificationPreferenceRepository>,
+        subscriptions: Arc<dyn DigestSubscriptionRepository>,
+        mail: Arc<dyn MailTransport>,
+    ) -> Self {
+        Self {
+            discovery: DiscoveryService::new(feed_repo, notification_repo)
+                .with_notification_preferences(notification_preferences),
+            subscriptions,
+            mail,
+        }
+    }
+
+    /// Renders and sends one digest. Returns `false` when the window had nothing to report.
+    async fn send(
+        &self,
+        config: &AppConfig,
+        subscription: &DigestSubscription,
+        window_start_ms: i64,
+        window_end_ms: i64,
+    ) -> anyhow::Result<bool> {
+        let digest = self
+            .discovery
+            .email_digest(&subscription.user_id, window_start_ms, window_end_ms)
+            .await?;
+        if digest.events.is_empty() {
+            return Ok(false);
+        }
+        let unsubscribe_url = unsubscribe_url(config, &subscription.user_id)?;
+        let rendered = render_digest(&digest, subscription, &unsubscribe_url)?;
+        let email = OutboundEmail {
+            to: subscription.email.clone(),
+            subject: rendered.subject,
+            text_body: rendered.text,
+            html_body: rendered.html,
+            headers: BTreeMap::from([
+                (
+                    "List-Unsubscribe".to_string(),
+                    format!("<{unsubscribe_url}>"),
+                ),
+                (
+                    "List-Unsubscribe-Post".to_string(),
+                    "List-Unsubscribe=One-Click".to_string(),
+                ),
+            ]),
+        };
+        self.mail.send(&email).await?;
+        Ok(true)
+    }
+}
+
+fn unsubscribe_url(config: &AppConfig, user_id: &str) -> anyhow::Result<String> {
+    let token = sign_unsubscribe_token(&config.digest_unsubscribe_secret, user_id)?;
+    Ok(format!(
+        "{}/v1/digest/unsubscribe?token={token}",
+        config.digest_public_base_url.trim().trim_end_matches('/')
+    ))
+}
+
+pub fn parse_digest_send_payload(job: &JobEnvelope) -> anyhow::Result<DigestSendPayload> {
+    let payload: DigestSendPayload = serde_json::from_value(job.payload.clone())
+        .map_err(|err| anyhow::anyhow!("invalid digest send payload: {err}"))?;
+    if payload.scheduled_ms < 0 {
+        return Err(anyhow::anyhow!(
+            "invalid digest send payload: scheduled_ms must be non-negative"
+        ));
+    }
+    Ok(payload)
+}
+
+/// Sends every digest whose local send time has passed since the user's last delivery.
+/// Each user is marked as sent right after delivery, so a failed run only retries the
+/// users that did not get their email.
+pub async fn handle_digest_send(
+    config: &AppConfig,
+    sender: Option<&DigestSender>,
+    job: &JobEnvelope,
+) -> anyhow::Result<()> {
+    parse_digest_send_payload(job)?;
+    let Some(sender) = sender else {
+        warn!(
+            job_id = %job.job_id,
+            "skipping digest send: digest repositories or mail transport are unavailable"
+        );
+        return Ok(());
+    };
+
+    let mut scanned = 0usize;
+    let mut sent = 0usize;
+    let mut empty = 0usize;
+    let mut failed = 0usize;
+    let mut after_user_id: Option<String> = None;
+    loop {
+        let page = sender
+            .subscriptions
+            .list_active_subscriptions(after_user_id.as_deref(), DIGEST_SCAN_PAGE_SIZE)
+            .await
+            .map_err(|err| anyhow::anyhow!("digest subscription scan failed: {err}"))?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        after_user_id = Some(last.user_id.clone());
+        let page_len = page.len();
+
+        for subscription in page {
+            scanned += 1;
+            let now = now_ms();
+            let (window_start_ms, window_end_ms) = match subscription.due_window(now) {
+                Ok(Some(window)) => window,
+                Ok(None) => continue,
+                Err(err) => {
+                    warn!(
+                        job_id = %job.job_id,
+                        user_id = %subscription.user_id,
+                        error = %err,
+                        "skipping digest subscription with invalid schedule"
+                    );
+                    continue;
+                }
+            };
+            match sender
+                .send(config, &subscription, window_start_ms, window_end_ms)
+                .await
+            {
+                Ok(delivered) => {
+                    if delivered {
+                        sent += 1;
+                    } else {
+                        empty += 1;
+                    }
+                    if let Err(err) = sender
+                        .subscriptions
+                        .mark_sent(&subscription.user_id, now)
+                        .await
+                    {
+                        failed += 1;
+                        warn!(
+                            job_id = %job.job_id,
+                            user_id = %subscription.user_id,
+                            error = %err,
+                            "failed to record digest delivery"
+                        );
+                    }
+                }
+                Err(err) => {
+                    failed += 1;
+                    warn!(
+                        job_id = %job.job_id,
+                        user_id = %subscription.user_id,
+                        error = %err,
+                        "failed to send digest"
+                    );
+                }
+            }
+        }
+
+        if page_len < DIGEST_SCAN_PAGE_SIZE {
+            break;
+        }
+    }
+
+    info!(
+        job_id = %job.job_id,
+        scanned,
+        sent,
+        empty,
+        failed,
+        "handled digest send job"
+    );
+    if failed > 0 {
+        anyhow::bail!("digest send failed for {failed} subscriptions");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gotong_domain::digest::{DigestFrequency, DigestLocale, verify_unsubscribe_token};
+    use gotong_domain::discovery::InAppNotification;
+    use gotong_domain::ports::jobs::JobType;
+    use gotong_infra::mail::CapturedMailTransport;
+    use gotong_infra::repositories::{
+        InMemoryDigestSubscriptionRepository, InMemoryDiscoveryFeedRepository,
+        InMemoryDiscoveryNotificationRepository, InMemoryNotificationPreferenceRepository,
+    };
+    use serde_json::json;
+
+    fn digest_job() -> JobEnvelope {
+        JobEnvelope {
+            job_id: "job-digest".to_string(),
+            job_type: JobType::DigestSend,
+            payload: json!({ "scheduled_ms": 1_000 }),
+            request_id: "req-digest".to_string(),
+            correlation_id: "corr-digest".to_string(),
+            attempt: 1,
+            max_attempts: 1,
+            run_at_ms: 1_000,
+            created_at_ms: 1_000,
+        }
+    }
+
+    fn subscription(user_id: &str, locale: DigestLocale) -> DigestSubscription {
+        DigestSubscription {
+            user_id: user_id.to_string(),
+            email: format!("{user_id}@example.org"),
+            locale,
+            timezone: "Asia/Jakarta".to_string(),
+            frequency: DigestFrequency::Daily,
+            send_hour_local: 7,
+            send_weekday: 1,
+            last_sent_at_ms: None,
+            created_at_ms: 0,
+            updated_at_ms: 0,
+        }
+    }
+
+    fn notification(user_id: &str, created_at_ms: i64) -> InAppNotification {
+        InAppNotification {
+            notification_id: format!("notif-{user_id}"),
+            user_id: user_id.to_string(),
+            actor_id: "user-2".to_string(),
+            actor_username: "budi".to_string(),
+            notification_type: "mention".to_string(),
+            source_type: "contribution".to_string(),
+            source_id: "c-1".to_string(),
+            title: "Kerja bakti saluran air".to_string(),
+            body: "Budi menyebut Anda".to_string(),
+            payload: None,
+            privacy_level: Some("public".to_string()),
+            created_at_ms,
+            read_at_ms: None,
+            deliver_at_ms: None,
+            request_id: "req-notif".to_string(),
+            correlation_id: "corr-notif".to_string(),
+            dedupe_key: format!("dedupe-{user_id}"),
+            group_key: None,
+            in_app_hidden: false,
+        }
+    }
+
+    #[test]
+    fn parse_digest_send_payload_rejects_negative_scheduled_ms() {
+        let mut job = digest_job();
+        job.payload = json!({ "scheduled_ms": -1 });
+        assert!(parse_digest_send_payload(&job).is_err());
+    }
+
+    #[tokio::test]
+    async fn handle_digest_send_delivers_due_digests_once_with_unsubscribe_link() {
+        let mut config = AppConfig::load().expect("config");
+        config.digest_unsubscribe_secret = "worker-test-digest-secret".to_string();
+        config.digest_public_base_url = "https://gotong.example/".to_string();
+        let subscriptions = Arc::new(InMemoryDigestSubscriptionRepository::new());
+        let notifications = Arc::new(InMemoryDiscoveryNotificationRepository::new());
+        let mail = CapturedMailTransport::new();
+
+        let mut off = subscription("user-off", DigestLocale::Id);
+        off.frequency = DigestFrequency::Off;
+        for row in [
+            subscription("user-en", DigestLocale::En),
+            subscription("user-quiet", DigestLocale::Id),
+            off,
+        ] {
+            subscriptions
+                .upsert_subscription(&row)
+                .await
+                .expect("subscription");
+        }
+        let (_, window_end_ms) = subscription("user-en", DigestLocale::En)
+            .due_window(now_ms())
+            .expect("schedule")
+            .expect("due");
+        for user_id in ["user-en", "user-off"] {
+            notifications
+                .create_notification(&notification(user_id, window_end_ms - 60_000))
+                .await
+                .expect("notification");
+        }
+
+        let sender = DigestSender::new(
+            Arc::new(InMemoryDiscoveryFeedRepository::new()),
+            notifications,
+            Arc::new(InMemoryNotificationPreferenceRepository::new()),
+            subscriptions.clone(),
+            Arc::new(mail.clone()),
+        );
+        handle_digest_send(&config, Some(&sender), &digest_job())
+            .await
+            .expect("digest send");
+
+        let sent = mail.sent();
+        assert_eq!(sent.len(), 1);
+        let email = &sent[0];
+        assert_eq!(email.to, "user-en@example.org");
+        assert!(email.subject.starts_with("Your Gotong daily digest"));
+        assert!(email.text_body.contains("Kerja bakti saluran air"));
+        let token = email
+            .headers
+            .get("List-Unsubscribe")
+            .expect("unsubscribe header")
+            .trim_matches(|ch| ch == '<' || ch == '>')
+            .strip_prefix("https://gotong.example/v1/digest/unsubscribe?token=")
+            .expect("unsubscribe url")
+            .to_string();
+        assert_eq!(
+            verify_unsubscribe_token(&config.digest_unsubscribe_secret, &token).expect("token"),
+            "user-en"
+        );
+        for user_id in ["user-en", "user-quiet"] {
+            let stored = subscriptions
+                .get_subscription(user_id)
+                .await
+                .expect("get")
+                .expect("row");
+            assert!(stored.last_sent_at_ms.is_some(), "{user_id} marked");
+        }
+
+        handle_digest_send(&config, Some(&sender), &digest_job())
+            .await
+            .expect("second run");
+        assert_eq!(mail.sent().len(), 1, "already-sent slot is not resent");
+    }
+}
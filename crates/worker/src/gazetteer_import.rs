@@ -0,0 +1,473 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::sync::Arc;
+
+use gotong_domain::geo::GeoPoint;
+use gotong_domain::ontology_gazetteer::{AdminArea, normalize_admin_code};
+use gotong_domain::ports::ontology::OntologyRepository;
+use gotong_infra::config::AppConfig;
+use gotong_infra::db::DbConfig;
+use gotong_infra::repositories::SurrealOntologyRepository;
+use serde_json::Value;
+use tracing::warn;
+
+#[derive(Debug, Clone)]
+pub struct GazetteerImportOptions {
+    /// CSV of `code,name[,lat,lng]`, one area per line.
+    pub areas_file: String,
+    /// GeoJSON `FeatureCollection` of Polygon / MultiPolygon boundaries.
+    pub boundaries_file: Option<String>,
+    /// Feature property holding the area code.
+    pub code_property: String,
+    pub dry_run: bool,
+    pub batch_size: usize,
+}
+
+impl Default for GazetteerImportOptions {
+    fn default() -> Self {
+        Self {
+            areas_file: String::new(),
+            boundaries_file: None,
+            code_property: "kode".to_string(),
+            dry_run: false,
+            batch_size: 500,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GazetteerImportSummary {
+    pub lines: u64,
+    pub imported: u64,
+    pub with_boundary: u64,
+    pub missing_centroid: u64,
+    pub invalid: u64,
+    pub unmatched_boundaries: u64,
+}
+
+/// One CSV row before boundaries are attached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GazetteerRow {
+    pub code: String,
+    pub name: String,
+    pub centroid: Option<GeoPoint>,
+}
+
+pub fn parse_gazetteer_import_options(args: &[String]) -> anyhow::Result<GazetteerImportOptions> {
+    let mut opts = GazetteerImportOptions::default();
+    let mut idx = 0usize;
+    while idx < args.len() {
+        match args[idx].as_str() {
+            "--areas" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --areas"))?;
+                opts.areas_file = value.trim().to_string();
+                idx += 2;
+            }
+            "--boundaries" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --boundaries"))?;
+                if value.trim().is_empty() {
+                    return Err(anyhow::anyhow!("--boundaries must not be empty"));
+                }
+                opts.boundaries_file = Some(value.trim().to_string());
+                idx += 2;
+            }
+            "--code-property" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --code-property"))?;
+                if value.trim().is_empty() {
+                    return Err(anyhow::anyhow!("--code-property must not be empty"));
+                }
+                opts.code_property = value.trim().to_string();
+                idx += 2;
+            }
+            "--dry-run" => {
+                opts.dry_run = true;
+                idx += 1;
+            }
+            "--batch-size" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --batch-size"))?;
+                let parsed = value
+                    .parse::<usize>()
+                    .map_err(|err| anyhow::anyhow!("invalid --batch-size value: {err}"))?;
+                if parsed == 0 {
+                    return Err(anyhow::anyhow!("--batch-size must be >= 1"));
+                }
+                opts.batch_size = parsed.min(5_000);
+                idx += 2;
+            }
+            other => {
+                return Err(anyhow::anyhow!(
+                    "unknown argument for gazetteer-import: {other}"
+                ));
+            }
+        }
+    }
+    if opts.areas_file.is_empty() {
+        return Err(anyhow::anyhow!(
+            "gazetteer-import needs --areas <wilayah.csv>"
+        ));
+    }
+    Ok(opts)
+}
+
+/// Parses `code,name[,lat,lng]`. Blank lines, `#` comments and a header row
+/// (any first field that is not a code, e.g. `kode`) yield `None`.
+pub fn parse_area_line(line: &str) -> anyhow::Result<Option<GazetteerRow>> {
+    let line = line.trim().trim_start_matches('\u{feff}');
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    let fields = split_csv_line(line);
+    let code = fields.first().map(String::as_str).unwrap_or_default();
+    if !code.bytes().any(|b| b.is_ascii_digit()) {
+        return Ok(None);
+    }
+    let code = normalize_admin_code(code).map_err(|err| anyhow::anyhow!("{err}"))?;
+    let name = fields
+        .get(1)
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("area {code} has no name"))?;
+    let coordinate = |index: usize| -> anyhow::Result<Option<f64>> {
+        match fields.get(index).map(|value| value.trim()) {
+            None | Some("") => Ok(None),
+            Some(value) => value
+                .parse::<f64>()
+                .map(Some)
+                .map_err(|err| anyhow::anyhow!("area {code} has an invalid coordinate: {err}")),
+        }
+    };
+    let centroid = match (coordinate(2)?, coordinate(3)?) {
+        (Some(lat), Some(lng)) => {
+            Some(GeoPoint::new(lat, lng).map_err(|err| anyhow::anyhow!("area {code}: {err}"))?)
+        }
+        (None, None) => None,
+        _ => return Err(anyhow::anyhow!("area {code} needs both lat and lng")),
+    };
+    Ok(Some(GazetteerRow {
+        code,
+        name,
+        centroid,
+    }))
+}
+
+/// Comma-separated fields; double quotes wrap fields with commas and `""`
+/// escapes a quote inside them.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(ch),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Outer rings by area code. Holes are dropped; features without a valid
+/// code or a Polygon / MultiPolygon geometry are skipped with a warning.
+pub fn parse_boundaries(
+    collection: &Value,
+    code_property: &str,
+) -> anyhow::Result<HashMap<String, Vec<Vec<GeoPoint>>>> {
+    let features = collection
+        .get("features")
+        .and_then(Value::as_array)
+        .ok_or_else(|| anyhow::anyhow!("boundaries must be a GeoJSON FeatureCollection"))?;
+    let mut boundaries: HashMap<String, Vec<Vec<GeoPoint>>> = HashMap::new();
+    for (index, feature) in features.iter().enumerate() {
+        let code = feature
+            .get("properties")
+            .and_then(|properties| properties.get(code_property))
+            .and_then(|code| match code {
+                Value::String(code) => Some(code.clone()),
+                Value::Number(code) => Some(code.to_string()),
+                _ => None,
+            })
+            .and_then(|code| normalize_admin_code(&code).ok());
+        let Some(code) = code else {
+            warn!(
+                feature = index,
+                "skipping boundary without a valid area code"
+            );
+            continue;
+        };
+        let geometry = feature.get("geometry").unwrap_or(&Value::Null);
+        let coordinates = geometry.get("coordinates").unwrap_or(&Value::Null);
+        let polygons = match geometry.get("type").and_then(Value::as_str) {
+            Some("Polygon") => vec![coordinates],
+            Some("MultiPolygon") => coordinates
+                .as_array()
+                .map(|polygons| polygons.iter().collect())
+                .unwrap_or_default(),
+            _ => {
+                warn!(code = %code, "skipping boundary that is not a Polygon or MultiPolygon");
+                continue;
+            }
+        };
+        let rings = polygons
+            .into_iter()
+            .filter_map(|polygon| polygon.as_array()?.first())
+            .filter_map(parse_ring)
+            .collect::<Vec<_>>();
+        if rings.is_empty() {
+            warn!(code = %code, "skipping boundary without usable rings");
+            continue;
+        }
+        boundaries.entry(code).or_default().extend(rings);
+    }
+    Ok(boundaries)
+}
+
+fn parse_ring(ring: &Value) -> Option<Vec<GeoPoint>> {
+    let points = ring
+        .as_array()?
+        .iter()
+        .map(|position| {
+            let position = position.as_array()?;
+            GeoPoint::new(position.get(1)?.as_f64()?, position.first()?.as_f64()?).ok()
+        })
+        .collect::<Option<Vec<_>>>()?;
+    (points.len() >= 3).then_some(points)
+}
+
+/// Centroid of the largest ring, for areas the CSV gives no coordinates.
+fn boundary_centroid(rings: &[Vec<GeoPoint>]) -> Option<GeoPoint> {
+    let (twice_area, lat, lng) = rings
+        .iter()
+        .map(|ring| {
+            let mut twice_area = 0.0;
+            let (mut lat, mut lng) = (0.0, 0.0);
+            let mut previous = ring[ring.len() - 1];
+            for current in ring {
+                let cross = previous.lng * current.lat - current.lng * previous.lat;
+                twice_area += cross;
+                lat += (previous.lat + current.lat) * cross;
+                lng += (previous.lng + current.lng) * cross;
+                previous = *current;
+            }
+            (twice_area, lat, lng)
+        })
+        .max_by(|a, b| a.0.abs().total_cmp(&b.0.abs()))?;
+    if twice_area.abs() <= f64::EPSILON {
+        return None;
+    }
+    GeoPoint::new(lat / (3.0 * twice_area), lng / (3.0 * twice_area)).ok()
+}
+
+/// Joins CSV rows with their boundaries into validated areas, sorted by code
+/// so parents are written before their children.
+pub fn build_admin_areas(
+    rows: Vec<GazetteerRow>,
+    mut boundaries: HashMap<String, Vec<Vec<GeoPoint>>>,
+    summary: &mut GazetteerImportSummary,
+) -> Vec<AdminArea> {
+    let mut areas = Vec::with_capacity(rows.len());
+    for row in rows {
+        let boundary = boundaries.remove(&row.code);
+        let Some(centroid) = row
+            .centroid
+            .or_else(|| boundary.as_deref().and_then(boundary_centroid))
+        else {
+            summary.missing_centroid += 1;
+            continue;
+        };
+        match AdminArea::new(&row.code, &row.name, centroid, boundary) {
+            Ok(area) => {
+                if area.boundary.is_some() {
+                    summary.with_boundary += 1;
+                }
+                areas.push(area);
+            }
+            Err(err) => {
+                summary.invalid += 1;
+                warn!(code = %row.code, error = %err, "skipping invalid admin area");
+            }
+        }
+    }
+    summary.unmatched_boundaries += boundaries.len() as u64;
+    areas.sort_by(|a, b| a.code.cmp(&b.code));
+    areas
+}
+
+pub async fn run_gazetteer_import_mode(config: &AppConfig, args: &[String]) -> anyhow::Result<()> {
+    let options = parse_gazetteer_import_options(args)?;
+    let repo: Arc<dyn OntologyRepository> = if options.dry_run {
+        Arc::new(gotong_infra::repositories::InMemoryOntologyRepository::new())
+    } else {
+        let db_config = DbConfig::from_app_config(config);
+        Arc::new(SurrealOntologyRepository::new(&db_config).await?)
+    };
+    println!(
+        "[gazetteer-import] start areas={} boundaries={} batch_size={} dry_run={}",
+        options.areas_file,
+        options.boundaries_file.as_deref().unwrap_or("-"),
+        options.batch_size,
+        options.dry_run
+    );
+
+    let mut summary = GazetteerImportSummary::default();
+    let file = File::open(&options.areas_file)
+        .map_err(|err| anyhow::anyhow!("failed to open --areas {}: {err}", options.areas_file))?;
+    let mut rows = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        summary.lines += 1;
+        match parse_area_line(&line) {
+            Ok(Some(row)) => rows.push(row),
+            Ok(None) => {}
+            Err(err) => {
+                summary.invalid += 1;
+                warn!(line = summary.lines, error = %err, "skipping invalid gazetteer line");
+            }
+        }
+    }
+    let boundaries = match options.boundaries_file.as_deref() {
+        Some(path) => {
+            let file = File::open(path)
+                .map_err(|err| anyhow::anyhow!("failed to open --boundaries {path}: {err}"))?;
+            let collection: Value = serde_json::from_reader(BufReader::new(file))
+                .map_err(|err| anyhow::anyhow!("invalid GeoJSON in {path}: {err}"))?;
+            parse_boundaries(&collection, &options.code_property)?
+        }
+        None => HashMap::new(),
+    };
+
+    let areas = build_admin_areas(rows, boundaries, &mut summary);
+    import_admin_areas(repo.as_ref(), &areas, options.batch_size).await?;
+    summary.imported = areas.len() as u64;
+
+    println!(
+        "[gazetteer-import] done lines={} imported={} with_boundary={} missing_centroid={} invalid={} unmatched_boundaries={} dry_run={}",
+        summary.lines,
+        summary.imported,
+        summary.with_boundary,
+        summary.missing_centroid,
+        summary.invalid,
+        summary.unmatched_boundaries,
+        options.dry_run
+    );
+    Ok(())
+}
+
+/// Upserts are keyed by code, so a rerun with a corrected file just
+/// overwrites the earlier import.
+pub async fn import_admin_areas(
+    repo: &dyn OntologyRepository,
+    areas: &[AdminArea],
+    batch_size: usize,
+) -> anyhow::Result<()> {
+    for batch in areas.chunks(batch_size.max(1)) {
+        repo.upsert_admin_areas(batch)
+            .await
+            .map_err(|err| anyhow::anyhow!("failed to write admin areas: {err}"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gotong_domain::ontology_gazetteer::{AdminLevel, reverse_geocode};
+    use gotong_infra::repositories::InMemoryOntologyRepository;
+
+    #[test]
+    fn area_lines_accept_headers_quotes_and_optional_coordinates() {
+        assert_eq!(parse_area_line("kode,nama,lat,lng").unwrap(), None);
+        assert_eq!(
+            parse_area_line("32.73,\"Kota Bandung, Jawa Barat\",-6.91,107.61").unwrap(),
+            Some(GazetteerRow {
+                code: "32.73".to_string(),
+                name: "Kota Bandung, Jawa Barat".to_string(),
+                centroid: Some(GeoPoint {
+                    lat: -6.91,
+                    lng: 107.61
+                }),
+            })
+        );
+        let row = parse_area_line("3273011001,Sarijadi").unwrap().unwrap();
+        assert_eq!(row.code, "32.73.01.1001");
+        assert_eq!(row.centroid, None);
+        assert!(parse_area_line("32.73,Kota Bandung,-6.91").is_err());
+        assert!(parse_area_line("32.7,Salah").is_err());
+    }
+
+    #[tokio::test]
+    async fn boundaries_attach_to_rows_and_supply_missing_centroids() {
+        let collection = serde_json::json!({
+            "type": "FeatureCollection",
+            "features": [
+                {
+                    "type": "Feature",
+                    "properties": { "kode": "32.73.01.1001" },
+                    "geometry": {
+                        "type": "MultiPolygon",
+                        "coordinates": [[[
+                            [107.57, -6.88], [107.58, -6.88], [107.58, -6.87],
+                            [107.57, -6.87], [107.57, -6.88]
+                        ]]]
+                    }
+                },
+                {
+                    "type": "Feature",
+                    "properties": { "kode": "99.99.99.9999" },
+                    "geometry": {
+                        "type": "Polygon",
+                        "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]]]
+                    }
+                },
+                { "type": "Feature", "properties": {}, "geometry": null }
+            ]
+        });
+        let boundaries = parse_boundaries(&collection, "kode").unwrap();
+        assert_eq!(boundaries.len(), 2);
+
+        let rows = vec![
+            parse_area_line("32.73.01.1001,Sarijadi").unwrap().unwrap(),
+            parse_area_line("32.73.01,Sukasari,-6.88,107.58")
+                .unwrap()
+                .unwrap(),
+            parse_area_line("32.73.01.1002,Gegerkalong")
+                .unwrap()
+                .unwrap(),
+        ];
+        let mut summary = GazetteerImportSummary::default();
+        let areas = build_admin_areas(rows, boundaries, &mut summary);
+        assert_eq!(
+            areas
+                .iter()
+                .map(|area| area.code.as_str())
+                .collect::<Vec<_>>(),
+            vec!["32.73.01", "32.73.01.1001"]
+        );
+        assert_eq!(summary.with_boundary, 1);
+        assert_eq!(summary.missing_centroid, 1);
+        assert_eq!(summary.unmatched_boundaries, 1);
+        let centroid = areas[1].centroid;
+        assert!((centroid.lat + 6.875).abs() < 1e-6);
+        assert!((centroid.lng - 107.575).abs() < 1e-6);
+
+        let repo = InMemoryOntologyRepository::new();
+        import_admin_areas(&repo, &areas, 1).await.unwrap();
+        let result = reverse_geocode(&repo, &centroid).await.unwrap().unwrap();
+        assert_eq!(result.area.code, "32.73.01.1001");
+        assert_eq!(result.area.level, AdminLevel::DesaKelurahan);
+    }
+}
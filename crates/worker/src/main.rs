@@ -4,17 +4,28 @@ use std::io::{BufRead, BufReader};
 use std::sync::Arc;
 use std::time::Duration;
 
-use gotong_domain::ports::discovery::{FeedRepository, FeedRepositoryQuery};
+use chat_retention::handle_chat_retention_sweep;
+use concept_merge::{ConceptRedirectChecker, handle_concept_merge};
+use digest::{DigestSender, handle_digest_send};
+use gazetteer_import::run_gazetteer_import_mode;
+use gotong_domain::ports::chat::ChatRepository;
+use gotong_domain::ports::digest::DigestSubscriptionRepository;
+use gotong_domain::ports::discovery::{
+    FeedRepository, FeedRepositoryQuery, FeedSearchIndexQueue, NotificationRepository,
+};
 use gotong_domain::ports::jobs::{JobQueue, JobQueueError, JobType};
+use gotong_domain::ports::notification_preferences::NotificationPreferenceRepository;
 use gotong_domain::ports::ontology::OntologyRepository;
+use gotong_domain::ports::push::PushSubscriptionRepository;
 use gotong_domain::ports::webhook::WebhookOutboxRepository;
 use gotong_domain::{
     auth::Role,
     discovery::{FEED_SOURCE_ONTOLOGY_NOTE, FeedItem},
     identity::ActorIdentity,
     jobs::{
-        ConceptVerificationPayload, JobDefaults, OntologyNoteEnrichPayload, TTLCleanupPayload,
-        WebhookRetryPayload, backoff_ms, new_job, now_ms,
+        ChatRetentionSweepPayload, ConceptVerificationPayload, DigestSendPayload,
+        FeedSearchIndexSyncPayload, JobDefaults, OntologyNoteEnrichPayload, TTLCleanupPayload,
+        TrendingComputePayload, WebhookRetryPayload, backoff_ms, new_job, now_ms,
     },
     moderation::{ModerationAutoReleaseCommand, ModerationService},
     ontology::{OntologyConcept, OntologyEdgeKind},
@@ -25,23 +36,46 @@ use gotong_domain::{
     },
 };
 use gotong_infra::{
+    chat_attachment_storage::ChatAttachmentStorage,
     config::AppConfig,
     db::DbConfig,
     jobs::{JobQueueMetricsSnapshot, RedisJobQueue},
     logging::init_tracing,
+    mail::mail_transport_for_config,
     repositories::{
-        SurrealDiscoveryFeedRepository, SurrealDiscoveryFeedRepositoryOptions,
-        SurrealModerationRepository, SurrealOntologyRepository, SurrealWebhookOutboxRepository,
+        SurrealChatRepository, SurrealDigestSubscriptionRepository, SurrealDiscoveryFeedRepository,
+        SurrealDiscoveryFeedRepositoryOptions, SurrealDiscoveryNotificationRepository,
+        SurrealModerationRepository, SurrealNotificationPreferenceRepository,
+        SurrealOntologyRepository, SurrealPushSubscriptionRepository,
+        SurrealWebhookOutboxRepository,
+    },
+    search_index::{
+        FeedSearchIndexer, IndexedFeedRepository, JobFeedSearchIndexQueue, TantivyFeedSearchIndex,
     },
+    trending::RedisTrendingSnapshotStore,
+    web_push::{VapidKeys, WebPushGateway},
+    wikidata::WikidataRedirectClient,
 };
 use hmac::{Hmac, Mac};
+use ontology_export::run_ontology_export_mode;
 use serde_json::json;
 use sha2::Sha256;
 use tracing::{debug, error, info, warn};
+use trending::{TrendingComputer, handle_trending_compute};
 use uuid::Uuid;
+use web_push::{WebPushSender, handle_web_push_send};
+use wikidata_import::run_wikidata_import_mode;
 
 type HmacSha256 = Hmac<Sha256>;
+mod chat_retention;
+mod concept_merge;
+mod digest;
+mod gazetteer_import;
 mod observability;
+mod ontology_export;
+mod trending;
+mod web_push;
+mod wikidata_import;
 const ONTOLOGY_TTL_HIDDEN_REASON: &str = "ontology_ttl_expired";
 
 #[tokio::main]
@@ -69,6 +103,22 @@ async fn main() -> anyhow::Result<()> {
                 run_feed_participant_edge_backfill_mode(&config, &args[1..]).await?;
                 return Ok(());
             }
+            "feed-search-index-rebuild" => {
+                run_feed_search_index_rebuild_mode(&config, &args[1..]).await?;
+                return Ok(());
+            }
+            "ontology-export" => {
+                run_ontology_export_mode(&config, &args[1..]).await?;
+                return Ok(());
+            }
+            "wikidata-import" => {
+                run_wikidata_import_mode(&config, &args[1..]).await?;
+                return Ok(());
+            }
+            "gazetteer-import" => {
+                run_gazetteer_import_mode(&config, &args[1..]).await?;
+                return Ok(());
+            }
             _ => {}
         }
     }
@@ -100,6 +150,11 @@ async fn main() -> anyhow::Result<()> {
     let mut ontology_repo = None;
     let mut webhook_outbox_repo = None;
     let mut feed_repo = None;
+    let mut chat_repo = None;
+    let mut notification_repo = None;
+    let mut digest_subscription_repo = None;
+    let mut notification_preference_repo = None;
+    let mut push_subscription_repo = None;
     let backend = config.data_backend.trim().to_ascii_lowercase();
     if matches!(backend.as_str(), "surreal" | "surrealdb" | "tikv") {
         let db_config = DbConfig::from_app_config(&config);
@@ -118,15 +173,137 @@ async fn main() -> anyhow::Result<()> {
         )
         .await?;
         feed_repo = Some(Arc::new(feed_repository) as Arc<dyn FeedRepository>);
-    }
+        let chat_repository = SurrealChatRepository::new(&db_config).await?;
+        chat_repo = Some(Arc::new(chat_repository) as Arc<dyn ChatRepository>);
+        let notification_repository =
+            SurrealDiscoveryNotificationRepository::new(&db_config).await?;
+        notification_repo =
+            Some(Arc::new(notification_repository) as Arc<dyn NotificationRepository>);
+        let digest_repository = SurrealDigestSubscriptionRepository::new(&db_config).await?;
+        digest_subscription_repo =
+            Some(Arc::new(digest_repository) as Arc<dyn DigestSubscriptionRepository>);
+        let preference_repository =
+            SurrealNotificationPreferenceRepository::new(&db_config).await?;
+        notification_preference_repo =
+            Some(Arc::new(preference_repository) as Arc<dyn NotificationPreferenceRepository>);
+        let push_repository = SurrealPushSubscriptionRepository::new(&db_config).await?;
+        push_subscription_repo =
+            Some(Arc::new(push_repository) as Arc<dyn PushSubscriptionRepository>);
+    }
+    let feed_search_indexer = feed_search_indexer_for_config(&config, feed_repo.as_ref());
+    let feed_repo = match (feed_repo, feed_search_indexer.as_ref()) {
+        (Some(feed_repo), Some(indexer)) => Some(Arc::new(IndexedFeedRepository::new(
+            feed_repo,
+            Arc::new(indexer.clone()),
+        )) as Arc<dyn FeedRepository>),
+        (feed_repo, _) => feed_repo,
+    };
+    let chat_attachment_store = match ChatAttachmentStorage::from_config(&config).await {
+        Ok(store) => Some(store),
+        Err(err) => {
+            warn!(
+                error = %err,
+                "chat attachment storage unavailable; chat retention will skip attachments"
+            );
+            None
+        }
+    };
+
+    let digest_sender = match (
+        feed_repo.clone(),
+        notification_repo,
+        digest_subscription_repo,
+        notification_preference_repo,
+    ) {
+        (
+            Some(feed_repo),
+            Some(notification_repo),
+            Some(digest_subscription_repo),
+            Some(notification_preference_repo),
+        ) => match mail_transport_for_config(&config) {
+            Ok(mail) => Some(DigestSender::new(
+                feed_repo,
+                notification_repo,
+                notification_preference_repo,
+                digest_subscription_repo,
+                mail,
+            )),
+            Err(err) => {
+                warn!(error = %err, "mail transport unavailable; digest send will be skipped");
+                None
+            }
+        },
+        _ => None,
+    };
+
+    let trending_computer = match feed_repo.clone() {
+        Some(feed_repo) => match RedisTrendingSnapshotStore::connect(&config.redis_url).await {
+            Ok(store) => Some(TrendingComputer::new(feed_repo, Arc::new(store))),
+            Err(err) => {
+                warn!(
+                    error = %err,
+                    "trending snapshot store unavailable; trending compute will be skipped"
+                );
+                None
+            }
+        },
+        None => None,
+    };
+
+    let web_push_sender = match push_subscription_repo {
+        Some(push_subscription_repo) => match VapidKeys::from_config(&config)
+            .and_then(|keys| keys.map(WebPushGateway::new).transpose())
+        {
+            Ok(Some(gateway)) => Some(WebPushSender::new(
+                push_subscription_repo,
+                Arc::new(gateway),
+                Arc::new(queue.clone()),
+            )),
+            Ok(None) => {
+                info!("web push VAPID keys not configured; web push send will be skipped");
+                None
+            }
+            Err(err) => {
+                warn!(error = %err, "web push gateway unavailable; web push send will be skipped");
+                None
+            }
+        },
+        None => None,
+    };
+
+    let concept_redirect_checker = match WikidataRedirectClient::from_config(&config) {
+        Ok(Some(client)) => Some(ConceptRedirectChecker::new(
+            Arc::new(client),
+            Arc::new(queue.clone()),
+        )),
+        Ok(None) => {
+            info!(
+                "wikidata api url not configured; upstream concept redirects will not be checked"
+            );
+            None
+        }
+        Err(err) => {
+            warn!(error = %err, "wikidata client unavailable; upstream concept redirects will not be checked");
+            None
+        }
+    };
 
     let worker = Worker::new(
         queue,
         config,
-        moderation_repo,
-        ontology_repo,
-        webhook_outbox_repo,
-        feed_repo,
+        WorkerDeps {
+            moderation_repo,
+            ontology_repo,
+            webhook_outbox_repo,
+            feed_repo,
+            feed_search_indexer,
+            chat_repo,
+            chat_attachment_store,
+            digest_sender,
+            web_push_sender,
+            trending_computer,
+            concept_redirect_checker,
+        },
     );
     info!("worker starting");
     worker.run().await?;
@@ -137,10 +314,23 @@ async fn main() -> anyhow::Result<()> {
 struct Worker {
     queue: RedisJobQueue,
     config: AppConfig,
+    deps: WorkerDeps,
+}
+
+/// What job handlers run against. Each dependency is `None` when its backend is
+/// not configured; the jobs that need it are then skipped with a warning.
+struct WorkerDeps {
     moderation_repo: Option<Arc<dyn ModerationRepository>>,
     ontology_repo: Option<Arc<dyn OntologyRepository>>,
     webhook_outbox_repo: Option<Arc<dyn WebhookOutboxRepository>>,
     feed_repo: Option<Arc<dyn FeedRepository>>,
+    feed_search_indexer: Option<FeedSearchIndexer>,
+    chat_repo: Option<Arc<dyn ChatRepository>>,
+    chat_attachment_store: Option<ChatAttachmentStorage>,
+    digest_sender: Option<DigestSender>,
+    web_push_sender: Option<WebPushSender>,
+    trending_computer: Option<TrendingComputer>,
+    concept_redirect_checker: Option<ConceptRedirectChecker>,
 }
 
 #[derive(Debug, Clone)]
@@ -252,6 +442,23 @@ impl Default for FeedParticipantEdgeBackfillOptions {
     }
 }
 
+#[derive(Debug, Clone)]
+struct FeedSearchIndexRebuildOptions {
+    index_dir: Option<String>,
+    page_size: usize,
+    progress_every: usize,
+}
+
+impl Default for FeedSearchIndexRebuildOptions {
+    fn default() -> Self {
+        Self {
+            index_dir: None,
+            page_size: 500,
+            progress_every: 5_000,
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 struct FeedParticipantEdgeBackfillSummary {
     scanned: usize,
@@ -526,6 +733,59 @@ fn parse_feed_participant_edge_backfill_options(
     Ok(opts)
 }
 
+fn parse_feed_search_index_rebuild_options(
+    args: &[String],
+) -> anyhow::Result<FeedSearchIndexRebuildOptions> {
+    let mut opts = FeedSearchIndexRebuildOptions::default();
+    let mut idx = 0usize;
+    while idx < args.len() {
+        match args[idx].as_str() {
+            "--index-dir" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --index-dir"))?;
+                if value.trim().is_empty() {
+                    return Err(anyhow::anyhow!("--index-dir must not be empty"));
+                }
+                opts.index_dir = Some(value.trim().to_string());
+                idx += 2;
+            }
+            "--page-size" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --page-size"))?;
+                let parsed = value
+                    .parse::<usize>()
+                    .map_err(|err| anyhow::anyhow!("invalid --page-size value: {err}"))?;
+                if parsed == 0 {
+                    return Err(anyhow::anyhow!("--page-size must be >= 1"));
+                }
+                opts.page_size = parsed.min(10_000);
+                idx += 2;
+            }
+            "--progress-every" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --progress-every"))?;
+                let parsed = value
+                    .parse::<usize>()
+                    .map_err(|err| anyhow::anyhow!("invalid --progress-every value: {err}"))?;
+                if parsed == 0 {
+                    return Err(anyhow::anyhow!("--progress-every must be >= 1"));
+                }
+                opts.progress_every = parsed;
+                idx += 2;
+            }
+            other => {
+                return Err(anyhow::anyhow!(
+                    "unknown argument for feed-search-index-rebuild: {other}"
+                ));
+            }
+        }
+    }
+    Ok(opts)
+}
+
 fn feed_item_lifecycle_hidden(item: &FeedItem) -> bool {
     item.payload
         .as_ref()
@@ -649,6 +909,8 @@ async fn run_feed_participant_edge_backfill_mode(
                 from_ms: options.from_ms,
                 to_ms: options.to_ms,
                 involvement_only: false,
+                followed_entity_ids: Vec::new(),
+                near: None,
             })
             .await
             .map_err(|err| anyhow::anyhow!("failed listing feed rows for edge backfill: {err}"))?;
@@ -724,6 +986,125 @@ async fn run_feed_participant_edge_backfill_mode(
     Ok(())
 }
 
+fn feed_search_indexer_for_config(
+    config: &AppConfig,
+    feed_repo: Option<&Arc<dyn FeedRepository>>,
+) -> Option<FeedSearchIndexer> {
+    let dir = config.discovery_search_index_dir.trim();
+    let feed_repo = feed_repo?;
+    if dir.is_empty() {
+        return None;
+    }
+    match TantivyFeedSearchIndex::open(dir) {
+        Ok(index) => Some(FeedSearchIndexer::spawn(index, feed_repo.clone())),
+        Err(err) => {
+            warn!(
+                index_dir = dir,
+                error = %err,
+                "feed search index unavailable; index sync jobs will be skipped"
+            );
+            None
+        }
+    }
+}
+
+async fn run_feed_search_index_rebuild_mode(
+    config: &AppConfig,
+    args: &[String],
+) -> anyhow::Result<()> {
+    let options = parse_feed_search_index_rebuild_options(args)?;
+    let index_dir = options
+        .index_dir
+        .clone()
+        .unwrap_or_else(|| config.discovery_search_index_dir.trim().to_string());
+    if index_dir.is_empty() {
+        return Err(anyhow::anyhow!(
+            "feed-search-index-rebuild needs --index-dir or DISCOVERY_SEARCH_INDEX_DIR"
+        ));
+    }
+    let index = TantivyFeedSearchIndex::open(&index_dir)?;
+    let db_config = DbConfig::from_app_config(config);
+    let feed_repo: Arc<dyn FeedRepository> = Arc::new(
+        SurrealDiscoveryFeedRepository::new_with_options(
+            &db_config,
+            SurrealDiscoveryFeedRepositoryOptions {
+                involvement_fallback_enabled: config.discovery_feed_involvement_fallback_enabled,
+            },
+        )
+        .await?,
+    );
+
+    println!(
+        "[feed-search-index-rebuild] start index_dir={} page_size={} progress_every={}",
+        index_dir, options.page_size, options.progress_every
+    );
+
+    // (cursor_occurred_at_ms, cursor_feed_id, scanned, exhausted)
+    let state = std::sync::Mutex::new((None::<i64>, None::<String>, 0usize, false));
+    let state = &state;
+    let feed_repo = &feed_repo;
+    let page_size = options.page_size;
+    let progress_every = options.progress_every;
+    let indexed = index
+        .rebuild(move || async move {
+            let (cursor_occurred_at_ms, cursor_feed_id, exhausted) = {
+                let guard = state
+                    .lock()
+                    .map_err(|_| anyhow::anyhow!("rebuild cursor lock poisoned"))?;
+                (guard.0, guard.1.clone(), guard.3)
+            };
+            if exhausted {
+                return Ok(Vec::new());
+            }
+            let rows = feed_repo
+                .list_feed(&FeedRepositoryQuery {
+                    actor_id: "system".to_string(),
+                    cursor_occurred_at_ms,
+                    cursor_feed_id: cursor_feed_id.clone(),
+                    limit: page_size,
+                    scope_id: None,
+                    privacy_level: None,
+                    from_ms: None,
+                    to_ms: None,
+                    involvement_only: false,
+                    followed_entity_ids: Vec::new(),
+                    near: None,
+                })
+                .await
+                .map_err(|err| {
+                    anyhow::anyhow!("failed listing feed rows for search index rebuild: {err}")
+                })?;
+            let mut guard = state
+                .lock()
+                .map_err(|_| anyhow::anyhow!("rebuild cursor lock poisoned"))?;
+            let before = guard.2;
+            guard.2 = before.saturating_add(rows.len());
+            if guard.2 / progress_every > before / progress_every {
+                println!("[feed-search-index-rebuild] progress scanned={}", guard.2);
+            }
+            match rows.last() {
+                Some(last_row)
+                    if rows.len() >= page_size
+                        && !(cursor_occurred_at_ms == Some(last_row.occurred_at_ms)
+                            && cursor_feed_id.as_deref() == Some(last_row.feed_id.as_str())) =>
+                {
+                    guard.0 = Some(last_row.occurred_at_ms);
+                    guard.1 = Some(last_row.feed_id.clone());
+                }
+                _ => guard.3 = true,
+            }
+            Ok(rows)
+        })
+        .await?;
+
+    println!(
+        "[feed-search-index-rebuild] done indexed={} num_docs={}",
+        indexed,
+        index.num_docs()
+    );
+    Ok(())
+}
+
 async fn run_ontology_feed_backfill_mode(
     config: &AppConfig,
     args: &[String],
@@ -739,6 +1120,19 @@ async fn run_ontology_feed_backfill_mode(
         )
         .await?,
     );
+    let feed_repo = if config.discovery_search_index_dir.trim().is_empty() || options.dry_run {
+        feed_repo
+    } else {
+        let queue = RedisJobQueue::connect_with_prefix(
+            &config.redis_url,
+            config.worker_queue_prefix.clone(),
+        )
+        .await?;
+        Arc::new(IndexedFeedRepository::new(
+            feed_repo,
+            Arc::new(JobFeedSearchIndexQueue::new(Arc::new(queue))),
+        )) as Arc<dyn FeedRepository>
+    };
     let ontology_repo = SurrealOntologyRepository::new(&db_config).await?;
     let cutoff_ms = options.cutoff_ms.unwrap_or_else(now_ms);
     let mut summary = OntologyFeedBackfillSummary::default();
@@ -762,6 +1156,8 @@ async fn run_ontology_feed_backfill_mode(
                 from_ms: None,
                 to_ms: None,
                 involvement_only: false,
+                followed_entity_ids: Vec::new(),
+                near: None,
             })
             .await
             .map_err(|err| anyhow::anyhow!("failed listing feed rows for backfill: {err}"))?;
@@ -1218,21 +1614,11 @@ async fn run_webhook_replay_mode(config: &AppConfig, args: &[String]) -> anyhow:
 }
 
 impl Worker {
-    fn new(
-        queue: RedisJobQueue,
-        config: AppConfig,
-        moderation_repo: Option<Arc<dyn ModerationRepository>>,
-        ontology_repo: Option<Arc<dyn OntologyRepository>>,
-        webhook_outbox_repo: Option<Arc<dyn WebhookOutboxRepository>>,
-        feed_repo: Option<Arc<dyn FeedRepository>>,
-    ) -> Self {
+    fn new(queue: RedisJobQueue, config: AppConfig, deps: WorkerDeps) -> Self {
         Self {
             queue,
             config,
-            moderation_repo,
-            ontology_repo,
-            webhook_outbox_repo,
-            feed_repo,
+            deps,
         }
     }
 
@@ -1262,6 +1648,9 @@ impl Worker {
         self.emit_queue_metrics().await;
         let mut next_ttl_cleanup_at_ms = 0_i64;
         let mut next_concept_verification_at_ms = 0_i64;
+        let mut next_chat_retention_at_ms = 0_i64;
+        let mut next_digest_at_ms = 0_i64;
+        let mut next_trending_at_ms = 0_i64;
         let mut next_dead_letter_metric_at_ms = 0_i64;
         loop {
             self.emit_queue_metrics().await;
@@ -1282,6 +1671,9 @@ impl Worker {
                 now,
                 &mut next_ttl_cleanup_at_ms,
                 &mut next_concept_verification_at_ms,
+                &mut next_chat_retention_at_ms,
+                &mut next_digest_at_ms,
+                &mut next_trending_at_ms,
             )
             .await;
 
@@ -1289,16 +1681,7 @@ impl Worker {
                 Ok(Some(job)) => {
                     let started_at = now_ms();
                     let job_type_label = job_type_label(&job.job_type);
-                    if let Err(err) = handle_job(
-                        &self.config,
-                        &job,
-                        self.moderation_repo.as_ref(),
-                        self.ontology_repo.as_ref(),
-                        self.webhook_outbox_repo.as_ref(),
-                        self.feed_repo.as_ref(),
-                    )
-                    .await
-                    {
+                    if let Err(err) = handle_job(&self.config, &job, &self.deps).await {
                         let duration_ms = now_ms() - started_at;
                         let job_id = job.job_id.clone();
                         if let Err(handle_err) = self.handle_failure(job, err).await {
@@ -1363,7 +1746,7 @@ impl Worker {
     }
 
     async fn emit_dead_letter_metrics(&self) {
-        let Some(repo) = self.webhook_outbox_repo.as_ref() else {
+        let Some(repo) = self.deps.webhook_outbox_repo.as_ref() else {
             observability::set_webhook_dead_letter_depth(0);
             return;
         };
@@ -1385,6 +1768,9 @@ impl Worker {
         now: i64,
         next_ttl_cleanup_at_ms: &mut i64,
         next_concept_verification_at_ms: &mut i64,
+        next_chat_retention_at_ms: &mut i64,
+        next_digest_at_ms: &mut i64,
+        next_trending_at_ms: &mut i64,
     ) {
         let ttl_interval_ms = self.config.worker_ttl_cleanup_interval_ms.max(60_000);
         if now >= *next_ttl_cleanup_at_ms {
@@ -1440,6 +1826,60 @@ impl Worker {
             }
             *next_concept_verification_at_ms = slot_start_ms + concept_interval_ms as i64;
         }
+
+        let chat_retention_interval_ms = self.config.worker_chat_retention_interval_ms.max(60_000);
+        if now >= *next_chat_retention_at_ms {
+            let slot_start_ms = periodic_slot_start_ms(now, chat_retention_interval_ms);
+            let job_id = format!("system:chat_retention_sweep:{slot_start_ms}");
+            let payload = ChatRetentionSweepPayload { scheduled_ms: now };
+            self.enqueue_periodic_job(
+                JobType::ChatRetentionSweep,
+                job_id,
+                json!(payload),
+                now,
+                1,
+                "chat_retention_sweep",
+                chat_retention_interval_ms,
+            )
+            .await;
+            *next_chat_retention_at_ms = slot_start_ms + chat_retention_interval_ms as i64;
+        }
+
+        let digest_interval_ms = self.config.worker_digest_interval_ms.max(60_000);
+        if now >= *next_digest_at_ms {
+            let slot_start_ms = periodic_slot_start_ms(now, digest_interval_ms);
+            let job_id = format!("system:digest_send:{slot_start_ms}");
+            let payload = DigestSendPayload { scheduled_ms: now };
+            self.enqueue_periodic_job(
+                JobType::DigestSend,
+                job_id,
+                json!(payload),
+                now,
+                1,
+                "digest_send",
+                digest_interval_ms,
+            )
+            .await;
+            *next_digest_at_ms = slot_start_ms + digest_interval_ms as i64;
+        }
+
+        let trending_interval_ms = self.config.worker_trending_interval_ms.max(60_000);
+        if now >= *next_trending_at_ms {
+            let slot_start_ms = periodic_slot_start_ms(now, trending_interval_ms);
+            let job_id = format!("system:trending_compute:{slot_start_ms}");
+            let payload = TrendingComputePayload { scheduled_ms: now };
+            self.enqueue_periodic_job(
+                JobType::TrendingCompute,
+                job_id,
+                json!(payload),
+                now,
+                1,
+                "trending_compute",
+                trending_interval_ms,
+            )
+            .await;
+            *next_trending_at_ms = slot_start_ms + trending_interval_ms as i64;
+        }
     }
 
     async fn enqueue_periodic_job(
@@ -1559,14 +1999,11 @@ fn next_retry_job_id(current_job_id: &str, next_attempt: u32) -> String {
 async fn handle_job(
     config: &AppConfig,
     job: &JobEnvelope,
-    moderation_repo: Option<&Arc<dyn ModerationRepository>>,
-    ontology_repo: Option<&Arc<dyn OntologyRepository>>,
-    webhook_outbox_repo: Option<&Arc<dyn WebhookOutboxRepository>>,
-    feed_repo: Option<&Arc<dyn FeedRepository>>,
+    deps: &WorkerDeps,
 ) -> anyhow::Result<()> {
     match job.job_type {
         JobType::ModerationAutoRelease => {
-            let Some(repo) = moderation_repo else {
+            let Some(repo) = deps.moderation_repo.as_ref() else {
                 warn!(
                     job_id = %job.job_id,
                     "skipping moderation auto-release job: moderation repository is unavailable"
@@ -1592,7 +2029,7 @@ async fn handle_job(
                 .await?;
         }
         JobType::WebhookRetry => {
-            let Some(repo) = webhook_outbox_repo else {
+            let Some(repo) = deps.webhook_outbox_repo.as_ref() else {
                 warn!(
                     job_id = %job.job_id,
                     "skipping webhook retry job: webhook outbox repository is unavailable"
@@ -1602,16 +2039,43 @@ async fn handle_job(
             handle_webhook_retry(config, repo.clone(), job).await?;
         }
         JobType::DigestSend => {
-            info!(job_id = %job.job_id, "handling digest send (stub)");
+            handle_digest_send(config, deps.digest_sender.as_ref(), job).await?;
         }
         JobType::TTLCleanup => {
-            handle_ttl_cleanup(ontology_repo, feed_repo, job).await?;
+            handle_ttl_cleanup(deps.ontology_repo.as_ref(), deps.feed_repo.as_ref(), job).await?;
         }
         JobType::ConceptVerification => {
-            handle_concept_verification(ontology_repo, job).await?;
+            handle_concept_verification(
+                deps.ontology_repo.as_ref(),
+                deps.concept_redirect_checker.as_ref(),
+                job,
+            )
+            .await?;
+        }
+        JobType::ConceptMerge => {
+            handle_concept_merge(deps.ontology_repo.as_ref(), job).await?;
         }
         JobType::OntologyNoteEnrich => {
-            handle_ontology_note_enrich(ontology_repo, feed_repo, job).await?;
+            handle_ontology_note_enrich(deps.ontology_repo.as_ref(), deps.feed_repo.as_ref(), job)
+                .await?;
+        }
+        JobType::ChatRetentionSweep => {
+            handle_chat_retention_sweep(
+                config,
+                deps.chat_repo.as_ref(),
+                deps.chat_attachment_store.as_ref(),
+                job,
+            )
+            .await?;
+        }
+        JobType::WebPushSend => {
+            handle_web_push_send(config, deps.web_push_sender.as_ref(), job).await?;
+        }
+        JobType::TrendingCompute => {
+            handle_trending_compute(config, deps.trending_computer.as_ref(), job).await?;
+        }
+        JobType::FeedSearchIndexSync => {
+            handle_feed_search_index_sync(deps.feed_search_indexer.as_ref(), job).await?;
         }
     }
 
@@ -1625,7 +2089,12 @@ fn job_type_label(job_type: &JobType) -> &'static str {
         JobType::DigestSend => "digest_send",
         JobType::TTLCleanup => "ttl_cleanup",
         JobType::ConceptVerification => "concept_verification",
+        JobType::ConceptMerge => "concept_merge",
         JobType::OntologyNoteEnrich => "ontology_note_enrich",
+        JobType::ChatRetentionSweep => "chat_retention_sweep",
+        JobType::WebPushSend => "web_push_send",
+        JobType::TrendingCompute => "trending_compute",
+        JobType::FeedSearchIndexSync => "feed_search_index_sync",
     }
 }
 
@@ -1690,6 +2159,13 @@ fn parse_ttl_cleanup_payload(job: &JobEnvelope) -> anyhow::Result<TTLCleanupPayl
     Ok(payload)
 }
 
+fn parse_feed_search_index_sync_payload(
+    job: &JobEnvelope,
+) -> anyhow::Result<FeedSearchIndexSyncPayload> {
+    serde_json::from_value(job.payload.clone())
+        .map_err(|err| anyhow::anyhow!("invalid feed search index sync payload: {err}"))
+}
+
 fn parse_concept_verification_payload(
     job: &JobEnvelope,
 ) -> anyhow::Result<ConceptVerificationPayload> {
@@ -1877,6 +2353,24 @@ async fn handle_ontology_note_enrich(
     Ok(())
 }
 
+/// The worker owns the only index writer; other processes send the feed ids
+/// they touched here instead of committing to the index themselves.
+async fn handle_feed_search_index_sync(
+    feed_search_indexer: Option<&FeedSearchIndexer>,
+    job: &JobEnvelope,
+) -> anyhow::Result<()> {
+    let payload = parse_feed_search_index_sync_payload(job)?;
+    let Some(indexer) = feed_search_indexer else {
+        warn!(
+            job_id = %job.job_id,
+            "skipping feed search index sync job: search index is unavailable"
+        );
+        return Ok(());
+    };
+    indexer.enqueue(&payload.feed_ids).await?;
+    Ok(())
+}
+
 async fn handle_ttl_cleanup(
     ontology_repo: Option<&Arc<dyn OntologyRepository>>,
     feed_repo: Option<&Arc<dyn FeedRepository>>,
@@ -1941,6 +2435,7 @@ async fn handle_ttl_cleanup(
 
 async fn handle_concept_verification(
     ontology_repo: Option<&Arc<dyn OntologyRepository>>,
+    redirect_checker: Option<&ConceptRedirectChecker>,
     job: &JobEnvelope,
 ) -> anyhow::Result<()> {
     let payload = parse_concept_verification_payload(job)?;
@@ -1951,6 +2446,15 @@ async fn handle_concept_verification(
         );
         return Ok(());
     };
+    // A QID Wikidata merged away is not verified; the queued merge folds it into its target.
+    if let Some(checker) = redirect_checker
+        && checker
+            .queue_merge_if_redirected(&payload.qid, job, payload.scheduled_ms)
+            .await?
+            .is_some()
+    {
+        return Ok(());
+    }
 
     let current = repo
         .get_concept_by_qid(&payload.qid)
@@ -2516,6 +3020,32 @@ mod periodic_job_tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn parse_feed_search_index_rebuild_options_defaults_and_flags() {
+        let defaults =
+            parse_feed_search_index_rebuild_options(&[]).expect("default rebuild options");
+        assert_eq!(defaults.index_dir, None);
+        assert_eq!(defaults.page_size, 500);
+        assert_eq!(defaults.progress_every, 5_000);
+
+        let args = vec![
+            "--index-dir".to_string(),
+            "/var/lib/gotong/search".to_string(),
+            "--page-size".to_string(),
+            "20000".to_string(),
+            "--progress-every".to_string(),
+            "100".to_string(),
+        ];
+        let parsed =
+            parse_feed_search_index_rebuild_options(&args).expect("custom rebuild options");
+        assert_eq!(parsed.index_dir.as_deref(), Some("/var/lib/gotong/search"));
+        assert_eq!(parsed.page_size, 10_000);
+        assert_eq!(parsed.progress_every, 100);
+
+        let unknown = vec!["--dry-run".to_string()];
+        assert!(parse_feed_search_index_rebuild_options(&unknown).is_err());
+    }
+
     #[test]
     fn feed_participant_actor_count_dedupes_actor_and_participants() {
         let item = FeedItem {
@@ -2540,6 +3070,8 @@ mod periodic_job_tests {
                 " ".to_string(),
             ],
             payload: None,
+            location: None,
+            distance_m: None,
         };
         assert_eq!(feed_participant_actor_count(&item), 3);
     }
@@ -2564,6 +3096,8 @@ mod periodic_job_tests {
             payload: Some(serde_json::json!({
                 "note": { "ttl_expires_ms": 500 }
             })),
+            location: None,
+            distance_m: None,
         };
         assert!(should_hide_expired_ontology_feed_item_with_ttl(
             &expired,
@@ -2617,6 +3151,8 @@ mod periodic_job_tests {
             payload: Some(serde_json::json!({
                 "note": { "note_id": "note-lookup" }
             })),
+            location: None,
+            distance_m: None,
         };
         let mut ttl_by_note_id = HashMap::new();
         ttl_by_note_id.insert("note-lookup".to_string(), 750);
@@ -2724,6 +3260,8 @@ mod periodic_job_tests {
                     "note": { "note_id": expired.note_id },
                     "enrichment": { "status": "computed" }
                 })),
+                location: None,
+                distance_m: None,
             })
             .await
             .expect("create expired feed item");
@@ -2748,6 +3286,8 @@ mod periodic_job_tests {
                     "note": { "note_id": active.note_id },
                     "enrichment": { "status": "computed" }
                 })),
+                location: None,
+                distance_m: None,
             })
             .await
             .expect("create active feed item");
@@ -2902,6 +3442,8 @@ mod periodic_job_tests {
                         }
                     }
                 })),
+                location: None,
+                distance_m: None,
             })
             .await
             .expect("create feed item");
@@ -2983,7 +3525,7 @@ mod periodic_job_tests {
             "qid": "Q111",
             "scheduled_ms": 1_000,
         }));
-        handle_concept_verification(Some(&repo), &update_job)
+        handle_concept_verification(Some(&repo), None, &update_job)
             .await
             .expect("update existing concept");
 
@@ -2998,7 +3540,7 @@ mod periodic_job_tests {
             "qid": "Q222",
             "scheduled_ms": 1_000,
         }));
-        handle_concept_verification(Some(&repo), &create_job)
+        handle_concept_verification(Some(&repo), None, &create_job)
             .await
             .expect("create missing concept");
 
@@ -3028,7 +3570,7 @@ mod periodic_job_tests {
             "qid": "Q_MISSING_ID",
             "scheduled_ms": 1_000,
         }));
-        handle_concept_verification(Some(&repo), &job)
+        handle_concept_verification(Some(&repo), None, &job)
             .await
             .expect("verify concept");
 
@@ -3047,7 +3589,7 @@ mod periodic_job_tests {
             "qid": "Q2095",
             "scheduled_ms": 1_000,
         }));
-        assert!(handle_concept_verification(None, &job).await.is_ok());
+        assert!(handle_concept_verification(None, None, &job).await.is_ok());
     }
 
     #[test]
@@ -0,0 +1,168 @@
+use std::io::Write;
+
+use gotong_domain::jobs::now_ms;
+use gotong_domain::ontology_export::{
+    MAX_RAHASIA_LEVEL, OntologyExportFilter, OntologyExportFormat, load_ontology_export,
+    render_ontology_export,
+};
+use gotong_infra::config::AppConfig;
+use gotong_infra::db::DbConfig;
+use gotong_infra::repositories::SurrealOntologyRepository;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OntologyExportOptions {
+    pub format: OntologyExportFormat,
+    /// File to write, or `-` for stdout.
+    pub output: String,
+    pub filter: OntologyExportFilter,
+}
+
+impl Default for OntologyExportOptions {
+    fn default() -> Self {
+        Self {
+            format: OntologyExportFormat::Turtle,
+            output: "-".to_string(),
+            filter: OntologyExportFilter::default(),
+        }
+    }
+}
+
+pub fn parse_ontology_export_options(args: &[String]) -> anyhow::Result<OntologyExportOptions> {
+    let mut opts = OntologyExportOptions::default();
+    let mut idx = 0usize;
+    while idx < args.len() {
+        match args[idx].as_str() {
+            "--format" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --format"))?;
+                opts.format = OntologyExportFormat::parse(value)
+                    .map_err(|err| anyhow::anyhow!("invalid --format value: {err}"))?;
+                idx += 2;
+            }
+            "--output" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --output"))?;
+                if value.trim().is_empty() {
+                    return Err(anyhow::anyhow!("--output must not be empty"));
+                }
+                opts.output = value.trim().to_string();
+                idx += 2;
+            }
+            "--community-id" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --community-id"))?;
+                if value.trim().is_empty() {
+                    return Err(anyhow::anyhow!("--community-id must not be empty"));
+                }
+                opts.filter.community_id = Some(value.trim().to_string());
+                idx += 2;
+            }
+            "--max-rahasia-level" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --max-rahasia-level"))?;
+                let parsed = value
+                    .parse::<i64>()
+                    .map_err(|err| anyhow::anyhow!("invalid --max-rahasia-level value: {err}"))?;
+                if !(0..=MAX_RAHASIA_LEVEL).contains(&parsed) {
+                    return Err(anyhow::anyhow!(
+                        "--max-rahasia-level must be between 0 and {MAX_RAHASIA_LEVEL}"
+                    ));
+                }
+                opts.filter.max_rahasia_level = parsed;
+                idx += 2;
+            }
+            "--include-non-ai-readable" => {
+                opts.filter.ai_readable_only = false;
+                idx += 1;
+            }
+            other => {
+                return Err(anyhow::anyhow!(
+                    "unknown argument for ontology-export: {other}"
+                ));
+            }
+        }
+    }
+    Ok(opts)
+}
+
+pub async fn run_ontology_export_mode(config: &AppConfig, args: &[String]) -> anyhow::Result<()> {
+    let options = parse_ontology_export_options(args)?;
+    let db_config = DbConfig::from_app_config(config);
+    let repo = SurrealOntologyRepository::new(&db_config).await?;
+
+    // Progress goes to stderr so `--output -` stays a clean document.
+    eprintln!(
+        "[ontology-export] start format={} output={} community_id={} max_rahasia_level={} ai_readable_only={}",
+        options.format.file_extension(),
+        options.output,
+        options.filter.community_id.as_deref().unwrap_or("*"),
+        options.filter.max_rahasia_level,
+        options.filter.ai_readable_only
+    );
+    let graph = load_ontology_export(&repo, &options.filter, now_ms())
+        .await
+        .map_err(|err| anyhow::anyhow!("failed to load ontology export: {err}"))?;
+    let document = render_ontology_export(&graph, options.format, &config.ontology_export_base_iri);
+    if options.output == "-" {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(document.as_bytes())?;
+        stdout.flush()?;
+    } else {
+        // Written beside the target and renamed so readers never see half a file.
+        let tmp = format!("{}.tmp", options.output);
+        std::fs::write(&tmp, document.as_bytes())?;
+        std::fs::rename(&tmp, &options.output)?;
+    }
+    eprintln!(
+        "[ontology-export] done concepts={} broader_edges={} notes={} triples={} actions={} places={} bytes={}",
+        graph.concepts.len(),
+        graph.broader_edges.len(),
+        graph.notes.len(),
+        graph.triples.len(),
+        graph.actions.len(),
+        graph.places.len(),
+        document.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_ontology_export_options_defaults_to_public_turtle() {
+        let options = parse_ontology_export_options(&[]).expect("defaults");
+        assert_eq!(options, OntologyExportOptions::default());
+        assert_eq!(options.filter.max_rahasia_level, 0);
+        assert!(options.filter.ai_readable_only);
+
+        let options = parse_ontology_export_options(&args(&[
+            "--format",
+            "jsonld",
+            "--output",
+            "/tmp/ontology.jsonld",
+            "--community-id",
+            "rw-07",
+            "--max-rahasia-level",
+            "1",
+        ]))
+        .expect("flags");
+        assert_eq!(options.format, OntologyExportFormat::JsonLd);
+        assert_eq!(options.output, "/tmp/ontology.jsonld");
+        assert_eq!(options.filter.community_id.as_deref(), Some("rw-07"));
+        assert_eq!(options.filter.max_rahasia_level, 1);
+
+        assert!(parse_ontology_export_options(&args(&["--max-rahasia-level", "4"])).is_err());
+        assert!(parse_ontology_export_options(&args(&["--format", "xml"])).is_err());
+        assert!(parse_ontology_export_options(&args(&["--bogus"])).is_err());
+    }
+}
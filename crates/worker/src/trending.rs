@@ -0,0 +1,214 @@
+use std::sync::Arc;
+
+use gotong_domain::jobs::TrendingComputePayload;
+use gotong_domain::ports::discovery::{FeedRepository, FeedRepositoryQuery};
+use gotong_domain::ports::jobs::JobEnvelope;
+use gotong_domain::ports::trending::TrendingSnapshotStore;
+use gotong_domain::trending::{TrendingAccumulator, TrendingConfig};
+use gotong_infra::config::AppConfig;
+use tracing::{info, warn};
+
+/// Feed rows fetched per page while scanning the trending horizon.
+const TRENDING_SCAN_PAGE_SIZE: usize = 500;
+/// Rows read per run at most, so a very busy horizon cannot stall the queue.
+const TRENDING_SCAN_MAX_ROWS: usize = 50_000;
+/// Snapshots outlive a few missed runs before scopes stop serving them.
+const TRENDING_TTL_INTERVALS: u64 = 4;
+
+/// Everything the trending job needs; only built when the feed is stored.
+pub struct TrendingComputer {
+    feed_repo: Arc<dyn FeedRepository>,
+    store: Arc<dyn TrendingSnapshotStore>,
+}
+
+impl TrendingComputer {
+    pub fn new(feed_repo: Arc<dyn FeedRepository>, store: Arc<dyn TrendingSnapshotStore>) -> Self {
+        Self { feed_repo, store }
+    }
+}
+
+pub fn trending_config(config: &AppConfig) -> TrendingConfig {
+    TrendingConfig {
+        window_ms: i64::try_from(config.trending_window_ms).unwrap_or(i64::MAX),
+        baseline_windows: config.trending_baseline_windows,
+        ..TrendingConfig::default()
+    }
+}
+
+pub fn parse_trending_compute_payload(job: &JobEnvelope) -> anyhow::Result<TrendingComputePayload> {
+    serde_json::from_value(job.payload.clone())
+        .map_err(|err| anyhow::anyhow!("invalid trending compute payload: {err}"))
+}
+
+/// Recounts topics over the horizon ending at the scheduled time and replaces the
+/// cached community-wide and per-scope snapshots.
+pub async fn handle_trending_compute(
+    config: &AppConfig,
+    computer: Option<&TrendingComputer>,
+    job: &JobEnvelope,
+) -> anyhow::Result<()> {
+    let payload = parse_trending_compute_payload(job)?;
+    let Some(computer) = computer else {
+        warn!(
+            job_id = %job.job_id,
+            "skipping trending compute: feed repository is unavailable"
+        );
+        return Ok(());
+    };
+
+    let trending = trending_config(config);
+    let now_ms = payload.scheduled_ms;
+    let mut accumulator = TrendingAccumulator::new(now_ms, trending);
+    let mut cursor_occurred_at_ms: Option<i64> = None;
+    let mut cursor_feed_id: Option<String> = None;
+    let mut scanned = 0usize;
+    loop {
+        let page_limit = TRENDING_SCAN_PAGE_SIZE.min(TRENDING_SCAN_MAX_ROWS - scanned);
+        let rows = computer
+            .feed_repo
+            .list_feed(&FeedRepositoryQuery {
+                actor_id: "system".to_string(),
+                cursor_occurred_at_ms,
+                cursor_feed_id: cursor_feed_id.clone(),
+                limit: page_limit,
+                scope_id: None,
+                privacy_level: None,
+                from_ms: Some(trending.horizon_start_ms(now_ms)),
+                to_ms: Some(now_ms),
+                involvement_only: false,
+                followed_entity_ids: Vec::new(),
+                near: None,
+            })
+            .await
+            .map_err(|err| anyhow::anyhow!("failed listing feed rows for trending: {err}"))?;
+        for item in &rows {
+            accumulator.add(item);
+        }
+        scanned += rows.len();
+
+        let Some(last_row) = rows.last() else {
+            break;
+        };
+        if rows.len() < page_limit || scanned >= TRENDING_SCAN_MAX_ROWS {
+            break;
+        }
+        cursor_occurred_at_ms = Some(last_row.occurred_at_ms);
+        cursor_feed_id = Some(last_row.feed_id.clone());
+    }
+    if scanned >= TRENDING_SCAN_MAX_ROWS {
+        warn!(
+            job_id = %job.job_id,
+            scanned,
+            "trending scan hit its row cap; the oldest baseline windows are undercounted"
+        );
+    }
+
+    let snapshots = accumulator.finish();
+    let ttl_ms = config
+        .worker_trending_interval_ms
+        .max(60_000)
+        .saturating_mul(TRENDING_TTL_INTERVALS);
+    computer
+        .store
+        .put_snapshots(&snapshots, ttl_ms)
+        .await
+        .map_err(|err| anyhow::anyhow!("failed to store trending snapshots: {err}"))?;
+
+    info!(
+        job_id = %job.job_id,
+        scanned,
+        scopes = snapshots.len() - 1,
+        global_topics = snapshots[0].topics.len(),
+        "handled trending compute job"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gotong_domain::discovery::FeedItem;
+    use gotong_domain::ports::jobs::JobType;
+    use gotong_domain::trending::InMemoryTrendingSnapshotStore;
+    use gotong_infra::repositories::InMemoryDiscoveryFeedRepository;
+
+    const HOUR_MS: i64 = 60 * 60 * 1000;
+
+    fn tagged_item(feed_id: &str, occurred_at_ms: i64) -> FeedItem {
+        FeedItem {
+            feed_id: feed_id.to_string(),
+            source_type: "ontology_note".to_string(),
+            source_id: feed_id.to_string(),
+            actor_id: "warga-1".to_string(),
+            actor_username: "warga-1".to_string(),
+            title: "Banjir di RT 03".to_string(),
+            summary: None,
+            scope_id: Some("rw-07".to_string()),
+            privacy_level: Some("public".to_string()),
+            occurred_at_ms,
+            created_at_ms: occurred_at_ms,
+            request_id: format!("req-{feed_id}"),
+            correlation_id: format!("corr-{feed_id}"),
+            participant_ids: vec![],
+            payload: Some(serde_json::json!({
+                "enrichment": {
+                    "tags": {
+                        "concept_qids": ["Q8068"],
+                        "action_types": [],
+                        "place_ids": ["place:rt-03"],
+                    }
+                }
+            })),
+            location: None,
+            distance_m: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_trending_compute_pages_feed_and_caches_per_scope() {
+        let mut config = AppConfig::load().expect("config");
+        config.trending_window_ms = 6 * HOUR_MS as u64;
+        config.trending_baseline_windows = 4;
+        let now_ms = 100 * HOUR_MS;
+        let feed_repo = Arc::new(InMemoryDiscoveryFeedRepository::new());
+        for index in 0..(TRENDING_SCAN_PAGE_SIZE as i64 + 5) {
+            feed_repo
+                .create_feed_item(&tagged_item(&format!("feed-{index:04}"), now_ms - index))
+                .await
+                .expect("feed item");
+        }
+        let store = Arc::new(InMemoryTrendingSnapshotStore::new());
+        let computer = TrendingComputer::new(feed_repo, store.clone());
+        let job = JobEnvelope {
+            job_id: "system:trending_compute:1".to_string(),
+            job_type: JobType::TrendingCompute,
+            payload: serde_json::json!({ "scheduled_ms": now_ms }),
+            request_id: "system:trending_compute:1".to_string(),
+            correlation_id: "corr:system:trending_compute:1".to_string(),
+            attempt: 1,
+            max_attempts: 1,
+            run_at_ms: now_ms,
+            created_at_ms: now_ms,
+        };
+
+        handle_trending_compute(&config, Some(&computer), &job)
+            .await
+            .expect("trending compute");
+
+        let scope = store
+            .get_snapshot(Some("rw-07"))
+            .await
+            .expect("snapshot")
+            .expect("scope snapshot cached");
+        assert_eq!(scope.item_count, TRENDING_SCAN_PAGE_SIZE + 5);
+        let ids = scope
+            .topics
+            .iter()
+            .map(|topic| topic.entity_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&"Q8068") && ids.contains(&"place:rt-03"));
+        let global = store.get_snapshot(None).await.expect("snapshot");
+        assert!(global.is_some_and(|global| global.topics.len() == 2));
+    }
+}
@@ -0,0 +1,398 @@
+use std::sync::Arc;
+
+use gotong_domain::jobs::{WebPushSendPayload, backoff_ms, now_ms};
+use gotong_domain::ports::jobs::{JobEnvelope, JobQueue};
+use gotong_domain::ports::push::{
+    PushDelivery, PushDeliveryOutcome, PushGateway, PushSubscriptionRepository,
+};
+use gotong_domain::push::validate_push_endpoint;
+use gotong_infra::config::AppConfig;
+use tracing::{info, warn};
+
+use crate::next_retry_job_id;
+
+/// Everything the push job needs; only built when subscriptions are stored and VAPID keys are set.
+pub struct WebPushSender {
+    subscriptions: Arc<dyn PushSubscriptionRepository>,
+    gateway: Arc<dyn PushGateway>,
+    queue: Arc<dyn JobQueue>,
+}
+
+impl WebPushSender {
+    pub fn new(
+        subscriptions: Arc<dyn PushSubscriptionRepository>,
+        gateway: Arc<dyn PushGateway>,
+        queue: Arc<dyn JobQueue>,
+    ) -> Self {
+        Self {
+            subscriptions,
+            gateway,
+            queue,
+        }
+    }
+}
+
+pub fn parse_web_push_send_payload(job: &JobEnvelope) -> anyhow::Result<WebPushSendPayload> {
+    let payload: WebPushSendPayload = serde_json::from_value(job.payload.clone())
+        .map_err(|err| anyhow::anyhow!("invalid web push payload: {err}"))?;
+    if payload.user_id.trim().is_empty() {
+        return Err(anyhow::anyhow!(
+            "invalid web push payload: user_id is empty"
+        ));
+    }
+    Ok(payload)
+}
+
+/// Pushes one notification to the recipient's subscriptions. Expired subscriptions
+/// (404/410) are pruned; throttled or failed ones are retried in a follow-up job that
+/// targets only them, so delivered devices are not notified twice.
+pub async fn handle_web_push_send(
+    config: &AppConfig,
+    sender: Option<&WebPushSender>,
+    job: &JobEnvelope,
+) -> anyhow::Result<()> {
+    let payload = parse_web_push_send_payload(job)?;
+    let Some(sender) = sender else {
+        warn!(
+            job_id = %job.job_id,
+            "skipping web push send: push subscriptions or VAPID keys are unavailable"
+        );
+        return Ok(());
+    };
+
+    let subscriptions = sender
+        .subscriptions
+        .list_for_user(&payload.user_id)
+        .await
+        .map_err(|err| anyhow::anyhow!("push subscription lookup failed: {err}"))?;
+    let targets = subscriptions.into_iter().filter(|subscription| {
+        payload
+            .subscription_ids
+            .as_ref()
+            .is_none_or(|ids| ids.contains(&subscription.subscription_id))
+    });
+    let delivery = PushDelivery {
+        payload: serde_json::to_vec(&payload.message)?,
+        urgency: payload.urgency,
+        ttl_seconds: config.web_push_ttl_seconds,
+    };
+
+    let mut delivered = 0usize;
+    let mut pruned = 0usize;
+    let mut rejected = 0usize;
+    let mut retry_ids = Vec::new();
+    let mut retry_after_ms = 0u64;
+    for subscription in targets {
+        if let Err(err) = validate_push_endpoint(&subscription.endpoint) {
+            warn!(
+                job_id = %job.job_id,
+                subscription_id = %subscription.subscription_id,
+                error = %err,
+                "pruning push subscription with a disallowed endpoint"
+            );
+            pruned += 1;
+            if let Err(err) = sender
+                .subscriptions
+                .delete_subscription(&payload.user_id, &subscription.subscription_id)
+                .await
+            {
+                warn!(
+                    job_id = %job.job_id,
+                    subscription_id = %subscription.subscription_id,
+                    error = %err,
+                    "failed to prune push subscription"
+                );
+            }
+            continue;
+        }
+        let outcome = match sender.gateway.deliver(&subscription, &delivery).await {
+            Ok(outcome) => outcome,
+            Err(err) => {
+                warn!(
+                    job_id = %job.job_id,
+                    subscription_id = %subscription.subscription_id,
+                    error = %err,
+                    "failed to prepare web push"
+                );
+                rejected += 1;
+                continue;
+            }
+        };
+        match outcome {
+            PushDeliveryOutcome::Delivered => delivered += 1,
+            PushDeliveryOutcome::Gone => {
+                pruned += 1;
+                if let Err(err) = sender
+                    .subscriptions
+                    .delete_subscription(&payload.user_id, &subscription.subscription_id)
+                    .await
+                {
+                    warn!(
+                        job_id = %job.job_id,
+                        subscription_id = %subscription.subscription_id,
+                        error = %err,
+                        "failed to prune expired push subscription"
+                    );
+                }
+            }
+            PushDeliveryOutcome::Retryable {
+                retry_after_ms: hint,
+            } => {
+                retry_after_ms = retry_after_ms.max(hint.unwrap_or_default());
+                retry_ids.push(subscription.subscription_id);
+            }
+            PushDeliveryOutcome::Rejected { status } => {
+                rejected += 1;
+                warn!(
+                    job_id = %job.job_id,
+                    subscription_id = %subscription.subscription_id,
+                    status,
+                    "push service rejected web push"
+                );
+            }
+        }
+    }
+
+    let retrying = retry_ids.len();
+    if !retry_ids.is_empty() {
+        if job.attempt >= job.max_attempts {
+            warn!(
+                job_id = %job.job_id,
+                attempt = job.attempt,
+                subscriptions = retrying,
+                "giving up on web push after final attempt"
+            );
+        } else {
+            let delay_ms = backoff_ms(
+                config.worker_backoff_base_ms,
+                job.attempt,
+                config.worker_backoff_max_ms,
+            )
+            .max(retry_after_ms);
+            let retry_payload = WebPushSendPayload {
+                subscription_ids: Some(retry_ids),
+                ..payload
+            };
+            let mut retry = job.clone();
+            retry.payload = serde_json::to_value(retry_payload)?;
+            retry.attempt = job.next_attempt();
+            retry.run_at_ms = now_ms() + delay_ms as i64;
+            retry.job_id = next_retry_job_id(&job.job_id, retry.attempt);
+            sender
+                .queue
+                .enqueue(&retry)
+                .await
+                .map_err(|err| anyhow::anyhow!("failed to enqueue web push retry: {err}"))?;
+        }
+    }
+
+    info!(
+        job_id = %job.job_id,
+        delivered,
+        pruned,
+        rejected,
+        retrying,
+        "handled web push send job"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use super::*;
+    use gotong_domain::DomainResult;
+    use gotong_domain::ports::BoxFuture;
+    use gotong_domain::ports::jobs::{JobQueueError, JobType};
+    use gotong_domain::push::{PushSubscription, PushUrgency, WebPushMessage};
+    use gotong_infra::repositories::InMemoryPushSubscriptionRepository;
+
+    /// Answers each endpoint with a fixed outcome and records what was sent.
+    struct ScriptedGateway {
+        outcomes: HashMap<String, PushDeliveryOutcome>,
+        sent: Mutex<Vec<(String, PushUrgency)>>,
+    }
+
+    impl PushGateway for ScriptedGateway {
+        fn deliver(
+            &self,
+            subscription: &PushSubscription,
+            delivery: &PushDelivery,
+        ) -> BoxFuture<'_, DomainResult<PushDeliveryOutcome>> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((subscription.endpoint.clone(), delivery.urgency));
+            let outcome = self.outcomes[&subscription.endpoint].clone();
+            Box::pin(async move { Ok(outcome) })
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingQueue {
+        jobs: Mutex<Vec<JobEnvelope>>,
+    }
+
+    impl JobQueue for RecordingQueue {
+        fn enqueue(&self, job: &JobEnvelope) -> BoxFuture<'_, Result<(), JobQueueError>> {
+            self.jobs.lock().unwrap().push(job.clone());
+            Box::pin(async { Ok(()) })
+        }
+
+        fn dequeue(
+            &self,
+            _timeout: Duration,
+        ) -> BoxFuture<'_, Result<Option<JobEnvelope>, JobQueueError>> {
+            Box::pin(async { Ok(None) })
+        }
+
+        fn ack(&self, _job_id: &str) -> BoxFuture<'_, Result<(), JobQueueError>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn promote_due(
+            &self,
+            _now_ms: i64,
+            _limit: usize,
+        ) -> BoxFuture<'_, Result<usize, JobQueueError>> {
+            Box::pin(async { Ok(0) })
+        }
+
+        fn requeue_processing(&self, _limit: usize) -> BoxFuture<'_, Result<usize, JobQueueError>> {
+            Box::pin(async { Ok(0) })
+        }
+    }
+
+    fn subscription(id: &str) -> PushSubscription {
+        PushSubscription {
+            subscription_id: id.to_string(),
+            user_id: "warga-1".to_string(),
+            endpoint: format!("https://fcm.googleapis.com/fcm/send/{id}"),
+            p256dh: "key".to_string(),
+            auth: "secret".to_string(),
+            user_agent: None,
+            created_at_ms: 0,
+            updated_at_ms: 0,
+        }
+    }
+
+    fn push_job() -> JobEnvelope {
+        let payload = WebPushSendPayload {
+            user_id: "warga-1".to_string(),
+            message: WebPushMessage {
+                notification_id: "notif-1".to_string(),
+                notification_type: "siaga".to_string(),
+                title: "Banjir".to_string(),
+                body: "Air naik di RT 03".to_string(),
+                source_type: "siaga".to_string(),
+                source_id: "siaga-1".to_string(),
+                created_at_ms: 1_000,
+            },
+            urgency: PushUrgency::High,
+            subscription_ids: None,
+        };
+        JobEnvelope {
+            job_id: "web_push:notif-1".to_string(),
+            job_type: JobType::WebPushSend,
+            payload: serde_json::to_value(payload).unwrap(),
+            request_id: "req-1".to_string(),
+            correlation_id: "corr-1".to_string(),
+            attempt: 1,
+            max_attempts: 5,
+            run_at_ms: 1_000,
+            created_at_ms: 1_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_web_push_send_prunes_gone_and_disallowed_and_retries_only_throttled() {
+        let mut config = AppConfig::load().expect("config");
+        config.worker_backoff_base_ms = 1_000;
+        config.worker_backoff_max_ms = 60_000;
+        let repo = Arc::new(InMemoryPushSubscriptionRepository::new());
+        for id in ["ok", "gone", "busy"] {
+            repo.upsert_subscription(&subscription(id))
+                .await
+                .expect("subscription");
+        }
+        let mut internal = subscription("internal");
+        internal.endpoint = "https://169.254.169.254/latest/meta-data".to_string();
+        repo.upsert_subscription(&internal)
+            .await
+            .expect("internal subscription");
+        let gateway = Arc::new(ScriptedGateway {
+            outcomes: HashMap::from([
+                (
+                    "https://fcm.googleapis.com/fcm/send/ok".to_string(),
+                    PushDeliveryOutcome::Delivered,
+                ),
+                (
+                    "https://fcm.googleapis.com/fcm/send/gone".to_string(),
+                    PushDeliveryOutcome::Gone,
+                ),
+                (
+                    "https://fcm.googleapis.com/fcm/send/busy".to_string(),
+                    PushDeliveryOutcome::Retryable {
+                        retry_after_ms: Some(120_000),
+                    },
+                ),
+            ]),
+            sent: Mutex::new(Vec::new()),
+        });
+        let queue = Arc::new(RecordingQueue::default());
+        let sender = WebPushSender::new(repo.clone(), gateway.clone(), queue.clone());
+
+        let before_ms = now_ms();
+        handle_web_push_send(&config, Some(&sender), &push_job())
+            .await
+            .expect("push send");
+
+        let sent = gateway.sent.lock().unwrap().clone();
+        assert_eq!(sent.len(), 3);
+        assert!(
+            sent.iter()
+                .all(|(_, urgency)| *urgency == PushUrgency::High)
+        );
+        let remaining: Vec<String> = repo
+            .list_for_user("warga-1")
+            .await
+            .expect("list")
+            .into_iter()
+            .map(|subscription| subscription.subscription_id)
+            .collect();
+        assert_eq!(remaining.len(), 2);
+        assert!(!remaining.contains(&"gone".to_string()));
+        assert!(!remaining.contains(&"internal".to_string()));
+
+        let retries = queue.jobs.lock().unwrap().clone();
+        assert_eq!(retries.len(), 1);
+        let retry = &retries[0];
+        assert_eq!(retry.attempt, 2);
+        assert!(retry.job_id.starts_with("web_push:notif-1:retry:2:"));
+        assert!(
+            retry.run_at_ms >= before_ms + 120_000,
+            "honours Retry-After"
+        );
+        let retry_payload = parse_web_push_send_payload(retry).expect("retry payload");
+        assert_eq!(
+            retry_payload.subscription_ids,
+            Some(vec!["busy".to_string()])
+        );
+
+        gateway.sent.lock().unwrap().clear();
+        handle_web_push_send(&config, Some(&sender), retry)
+            .await
+            .expect("retry send");
+        let sent = gateway.sent.lock().unwrap().clone();
+        assert_eq!(
+            sent,
+            vec![(
+                "https://fcm.googleapis.com/fcm/send/busy".to_string(),
+                PushUrgency::High
+            )]
+        );
+    }
+}
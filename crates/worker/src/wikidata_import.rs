@@ -0,0 +1,983 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use gotong_domain::jobs::now_ms;
+use gotong_domain::ontology::{OntologyConcept, OntologyConceptLabel};
+use gotong_domain::ontology_autocomplete::{MAX_LABELS_PER_CONCEPT, normalize_concept_labels};
+use gotong_domain::ports::ontology::OntologyRepository;
+use gotong_infra::config::AppConfig;
+use gotong_infra::db::DbConfig;
+use gotong_infra::repositories::SurrealOntologyRepository;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+#[derive(Debug, Clone)]
+pub struct WikidataImportOptions {
+    /// Dump path, or `-` for stdin (e.g. `zcat latest-all.json.gz | ...`).
+    pub file: String,
+    pub checkpoint: Option<String>,
+    pub restart: bool,
+    pub qids_file: Option<String>,
+    pub dry_run: bool,
+    pub batch_size: usize,
+    pub progress_every: u64,
+    pub max_lines: Option<u64>,
+}
+
+impl Default for WikidataImportOptions {
+    fn default() -> Self {
+        Self {
+            file: String::new(),
+            checkpoint: None,
+            restart: false,
+            qids_file: None,
+            dry_run: false,
+            batch_size: 500,
+            progress_every: 100_000,
+            max_lines: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WikidataImportSummary {
+    pub lines: u64,
+    pub imported: u64,
+    pub skipped: u64,
+    pub invalid: u64,
+    pub broader_edges: u64,
+}
+
+/// Position after the last fully written batch. Totals are cumulative across
+/// resumed runs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WikidataImportCheckpoint {
+    pub file: String,
+    /// Size of the dump when the import started; a different size means the
+    /// file was replaced and the offset is meaningless.
+    pub file_len: Option<u64>,
+    pub byte_offset: u64,
+    pub line: u64,
+    pub summary: WikidataImportSummary,
+    pub completed: bool,
+    pub updated_at_ms: i64,
+}
+
+/// One dump entity reduced to what the ontology stores.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WikidataConcept {
+    pub concept: OntologyConcept,
+    pub broader_qids: Vec<String>,
+    /// Javanese and Sundanese labels plus aliases in every imported language,
+    /// for autocomplete.
+    pub labels: Vec<OntologyConceptLabel>,
+}
+
+#[derive(Deserialize)]
+struct DumpEntity {
+    #[serde(default, rename = "type")]
+    entity_type: String,
+    id: String,
+    #[serde(default)]
+    labels: DumpLabels,
+    #[serde(default)]
+    aliases: DumpAliases,
+    #[serde(default)]
+    claims: DumpClaims,
+}
+
+#[derive(Default, Deserialize)]
+struct DumpLabels {
+    id: Option<DumpLabel>,
+    en: Option<DumpLabel>,
+    jv: Option<DumpLabel>,
+    su: Option<DumpLabel>,
+    /// Language-neutral label Wikidata uses for names that read the same everywhere.
+    mul: Option<DumpLabel>,
+}
+
+#[derive(Default, Deserialize)]
+struct DumpAliases {
+    #[serde(default)]
+    id: Vec<DumpLabel>,
+    #[serde(default)]
+    en: Vec<DumpLabel>,
+    #[serde(default)]
+    jv: Vec<DumpLabel>,
+    #[serde(default)]
+    su: Vec<DumpLabel>,
+}
+
+#[derive(Deserialize)]
+struct DumpLabel {
+    value: String,
+}
+
+/// `P279` subclass of and `P31` instance of both become `BROADER` edges.
+#[derive(Default, Deserialize)]
+struct DumpClaims {
+    #[serde(default, rename = "P279")]
+    subclass_of: Vec<DumpClaim>,
+    #[serde(default, rename = "P31")]
+    instance_of: Vec<DumpClaim>,
+}
+
+#[derive(Deserialize)]
+struct DumpClaim {
+    mainsnak: DumpSnak,
+    #[serde(default)]
+    rank: String,
+}
+
+#[derive(Deserialize)]
+struct DumpSnak {
+    datavalue: Option<DumpDataValue>,
+}
+
+#[derive(Deserialize)]
+struct DumpDataValue {
+    value: serde_json::Value,
+}
+
+/// Parses one line of the standard dump (a JSON array with one entity per line)
+/// or of a JSON-lines subset. Array brackets, blank lines, non-items and items
+/// with neither an Indonesian nor an English label yield `None`.
+pub fn parse_dump_line(line: &str) -> anyhow::Result<Option<WikidataConcept>> {
+    let line = line.trim().trim_end_matches(',');
+    if line.is_empty() || line == "[" || line == "]" {
+        return Ok(None);
+    }
+    let entity: DumpEntity =
+        serde_json::from_str(line).map_err(|err| anyhow::anyhow!("invalid dump entity: {err}"))?;
+    if entity.entity_type != "item" || !is_qid(&entity.id) {
+        return Ok(None);
+    }
+    let label = |label: Option<DumpLabel>| {
+        label
+            .map(|label| label.value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let mul = label(entity.labels.mul);
+    let label_id = label(entity.labels.id).or_else(|| mul.clone());
+    let label_en = label(entity.labels.en).or(mul);
+    if label_id.is_none() && label_en.is_none() {
+        return Ok(None);
+    }
+    let mut labels = Vec::new();
+    for (lang, value) in [("jv", entity.labels.jv), ("su", entity.labels.su)] {
+        if let Some(value) = label(value) {
+            labels.push(OntologyConceptLabel {
+                lang: lang.to_string(),
+                label: value,
+                alias: false,
+            });
+        }
+    }
+    let aliases = entity.aliases;
+    for (lang, values) in [
+        ("id", aliases.id),
+        ("en", aliases.en),
+        ("jv", aliases.jv),
+        ("su", aliases.su),
+    ] {
+        for value in values.into_iter().filter_map(|value| label(Some(value))) {
+            labels.push(OntologyConceptLabel {
+                lang: lang.to_string(),
+                label: value,
+                alias: true,
+            });
+        }
+    }
+
+    let mut broader_qids = Vec::new();
+    for claim in entity
+        .claims
+        .subclass_of
+        .iter()
+        .chain(entity.claims.instance_of.iter())
+    {
+        if claim.rank == "deprecated" {
+            continue;
+        }
+        let target = claim
+            .mainsnak
+            .datavalue
+            .as_ref()
+            .and_then(|datavalue| datavalue.value.get("id"))
+            .and_then(serde_json::Value::as_str);
+        if let Some(target) = target
+            && is_qid(target)
+            && target != entity.id
+            && !broader_qids.iter().any(|qid| qid == target)
+        {
+            broader_qids.push(target.to_string());
+        }
+    }
+
+    Ok(Some(WikidataConcept {
+        concept: OntologyConcept {
+            concept_id: entity.id.clone(),
+            qid: entity.id,
+            label_id,
+            label_en,
+            verified: true,
+        },
+        broader_qids,
+        labels,
+    }))
+}
+
+fn is_qid(value: &str) -> bool {
+    value
+        .strip_prefix('Q')
+        .is_some_and(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()))
+}
+
+pub fn parse_wikidata_import_options(args: &[String]) -> anyhow::Result<WikidataImportOptions> {
+    let mut opts = WikidataImportOptions::default();
+    let mut idx = 0usize;
+    while idx < args.len() {
+        match args[idx].as_str() {
+            "--file" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --file"))?;
+                opts.file = value.trim().to_string();
+                idx += 2;
+            }
+            "--checkpoint" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --checkpoint"))?;
+                if value.trim().is_empty() {
+                    return Err(anyhow::anyhow!("--checkpoint must not be empty"));
+                }
+                opts.checkpoint = Some(value.trim().to_string());
+                idx += 2;
+            }
+            "--qids-file" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --qids-file"))?;
+                if value.trim().is_empty() {
+                    return Err(anyhow::anyhow!("--qids-file must not be empty"));
+                }
+                opts.qids_file = Some(value.trim().to_string());
+                idx += 2;
+            }
+            "--restart" => {
+                opts.restart = true;
+                idx += 1;
+            }
+            "--dry-run" => {
+                opts.dry_run = true;
+                idx += 1;
+            }
+            "--batch-size" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --batch-size"))?;
+                let parsed = value
+                    .parse::<usize>()
+                    .map_err(|err| anyhow::anyhow!("invalid --batch-size value: {err}"))?;
+                if parsed == 0 {
+                    return Err(anyhow::anyhow!("--batch-size must be >= 1"));
+                }
+                opts.batch_size = parsed.min(10_000);
+                idx += 2;
+            }
+            "--progress-every" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --progress-every"))?;
+                let parsed = value
+                    .parse::<u64>()
+                    .map_err(|err| anyhow::anyhow!("invalid --progress-every value: {err}"))?;
+                if parsed == 0 {
+                    return Err(anyhow::anyhow!("--progress-every must be >= 1"));
+                }
+                opts.progress_every = parsed;
+                idx += 2;
+            }
+            "--max-lines" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --max-lines"))?;
+                let parsed = value
+                    .parse::<u64>()
+                    .map_err(|err| anyhow::anyhow!("invalid --max-lines value: {err}"))?;
+                if parsed == 0 {
+                    return Err(anyhow::anyhow!("--max-lines must be >= 1"));
+                }
+                opts.max_lines = Some(parsed);
+                idx += 2;
+            }
+            other => {
+                return Err(anyhow::anyhow!(
+                    "unknown argument for wikidata-import: {other}"
+                ));
+            }
+        }
+    }
+    if opts.file.is_empty() {
+        return Err(anyhow::anyhow!(
+            "wikidata-import needs --file <dump.json> (or - for stdin)"
+        ));
+    }
+    if opts.checkpoint.is_none() && opts.file != "-" {
+        opts.checkpoint = Some(format!("{}.import-checkpoint.json", opts.file));
+    }
+    Ok(opts)
+}
+
+pub async fn run_wikidata_import_mode(config: &AppConfig, args: &[String]) -> anyhow::Result<()> {
+    let options = parse_wikidata_import_options(args)?;
+    let repo: Arc<dyn OntologyRepository> = if options.dry_run {
+        Arc::new(gotong_infra::repositories::InMemoryOntologyRepository::new())
+    } else {
+        let db_config = DbConfig::from_app_config(config);
+        Arc::new(SurrealOntologyRepository::new(&db_config).await?)
+    };
+    let allowlist = options
+        .qids_file
+        .as_deref()
+        .map(read_qids_file)
+        .transpose()?;
+
+    let from_stdin = options.file == "-";
+    let file_len = if from_stdin {
+        None
+    } else {
+        Some(std::fs::metadata(&options.file)?.len())
+    };
+    let checkpoint_path = options.checkpoint.as_deref().map(PathBuf::from);
+    let start = match checkpoint_path.as_deref() {
+        Some(path) if !options.restart => read_checkpoint(path)?,
+        _ => None,
+    };
+    let start = match start {
+        Some(checkpoint) => {
+            if checkpoint.file != options.file || checkpoint.file_len != file_len {
+                return Err(anyhow::anyhow!(
+                    "checkpoint {} belongs to a different dump ({}); pass --restart to start over",
+                    options.checkpoint.as_deref().unwrap_or_default(),
+                    checkpoint.file
+                ));
+            }
+            if checkpoint.completed {
+                println!(
+                    "[wikidata-import] already completed lines={} imported={}; pass --restart to import again",
+                    checkpoint.summary.lines, checkpoint.summary.imported
+                );
+                return Ok(());
+            }
+            checkpoint
+        }
+        None => WikidataImportCheckpoint {
+            file: options.file.clone(),
+            file_len,
+            byte_offset: 0,
+            line: 0,
+            summary: WikidataImportSummary::default(),
+            completed: false,
+            updated_at_ms: now_ms(),
+        },
+    };
+
+    println!(
+        "[wikidata-import] start file={} resume_line={} batch_size={} dry_run={} allowlist={}",
+        options.file,
+        start.line,
+        options.batch_size,
+        options.dry_run,
+        allowlist.as_ref().map_or(0, HashSet::len)
+    );
+
+    let checkpoint = if from_stdin {
+        let stdin = std::io::stdin();
+        let mut reader = stdin.lock();
+        skip_lines(&mut reader, start.line)?;
+        import_wikidata_dump(
+            &mut reader,
+            repo.as_ref(),
+            &options,
+            allowlist.as_ref(),
+            checkpoint_path.as_deref(),
+            start,
+        )
+        .await?
+    } else {
+        let mut file = File::open(&options.file)?;
+        file.seek(SeekFrom::Start(start.byte_offset))?;
+        let mut reader = BufReader::with_capacity(1 << 20, file);
+        import_wikidata_dump(
+            &mut reader,
+            repo.as_ref(),
+            &options,
+            allowlist.as_ref(),
+            checkpoint_path.as_deref(),
+            start,
+        )
+        .await?
+    };
+
+    let summary = checkpoint.summary;
+    println!(
+        "[wikidata-import] done lines={} imported={} skipped={} invalid={} broader_edges={} completed={} dry_run={}",
+        summary.lines,
+        summary.imported,
+        summary.skipped,
+        summary.invalid,
+        summary.broader_edges,
+        checkpoint.completed,
+        options.dry_run
+    );
+    Ok(())
+}
+
+/// Streams `reader` from `start`, writing concepts and `BROADER` edges one batch
+/// at a time and checkpointing after each batch. Writes are upserts, so a crash
+/// between a batch and its checkpoint only repeats that batch. Dry runs write
+/// nothing, checkpoint included, so a later real run still starts from `start`.
+pub async fn import_wikidata_dump<R: BufRead>(
+    reader: &mut R,
+    repo: &dyn OntologyRepository,
+    options: &WikidataImportOptions,
+    allowlist: Option<&HashSet<String>>,
+    checkpoint_path: Option<&Path>,
+    start: WikidataImportCheckpoint,
+) -> anyhow::Result<WikidataImportCheckpoint> {
+    let checkpoint_path = checkpoint_path.filter(|_| !options.dry_run);
+    let mut checkpoint = start;
+    let mut batch: Vec<WikidataConcept> = Vec::with_capacity(options.batch_size);
+    let mut batch_lines = 0u64;
+    let mut batch_bytes = 0u64;
+    let mut batch_summary = WikidataImportSummary::default();
+    let mut line = String::new();
+    let mut lines_this_run = 0u64;
+    let mut exhausted = false;
+
+    loop {
+        let at_limit = options.max_lines.is_some_and(|max| lines_this_run >= max);
+        if !at_limit {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            if read == 0 {
+                exhausted = true;
+            } else {
+                lines_this_run += 1;
+                batch_lines += 1;
+                batch_bytes += read as u64;
+                match parse_dump_line(&line) {
+                    Ok(Some(mut entity)) => {
+                        if allowlist.is_none_or(|qids| qids.contains(&entity.concept.qid)) {
+                            if let Some(qids) = allowlist {
+                                entity.broader_qids.retain(|qid| qids.contains(qid));
+                            }
+                            batch.push(entity);
+                        } else {
+                            batch_summary.skipped += 1;
+                        }
+                    }
+                    Ok(None) => batch_summary.skipped += 1,
+                    Err(err) => {
+                        batch_summary.invalid += 1;
+                        warn!(
+                            line = checkpoint.line + batch_lines,
+                            error = %err,
+                            "skipping unparseable wikidata dump line"
+                        );
+                    }
+                }
+            }
+        }
+
+        let flush = exhausted || at_limit || batch.len() >= options.batch_size;
+        if flush && batch_lines > 0 {
+            if !options.dry_run {
+                write_batch(repo, &batch, &mut batch_summary).await?;
+            } else {
+                batch_summary.imported += batch.len() as u64;
+                batch_summary.broader_edges += batch
+                    .iter()
+                    .map(|entity| entity.broader_qids.len() as u64)
+                    .sum::<u64>();
+            }
+            let before = checkpoint.summary.lines;
+            checkpoint.line += batch_lines;
+            checkpoint.byte_offset += batch_bytes;
+            checkpoint.summary.lines += batch_lines;
+            checkpoint.summary.imported += batch_summary.imported;
+            checkpoint.summary.skipped += batch_summary.skipped;
+            checkpoint.summary.invalid += batch_summary.invalid;
+            checkpoint.summary.broader_edges += batch_summary.broader_edges;
+            checkpoint.completed = exhausted;
+            checkpoint.updated_at_ms = now_ms();
+            if let Some(path) = checkpoint_path {
+                write_checkpoint(path, &checkpoint)?;
+            }
+            if checkpoint.summary.lines / options.progress_every > before / options.progress_every {
+                println!(
+                    "[wikidata-import] progress lines={} imported={} broader_edges={}",
+                    checkpoint.summary.lines,
+                    checkpoint.summary.imported,
+                    checkpoint.summary.broader_edges
+                );
+            }
+            batch.clear();
+            batch_lines = 0;
+            batch_bytes = 0;
+            batch_summary = WikidataImportSummary::default();
+        } else if exhausted {
+            checkpoint.completed = true;
+            checkpoint.updated_at_ms = now_ms();
+            if let Some(path) = checkpoint_path {
+                write_checkpoint(path, &checkpoint)?;
+            }
+        }
+        if exhausted || at_limit {
+            return Ok(checkpoint);
+        }
+    }
+}
+
+/// Labels already stored win only where the dump has none, so curated labels
+/// are not blanked by a sparse subset. The same holds for the extra labels:
+/// they are replaced only when the dump carries some.
+async fn write_batch(
+    repo: &dyn OntologyRepository,
+    batch: &[WikidataConcept],
+    summary: &mut WikidataImportSummary,
+) -> anyhow::Result<()> {
+    let qids = batch
+        .iter()
+        .map(|entity| entity.concept.qid.clone())
+        .collect::<Vec<_>>();
+    let existing = repo
+        .get_concepts_by_qids(&qids)
+        .await
+        .map_err(|err| anyhow::anyhow!("failed to fetch existing concepts: {err}"))?
+        .into_iter()
+        .map(|concept| (concept.qid.clone(), concept))
+        .collect::<HashMap<_, _>>();
+    for entity in batch {
+        let mut concept = entity.concept.clone();
+        if let Some(current) = existing.get(&concept.qid) {
+            concept.label_id = concept.label_id.or_else(|| current.label_id.clone());
+            concept.label_en = concept.label_en.or_else(|| current.label_en.clone());
+        }
+        repo.upsert_concept(&concept)
+            .await
+            .map_err(|err| anyhow::anyhow!("failed to upsert concept {}: {err}", concept.qid))?;
+        summary.imported += 1;
+        if !entity.labels.is_empty() {
+            write_labels(repo, &concept.qid, &entity.labels).await?;
+        }
+        for broader in &entity.broader_qids {
+            repo.add_broader_edge(&concept.concept_id, broader)
+                .await
+                .map_err(|err| {
+                    anyhow::anyhow!(
+                        "failed to add BROADER edge {} -> {broader}: {err}",
+                        concept.qid
+                    )
+                })?;
+            summary.broader_edges += 1;
+        }
+    }
+    Ok(())
+}
+
+async fn write_labels(
+    repo: &dyn OntologyRepository,
+    qid: &str,
+    labels: &[OntologyConceptLabel],
+) -> anyhow::Result<()> {
+    // Popular items carry dozens of aliases, and some are punctuation only;
+    // drop those rather than the item.
+    let mut labels = labels
+        .iter()
+        .filter(|label| normalize_concept_labels(std::slice::from_ref(*label)).is_ok())
+        .cloned()
+        .collect::<Vec<_>>();
+    labels.truncate(MAX_LABELS_PER_CONCEPT);
+    let labels = normalize_concept_labels(&labels)
+        .map_err(|err| anyhow::anyhow!("invalid labels for {qid}: {err}"))?;
+    repo.replace_concept_labels(qid, &labels)
+        .await
+        .map_err(|err| anyhow::anyhow!("failed to store labels of {qid}: {err}"))
+}
+
+fn skip_lines<R: BufRead>(reader: &mut R, count: u64) -> anyhow::Result<()> {
+    let mut buffer = Vec::new();
+    for _ in 0..count {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            return Err(anyhow::anyhow!(
+                "input ended before the checkpointed line {count}"
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn read_qids_file(path: &str) -> anyhow::Result<HashSet<String>> {
+    let file = File::open(path)
+        .map_err(|err| anyhow::anyhow!("failed to open --qids-file {path}: {err}"))?;
+    let mut qids = HashSet::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        let qid = line.trim();
+        if qid.is_empty() || qid.starts_with('#') {
+            continue;
+        }
+        if !is_qid(qid) {
+            return Err(anyhow::anyhow!("invalid QID in --qids-file: {qid}"));
+        }
+        qids.insert(qid.to_string());
+    }
+    Ok(qids)
+}
+
+fn read_checkpoint(path: &Path) -> anyhow::Result<Option<WikidataImportCheckpoint>> {
+    match std::fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|err| anyhow::anyhow!("invalid checkpoint {}: {err}", path.display())),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Written to a sibling temp file and renamed so a crash never leaves a torn checkpoint.
+fn write_checkpoint(path: &Path, checkpoint: &WikidataImportCheckpoint) -> anyhow::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    std::fs::write(&tmp, serde_json::to_vec_pretty(checkpoint)?)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gotong_infra::repositories::InMemoryOntologyRepository;
+
+    fn entity_line(qid: &str, label_id: Option<&str>, broader: &[(&str, &str)]) -> String {
+        let mut labels = serde_json::Map::new();
+        labels.insert(
+            "en".to_string(),
+            serde_json::json!({ "language": "en", "value": format!("{qid} en") }),
+        );
+        if let Some(label_id) = label_id {
+            labels.insert(
+                "id".to_string(),
+                serde_json::json!({ "language": "id", "value": label_id }),
+            );
+        }
+        let mut claims = serde_json::Map::new();
+        for (property, target) in broader {
+            claims
+                .entry(property.to_string())
+                .or_insert_with(|| serde_json::json!([]))
+                .as_array_mut()
+                .unwrap()
+                .push(serde_json::json!({
+                    "mainsnak": {
+                        "snaktype": "value",
+                        "property": property,
+                        "datavalue": {
+                            "value": { "entity-type": "item", "id": target },
+                            "type": "wikibase-entityid"
+                        }
+                    },
+                    "type": "statement",
+                    "rank": "normal"
+                }));
+        }
+        serde_json::json!({
+            "type": "item",
+            "id": qid,
+            "labels": labels,
+            "descriptions": {},
+            "claims": claims,
+        })
+        .to_string()
+    }
+
+    fn dump() -> String {
+        [
+            "[".to_string(),
+            format!(
+                "{},",
+                entity_line("Q8068", Some("banjir"), &[("P279", "Q8065"), ("P31", "Q1")])
+            ),
+            "{\"type\":\"property\",\"id\":\"P31\",\"labels\":{}},".to_string(),
+            "{not json".to_string(),
+            format!("{},", entity_line("Q8065", Some("bencana alam"), &[])),
+            entity_line("Q1", None, &[("P31", "Q1")]),
+            "]".to_string(),
+        ]
+        .join("\n")
+            + "\n"
+    }
+
+    fn fresh_checkpoint(file: &str) -> WikidataImportCheckpoint {
+        WikidataImportCheckpoint {
+            file: file.to_string(),
+            file_len: None,
+            byte_offset: 0,
+            line: 0,
+            summary: WikidataImportSummary::default(),
+            completed: false,
+            updated_at_ms: 0,
+        }
+    }
+
+    #[test]
+    fn parse_dump_line_reads_labels_and_broader_targets() {
+        let line = format!(
+            "{},",
+            entity_line(
+                "Q8068",
+                Some("banjir"),
+                &[("P279", "Q8065"), ("P31", "Q8068")]
+            )
+        );
+        let entity = parse_dump_line(&line).expect("parse").expect("item");
+        assert_eq!(entity.concept.qid, "Q8068");
+        assert_eq!(entity.concept.label_id.as_deref(), Some("banjir"));
+        assert_eq!(entity.concept.label_en.as_deref(), Some("Q8068 en"));
+        assert!(entity.concept.verified);
+        assert_eq!(entity.broader_qids, vec!["Q8065".to_string()]);
+
+        assert!(parse_dump_line("[").expect("bracket").is_none());
+        assert!(
+            parse_dump_line(r#"{"type":"item","id":"Q5","labels":{"fr":{"value":"x"}}}"#)
+                .expect("no usable label")
+                .is_none()
+        );
+        assert!(parse_dump_line("{not json").is_err());
+    }
+
+    fn regional_line() -> &'static str {
+        r#"{"type":"item","id":"Q8068","labels":{"id":{"value":"Banjir"},"jv":{"value":"Banjir"},"su":{"value":"Caah"},"fr":{"value":"Inondation"}},"aliases":{"id":[{"value":"Bah"}],"jv":[{"value":"Banjir bandhang"},{"value":" "}],"su":[{"value":"Caah dengdeng"}]},"claims":{}}"#
+    }
+
+    #[test]
+    fn parse_dump_line_keeps_regional_labels_and_aliases() {
+        let entity = parse_dump_line(regional_line())
+            .expect("parse")
+            .expect("item");
+        let labels = entity
+            .labels
+            .iter()
+            .map(|label| (label.lang.as_str(), label.label.as_str(), label.alias))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec![
+                ("jv", "Banjir", false),
+                ("su", "Caah", false),
+                ("id", "Bah", true),
+                ("jv", "Banjir bandhang", true),
+                ("su", "Caah dengdeng", true),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn write_batch_stores_labels_only_when_the_dump_has_some() {
+        let repo = InMemoryOntologyRepository::new();
+        let entity = parse_dump_line(regional_line())
+            .expect("parse")
+            .expect("item");
+        let mut summary = WikidataImportSummary::default();
+        write_batch(&repo, std::slice::from_ref(&entity), &mut summary)
+            .await
+            .expect("write");
+        let sparse = parse_dump_line(&entity_line("Q8068", Some("banjir"), &[]))
+            .expect("parse")
+            .expect("item");
+        assert!(sparse.labels.is_empty());
+        write_batch(&repo, &[sparse], &mut summary)
+            .await
+            .expect("rewrite");
+
+        let stored = repo
+            .get_concept_labels(&["Q8068".to_string()])
+            .await
+            .expect("labels");
+        let langs = stored[0]
+            .labels
+            .iter()
+            .filter(|label| label.lang == "su")
+            .map(|label| label.label.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(langs, vec!["Caah", "Caah dengdeng"]);
+    }
+
+    #[tokio::test]
+    async fn import_resumes_from_checkpoint_without_duplicating() {
+        let dir = std::env::temp_dir().join(format!("wikidata-import-{}", uuid::Uuid::now_v7()));
+        std::fs::create_dir_all(&dir).expect("dir");
+        let checkpoint_path = dir.join("checkpoint.json");
+        let repo = InMemoryOntologyRepository::new();
+        let input = dump();
+        let options = WikidataImportOptions {
+            file: "dump.json".to_string(),
+            batch_size: 1,
+            max_lines: Some(3),
+            ..WikidataImportOptions::default()
+        };
+
+        let first = import_wikidata_dump(
+            &mut input.as_bytes(),
+            &repo,
+            &options,
+            None,
+            Some(&checkpoint_path),
+            fresh_checkpoint("dump.json"),
+        )
+        .await
+        .expect("first run");
+        assert_eq!(first.line, 3);
+        assert!(!first.completed);
+        assert_eq!(first.summary.imported, 1);
+
+        let saved = read_checkpoint(&checkpoint_path)
+            .expect("read")
+            .expect("checkpoint written");
+        assert_eq!(saved, first);
+        let mut rest = &input.as_bytes()[saved.byte_offset as usize..];
+        let options = WikidataImportOptions {
+            max_lines: None,
+            ..options
+        };
+        let done = import_wikidata_dump(
+            &mut rest,
+            &repo,
+            &options,
+            None,
+            Some(&checkpoint_path),
+            saved,
+        )
+        .await
+        .expect("resumed run");
+        assert!(done.completed);
+        assert_eq!(done.summary.lines, 7);
+        assert_eq!(done.summary.imported, 3);
+        assert_eq!(done.summary.invalid, 1);
+        assert_eq!(done.summary.broader_edges, 2);
+
+        let concepts = repo
+            .get_concepts_by_qids(&["Q8068".to_string(), "Q8065".to_string(), "Q1".to_string()])
+            .await
+            .expect("concepts");
+        assert_eq!(concepts.len(), 3);
+        assert!(concepts.iter().all(|concept| concept.verified));
+        let broader = repo
+            .list_broader_concepts("Q8068")
+            .await
+            .expect("broader")
+            .into_iter()
+            .map(|concept| concept.qid)
+            .collect::<HashSet<_>>();
+        assert_eq!(
+            broader,
+            HashSet::from(["Q8065".to_string(), "Q1".to_string()])
+        );
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn dry_run_leaves_the_checkpoint_for_the_real_run() {
+        let dir = std::env::temp_dir().join(format!("wikidata-import-{}", uuid::Uuid::now_v7()));
+        std::fs::create_dir_all(&dir).expect("dir");
+        let checkpoint_path = dir.join("checkpoint.json");
+        let repo = InMemoryOntologyRepository::new();
+        let input = dump();
+        let options = WikidataImportOptions {
+            file: "dump.json".to_string(),
+            dry_run: true,
+            ..WikidataImportOptions::default()
+        };
+
+        let dry = import_wikidata_dump(
+            &mut input.as_bytes(),
+            &repo,
+            &options,
+            None,
+            Some(&checkpoint_path),
+            fresh_checkpoint("dump.json"),
+        )
+        .await
+        .expect("dry run");
+        assert!(dry.completed);
+        assert_eq!(dry.summary.imported, 3);
+        assert!(read_checkpoint(&checkpoint_path).expect("read").is_none());
+        assert!(
+            repo.get_concept_by_qid("Q8068")
+                .await
+                .expect("lookup")
+                .is_none()
+        );
+
+        let options = WikidataImportOptions {
+            dry_run: false,
+            ..options
+        };
+        let done = import_wikidata_dump(
+            &mut input.as_bytes(),
+            &repo,
+            &options,
+            None,
+            Some(&checkpoint_path),
+            fresh_checkpoint("dump.json"),
+        )
+        .await
+        .expect("real run");
+        assert!(done.completed);
+        assert_eq!(done.summary.imported, 3);
+        assert_eq!(read_checkpoint(&checkpoint_path).expect("read"), Some(done));
+        assert!(
+            repo.get_concept_by_qid("Q8068")
+                .await
+                .expect("lookup")
+                .is_some()
+        );
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn allowlist_keeps_the_graph_closed() {
+        let repo = InMemoryOntologyRepository::new();
+        let allowlist = HashSet::from(["Q8068".to_string(), "Q8065".to_string()]);
+        let options = WikidataImportOptions {
+            file: "-".to_string(),
+            ..WikidataImportOptions::default()
+        };
+        let done = import_wikidata_dump(
+            &mut dump().as_bytes(),
+            &repo,
+            &options,
+            Some(&allowlist),
+            None,
+            fresh_checkpoint("-"),
+        )
+        .await
+        .expect("import");
+        assert_eq!(done.summary.imported, 2);
+        assert_eq!(done.summary.broader_edges, 1, "edge to Q1 dropped");
+        assert!(
+            repo.get_concept_by_qid("Q1")
+                .await
+                .expect("lookup")
+                .is_none()
+        );
+    }
+}
@@ -62,6 +62,23 @@ CHECKS=(
   "0029_group_read_model_schema_check.surql"
   "0030_webhook_payload_flexible_check.surql"
   "0031_feed_preference_schema_check.surql"
+  "0032_chat_attachment_schema_check.surql"
+  "0033_chat_thread_retention_check.surql"
+  "0034_chat_read_receipts_check.surql"
+  "0035_digest_subscription_schema_check.surql"
+  "0036_notification_preferences_schema_check.surql"
+  "0037_push_subscription_schema_check.surql"
+  "0038_notification_grouping_check.surql"
+  "0039_feed_geo_location_check.surql"
+  "0040_concept_redirect_check.surql"
+  "0041_concept_label_check.surql"
+  "0042_admin_area_check.surql"
+  "0043_note_version_check.surql"
+  "0044_path_plan_version_check.surql"
+  "0045_path_plan_event_actor_flexible_check.surql"
+  "0046_chat_attachment_quota_lock_check.surql"
+  "0047_notification_in_app_hidden_check.surql"
+  "0048_notification_siaga_bypass_opt_in_check.surql"
 )
 
 run_check() {
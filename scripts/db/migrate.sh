@@ -107,6 +107,23 @@ for migration_file in \
   "0028_chat_message_request_index_order.surql" \
   "0029_group_read_model_schema.surql" \
   "0030_webhook_payload_flexible.surql" \
-  "0031_feed_preference_schema.surql"; do
+  "0031_feed_preference_schema.surql" \
+  "0032_chat_attachment_schema.surql" \
+  "0033_chat_thread_retention.surql" \
+  "0034_chat_read_receipts.surql" \
+  "0035_digest_subscription_schema.surql" \
+  "0036_notification_preferences_schema.surql" \
+  "0037_push_subscription_schema.surql" \
+  "0038_notification_grouping.surql" \
+  "0039_feed_geo_location.surql" \
+  "0040_concept_redirect.surql" \
+  "0041_concept_label.surql" \
+  "0042_admin_area.surql" \
+  "0043_note_version.surql" \
+  "0044_path_plan_version.surql" \
+  "0045_path_plan_event_actor_flexible.surql" \
+  "0046_chat_attachment_quota_lock.surql" \
+  "0047_notification_in_app_hidden.surql" \
+  "0048_notification_siaga_bypass_opt_in.surql"; do
   run_migration "$migration_file"
 done
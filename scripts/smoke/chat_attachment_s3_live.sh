@@ -207,7 +207,8 @@ JSON
 token="$(json_get "$signup" "access_token")"
 
 UPLOAD_FILE="$(mktemp)"
-printf 'PNGDATA-S3-SMOKE-%s' "$run_id" > "${UPLOAD_FILE}"
+# Uploads are sniffed by magic bytes, so the payload starts with an ID3 tag header.
+printf 'ID3\004\000\000\000\000\000\000S3-SMOKE-%s' "$run_id" > "${UPLOAD_FILE}"
 DOWNLOAD_BODY="$(mktemp)"
 DOWNLOAD_HEADERS="$(mktemp)"
 S3_BODY="$(mktemp)"
@@ -218,7 +219,7 @@ upload_response="$(
     -H "authorization: Bearer ${token}" \
     -H "x-request-id: $(uuid_hex)" \
     -H "x-correlation-id: $(uuid_hex)" \
-    -F "file=@${UPLOAD_FILE};filename=foto.png;type=image/png"
+    -F "file=@${UPLOAD_FILE};filename=smoke.mp3;type=audio/mpeg"
 )"
 download_path="$(json_get "${upload_response}" "url")"
 attachment_id="$(json_get "${upload_response}" "attachment_id")"